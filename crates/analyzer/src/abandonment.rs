@@ -0,0 +1,119 @@
+//! Abandonment risk: is anyone still working on this distro?
+//!
+//! Health scores rank distros against each other; this answers a yes/no question instead.
+//! Each warning sign found becomes a listed factor, and the tier follows from how many
+//! turned up, so the API can say why a distro is flagged rather than just that it is.
+
+use crate::velocity::{commit_trend, github_baseline, COMMIT_TREND_DAYS};
+use crate::Result;
+use chrono::Utc;
+use distrovitals_database::{Database, Distribution};
+use serde::Serialize;
+
+/// Releases older than this (about 18 months) count as stalled
+const STALE_RELEASE_DAYS: i64 = 548;
+
+/// A drop in 30-day commits at least this large counts as declining
+const COMMIT_DECLINE_PCT: f64 = -50.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RiskTier {
+    Healthy,
+    AtRisk,
+    LikelyAbandoned,
+}
+
+impl RiskTier {
+    /// Tier for a set of factors; several broken links are still one warning sign
+    fn from_factors(factors: &[RiskFactor]) -> Self {
+        let mut kinds: Vec<&str> = factors.iter().map(|f| f.kind).collect();
+        kinds.dedup();
+        match kinds.len() {
+            0 => Self::Healthy,
+            1 => Self::AtRisk,
+            _ => Self::LikelyAbandoned,
+        }
+    }
+}
+
+/// One warning sign behind a distro's risk tier
+#[derive(Debug, Clone, Serialize)]
+pub struct RiskFactor {
+    /// "declining_commits", "single_maintainer", "no_recent_release" or "dead_infrastructure"
+    pub kind: &'static str,
+    pub detail: String,
+}
+
+/// Abandonment risk tier of a distribution and the factors that put it there
+#[derive(Debug, Clone, Serialize)]
+pub struct AbandonmentRisk {
+    pub tier: RiskTier,
+    pub factors: Vec<RiskFactor>,
+}
+
+impl AbandonmentRisk {
+    /// Classify a distribution, `None` when no GitHub activity has been collected
+    pub async fn assess(db: &Database, distro: &Distribution) -> Result<Option<Self>> {
+        let github = db.get_latest_github_snapshots(distro.id).await?;
+        if github.is_empty() {
+            return Ok(None);
+        }
+
+        let mut factors = Vec::new();
+
+        let commits_30d: i64 = github.iter().map(|s| s.commits_30d).sum();
+        let baseline = github_baseline(db, distro.id, &github, COMMIT_TREND_DAYS).await?;
+        if commits_30d == 0 {
+            factors.push(RiskFactor {
+                kind: "declining_commits",
+                detail: "No commits in the last 30 days".to_string(),
+            });
+        } else if let Some(trend) = commit_trend(&github, &baseline) {
+            let change_pct = trend.delta as f64 / trend.baseline.max(1) as f64 * 100.0;
+            if change_pct <= COMMIT_DECLINE_PCT {
+                factors.push(RiskFactor {
+                    kind: "declining_commits",
+                    detail: format!("Commits in the last 30 days down {:.0}% on a month earlier", -change_pct),
+                });
+            }
+        }
+
+        // Per repo rather than summed, since the same people work across repos
+        let contributors = github.iter().map(|s| s.contributors_30d).max().unwrap_or(0);
+        if commits_30d > 0 && contributors <= 1 {
+            factors.push(RiskFactor {
+                kind: "single_maintainer",
+                detail: "Only one contributor active in the last 30 days".to_string(),
+            });
+        }
+
+        let releases = db.get_latest_release_snapshots(distro.id).await?;
+        let latest_release = releases
+            .iter()
+            .filter(|r| !r.is_prerelease)
+            .filter_map(|r| r.published_at)
+            .max();
+        if let Some(published) = latest_release {
+            let days = (Utc::now() - published).num_days();
+            if days > STALE_RELEASE_DAYS {
+                factors.push(RiskFactor {
+                    kind: "no_recent_release",
+                    detail: format!("Last stable release {} months ago", days / 30),
+                });
+            }
+        }
+
+        for issue in db.get_distro_config_issues(distro.id).await? {
+            factors.push(RiskFactor {
+                kind: "dead_infrastructure",
+                detail: format!("{} {}: {}", issue.field, issue.value, issue.problem),
+            });
+        }
+
+        Ok(Some(Self {
+            tier: RiskTier::from_factors(&factors),
+            factors,
+        }))
+    }
+}
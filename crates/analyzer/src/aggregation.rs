@@ -0,0 +1,128 @@
+//! Per-distro strategies for aggregating repo metrics
+//!
+//! A distro whose work lives in one monorepo and one spread over hundreds of small
+//! repos produce very different sums. The strategy is configured per distro and used
+//! for both the reported metrics and scoring.
+
+use distrovitals_database::GithubSnapshot;
+use std::fmt;
+
+/// Number of repos counted by `top-k` when no k is given
+const DEFAULT_TOP_K: usize = 10;
+
+/// How per-repo values are combined into a distro-wide value
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Aggregation {
+    /// Plain sum over all repos
+    #[default]
+    Sum,
+    /// Average per repo
+    Mean,
+    /// Mean of the k largest repos, each weighted by 1/rank so the largest count most
+    TopK(usize),
+}
+
+impl Aggregation {
+    /// Parse a config value: "sum", "mean", "top-k" or "top-k:N"
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "sum" => Some(Self::Sum),
+            "mean" => Some(Self::Mean),
+            "top-k" => Some(Self::TopK(DEFAULT_TOP_K)),
+            other => {
+                let k: usize = other.strip_prefix("top-k:")?.parse().ok()?;
+                (k > 0).then_some(Self::TopK(k))
+            }
+        }
+    }
+
+    /// Strategy for a distro's stored setting, falling back to sum
+    pub fn from_config(value: Option<&str>) -> Self {
+        value.and_then(Self::parse).unwrap_or_default()
+    }
+
+    /// Combine per-repo values
+    pub fn apply(&self, values: impl IntoIterator<Item = i64>) -> i64 {
+        let mut values: Vec<i64> = values.into_iter().collect();
+        if values.is_empty() {
+            return 0;
+        }
+
+        match self {
+            Self::Sum => values.iter().sum(),
+            Self::Mean => (values.iter().sum::<i64>() as f64 / values.len() as f64).round() as i64,
+            Self::TopK(k) => {
+                values.sort_unstable_by(|a, b| b.cmp(a));
+                let top = &values[..values.len().min(*k)];
+
+                let weights: Vec<f64> = (1..=top.len()).map(|rank| 1.0 / rank as f64).collect();
+                let weighted: f64 = top.iter().zip(&weights).map(|(v, w)| *v as f64 * w).sum();
+                (weighted / weights.iter().sum::<f64>()).round() as i64
+            }
+        }
+    }
+}
+
+impl fmt::Display for Aggregation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Sum => write!(f, "sum"),
+            Self::Mean => write!(f, "mean"),
+            Self::TopK(k) => write!(f, "top-k:{}", k),
+        }
+    }
+}
+
+/// Distro-wide GitHub totals under an aggregation strategy
+#[derive(Debug, Clone, Default)]
+pub struct GithubTotals {
+    pub stars: i64,
+    pub forks: i64,
+    pub contributors: i64,
+    pub commits_30d: i64,
+    pub commits_365d: i64,
+    pub bot_commits_30d: i64,
+    pub open_issues: i64,
+    pub open_prs: i64,
+}
+
+impl GithubTotals {
+    /// Aggregate snapshots field by field
+    pub fn aggregate(snapshots: &[GithubSnapshot], aggregation: Aggregation) -> Self {
+        let field = |f: fn(&GithubSnapshot) -> i64| aggregation.apply(snapshots.iter().map(f));
+
+        Self {
+            stars: field(|s| s.stars),
+            forks: field(|s| s.forks),
+            contributors: field(|s| s.contributors_30d),
+            commits_30d: field(|s| s.commits_30d),
+            commits_365d: field(|s| s.commits_365d),
+            bot_commits_30d: field(|s| s.bot_commits_30d),
+            open_issues: field(|s| s.open_issues),
+            open_prs: field(|s| s.open_prs),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn top_k_is_a_weighted_mean() {
+        let top_k = Aggregation::TopK(10);
+        assert_eq!(top_k.apply([7; 10]), 7);
+        // Only the largest k count
+        assert_eq!(top_k.apply([7; 50]), 7);
+        assert_eq!(Aggregation::TopK(2).apply([30, 10, 1000]), 677);
+    }
+
+    #[test]
+    fn top_k_never_exceeds_its_largest_repo() {
+        let mut values = vec![0; 10];
+        values[0] = 100;
+        // 100 / H(10)
+        assert_eq!(Aggregation::TopK(10).apply(values.clone()), 34);
+        assert!(Aggregation::TopK(10).apply(values.clone()) <= Aggregation::Sum.apply(values));
+    }
+}
@@ -0,0 +1,237 @@
+//! What changed for a distro between two points in time
+//!
+//! Compares the snapshots current at `since` with the latest ones. Counts are only
+//! compared across repos and subreddits present at both points, so a newly tracked repo
+//! doesn't show up as thousands of new stars.
+
+use crate::Result;
+use chrono::{DateTime, Utc};
+use distrovitals_database::{CommunitySnapshot, Database, Distribution, GithubSnapshot};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Key, label and accessor of a compared count
+type Field<T> = (&'static str, &'static str, fn(&T) -> i64);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Changes {
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    /// One short line per change, e.g. "stars +1.2k", for notifications and summaries
+    pub summary: Vec<String>,
+    /// Counts that moved, in a fixed order
+    pub metrics: Vec<MetricChange>,
+    pub score: Option<ScoreChange>,
+    pub kernel: Option<KernelChange>,
+    /// Releases published in the window, newest first
+    pub releases: Vec<NewRelease>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricChange {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub before: i64,
+    pub after: i64,
+    pub delta: i64,
+    /// Change relative to `before`; None when it was zero
+    pub delta_pct: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoreChange {
+    pub before: f64,
+    pub after: f64,
+    pub delta: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct KernelChange {
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NewRelease {
+    pub repo_name: String,
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub published_at: DateTime<Utc>,
+    pub prerelease: bool,
+}
+
+impl Changes {
+    /// Diff a distro's stored data between `since` and now
+    pub async fn load(db: &Database, distro: &Distribution, since: DateTime<Utc>) -> Result<Self> {
+        let until = Utc::now();
+        let mut metrics = Vec::new();
+
+        let github_before = db.get_github_snapshots_as_of(distro.id, since).await?;
+        let github_after = db.get_latest_github_snapshots(distro.id).await?;
+        let repos = paired(&github_before, &github_after, |s| s.repo_name.as_str());
+        let github_fields: [Field<GithubSnapshot>; 6] = [
+            ("stars", "stars", |s| s.stars),
+            ("forks", "forks", |s| s.forks),
+            ("open_issues", "open issues", |s| s.open_issues),
+            ("open_prs", "open PRs", |s| s.open_prs),
+            ("commits_30d", "commits (30d)", |s| s.commits_30d),
+            ("contributors_30d", "contributors (30d)", |s| s.contributors_30d),
+        ];
+        for (key, label, value) in github_fields {
+            metrics.extend(MetricChange::between(key, label, &repos, value));
+        }
+
+        let community_before = db.get_community_snapshots_as_of(distro.id, since).await?;
+        let community_after = db.get_latest_community_snapshots(distro.id).await?;
+        let subreddits: Vec<_> = paired(&community_before, &community_after, |s| s.source.as_str())
+            .into_iter()
+            .filter(|(before, _)| before.source.starts_with("reddit:"))
+            .collect();
+        let community_fields: [Field<CommunitySnapshot>; 2] = [
+            ("reddit_subscribers", "subscribers", |s| s.active_users_30d.unwrap_or(0)),
+            ("reddit_posts_30d", "posts (30d)", |s| s.posts_30d.unwrap_or(0)),
+        ];
+        for (key, label, value) in community_fields {
+            metrics.extend(MetricChange::between(key, label, &subreddits, value));
+        }
+
+        let mut kernel = None;
+        let packages_before = db.get_package_snapshot_as_of(distro.id, since).await?;
+        let packages_after = db.get_latest_package_snapshot(distro.id).await?;
+        if let (Some(before), Some(after)) = (packages_before, packages_after) {
+            let pair = [(&before, &after)];
+            metrics.extend(MetricChange::between("outdated_packages", "outdated packages", &pair, |s| {
+                s.outdated_packages
+            }));
+            metrics.extend(MetricChange::between("security_updates", "security updates", &pair, |s| {
+                s.security_updates
+            }));
+            if let (Some(old), Some(new)) = (before.kernel_version, after.kernel_version) {
+                if old != new {
+                    kernel = Some(KernelChange { before: old, after: new });
+                }
+            }
+        }
+
+        let score_before = db
+            .get_health_scores_as_of(since)
+            .await?
+            .into_iter()
+            .find(|s| s.distro_id == distro.id);
+        let score_after = db.get_latest_health_score(distro.id).await?;
+        let score = match (score_before, score_after) {
+            (Some(before), Some(after)) => {
+                let delta = round(after.overall_score - before.overall_score);
+                (delta != 0.0).then(|| ScoreChange {
+                    before: round(before.overall_score),
+                    after: round(after.overall_score),
+                    delta,
+                })
+            }
+            _ => None,
+        };
+
+        let mut releases: Vec<NewRelease> = db
+            .get_latest_release_snapshots(distro.id)
+            .await?
+            .into_iter()
+            .filter_map(|r| {
+                let published_at = r.published_at.filter(|&at| at > since && at <= until)?;
+                Some(NewRelease {
+                    repo_name: r.repo_name,
+                    tag_name: r.tag_name,
+                    name: r.release_name.filter(|n| !n.is_empty()),
+                    published_at,
+                    prerelease: r.is_prerelease,
+                })
+            })
+            .collect();
+        releases.sort_by_key(|r| std::cmp::Reverse(r.published_at));
+
+        let mut summary = Vec::new();
+        if let Some(score) = &score {
+            summary.push(format!("health score {}", signed(score.delta)));
+        }
+        for metric in &metrics {
+            summary.push(format!("{} {}", metric.label, signed_count(metric.delta)));
+        }
+        if let Some(kernel) = &kernel {
+            summary.push(format!("kernel {} → {}", kernel.before, kernel.after));
+        }
+        for release in &releases {
+            let kind = if release.prerelease { "new pre-release" } else { "new release" };
+            summary.push(format!("{} {}", kind, release.name.as_deref().unwrap_or(&release.tag_name)));
+        }
+
+        Ok(Self {
+            since,
+            until,
+            summary,
+            metrics,
+            score,
+            kernel,
+            releases,
+        })
+    }
+}
+
+impl MetricChange {
+    /// Sum `value` on both sides of each pair; None when nothing moved
+    fn between<T>(
+        key: &'static str,
+        label: &'static str,
+        pairs: &[(&T, &T)],
+        value: impl Fn(&T) -> i64,
+    ) -> Option<Self> {
+        if pairs.is_empty() {
+            return None;
+        }
+        let before: i64 = pairs.iter().map(|(before, _)| value(before)).sum();
+        let after: i64 = pairs.iter().map(|(_, after)| value(after)).sum();
+        let delta = after - before;
+
+        (delta != 0).then(|| Self {
+            key,
+            label,
+            before,
+            after,
+            delta,
+            delta_pct: (before != 0).then(|| round(delta as f64 / before as f64 * 100.0)),
+        })
+    }
+}
+
+/// Match snapshots present at both points by `key`
+fn paired<'a, T>(before: &'a [T], after: &'a [T], key: impl Fn(&T) -> &str) -> Vec<(&'a T, &'a T)> {
+    let earlier: HashMap<&str, &T> = before.iter().map(|s| (key(s), s)).collect();
+    after
+        .iter()
+        .filter_map(|now| Some((*earlier.get(key(now))?, now)))
+        .collect()
+}
+
+fn round(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+fn signed(delta: f64) -> String {
+    if delta < 0.0 {
+        format!("−{:.1}", -delta)
+    } else {
+        format!("+{:.1}", delta)
+    }
+}
+
+/// "+40", "−1.2k", "+3.4M"
+fn signed_count(delta: i64) -> String {
+    let sign = if delta < 0 { "−" } else { "+" };
+    let n = delta.unsigned_abs();
+    let compact = if n < 1_000 {
+        n.to_string()
+    } else if n < 1_000_000 {
+        format!("{:.1}k", n as f64 / 1_000.0)
+    } else {
+        format!("{:.1}M", n as f64 / 1_000_000.0)
+    };
+    format!("{}{}", sign, compact.replace(".0k", "k").replace(".0M", "M"))
+}
@@ -0,0 +1,152 @@
+//! Cohort benchmarking: where a distro's scores sit among comparable distros
+//!
+//! A distro's cohort is its family (the root of its `based_on` chain, e.g. every
+//! Arch derivative plus Arch itself). Families too small to compare against fall back
+//! to the OS family (all Linux distributions, all BSDs).
+
+use crate::stats::{percentile_rank, quantile};
+use distrovitals_database::{Distribution, HealthScore};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Fewest scored members a cohort needs for percentiles to mean anything
+const MIN_COHORT_SIZE: usize = 3;
+
+/// Longest `based_on` chain followed before giving up (guards against cycles)
+const MAX_LINEAGE_DEPTH: usize = 8;
+
+/// A distro's standing within its cohort
+#[derive(Debug, Clone, Serialize)]
+pub struct CohortStats {
+    /// Cohort identifier, e.g. "family:arch" or "os:bsd"
+    pub cohort: String,
+    pub label: String,
+    /// Scored distros in the cohort, including this one
+    pub size: usize,
+    pub overall_percentile: f64,
+    pub development_percentile: f64,
+    pub community_percentile: f64,
+    pub maintenance_percentile: f64,
+    pub median_overall: f64,
+    /// The distro's strongest component in words
+    pub summary: String,
+}
+
+/// Compute cohort standings for every scored distro, keyed by distro id
+pub fn cohort_stats(distros: &[Distribution], scores: &[HealthScore]) -> HashMap<i64, CohortStats> {
+    let by_slug: HashMap<&str, &Distribution> = distros.iter().map(|d| (d.slug.as_str(), d)).collect();
+    let by_id: HashMap<i64, &Distribution> = distros.iter().map(|d| (d.id, d)).collect();
+
+    // Group scores by family root and by OS family
+    let mut families: HashMap<&str, Vec<&HealthScore>> = HashMap::new();
+    let mut os_families: HashMap<&str, Vec<&HealthScore>> = HashMap::new();
+    for score in scores {
+        let Some(distro) = by_id.get(&score.distro_id) else {
+            continue;
+        };
+        families.entry(family_root(distro, &by_slug).slug.as_str()).or_default().push(score);
+        os_families.entry(distro.os_family.as_str()).or_default().push(score);
+    }
+
+    let mut result = HashMap::new();
+    for score in scores {
+        let Some(distro) = by_id.get(&score.distro_id) else {
+            continue;
+        };
+
+        let root = family_root(distro, &by_slug);
+        let (cohort, label, members) = match families.get(root.slug.as_str()) {
+            Some(members) if members.len() >= MIN_COHORT_SIZE => (
+                format!("family:{}", root.slug),
+                format!("the {} family", root.name),
+                members,
+            ),
+            _ => match os_families.get(distro.os_family.as_str()) {
+                Some(members) if members.len() >= MIN_COHORT_SIZE => (
+                    format!("os:{}", distro.os_family),
+                    os_family_label(&distro.os_family),
+                    members,
+                ),
+                _ => continue,
+            },
+        };
+
+        let percentile = |component: fn(&HealthScore) -> f64| {
+            let values: Vec<f64> = members.iter().map(|s| component(s)).collect();
+            percentile_rank(&values, component(score))
+        };
+
+        let overall_percentile = percentile(|s| s.overall_score);
+        let development_percentile = percentile(|s| s.development_score);
+        let community_percentile = percentile(|s| s.community_score);
+        let maintenance_percentile = percentile(|s| s.maintenance_score);
+
+        let mut overall: Vec<f64> = members.iter().map(|s| s.overall_score).collect();
+        overall.sort_by(f64::total_cmp);
+
+        let (strongest, strongest_percentile) = [
+            ("Development", development_percentile),
+            ("Community", community_percentile),
+            ("Maintenance", maintenance_percentile),
+        ]
+        .into_iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap_or(("Overall", overall_percentile));
+
+        let summary = format!(
+            "{} score is in the {} percentile among {} ({} distros)",
+            strongest,
+            ordinal(strongest_percentile.round() as u32),
+            label,
+            members.len()
+        );
+
+        result.insert(
+            score.distro_id,
+            CohortStats {
+                cohort,
+                label,
+                size: members.len(),
+                overall_percentile,
+                development_percentile,
+                community_percentile,
+                maintenance_percentile,
+                median_overall: (quantile(&overall, 0.5) * 100.0).round() / 100.0,
+                summary,
+            },
+        );
+    }
+
+    result
+}
+
+/// Follow `based_on` to the distro at the root of the family
+fn family_root<'a>(distro: &'a Distribution, by_slug: &HashMap<&str, &'a Distribution>) -> &'a Distribution {
+    let mut current = distro;
+    for _ in 0..MAX_LINEAGE_DEPTH {
+        match current.based_on.as_deref().and_then(|parent| by_slug.get(parent)) {
+            Some(parent) if parent.id != current.id => current = parent,
+            _ => break,
+        }
+    }
+    current
+}
+
+fn os_family_label(os_family: &str) -> String {
+    match os_family {
+        "linux" => "Linux distributions".to_string(),
+        "bsd" => "BSD systems".to_string(),
+        other => format!("{} systems", other),
+    }
+}
+
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
@@ -0,0 +1,289 @@
+//! Correlations between raw metrics and score components
+//!
+//! Two views help validate the scoring methodology:
+//!
+//! - **Cross-sectional**: current raw metrics against current score components,
+//!   across all scored distros.
+//! - **Predictive**: values at the start of a window against how scores and raw
+//!   totals changed over it (e.g. do Reddit subscribers predict commit growth?).
+//!
+//! Both Pearson (linear) and Spearman (rank) coefficients are reported; with a few
+//! dozen distros and heavy-tailed metrics like stars, Spearman is usually the one to
+//! trust.
+
+use crate::stats::{self, Metric};
+use crate::Result;
+use chrono::{DateTime, TimeDelta, Utc};
+use distrovitals_database::{Database, HealthScore, MetricTotals};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Fewest paired observations worth correlating
+const MIN_SAMPLES: usize = 5;
+
+/// Score components used as outcomes
+const SCORE_METRICS: &[Metric] = &[
+    Metric::OverallScore,
+    Metric::DevelopmentScore,
+    Metric::CommunityScore,
+    Metric::MaintenanceScore,
+];
+
+/// Raw totals whose growth is an outcome in the predictive view
+const GROWTH_OUTCOMES: &[&str] = &["stars", "commits_30d", "contributors_30d", "reddit_subscribers"];
+
+/// Named values for one distro
+type Fields = Vec<(&'static str, f64)>;
+
+/// Correlation between two series
+#[derive(Debug, Clone, Serialize)]
+pub struct Correlation {
+    pub x: String,
+    pub y: String,
+    /// Number of distros with both values
+    pub n: usize,
+    pub pearson: Option<f64>,
+    pub spearman: Option<f64>,
+}
+
+impl Correlation {
+    fn between(x: &str, y: &str, pairs: &[(f64, f64)]) -> Self {
+        let (xs, ys): (Vec<f64>, Vec<f64>) = pairs.iter().copied().unzip();
+        let enough = pairs.len() >= MIN_SAMPLES;
+
+        Self {
+            x: x.to_string(),
+            y: y.to_string(),
+            n: pairs.len(),
+            pearson: enough.then(|| pearson(&xs, &ys)).flatten().map(round3),
+            spearman: enough.then(|| spearman(&xs, &ys)).flatten().map(round3),
+        }
+    }
+
+    /// Strength used for ordering, preferring the rank coefficient
+    fn strength(&self) -> f64 {
+        self.spearman.or(self.pearson).map(f64::abs).unwrap_or(0.0)
+    }
+}
+
+/// Cross-sectional and predictive correlations across the field
+#[derive(Debug, Clone, Serialize)]
+pub struct CorrelationReport {
+    pub generated_at: DateTime<Utc>,
+    /// Length of the predictive window in days
+    pub window_days: i64,
+    /// Distros in the cross-sectional view
+    pub distros: usize,
+    /// Distros scored both at the start and end of the window
+    pub predictive_distros: usize,
+    pub cross_sectional: Vec<Correlation>,
+    pub predictive: Vec<Correlation>,
+}
+
+impl CorrelationReport {
+    /// Compute both views, with the predictive window ending now
+    pub async fn build(db: &Database, window_days: i64) -> Result<Self> {
+        let now = Utc::now();
+        let start = now - TimeDelta::days(window_days);
+
+        // Cross-sectional: current raw metrics against current score components
+        let samples = stats::load_samples(db).await?;
+        let mut cross_sectional = Vec::new();
+        for x in Metric::ALL.iter().filter(|m| !m.is_score()) {
+            for y in SCORE_METRICS {
+                let pairs: Vec<(f64, f64)> = samples
+                    .iter()
+                    .filter_map(|s| Some((x.value(&s.score, &s.metrics)?, y.value(&s.score, &s.metrics)?)))
+                    .collect();
+                cross_sectional.push(Correlation::between(x.as_str(), y.as_str(), &pairs));
+            }
+        }
+
+        // Predictive: levels at the start of the window against changes over it
+        let before: HashMap<i64, HealthScore> = db
+            .get_health_scores_as_of(start)
+            .await?
+            .into_iter()
+            .map(|s| (s.distro_id, s))
+            .collect();
+        let after = db.get_health_scores_as_of(now).await?;
+
+        let mut rows: Vec<(Fields, Fields)> = Vec::new();
+        for end in &after {
+            let Some(begin) = before.get(&end.distro_id) else {
+                continue;
+            };
+            let totals_before = db.get_metric_totals_as_of(end.distro_id, start).await?;
+            let totals_after = db.get_metric_totals_as_of(end.distro_id, now).await?;
+
+            let mut predictors = totals_fields(&totals_before);
+            predictors.extend(score_fields(begin));
+
+            let mut outcomes: Fields = score_fields(end)
+                .into_iter()
+                .zip(score_fields(begin))
+                .map(|((name, a), (_, b))| (name, a - b))
+                .collect();
+            outcomes.extend(
+                totals_fields(&totals_after)
+                    .into_iter()
+                    .zip(totals_fields(&totals_before))
+                    .filter(|((name, _), _)| GROWTH_OUTCOMES.contains(name))
+                    .map(|((name, a), (_, b))| (name, a - b)),
+            );
+
+            rows.push((predictors, outcomes));
+        }
+
+        let mut predictive = Vec::new();
+        if let Some((predictors, outcomes)) = rows.first() {
+            for (xi, (x, _)) in predictors.iter().enumerate() {
+                for (yi, (y, _)) in outcomes.iter().enumerate() {
+                    // A metric predicting its own growth is mostly regression to the mean
+                    if x == y {
+                        continue;
+                    }
+                    let pairs: Vec<(f64, f64)> =
+                        rows.iter().map(|(p, o)| (p[xi].1, o[yi].1)).collect();
+                    predictive.push(Correlation::between(x, &format!("{} change", y), &pairs));
+                }
+            }
+        }
+
+        cross_sectional.sort_by(|a, b| b.strength().total_cmp(&a.strength()));
+        predictive.sort_by(|a, b| b.strength().total_cmp(&a.strength()));
+
+        Ok(Self {
+            generated_at: now,
+            window_days,
+            distros: samples.len(),
+            predictive_distros: rows.len(),
+            cross_sectional,
+            predictive,
+        })
+    }
+
+    /// Render the report as Markdown
+    pub fn to_markdown(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "# Metric correlations\n");
+        let _ = writeln!(out, "Generated {}.\n", self.generated_at.format("%Y-%m-%d %H:%M UTC"));
+        let _ = writeln!(
+            out,
+            "Coefficients range from -1 to 1. Pairs with fewer than {} distros are left blank.\n",
+            MIN_SAMPLES
+        );
+
+        let _ = writeln!(out, "## Cross-sectional ({} distros)\n", self.distros);
+        let _ = writeln!(out, "Current raw metrics against current score components.\n");
+        write_table(&mut out, &self.cross_sectional);
+
+        let _ = writeln!(
+            out,
+            "\n## Predictive ({} days, {} distros)\n",
+            self.window_days, self.predictive_distros
+        );
+        let _ = writeln!(out, "Values at the start of the window against the change over it.\n");
+        write_table(&mut out, &self.predictive);
+
+        out
+    }
+}
+
+fn totals_fields(t: &MetricTotals) -> Fields {
+    vec![
+        ("stars", t.stars as f64),
+        ("forks", t.forks as f64),
+        ("commits_30d", t.commits_30d as f64),
+        ("contributors_30d", t.contributors_30d as f64),
+        ("open_issues", t.open_issues as f64),
+        ("open_prs", t.open_prs as f64),
+        ("reddit_subscribers", t.reddit_subscribers as f64),
+        ("reddit_posts_30d", t.reddit_posts_30d as f64),
+    ]
+}
+
+fn score_fields(s: &HealthScore) -> Fields {
+    vec![
+        ("overall_score", s.overall_score),
+        ("development_score", s.development_score),
+        ("community_score", s.community_score),
+        ("maintenance_score", s.maintenance_score),
+    ]
+}
+
+fn write_table(out: &mut String, rows: &[Correlation]) {
+    let _ = writeln!(out, "| x | y | n | Spearman | Pearson |");
+    let _ = writeln!(out, "|---|---|---|---|---|");
+    for c in rows {
+        let _ = writeln!(
+            out,
+            "| {} | {} | {} | {} | {} |",
+            c.x,
+            c.y,
+            c.n,
+            format_coefficient(c.spearman),
+            format_coefficient(c.pearson)
+        );
+    }
+}
+
+pub fn format_coefficient(value: Option<f64>) -> String {
+    value.map(|v| format!("{:+.3}", v)).unwrap_or_default()
+}
+
+/// Pearson correlation coefficient, or None when either series is constant
+pub fn pearson(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    let n = xs.len().min(ys.len());
+    if n < 2 {
+        return None;
+    }
+
+    let mean_x = xs[..n].iter().sum::<f64>() / n as f64;
+    let mean_y = ys[..n].iter().sum::<f64>() / n as f64;
+
+    let (mut cov, mut var_x, mut var_y) = (0.0, 0.0, 0.0);
+    for (x, y) in xs.iter().zip(ys) {
+        let (dx, dy) = (x - mean_x, y - mean_y);
+        cov += dx * dy;
+        var_x += dx * dx;
+        var_y += dy * dy;
+    }
+
+    if var_x == 0.0 || var_y == 0.0 {
+        return None;
+    }
+
+    Some(cov / (var_x.sqrt() * var_y.sqrt()))
+}
+
+/// Spearman rank correlation: Pearson over ranks, with ties given their average rank
+pub fn spearman(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    pearson(&ranks(xs), &ranks(ys))
+}
+
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut i = 0;
+    while i < order.len() {
+        let mut j = i;
+        while j + 1 < order.len() && values[order[j + 1]] == values[order[i]] {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        for &index in &order[i..=j] {
+            ranks[index] = rank;
+        }
+        i = j + 1;
+    }
+
+    ranks
+}
+
+fn round3(value: f64) -> f64 {
+    (value * 1000.0).round() / 1000.0
+}
@@ -0,0 +1,321 @@
+//! Weekly digest posted to a Mastodon account
+//!
+//! The digest compares this week's rankings with last week's and lists annotations and
+//! milestones from the week.
+//! The summary post is rendered from a template; each event follows as a reply in the
+//! same thread.
+
+use crate::Result;
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use distrovitals_database::Database;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Mastodon's default status length limit
+const MAX_STATUS_CHARS: usize = 500;
+
+/// Entries shown per list when the post fits
+const LIST_LENGTH: usize = 3;
+
+/// Template used when none is configured
+///
+/// Placeholders: `{week}`, `{top}`, `{risers}`, `{fallers}`, `{new}`, `{events}`.
+pub const DEFAULT_TEMPLATE: &str = "DistroVitals weekly rankings, week ending {week}
+
+Top of the table:
+{top}
+
+Biggest climbers:
+{risers}
+
+Biggest drops:
+{fallers}
+
+#Linux #OpenSource";
+
+/// A distro's position this week against last week
+#[derive(Debug, Clone, Serialize)]
+pub struct RankChange {
+    pub slug: String,
+    pub name: String,
+    pub rank: usize,
+    pub previous_rank: Option<usize>,
+    pub score: f64,
+    pub previous_score: Option<f64>,
+}
+
+impl RankChange {
+    /// Places gained since last week (negative when it dropped)
+    pub fn places_gained(&self) -> Option<i64> {
+        self.previous_rank.map(|prev| prev as i64 - self.rank as i64)
+    }
+}
+
+/// A notable event during the week
+#[derive(Debug, Clone, Serialize)]
+pub struct DigestEvent {
+    pub date: NaiveDate,
+    pub distro: String,
+    pub note: String,
+}
+
+/// Ranking changes and events over the week ending at a point in time
+#[derive(Debug, Clone, Serialize)]
+pub struct WeeklyDigest {
+    pub week_ending: NaiveDate,
+    pub rankings: Vec<RankChange>,
+    pub events: Vec<DigestEvent>,
+}
+
+impl WeeklyDigest {
+    /// Build the digest for the seven days before `now`
+    pub async fn build(db: &Database, now: DateTime<Utc>) -> Result<Self> {
+        let week_ago = now - TimeDelta::days(7);
+
+        let distros: HashMap<i64, (String, String)> = db
+            .get_distributions()
+            .await?
+            .into_iter()
+            .map(|d| (d.id, (d.slug, d.name)))
+            .collect();
+
+        let previous: HashMap<i64, (usize, f64)> = db
+            .get_health_scores_as_of(week_ago)
+            .await?
+            .into_iter()
+            .enumerate()
+            .map(|(i, s)| (s.distro_id, (i + 1, s.overall_score)))
+            .collect();
+
+        let rankings = db
+            .get_health_scores_as_of(now)
+            .await?
+            .into_iter()
+            .filter_map(|s| {
+                let (slug, name) = distros.get(&s.distro_id)?.clone();
+                let previous = previous.get(&s.distro_id);
+                Some((slug, name, s.overall_score, previous.copied()))
+            })
+            .enumerate()
+            .map(|(i, (slug, name, score, previous))| RankChange {
+                slug,
+                name,
+                rank: i + 1,
+                previous_rank: previous.map(|(rank, _)| rank),
+                score,
+                previous_score: previous.map(|(_, score)| score),
+            })
+            .collect();
+
+        let mut events: Vec<DigestEvent> = db
+            .get_annotations_since(week_ago.date_naive())
+            .await?
+            .into_iter()
+            .filter_map(|a| {
+                let (_, name) = distros.get(&a.distro_id)?;
+                Some(DigestEvent {
+                    date: a.annotated_on,
+                    distro: name.clone(),
+                    note: a.note,
+                })
+            })
+            .collect();
+
+        for m in db.get_milestones(None, week_ago).await? {
+            if let Some((_, name)) = distros.get(&m.distro_id) {
+                events.push(DigestEvent {
+                    date: m.occurred_at.date_naive(),
+                    distro: name.clone(),
+                    note: m.title,
+                });
+            }
+        }
+        events.sort_by_key(|e| e.date);
+
+        Ok(Self {
+            week_ending: now.date_naive(),
+            rankings,
+            events,
+        })
+    }
+
+    /// Render the summary post, shortening the lists until it fits in one status
+    pub fn render(&self, template: &str) -> String {
+        for n in (1..=LIST_LENGTH).rev() {
+            let post = self.render_with(template, n);
+            if post.chars().count() <= MAX_STATUS_CHARS {
+                return post;
+            }
+        }
+
+        truncate(&self.render_with(template, 1))
+    }
+
+    /// One reply per event, in date order
+    pub fn event_posts(&self) -> Vec<String> {
+        self.events
+            .iter()
+            .map(|e| truncate(&format!("{} ({}): {}", e.distro, e.date.format("%b %-d"), e.note)))
+            .collect()
+    }
+
+    fn render_with(&self, template: &str, n: usize) -> String {
+        let top = self
+            .rankings
+            .iter()
+            .take(n)
+            .map(|r| format!("{}. {} ({:.1})", r.rank, r.name, r.score))
+            .collect::<Vec<_>>();
+
+        let mut movers: Vec<(&RankChange, i64)> = self
+            .rankings
+            .iter()
+            .filter_map(|r| r.places_gained().map(|gained| (r, gained)))
+            .filter(|(_, gained)| *gained != 0)
+            .collect();
+        movers.sort_by_key(|(_, gained)| std::cmp::Reverse(*gained));
+
+        let risers = movers
+            .iter()
+            .filter(|(_, gained)| *gained > 0)
+            .take(n)
+            .map(|(r, gained)| format!("▲{} {} → #{}", gained, r.name, r.rank))
+            .collect::<Vec<_>>();
+
+        let fallers = movers
+            .iter()
+            .rev()
+            .filter(|(_, gained)| *gained < 0)
+            .take(n)
+            .map(|(r, gained)| format!("▼{} {} → #{}", -gained, r.name, r.rank))
+            .collect::<Vec<_>>();
+
+        let new = self
+            .rankings
+            .iter()
+            .filter(|r| r.previous_rank.is_none())
+            .take(n)
+            .map(|r| format!("{} enters at #{}", r.name, r.rank))
+            .collect::<Vec<_>>();
+
+        let events = self
+            .events
+            .iter()
+            .take(n)
+            .map(|e| format!("{}: {}", e.distro, e.note))
+            .collect::<Vec<_>>();
+
+        template
+            .replace("{week}", &self.week_ending.format("%B %-d, %Y").to_string())
+            .replace("{top}", &list_or_none(&top))
+            .replace("{risers}", &list_or_none(&risers))
+            .replace("{fallers}", &list_or_none(&fallers))
+            .replace("{new}", &list_or_none(&new))
+            .replace("{events}", &list_or_none(&events))
+    }
+}
+
+fn list_or_none(lines: &[String]) -> String {
+    if lines.is_empty() {
+        "None this week".to_string()
+    } else {
+        lines.join("\n")
+    }
+}
+
+fn truncate(post: &str) -> String {
+    if post.chars().count() <= MAX_STATUS_CHARS {
+        return post.to_string();
+    }
+
+    let mut cut: String = post.chars().take(MAX_STATUS_CHARS - 1).collect();
+    cut.push('…');
+    cut
+}
+
+/// Posts statuses to a Mastodon account
+pub struct MastodonPublisher {
+    client: reqwest::Client,
+    instance: String,
+    token: String,
+    visibility: String,
+}
+
+/// Posts made by a publish run
+#[derive(Debug, Clone)]
+pub struct PublishedThread {
+    pub status_ids: Vec<String>,
+}
+
+#[derive(serde::Deserialize)]
+struct StatusResponse {
+    id: String,
+}
+
+impl MastodonPublisher {
+    /// Publisher for `DV_MASTODON_INSTANCE` and `DV_MASTODON_TOKEN`, if both are set
+    ///
+    /// `DV_MASTODON_VISIBILITY` may be public (default), unlisted or private.
+    pub fn from_env() -> Option<Self> {
+        let instance = std::env::var("DV_MASTODON_INSTANCE").ok().filter(|s| !s.is_empty())?;
+        let token = std::env::var("DV_MASTODON_TOKEN").ok().filter(|s| !s.is_empty())?;
+        let visibility = std::env::var("DV_MASTODON_VISIBILITY")
+            .ok()
+            .filter(|s| !s.is_empty())
+            .unwrap_or_else(|| "public".to_string());
+
+        Some(Self {
+            client: reqwest::Client::new(),
+            instance: instance.trim_end_matches('/').to_string(),
+            token,
+            visibility,
+        })
+    }
+
+    /// Post the summary and thread each event under it
+    ///
+    /// The idempotency key makes a retried run for the same week return the original
+    /// statuses instead of posting duplicates.
+    pub async fn publish(
+        &self,
+        key: &str,
+        summary: &str,
+        replies: &[String],
+    ) -> std::result::Result<PublishedThread, String> {
+        let mut status_ids: Vec<String> = Vec::new();
+        let statuses = std::iter::once(summary).chain(replies.iter().map(String::as_str));
+
+        for (i, status) in statuses.enumerate() {
+            let mut form = vec![
+                ("status", status.to_string()),
+                ("visibility", self.visibility.clone()),
+            ];
+            if let Some(parent) = status_ids.last() {
+                form.push(("in_reply_to_id", parent.clone()));
+            }
+
+            let response = self
+                .client
+                .post(format!("{}/api/v1/statuses", self.instance))
+                .bearer_auth(&self.token)
+                .header("Idempotency-Key", format!("{}-{}", key, i))
+                .form(&form)
+                .timeout(Duration::from_secs(30))
+                .send()
+                .await
+                .map_err(|e| e.to_string())?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let body = response.text().await.unwrap_or_default();
+                return Err(format!("Mastodon responded with {}: {}", status, body.trim()));
+            }
+
+            let posted: StatusResponse = response.json().await.map_err(|e| e.to_string())?;
+            status_ids.push(posted.id);
+        }
+
+        Ok(PublishedThread { status_ids })
+    }
+}
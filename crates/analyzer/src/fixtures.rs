@@ -0,0 +1,515 @@
+//! Representative snapshot bundles for exercising the scoring
+//!
+//! Each bundle is what [`Analyzer::score`](crate::Analyzer::score) would be handed for one
+//! kind of distro, dated relative to `now`. The golden tests below pin their scores, so a
+//! change to the methodology shows up as a deliberate edit to the expected numbers rather
+//! than a silent shift in the rankings.
+
+use crate::{Aggregation, ScoreInputs, StarVelocity, SubscriberVelocity};
+use chrono::{DateTime, TimeDelta, Utc};
+use distrovitals_database::{
+    ArchiveSnapshot, CommunitySnapshot, GithubSnapshot, InfraSnapshot, IssueActivity, IssueLabelCount,
+    PackageSnapshot,
+};
+
+/// A large, busy distro: many contributors, a big backlog, a popular subreddit and history
+/// to measure growth against
+pub fn big_distro(now: DateTime<Utc>) -> ScoreInputs {
+    ScoreInputs {
+        github: vec![
+            GithubSnapshot {
+                stars: 14_200,
+                forks: 3_100,
+                open_issues: 820,
+                open_prs: 140,
+                commits_30d: 640,
+                commits_365d: 7_300,
+                contributors_30d: 95,
+                bot_commits_30d: 85,
+                bot_commits_365d: 1_020,
+                last_commit_at: Some(now - TimeDelta::hours(3)),
+                ..repo("big/packages", now)
+            },
+            GithubSnapshot {
+                stars: 2_300,
+                forks: 410,
+                open_issues: 150,
+                open_prs: 25,
+                commits_30d: 120,
+                commits_365d: 1_450,
+                contributors_30d: 30,
+                last_commit_at: Some(now - TimeDelta::days(1)),
+                ..repo("big/installer", now)
+            },
+            GithubSnapshot {
+                stars: 640,
+                forks: 90,
+                open_issues: 35,
+                open_prs: 6,
+                commits_30d: 18,
+                commits_365d: 260,
+                contributors_30d: 7,
+                last_commit_at: Some(now - TimeDelta::days(9)),
+                ..repo("big/website", now)
+            },
+        ],
+        community: vec![
+            community("reddit:r/bigdistro", Some(240_000), Some(950), now),
+            community("discourse:discuss.bigdistro.org", Some(5_200), Some(1_800), now),
+        ],
+        packages: Some(packages(62_000, 5_400, Some("longterm"), now)),
+        issue_labels: issue_labels(&[("security", 40), ("bug", 300), ("packaging", 120), ("feature", 180)]),
+        issue_activity: Some(IssueActivity {
+            opened_30d: 410,
+            closed_30d: 365,
+            stale_closed_30d: 0,
+        }),
+        first_time_contributors: Some(14),
+        infra: Some(infra(180, Some(45), now)),
+        archive: None,
+        aggregation: Aggregation::Sum,
+        star_velocity: Some(StarVelocity {
+            delta: 310,
+            baseline: 16_830,
+            days: 31,
+        }),
+        subscriber_velocity: Some(SubscriberVelocity {
+            window_days: 90,
+            delta: 4_800,
+            baseline: 235_200,
+        }),
+    }
+}
+
+/// A one-person distro with a single repo, a handful of users and no growth history yet
+pub fn tiny_distro(now: DateTime<Utc>) -> ScoreInputs {
+    ScoreInputs {
+        github: vec![GithubSnapshot {
+            stars: 42,
+            forks: 3,
+            open_issues: 2,
+            commits_30d: 6,
+            commits_365d: 71,
+            contributors_30d: 1,
+            last_commit_at: Some(now - TimeDelta::days(12)),
+            ..repo("tiny/tiny-iso", now)
+        }],
+        community: vec![community("reddit:r/tinydistro", Some(830), Some(4), now)],
+        packages: None,
+        issue_labels: Vec::new(),
+        issue_activity: None,
+        first_time_contributors: None,
+        infra: None,
+        archive: None,
+        aggregation: Aggregation::Sum,
+        star_velocity: None,
+        subscriber_velocity: None,
+    }
+}
+
+/// A healthy mid-sized distro whose community lives on its own forum, not Reddit
+pub fn no_reddit_distro(now: DateTime<Utc>) -> ScoreInputs {
+    ScoreInputs {
+        github: vec![
+            GithubSnapshot {
+                stars: 3_900,
+                forks: 520,
+                open_issues: 180,
+                open_prs: 32,
+                commits_30d: 260,
+                commits_365d: 2_900,
+                contributors_30d: 38,
+                bot_commits_30d: 40,
+                last_commit_at: Some(now - TimeDelta::hours(20)),
+                ..repo("quiet/ports", now)
+            },
+            GithubSnapshot {
+                stars: 480,
+                forks: 60,
+                open_issues: 24,
+                open_prs: 4,
+                commits_30d: 30,
+                commits_365d: 310,
+                contributors_30d: 9,
+                last_commit_at: Some(now - TimeDelta::days(4)),
+                ..repo("quiet/base", now)
+            },
+        ],
+        community: vec![community("discourse:forum.quiet.org", Some(2_100), Some(640), now)],
+        packages: Some(packages(31_000, 4_650, Some("stable"), now)),
+        issue_labels: Vec::new(),
+        issue_activity: None,
+        first_time_contributors: None,
+        infra: None,
+        archive: None,
+        aggregation: Aggregation::Sum,
+        star_velocity: Some(StarVelocity {
+            delta: 45,
+            baseline: 4_335,
+            days: 30,
+        }),
+        subscriber_velocity: None,
+    }
+}
+
+/// A distro that has stopped moving: no recent commits, an old backlog kept down by a stale
+/// bot, an EOL kernel and a shrinking subreddit
+pub fn stale_distro(now: DateTime<Utc>) -> ScoreInputs {
+    ScoreInputs {
+        github: vec![
+            GithubSnapshot {
+                stars: 5_600,
+                forks: 700,
+                open_issues: 430,
+                open_prs: 75,
+                commits_365d: 12,
+                last_commit_at: Some(now - TimeDelta::days(290)),
+                ..repo("stale/core", now)
+            },
+            GithubSnapshot {
+                stars: 310,
+                forks: 44,
+                open_issues: 61,
+                open_prs: 9,
+                commits_365d: 3,
+                last_commit_at: Some(now - TimeDelta::days(410)),
+                ..repo("stale/tools", now)
+            },
+        ],
+        community: vec![community("reddit:r/staledistro", Some(18_500), Some(11), now)],
+        packages: Some(packages(24_000, 13_900, Some("eol"), now)),
+        issue_labels: Vec::new(),
+        // A stale bot closing nearly everything keeps the backlog looking small
+        issue_activity: Some(IssueActivity {
+            opened_30d: 36,
+            closed_30d: 41,
+            stale_closed_30d: 38,
+        }),
+        first_time_contributors: Some(0),
+        // Everything from one university server
+        infra: Some(infra(1, Some(1), now)),
+        archive: None,
+        aggregation: Aggregation::Sum,
+        star_velocity: Some(StarVelocity {
+            delta: -12,
+            baseline: 5_922,
+            days: 33,
+        }),
+        subscriber_velocity: Some(SubscriberVelocity {
+            window_days: 90,
+            delta: -240,
+            baseline: 18_740,
+        }),
+    }
+}
+
+/// A big distro developed in its own package archive rather than on a forge, like Debian
+pub fn archive_distro(now: DateTime<Utc>) -> ScoreInputs {
+    ScoreInputs {
+        github: Vec::new(),
+        community: vec![community("reddit:r/archivedistro", Some(120_000), Some(310), now)],
+        packages: Some(packages(37_000, 4_100, Some("longterm"), now)),
+        issue_labels: Vec::new(),
+        issue_activity: None,
+        first_time_contributors: None,
+        infra: Some(infra(340, Some(70), now)),
+        archive: Some(archive(37_500, Some(610), Some(2_700), now)),
+        aggregation: Aggregation::Sum,
+        star_velocity: None,
+        subscriber_velocity: None,
+    }
+}
+
+/// A young distro whose users ask for help in GitHub Discussions rather than on Reddit,
+/// like Bazzite
+pub fn discussions_distro(now: DateTime<Utc>) -> ScoreInputs {
+    ScoreInputs {
+        github: vec![GithubSnapshot {
+            stars: 6_800,
+            forks: 240,
+            open_issues: 150,
+            open_prs: 12,
+            commits_30d: 210,
+            commits_365d: 2_400,
+            contributors_30d: 24,
+            bot_commits_30d: 90,
+            last_commit_at: Some(now - TimeDelta::hours(6)),
+            ..repo("young/young-os", now)
+        }],
+        community: vec![discussions(85, 40, 26, Some(30.0), now)],
+        packages: None,
+        issue_labels: Vec::new(),
+        issue_activity: None,
+        first_time_contributors: None,
+        infra: None,
+        archive: None,
+        aggregation: Aggregation::Sum,
+        star_velocity: None,
+        subscriber_velocity: None,
+    }
+}
+
+/// Every bundle, by name
+pub fn all(now: DateTime<Utc>) -> Vec<(&'static str, ScoreInputs)> {
+    vec![
+        ("big", big_distro(now)),
+        ("tiny", tiny_distro(now)),
+        ("no-reddit", no_reddit_distro(now)),
+        ("stale", stale_distro(now)),
+        ("archive", archive_distro(now)),
+        ("discussions", discussions_distro(now)),
+    ]
+}
+
+/// A repo with nothing going on, collected at `now`
+pub(crate) fn repo(name: &str, now: DateTime<Utc>) -> GithubSnapshot {
+    GithubSnapshot {
+        id: 0,
+        distro_id: 0,
+        repo_name: name.to_string(),
+        stars: 0,
+        forks: 0,
+        open_issues: 0,
+        open_prs: 0,
+        commits_30d: 0,
+        commits_365d: 0,
+        contributors_30d: 0,
+        bot_commits_30d: 0,
+        bot_commits_365d: 0,
+        is_mirror: false,
+        topics: None,
+        last_commit_at: None,
+        provenance: None,
+        collected_at: now,
+    }
+}
+
+pub(crate) fn community(
+    source: &str,
+    active_users: Option<i64>,
+    posts: Option<i64>,
+    now: DateTime<Utc>,
+) -> CommunitySnapshot {
+    CommunitySnapshot {
+        id: 0,
+        distro_id: 0,
+        source: source.to_string(),
+        active_users_30d: active_users,
+        posts_30d: posts,
+        response_time_avg_hours: None,
+        questions_30d: None,
+        answers_30d: None,
+        provenance: None,
+        collected_at: now,
+    }
+}
+
+pub(crate) fn discussions(
+    posts: i64,
+    questions: i64,
+    answers: i64,
+    answer_hours: Option<f64>,
+    now: DateTime<Utc>,
+) -> CommunitySnapshot {
+    CommunitySnapshot {
+        questions_30d: Some(questions),
+        answers_30d: Some(answers),
+        response_time_avg_hours: answer_hours,
+        ..community("github-discussions:org/repo", Some(posts), Some(posts), now)
+    }
+}
+
+pub(crate) fn packages(
+    total: i64,
+    outdated: i64,
+    kernel_status: Option<&str>,
+    now: DateTime<Utc>,
+) -> PackageSnapshot {
+    PackageSnapshot {
+        id: 0,
+        distro_id: 0,
+        total_packages: total,
+        outdated_packages: outdated,
+        security_updates: 0,
+        kernel_version: None,
+        kernel_series: None,
+        kernel_status: kernel_status.map(str::to_string),
+        kernel_upstream: None,
+        mesa_version: None,
+        mesa_upstream: None,
+        official_packages: None,
+        flagged_packages: None,
+        aur_packages: None,
+        provenance: None,
+        collected_at: now,
+    }
+}
+
+pub(crate) fn infra(mirrors: i64, countries: Option<i64>, now: DateTime<Utc>) -> InfraSnapshot {
+    InfraSnapshot {
+        id: 0,
+        distro_id: 0,
+        mirrors,
+        mirror_countries: countries,
+        provenance: None,
+        collected_at: now,
+    }
+}
+
+pub(crate) fn archive(
+    source_packages: i64,
+    rc_bugs: Option<i64>,
+    uploads_30d: Option<i64>,
+    now: DateTime<Utc>,
+) -> ArchiveSnapshot {
+    ArchiveSnapshot {
+        id: 0,
+        distro_id: 0,
+        source_packages,
+        rc_bugs,
+        uploads: None,
+        uploads_30d,
+        provenance: None,
+        collected_at: now,
+    }
+}
+
+/// Open issues per label category, each counted in one repo
+pub(crate) fn issue_labels(counts: &[(&str, i64)]) -> Vec<IssueLabelCount> {
+    counts
+        .iter()
+        .map(|(category, open_issues)| IssueLabelCount {
+            category: category.to_string(),
+            open_issues: *open_issues,
+            repos: 1,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Analyzer, Scores};
+
+    fn assert_scores(inputs: ScoreInputs, expected: Scores) {
+        let scores = Analyzer::score(&inputs, Utc::now());
+        for (component, actual, expected) in [
+            ("overall", scores.overall, expected.overall),
+            ("development", scores.development, expected.development),
+            ("community", scores.community, expected.community),
+            ("maintenance", scores.maintenance, expected.maintenance),
+        ] {
+            assert!(
+                (actual - expected).abs() < 1e-9,
+                "{} score changed: expected {}, got {}",
+                component,
+                expected,
+                actual
+            );
+        }
+    }
+
+    #[test]
+    fn big_distro_score() {
+        assert_scores(
+            big_distro(Utc::now()),
+            Scores {
+                overall: 81.0614,
+                development: 95.0,
+                community: 72.01,
+                maintenance: 71.528,
+            },
+        );
+    }
+
+    #[test]
+    fn tiny_distro_score() {
+        assert_scores(
+            tiny_distro(Utc::now()),
+            Scores {
+                overall: 39.8,
+                development: 20.0,
+                community: 20.0,
+                maintenance: 86.0,
+            },
+        );
+    }
+
+    #[test]
+    fn no_reddit_distro_score() {
+        assert_scores(
+            no_reddit_distro(Utc::now()),
+            Scores {
+                overall: 74.54,
+                development: 80.0,
+                community: 65.0,
+                maintenance: 76.8,
+            },
+        );
+    }
+
+    #[test]
+    fn stale_distro_score() {
+        assert_scores(
+            stale_distro(Utc::now()),
+            Scores {
+                overall: 20.7074,
+                development: 20.0,
+                community: 27.99,
+                maintenance: 14.368,
+            },
+        );
+    }
+
+    #[test]
+    fn archive_distro_score() {
+        assert_scores(
+            archive_distro(Utc::now()),
+            Scores {
+                overall: 82.13,
+                development: 95.0,
+                community: 72.8,
+                maintenance: 74.3,
+            },
+        );
+    }
+
+    #[test]
+    fn discussions_distro_score() {
+        assert_scores(
+            discussions_distro(Utc::now()),
+            Scores {
+                overall: 77.1,
+                development: 72.0,
+                community: 76.0,
+                maintenance: 85.0,
+            },
+        );
+    }
+
+    #[test]
+    fn bundles_rank_in_expected_order() {
+        let now = Utc::now();
+        let overall = |inputs: ScoreInputs| Analyzer::score(&inputs, now).overall;
+
+        assert!(overall(big_distro(now)) > overall(no_reddit_distro(now)));
+        assert!(overall(no_reddit_distro(now)) > overall(tiny_distro(now)));
+        assert!(overall(tiny_distro(now)) > overall(stale_distro(now)));
+    }
+
+    #[test]
+    fn fingerprint_follows_snapshot_ids_not_their_order() {
+        let now = Utc::now();
+        let mut inputs = big_distro(now);
+        for (id, snapshot) in inputs.github.iter_mut().enumerate() {
+            snapshot.id = id as i64 + 1;
+        }
+        let fingerprint = inputs.fingerprint();
+
+        inputs.github.reverse();
+        assert_eq!(inputs.fingerprint(), fingerprint);
+
+        inputs.github[0].id += 100;
+        assert_ne!(inputs.fingerprint(), fingerprint);
+    }
+}
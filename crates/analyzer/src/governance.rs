@@ -0,0 +1,71 @@
+//! Governance checklist: whether a newcomer can find out how to take part
+//!
+//! Shown next to the health score rather than folded into it. Having the documents says
+//! nothing about how well a project is run, but missing ones are a real hurdle for new
+//! contributors and for anyone reporting a vulnerability.
+
+use crate::Result;
+use chrono::{DateTime, Utc};
+use distrovitals_database::{Database, GovernanceSnapshot};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChecklistItem {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub present: bool,
+}
+
+/// The checklist for one distribution, from its latest governance collection
+#[derive(Debug, Clone, Serialize)]
+pub struct GovernanceChecklist {
+    pub items: Vec<ChecklistItem>,
+    /// Share of items present, 0-100
+    pub score: f64,
+    pub repos_checked: i64,
+    pub checked_at: DateTime<Utc>,
+}
+
+impl GovernanceChecklist {
+    pub fn from_snapshot(snapshot: &GovernanceSnapshot) -> Self {
+        let items = vec![
+            ChecklistItem {
+                key: "contributing",
+                label: "Contributing guide",
+                present: snapshot.contributing,
+            },
+            ChecklistItem {
+                key: "code_of_conduct",
+                label: "Code of conduct",
+                present: snapshot.code_of_conduct,
+            },
+            ChecklistItem {
+                key: "security_policy",
+                label: "Security policy",
+                present: snapshot.security_policy,
+            },
+            ChecklistItem {
+                key: "governance",
+                label: "Governance docs",
+                present: snapshot.governance,
+            },
+        ];
+        let present = items.iter().filter(|item| item.present).count();
+        let score = (present as f64 / items.len() as f64 * 1000.0).round() / 10.0;
+
+        Self {
+            items,
+            score,
+            repos_checked: snapshot.repos_checked,
+            checked_at: snapshot.collected_at,
+        }
+    }
+
+    /// Checklist for a distribution, `None` before its governance docs were first checked
+    pub async fn load(db: &Database, distro_id: i64) -> Result<Option<Self>> {
+        Ok(db
+            .get_latest_governance_snapshot(distro_id)
+            .await?
+            .map(|snapshot| Self::from_snapshot(&snapshot)))
+    }
+}
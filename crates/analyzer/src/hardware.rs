@@ -0,0 +1,149 @@
+//! Hardware enablement lag for point-release distros
+//!
+//! New laptops need a recent kernel and Mesa. A point release freezes both when it ships,
+//! so what a buyer cares about is how far behind upstream the installer already was on
+//! release day. Release day is taken as the first package snapshot showing the kernel
+//! series the distro ships now, so distros tracked only after their release look as far
+//! behind as upstream had moved by then. Informational; not part of the health score.
+
+use crate::Result;
+use chrono::{DateTime, Utc};
+use distrovitals_database::{Database, Distribution, PackageSnapshot};
+use serde::Serialize;
+
+/// Upper bound on a kernel major's minor series, e.g. 5.19 was followed by 6.0
+const KERNEL_SERIES_PER_MAJOR: u32 = 20;
+
+/// Mesa's feature releases per year (YY.0 to YY.3)
+const MESA_SERIES_PER_MAJOR: u32 = 4;
+
+/// How far one component in the installer trails upstream
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ComponentLag {
+    pub shipped: String,
+    /// Upstream's newest version when the shipped kernel series arrived
+    pub upstream: String,
+    /// Upstream feature releases (kernel or Mesa series) the installer lacks
+    pub releases_behind: i64,
+}
+
+/// Hardware enablement lag for one distribution
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HardwareEnablement {
+    pub kernel: Option<ComponentLag>,
+    pub mesa: Option<ComponentLag>,
+    /// The larger of the kernel and Mesa lag
+    pub lag: i64,
+    /// When the shipped kernel series was first seen, standing in for the release date
+    pub released_at: DateTime<Utc>,
+}
+
+impl HardwareEnablement {
+    /// Lag of the installer in `latest` against upstream as recorded in `at_release`
+    pub fn measure(latest: &PackageSnapshot, at_release: &PackageSnapshot) -> Option<Self> {
+        let kernel = component_lag(
+            latest.kernel_version.as_deref(),
+            at_release.kernel_upstream.as_deref(),
+            KERNEL_SERIES_PER_MAJOR,
+        );
+        let mesa = component_lag(
+            latest.mesa_version.as_deref(),
+            at_release.mesa_upstream.as_deref(),
+            MESA_SERIES_PER_MAJOR,
+        );
+        let lag = kernel.iter().chain(&mesa).map(|c| c.releases_behind).max()?;
+
+        Some(Self {
+            kernel,
+            mesa,
+            lag,
+            released_at: at_release.collected_at,
+        })
+    }
+
+    /// Lag for a distribution; `None` unless it's a point release with kernel history
+    pub async fn load(db: &Database, distro: &Distribution) -> Result<Option<Self>> {
+        if distro.release_model.as_deref() != Some("point") {
+            return Ok(None);
+        }
+        let Some(latest) = db.get_latest_package_snapshot(distro.id).await? else {
+            return Ok(None);
+        };
+        let Some(ref series) = latest.kernel_series else {
+            return Ok(None);
+        };
+        let Some(at_release) = db.get_first_package_snapshot_with_kernel(distro.id, series).await? else {
+            return Ok(None);
+        };
+
+        Ok(Self::measure(&latest, &at_release))
+    }
+}
+
+fn component_lag(shipped: Option<&str>, upstream: Option<&str>, per_major: u32) -> Option<ComponentLag> {
+    let (shipped, upstream) = (shipped?, upstream?);
+    Some(ComponentLag {
+        shipped: shipped.to_string(),
+        upstream: upstream.to_string(),
+        releases_behind: series_behind(series(shipped)?, series(upstream)?, per_major),
+    })
+}
+
+/// (major, minor) of a version string, e.g. "6.1.119" -> (6, 1)
+fn series(version: &str) -> Option<(u32, u32)> {
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    Some((parts.next()?.parse().ok()?, parts.next()?.parse().ok()?))
+}
+
+/// Feature releases between two series, counting at least one per major bump
+fn series_behind(shipped: (u32, u32), upstream: (u32, u32), per_major: u32) -> i64 {
+    if shipped >= upstream {
+        return 0;
+    }
+    let ((shipped_major, shipped_minor), (upstream_major, upstream_minor)) = (shipped, upstream);
+    if shipped_major == upstream_major {
+        return (upstream_minor - shipped_minor) as i64;
+    }
+
+    // Rest of the shipped major, whole majors in between, then the upstream major so far
+    let rest_of_major = per_major.saturating_sub(shipped_minor).max(1);
+    let between = (upstream_major - shipped_major - 1) * per_major;
+    (rest_of_major + between + upstream_minor) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::packages;
+
+    #[test]
+    fn counts_series_across_major_bumps() {
+        assert_eq!(series_behind((6, 1), (6, 12), KERNEL_SERIES_PER_MAJOR), 11);
+        assert_eq!(series_behind((5, 19), (6, 2), KERNEL_SERIES_PER_MAJOR), 3);
+        assert_eq!(series_behind((4, 20), (5, 1), KERNEL_SERIES_PER_MAJOR), 2);
+        assert_eq!(series_behind((24, 2), (25, 1), MESA_SERIES_PER_MAJOR), 3);
+        assert_eq!(series_behind((6, 12), (6, 1), KERNEL_SERIES_PER_MAJOR), 0);
+    }
+
+    #[test]
+    fn lag_is_the_worse_component_against_upstream_at_release() {
+        let now = Utc::now();
+        let mut at_release = packages(100, 10, Some("longterm"), now - chrono::TimeDelta::days(200));
+        at_release.kernel_upstream = Some("6.5.3".into());
+        at_release.mesa_upstream = Some("23.2.1".into());
+        let mut latest = packages(100, 10, Some("longterm"), now);
+        latest.kernel_version = Some("6.1.119".into());
+        latest.kernel_upstream = Some("6.12.4".into());
+        latest.mesa_version = Some("22.3.6".into());
+
+        let lag = HardwareEnablement::measure(&latest, &at_release).unwrap();
+        assert_eq!(lag.kernel.as_ref().map(|k| k.releases_behind), Some(4));
+        assert_eq!(lag.mesa.as_ref().map(|m| m.releases_behind), Some(3));
+        assert_eq!(lag.lag, 4);
+        assert_eq!(lag.released_at, at_release.collected_at);
+
+        latest.kernel_version = None;
+        latest.mesa_version = None;
+        assert_eq!(HardwareEnablement::measure(&latest, &at_release), None);
+    }
+}
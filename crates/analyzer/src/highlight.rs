@@ -0,0 +1,185 @@
+//! "Distro of the week" selection for the homepage hero
+//!
+//! Candidates are tried in order of how rare they are: a score milestone crossed this
+//! week, a newly tracked distro, the biggest improver, and finally the top of the table.
+//! Last week's pick is skipped so the card rotates.
+
+use crate::Result;
+use chrono::{DateTime, TimeDelta, Utc};
+use distrovitals_database::{Database, HealthScore, Highlight, NewHighlight};
+use std::collections::HashMap;
+
+/// Smallest weekly gain that counts as an improvement worth featuring
+const MIN_IMPROVEMENT: f64 = 1.0;
+
+/// Overall scores are featured when they cross a multiple of this
+const MILESTONE_STEP: f64 = 10.0;
+
+/// Picks and records highlighted distros
+pub struct Highlighter;
+
+impl Highlighter {
+    /// Select this week's highlight and record it
+    pub async fn run(db: &Database) -> Result<Option<Highlight>> {
+        let Some(pick) = Self::select(db, Utc::now()).await? else {
+            return Ok(None);
+        };
+
+        db.insert_highlight(pick).await?;
+        Ok(db.get_latest_highlight().await?)
+    }
+
+    /// Select a new highlight only if the current one is more than a week old
+    pub async fn refresh(db: &Database) -> Result<Option<Highlight>> {
+        match db.get_latest_highlight().await? {
+            Some(current) if current.selected_at > Utc::now() - TimeDelta::days(7) => Ok(Some(current)),
+            _ => Self::run(db).await,
+        }
+    }
+
+    /// Choose a highlight for the week ending at `now` without recording it
+    pub async fn select(db: &Database, now: DateTime<Utc>) -> Result<Option<NewHighlight>> {
+        let week_ago = now - TimeDelta::days(7);
+
+        let names: HashMap<i64, String> = db
+            .get_distributions()
+            .await?
+            .into_iter()
+            .map(|d| (d.id, d.name))
+            .collect();
+
+        let previous: HashMap<i64, HealthScore> = db
+            .get_health_scores_as_of(week_ago)
+            .await?
+            .into_iter()
+            .map(|s| (s.distro_id, s))
+            .collect();
+
+        let first_scored: HashMap<i64, DateTime<Utc>> =
+            db.get_first_score_times().await?.into_iter().collect();
+
+        // Don't feature the same distro two weeks running
+        let last_pick = db
+            .get_latest_highlight()
+            .await?
+            .filter(|h| h.selected_at > now - TimeDelta::days(14))
+            .map(|h| h.distro_id);
+
+        let current: Vec<(usize, HealthScore)> = db
+            .get_health_scores_as_of(now)
+            .await?
+            .into_iter()
+            .filter(|s| names.contains_key(&s.distro_id))
+            .enumerate()
+            .map(|(i, s)| (i + 1, s))
+            .collect();
+
+        let candidates = || current.iter().filter(|(_, s)| Some(s.distro_id) != last_pick);
+        let name = |s: &HealthScore| names[&s.distro_id].clone();
+
+        // Highest milestone crossed, then the biggest jump across it
+        let milestone = candidates()
+            .filter_map(|(_, s)| {
+                let prev = previous.get(&s.distro_id)?;
+                let threshold = (s.overall_score / MILESTONE_STEP).floor() * MILESTONE_STEP;
+                (prev.overall_score < threshold && threshold > 0.0)
+                    .then_some((s, prev, threshold))
+            })
+            .max_by(|a, b| {
+                let gain = |(s, prev, _): &(&HealthScore, &HealthScore, f64)| {
+                    s.overall_score - prev.overall_score
+                };
+                a.2.total_cmp(&b.2).then(gain(a).total_cmp(&gain(b)))
+            });
+
+        if let Some((s, prev, threshold)) = milestone {
+            return Ok(Some(NewHighlight {
+                distro_id: s.distro_id,
+                reason: "milestone".to_string(),
+                blurb: format!(
+                    "{} crossed a health score of {:.0} this week, rising from {:.1} to {:.1}.",
+                    name(s),
+                    threshold,
+                    prev.overall_score,
+                    s.overall_score
+                ),
+                overall_score: s.overall_score,
+                score_change: Some(s.overall_score - prev.overall_score),
+            }));
+        }
+
+        let newcomer = candidates()
+            .filter(|(_, s)| first_scored.get(&s.distro_id).is_some_and(|t| *t > week_ago))
+            .min_by_key(|(rank, _)| *rank);
+
+        if let Some((rank, s)) = newcomer {
+            return Ok(Some(NewHighlight {
+                distro_id: s.distro_id,
+                reason: "newly_tracked".to_string(),
+                blurb: format!(
+                    "{} is newly tracked and debuts at #{} with a health score of {:.1}.",
+                    name(s),
+                    rank,
+                    s.overall_score
+                ),
+                overall_score: s.overall_score,
+                score_change: None,
+            }));
+        }
+
+        let improver = candidates()
+            .filter_map(|(rank, s)| {
+                let prev = previous.get(&s.distro_id)?;
+                let gain = s.overall_score - prev.overall_score;
+                (gain >= MIN_IMPROVEMENT).then_some((rank, s, prev, gain))
+            })
+            .max_by(|a, b| a.3.total_cmp(&b.3));
+
+        if let Some((rank, s, prev, gain)) = improver {
+            return Ok(Some(NewHighlight {
+                distro_id: s.distro_id,
+                reason: "improver".to_string(),
+                blurb: format!(
+                    "{} improved the most this week, up {:.1} points to {:.1} (#{} overall), \
+                     led by its {} score.",
+                    name(s),
+                    gain,
+                    s.overall_score,
+                    rank,
+                    strongest_gain(s, prev)
+                ),
+                overall_score: s.overall_score,
+                score_change: Some(gain),
+            }));
+        }
+
+        let top = candidates().next().or(current.first());
+
+        Ok(top.map(|(rank, s)| NewHighlight {
+            distro_id: s.distro_id,
+            reason: "top_ranked".to_string(),
+            blurb: if *rank == 1 {
+                format!("{} leads the rankings with a health score of {:.1}.", name(s), s.overall_score)
+            } else {
+                format!("{} ranks #{} with a health score of {:.1}.", name(s), rank, s.overall_score)
+            },
+            overall_score: s.overall_score,
+            score_change: previous
+                .get(&s.distro_id)
+                .map(|prev| s.overall_score - prev.overall_score),
+        }))
+    }
+}
+
+/// Which sub-score gained the most between two scores
+fn strongest_gain(current: &HealthScore, previous: &HealthScore) -> &'static str {
+    [
+        ("development", current.development_score - previous.development_score),
+        ("community", current.community_score - previous.community_score),
+        ("maintenance", current.maintenance_score - previous.maintenance_score),
+    ]
+    .into_iter()
+    .max_by(|a, b| a.1.total_cmp(&b.1))
+    .map(|(name, _)| name)
+    .unwrap_or("development")
+}
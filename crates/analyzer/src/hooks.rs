@@ -0,0 +1,202 @@
+//! User-configured hooks run after each analysis run
+//!
+//! Hooks come from `DV_ANALYSIS_HOOKS`, one per line. Entries starting with `http://`
+//! or `https://` are webhooks and receive the run summary as a JSON POST; anything
+//! else is run with `sh -c` and gets the same JSON on stdin. Hook failures are logged
+//! and never fail the run.
+
+use chrono::{DateTime, Utc};
+use distrovitals_database::{Distribution, HealthScore};
+use serde::Serialize;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use tracing::{info, warn};
+
+/// How long a command hook may run before it is killed
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long a webhook may take to respond
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// What an analysis run produced, as passed to hooks
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+    pub scores: Vec<ScoredDistro>,
+    pub failures: Vec<FailedDistro>,
+}
+
+/// A distro scored during the run
+#[derive(Debug, Clone, Serialize)]
+pub struct ScoredDistro {
+    pub slug: String,
+    pub name: String,
+    pub overall_score: f64,
+    pub development_score: f64,
+    pub community_score: f64,
+    pub maintenance_score: f64,
+    pub trend: String,
+}
+
+/// A distro whose analysis failed
+#[derive(Debug, Clone, Serialize)]
+pub struct FailedDistro {
+    pub slug: String,
+    pub error: String,
+}
+
+impl RunSummary {
+    /// Start a summary for a run beginning now
+    pub fn start() -> Self {
+        let now = Utc::now();
+        Self {
+            started_at: now,
+            finished_at: now,
+            scores: Vec::new(),
+            failures: Vec::new(),
+        }
+    }
+
+    pub fn record_score(&mut self, distro: &Distribution, score: &HealthScore) {
+        self.scores.push(ScoredDistro {
+            slug: distro.slug.clone(),
+            name: distro.name.clone(),
+            overall_score: score.overall_score,
+            development_score: score.development_score,
+            community_score: score.community_score,
+            maintenance_score: score.maintenance_score,
+            trend: score.trend.clone(),
+        });
+    }
+
+    pub fn record_failure(&mut self, distro: &Distribution, error: impl ToString) {
+        self.failures.push(FailedDistro {
+            slug: distro.slug.clone(),
+            error: error.to_string(),
+        });
+    }
+
+    /// Stamp the end of the run
+    pub fn finish(mut self) -> Self {
+        self.finished_at = Utc::now();
+        self
+    }
+}
+
+/// A single configured hook
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hook {
+    Command(String),
+    Webhook(String),
+}
+
+impl Hook {
+    pub fn parse(entry: &str) -> Option<Self> {
+        let entry = entry.trim();
+        if entry.is_empty() || entry.starts_with('#') {
+            None
+        } else if entry.starts_with("http://") || entry.starts_with("https://") {
+            Some(Self::Webhook(entry.to_string()))
+        } else {
+            Some(Self::Command(entry.to_string()))
+        }
+    }
+}
+
+/// The hooks to run after analysis
+#[derive(Debug, Clone, Default)]
+pub struct PostAnalysisHooks {
+    hooks: Vec<Hook>,
+}
+
+impl PostAnalysisHooks {
+    /// Load hooks from `DV_ANALYSIS_HOOKS`, one per line
+    pub fn from_env() -> Self {
+        let hooks = std::env::var("DV_ANALYSIS_HOOKS")
+            .unwrap_or_default()
+            .lines()
+            .filter_map(Hook::parse)
+            .collect();
+
+        Self { hooks }
+    }
+
+    /// Add hooks given on the command line
+    pub fn with(mut self, entries: &[String]) -> Self {
+        self.hooks.extend(entries.iter().filter_map(|e| Hook::parse(e)));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+
+    /// Run every hook in order with the summary
+    pub async fn run(&self, summary: &RunSummary) {
+        if self.hooks.is_empty() {
+            return;
+        }
+
+        let payload = match serde_json::to_vec(summary) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Failed to serialize run summary for hooks: {}", e);
+                return;
+            }
+        };
+
+        for hook in &self.hooks {
+            let result = match hook {
+                Hook::Command(command) => run_command(command, &payload).await,
+                Hook::Webhook(url) => post_webhook(url, &payload).await,
+            };
+
+            match result {
+                Ok(()) => info!("Post-analysis hook succeeded: {:?}", hook),
+                Err(e) => warn!("Post-analysis hook failed: {:?}: {}", hook, e),
+            }
+        }
+    }
+}
+
+async fn run_command(command: &str, payload: &[u8]) -> Result<(), String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| e.to_string())?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A hook that ignores stdin closes the pipe early; that's not a failure
+        let _ = stdin.write_all(payload).await;
+    }
+
+    match tokio::time::timeout(COMMAND_TIMEOUT, child.wait()).await {
+        Ok(Ok(status)) if status.success() => Ok(()),
+        Ok(Ok(status)) => Err(format!("exited with {}", status)),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Err(format!("timed out after {}s", COMMAND_TIMEOUT.as_secs())),
+    }
+}
+
+async fn post_webhook(url: &str, payload: &[u8]) -> Result<(), String> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .body(payload.to_vec())
+        .timeout(WEBHOOK_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("responded with {}", response.status()))
+    }
+}
@@ -2,10 +2,64 @@
 //!
 //! Calculates health scores based on collected metrics.
 
-use chrono::Utc;
+mod abandonment;
+mod aggregation;
+mod changes;
+mod cohort;
+pub mod correlation;
+mod fediverse;
+pub mod fixtures;
+mod governance;
+mod hardware;
+mod highlight;
+mod hooks;
+pub mod lifecycle;
+mod load;
+pub mod methodology;
+pub mod milestones;
+mod momentum;
+#[cfg(test)]
+mod properties;
+mod quality;
+pub mod stats;
+mod tech;
+pub mod trend;
+mod upstream;
+mod variant;
+mod velocity;
+
+pub use abandonment::{AbandonmentRisk, RiskFactor, RiskTier};
+pub use aggregation::{Aggregation, GithubTotals};
+pub use changes::{Changes, KernelChange, MetricChange, NewRelease, ScoreChange};
+pub use cohort::{cohort_stats, CohortStats};
+pub use fediverse::{
+    DigestEvent, MastodonPublisher, PublishedThread, RankChange, WeeklyDigest, DEFAULT_TEMPLATE,
+};
+pub use governance::{ChecklistItem, GovernanceChecklist};
+pub use hardware::{ComponentLag, HardwareEnablement};
+pub use highlight::Highlighter;
+pub use momentum::{Momentum, MomentumComponents};
+pub use quality::{DataQuality, QualityFlag, QualityLevel};
+pub use hooks::{FailedDistro, Hook, PostAnalysisHooks, RunSummary, ScoredDistro};
+pub use load::{is_overloaded, MaintainerLoad};
+pub use tech::{LanguageShare, TechBreakdown, TopicCount};
+pub use upstream::{UpstreamRisk, UpstreamStatus};
+pub use velocity::{StarVelocity, SubscriberVelocity, STAR_GROWTH_DAYS, SUBSCRIBER_GROWTH_DAYS};
+
+use chrono::{DateTime, Utc};
+use methodology::{
+    ANSWERED_QUESTIONS, ANSWER_TIME, COMMITS, COMMIT_RECENCY, COMMUNITY, CONTRIBUTORS, DEVELOPMENT, DISCUSSIONS,
+    EOL_KERNEL_PENALTY, FIRST_TIME_CONTRIBUTORS, FORKS, FORUM_WEIGHT, ISSUE_LABEL_WEIGHTS, ISSUE_RESOLUTION,
+    MAINTENANCE, MIRROR_REDUNDANCY, NEUTRAL_SCORE, OPEN_ISSUES, OPEN_PRS, PACKAGE_FRESHNESS, POSTS, RC_BUGS,
+    SINGLE_COUNTRY_MIRRORS, STARS, SUBSCRIBERS, UPLOADS,
+};
 use distrovitals_database::{
-    CommunitySnapshot, Database, GithubSnapshot, HealthScore, NewHealthScore, ReleaseSnapshot,
+    ArchiveSnapshot, BugtrackerSnapshot, CommunitySnapshot, Database, GithubSnapshot, HealthScore, InfraSnapshot,
+    IssueActivity, IssueLabelCount, MetricKey, MetricValue, NewHealthScore, NewMetricValue, PackageSnapshot,
+    PopularitySnapshot, ReleaseSnapshot, ScoreFreeze,
 };
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use thiserror::Error;
 use tracing::info;
 
@@ -20,113 +74,266 @@ pub enum AnalyzerError {
 
 pub type Result<T> = std::result::Result<T, AnalyzerError>;
 
+/// Installs that must have pinged before a distro's install count is published, so a
+/// small count can't point at individual users
+pub const MIN_PING_INSTALLS: i64 = 50;
+
 /// Health score analyzer
 pub struct Analyzer;
 
 impl Analyzer {
     /// Calculate health score for a distribution
-    pub async fn calculate_health_score(db: &Database, distro_id: i64) -> Result<i64> {
-        let github_snapshots = db.get_latest_github_snapshots(distro_id).await?;
+    ///
+    /// While a score freeze covers the distro nothing is recalculated or recorded, so data
+    /// known to be bad can't publish a new score or trend.
+    pub async fn calculate_health_score(db: &Database, distro_id: i64) -> Result<ScoreOutcome> {
+        let started = Utc::now();
+        if let Some(freeze) = db.get_active_score_freeze(distro_id).await? {
+            info!(distro_id = distro_id, reason = %freeze.reason, "Scores frozen, keeping the previous score");
+            // Snapshots collected during the freeze are considered, so they aren't retried
+            db.mark_distro_analyzed(distro_id, started).await?;
+            return Ok(ScoreOutcome::Frozen(freeze));
+        }
+
+        let distro = db.get_distribution_by_id(distro_id).await?;
+        let aggregation = Aggregation::from_config(distro.aggregation.as_deref());
+        // A variant's development and maintenance data is its parent's
+        let upstream_id = variant::upstream_id(db, &distro).await?;
+
+        let github_snapshots = db.get_latest_github_snapshots(upstream_id).await?;
         let community_snapshots = db.get_latest_community_snapshots(distro_id).await?;
-        let previous_score = db.get_latest_health_score(distro_id).await?;
+        let package_snapshot = db.get_latest_package_snapshot(upstream_id).await?;
+        let issue_labels = db.get_issue_label_counts(upstream_id).await?;
+        let issue_activity = db.get_issue_activity(upstream_id).await?;
+        let infra = db.get_latest_infra_snapshot(upstream_id).await?;
+        let archive = db.get_latest_archive_snapshot(upstream_id).await?;
+        let first_time_contributors = variant::latest_metrics(db, distro_id, upstream_id)
+            .await?
+            .into_iter()
+            .find(|m| m.key == MetricKey::FirstTimeContributors30d)
+            .map(|m| m.value as i64);
+        let recent_scores = db.get_recent_health_scores(distro_id, trend::trend_window() - 1).await?;
+        let previous_score = recent_scores.first();
+
+        let star_velocity = StarVelocity::load(db, upstream_id, &github_snapshots).await?;
+        let subscriber_velocities = SubscriberVelocity::load(db, distro_id, &community_snapshots).await?;
+        let load = MaintainerLoad::measure(&github_snapshots, &community_snapshots);
+
+        db.record_ping_popularity(distro_id, MIN_PING_INSTALLS).await?;
+
+        let inputs = ScoreInputs {
+            github: github_snapshots,
+            community: community_snapshots,
+            packages: package_snapshot,
+            issue_labels,
+            issue_activity,
+            first_time_contributors,
+            infra,
+            archive,
+            aggregation,
+            star_velocity,
+            // The longest window is the least noisy
+            subscriber_velocity: subscriber_velocities.last().copied(),
+        };
+        let scores = Self::score(&inputs, Utc::now());
+        let overall_score = scores.overall;
+        let fingerprint = inputs.fingerprint();
+
+        // Nothing was collected since the last score: keep it rather than record a copy.
+        // Commit recency ages without new snapshots, so the scores must match as well.
+        if let Some(previous) = previous_score {
+            if db.get_health_score_fingerprint(previous.id).await?.as_deref() == Some(fingerprint.as_str())
+                && scores.matches(previous)
+            {
+                info!(distro_id = distro_id, score_id = previous.id, "Inputs unchanged, keeping the previous score");
+                // Snapshots the fingerprint doesn't cover were still considered
+                db.mark_distro_analyzed(distro_id, started).await?;
+                return Ok(ScoreOutcome::Unchanged(previous.id));
+            }
+        }
 
-        let development_score = Self::calculate_development_score(&github_snapshots);
-        let community_score = Self::calculate_community_score(&github_snapshots, &community_snapshots);
-        let maintenance_score = Self::calculate_maintenance_score(&github_snapshots);
+        if let Some(velocity) = star_velocity {
+            db.insert_metric(NewMetricValue {
+                distro_id,
+                source: "analyzer".to_string(),
+                key: MetricKey::StarGrowth30d,
+                value: velocity.delta as f64,
+            })
+            .await?;
+        }
 
-        let overall_score = (development_score * 0.4)
-            + (community_score * 0.3)
-            + (maintenance_score * 0.3);
+        for velocity in &subscriber_velocities {
+            db.insert_metric(NewMetricValue {
+                distro_id,
+                source: "analyzer".to_string(),
+                key: velocity.metric_key(),
+                value: velocity.delta as f64,
+            })
+            .await?;
+        }
 
-        let trend = Self::determine_trend(overall_score, previous_score.as_ref());
+        if let Some(load) = load {
+            db.insert_metric(NewMetricValue {
+                distro_id,
+                source: "analyzer".to_string(),
+                key: MetricKey::MaintainerLoad,
+                value: load.load,
+            })
+            .await?;
+        }
+
+        if let Some(hardware) = HardwareEnablement::load(db, &distro).await? {
+            db.insert_metric(NewMetricValue {
+                distro_id,
+                source: "analyzer".to_string(),
+                key: MetricKey::HardwareEnablementLag,
+                value: hardware.lag as f64,
+            })
+            .await?;
+        }
+
+        let mut series: Vec<f64> = recent_scores.iter().rev().map(|s| s.overall_score).collect();
+        series.push(overall_score);
+        let trend = trend::determine_trend(&series).to_string();
 
         let score = NewHealthScore {
             distro_id,
             overall_score,
-            development_score,
-            community_score,
-            maintenance_score,
+            development_score: scores.development,
+            community_score: scores.community,
+            maintenance_score: scores.maintenance,
             trend,
+            inputs_fingerprint: Some(fingerprint),
         };
 
         let id = db.insert_health_score(score).await?;
         info!(distro_id = distro_id, overall_score = overall_score, "Calculated health score");
+        db.archive_rankings(Utc::now().date_naive()).await?;
+
+        let reached =
+            milestones::record(db, distro_id, previous_score, overall_score, Utc::now()).await?;
+        for milestone in &reached {
+            info!(distro_id = distro_id, "Milestone: {}", milestone.title);
+        }
+        db.mark_distro_analyzed(distro_id, started).await?;
 
-        Ok(id)
+        Ok(ScoreOutcome::Scored(id))
+    }
+
+    /// Score a distro's latest snapshots as of `now`, without touching the database
+    pub fn score(inputs: &ScoreInputs, now: DateTime<Utc>) -> Scores {
+        let github = &inputs.github;
+        let totals = GithubTotals::aggregate(github, inputs.aggregation);
+
+        let development = Self::calculate_development_score(github, &totals, inputs.archive.as_ref());
+        let community = Self::calculate_community_score(
+            github,
+            &totals,
+            inputs.star_velocity.as_ref(),
+            &inputs.community,
+            inputs.subscriber_velocity.as_ref(),
+            inputs.first_time_contributors,
+        );
+        let maintenance = Self::calculate_maintenance_score(
+            github,
+            &totals,
+            &inputs.issue_labels,
+            inputs.issue_activity.as_ref(),
+            inputs.packages.as_ref(),
+            inputs.infra.as_ref(),
+            inputs.archive.as_ref(),
+            now,
+        );
+
+        Scores {
+            overall: development * DEVELOPMENT.weight
+                + community * COMMUNITY.weight
+                + maintenance * MAINTENANCE.weight,
+            development,
+            community,
+            maintenance,
+        }
     }
 
     /// Calculate development activity score (0-100)
-    fn calculate_development_score(github: &[GithubSnapshot]) -> f64 {
+    /// Distros developed in their own archive rather than on a forge are scored on uploads
+    fn calculate_development_score(
+        github: &[GithubSnapshot],
+        totals: &GithubTotals,
+        archive: Option<&ArchiveSnapshot>,
+    ) -> f64 {
         if github.is_empty() {
-            return 50.0; // Neutral score when no data
+            return match archive.and_then(|a| a.uploads_30d) {
+                Some(uploads) => UPLOADS.bands.score(uploads as f64),
+                None => NEUTRAL_SCORE, // Neutral score when no data
+            };
         }
 
-        let total_commits: i64 = github.iter().map(|s| s.commits_30d).sum();
-        let total_contributors: i64 = github.iter().map(|s| s.contributors_30d).sum();
+        let commit_score = COMMITS.bands.score(totals.commits_30d as f64);
+        let contributor_score = CONTRIBUTORS.bands.score(totals.contributors as f64);
 
-        // Score based on activity levels
-        let commit_score: f64 = match total_commits {
-            0..=10 => 20.0,
-            11..=50 => 40.0,
-            51..=200 => 60.0,
-            201..=500 => 80.0,
-            _ => 95.0,
-        };
-
-        let contributor_score: f64 = match total_contributors {
-            0..=2 => 20.0,
-            3..=10 => 40.0,
-            11..=30 => 60.0,
-            31..=100 => 80.0,
-            _ => 95.0,
-        };
-
-        (commit_score * 0.6 + contributor_score * 0.4).min(100.0)
+        (commit_score * COMMITS.weight + contributor_score * CONTRIBUTORS.weight).min(100.0)
     }
 
     /// Calculate community engagement score (0-100)
-    /// Combines GitHub metrics (stars, forks) with Reddit community data
-    fn calculate_community_score(github: &[GithubSnapshot], community: &[CommunitySnapshot]) -> f64 {
+    /// Combines GitHub metrics (star growth, forks) with Reddit and GitHub Discussions data
+    fn calculate_community_score(
+        github: &[GithubSnapshot],
+        totals: &GithubTotals,
+        star_velocity: Option<&StarVelocity>,
+        community: &[CommunitySnapshot],
+        subscriber_velocity: Option<&SubscriberVelocity>,
+        first_time_contributors: Option<i64>,
+    ) -> f64 {
         // GitHub component (stars + forks)
         let github_score = if github.is_empty() {
-            50.0
+            NEUTRAL_SCORE
         } else {
-            let total_stars: i64 = github.iter().map(|s| s.stars).sum();
-            let total_forks: i64 = github.iter().map(|s| s.forks).sum();
-
-            let star_score: f64 = match total_stars {
-                0..=100 => 20.0,
-                101..=1000 => 40.0,
-                1001..=5000 => 60.0,
-                5001..=20000 => 80.0,
-                _ => 95.0,
+            // Growth once there's a month of history, absolute stars until then
+            let star_score = match star_velocity {
+                Some(velocity) => velocity.score(),
+                None => STARS.bands.score(totals.stars as f64),
             };
+            let fork_score = FORKS.bands.score(totals.forks as f64);
 
-            let fork_score: f64 = match total_forks {
-                0..=10 => 20.0,
-                11..=100 => 40.0,
-                101..=500 => 60.0,
-                501..=2000 => 80.0,
-                _ => 95.0,
-            };
-
-            star_score * 0.5 + fork_score * 0.5
+            star_score * STARS.weight + fork_score * FORKS.weight
         };
 
-        // Reddit component (subscribers + activity)
-        let reddit_score = Self::calculate_reddit_score(community);
-
-        // Weight: 40% GitHub, 60% Reddit (Reddit is better indicator of user community)
-        // If no Reddit data, use 100% GitHub
-        if reddit_score > 0.0 {
-            (github_score * 0.4 + reddit_score * 0.6).min(100.0)
-        } else {
+        // Forum component: Reddit (subscribers + activity) and GitHub Discussions (activity +
+        // answers), averaged when a distro has both
+        let forums: Vec<f64> = [
+            Self::calculate_reddit_score(community, subscriber_velocity),
+            Self::calculate_discussions_score(community),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        // Forums are the better indicator of a user community; without one, GitHub alone
+        let score = if forums.is_empty() {
             github_score.min(100.0)
+        } else {
+            let forum_score = forums.iter().sum::<f64>() / forums.len() as f64;
+            (github_score * (1.0 - FORUM_WEIGHT) + forum_score * FORUM_WEIGHT).min(100.0)
+        };
+
+        // Newcomers getting their work merged is the best sign of a welcoming project
+        match first_time_contributors {
+            Some(newcomers) => {
+                score * (1.0 - FIRST_TIME_CONTRIBUTORS.weight)
+                    + FIRST_TIME_CONTRIBUTORS.bands.score(newcomers as f64) * FIRST_TIME_CONTRIBUTORS.weight
+            }
+            None => score,
         }
     }
 
     /// Calculate Reddit community score based on subscribers and activity
-    fn calculate_reddit_score(community: &[CommunitySnapshot]) -> f64 {
+    ///
+    /// Subscriber growth replaces the absolute subscriber count once there's enough history.
+    fn calculate_reddit_score(
+        community: &[CommunitySnapshot],
+        velocity: Option<&SubscriberVelocity>,
+    ) -> Option<f64> {
         // Find Reddit snapshots
         let reddit_snapshots: Vec<_> = community
             .iter()
@@ -134,7 +341,7 @@ impl Analyzer {
             .collect();
 
         if reddit_snapshots.is_empty() {
-            return 0.0; // No Reddit data
+            return None; // No Reddit data
         }
 
         // Sum subscribers across all Reddit sources (usually just one subreddit)
@@ -149,141 +356,419 @@ impl Analyzer {
             .filter_map(|s| s.posts_30d)
             .sum();
 
-        // Score based on subscriber count
-        // Linux distro subreddits range from ~1k to ~350k
-        let subscriber_score: f64 = match total_subscribers {
-            0..=1000 => 20.0,
-            1001..=5000 => 30.0,
-            5001..=15000 => 45.0,
-            15001..=50000 => 60.0,
-            50001..=100000 => 75.0,
-            100001..=200000 => 85.0,
-            _ => 95.0, // 200k+ (Arch, Ubuntu territory)
+        // Score based on subscriber growth, or count until there's history
+        let subscriber_score = match velocity {
+            Some(velocity) => velocity.score(),
+            None => SUBSCRIBERS.bands.score(total_subscribers as f64),
         };
+        let activity_score = POSTS.bands.score(total_posts as f64);
+
+        Some(subscriber_score * SUBSCRIBERS.weight + activity_score * POSTS.weight)
+    }
+
+    /// Calculate GitHub Discussions score based on activity and how questions get answered
+    ///
+    /// Without questions asked, activity alone; questions without any answer score the
+    /// slowest answer time.
+    fn calculate_discussions_score(community: &[CommunitySnapshot]) -> Option<f64> {
+        let snapshots: Vec<_> = community
+            .iter()
+            .filter(|c| c.source.starts_with("github-discussions:"))
+            .collect();
+        if snapshots.is_empty() {
+            return None;
+        }
+
+        let posts: i64 = snapshots.iter().filter_map(|s| s.posts_30d).sum();
+        let questions: i64 = snapshots.iter().filter_map(|s| s.questions_30d).sum();
+        let answers: i64 = snapshots.iter().filter_map(|s| s.answers_30d).sum();
+        let activity_score = DISCUSSIONS.bands.score(posts as f64);
+        if questions == 0 {
+            return Some(activity_score);
+        }
 
-        // Score based on recent activity (posts in last 30 days)
-        let activity_score: f64 = match total_posts {
-            0..=10 => 20.0,
-            11..=30 => 40.0,
-            31..=60 => 60.0,
-            61..=100 => 80.0,
-            _ => 95.0,
+        let answered_pct = answers.min(questions) as f64 / questions as f64 * 100.0;
+        // Weighted by answers so a repo answering more questions counts for more
+        let answer_hours = snapshots
+            .iter()
+            .filter_map(|s| Some((s.response_time_avg_hours?, s.answers_30d.filter(|&a| a > 0)? as f64)))
+            .fold((0.0, 0.0), |(hours, weight), (h, w)| (hours + h * w, weight + w));
+        let time_score = match answer_hours {
+            (hours, weight) if weight > 0.0 => ANSWER_TIME.bands.score(hours / weight),
+            _ => ANSWER_TIME.bands.otherwise,
         };
 
-        // Weight: 70% subscribers, 30% activity
-        subscriber_score * 0.7 + activity_score * 0.3
+        Some(
+            activity_score * DISCUSSIONS.weight
+                + ANSWERED_QUESTIONS.bands.score(answered_pct) * ANSWERED_QUESTIONS.weight
+                + time_score * ANSWER_TIME.weight,
+        )
     }
 
     /// Calculate maintenance health score (0-100)
-    fn calculate_maintenance_score(github: &[GithubSnapshot]) -> f64 {
-        if github.is_empty() {
-            return 50.0;
+    /// Blends in issue resolution when core repos have issue activity, package/ports freshness
+    /// when Repology data is available and mirror redundancy when the mirror list is tracked,
+    /// and penalizes shipping a kernel series that is EOL upstream. Without forge repos,
+    /// release-critical bugs in the distro's archive take the GitHub backlog's place.
+    #[allow(clippy::too_many_arguments)]
+    fn calculate_maintenance_score(
+        github: &[GithubSnapshot],
+        totals: &GithubTotals,
+        issue_labels: &[IssueLabelCount],
+        issue_activity: Option<&IssueActivity>,
+        packages: Option<&PackageSnapshot>,
+        infra: Option<&InfraSnapshot>,
+        archive: Option<&ArchiveSnapshot>,
+        now: DateTime<Utc>,
+    ) -> f64 {
+        let backlog_score = match archive.and_then(Self::calculate_rc_bug_score) {
+            Some(rc_bugs) if github.is_empty() => rc_bugs,
+            _ => Self::calculate_github_maintenance_score(github, totals, issue_labels, now),
+        };
+        let github_score = match issue_activity.and_then(IssueActivity::adjusted_resolution_pct) {
+            Some(resolution) if !github.is_empty() => {
+                backlog_score * (1.0 - ISSUE_RESOLUTION.weight)
+                    + ISSUE_RESOLUTION.bands.score(resolution) * ISSUE_RESOLUTION.weight
+            }
+            _ => backlog_score,
+        };
+
+        let score = match packages.and_then(Self::calculate_freshness_score) {
+            Some(freshness) => (github_score * (1.0 - PACKAGE_FRESHNESS.weight)
+                + freshness * PACKAGE_FRESHNESS.weight)
+                .min(100.0),
+            None => github_score,
+        };
+
+        let score = match infra {
+            Some(infra) => {
+                score * (1.0 - MIRROR_REDUNDANCY.weight)
+                    + MIRROR_REDUNDANCY.bands.score(Self::redundant_mirrors(infra) as f64) * MIRROR_REDUNDANCY.weight
+            }
+            None => score,
+        };
+
+        let ships_eol_kernel = packages.and_then(|p| p.kernel_status.as_deref()) == Some("eol");
+        if ships_eol_kernel {
+            (score - EOL_KERNEL_PENALTY).max(0.0)
+        } else {
+            score
+        }
+    }
+
+    /// Mirrors that count toward redundancy: all of them, unless they're known to share a country
+    fn redundant_mirrors(infra: &InfraSnapshot) -> i64 {
+        match infra.mirror_countries {
+            Some(countries) if countries <= 1 => infra.mirrors.min(SINGLE_COUNTRY_MIRRORS),
+            _ => infra.mirrors,
+        }
+    }
+
+    /// Calculate release-critical bug score (0-100) from RC bugs per thousand source packages
+    fn calculate_rc_bug_score(archive: &ArchiveSnapshot) -> Option<f64> {
+        let rc_bugs = archive.rc_bugs?;
+        if archive.source_packages == 0 {
+            return None;
+        }
+
+        let per_thousand = rc_bugs as f64 / archive.source_packages as f64 * 1000.0;
+        Some(RC_BUGS.bands.score(per_thousand))
+    }
+
+    /// Calculate package freshness score (0-100) from the share of outdated packages
+    fn calculate_freshness_score(packages: &PackageSnapshot) -> Option<f64> {
+        if packages.total_packages == 0 {
+            return None;
         }
 
-        let total_issues: i64 = github.iter().map(|s| s.open_issues).sum();
-        let total_prs: i64 = github.iter().map(|s| s.open_prs).sum();
+        let outdated_pct = packages.outdated_packages as f64 / packages.total_packages as f64 * 100.0;
+        Some(PACKAGE_FRESHNESS.bands.score(outdated_pct))
+    }
+
+    /// Calculate maintenance score from GitHub issue/PR backlog and commit recency
+    fn calculate_github_maintenance_score(
+        github: &[GithubSnapshot],
+        totals: &GithubTotals,
+        issue_labels: &[IssueLabelCount],
+        now: DateTime<Utc>,
+    ) -> f64 {
+        if github.is_empty() {
+            return NEUTRAL_SCORE;
+        }
 
         // Lower open issues/PRs relative to activity is better
         // But some activity is expected for healthy projects
-        let issue_score: f64 = match total_issues {
-            0..=10 => 90.0,
-            11..=50 => 80.0,
-            51..=200 => 70.0,
-            201..=500 => 50.0,
-            501..=1000 => 30.0,
-            _ => 20.0,
-        };
-
-        let pr_score: f64 = match total_prs {
-            0..=5 => 90.0,
-            6..=20 => 80.0,
-            21..=50 => 70.0,
-            51..=100 => 50.0,
-            _ => 30.0,
-        };
+        let issue_score = OPEN_ISSUES.bands.score(Self::weighted_open_issues(totals.open_issues, issue_labels));
+        let pr_score = OPEN_PRS.bands.score(totals.open_prs as f64);
 
         // Check recency of last commit
-        let recency_score: f64 = github
+        let recency_score = github
             .iter()
             .filter_map(|s| s.last_commit_at)
             .max()
-            .map(|last| {
-                let days_ago = (Utc::now() - last).num_days();
-                match days_ago {
-                    0..=7 => 100.0,
-                    8..=30 => 80.0,
-                    31..=90 => 60.0,
-                    91..=180 => 40.0,
-                    _ => 20.0,
-                }
+            .map(|last| COMMIT_RECENCY.bands.score((now - last).num_days() as f64))
+            .unwrap_or(NEUTRAL_SCORE);
+
+        (issue_score * OPEN_ISSUES.weight
+            + pr_score * OPEN_PRS.weight
+            + recency_score * COMMIT_RECENCY.weight)
+            .min(100.0)
+    }
+
+    /// Open issues with labelled ones reweighted by category
+    fn weighted_open_issues(open_issues: i64, issue_labels: &[IssueLabelCount]) -> f64 {
+        let adjustment: f64 = issue_labels
+            .iter()
+            .filter_map(|count| {
+                let (_, weight) = ISSUE_LABEL_WEIGHTS.iter().find(|(category, _)| *category == count.category)?;
+                Some((weight - 1.0) * count.open_issues as f64)
             })
-            .unwrap_or(50.0);
+            .sum();
+        (open_issues as f64 + adjustment).max(0.0)
+    }
+}
 
-        (issue_score * 0.3 + pr_score * 0.3 + recency_score * 0.4).min(100.0)
+/// What a health score calculation did
+#[derive(Debug, Clone)]
+pub enum ScoreOutcome {
+    /// Recorded a new score with this ID
+    Scored(i64),
+    /// Nothing changed since the score with this ID, which was kept
+    Unchanged(i64),
+    /// Skipped because of this freeze
+    Frozen(ScoreFreeze),
+}
+
+/// The snapshots and growth figures a health score is computed from
+#[derive(Debug, Clone, Default)]
+pub struct ScoreInputs {
+    /// Latest snapshot of each tracked repo
+    pub github: Vec<GithubSnapshot>,
+    /// Latest snapshot of each community source
+    pub community: Vec<CommunitySnapshot>,
+    pub packages: Option<PackageSnapshot>,
+    /// Open issues by label category across core repos
+    pub issue_labels: Vec<IssueLabelCount>,
+    /// Issues opened and closed in core repos over the last 30 days
+    pub issue_activity: Option<IssueActivity>,
+    /// Authors of a first merged pull request to a core repo in the last 30 days
+    pub first_time_contributors: Option<i64>,
+    /// Latest count of the distro's download mirrors
+    pub infra: Option<InfraSnapshot>,
+    /// Latest measure of the distro's own package archive
+    pub archive: Option<ArchiveSnapshot>,
+    pub aggregation: Aggregation,
+    /// Growth since a month ago, once there is that much history
+    pub star_velocity: Option<StarVelocity>,
+    pub subscriber_velocity: Option<SubscriberVelocity>,
+}
+
+impl ScoreInputs {
+    /// Hash of the snapshots, settings and methodology version the inputs stand for, equal
+    /// when the same batch is scored again
+    pub fn fingerprint(&self) -> String {
+        let mut github: Vec<i64> = self.github.iter().map(|s| s.id).collect();
+        github.sort_unstable();
+        let mut community: Vec<i64> = self.community.iter().map(|s| s.id).collect();
+        community.sort_unstable();
+
+        let text = format!(
+            "{}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}",
+            methodology::METHODOLOGY_VERSION,
+            self.aggregation,
+            github,
+            community,
+            self.packages.as_ref().map(|p| p.id),
+            self.infra.as_ref().map(|i| i.id),
+            self.archive.as_ref().map(|a| a.id),
+            self.issue_labels,
+            self.issue_activity,
+            self.first_time_contributors,
+            self.star_velocity,
+            self.subscriber_velocity,
+        );
+        hex::encode(Sha256::digest(text))
     }
+}
 
-    /// Determine trend based on previous score
-    fn determine_trend(current: f64, previous: Option<&HealthScore>) -> String {
-        match previous {
-            Some(prev) => {
-                let diff = current - prev.overall_score;
-                if diff > 2.0 {
-                    "up".to_string()
-                } else if diff < -2.0 {
-                    "down".to_string()
-                } else {
-                    "stable".to_string()
-                }
-            }
-            None => "stable".to_string(),
-        }
+/// Overall and component health scores (0-100)
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize)]
+pub struct Scores {
+    pub overall: f64,
+    pub development: f64,
+    pub community: f64,
+    pub maintenance: f64,
+}
+
+impl Scores {
+    /// Whether these are the scores recorded in `score`
+    pub fn matches(&self, score: &HealthScore) -> bool {
+        self.overall == score.overall_score
+            && self.development == score.development_score
+            && self.community == score.community_score
+            && self.maintenance == score.maintenance_score
     }
 }
 
 /// Raw metrics aggregated from snapshots
 #[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct RawMetrics {
+    /// Strategy used to combine per-repo values ("sum", "mean", "top-k:N")
+    pub aggregation: String,
     pub repos_tracked: i64,
     pub total_stars: i64,
+    /// Stars gained over the last 30 days, once there is that much history
+    pub star_growth_30d: Option<i64>,
     pub total_forks: i64,
     pub total_contributors: i64,
     pub commits_30d: i64,
     pub commits_365d: i64,
+    /// Commits by automation accounts, excluded from commits_30d
+    pub bot_commits_30d: i64,
     pub open_issues: i64,
     pub open_prs: i64,
     pub total_releases: i64,
     pub releases_30d: i64,
     pub latest_release: Option<String>,
     pub days_since_release: Option<i64>,
+    /// Whether the latest release ships a detached signature / checksums (None when unknown)
+    pub latest_release_signed: Option<bool>,
+    pub latest_release_checksummed: Option<bool>,
+    /// Asset downloads summed over all releases (None when no release has assets)
+    pub release_downloads: Option<i64>,
+    // Package metrics (Repology)
+    pub total_packages: i64,
+    pub outdated_packages: i64,
+    pub kernel_version: Option<String>,
+    pub kernel_series: Option<String>,
+    pub kernel_eol: Option<bool>,
+    /// Arch's official repos as counted by archlinux.org, and how many are flagged out of date
+    pub official_packages: Option<i64>,
+    pub flagged_packages: Option<i64>,
+    /// AUR size, for Arch and its derivatives
+    pub aur_packages: Option<i64>,
     // Reddit metrics
     pub reddit_subscribers: i64,
     pub reddit_posts_30d: i64,
+    /// Subscribers gained over the last 30 / 90 days, once there is that much history
+    pub reddit_subscriber_growth_30d: Option<i64>,
+    pub reddit_subscriber_growth_90d: Option<i64>,
     pub subreddit: Option<String>,
+    // GitHub Discussions metrics
+    /// Discussions started in the last 30 days, the questions among them and how many of
+    /// those have an accepted answer
+    pub discussions_30d: Option<i64>,
+    pub discussion_questions_30d: Option<i64>,
+    pub discussion_answers_30d: Option<i64>,
+    /// Mean hours from a question to the comment accepted as its answer
+    pub discussion_answer_hours: Option<f64>,
+    /// Reddit subscribers plus open issues per active maintainer (derived by the analyzer)
+    pub maintainer_load: Option<f64>,
+    /// Authors of a first merged pull request to a core repo in the last 30 days
+    pub first_time_contributors_30d: Option<i64>,
+    /// Issues opened and closed in core repos over the last 30 days
+    pub issue_activity: Option<IssueActivity>,
+    /// Issues closed per issue opened, in percent, with and without stale-bot closures
+    pub issue_resolution_pct: Option<f64>,
+    pub issue_resolution_pct_adjusted: Option<f64>,
+    /// Download mirrors in the official mirror list, and the countries they're in
+    pub mirrors: Option<i64>,
+    pub mirror_countries: Option<i64>,
+    /// Installer image size and a month of update downloads, in MB (informational, not scored)
+    pub iso_size_mb: Option<f64>,
+    pub update_payload_mb_30d: Option<f64>,
+    /// Kernel/Mesa releases a point release's installer lacks (informational, not scored)
+    pub hardware_enablement_lag: Option<i64>,
+    /// Installs that sent an opt-in ping in the last 30 days (informational, not scored)
+    ///
+    /// Unverified: any client can ping as a new install, limited only by the per-address cap,
+    /// so it's shown as a self-reported figure rather than a measurement.
+    pub active_installs_30d: Option<i64>,
+    /// The distro's own package archive: source packages, open release-critical bugs and
+    /// uploads over the last 30 days
+    pub source_packages: Option<i64>,
+    pub rc_bugs: Option<i64>,
+    pub uploads_30d: Option<i64>,
+    /// The distro's Bugzilla: open bugs, bugs filed and resolved over the last 30 days and
+    /// mean days to resolution (informational, not scored)
+    pub bugzilla_open_bugs: Option<i64>,
+    pub bugs_opened_30d: Option<i64>,
+    pub bugs_closed_30d: Option<i64>,
+    pub bug_resolution_days: Option<f64>,
+    /// Operator-defined metrics from `DV_CUSTOM_METRICS`, by key; shown but not scored
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub custom: BTreeMap<String, f64>,
 }
 
 impl RawMetrics {
-    /// Aggregate metrics from GitHub snapshots
+    /// Aggregate metrics from GitHub snapshots by summing across repos
     pub fn from_github_snapshots(snapshots: &[GithubSnapshot]) -> Self {
+        Self::from_github_snapshots_with(snapshots, Aggregation::Sum)
+    }
+
+    /// Aggregate metrics from GitHub snapshots using a distro's aggregation strategy
+    pub fn from_github_snapshots_with(snapshots: &[GithubSnapshot], aggregation: Aggregation) -> Self {
+        let totals = GithubTotals::aggregate(snapshots, aggregation);
+
         Self {
+            aggregation: aggregation.to_string(),
             repos_tracked: snapshots.len() as i64,
-            total_stars: snapshots.iter().map(|s| s.stars).sum(),
-            total_forks: snapshots.iter().map(|s| s.forks).sum(),
-            total_contributors: snapshots.iter().map(|s| s.contributors_30d).sum(),
-            commits_30d: snapshots.iter().map(|s| s.commits_30d).sum(),
-            commits_365d: snapshots.iter().map(|s| s.commits_365d).sum(),
-            open_issues: snapshots.iter().map(|s| s.open_issues).sum(),
-            open_prs: snapshots.iter().map(|s| s.open_prs).sum(),
+            total_stars: totals.stars,
+            star_growth_30d: None,
+            total_forks: totals.forks,
+            total_contributors: totals.contributors,
+            commits_30d: totals.commits_30d,
+            commits_365d: totals.commits_365d,
+            bot_commits_30d: totals.bot_commits_30d,
+            open_issues: totals.open_issues,
+            open_prs: totals.open_prs,
             total_releases: 0,
             releases_30d: 0,
             latest_release: None,
             days_since_release: None,
+            latest_release_signed: None,
+            latest_release_checksummed: None,
+            release_downloads: None,
+            total_packages: 0,
+            outdated_packages: 0,
+            kernel_version: None,
+            kernel_series: None,
+            kernel_eol: None,
+            official_packages: None,
+            flagged_packages: None,
+            aur_packages: None,
             reddit_subscribers: 0,
             reddit_posts_30d: 0,
+            reddit_subscriber_growth_30d: None,
+            reddit_subscriber_growth_90d: None,
             subreddit: None,
+            discussions_30d: None,
+            discussion_questions_30d: None,
+            discussion_answers_30d: None,
+            discussion_answer_hours: None,
+            maintainer_load: None,
+            first_time_contributors_30d: None,
+            issue_activity: None,
+            issue_resolution_pct: None,
+            issue_resolution_pct_adjusted: None,
+            mirrors: None,
+            mirror_countries: None,
+            iso_size_mb: None,
+            update_payload_mb_30d: None,
+            hardware_enablement_lag: None,
+            active_installs_30d: None,
+            source_packages: None,
+            rc_bugs: None,
+            uploads_30d: None,
+            bugzilla_open_bugs: None,
+            bugs_opened_30d: None,
+            bugs_closed_30d: None,
+            bug_resolution_days: None,
+            custom: BTreeMap::new(),
         }
     }
 
-    /// Add Reddit community metrics
+    /// Add Reddit and GitHub Discussions community metrics
     pub fn with_community(mut self, community: &[CommunitySnapshot]) -> Self {
         // Find Reddit snapshots
         for snap in community.iter().filter(|c| c.source.starts_with("reddit:")) {
@@ -298,6 +783,108 @@ impl RawMetrics {
                 self.subreddit = snap.source.strip_prefix("reddit:r/").map(String::from);
             }
         }
+
+        // Usually a single repo; with more, the answer time is averaged over all answers
+        let discussions: Vec<_> = community
+            .iter()
+            .filter(|c| c.source.starts_with("github-discussions:"))
+            .collect();
+        if !discussions.is_empty() {
+            let sum = |field: fn(&CommunitySnapshot) -> Option<i64>| -> i64 {
+                discussions.iter().filter_map(|s| field(s)).sum()
+            };
+            let answers = sum(|s| s.answers_30d);
+            let answer_hours: f64 = discussions
+                .iter()
+                .filter_map(|s| Some(s.response_time_avg_hours? * s.answers_30d? as f64))
+                .sum();
+            self.discussions_30d = Some(sum(|s| s.posts_30d));
+            self.discussion_questions_30d = Some(sum(|s| s.questions_30d));
+            self.discussion_answers_30d = Some(answers);
+            self.discussion_answer_hours = (answers > 0).then(|| answer_hours / answers as f64);
+        }
+        self
+    }
+
+    /// Add metrics derived by earlier analysis runs
+    pub fn with_metrics(mut self, metrics: &[MetricValue]) -> Self {
+        for metric in metrics {
+            let value = Some(metric.value as i64);
+            match metric.key {
+                MetricKey::StarGrowth30d => self.star_growth_30d = value,
+                MetricKey::SubscriberGrowth30d => self.reddit_subscriber_growth_30d = value,
+                MetricKey::SubscriberGrowth90d => self.reddit_subscriber_growth_90d = value,
+                MetricKey::MaintainerLoad => self.maintainer_load = Some(metric.value),
+                MetricKey::FirstTimeContributors30d => self.first_time_contributors_30d = value,
+                MetricKey::IsoSizeMb => self.iso_size_mb = Some(metric.value),
+                MetricKey::UpdatePayloadMb30d => self.update_payload_mb_30d = Some(metric.value),
+                MetricKey::HardwareEnablementLag => self.hardware_enablement_lag = value,
+                MetricKey::Custom(ref key) => {
+                    self.custom.insert(key.clone(), metric.value);
+                }
+                _ => {}
+            }
+        }
+        self
+    }
+
+    /// Add core repo issue activity
+    pub fn with_issue_activity(mut self, activity: Option<IssueActivity>) -> Self {
+        let round = |pct: f64| (pct * 10.0).round() / 10.0;
+        self.issue_activity = activity;
+        self.issue_resolution_pct = activity.and_then(|a| a.resolution_pct()).map(round);
+        self.issue_resolution_pct_adjusted = activity.and_then(|a| a.adjusted_resolution_pct()).map(round);
+        self
+    }
+
+    /// Add the mirror network size
+    pub fn with_infra(mut self, infra: Option<&InfraSnapshot>) -> Self {
+        if let Some(snap) = infra {
+            self.mirrors = Some(snap.mirrors);
+            self.mirror_countries = snap.mirror_countries;
+        }
+        self
+    }
+
+    /// Add the package archive's size and activity
+    pub fn with_archive(mut self, archive: Option<&ArchiveSnapshot>) -> Self {
+        if let Some(snap) = archive {
+            self.source_packages = Some(snap.source_packages);
+            self.rc_bugs = snap.rc_bugs;
+            self.uploads_30d = snap.uploads_30d;
+        }
+        self
+    }
+
+    /// Add the Bugzilla counts
+    pub fn with_bugtracker(mut self, bugtracker: Option<&BugtrackerSnapshot>) -> Self {
+        if let Some(snap) = bugtracker {
+            self.bugzilla_open_bugs = Some(snap.open_bugs);
+            self.bugs_opened_30d = snap.opened_30d;
+            self.bugs_closed_30d = snap.closed_30d;
+            self.bug_resolution_days = snap.mean_resolution_days;
+        }
+        self
+    }
+
+    /// Add the install base measured from pings
+    pub fn with_popularity(mut self, popularity: Option<&PopularitySnapshot>) -> Self {
+        self.active_installs_30d = popularity.map(|p| p.active_installs_30d);
+        self
+    }
+
+    /// Add package freshness metrics
+    pub fn with_packages(mut self, packages: Option<&PackageSnapshot>) -> Self {
+        if let Some(snap) = packages {
+            self.total_packages = snap.total_packages;
+            self.outdated_packages = snap.outdated_packages;
+            self.kernel_version = snap.kernel_version.clone();
+            self.kernel_series = snap.kernel_series.clone();
+            self.kernel_eol = snap.kernel_status.as_deref().map(|s| s == "eol");
+            self.official_packages = snap.official_packages;
+            self.flagged_packages = snap.flagged_packages;
+            self.aur_packages = snap.aur_packages;
+        }
         self
     }
 
@@ -313,6 +900,11 @@ impl RawMetrics {
             .filter(|r| r.published_at.map(|d| d > thirty_days_ago).unwrap_or(false))
             .count() as i64;
 
+        self.release_downloads = releases
+            .iter()
+            .filter_map(|r| r.downloads)
+            .reduce(|total, downloads| total + downloads);
+
         // Find latest non-prerelease
         if let Some(latest) = releases
             .iter()
@@ -320,6 +912,8 @@ impl RawMetrics {
             .max_by_key(|r| r.published_at)
         {
             self.latest_release = Some(latest.tag_name.clone());
+            self.latest_release_signed = latest.signed;
+            self.latest_release_checksummed = latest.checksummed;
             if let Some(published) = latest.published_at {
                 self.days_since_release = Some((Utc::now() - published).num_days());
             }
@@ -334,14 +928,31 @@ impl RawMetrics {
 pub struct DistroHealthSummary {
     pub slug: String,
     pub name: String,
+    pub os_family: String,
     pub overall_score: f64,
     pub development_score: f64,
     pub community_score: f64,
     pub maintenance_score: f64,
     pub trend: String,
     pub rank: usize,
+    /// Rank in the rankings archived 30 days earlier, among distros the same filter matches
+    pub previous_rank: Option<usize>,
+    /// Places gained (positive) or lost since `previous_rank`
+    pub rank_change_30d: Option<i64>,
     pub metrics: RawMetrics,
     pub github_org: Option<String>,
+    pub forgejo_org: Option<String>,
     pub subreddit: Option<String>,
     pub description: Option<String>,
+    /// Parent whose development and maintenance metrics a variant's are
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub variant_of: Option<String>,
+    /// Standing among comparable distros, when the cohort is large enough
+    pub cohort: Option<CohortStats>,
+    /// How complete and current the data behind the score is; None when nothing has been
+    /// collected, and in archived rankings
+    pub data_quality: Option<DataQuality>,
+    /// Only computed when rankings are sorted by momentum
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub momentum: Option<Momentum>,
 }
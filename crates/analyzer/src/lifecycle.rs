@@ -0,0 +1,167 @@
+//! Release lifecycle forecast: how likely is a distro to go a year without a stable release?
+//!
+//! Aimed at picking a distro for long-lived servers, where a release line that quietly stops
+//! matters more than day-to-day activity. The gaps between a distro's past stable releases
+//! are treated as survival times, with the time since its latest release as one more gap
+//! that hasn't ended yet:
+//!
+//! - **Empirical**: of the past gaps that lasted at least as long as the current one, the
+//!   share that went on for another horizon. This captures regular cadences (a distro that
+//!   releases every two years is nearly certain to skip the next twelve months right after a
+//!   release), but rests on a handful of gaps.
+//! - **Exponential**: a constant release rate, estimated as releases per day of history
+//!   including the open gap, so a long silence lowers the rate on its own.
+//!
+//! The empirical estimate is shrunk towards the exponential one by [`PRIOR_GAPS`] pseudo
+//! observations, and used alone when no past gap was as long as the current one.
+
+use crate::stats::quantile;
+use crate::Result;
+use chrono::{DateTime, TimeDelta, Utc};
+use distrovitals_database::{Database, ReleaseSnapshot};
+use serde::Serialize;
+
+/// Stable releases needed before a distro gets a forecast
+const MIN_RELEASES: usize = 3;
+
+/// Releases this close to the previous one are the same release (a fix-up, or the same
+/// version tagged in several repos)
+const MERGE_DAYS: f64 = 7.0;
+
+/// Weight of the exponential estimate, in gaps
+const PRIOR_GAPS: f64 = 2.0;
+
+/// Forecast for one distribution
+#[derive(Debug, Clone, Serialize)]
+pub struct LifecycleForecast {
+    pub slug: String,
+    pub name: String,
+    /// Distinct stable releases the forecast is based on
+    pub stable_releases: usize,
+    pub median_gap_days: f64,
+    pub last_release_at: DateTime<Utc>,
+    pub days_since_release: i64,
+    /// Probability, 0-1, of no stable release within the horizon
+    pub probability_no_release: f64,
+}
+
+/// Forecasts for every distro with enough release history, riskiest first
+#[derive(Debug, Clone, Serialize)]
+pub struct LifecycleReport {
+    pub generated_at: DateTime<Utc>,
+    pub horizon_days: i64,
+    pub forecasts: Vec<LifecycleForecast>,
+    /// Slugs of distros with too few stable releases to forecast
+    pub insufficient_history: Vec<String>,
+}
+
+impl LifecycleReport {
+    pub async fn build(db: &Database, horizon_days: i64) -> Result<Self> {
+        let now = Utc::now();
+        let mut forecasts = Vec::new();
+        let mut insufficient_history = Vec::new();
+
+        for distro in db.get_distributions().await? {
+            let releases = stable_releases(&db.get_latest_release_snapshots(distro.id).await?);
+            let Some(&last) = releases.last() else {
+                insufficient_history.push(distro.slug);
+                continue;
+            };
+            if releases.len() < MIN_RELEASES {
+                insufficient_history.push(distro.slug);
+                continue;
+            }
+
+            let mut gaps: Vec<f64> = releases.windows(2).map(|pair| days_between(pair[0], pair[1])).collect();
+            let open = days_between(last, now).max(0.0);
+            let probability = probability_no_release(&gaps, open, horizon_days as f64);
+            gaps.sort_by(f64::total_cmp);
+
+            forecasts.push(LifecycleForecast {
+                slug: distro.slug,
+                name: distro.name,
+                stable_releases: releases.len(),
+                median_gap_days: quantile(&gaps, 0.5).round(),
+                last_release_at: last,
+                days_since_release: (now - last).num_days(),
+                probability_no_release: (probability * 1000.0).round() / 1000.0,
+            });
+        }
+
+        forecasts.sort_by(|a, b| {
+            b.probability_no_release
+                .total_cmp(&a.probability_no_release)
+                .then_with(|| a.slug.cmp(&b.slug))
+        });
+
+        Ok(Self {
+            generated_at: now,
+            horizon_days,
+            forecasts,
+            insufficient_history,
+        })
+    }
+}
+
+fn days_between(from: DateTime<Utc>, to: DateTime<Utc>) -> f64 {
+    (to - from).num_seconds() as f64 / TimeDelta::days(1).num_seconds() as f64
+}
+
+/// Publication dates of distinct stable releases, oldest first
+fn stable_releases(snapshots: &[ReleaseSnapshot]) -> Vec<DateTime<Utc>> {
+    let mut dates: Vec<DateTime<Utc>> = snapshots
+        .iter()
+        .filter(|r| !r.is_prerelease)
+        .filter_map(|r| r.published_at)
+        .collect();
+    dates.sort();
+
+    let mut distinct: Vec<DateTime<Utc>> = Vec::with_capacity(dates.len());
+    for date in dates {
+        match distinct.last() {
+            Some(&previous) if days_between(previous, date) < MERGE_DAYS => {}
+            _ => distinct.push(date),
+        }
+    }
+    distinct
+}
+
+/// Probability of no release in the next `horizon` days, given past `gaps` between releases
+/// and `open` days since the latest one
+fn probability_no_release(gaps: &[f64], open: f64, horizon: f64) -> f64 {
+    let exposure = gaps.iter().sum::<f64>() + open;
+    let exponential = if exposure > 0.0 {
+        (-(gaps.len() as f64) / exposure * horizon).exp()
+    } else {
+        0.0
+    };
+
+    let at_risk = gaps.iter().filter(|&&gap| gap >= open).count() as f64;
+    let survived = gaps.iter().filter(|&&gap| gap > open + horizon).count() as f64;
+    (survived + exponential * PRIOR_GAPS) / (at_risk + PRIOR_GAPS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regular_cadence_is_predictable() {
+        // Every six months, last one a month ago: almost certainly another within a year
+        let gaps = [182.0; 8];
+        assert!(probability_no_release(&gaps, 30.0, 365.0) < 0.05);
+
+        // Every two years, last one a month ago: almost certainly not
+        let gaps = [730.0; 5];
+        assert!(probability_no_release(&gaps, 30.0, 365.0) > 0.7);
+    }
+
+    #[test]
+    fn long_silence_raises_the_risk() {
+        let gaps = [120.0, 150.0, 200.0, 90.0];
+        let recent = probability_no_release(&gaps, 20.0, 365.0);
+        let silent = probability_no_release(&gaps, 900.0, 365.0);
+        assert!(silent > recent, "{} should exceed {}", silent, recent);
+        assert!((0.0..=1.0).contains(&silent));
+    }
+}
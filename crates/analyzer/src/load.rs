@@ -0,0 +1,62 @@
+//! Maintainer load: how much community there is per person keeping the distro going
+//!
+//! A growing subreddit and issue tracker look like health, until the same handful of people
+//! are fielding all of it. Load divides community size (Reddit subscribers plus open issues)
+//! by the maintainers active in the last 30 days, and flags distros where it has got out of
+//! proportion.
+
+use distrovitals_database::{CommunitySnapshot, GithubSnapshot};
+use serde::Serialize;
+
+/// Load beyond which demand is outpacing the maintainers. Large distros with dozens of
+/// active contributors sit well below this even with six-figure subreddits.
+pub const OVERLOADED: f64 = 5_000.0;
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct MaintainerLoad {
+    pub subscribers: i64,
+    pub open_issues: i64,
+    /// Contributors active in the last 30 days in the busiest repo, at least one
+    pub maintainers: i64,
+    /// Subscribers plus open issues per maintainer
+    pub load: f64,
+}
+
+impl MaintainerLoad {
+    /// None without GitHub data to count maintainers, or with no community to speak of
+    pub fn measure(github: &[GithubSnapshot], community: &[CommunitySnapshot]) -> Option<Self> {
+        if github.is_empty() {
+            return None;
+        }
+
+        let subscribers: i64 = community
+            .iter()
+            .filter(|c| c.source.starts_with("reddit:"))
+            .filter_map(|c| c.active_users_30d)
+            .sum();
+        let open_issues: i64 = github.iter().map(|s| s.open_issues).sum();
+        if subscribers + open_issues == 0 {
+            return None;
+        }
+
+        // Per repo rather than summed, since the same people work across repos; nobody
+        // active counts as one so the load stays finite and still stands out
+        let maintainers = github.iter().map(|s| s.contributors_30d).max().unwrap_or(0).max(1);
+
+        Some(Self {
+            subscribers,
+            open_issues,
+            maintainers,
+            load: ((subscribers + open_issues) as f64 / maintainers as f64).round(),
+        })
+    }
+
+    pub fn is_overloaded(&self) -> bool {
+        is_overloaded(self.load)
+    }
+}
+
+/// Whether a recorded load value is past [`OVERLOADED`]
+pub fn is_overloaded(load: f64) -> bool {
+    load > OVERLOADED
+}
@@ -0,0 +1,475 @@
+//! The health scoring rules, as data
+//!
+//! Score bands and weights live here rather than inline in the scoring functions so the
+//! analyzer and `GET /methodology` read the same tables: what the endpoint publishes is
+//! exactly what scores are calculated with.
+
+use serde::Serialize;
+
+/// Version of the scoring rules below; bump it and add a `methodology_changes` row
+/// whenever a weight, band or input changes
+pub const METHODOLOGY_VERSION: &str = "1.13";
+
+/// Upper limit of a score band
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Limit {
+    /// Up to and including the value
+    AtMost(f64),
+    /// Anything below the value
+    Below(f64),
+}
+
+/// One band: values within the limit score `score`
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Step {
+    #[serde(flatten)]
+    pub limit: Limit,
+    pub score: f64,
+}
+
+const fn at_most(limit: f64, score: f64) -> Step {
+    Step { limit: Limit::AtMost(limit), score }
+}
+
+const fn below(limit: f64, score: f64) -> Step {
+    Step { limit: Limit::Below(limit), score }
+}
+
+/// Maps a measured value to a 0-100 score
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Bands {
+    /// Checked in order; the first band the value falls within wins
+    pub steps: &'static [Step],
+    /// Score above the last band
+    pub otherwise: f64,
+}
+
+impl Bands {
+    pub fn score(&self, value: f64) -> f64 {
+        self.steps
+            .iter()
+            .find(|step| match step.limit {
+                Limit::AtMost(limit) => value <= limit,
+                Limit::Below(limit) => value < limit,
+            })
+            .map_or(self.otherwise, |step| step.score)
+    }
+}
+
+/// One weighted input to a component score
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Input {
+    pub key: &'static str,
+    pub description: &'static str,
+    pub weight: f64,
+    pub bands: Bands,
+}
+
+/// One of the three component scores making up the overall health score
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Component {
+    pub key: &'static str,
+    pub description: &'static str,
+    /// Share of the overall score
+    pub weight: f64,
+    pub inputs: &'static [Input],
+    /// How inputs are combined when it isn't a plain weighted sum
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub notes: Option<&'static str>,
+}
+
+/// Score given to a component with no data to score it on
+pub const NEUTRAL_SCORE: f64 = 50.0;
+
+/// Slope of the overall score, in points per run, beyond which the trend is "up" or "down"
+pub const TREND_THRESHOLD: f64 = 2.0;
+
+/// Scores, including the new one, that the trend slope is fitted over
+pub const TREND_WINDOW: usize = 5;
+
+/// Points taken off maintenance when the shipped kernel series is EOL upstream
+pub const EOL_KERNEL_PENALTY: f64 = 15.0;
+
+pub const COMMITS: Input = Input {
+    key: "commits_30d",
+    description: "Non-bot commits in the last 30 days across tracked repos",
+    weight: 0.6,
+    bands: Bands {
+        steps: &[
+            at_most(10.0, 20.0),
+            at_most(50.0, 40.0),
+            at_most(200.0, 60.0),
+            at_most(500.0, 80.0),
+        ],
+        otherwise: 95.0,
+    },
+};
+
+pub const CONTRIBUTORS: Input = Input {
+    key: "contributors_30d",
+    description: "Non-bot contributors in the last 30 days",
+    weight: 0.4,
+    bands: Bands {
+        steps: &[
+            at_most(2.0, 20.0),
+            at_most(10.0, 40.0),
+            at_most(30.0, 60.0),
+            at_most(100.0, 80.0),
+        ],
+        otherwise: 95.0,
+    },
+};
+
+pub const STAR_GROWTH: Input = Input {
+    key: "star_growth_30d",
+    description: "Stars gained per 30 days by repos tracked throughout",
+    weight: 0.5,
+    bands: Bands {
+        steps: &[
+            at_most(0.0, 10.0),
+            at_most(10.0, 30.0),
+            at_most(50.0, 50.0),
+            at_most(200.0, 70.0),
+            at_most(1000.0, 85.0),
+        ],
+        otherwise: 95.0,
+    },
+};
+
+pub const STAR_GROWTH_PCT: Input = Input {
+    key: "star_growth_pct",
+    description: "Stars gained per 30 days relative to the stars at the start, in percent",
+    weight: 0.5,
+    bands: Bands {
+        steps: &[
+            at_most(0.0, 10.0),
+            below(1.0, 30.0),
+            below(3.0, 50.0),
+            below(7.0, 70.0),
+            below(15.0, 85.0),
+        ],
+        otherwise: 95.0,
+    },
+};
+
+pub const STARS: Input = Input {
+    key: "stars",
+    description: "Total stars, used in place of star growth until there are 30 days of history",
+    weight: 0.5,
+    bands: Bands {
+        steps: &[
+            at_most(100.0, 20.0),
+            at_most(1000.0, 40.0),
+            at_most(5000.0, 60.0),
+            at_most(20000.0, 80.0),
+        ],
+        otherwise: 95.0,
+    },
+};
+
+pub const FORKS: Input = Input {
+    key: "forks",
+    description: "Total forks",
+    weight: 0.5,
+    bands: Bands {
+        steps: &[
+            at_most(10.0, 20.0),
+            at_most(100.0, 40.0),
+            at_most(500.0, 60.0),
+            at_most(2000.0, 80.0),
+        ],
+        otherwise: 95.0,
+    },
+};
+
+pub const SUBSCRIBER_GROWTH: Input = Input {
+    key: "subscriber_growth_pct",
+    description: "Subreddit growth per 30 days in percent, over the longest window with history",
+    weight: 0.7,
+    bands: Bands {
+        steps: &[
+            at_most(0.0, 10.0),
+            below(0.5, 30.0),
+            below(1.0, 50.0),
+            below(2.0, 70.0),
+            below(4.0, 85.0),
+        ],
+        otherwise: 95.0,
+    },
+};
+
+pub const SUBSCRIBERS: Input = Input {
+    key: "subscribers",
+    description: "Subreddit subscribers, used in place of growth until there are 30 days of history",
+    weight: 0.7,
+    bands: Bands {
+        steps: &[
+            at_most(1000.0, 20.0),
+            at_most(5000.0, 30.0),
+            at_most(15000.0, 45.0),
+            at_most(50000.0, 60.0),
+            at_most(100000.0, 75.0),
+            at_most(200000.0, 85.0),
+        ],
+        otherwise: 95.0,
+    },
+};
+
+pub const POSTS: Input = Input {
+    key: "posts_30d",
+    description: "Subreddit posts in the last 30 days",
+    weight: 0.3,
+    bands: Bands {
+        steps: &[
+            at_most(10.0, 20.0),
+            at_most(30.0, 40.0),
+            at_most(60.0, 60.0),
+            at_most(100.0, 80.0),
+        ],
+        otherwise: 95.0,
+    },
+};
+
+pub const DISCUSSIONS: Input = Input {
+    key: "discussions_30d",
+    description: "GitHub Discussions started in the last 30 days",
+    weight: 0.4,
+    bands: Bands {
+        steps: &[
+            at_most(10.0, 20.0),
+            at_most(30.0, 40.0),
+            at_most(60.0, 60.0),
+            at_most(100.0, 80.0),
+        ],
+        otherwise: 95.0,
+    },
+};
+
+pub const ANSWERED_QUESTIONS: Input = Input {
+    key: "answered_questions_pct",
+    description: "Questions asked in GitHub Discussions in the last 30 days that have an accepted answer, \
+                  in percent",
+    weight: 0.3,
+    bands: Bands {
+        steps: &[below(20.0, 20.0), below(40.0, 40.0), below(60.0, 60.0), below(80.0, 80.0)],
+        otherwise: 95.0,
+    },
+};
+
+pub const ANSWER_TIME: Input = Input {
+    key: "answer_hours",
+    description: "Mean hours from a question in GitHub Discussions to the comment accepted as its answer",
+    weight: 0.3,
+    bands: Bands {
+        steps: &[
+            at_most(24.0, 95.0),
+            at_most(72.0, 80.0),
+            at_most(168.0, 60.0),
+            at_most(336.0, 40.0),
+        ],
+        otherwise: 20.0,
+    },
+};
+
+/// How much an open issue in a core repo counts toward the backlog, by label category;
+/// unlabelled issues count once
+pub const ISSUE_LABEL_WEIGHTS: [(&str, f64); 4] =
+    [("security", 3.0), ("bug", 1.0), ("packaging", 1.0), ("feature", 0.5)];
+
+pub const OPEN_ISSUES: Input = Input {
+    key: "open_issues",
+    description: "Open issues across tracked repos, weighted by label in core repos",
+    weight: 0.3,
+    bands: Bands {
+        steps: &[
+            at_most(10.0, 90.0),
+            at_most(50.0, 80.0),
+            at_most(200.0, 70.0),
+            at_most(500.0, 50.0),
+            at_most(1000.0, 30.0),
+        ],
+        otherwise: 20.0,
+    },
+};
+
+pub const OPEN_PRS: Input = Input {
+    key: "open_prs",
+    description: "Open pull requests across tracked repos",
+    weight: 0.3,
+    bands: Bands {
+        steps: &[
+            at_most(5.0, 90.0),
+            at_most(20.0, 80.0),
+            at_most(50.0, 70.0),
+            at_most(100.0, 50.0),
+        ],
+        otherwise: 30.0,
+    },
+};
+
+pub const COMMIT_RECENCY: Input = Input {
+    key: "days_since_commit",
+    description: "Days since the most recent commit to any tracked repo",
+    weight: 0.4,
+    bands: Bands {
+        steps: &[
+            at_most(7.0, 100.0),
+            at_most(30.0, 80.0),
+            at_most(90.0, 60.0),
+            at_most(180.0, 40.0),
+        ],
+        otherwise: 20.0,
+    },
+};
+
+pub const ISSUE_RESOLUTION: Input = Input {
+    key: "issue_resolution_pct",
+    description: "Issues closed in the last 30 days per issue opened in core repos, in percent, not \
+                  counting closures by stale bots",
+    weight: 0.2,
+    bands: Bands {
+        steps: &[below(25.0, 20.0), below(50.0, 40.0), below(75.0, 60.0), below(100.0, 80.0)],
+        otherwise: 95.0,
+    },
+};
+
+pub const PACKAGE_FRESHNESS: Input = Input {
+    key: "outdated_packages_pct",
+    description: "Share of packages outdated according to Repology, in percent",
+    weight: 0.2,
+    bands: Bands {
+        steps: &[below(10.0, 95.0), below(20.0, 80.0), below(35.0, 60.0), below(50.0, 40.0)],
+        otherwise: 20.0,
+    },
+};
+
+pub const FIRST_TIME_CONTRIBUTORS: Input = Input {
+    key: "first_time_contributors_30d",
+    description: "Authors whose first pull request to a core repo was merged in the last 30 days",
+    weight: 0.15,
+    bands: Bands {
+        steps: &[
+            at_most(0.0, 20.0),
+            at_most(2.0, 45.0),
+            at_most(5.0, 65.0),
+            at_most(15.0, 80.0),
+        ],
+        otherwise: 95.0,
+    },
+};
+
+pub const UPLOADS: Input = Input {
+    key: "uploads_30d",
+    description: "Source packages uploaded to the distro's own archive in the last 30 days, in place of \
+                  commits and contributors when no forge repos are tracked",
+    weight: 1.0,
+    bands: Bands {
+        steps: &[
+            at_most(20.0, 20.0),
+            at_most(100.0, 40.0),
+            at_most(500.0, 60.0),
+            at_most(2000.0, 80.0),
+        ],
+        otherwise: 95.0,
+    },
+};
+
+pub const RC_BUGS: Input = Input {
+    key: "rc_bugs_per_1000_packages",
+    description: "Open release-critical bugs per thousand source packages in the distro's own archive, in \
+                  place of the forge backlog when no forge repos are tracked",
+    weight: 1.0,
+    bands: Bands {
+        steps: &[below(10.0, 90.0), below(20.0, 70.0), below(35.0, 50.0), below(60.0, 30.0)],
+        otherwise: 15.0,
+    },
+};
+
+/// Mirrors counted at most when every mirror is in one country: a single region's outage
+/// or blocking takes them all out at once
+pub const SINGLE_COUNTRY_MIRRORS: i64 = 3;
+
+pub const MIRROR_REDUNDANCY: Input = Input {
+    key: "mirrors",
+    description: "Download mirrors in the official mirror list, counted by host",
+    weight: 0.1,
+    bands: Bands {
+        steps: &[
+            at_most(1.0, 10.0),
+            at_most(3.0, 40.0),
+            at_most(10.0, 65.0),
+            at_most(30.0, 80.0),
+        ],
+        otherwise: 95.0,
+    },
+};
+
+/// Share of the community score taken by forums (Reddit, GitHub Discussions) when any is tracked
+pub const FORUM_WEIGHT: f64 = 0.6;
+
+pub const DEVELOPMENT: Component = Component {
+    key: "development",
+    description: "Commit and contributor activity",
+    weight: 0.4,
+    inputs: &[COMMITS, CONTRIBUTORS, UPLOADS],
+    notes: Some(
+        "Archive uploads score development on their own for distros developed in their package archive \
+         rather than on a tracked forge.",
+    ),
+};
+
+pub const COMMUNITY: Component = Component {
+    key: "community",
+    description: "GitHub stars and forks, and subreddit and GitHub Discussions size and activity",
+    weight: 0.3,
+    inputs: &[
+        STAR_GROWTH,
+        STAR_GROWTH_PCT,
+        STARS,
+        FORKS,
+        SUBSCRIBER_GROWTH,
+        SUBSCRIBERS,
+        POSTS,
+        DISCUSSIONS,
+        ANSWERED_QUESTIONS,
+        ANSWER_TIME,
+        FIRST_TIME_CONTRIBUTORS,
+    ],
+    notes: Some(
+        "GitHub (stars 50%, forks 50%) and forum sub-scores are combined 40/60 when a subreddit or \
+         GitHub Discussions are tracked, otherwise GitHub alone. The forum sub-score is Reddit \
+         (subscribers 70%, posts 30%), GitHub Discussions (discussions 40%, answered questions 30%, \
+         answer time 30%, or discussions alone when no questions were asked; no answers score the \
+         slowest answer time) or the mean of both. Growth inputs replace the absolute counts once there \
+         are 30 days of history. First-time contributors take 15% once measured, scaling the rest down \
+         to 85%.",
+    ),
+};
+
+pub const MAINTENANCE: Component = Component {
+    key: "maintenance",
+    description: "Issue and pull request backlog, commit recency, package freshness and mirror redundancy",
+    weight: 0.3,
+    inputs: &[
+        OPEN_ISSUES,
+        OPEN_PRS,
+        COMMIT_RECENCY,
+        ISSUE_RESOLUTION,
+        RC_BUGS,
+        PACKAGE_FRESHNESS,
+        MIRROR_REDUNDANCY,
+    ],
+    notes: Some(
+        "Issue resolution takes 20% of the GitHub part when core repos opened issues in the last \
+         30 days. Package freshness takes 20% when Repology data is available, scaling the GitHub inputs \
+         down to 80%. Mirror redundancy then takes 10% when the mirror list is tracked, counting no \
+         more than 3 mirrors when they are all in one country. Shipping a kernel series that is EOL \
+         upstream subtracts 15 points. Open security-labelled issues in core repos count triple and \
+         feature requests half. Without \
+         forge repos, release-critical bugs in the distro's own archive stand in for the GitHub part.",
+    ),
+};
+
+/// Components of the overall health score, in order
+pub const COMPONENTS: [Component; 3] = [DEVELOPMENT, COMMUNITY, MAINTENANCE];
@@ -0,0 +1,126 @@
+//! Milestone detection run after each health score calculation
+//!
+//! Everything is measured against the previous analysis run, so a milestone is only
+//! recorded when it was crossed in between. A distro's first analysis establishes the
+//! baseline and records nothing.
+
+use crate::Result;
+use chrono::{DateTime, Datelike, Months, Utc};
+use distrovitals_database::{Database, HealthScore, NewMilestone};
+
+/// Overall scores are recorded when they cross a multiple of this
+const SCORE_STEP: f64 = 10.0;
+
+/// Total star counts worth marking
+const STAR_MILESTONES: &[i64] = &[
+    1_000, 2_500, 5_000, 10_000, 25_000, 50_000, 100_000, 250_000, 500_000, 1_000_000,
+];
+
+/// Release anniversaries worth marking, in years
+const ANNIVERSARY_YEARS: &[u32] = &[1, 5, 10, 15, 20, 25, 30];
+
+/// Detect and record milestones crossed since the previous score
+///
+/// Returns the milestones that were newly recorded.
+pub async fn record(
+    db: &Database,
+    distro_id: i64,
+    previous: Option<&HealthScore>,
+    overall_score: f64,
+    now: DateTime<Utc>,
+) -> Result<Vec<NewMilestone>> {
+    let Some(previous) = previous else {
+        return Ok(Vec::new());
+    };
+    let since = previous.calculated_at;
+
+    let mut found = Vec::new();
+
+    // Highest score threshold crossed upwards
+    let threshold = (overall_score / SCORE_STEP).floor() * SCORE_STEP;
+    if threshold > 0.0 && previous.overall_score < threshold {
+        found.push(NewMilestone {
+            distro_id,
+            kind: "score_threshold".to_string(),
+            key: format!("score:{:.0}:{}", threshold, now.format("%Y-%m-%d")),
+            title: format!("Health score reached {:.0}", threshold),
+            value: Some(threshold),
+        });
+    }
+
+    // Highest star count crossed; a zero baseline means stars were never collected
+    let stars_before = db.get_metric_totals_as_of(distro_id, since).await?.stars;
+    let stars_now = db.get_metric_totals_as_of(distro_id, now).await?.stars;
+    if stars_before > 0 {
+        if let Some(&stars) = STAR_MILESTONES
+            .iter()
+            .rev()
+            .find(|&&m| stars_before < m && m <= stars_now)
+        {
+            found.push(NewMilestone {
+                distro_id,
+                kind: "stars".to_string(),
+                key: format!("stars:{}", stars),
+                title: format!("{} stars across tracked repos", group_thousands(stars)),
+                value: Some(stars as f64),
+            });
+        }
+    }
+
+    // Round anniversaries of stable releases falling since the last run
+    for release in db.get_latest_release_snapshots(distro_id).await? {
+        let Some(published) = release.published_at else { continue };
+        if release.is_prerelease {
+            continue;
+        }
+
+        for &years in ANNIVERSARY_YEARS {
+            let Some(anniversary) = published.checked_add_months(Months::new(years * 12)) else {
+                continue;
+            };
+            if anniversary <= since || anniversary > now {
+                continue;
+            }
+
+            let name = release
+                .release_name
+                .as_deref()
+                .filter(|n| !n.is_empty())
+                .unwrap_or(&release.tag_name);
+            found.push(NewMilestone {
+                distro_id,
+                kind: "release_anniversary".to_string(),
+                key: format!("release:{}:{}:{}", release.repo_name, release.tag_name, years),
+                title: format!(
+                    "{} year{} since {} ({})",
+                    years,
+                    if years == 1 { "" } else { "s" },
+                    name,
+                    published.year()
+                ),
+                value: Some(years as f64),
+            });
+        }
+    }
+
+    let mut recorded = Vec::new();
+    for milestone in found {
+        if db.insert_milestone(milestone.clone()).await? {
+            recorded.push(milestone);
+        }
+    }
+
+    Ok(recorded)
+}
+
+fn group_thousands(n: i64) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
@@ -0,0 +1,109 @@
+//! Momentum: which distros are on the way up
+//!
+//! A separate index from health. A large, stable distro can be perfectly healthy with
+//! flat growth; momentum only looks at direction: star and subscriber growth, whether
+//! commit activity is rising, and how recently something shipped. 50 means steady.
+
+use crate::velocity::{commit_trend, github_baseline, COMMIT_TREND_DAYS};
+use crate::{Result, StarVelocity, SubscriberVelocity};
+use distrovitals_database::{Database, Distribution};
+use serde::Serialize;
+
+/// A distro's momentum and the signals behind it
+#[derive(Debug, Clone, Serialize)]
+pub struct Momentum {
+    /// Average of the available component scores (0-100), None without any signal
+    pub score: Option<f64>,
+    pub components: MomentumComponents,
+    /// Stars gained per 30 days
+    pub star_growth_30d: Option<i64>,
+    /// Subreddit growth per 30 days, in percent
+    pub subscriber_growth_pct: Option<f64>,
+    /// Change in 30-day commit count versus a month earlier, in percent
+    pub commit_change_pct: Option<f64>,
+    pub days_since_release: Option<i64>,
+}
+
+/// Component scores, 0-100; None when there isn't enough history for the signal
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MomentumComponents {
+    pub star_growth: Option<f64>,
+    pub subscriber_growth: Option<f64>,
+    pub commit_trend: Option<f64>,
+    pub release_recency: Option<f64>,
+}
+
+impl Momentum {
+    /// Compute momentum from a distro's stored snapshots
+    pub async fn load(db: &Database, distro: &Distribution) -> Result<Self> {
+        let github = db.get_latest_github_snapshots(distro.id).await?;
+        let community = db.get_latest_community_snapshots(distro.id).await?;
+        let releases = db.get_latest_release_snapshots(distro.id).await?;
+
+        let stars = StarVelocity::load(db, distro.id, &github).await?;
+        // The longest window is the least noisy
+        let subscribers = SubscriberVelocity::load(db, distro.id, &community).await?.pop();
+        let commits = commit_trend(&github, &github_baseline(db, distro.id, &github, COMMIT_TREND_DAYS).await?);
+        let days_since_release = releases
+            .iter()
+            .filter(|r| !r.is_prerelease)
+            .filter_map(|r| r.published_at)
+            .max()
+            .map(|published| (chrono::Utc::now() - published).num_days());
+
+        let commit_change_pct = commits.map(|g| g.delta as f64 / g.baseline.max(1) as f64 * 100.0);
+        let components = MomentumComponents {
+            star_growth: stars.map(|v| v.score()),
+            subscriber_growth: subscribers.map(|v| v.score()),
+            commit_trend: commit_change_pct.map(commit_trend_score),
+            release_recency: days_since_release.map(release_recency_score),
+        };
+
+        let available: Vec<f64> = [
+            components.star_growth,
+            components.subscriber_growth,
+            components.commit_trend,
+            components.release_recency,
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+        let score = (!available.is_empty())
+            .then(|| (available.iter().sum::<f64>() / available.len() as f64 * 10.0).round() / 10.0);
+
+        Ok(Self {
+            score,
+            components,
+            star_growth_30d: stars.map(|v| v.delta),
+            subscriber_growth_pct: subscribers.map(|v| (v.monthly_growth_pct() * 100.0).round() / 100.0),
+            commit_change_pct: commit_change_pct.map(|pct| (pct * 10.0).round() / 10.0),
+            days_since_release,
+        })
+    }
+}
+
+fn commit_trend_score(change_pct: f64) -> f64 {
+    if change_pct <= -50.0 {
+        10.0
+    } else if change_pct < -15.0 {
+        30.0
+    } else if change_pct < 15.0 {
+        50.0
+    } else if change_pct < 50.0 {
+        70.0
+    } else if change_pct < 100.0 {
+        85.0
+    } else {
+        95.0
+    }
+}
+
+fn release_recency_score(days: i64) -> f64 {
+    match days {
+        i64::MIN..=30 => 95.0,
+        31..=90 => 75.0,
+        91..=180 => 55.0,
+        181..=365 => 35.0,
+        _ => 15.0,
+    }
+}
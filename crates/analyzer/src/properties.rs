@@ -0,0 +1,409 @@
+//! Invariants of the scoring, checked over randomly generated inputs
+//!
+//! Bucketed scoring makes it easy to add a band that scores lower than the one before it,
+//! or a weight that no longer sums with its siblings to one. These generate a few thousand
+//! distros from fixed seeds and check that no score leaves 0-100 and that improving any
+//! single input never lowers a score. A failure prints the inputs that broke it.
+
+use crate::fixtures::{archive, community, discussions, infra, issue_labels, packages, repo};
+use crate::methodology::{
+    Input, ANSWERED_QUESTIONS, ANSWER_TIME, COMMITS, COMMIT_RECENCY, COMPONENTS, CONTRIBUTORS, DISCUSSIONS,
+    FIRST_TIME_CONTRIBUTORS, FORKS, FORUM_WEIGHT, ISSUE_RESOLUTION, MIRROR_REDUNDANCY, OPEN_ISSUES, OPEN_PRS,
+    PACKAGE_FRESHNESS, POSTS, RC_BUGS, STARS, STAR_GROWTH, STAR_GROWTH_PCT, SUBSCRIBERS, SUBSCRIBER_GROWTH,
+    UPLOADS,
+};
+use crate::{Aggregation, Analyzer, ScoreInputs, Scores, StarVelocity, SubscriberVelocity};
+use chrono::{DateTime, TimeDelta, Utc};
+use distrovitals_database::{GithubSnapshot, IssueActivity};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Random distros checked per property
+const CASES: usize = 2_000;
+
+const EPSILON: f64 = 1e-9;
+
+/// A count spread over several orders of magnitude, so every band gets exercised
+fn count(rng: &mut StdRng) -> i64 {
+    let magnitude = rng.gen_range(0..7);
+    rng.gen_range(0..10i64.pow(magnitude) * 5)
+}
+
+fn arbitrary_repo(rng: &mut StdRng, index: usize, now: DateTime<Utc>) -> GithubSnapshot {
+    GithubSnapshot {
+        stars: count(rng),
+        forks: count(rng),
+        open_issues: count(rng),
+        open_prs: count(rng),
+        commits_30d: count(rng),
+        commits_365d: count(rng),
+        contributors_30d: count(rng),
+        last_commit_at: rng
+            .gen_bool(0.9)
+            .then(|| now - TimeDelta::days(rng.gen_range(0..1_500))),
+        ..repo(&format!("org/repo-{}", index), now)
+    }
+}
+
+fn arbitrary_inputs(rng: &mut StdRng, now: DateTime<Utc>) -> ScoreInputs {
+    let github = (0..rng.gen_range(0..6)).map(|i| arbitrary_repo(rng, i, now)).collect();
+
+    let mut sources = Vec::new();
+    if rng.gen_bool(0.7) {
+        let users = rng.gen_bool(0.9).then(|| count(rng));
+        let posts = rng.gen_bool(0.9).then(|| count(rng));
+        sources.push(community("reddit:r/distro", users, posts, now));
+    }
+    if rng.gen_bool(0.3) {
+        sources.push(community("discourse:forum.example.org", Some(count(rng)), Some(count(rng)), now));
+    }
+    if rng.gen_bool(0.3) {
+        let posts = count(rng);
+        let questions = rng.gen_range(0..=posts);
+        let answers = rng.gen_range(0..=questions);
+        let hours = (answers > 0).then(|| rng.gen_range(0.0..2_000.0));
+        sources.push(discussions(posts, questions, answers, hours, now));
+    }
+
+    let packages = rng.gen_bool(0.6).then(|| {
+        let total = count(rng);
+        let outdated = rng.gen_range(0..=total);
+        let status = ["mainline", "stable", "longterm", "eol"][rng.gen_range(0..4)];
+        packages(total, outdated, rng.gen_bool(0.8).then_some(status), now)
+    });
+
+    let mut labels = Vec::new();
+    for category in ["security", "bug", "packaging", "feature"] {
+        if rng.gen_bool(0.5) {
+            labels.push((category, count(rng)));
+        }
+    }
+
+    let aggregation = match rng.gen_range(0..3) {
+        0 => Aggregation::Sum,
+        1 => Aggregation::Mean,
+        _ => Aggregation::TopK(rng.gen_range(1..5)),
+    };
+
+    ScoreInputs {
+        github,
+        community: sources,
+        packages,
+        issue_labels: issue_labels(&labels),
+        issue_activity: rng.gen_bool(0.5).then(|| {
+            let closed_30d = count(rng);
+            IssueActivity {
+                opened_30d: count(rng),
+                closed_30d,
+                stale_closed_30d: rng.gen_range(0..=closed_30d),
+            }
+        }),
+        first_time_contributors: rng.gen_bool(0.5).then(|| count(rng)),
+        infra: rng.gen_bool(0.5).then(|| {
+            let mirrors = rng.gen_range(1..500);
+            let countries = rng.gen_bool(0.8).then(|| rng.gen_range(1..=mirrors.min(60)));
+            infra(mirrors, countries, now)
+        }),
+        archive: rng.gen_bool(0.5).then(|| {
+            let rc_bugs = rng.gen_bool(0.9).then(|| count(rng));
+            let uploads_30d = rng.gen_bool(0.9).then(|| count(rng));
+            archive(count(rng), rc_bugs, uploads_30d, now)
+        }),
+        aggregation,
+        star_velocity: rng.gen_bool(0.5).then(|| StarVelocity {
+            delta: rng.gen_range(-500..5_000),
+            baseline: count(rng),
+            days: rng.gen_range(30..120),
+        }),
+        subscriber_velocity: rng.gen_bool(0.5).then(|| SubscriberVelocity {
+            window_days: [30, 90][rng.gen_range(0..2)],
+            delta: rng.gen_range(-5_000..20_000),
+            baseline: count(rng),
+        }),
+    }
+}
+
+/// Run `check` against `CASES` random distros generated from `seed`
+fn for_all(seed: u64, mut check: impl FnMut(&mut StdRng, ScoreInputs, DateTime<Utc>)) {
+    let now = Utc::now();
+    let mut rng = StdRng::seed_from_u64(seed);
+    for _ in 0..CASES {
+        let inputs = arbitrary_inputs(&mut rng, now);
+        check(&mut rng, inputs, now);
+    }
+}
+
+fn components(scores: &Scores) -> [(&'static str, f64); 4] {
+    [
+        ("overall", scores.overall),
+        ("development", scores.development),
+        ("community", scores.community),
+        ("maintenance", scores.maintenance),
+    ]
+}
+
+#[test]
+fn scores_stay_within_bounds() {
+    for_all(1, |_, inputs, now| {
+        let scores = Analyzer::score(&inputs, now);
+        for (component, score) in components(&scores) {
+            assert!(
+                (0.0..=100.0).contains(&score),
+                "{} score {} out of range for {:#?}",
+                component,
+                score,
+                inputs
+            );
+        }
+    });
+}
+
+#[test]
+fn overall_is_between_its_components() {
+    for_all(2, |_, inputs, now| {
+        let scores = Analyzer::score(&inputs, now);
+        let parts = [scores.development, scores.community, scores.maintenance];
+        let lowest = parts.iter().copied().fold(f64::INFINITY, f64::min);
+        let highest = parts.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        assert!(
+            scores.overall >= lowest - EPSILON && scores.overall <= highest + EPSILON,
+            "overall {} outside its components {:?} for {:#?}",
+            scores.overall,
+            parts,
+            inputs
+        );
+    });
+}
+
+/// Whether making an input larger should raise scores or lower them
+#[derive(Debug, Clone, Copy)]
+enum Better {
+    Higher,
+    Lower,
+}
+
+type Bump = fn(&mut ScoreInputs, i64);
+
+/// One input at a time, each increased by a random amount
+const BUMPS: [(&str, Better, Bump); 24] = [
+    ("commits_30d", Better::Higher, |inputs, n| {
+        inputs.github.iter_mut().for_each(|r| r.commits_30d += n)
+    }),
+    ("contributors_30d", Better::Higher, |inputs, n| {
+        inputs.github.iter_mut().for_each(|r| r.contributors_30d += n)
+    }),
+    ("stars", Better::Higher, |inputs, n| inputs.github.iter_mut().for_each(|r| r.stars += n)),
+    ("forks", Better::Higher, |inputs, n| inputs.github.iter_mut().for_each(|r| r.forks += n)),
+    ("star_growth", Better::Higher, |inputs, n| {
+        if let Some(velocity) = &mut inputs.star_velocity {
+            velocity.delta += n;
+        }
+    }),
+    ("subscribers", Better::Higher, |inputs, n| {
+        for source in inputs.community.iter_mut().filter(|c| c.source.starts_with("reddit:")) {
+            source.active_users_30d = source.active_users_30d.map(|users| users + n);
+        }
+    }),
+    ("posts_30d", Better::Higher, |inputs, n| {
+        for source in inputs.community.iter_mut().filter(|c| c.source.starts_with("reddit:")) {
+            source.posts_30d = source.posts_30d.map(|posts| posts + n);
+        }
+    }),
+    ("discussions_30d", Better::Higher, |inputs, n| {
+        for source in inputs.community.iter_mut().filter(|c| c.source.starts_with("github-discussions:")) {
+            source.posts_30d = source.posts_30d.map(|posts| posts + n);
+        }
+    }),
+    ("answered_questions", Better::Higher, |inputs, n| {
+        for source in inputs.community.iter_mut().filter(|c| c.source.starts_with("github-discussions:")) {
+            let questions = source.questions_30d.unwrap_or(0);
+            source.answers_30d = source.answers_30d.map(|answers| (answers + n).min(questions));
+        }
+    }),
+    ("answer_hours", Better::Lower, |inputs, n| {
+        for source in inputs.community.iter_mut().filter(|c| c.source.starts_with("github-discussions:")) {
+            source.response_time_avg_hours = source.response_time_avg_hours.map(|hours| hours + n as f64);
+        }
+    }),
+    ("subscriber_growth", Better::Higher, |inputs, n| {
+        if let Some(velocity) = &mut inputs.subscriber_velocity {
+            velocity.delta += n;
+        }
+    }),
+    ("first_time_contributors", Better::Higher, |inputs, n| {
+        inputs.first_time_contributors = inputs.first_time_contributors.map(|newcomers| newcomers + n);
+    }),
+    ("open_issues", Better::Lower, |inputs, n| {
+        inputs.github.iter_mut().for_each(|r| r.open_issues += n)
+    }),
+    ("security_issues", Better::Lower, |inputs, n| {
+        for count in inputs.issue_labels.iter_mut().filter(|c| c.category == "security") {
+            count.open_issues += n;
+        }
+    }),
+    ("issues_resolved", Better::Higher, |inputs, n| {
+        if let Some(activity) = &mut inputs.issue_activity {
+            activity.closed_30d += n;
+        }
+    }),
+    ("stale_closures", Better::Lower, |inputs, n| {
+        if let Some(activity) = &mut inputs.issue_activity {
+            activity.stale_closed_30d = (activity.stale_closed_30d + n).min(activity.closed_30d);
+        }
+    }),
+    ("open_prs", Better::Lower, |inputs, n| inputs.github.iter_mut().for_each(|r| r.open_prs += n)),
+    ("days_since_commit", Better::Lower, |inputs, n| {
+        for repo in &mut inputs.github {
+            repo.last_commit_at = repo.last_commit_at.map(|at| at - TimeDelta::days(n));
+        }
+    }),
+    ("outdated_packages", Better::Lower, |inputs, n| {
+        if let Some(packages) = &mut inputs.packages {
+            packages.outdated_packages = (packages.outdated_packages + n).min(packages.total_packages);
+        }
+    }),
+    ("mirrors", Better::Higher, |inputs, n| {
+        if let Some(infra) = &mut inputs.infra {
+            infra.mirrors += n;
+        }
+    }),
+    ("mirror_countries", Better::Higher, |inputs, n| {
+        if let Some(infra) = &mut inputs.infra {
+            infra.mirror_countries = infra.mirror_countries.map(|countries| (countries + n).min(infra.mirrors));
+        }
+    }),
+    ("uploads_30d", Better::Higher, |inputs, n| {
+        if let Some(archive) = &mut inputs.archive {
+            archive.uploads_30d = archive.uploads_30d.map(|uploads| uploads + n);
+        }
+    }),
+    ("rc_bugs", Better::Lower, |inputs, n| {
+        if let Some(archive) = &mut inputs.archive {
+            archive.rc_bugs = archive.rc_bugs.map(|bugs| bugs + n);
+        }
+    }),
+    ("kernel_eol", Better::Lower, |inputs, _| {
+        if let Some(packages) = &mut inputs.packages {
+            packages.kernel_status = Some("eol".to_string());
+        }
+    }),
+];
+
+#[test]
+fn scores_are_monotonic_in_each_input() {
+    for (input, better, bump) in BUMPS {
+        for_all(3, |rng, inputs, now| {
+            let before = Analyzer::score(&inputs, now);
+            let mut bumped = inputs.clone();
+            bump(&mut bumped, rng.gen_range(1..10_000));
+            let after = Analyzer::score(&bumped, now);
+
+            for ((component, was), (_, is)) in components(&before).into_iter().zip(components(&after)) {
+                let ok = match better {
+                    Better::Higher => is >= was - EPSILON,
+                    Better::Lower => is <= was + EPSILON,
+                };
+                assert!(
+                    ok,
+                    "raising {} moved the {} score the wrong way ({} -> {}) for {:#?}",
+                    input,
+                    component,
+                    was,
+                    is,
+                    inputs
+                );
+            }
+        });
+    }
+}
+
+/// Every band scores within 0-100 and never lower than a band for a worse value
+fn assert_bands_ordered(input: &Input, better: Better) {
+    let mut scores: Vec<f64> = input.bands.steps.iter().map(|step| step.score).collect();
+    scores.push(input.bands.otherwise);
+    for score in &scores {
+        assert!((0.0..=100.0).contains(score), "{} has a band scoring {}", input.key, score);
+    }
+    let ordered = scores.windows(2).all(|pair| match better {
+        Better::Higher => pair[0] <= pair[1],
+        Better::Lower => pair[0] >= pair[1],
+    });
+    assert!(ordered, "{} bands aren't ordered: {:?}", input.key, scores);
+}
+
+#[test]
+fn bands_are_ordered() {
+    let higher = [
+        COMMITS,
+        CONTRIBUTORS,
+        STAR_GROWTH,
+        STAR_GROWTH_PCT,
+        STARS,
+        FORKS,
+        SUBSCRIBER_GROWTH,
+        SUBSCRIBERS,
+        POSTS,
+        DISCUSSIONS,
+        FIRST_TIME_CONTRIBUTORS,
+        MIRROR_REDUNDANCY,
+        UPLOADS,
+    ];
+    for input in &higher {
+        assert_bands_ordered(input, Better::Higher);
+    }
+    assert_bands_ordered(&ISSUE_RESOLUTION, Better::Higher);
+    assert_bands_ordered(&ANSWERED_QUESTIONS, Better::Higher);
+    for input in &[OPEN_ISSUES, OPEN_PRS, COMMIT_RECENCY, ANSWER_TIME, PACKAGE_FRESHNESS, RC_BUGS] {
+        assert_bands_ordered(input, Better::Lower);
+    }
+}
+
+/// Weights that are blended together must sum to one, or a distro maxing every input
+/// wouldn't score 100
+#[test]
+fn weights_renormalize() {
+    let sums = [
+        ("components", COMPONENTS.iter().map(|c| c.weight).sum::<f64>()),
+        ("development", COMMITS.weight + CONTRIBUTORS.weight),
+        ("github community", STARS.weight + FORKS.weight),
+        ("star growth", STAR_GROWTH.weight + STAR_GROWTH_PCT.weight),
+        ("reddit", SUBSCRIBERS.weight + POSTS.weight),
+        ("reddit growth", SUBSCRIBER_GROWTH.weight + POSTS.weight),
+        ("discussions", DISCUSSIONS.weight + ANSWERED_QUESTIONS.weight + ANSWER_TIME.weight),
+        ("maintenance", OPEN_ISSUES.weight + OPEN_PRS.weight + COMMIT_RECENCY.weight),
+    ];
+    for (group, sum) in sums {
+        assert!((sum - 1.0).abs() < EPSILON, "{} weights sum to {}", group, sum);
+    }
+    assert!((0.0..=1.0).contains(&FORUM_WEIGHT));
+}
+
+/// Package freshness is blended in rather than added on: with it, maintenance lands
+/// between the GitHub-only score and the freshness score (leaving mirrors out, which are
+/// blended in after it)
+#[test]
+fn package_freshness_blends_between_scores() {
+    for_all(4, |_, inputs, now| {
+        let inputs = ScoreInputs { infra: None, ..inputs };
+        let Some(snapshot) = inputs.packages.as_ref() else {
+            return;
+        };
+        if snapshot.total_packages == 0 || snapshot.kernel_status.as_deref() == Some("eol") {
+            return;
+        }
+
+        let outdated_pct = snapshot.outdated_packages as f64 / snapshot.total_packages as f64 * 100.0;
+        let freshness = PACKAGE_FRESHNESS.bands.score(outdated_pct);
+        let without = Analyzer::score(&ScoreInputs { packages: None, ..inputs.clone() }, now).maintenance;
+        let with = Analyzer::score(&inputs, now).maintenance;
+
+        assert!(
+            with >= without.min(freshness) - EPSILON && with <= without.max(freshness) + EPSILON,
+            "maintenance {} isn't between {} and freshness {} for {:#?}",
+            with,
+            without,
+            freshness,
+            inputs
+        );
+    });
+}
@@ -0,0 +1,248 @@
+//! Data quality: how far a distro's health score can be trusted
+//!
+//! A low health score can mean a struggling distro or collections that stopped working.
+//! This scores the data instead: the share of the distro's sources whose latest collection
+//! succeeded and is recent, less a penalty for each sign that what was collected is off,
+//! so consumers can tell "low score" from "bad data".
+
+use crate::Result;
+use chrono::{DateTime, TimeDelta, Utc};
+use distrovitals_database::{Database, GithubSnapshot, Provenance, SourceRunStatus};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Sources not run for this long are taken to be no longer configured for the distro
+const SOURCE_WINDOW_DAYS: i64 = 90;
+
+/// A source whose last successful collection is older than this is stale; longer than
+/// any source's default TTL, so it means collection stopped rather than wasn't due
+const STALE_DAYS: i64 = 14;
+
+/// Days back the star count is compared against
+const STAR_DROP_DAYS: i64 = 7;
+
+/// Stars lost in `STAR_DROP_DAYS` that look like a collection problem rather than a
+/// distro losing its following
+const STAR_DROP_PCT: f64 = 50.0;
+
+/// Points off the score for each anomaly in the collected data
+const ANOMALY_PENALTY: f64 = 10.0;
+
+/// Scores at or above these are good and degraded respectively; lower ones are poor
+const GOOD_SCORE: f64 = 80.0;
+const DEGRADED_SCORE: f64 = 50.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum QualityLevel {
+    Good,
+    Degraded,
+    Poor,
+}
+
+impl QualityLevel {
+    fn from_score(score: f64) -> Self {
+        if score >= GOOD_SCORE {
+            Self::Good
+        } else if score >= DEGRADED_SCORE {
+            Self::Degraded
+        } else {
+            Self::Poor
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Good => "good",
+            Self::Degraded => "degraded",
+            Self::Poor => "poor",
+        }
+    }
+}
+
+/// One problem with a distro's data
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct QualityFlag {
+    /// "failed_source", "stale_source", "star_drop" or "partial_collection"
+    pub kind: &'static str,
+    pub detail: String,
+}
+
+impl QualityFlag {
+    /// Whether the flag is about what was collected rather than whether collection ran
+    fn is_anomaly(&self) -> bool {
+        matches!(self.kind, "star_drop" | "partial_collection")
+    }
+}
+
+/// How complete and current the data behind a distro's health score is
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DataQuality {
+    /// 0-100
+    pub score: f64,
+    pub level: QualityLevel,
+    /// Sources collected for the distro in the last 90 days
+    pub sources: usize,
+    /// Of those, the ones whose latest collection succeeded within the last 14 days
+    pub sources_ok: usize,
+    /// Hours since the stalest source last collected successfully
+    pub oldest_data_hours: Option<i64>,
+    pub flags: Vec<QualityFlag>,
+}
+
+impl DataQuality {
+    /// Assess a distribution's data, `None` when none of its sources has been collected
+    pub async fn assess(db: &Database, distro_id: i64) -> Result<Option<Self>> {
+        let now = Utc::now();
+        let runs = db
+            .get_source_run_status(distro_id, now - TimeDelta::days(SOURCE_WINDOW_DAYS))
+            .await?;
+        if runs.is_empty() {
+            return Ok(None);
+        }
+
+        let mut anomalies = Vec::new();
+        let github = db.get_latest_github_snapshots(distro_id).await?;
+        if let Some(latest) = github.iter().map(|s| s.collected_at).max() {
+            let before = db
+                .get_github_snapshots_as_of(distro_id, latest - TimeDelta::days(STAR_DROP_DAYS))
+                .await?;
+            anomalies.extend(star_drop(&github, &before));
+        }
+        let community = db.get_latest_community_snapshots(distro_id).await?;
+        let provenances = github
+            .iter()
+            .filter_map(|s| s.provenance.as_deref())
+            .chain(community.iter().filter_map(|s| s.provenance.as_deref()));
+        anomalies.extend(partial_collection(provenances));
+
+        Ok(Some(Self::from_runs(&runs, anomalies, now)))
+    }
+
+    /// Score the latest runs of each source together with anomalies found in the data
+    pub fn from_runs(runs: &[SourceRunStatus], anomalies: Vec<QualityFlag>, now: DateTime<Utc>) -> Self {
+        let mut flags = Vec::new();
+        let mut sources_ok = 0;
+        for run in runs {
+            let age_days = run.last_success_at.map(|at| (now - at).num_days());
+            match (&run.last_error, age_days) {
+                (Some(error), Some(days)) => flags.push(QualityFlag {
+                    kind: "failed_source",
+                    detail: format!("{} failed: {} (last succeeded {} days ago)", run.source, error, days),
+                }),
+                (Some(error), None) => flags.push(QualityFlag {
+                    kind: "failed_source",
+                    detail: format!("{} failed: {} (never succeeded)", run.source, error),
+                }),
+                (None, Some(days)) if days > STALE_DAYS => flags.push(QualityFlag {
+                    kind: "stale_source",
+                    detail: format!("{} last collected {} days ago", run.source, days),
+                }),
+                _ => sources_ok += 1,
+            }
+        }
+        flags.extend(anomalies);
+
+        let collected = sources_ok as f64 / runs.len() as f64 * 100.0;
+        let penalty = flags.iter().filter(|f| f.is_anomaly()).count() as f64 * ANOMALY_PENALTY;
+        let score = ((collected - penalty).clamp(0.0, 100.0) * 10.0).round() / 10.0;
+        // A source that never succeeded has no data to be old
+        let oldest = runs.iter().filter_map(|r| r.last_success_at).min();
+
+        Self {
+            score,
+            level: QualityLevel::from_score(score),
+            sources: runs.len(),
+            sources_ok,
+            oldest_data_hours: oldest.map(|at| (now - at).num_hours()),
+            flags,
+        }
+    }
+}
+
+/// A flag when repos tracked in both batches lost at least `STAR_DROP_PCT` of their stars
+fn star_drop(current: &[GithubSnapshot], before: &[GithubSnapshot]) -> Option<QualityFlag> {
+    let earlier: HashMap<&str, i64> = before.iter().map(|s| (s.repo_name.as_str(), s.stars)).collect();
+    let (now, then) = current
+        .iter()
+        .filter_map(|s| earlier.get(s.repo_name.as_str()).map(|&then| (s.stars, then)))
+        .fold((0, 0), |(now, then), (n, t)| (now + n, then + t));
+    if then == 0 {
+        return None;
+    }
+
+    let drop_pct = (then - now) as f64 / then as f64 * 100.0;
+    (drop_pct >= STAR_DROP_PCT).then(|| QualityFlag {
+        kind: "star_drop",
+        detail: format!("GitHub stars fell from {} to {} in {} days", then, now, STAR_DROP_DAYS),
+    })
+}
+
+/// A flag when the latest snapshots were collected with failed requests filled in with
+/// zeros or left unset
+fn partial_collection<'a>(provenances: impl IntoIterator<Item = &'a Provenance>) -> Option<QualityFlag> {
+    let failures: Vec<&str> = provenances
+        .into_iter()
+        .flat_map(|p| &p.fallbacks)
+        .filter(|note| note.contains(" failed ("))
+        .map(String::as_str)
+        .collect();
+    let first = failures.first()?;
+
+    let detail = match failures.len() {
+        1 => format!("Collected with a failed request: {}", first),
+        n => format!("Collected with {} failed requests, e.g. {}", n, first),
+    };
+    Some(QualityFlag { kind: "partial_collection", detail })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(source: &str, error: Option<&str>, success_days_ago: Option<i64>, now: DateTime<Utc>) -> SourceRunStatus {
+        SourceRunStatus {
+            source: source.to_string(),
+            last_run_at: now,
+            last_error: error.map(str::to_string),
+            last_error_kind: error.map(|_| "http".to_string()),
+            last_success_at: success_days_ago.map(|days| now - TimeDelta::days(days)),
+        }
+    }
+
+    #[test]
+    fn scores_the_share_of_sources_collected_less_anomalies() {
+        let now = Utc::now();
+        let runs = [
+            run("github", None, Some(0), now),
+            run("reddit", None, Some(1), now),
+            run("packages", Some("timed out"), Some(3), now),
+            run("mirrors", None, Some(30), now),
+        ];
+
+        let quality = DataQuality::from_runs(&runs, Vec::new(), now);
+        assert_eq!(quality.score, 50.0);
+        assert_eq!(quality.level, QualityLevel::Degraded);
+        assert_eq!((quality.sources, quality.sources_ok), (4, 2));
+        assert_eq!(quality.oldest_data_hours, Some(30 * 24));
+        let kinds: Vec<_> = quality.flags.iter().map(|f| f.kind).collect();
+        assert_eq!(kinds, ["failed_source", "stale_source"]);
+
+        let drop = QualityFlag { kind: "star_drop", detail: String::new() };
+        let quality = DataQuality::from_runs(&runs[..2], vec![drop], now);
+        assert_eq!(quality.score, 90.0);
+        assert_eq!(quality.level, QualityLevel::Good);
+    }
+
+    #[test]
+    fn only_failed_requests_count_as_partial_collection() {
+        let mut provenance = Provenance::new("test");
+        provenance.fallback("no releases → tags");
+        assert_eq!(partial_collection([&provenance]), None);
+
+        provenance.fallback("open PR search failed (HTTP 502) → 0");
+        let flag = partial_collection([&provenance]).unwrap();
+        assert_eq!(flag.kind, "partial_collection");
+        assert!(flag.detail.contains("open PR search"));
+    }
+}
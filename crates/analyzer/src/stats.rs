@@ -0,0 +1,369 @@
+//! Field-wide statistics for scores and raw metrics
+//!
+//! Values are taken from each scored distro's latest health score and current raw
+//! metrics, so a distro's number can be placed against the rest of the field.
+
+use crate::{variant, Aggregation, RawMetrics, Result};
+use distrovitals_database::{Database, Distribution, HealthScore};
+use serde::Serialize;
+
+/// A per-distro number that can be compared across the field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    OverallScore,
+    DevelopmentScore,
+    CommunityScore,
+    MaintenanceScore,
+    TotalStars,
+    TotalForks,
+    TotalContributors,
+    Commits30d,
+    Commits365d,
+    OpenIssues,
+    OpenPrs,
+    Releases30d,
+    DaysSinceRelease,
+    TotalPackages,
+    OutdatedPackages,
+    RedditSubscribers,
+    RedditPosts30d,
+    MaintainerLoad,
+}
+
+impl Metric {
+    pub const ALL: &'static [Metric] = &[
+        Self::OverallScore,
+        Self::DevelopmentScore,
+        Self::CommunityScore,
+        Self::MaintenanceScore,
+        Self::TotalStars,
+        Self::TotalForks,
+        Self::TotalContributors,
+        Self::Commits30d,
+        Self::Commits365d,
+        Self::OpenIssues,
+        Self::OpenPrs,
+        Self::Releases30d,
+        Self::DaysSinceRelease,
+        Self::TotalPackages,
+        Self::OutdatedPackages,
+        Self::RedditSubscribers,
+        Self::RedditPosts30d,
+        Self::MaintainerLoad,
+    ];
+
+    /// Parse a metric by its field name in health summaries
+    pub fn parse(name: &str) -> Option<Self> {
+        let name = match name {
+            "contributors" => "total_contributors",
+            other => other,
+        };
+        Self::ALL.iter().copied().find(|m| m.as_str() == name)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::OverallScore => "overall_score",
+            Self::DevelopmentScore => "development_score",
+            Self::CommunityScore => "community_score",
+            Self::MaintenanceScore => "maintenance_score",
+            Self::TotalStars => "total_stars",
+            Self::TotalForks => "total_forks",
+            Self::TotalContributors => "total_contributors",
+            Self::Commits30d => "commits_30d",
+            Self::Commits365d => "commits_365d",
+            Self::OpenIssues => "open_issues",
+            Self::OpenPrs => "open_prs",
+            Self::Releases30d => "releases_30d",
+            Self::DaysSinceRelease => "days_since_release",
+            Self::TotalPackages => "total_packages",
+            Self::OutdatedPackages => "outdated_packages",
+            Self::RedditSubscribers => "reddit_subscribers",
+            Self::RedditPosts30d => "reddit_posts_30d",
+            Self::MaintainerLoad => "maintainer_load",
+        }
+    }
+
+    /// Score components live on a fixed 0-100 scale
+    pub fn is_score(&self) -> bool {
+        matches!(
+            self,
+            Self::OverallScore | Self::DevelopmentScore | Self::CommunityScore | Self::MaintenanceScore
+        )
+    }
+
+    /// Whether a smaller value ranks better, e.g. fewer days since the last release
+    pub fn lower_is_better(&self) -> bool {
+        matches!(self, Self::DaysSinceRelease | Self::OutdatedPackages | Self::MaintainerLoad)
+    }
+
+    /// The distro's value, or None when it has nothing to measure (no releases yet, no load
+    /// derived yet)
+    pub fn value(&self, score: &HealthScore, raw: &RawMetrics) -> Option<f64> {
+        let value = match self {
+            Self::OverallScore => score.overall_score,
+            Self::DevelopmentScore => score.development_score,
+            Self::CommunityScore => score.community_score,
+            Self::MaintenanceScore => score.maintenance_score,
+            Self::TotalStars => raw.total_stars as f64,
+            Self::TotalForks => raw.total_forks as f64,
+            Self::TotalContributors => raw.total_contributors as f64,
+            Self::Commits30d => raw.commits_30d as f64,
+            Self::Commits365d => raw.commits_365d as f64,
+            Self::OpenIssues => raw.open_issues as f64,
+            Self::OpenPrs => raw.open_prs as f64,
+            Self::Releases30d => raw.releases_30d as f64,
+            Self::DaysSinceRelease => raw.days_since_release? as f64,
+            Self::TotalPackages => raw.total_packages as f64,
+            Self::OutdatedPackages => raw.outdated_packages as f64,
+            Self::RedditSubscribers => raw.reddit_subscribers as f64,
+            Self::RedditPosts30d => raw.reddit_posts_30d as f64,
+            Self::MaintainerLoad => raw.maintainer_load?,
+        };
+        Some(value)
+    }
+}
+
+/// A scored distro with its current raw metrics
+#[derive(Debug, Clone)]
+pub struct DistroSample {
+    pub distro: Distribution,
+    pub score: HealthScore,
+    pub metrics: RawMetrics,
+}
+
+/// Load the latest score and raw metrics of every scored distro
+pub async fn load_samples(db: &Database) -> Result<Vec<DistroSample>> {
+    let distros = db.get_distributions().await?;
+    let mut samples = Vec::new();
+
+    for score in db.get_all_latest_health_scores().await? {
+        let Some(distro) = distros.iter().find(|d| d.id == score.distro_id) else {
+            continue;
+        };
+        let metrics = RawMetrics::load(db, distro).await?;
+        samples.push(DistroSample {
+            distro: distro.clone(),
+            score,
+            metrics,
+        });
+    }
+
+    Ok(samples)
+}
+
+impl RawMetrics {
+    /// Current raw metrics for a distro under its aggregation strategy
+    ///
+    /// A variant's development and maintenance metrics are its parent's; its community,
+    /// release and popularity metrics are its own.
+    pub async fn load(db: &Database, distro: &Distribution) -> Result<Self> {
+        let upstream_id = variant::upstream_id(db, distro).await?;
+        let snapshots = db.get_latest_github_snapshots(upstream_id).await?;
+        let releases = db.get_latest_release_snapshots(distro.id).await?;
+        let community = db.get_latest_community_snapshots(distro.id).await?;
+        let packages = db.get_latest_package_snapshot(upstream_id).await?;
+        let metrics = variant::latest_metrics(db, distro.id, upstream_id).await?;
+        let issue_activity = db.get_issue_activity(upstream_id).await?;
+        let infra = db.get_latest_infra_snapshot(upstream_id).await?;
+        let archive = db.get_latest_archive_snapshot(upstream_id).await?;
+        let bugtracker = db.get_latest_bugtracker_snapshot(upstream_id).await?;
+        let popularity = db.get_latest_popularity_snapshot(distro.id).await?;
+        let aggregation = Aggregation::from_config(distro.aggregation.as_deref());
+
+        Ok(Self::from_github_snapshots_with(&snapshots, aggregation)
+            .with_releases(&releases)
+            .with_community(&community)
+            .with_packages(packages.as_ref())
+            .with_issue_activity(issue_activity)
+            .with_infra(infra.as_ref())
+            .with_archive(archive.as_ref())
+            .with_bugtracker(bugtracker.as_ref())
+            .with_popularity(popularity.as_ref())
+            .with_metrics(&metrics))
+    }
+}
+
+/// Value at quantile `q` (0-1) of sorted values, interpolating between neighbours
+pub fn quantile(sorted: &[f64], q: f64) -> f64 {
+    match sorted.len() {
+        0 => 0.0,
+        1 => sorted[0],
+        n => {
+            let pos = q.clamp(0.0, 1.0) * (n - 1) as f64;
+            let lower = pos.floor() as usize;
+            let upper = pos.ceil() as usize;
+            sorted[lower] + (sorted[upper] - sorted[lower]) * (pos - lower as f64)
+        }
+    }
+}
+
+/// Percentage of values below `value`, counting ties as half
+pub fn percentile_rank(values: &[f64], value: f64) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    let below = values.iter().filter(|v| **v < value).count() as f64;
+    let equal = values.iter().filter(|v| **v == value).count() as f64;
+    ((below + equal / 2.0) / values.len() as f64 * 1000.0).round() / 10.0
+}
+
+/// One histogram bucket, covering `lower` (inclusive) to `upper` (exclusive, except the last)
+#[derive(Debug, Clone, Serialize)]
+pub struct HistogramBucket {
+    pub lower: f64,
+    pub upper: f64,
+    pub count: usize,
+}
+
+/// Where one distro sits in the field
+#[derive(Debug, Clone, Serialize)]
+pub struct DistroPosition {
+    pub slug: String,
+    pub value: f64,
+    pub percentile: f64,
+}
+
+/// Histogram and summary statistics for one metric across all scored distros
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricDistribution {
+    pub metric: String,
+    pub count: usize,
+    pub mean: f64,
+    pub median: f64,
+    pub p90: f64,
+    pub min: f64,
+    pub max: f64,
+    pub buckets: Vec<HistogramBucket>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub distro: Option<DistroPosition>,
+}
+
+impl MetricDistribution {
+    /// Bucket the metric into `buckets` equal-width bins
+    ///
+    /// Scores use the fixed 0-100 range; raw metrics span 0 to the field maximum.
+    pub fn build(
+        samples: &[DistroSample],
+        metric: Metric,
+        buckets: usize,
+        distro: Option<&str>,
+    ) -> Self {
+        let mut values: Vec<f64> = samples
+            .iter()
+            .filter_map(|s| metric.value(&s.score, &s.metrics))
+            .collect();
+        values.sort_by(f64::total_cmp);
+
+        let count = values.len();
+        let mean = if count > 0 {
+            values.iter().sum::<f64>() / count as f64
+        } else {
+            0.0
+        };
+        let min = values.first().copied().unwrap_or(0.0);
+        let max = values.last().copied().unwrap_or(0.0);
+
+        let buckets = buckets.max(1);
+        let upper_bound = if metric.is_score() { 100.0 } else { max.max(1.0) };
+        let width = upper_bound / buckets as f64;
+
+        let mut histogram: Vec<HistogramBucket> = (0..buckets)
+            .map(|i| HistogramBucket {
+                lower: round2(i as f64 * width),
+                upper: round2((i + 1) as f64 * width),
+                count: 0,
+            })
+            .collect();
+        for value in &values {
+            let index = ((value / width).floor().max(0.0) as usize).min(buckets - 1);
+            histogram[index].count += 1;
+        }
+
+        let distro = distro.and_then(|slug| {
+            let sample = samples.iter().find(|s| s.distro.slug == slug)?;
+            let value = metric.value(&sample.score, &sample.metrics)?;
+            Some(DistroPosition {
+                slug: slug.to_string(),
+                value,
+                percentile: percentile_rank(&values, value),
+            })
+        });
+
+        Self {
+            metric: metric.as_str().to_string(),
+            count,
+            mean: round2(mean),
+            median: round2(quantile(&values, 0.5)),
+            p90: round2(quantile(&values, 0.9)),
+            min,
+            max,
+            buckets: histogram,
+            distro,
+        }
+    }
+}
+
+/// A distro's place on a single-metric leaderboard
+#[derive(Debug, Clone, Serialize)]
+pub struct LeaderboardEntry {
+    /// 1 for the best value; tied values share a rank
+    pub rank: usize,
+    pub slug: String,
+    pub name: String,
+    pub value: f64,
+}
+
+/// The best and worst distros by one metric
+#[derive(Debug, Clone, Serialize)]
+pub struct Leaderboard {
+    pub metric: String,
+    pub lower_is_better: bool,
+    /// Distros with a value for the metric
+    pub count: usize,
+    /// Best first
+    pub top: Vec<LeaderboardEntry>,
+    /// Worst first
+    pub bottom: Vec<LeaderboardEntry>,
+}
+
+impl Leaderboard {
+    /// Rank every distro with a value and keep `limit` from each end
+    pub fn build(samples: &[DistroSample], metric: Metric, limit: usize) -> Self {
+        let mut ranked: Vec<(&DistroSample, f64)> = samples
+            .iter()
+            .filter_map(|s| Some((s, metric.value(&s.score, &s.metrics)?)))
+            .collect();
+        ranked.sort_by(|(a, x), (b, y)| {
+            let order = if metric.lower_is_better() { x.total_cmp(y) } else { y.total_cmp(x) };
+            order.then_with(|| a.distro.name.cmp(&b.distro.name))
+        });
+
+        let mut entries = Vec::with_capacity(ranked.len());
+        for (i, (sample, value)) in ranked.iter().enumerate() {
+            let rank = match entries.last() {
+                Some(LeaderboardEntry { rank, value: previous, .. }) if previous == value => *rank,
+                _ => i + 1,
+            };
+            entries.push(LeaderboardEntry {
+                rank,
+                slug: sample.distro.slug.clone(),
+                name: sample.distro.name.clone(),
+                value: *value,
+            });
+        }
+
+        Self {
+            metric: metric.as_str().to_string(),
+            lower_is_better: metric.lower_is_better(),
+            count: entries.len(),
+            top: entries.iter().take(limit).cloned().collect(),
+            bottom: entries.iter().rev().take(limit).cloned().collect(),
+        }
+    }
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
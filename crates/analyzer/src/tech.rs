@@ -0,0 +1,75 @@
+//! Language and topic breakdown of what a distribution builds in-house
+
+use crate::Result;
+use distrovitals_database::Database;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Languages and topics aggregated over a distribution's own (non-mirror) repos
+#[derive(Debug, Clone, Serialize)]
+pub struct TechBreakdown {
+    pub repos_tracked: i64,
+    pub languages: Vec<LanguageShare>,
+    pub topics: Vec<TopicCount>,
+}
+
+/// A language's share of the distro's code
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageShare {
+    pub language: String,
+    pub bytes: i64,
+    /// Percentage of all bytes, 0-100
+    pub share: f64,
+    pub repos: i64,
+}
+
+/// How many repos carry a topic
+#[derive(Debug, Clone, Serialize)]
+pub struct TopicCount {
+    pub topic: String,
+    pub repos: i64,
+}
+
+impl TechBreakdown {
+    /// Build the breakdown from the latest snapshot of each repo
+    pub async fn build(db: &Database, distro_id: i64) -> Result<Self> {
+        let snapshots = db.get_latest_github_snapshots(distro_id).await?;
+        let totals = db.get_distro_languages(distro_id).await?;
+
+        let all_bytes: i64 = totals.iter().map(|l| l.bytes).sum();
+        let languages = totals
+            .into_iter()
+            .map(|l| LanguageShare {
+                share: if all_bytes > 0 {
+                    (l.bytes as f64 / all_bytes as f64 * 1000.0).round() / 10.0
+                } else {
+                    0.0
+                },
+                language: l.language,
+                bytes: l.bytes,
+                repos: l.repos,
+            })
+            .collect();
+
+        let mut topic_counts: BTreeMap<String, i64> = BTreeMap::new();
+        for topic in snapshots
+            .iter()
+            .filter_map(|s| s.topics.as_deref())
+            .flat_map(|t| t.split(','))
+        {
+            *topic_counts.entry(topic.to_string()).or_default() += 1;
+        }
+
+        let mut topics: Vec<TopicCount> = topic_counts
+            .into_iter()
+            .map(|(topic, repos)| TopicCount { topic, repos })
+            .collect();
+        topics.sort_by_key(|t| std::cmp::Reverse(t.repos));
+
+        Ok(Self {
+            repos_tracked: snapshots.len() as i64,
+            languages,
+            topics,
+        })
+    }
+}
@@ -0,0 +1,74 @@
+//! Score trend from recent history
+//!
+//! Comparing a new score with the previous one alone lets a single bad collection flip the
+//! public arrow and flip it back a day later. The trend is instead the Theil-Sen slope over
+//! the last few runs: the median of the slopes between every pair of scores, which a
+//! minority of outliers can't drag past the threshold.
+
+use crate::methodology::{TREND_THRESHOLD, TREND_WINDOW};
+use crate::stats::quantile;
+use tracing::warn;
+
+/// Scores the trend is fitted over, including the new one: `DV_TREND_WINDOW` or
+/// [`TREND_WINDOW`]
+pub fn trend_window() -> usize {
+    let Ok(value) = std::env::var("DV_TREND_WINDOW") else {
+        return TREND_WINDOW;
+    };
+    match value.trim().parse::<usize>() {
+        Ok(window) if window >= 2 => window,
+        _ => {
+            warn!("Ignoring DV_TREND_WINDOW={:?}: expected a number of scores, at least 2", value);
+            TREND_WINDOW
+        }
+    }
+}
+
+/// Median slope between every pair of values, per step; None with fewer than two
+pub fn theil_sen_slope(values: &[f64]) -> Option<f64> {
+    let mut slopes = Vec::with_capacity(values.len() * values.len().saturating_sub(1) / 2);
+    for (i, a) in values.iter().enumerate() {
+        for (j, b) in values.iter().enumerate().skip(i + 1) {
+            slopes.push((b - a) / (j - i) as f64);
+        }
+    }
+    if slopes.is_empty() {
+        return None;
+    }
+
+    slopes.sort_by(f64::total_cmp);
+    Some(quantile(&slopes, 0.5))
+}
+
+/// "up", "down" or "stable" for overall scores, oldest first and ending with the new one
+pub fn determine_trend(scores: &[f64]) -> &'static str {
+    match theil_sen_slope(scores) {
+        Some(slope) if slope > TREND_THRESHOLD => "up",
+        Some(slope) if slope < -TREND_THRESHOLD => "down",
+        _ => "stable",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_outlier_does_not_flip_the_trend() {
+        assert_eq!(determine_trend(&[70.0, 70.5, 71.0, 70.5, 58.0]), "stable");
+        assert_eq!(determine_trend(&[70.0, 70.5, 40.0, 71.0, 70.5]), "stable");
+    }
+
+    #[test]
+    fn sustained_moves_are_trends() {
+        assert_eq!(determine_trend(&[60.0, 63.0, 66.0, 69.0, 72.0]), "up");
+        assert_eq!(determine_trend(&[72.0, 69.0, 66.0, 63.0, 60.0]), "down");
+    }
+
+    #[test]
+    fn two_scores_compare_directly() {
+        assert_eq!(determine_trend(&[70.0, 73.0]), "up");
+        assert_eq!(determine_trend(&[70.0, 71.0]), "stable");
+        assert_eq!(determine_trend(&[70.0]), "stable");
+    }
+}
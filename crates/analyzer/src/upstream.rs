@@ -0,0 +1,86 @@
+//! Upstream project risk assessment
+//!
+//! Distributions inherit risk from the desktop environments they ship. A stalled
+//! upstream is flagged on the distro's health payload rather than folded into its score.
+
+use crate::Result;
+use chrono::Utc;
+use distrovitals_database::{Database, UpstreamSnapshot};
+use serde::Serialize;
+
+/// Risk summary for all upstreams a distribution depends on
+#[derive(Debug, Clone, Serialize)]
+pub struct UpstreamRisk {
+    /// Worst level across all upstreams: "low", "medium", "high"
+    pub level: String,
+    pub upstreams: Vec<UpstreamStatus>,
+}
+
+/// Activity status of a single upstream project
+#[derive(Debug, Clone, Serialize)]
+pub struct UpstreamStatus {
+    pub slug: String,
+    pub name: String,
+    pub level: String,
+    pub commits_30d: i64,
+    pub days_since_commit: Option<i64>,
+}
+
+impl UpstreamRisk {
+    /// Assess upstream risk for a distribution, `None` when no upstreams have data
+    pub async fn assess(db: &Database, distro_id: i64) -> Result<Option<Self>> {
+        let upstreams = db.get_distro_upstreams(distro_id).await?;
+        let mut statuses = Vec::new();
+
+        for upstream in upstreams {
+            let snapshots = db.get_latest_upstream_snapshots(upstream.id).await?;
+            if snapshots.is_empty() {
+                continue;
+            }
+
+            let commits_30d: i64 = snapshots.iter().map(|s| s.commits_30d).sum();
+            let days_since_commit = Self::days_since_commit(&snapshots);
+
+            statuses.push(UpstreamStatus {
+                slug: upstream.slug,
+                name: upstream.name,
+                level: Self::level(commits_30d, days_since_commit).to_string(),
+                commits_30d,
+                days_since_commit,
+            });
+        }
+
+        if statuses.is_empty() {
+            return Ok(None);
+        }
+
+        let level = ["high", "medium", "low"]
+            .into_iter()
+            .find(|l| statuses.iter().any(|s| s.level == *l))
+            .unwrap_or("low")
+            .to_string();
+
+        Ok(Some(Self {
+            level,
+            upstreams: statuses,
+        }))
+    }
+
+    fn days_since_commit(snapshots: &[UpstreamSnapshot]) -> Option<i64> {
+        snapshots
+            .iter()
+            .filter_map(|s| s.last_commit_at)
+            .max()
+            .map(|last| (Utc::now() - last).num_days())
+    }
+
+    fn level(commits_30d: i64, days_since_commit: Option<i64>) -> &'static str {
+        match (commits_30d, days_since_commit) {
+            (_, Some(days)) if days > 180 => "high",
+            (0, _) => "high",
+            (_, Some(days)) if days > 30 => "medium",
+            (1..=20, _) => "medium",
+            _ => "low",
+        }
+    }
+}
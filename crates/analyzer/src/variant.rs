@@ -0,0 +1,43 @@
+//! Variants: editions of a distro built from the same code and packages
+//!
+//! Fedora Silverblue and Kinoite are Fedora, and openSUSE MicroOS is Tumbleweed, delivered
+//! differently. Collecting their development data again would only duplicate the parent's,
+//! so a variant (`variant_of` set to its parent's slug) is scored on its parent's
+//! repositories, packages, issues, mirrors and archive. Community and release data stay its
+//! own: it has its own users, forums and releases.
+//!
+//! Unlike `based_on`, which only groups derivatives into families, `variant_of` changes where
+//! a distro's data is read from. Variants of variants aren't followed.
+
+use crate::Result;
+use distrovitals_database::{Database, DatabaseError, Distribution, MetricValue};
+use tracing::warn;
+
+/// Id of the distro whose development and maintenance data `distro` is scored on: its
+/// parent if it's a variant, otherwise itself
+///
+/// A variant whose parent isn't tracked (or was archived) falls back to its own data.
+pub async fn upstream_id(db: &Database, distro: &Distribution) -> Result<i64> {
+    let Some(parent) = distro.variant_of.as_deref() else {
+        return Ok(distro.id);
+    };
+    match db.get_distribution_by_slug(parent).await {
+        Ok(parent) => Ok(parent.id),
+        Err(DatabaseError::NotFound(_)) => {
+            warn!(distro = distro.slug, parent = parent, "Variant's parent isn't tracked, using its own data");
+            Ok(distro.id)
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Latest metrics of a distro, with the upstream ones taken from `upstream_id` instead
+pub(crate) async fn latest_metrics(db: &Database, distro_id: i64, upstream_id: i64) -> Result<Vec<MetricValue>> {
+    let mut metrics = db.get_latest_metrics(distro_id).await?;
+    if upstream_id != distro_id {
+        metrics.retain(|m| !m.key.is_upstream());
+        let inherited = db.get_latest_metrics(upstream_id).await?;
+        metrics.extend(inherited.into_iter().filter(|m| m.key.is_upstream()));
+    }
+    Ok(metrics)
+}
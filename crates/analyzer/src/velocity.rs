@@ -0,0 +1,213 @@
+//! Star and subscriber growth between snapshot batches
+//!
+//! Absolute star and subscriber counts mostly measure age: a distro that launched last
+//! year can't have the following of one from 2002. Growth over recent weeks is what the
+//! community score uses instead, once there is enough history to compare against.
+
+use crate::methodology::{STAR_GROWTH, STAR_GROWTH_PCT, SUBSCRIBER_GROWTH};
+use crate::Result;
+use chrono::{DateTime, Utc};
+use distrovitals_database::{CommunitySnapshot, Database, GithubSnapshot, MetricKey};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Days of history star growth is measured over
+pub const STAR_GROWTH_DAYS: i64 = 30;
+
+/// Windows subscriber growth is measured over, shortest first
+pub const SUBSCRIBER_GROWTH_DAYS: [i64; 2] = [30, 90];
+
+/// Days commit activity is compared over
+pub(crate) const COMMIT_TREND_DAYS: i64 = 30;
+
+/// Growth of a count between two snapshot batches, scaled to the window
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Growth {
+    pub delta: i64,
+    pub baseline: i64,
+    pub days: i64,
+}
+
+impl Growth {
+    /// Sum growth over (now, before) pairs at least `window` days apart
+    ///
+    /// A longer gap is scaled down to the window. None when no pair is old enough.
+    pub fn measure(pairs: impl IntoIterator<Item = (Sample, Sample)>, window: i64) -> Option<Self> {
+        let mut delta = 0;
+        let mut baseline = 0;
+        let mut days = 0;
+        for (now, before) in pairs {
+            let elapsed = (now.at - before.at).num_days();
+            if elapsed < window {
+                continue;
+            }
+            delta += now.value - before.value;
+            baseline += before.value;
+            days = days.max(elapsed);
+        }
+
+        (days > 0).then(|| Self {
+            delta: (delta as f64 * window as f64 / days as f64).round() as i64,
+            baseline,
+            days,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Sample {
+    pub value: i64,
+    pub at: DateTime<Utc>,
+}
+
+/// Snapshots as of `days` before the newest of `current`, to measure growth against
+pub(crate) async fn github_baseline(
+    db: &Database,
+    distro_id: i64,
+    current: &[GithubSnapshot],
+    days: i64,
+) -> Result<Vec<GithubSnapshot>> {
+    // However old the latest batch is, compare against the one a window before it
+    match current.iter().map(|s| s.collected_at).max() {
+        Some(latest) => Ok(db
+            .get_github_snapshots_as_of(distro_id, latest - chrono::TimeDelta::days(days))
+            .await?),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Change in 30-day commit counts of repos tracked in both batches
+pub(crate) fn commit_trend(current: &[GithubSnapshot], baseline: &[GithubSnapshot]) -> Option<Growth> {
+    let before: HashMap<&str, &GithubSnapshot> = baseline.iter().map(|s| (s.repo_name.as_str(), s)).collect();
+    let pairs = current.iter().filter_map(|now| {
+        let earlier = before.get(now.repo_name.as_str())?;
+        Some((
+            Sample { value: now.commits_30d, at: now.collected_at },
+            Sample { value: earlier.commits_30d, at: earlier.collected_at },
+        ))
+    });
+
+    Growth::measure(pairs, COMMIT_TREND_DAYS)
+}
+
+/// Star growth of a distro's repos, scaled to 30 days
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct StarVelocity {
+    /// Stars gained per 30 days (negative when lost) by repos tracked in both batches
+    pub delta: i64,
+    /// Stars of those repos at the start of the window
+    pub baseline: i64,
+    /// Days between the baseline batch and the current one
+    pub days: i64,
+}
+
+impl StarVelocity {
+    /// Compare current snapshots to a batch at least 30 days older
+    ///
+    /// Only repos present in both count, so newly tracked repos don't show up as growth.
+    /// None when there's no baseline to compare against.
+    pub fn between(current: &[GithubSnapshot], baseline: &[GithubSnapshot]) -> Option<Self> {
+        let before: HashMap<&str, &GithubSnapshot> = baseline.iter().map(|s| (s.repo_name.as_str(), s)).collect();
+        let pairs = current.iter().filter_map(|now| {
+            let earlier = before.get(now.repo_name.as_str())?;
+            Some((
+                Sample { value: now.stars, at: now.collected_at },
+                Sample { value: earlier.stars, at: earlier.collected_at },
+            ))
+        });
+
+        Growth::measure(pairs, STAR_GROWTH_DAYS).map(|g| Self {
+            delta: g.delta,
+            baseline: g.baseline,
+            days: g.days,
+        })
+    }
+
+    /// Load the baseline batch and compare the current snapshots to it
+    pub async fn load(db: &Database, distro_id: i64, current: &[GithubSnapshot]) -> Result<Option<Self>> {
+        let baseline = github_baseline(db, distro_id, current, STAR_GROWTH_DAYS).await?;
+        Ok(Self::between(current, &baseline))
+    }
+
+    /// Growth relative to the baseline, in percent
+    pub fn growth_pct(&self) -> f64 {
+        self.delta as f64 / self.baseline.max(1) as f64 * 100.0
+    }
+
+    /// Score growth 0-100, half on stars gained and half on relative growth
+    pub fn score(&self) -> f64 {
+        STAR_GROWTH.bands.score(self.delta as f64) * STAR_GROWTH.weight
+            + STAR_GROWTH_PCT.bands.score(self.growth_pct()) * STAR_GROWTH_PCT.weight
+    }
+}
+
+/// Subscriber growth of a distro's subreddits over one window
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SubscriberVelocity {
+    /// Window length in days (30 or 90)
+    pub window_days: i64,
+    /// Subscribers gained over the window (negative when lost)
+    pub delta: i64,
+    /// Subscribers at the start of the window
+    pub baseline: i64,
+}
+
+impl SubscriberVelocity {
+    /// Compare current Reddit snapshots to a batch at least `window_days` older
+    ///
+    /// Only subreddits present in both count. None when there's no baseline.
+    pub fn between(current: &[CommunitySnapshot], baseline: &[CommunitySnapshot], window_days: i64) -> Option<Self> {
+        let reddit = |s: &&CommunitySnapshot| s.source.starts_with("reddit:");
+        let before: HashMap<&str, &CommunitySnapshot> =
+            baseline.iter().filter(reddit).map(|s| (s.source.as_str(), s)).collect();
+        let pairs = current.iter().filter(reddit).filter_map(|now| {
+            let earlier = before.get(now.source.as_str())?;
+            Some((
+                Sample { value: now.active_users_30d?, at: now.collected_at },
+                Sample { value: earlier.active_users_30d?, at: earlier.collected_at },
+            ))
+        });
+
+        Growth::measure(pairs, window_days).map(|g| Self {
+            window_days,
+            delta: g.delta,
+            baseline: g.baseline,
+        })
+    }
+
+    /// Growth over each of [`SUBSCRIBER_GROWTH_DAYS`] that has enough history, shortest first
+    pub async fn load(db: &Database, distro_id: i64, current: &[CommunitySnapshot]) -> Result<Vec<Self>> {
+        let Some(latest) = current.iter().map(|s| s.collected_at).max() else {
+            return Ok(Vec::new());
+        };
+
+        let mut velocities = Vec::new();
+        for window in SUBSCRIBER_GROWTH_DAYS {
+            let baseline = db
+                .get_community_snapshots_as_of(distro_id, latest - chrono::TimeDelta::days(window))
+                .await?;
+            velocities.extend(Self::between(current, &baseline, window));
+        }
+        Ok(velocities)
+    }
+
+    /// Key the window's growth is persisted under
+    pub fn metric_key(&self) -> MetricKey {
+        if self.window_days >= 90 {
+            MetricKey::SubscriberGrowth90d
+        } else {
+            MetricKey::SubscriberGrowth30d
+        }
+    }
+
+    /// Growth per 30 days relative to the baseline, in percent
+    pub fn monthly_growth_pct(&self) -> f64 {
+        let pct = self.delta as f64 / self.baseline.max(1) as f64 * 100.0;
+        pct * 30.0 / self.window_days as f64
+    }
+
+    /// Score the monthly growth rate 0-100
+    pub fn score(&self) -> f64 {
+        SUBSCRIBER_GROWTH.bands.score(self.monthly_growth_pct())
+    }
+}
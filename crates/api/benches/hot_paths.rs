@@ -0,0 +1,183 @@
+//! Timings for the paths that dominate load: the rankings endpoint, metric aggregation
+//! and snapshot inserts
+//!
+//! Run with `cargo bench -p distrovitals-api`, optionally followed by a name filter. By
+//! default it generates a modest synthetic database in a temporary file; point
+//! `DV_BENCH_DB` at a database filled by `dv bench seed` to measure at full size.
+
+use axum::body::Body;
+use axum::http::Request;
+use distrovitals_analyzer::{fixtures, Aggregation, RawMetrics};
+use distrovitals_api::{create_router, AppState};
+use distrovitals_database::{Database, NewGithubSnapshot, Provenance, SyntheticHistory};
+use std::future::Future;
+use std::hint::black_box;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinSet;
+use tower::ServiceExt;
+
+/// Size of the generated database when `DV_BENCH_DB` isn't set
+const DISTROS: usize = 100;
+const DAYS: i64 = 365;
+
+/// Snapshots inserted per batch-insert iteration
+const INSERTS: usize = 500;
+
+struct Bencher {
+    filter: Option<String>,
+}
+
+impl Bencher {
+    fn enabled(&self, name: &str) -> bool {
+        self.filter.as_deref().is_none_or(|filter| name.contains(filter))
+    }
+
+    /// Time `iterations` runs of `f` after one warm-up run
+    async fn run<F, Fut>(&self, name: &str, iterations: usize, mut f: F)
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = ()>,
+    {
+        if !self.enabled(name) {
+            return;
+        }
+
+        f().await;
+        let mut times = Vec::with_capacity(iterations);
+        for _ in 0..iterations {
+            let started = Instant::now();
+            f().await;
+            times.push(started.elapsed());
+        }
+        report(name, &mut times);
+    }
+}
+
+fn report(name: &str, times: &mut [Duration]) {
+    times.sort();
+    let total: Duration = times.iter().sum();
+    println!(
+        "{:<36} {:>6} iters   min {:>10.3?}   median {:>10.3?}   mean {:>10.3?}",
+        name,
+        times.len(),
+        times[0],
+        times[times.len() / 2],
+        total / times.len() as u32
+    );
+}
+
+async fn open_database() -> (Database, Option<PathBuf>) {
+    if let Some(path) = std::env::var_os("DV_BENCH_DB").filter(|p| !p.is_empty()) {
+        let db = Database::connect(&PathBuf::from(path)).await.expect("open DV_BENCH_DB");
+        return (db, None);
+    }
+
+    let path = std::env::temp_dir().join(format!("dv-bench-{}.db", std::process::id()));
+    let db = Database::connect(&path).await.expect("create bench database");
+    let distros = db
+        .create_synthetic_distributions("bench", DISTROS)
+        .await
+        .expect("create distros");
+    let history = SyntheticHistory {
+        days: DAYS,
+        ..SyntheticHistory::default()
+    };
+    db.generate_history(&distros, &history).await.expect("generate history");
+    (db, Some(path))
+}
+
+fn snapshot(distro_id: i64, n: usize) -> NewGithubSnapshot {
+    NewGithubSnapshot {
+        distro_id,
+        repo_name: format!("bench/insert-{}", n),
+        stars: 1_000,
+        forks: 100,
+        open_issues: 40,
+        open_prs: 8,
+        commits_30d: 120,
+        commits_365d: 1_400,
+        contributors_30d: 12,
+        bot_commits_30d: 10,
+        bot_commits_365d: 90,
+        is_mirror: false,
+        topics: vec!["linux".to_string()],
+        languages: vec![("C".to_string(), 100_000), ("Shell".to_string(), 20_000)],
+        contributors: vec![("alice".to_string(), 70), ("bob".to_string(), 50)],
+        issue_labels: Vec::new(),
+        issue_activity: None,
+        last_commit_at: Some(chrono::Utc::now()),
+        provenance: Provenance::new("bench"),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    // cargo passes --bench; anything else is a name filter
+    let bencher = Bencher {
+        filter: std::env::args().skip(1).find(|arg| !arg.starts_with('-')),
+    };
+
+    let (db, temporary) = open_database().await;
+    let distros = db.get_distributions().await.expect("load distros");
+    println!("{} distros\n", distros.len());
+
+    let router = create_router(Arc::new(AppState::new(db.clone())), None);
+    for (name, uri) in [
+        ("rankings", "/api/v1/rankings"),
+        ("rankings/os_family", "/api/v1/rankings?os_family=bsd,haiku"),
+        ("rankings/momentum", "/api/v1/rankings?sort=momentum"),
+    ] {
+        bencher
+            .run(name, 10, || async {
+                let request = Request::get(uri).body(Body::empty()).unwrap();
+                let response = router.clone().oneshot(request).await.unwrap();
+                assert!(response.status().is_success(), "{} returned {}", uri, response.status());
+                black_box(axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap());
+            })
+            .await;
+    }
+
+    let github = fixtures::big_distro(chrono::Utc::now()).github;
+    let many: Vec<_> = github.iter().cycle().take(300).cloned().collect();
+    for (name, snapshots, aggregation) in [
+        ("raw_metrics/3_repos", &github, Aggregation::Sum),
+        ("raw_metrics/300_repos/sum", &many, Aggregation::Sum),
+        ("raw_metrics/300_repos/top_k", &many, Aggregation::TopK(10)),
+    ] {
+        bencher
+            .run(name, 10_000, || async {
+                black_box(RawMetrics::from_github_snapshots_with(black_box(snapshots), aggregation));
+            })
+            .await;
+    }
+
+    let distro_id = distros[0].id;
+    bencher
+        .run("inserts/sequential", 5, || async {
+            for n in 0..INSERTS {
+                db.insert_github_snapshot(snapshot(distro_id, n)).await.unwrap();
+            }
+        })
+        .await;
+    bencher
+        .run("inserts/concurrent", 5, || async {
+            let mut tasks = JoinSet::new();
+            for n in 0..INSERTS {
+                let db = db.clone();
+                tasks.spawn(async move { db.insert_github_snapshot(snapshot(distro_id, n)).await });
+            }
+            while let Some(result) = tasks.join_next().await {
+                result.unwrap().unwrap();
+            }
+        })
+        .await;
+
+    db.close().await;
+    if let Some(path) = temporary {
+        for suffix in ["", "-wal", "-shm"] {
+            let _ = std::fs::remove_file(format!("{}{}", path.display(), suffix));
+        }
+    }
+}
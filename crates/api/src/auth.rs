@@ -0,0 +1,80 @@
+//! Admin and maintainer authentication for write endpoints
+
+use crate::handlers::ApiResponse;
+use crate::AppState;
+use axum::{
+    http::{header::AUTHORIZATION, HeaderMap, StatusCode},
+    Json,
+};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+
+type Rejection = (StatusCode, Json<ApiResponse<()>>);
+
+/// Require a valid `Authorization: Bearer <token>` admin header
+///
+/// Admin endpoints are disabled entirely when no token is configured.
+pub fn require_admin(state: &AppState, headers: &HeaderMap) -> Result<(), Rejection> {
+    let Some(ref expected) = state.admin_token else {
+        return Err(ApiResponse::error(
+            StatusCode::FORBIDDEN,
+            "Admin API is disabled (DV_ADMIN_TOKEN not set)",
+        ));
+    };
+
+    match bearer_token(headers) {
+        Some(token) if constant_time_eq(token.as_bytes(), expected.as_bytes()) => Ok(()),
+        _ => Err(ApiResponse::error(
+            StatusCode::UNAUTHORIZED,
+            "Invalid or missing admin token",
+        )),
+    }
+}
+
+/// Require the admin token or an active maintainer key scoped to `distro_id`
+pub async fn require_maintainer(
+    state: &AppState,
+    headers: &HeaderMap,
+    distro_id: i64,
+) -> Result<(), Rejection> {
+    if require_admin(state, headers).is_ok() {
+        return Ok(());
+    }
+
+    let Some(token) = bearer_token(headers) else {
+        return Err(ApiResponse::error(StatusCode::UNAUTHORIZED, "Missing API key"));
+    };
+
+    match state.db.get_active_maintainer(&hash_key(token)).await {
+        Ok(Some(maintainer)) if maintainer.distro_id == distro_id => Ok(()),
+        Ok(Some(_)) => Err(ApiResponse::error(
+            StatusCode::FORBIDDEN,
+            "API key is not scoped to this distribution",
+        )),
+        Ok(None) => Err(ApiResponse::error(StatusCode::UNAUTHORIZED, "Invalid API key")),
+        Err(e) => Err(ApiResponse::err(e.to_string())),
+    }
+}
+
+/// Generate a random token with the given prefix (e.g. "dvk_" for API keys)
+pub fn generate_token(prefix: &str) -> String {
+    let mut bytes = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("{}{}", prefix, hex::encode(bytes))
+}
+
+/// Hash an API key for storage
+pub fn hash_key(key: &str) -> String {
+    hex::encode(Sha256::digest(key.as_bytes()))
+}
+
+fn bearer_token(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,52 @@
+//! Optional captcha verification for public write endpoints
+//!
+//! Works with any provider exposing a Turnstile/hCaptcha-style `siteverify` endpoint.
+
+use serde::Deserialize;
+use tracing::warn;
+
+const DEFAULT_VERIFY_URL: &str = "https://challenges.cloudflare.com/turnstile/v0/siteverify";
+
+/// Captcha provider settings (from `DV_CAPTCHA_SECRET` / `DV_CAPTCHA_VERIFY_URL`)
+#[derive(Debug, Clone)]
+pub struct CaptchaConfig {
+    pub secret: String,
+    pub verify_url: String,
+}
+
+#[derive(Deserialize)]
+struct VerifyResponse {
+    success: bool,
+}
+
+impl CaptchaConfig {
+    /// Load captcha settings from the environment, `None` disables captcha checks
+    pub fn from_env() -> Option<Self> {
+        let secret = std::env::var("DV_CAPTCHA_SECRET").ok().filter(|s| !s.is_empty())?;
+        let verify_url = std::env::var("DV_CAPTCHA_VERIFY_URL")
+            .unwrap_or_else(|_| DEFAULT_VERIFY_URL.to_string());
+
+        Some(Self { secret, verify_url })
+    }
+
+    /// Verify a client-supplied captcha token with the provider
+    pub async fn verify(&self, token: &str) -> bool {
+        let result = reqwest::Client::new()
+            .post(&self.verify_url)
+            .form(&[("secret", self.secret.as_str()), ("response", token)])
+            .send()
+            .await;
+
+        match result {
+            Ok(response) => response
+                .json::<VerifyResponse>()
+                .await
+                .map(|r| r.success)
+                .unwrap_or(false),
+            Err(e) => {
+                warn!("Captcha verification request failed: {}", e);
+                false
+            }
+        }
+    }
+}
@@ -0,0 +1,234 @@
+//! Chart-ready series for a distro's detail page
+//!
+//! Everything a frontend would otherwise work out for itself: series binned onto a
+//! shared, gap-free set of buckets, axis ranges rounded to readable ticks, and colours
+//! for both light and dark themes. The shape isn't tied to any chart library: plain
+//! `{x, y}` points with `null` where a bucket has no data.
+
+use chrono::NaiveDate;
+use distrovitals_database::{CommitActivityBucket, HealthScoreBucket, ReleaseSnapshot, Resolution};
+use serde::Serialize;
+
+/// Series colours for each theme, from the frontend stylesheets
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Colors {
+    pub light: &'static str,
+    pub dark: &'static str,
+}
+
+const BLUE: Colors = Colors { light: "#0969da", dark: "#58a6ff" };
+const GREEN: Colors = Colors { light: "#1a7f37", dark: "#3fb950" };
+const PURPLE: Colors = Colors { light: "#8250df", dark: "#bc8cff" };
+const ORANGE: Colors = Colors { light: "#bc4c00", dark: "#f0883e" };
+const GREY: Colors = Colors { light: "#57606a", dark: "#8b949e" };
+
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Point {
+    /// First day of the bucket
+    pub x: NaiveDate,
+    pub y: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Series {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub colors: Colors,
+    pub points: Vec<Point>,
+}
+
+/// Suggested y-axis range, padded and rounded to tick boundaries
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Axis {
+    pub min: f64,
+    pub max: f64,
+    /// Distance between ticks
+    pub step: f64,
+    pub unit: &'static str,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Chart {
+    /// "line" or "bar"
+    pub kind: &'static str,
+    pub y_axis: Axis,
+    pub series: Vec<Series>,
+}
+
+/// A single release, for drawing as a marker on the timeline
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseMarker {
+    pub date: NaiveDate,
+    pub repo_name: String,
+    pub tag_name: String,
+    pub prerelease: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReleaseTimeline {
+    /// Releases per bucket
+    #[serde(flatten)]
+    pub chart: Chart,
+    pub releases: Vec<ReleaseMarker>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Charts {
+    pub resolution: &'static str,
+    pub since: NaiveDate,
+    pub until: NaiveDate,
+    pub score_history: Chart,
+    pub commit_activity: Chart,
+    pub release_timeline: ReleaseTimeline,
+}
+
+impl Charts {
+    /// Bin everything onto the buckets from `since` to `until`
+    pub fn build(
+        resolution: Resolution,
+        since: NaiveDate,
+        until: NaiveDate,
+        scores: &[HealthScoreBucket],
+        activity: &[CommitActivityBucket],
+        releases: &[ReleaseSnapshot],
+    ) -> Self {
+        let mut buckets = Vec::new();
+        let mut bucket = resolution.bucket_start(since);
+        while bucket <= until {
+            buckets.push(bucket);
+            bucket = resolution.next_bucket(bucket);
+        }
+
+        let series = |key, label, colors, value: &dyn Fn(NaiveDate) -> Option<f64>| Series {
+            key,
+            label,
+            colors,
+            points: buckets.iter().map(|&x| Point { x, y: value(x) }).collect(),
+        };
+
+        let score = |x: NaiveDate| scores.iter().find(|s| s.bucket == x);
+        let score_series = vec![
+            series("overall", "Overall", BLUE, &|x| score(x).map(|s| round(s.overall_avg))),
+            series("development", "Development", GREEN, &|x| score(x).map(|s| round(s.development_avg))),
+            series("community", "Community", PURPLE, &|x| score(x).map(|s| round(s.community_avg))),
+            series("maintenance", "Maintenance", ORANGE, &|x| score(x).map(|s| round(s.maintenance_avg))),
+        ];
+        let score_history = Chart {
+            kind: "line",
+            y_axis: score_axis(&score_series),
+            series: score_series,
+        };
+
+        let active = |x: NaiveDate| activity.iter().find(|a| a.bucket == x);
+        let activity_series = vec![
+            series("commits_30d", "Commits (30d)", GREEN, &|x| active(x).map(|a| a.commits_30d as f64)),
+            series("contributors_30d", "Contributors (30d)", BLUE, &|x| {
+                active(x).map(|a| a.contributors_30d as f64)
+            }),
+        ];
+        let commit_activity = Chart {
+            kind: "line",
+            y_axis: count_axis(&activity_series, "count"),
+            series: activity_series,
+        };
+
+        let mut markers: Vec<ReleaseMarker> = releases
+            .iter()
+            .filter_map(|r| {
+                let date = r.published_at?.date_naive();
+                (since..=until).contains(&date).then(|| ReleaseMarker {
+                    date,
+                    repo_name: r.repo_name.clone(),
+                    tag_name: r.tag_name.clone(),
+                    prerelease: r.is_prerelease,
+                })
+            })
+            .collect();
+        markers.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.tag_name.cmp(&b.tag_name)));
+
+        let released = |x: NaiveDate, prerelease: bool| {
+            let count = markers
+                .iter()
+                .filter(|m| m.prerelease == prerelease && resolution.bucket_start(m.date) == x)
+                .count();
+            Some(count as f64)
+        };
+        let release_series = vec![
+            series("stable", "Releases", BLUE, &|x| released(x, false)),
+            series("prerelease", "Pre-releases", GREY, &|x| released(x, true)),
+        ];
+        let release_timeline = ReleaseTimeline {
+            chart: Chart {
+                kind: "bar",
+                y_axis: count_axis(&release_series, "releases"),
+                series: release_series,
+            },
+            releases: markers,
+        };
+
+        Self {
+            resolution: resolution.as_str(),
+            since,
+            until,
+            score_history,
+            commit_activity,
+            release_timeline,
+        }
+    }
+}
+
+fn round(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+fn values(series: &[Series]) -> impl Iterator<Item = f64> + '_ {
+    series.iter().flat_map(|s| s.points.iter().filter_map(|p| p.y))
+}
+
+/// Scores zoomed to the data with some padding, in steps of 10 within 0-100
+fn score_axis(series: &[Series]) -> Axis {
+    let (low, high) = values(series).fold((f64::INFINITY, f64::NEG_INFINITY), |(low, high), v| {
+        (low.min(v), high.max(v))
+    });
+    let (min, max) = if low.is_finite() {
+        (
+            ((low - 5.0) / 10.0).floor().max(0.0) * 10.0,
+            ((high + 5.0) / 10.0).ceil().min(10.0) * 10.0,
+        )
+    } else {
+        (0.0, 100.0)
+    };
+
+    Axis {
+        min,
+        max,
+        step: 10.0,
+        unit: "score",
+    }
+}
+
+/// Counts from zero up to a round number of about five ticks
+fn count_axis(series: &[Series], unit: &'static str) -> Axis {
+    let high = values(series).fold(0.0, f64::max);
+    let step = nice_step(high / 5.0);
+
+    Axis {
+        min: 0.0,
+        max: ((high / step).ceil() * step).max(step),
+        step,
+        unit,
+    }
+}
+
+/// Smallest 1, 2 or 5 times a power of ten at least `raw`; 1 for anything under 1
+fn nice_step(raw: f64) -> f64 {
+    if raw <= 1.0 {
+        return 1.0;
+    }
+    let magnitude = 10f64.powf(raw.log10().floor());
+    [1.0, 2.0, 5.0, 10.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|&step| step >= raw)
+        .unwrap_or(10.0 * magnitude)
+}
@@ -0,0 +1,88 @@
+//! Development mode for frontend work (`dv serve --dev`)
+//!
+//! The API is served as usual and every other path is proxied to a frontend dev server,
+//! so the page and the API share an origin just as in production. Responses are marked
+//! uncacheable so edits show up on reload. WebSocket upgrades aren't proxied, so a dev
+//! server's hot reload has to connect to it directly (Vite: `server.hmr.clientPort`).
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    middleware,
+    response::{IntoResponse, Response},
+    Router,
+};
+use tracing::warn;
+
+/// Largest request body forwarded to the dev server
+const MAX_PROXIED_BODY: usize = 16 * 1024 * 1024;
+
+/// Headers that belong to a single connection and aren't forwarded
+const HOP_BY_HOP: [HeaderName; 6] = [
+    header::CONNECTION,
+    header::HOST,
+    header::PROXY_AUTHORIZATION,
+    header::TE,
+    header::TRAILER,
+    header::TRANSFER_ENCODING,
+];
+
+/// Proxy paths the router doesn't handle to `frontend` and turn off caching
+pub fn with_dev_proxy(router: Router, frontend: &str) -> Router {
+    let client = reqwest::Client::new();
+    let frontend = frontend.trim_end_matches('/').to_string();
+
+    router
+        .fallback(move |request: Request| proxy(client.clone(), frontend.clone(), request))
+        .layer(middleware::map_response(no_store))
+}
+
+async fn proxy(client: reqwest::Client, frontend: String, request: Request) -> Response {
+    let (parts, body) = request.into_parts();
+    let path = parts.uri.path_and_query().map_or("/", |p| p.as_str());
+    let url = format!("{}{}", frontend, path);
+
+    let body = match axum::body::to_bytes(body, MAX_PROXIED_BODY).await {
+        Ok(body) => body,
+        Err(e) => return (StatusCode::PAYLOAD_TOO_LARGE, e.to_string()).into_response(),
+    };
+
+    let mut upstream = client.request(parts.method, &url).body(body);
+    for (name, value) in &parts.headers {
+        if !HOP_BY_HOP.contains(name) {
+            upstream = upstream.header(name, value);
+        }
+    }
+
+    let upstream = match upstream.send().await {
+        Ok(response) => response,
+        Err(e) => {
+            warn!("Frontend dev server request failed: {}", e);
+            let message = format!("Frontend dev server at {} isn't reachable: {}", frontend, e);
+            return (StatusCode::BAD_GATEWAY, message).into_response();
+        }
+    };
+
+    let mut response = Response::builder().status(upstream.status());
+    for (name, value) in upstream.headers() {
+        if !HOP_BY_HOP.contains(name) {
+            response = response.header(name, value);
+        }
+    }
+    match upstream.bytes().await {
+        Ok(bytes) => response
+            .body(Body::from(bytes))
+            .unwrap_or_else(|e| (StatusCode::BAD_GATEWAY, e.to_string()).into_response()),
+        Err(e) => (StatusCode::BAD_GATEWAY, e.to_string()).into_response(),
+    }
+}
+
+async fn no_store(mut response: Response) -> Response {
+    response
+        .headers_mut()
+        .insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    response.headers_mut().remove(header::ETAG);
+    response.headers_mut().remove(header::LAST_MODIFIED);
+    response
+}
@@ -0,0 +1,437 @@
+//! The API's own description, served at `GET /api/v1` and `GET /api/v2`
+//!
+//! Endpoints are listed as data, like the scoring methodology, so clients can find them
+//! without reading the source. Every route in [`crate::routes`] needs an entry here.
+
+use crate::versioning::ApiVersion;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Credentials an endpoint needs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Auth {
+    None,
+    /// `Authorization: Bearer <DV_ADMIN_TOKEN>`
+    Admin,
+    /// A maintainer token for the distro, or the admin token
+    Maintainer,
+    /// Open to anyone when public triggers are enabled, otherwise admin only
+    PublicTrigger,
+}
+
+/// One query parameter an endpoint accepts
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Param {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+const fn param(name: &'static str, description: &'static str) -> Param {
+    Param { name, description }
+}
+
+/// An HTTP method a route answers, and what it takes to call it
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Method {
+    pub method: &'static str,
+    pub auth: Auth,
+}
+
+const GET: Method = Method { method: "GET", auth: Auth::None };
+const POST: Method = Method { method: "POST", auth: Auth::None };
+
+const fn admin(method: &'static str) -> Method {
+    Method { method, auth: Auth::Admin }
+}
+
+/// One route
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct Endpoint {
+    /// Link relation the endpoint is listed under in `_links`
+    pub rel: &'static str,
+    /// Path below the version's base, with `{name}` placeholders
+    pub path: &'static str,
+    pub methods: &'static [Method],
+    pub description: &'static str,
+    pub params: &'static [Param],
+}
+
+const INCLUDE_ARCHIVED: Param = param("include_archived", "Also match archived distributions");
+
+const PAGE: [Param; 5] = [
+    param("days", "Look-back window in days, used when since isn't given (default 30)"),
+    param("since", "Start of the range (RFC 3339 timestamp or YYYY-MM-DD)"),
+    param("until", "End of the range, inclusive (RFC 3339 timestamp or YYYY-MM-DD)"),
+    param("cursor", "Opaque cursor from the previous page's next_cursor"),
+    param("limit", "Rows per page (default 500, at most 1000)"),
+];
+
+/// Every route, in the order they're listed
+pub const ENDPOINTS: &[Endpoint] = &[
+    Endpoint {
+        rel: "self",
+        path: "",
+        methods: &[GET],
+        description: "This document",
+        params: &[],
+    },
+    Endpoint {
+        rel: "health",
+        path: "/health",
+        methods: &[GET],
+        description: "Liveness check with the server version",
+        params: &[],
+    },
+    Endpoint {
+        rel: "distros",
+        path: "/distros",
+        methods: &[GET],
+        description: "Tracked distributions",
+        params: &[INCLUDE_ARCHIVED],
+    },
+    Endpoint {
+        rel: "distro",
+        path: "/distros/{slug}",
+        methods: &[
+            GET,
+            Method { method: "PATCH", auth: Auth::Maintainer },
+            admin("DELETE"),
+        ],
+        description: "One distribution; PATCH updates its configuration, DELETE archives it",
+        params: &[INCLUDE_ARCHIVED],
+    },
+    Endpoint {
+        rel: "distro-claims",
+        path: "/distros/{slug}/claims",
+        methods: &[POST],
+        description: "Claim maintainership of a distribution",
+        params: &[],
+    },
+    Endpoint {
+        rel: "distro-health",
+        path: "/distros/{slug}/health",
+        methods: &[GET],
+        description: "Latest health score and raw metrics",
+        params: &[],
+    },
+    Endpoint {
+        rel: "distro-history",
+        path: "/distros/{slug}/history",
+        methods: &[GET],
+        description: "Health score history, paginated",
+        params: &[
+            PAGE[0],
+            PAGE[1],
+            PAGE[2],
+            PAGE[3],
+            PAGE[4],
+            param("resolution", "Downsample into daily, weekly or monthly buckets"),
+            param("include", "\"annotations\" to add annotations and freezes to v1's list of scores"),
+            INCLUDE_ARCHIVED,
+        ],
+    },
+    Endpoint {
+        rel: "distro-snapshots",
+        path: "/distros/{slug}/snapshots",
+        methods: &[GET],
+        description: "Raw GitHub snapshots, paginated",
+        params: &[
+            PAGE[0],
+            PAGE[1],
+            PAGE[2],
+            PAGE[3],
+            PAGE[4],
+            param("repo", "Only snapshots of this repository (owner/name)"),
+            INCLUDE_ARCHIVED,
+        ],
+    },
+    Endpoint {
+        rel: "distro-tech",
+        path: "/distros/{slug}/tech",
+        methods: &[GET],
+        description: "Languages and topics across the distribution's repos",
+        params: &[],
+    },
+    Endpoint {
+        rel: "distro-downloads",
+        path: "/distros/{slug}/downloads",
+        methods: &[GET],
+        description: "Release asset downloads over time",
+        params: &[param("days", "Look-back window in days")],
+    },
+    Endpoint {
+        rel: "distro-charts",
+        path: "/distros/{slug}/charts",
+        methods: &[GET],
+        description: "Chart-ready score and metric series",
+        params: &[
+            param("days", "Look-back window in days"),
+            param("resolution", "daily, weekly or monthly; picked from days when omitted"),
+        ],
+    },
+    Endpoint {
+        rel: "distro-momentum",
+        path: "/distros/{slug}/momentum",
+        methods: &[GET],
+        description: "Recent score momentum",
+        params: &[],
+    },
+    Endpoint {
+        rel: "distro-changes",
+        path: "/distros/{slug}/changes",
+        methods: &[GET],
+        description: "What changed in the scores and metrics since a time",
+        params: &[param("since", "7d, 24h, 2w, an RFC 3339 timestamp or YYYY-MM-DD (default a week ago)")],
+    },
+    Endpoint {
+        rel: "distro-risk",
+        path: "/distros/{slug}/risk",
+        methods: &[GET],
+        description: "Release cadence and the risk of going a year without a release",
+        params: &[],
+    },
+    Endpoint {
+        rel: "distro-jsonld",
+        path: "/distros/{slug}/jsonld",
+        methods: &[GET],
+        description: "Schema.org JSON-LD for the distribution",
+        params: &[],
+    },
+    Endpoint {
+        rel: "distro-og-image",
+        path: "/distros/{slug}/og.png",
+        methods: &[GET],
+        description: "Social preview image",
+        params: &[],
+    },
+    Endpoint {
+        rel: "distro-milestones",
+        path: "/distros/{slug}/milestones",
+        methods: &[GET],
+        description: "Milestones the distribution reached",
+        params: &[param("days", "Look-back window in days")],
+    },
+    Endpoint {
+        rel: "distro-annotations",
+        path: "/distros/{slug}/annotations",
+        methods: &[admin("POST")],
+        description: "Annotate the score history",
+        params: &[],
+    },
+    Endpoint {
+        rel: "rankings",
+        path: "/rankings",
+        methods: &[GET],
+        description: "Distributions ranked by health score or momentum; paginated in v2",
+        params: &[
+            param("os_family", "Comma-separated OS families to include"),
+            param("exclude_os_family", "Comma-separated OS families to exclude"),
+            param("sort", "score (default) or momentum"),
+            param("as_of", "Rankings as archived on this day (YYYY-MM-DD)"),
+            param("cursor", "v2 only: opaque cursor from the previous page's next_cursor"),
+            param("limit", "v2 only: distributions per page (default 50, at most 1000)"),
+        ],
+    },
+    Endpoint {
+        rel: "bulk",
+        path: "/bulk",
+        methods: &[GET],
+        description: "Every distribution with its rank and latest score in one response",
+        params: &[],
+    },
+    Endpoint {
+        rel: "sparklines",
+        path: "/rankings/sparklines",
+        methods: &[GET],
+        description: "Compact recent score series for every distribution",
+        params: &[param("days", "Days of scores (default 30)")],
+    },
+    Endpoint {
+        rel: "snapshots",
+        path: "/snapshots/{kind}",
+        methods: &[GET],
+        description: "Raw snapshots of one kind across distributions",
+        params: &[
+            param("distro", "Distribution slug"),
+            param("repo", "Repository (owner/name), for github and release snapshots"),
+            param("source", "Origin such as reddit, for community and metric snapshots"),
+            param("key", "Metric key, for metric snapshots"),
+            param("since", "Start of the range (RFC 3339 timestamp or YYYY-MM-DD)"),
+            param("until", "End of the range, inclusive (RFC 3339 timestamp or YYYY-MM-DD)"),
+            param("limit", "Rows to return"),
+            param("offset", "Rows to skip"),
+            param("order", "asc (default) or desc by collection time"),
+        ],
+    },
+    Endpoint {
+        rel: "highlight",
+        path: "/highlight",
+        methods: &[GET],
+        description: "This week's highlighted distribution",
+        params: &[],
+    },
+    Endpoint {
+        rel: "methodology",
+        path: "/methodology",
+        methods: &[GET],
+        description: "Scoring rules and their changelog",
+        params: &[],
+    },
+    Endpoint {
+        rel: "milestones",
+        path: "/milestones",
+        methods: &[GET],
+        description: "Milestones reached across distributions",
+        params: &[param("days", "Look-back window in days")],
+    },
+    Endpoint {
+        rel: "leaderboard",
+        path: "/leaderboards/{metric}",
+        methods: &[GET],
+        description: "Distributions ranked by one metric",
+        params: &[param("limit", "Entries to return")],
+    },
+    Endpoint {
+        rel: "contributor-overlap",
+        path: "/ecosystem/overlap",
+        methods: &[GET],
+        description: "Contributors shared between distributions",
+        params: &[
+            param("min_shared", "Leave out pairs sharing fewer contributors than this"),
+            param("distro", "Only pairs involving this distribution"),
+        ],
+    },
+    Endpoint {
+        rel: "metric-distribution",
+        path: "/stats/distribution",
+        methods: &[GET],
+        description: "Histogram of one metric across distributions",
+        params: &[
+            param("metric", "Metric to bucket"),
+            param("buckets", "Number of buckets"),
+            param("distro", "Slug to place within the distribution"),
+        ],
+    },
+    Endpoint {
+        rel: "correlations",
+        path: "/stats/correlations",
+        methods: &[GET],
+        description: "Correlations between metrics and score changes",
+        params: &[param("days", "Look-back window in days")],
+    },
+    Endpoint {
+        rel: "release-risks",
+        path: "/reports/risks",
+        methods: &[GET],
+        description: "Probability of each distribution going without a stable release",
+        params: &[param("horizon_days", "Forecast horizon in days")],
+    },
+    Endpoint {
+        rel: "collect-all",
+        path: "/collect",
+        methods: &[admin("POST")],
+        description: "Collect several distributions",
+        params: &[],
+    },
+    Endpoint {
+        rel: "collect",
+        path: "/collect/{slug}",
+        methods: &[Method { method: "POST", auth: Auth::PublicTrigger }],
+        description: "Collect one distribution",
+        params: &[param("sources", "Comma-separated sources to collect; all of them when absent")],
+    },
+    Endpoint {
+        rel: "job",
+        path: "/jobs/{id}",
+        methods: &[GET],
+        description: "Status of a queued collection job",
+        params: &[],
+    },
+    Endpoint {
+        rel: "suggestions",
+        path: "/suggestions",
+        methods: &[POST],
+        description: "Suggest a distribution to track",
+        params: &[],
+    },
+    Endpoint {
+        rel: "ping",
+        path: "/ping",
+        methods: &[POST],
+        description: "Opt-in anonymous install ping, counted towards an unverified install figure",
+        params: &[],
+    },
+    Endpoint {
+        rel: "claims",
+        path: "/claims",
+        methods: &[admin("GET")],
+        description: "Maintainership claims",
+        params: &[param("status", "pending (default), verified or rejected")],
+    },
+    Endpoint {
+        rel: "claim-verify",
+        path: "/claims/{id}/verify",
+        methods: &[POST],
+        description: "Check a pending claim's challenge and activate its maintainer key",
+        params: &[],
+    },
+    Endpoint {
+        rel: "claim-reject",
+        path: "/claims/{id}/reject",
+        methods: &[admin("POST")],
+        description: "Reject a claim",
+        params: &[],
+    },
+    Endpoint {
+        rel: "config-issues",
+        path: "/admin/config-issues",
+        methods: &[admin("GET")],
+        description: "Configured orgs, groups and subreddits that no longer exist",
+        params: &[],
+    },
+    Endpoint {
+        rel: "admin-health",
+        path: "/admin/health",
+        methods: &[admin("GET")],
+        description: "Collector success rates, rate limits and queue depth",
+        params: &[],
+    },
+    Endpoint {
+        rel: "freezes",
+        path: "/admin/freezes",
+        methods: &[admin("GET"), admin("POST")],
+        description: "Score freezes; POST starts one",
+        params: &[param("all", "Include freezes that have ended")],
+    },
+    Endpoint {
+        rel: "freeze",
+        path: "/admin/freezes/{id}",
+        methods: &[admin("DELETE")],
+        description: "End a score freeze",
+        params: &[],
+    },
+];
+
+/// A HAL link
+#[derive(Debug, Clone, Serialize)]
+pub struct Link {
+    pub href: String,
+    /// Set when `href` has `{name}` placeholders to fill in
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub templated: bool,
+}
+
+impl Endpoint {
+    pub fn link(&self, version: ApiVersion) -> Link {
+        Link {
+            href: format!("{}{}", version.base(), self.path),
+            templated: self.path.contains('{'),
+        }
+    }
+}
+
+/// `_links` for every endpoint under `version`, by relation
+pub fn links(version: ApiVersion) -> BTreeMap<&'static str, Link> {
+    ENDPOINTS.iter().map(|endpoint| (endpoint.rel, endpoint.link(version))).collect()
+}
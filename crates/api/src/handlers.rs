@@ -1,15 +1,51 @@
 //! API request handlers
 
-use crate::SharedState;
+use crate::auth::{generate_token, hash_key, require_admin, require_maintainer};
+use crate::charts::Charts;
+use crate::discovery;
+use crate::jobs::TriggerMode;
+use crate::og::ShareCard;
+use crate::validation::{Checks, ValidQuery, Validate};
+use crate::versioning::{self, ApiVersion};
+use crate::{AppState, SharedState};
+use crate::pagination::{
+    cursor_link, next_link, parse_since, split_page, PageQuery, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE,
+};
 use axum::{
-    extract::{Path, Query, State},
-    http::StatusCode,
+    extract::{ConnectInfo, Extension, OriginalUri, Path, Query, State},
+    http::{header, HeaderMap, StatusCode, Uri},
     response::IntoResponse,
     Json,
 };
-use distrovitals_analyzer::{Analyzer, DistroHealthSummary, RawMetrics};
-use distrovitals_collector::{github::GithubCollector, CollectorConfig};
+use distrovitals_analyzer::correlation::CorrelationReport;
+use distrovitals_analyzer::lifecycle::LifecycleReport;
+use distrovitals_analyzer::methodology;
+use distrovitals_analyzer::stats::{self, Leaderboard, Metric, MetricDistribution};
+use distrovitals_analyzer::trend;
+use distrovitals_analyzer::{
+    cohort_stats, AbandonmentRisk, Aggregation, Analyzer, Changes, DataQuality, DistroHealthSummary,
+    GovernanceChecklist, HardwareEnablement, Momentum, RawMetrics, RunSummary, ScoreOutcome, TechBreakdown, UpstreamRisk,
+};
+use distrovitals_collector::verification::{
+    ClaimVerifier, DOMAIN_CHALLENGE_PATH, GITHUB_CHALLENGE_REPO,
+};
+use distrovitals_collector::downloads::IndexFormat;
+use distrovitals_collector::forge::ForgeKind;
+use distrovitals_collector::mirrors::MirrorListFormat;
+use distrovitals_collector::sources::{Source, SourceCollectors, SourceOutcome};
+use distrovitals_collector::tokens::{TokenBudget, TokenPool};
+use distrovitals_collector::CollectorConfig;
+use chrono::NaiveDate;
+use distrovitals_database::{
+    parse_time_bound, BreakerStatus, CommunitySnapshot, DataFreshness, Database, DatabaseError, Distribution,
+    DistributionUpdate, GithubSnapshot, HealthScore, HealthScoreBucket, MethodologyChange, MetricKey, MetricValue,
+    NewAnnotation, NewClaim, NewScoreFreeze, NewSuggestion, OrgSnapshot, PackageSnapshot, QueueDepth,
+    ReleaseSnapshot, RepeatedFailure, Resolution, ScoreFreeze, SnapshotKind, SnapshotQuery, SnapshotTable,
+    SortOrder, TimeWindow,
+};
 use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::net::SocketAddr;
 use tracing::error;
 
 #[derive(Serialize)]
@@ -29,8 +65,12 @@ impl<T: Serialize> ApiResponse<T> {
     }
 
     pub fn err(message: impl Into<String>) -> (StatusCode, Json<Self>) {
+        Self::error(StatusCode::INTERNAL_SERVER_ERROR, message)
+    }
+
+    pub fn error(status: StatusCode, message: impl Into<String>) -> (StatusCode, Json<Self>) {
         (
-            StatusCode::INTERNAL_SERVER_ERROR,
+            status,
             Json(Self {
                 success: false,
                 data: None,
@@ -48,9 +88,60 @@ pub async fn health_check() -> impl IntoResponse {
     }))
 }
 
+/// Describe the API: its version, how fresh the data is and every endpoint, with HAL links
+pub async fn get_api_root(
+    State(state): State<SharedState>,
+    Extension(api): Extension<ApiVersion>,
+) -> impl IntoResponse {
+    #[derive(Serialize)]
+    struct ApiRoot {
+        api_version: &'static str,
+        version: &'static str,
+        methodology_version: &'static str,
+        freshness: DataFreshness,
+        versions: Vec<versioning::VersionInfo>,
+        endpoints: &'static [discovery::Endpoint],
+        #[serde(rename = "_links")]
+        links: BTreeMap<&'static str, discovery::Link>,
+    }
+
+    match state.reads().get_data_freshness().await {
+        Ok(freshness) => ApiResponse::ok(ApiRoot {
+            api_version: api.name(),
+            version: env!("CARGO_PKG_VERSION"),
+            methodology_version: methodology::METHODOLOGY_VERSION,
+            freshness,
+            versions: versioning::versions(),
+            endpoints: discovery::ENDPOINTS,
+            links: discovery::links(api),
+        })
+        .into_response(),
+        Err(e) => {
+            error!("Failed to get data freshness: {}", e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ArchiveQuery {
+    /// Also return archived distributions
+    #[serde(default)]
+    include_archived: bool,
+}
+
 /// List all tracked distributions
-pub async fn list_distros(State(state): State<SharedState>) -> impl IntoResponse {
-    match state.db.get_distributions().await {
+pub async fn list_distros(
+    State(state): State<SharedState>,
+    Query(archive): Query<ArchiveQuery>,
+) -> impl IntoResponse {
+    let distros = if archive.include_archived {
+        state.reads().get_distributions_including_archived().await
+    } else {
+        state.reads().get_distributions().await
+    };
+
+    match distros {
         Ok(distros) => ApiResponse::ok(distros).into_response(),
         Err(e) => {
             error!("Failed to list distros: {}", e);
@@ -63,9 +154,28 @@ pub async fn list_distros(State(state): State<SharedState>) -> impl IntoResponse
 pub async fn get_distro(
     State(state): State<SharedState>,
     Path(slug): Path<String>,
+    Query(archive): Query<ArchiveQuery>,
 ) -> impl IntoResponse {
-    match state.db.get_distribution_by_slug(&slug).await {
-        Ok(distro) => ApiResponse::ok(distro).into_response(),
+    #[derive(Serialize)]
+    struct DistroDetail {
+        #[serde(flatten)]
+        distro: Distribution,
+        /// Slugs of editions scored on this distro's development data
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        variants: Vec<String>,
+    }
+
+    match find_distro(&state, &slug, archive.include_archived).await {
+        Ok(distro) => {
+            let variants = match state.reads().get_variants(&distro.slug).await {
+                Ok(variants) => variants.into_iter().map(|v| v.slug).collect(),
+                Err(e) => {
+                    error!("Failed to get variants of {}: {}", slug, e);
+                    Vec::new()
+                }
+            };
+            ApiResponse::ok(DistroDetail { distro, variants }).into_response()
+        }
         Err(e) => {
             error!("Failed to get distro {}: {}", slug, e);
             (
@@ -81,12 +191,60 @@ pub async fn get_distro(
     }
 }
 
+/// Look up a distribution, optionally including archived ones
+async fn find_distro(
+    state: &AppState,
+    slug: &str,
+    include_archived: bool,
+) -> distrovitals_database::Result<Distribution> {
+    if include_archived {
+        state.reads().get_distribution_by_slug_including_archived(slug).await
+    } else {
+        state.reads().get_distribution_by_slug(slug).await
+    }
+}
+
+/// Archive a distribution (admin endpoint)
+///
+/// History is kept and stays readable with `include_archived=true`; `dv archive purge`
+/// removes it for good.
+pub async fn archive_distro(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&state, &headers) {
+        return rejection.into_response();
+    }
+
+    let distro = match state.db.get_distribution_by_slug(&slug).await {
+        Ok(d) => d,
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response()
+        }
+    };
+
+    if let Err(e) = state.db.archive_distribution(distro.id).await {
+        error!("Failed to archive distro {}: {}", slug, e);
+        return ApiResponse::<()>::err(e.to_string()).into_response();
+    }
+
+    match state.db.get_distribution_by_id(distro.id).await {
+        Ok(archived) => ApiResponse::ok(archived).into_response(),
+        Err(e) => ApiResponse::<()>::err(e.to_string()).into_response(),
+    }
+}
+
 /// Get health score for a distribution
 pub async fn get_distro_health(
     State(state): State<SharedState>,
     Path(slug): Path<String>,
 ) -> impl IntoResponse {
-    let distro = match state.db.get_distribution_by_slug(&slug).await {
+    let distro = match state.reads().get_distribution_by_slug(&slug).await {
         Ok(d) => d,
         Err(_) => {
             return (
@@ -101,8 +259,68 @@ pub async fn get_distro_health(
         }
     };
 
-    match state.db.get_latest_health_score(distro.id).await {
-        Ok(Some(score)) => ApiResponse::ok(score).into_response(),
+    match state.reads().get_latest_health_score(distro.id).await {
+        Ok(Some(score)) => {
+            #[derive(Serialize)]
+            struct DistroHealth {
+                #[serde(flatten)]
+                score: HealthScore,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                upstream_risk: Option<UpstreamRisk>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                governance: Option<GovernanceChecklist>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                hardware: Option<HardwareEnablement>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                data_quality: Option<DataQuality>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                organization: Option<OrgSnapshot>,
+            }
+
+            let upstream_risk = UpstreamRisk::assess(state.reads(), distro.id)
+                .await
+                .unwrap_or_else(|e| {
+                    error!("Failed to assess upstream risk for {}: {}", slug, e);
+                    None
+                });
+            let governance = GovernanceChecklist::load(state.reads(), distro.id)
+                .await
+                .unwrap_or_else(|e| {
+                    error!("Failed to load governance checklist for {}: {}", slug, e);
+                    None
+                });
+            let hardware = HardwareEnablement::load(state.reads(), &distro)
+                .await
+                .unwrap_or_else(|e| {
+                    error!("Failed to measure hardware enablement lag for {}: {}", slug, e);
+                    None
+                });
+
+            let data_quality = DataQuality::assess(state.reads(), distro.id)
+                .await
+                .unwrap_or_else(|e| {
+                    error!("Failed to assess data quality for {}: {}", slug, e);
+                    None
+                });
+            let organization = state
+                .reads()
+                .get_latest_org_snapshot(distro.id)
+                .await
+                .unwrap_or_else(|e| {
+                    error!("Failed to load GitHub org profile for {}: {}", slug, e);
+                    None
+                });
+
+            ApiResponse::ok(DistroHealth {
+                score,
+                upstream_risk,
+                governance,
+                hardware,
+                data_quality,
+                organization,
+            })
+            .into_response()
+        }
         Ok(None) => (
             StatusCode::NOT_FOUND,
             Json(ApiResponse::<()> {
@@ -119,115 +337,344 @@ pub async fn get_distro_health(
     }
 }
 
-#[derive(Deserialize)]
-pub struct HistoryQuery {
-    #[serde(default = "default_days")]
-    days: i32,
+/// Get the language and topic breakdown of a distribution's repos
+pub async fn get_distro_tech(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse {
+    let distro = match state.reads().get_distribution_by_slug(&slug).await {
+        Ok(d) => d,
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response()
+        }
+    };
+
+    match TechBreakdown::build(state.reads(), distro.id).await {
+        Ok(tech) => ApiResponse::ok(tech).into_response(),
+        Err(e) => {
+            error!("Failed to build tech breakdown for {}: {}", slug, e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
 }
 
-fn default_days() -> i32 {
-    30
+/// Get a distribution's abandonment risk tier and the factors behind it
+pub async fn get_distro_risk(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse {
+    let distro = match state.reads().get_distribution_by_slug(&slug).await {
+        Ok(d) => d,
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response()
+        }
+    };
+
+    match AbandonmentRisk::assess(state.reads(), &distro).await {
+        Ok(Some(risk)) => ApiResponse::ok(risk).into_response(),
+        Ok(None) => ApiResponse::<()>::error(
+            StatusCode::NOT_FOUND,
+            "No repository activity collected yet".to_string(),
+        )
+        .into_response(),
+        Err(e) => {
+            error!("Failed to assess abandonment risk for {}: {}", slug, e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
 }
 
-/// Get health score history for a distribution
-pub async fn get_distro_history(
+/// Get a distribution's momentum: growth and activity direction, separate from health
+pub async fn get_distro_momentum(
     State(state): State<SharedState>,
     Path(slug): Path<String>,
-    Query(query): Query<HistoryQuery>,
 ) -> impl IntoResponse {
-    let distro = match state.db.get_distribution_by_slug(&slug).await {
+    let distro = match state.reads().get_distribution_by_slug(&slug).await {
         Ok(d) => d,
         Err(_) => {
-            return (
+            return ApiResponse::<()>::error(
                 StatusCode::NOT_FOUND,
-                Json(ApiResponse::<()> {
-                    success: false,
-                    data: None,
-                    error: Some(format!("Distribution not found: {}", slug)),
-                }),
+                format!("Distribution not found: {}", slug),
             )
-                .into_response()
+            .into_response()
         }
     };
 
-    match state.db.get_health_score_history(distro.id, query.days).await {
-        Ok(history) => ApiResponse::ok(history).into_response(),
+    match Momentum::load(state.reads(), &distro).await {
+        Ok(momentum) => ApiResponse::ok(momentum).into_response(),
         Err(e) => {
-            error!("Failed to get history for {}: {}", slug, e);
+            error!("Failed to compute momentum for {}: {}", slug, e);
             ApiResponse::<()>::err(e.to_string()).into_response()
         }
     }
 }
 
-/// Get rankings of all distributions
-pub async fn get_rankings(State(state): State<SharedState>) -> impl IntoResponse {
-    let distros = match state.db.get_distributions().await {
+#[derive(Deserialize)]
+pub struct ChangesQuery {
+    /// "7d", "24h", "2w", an RFC 3339 timestamp or YYYY-MM-DD; a week ago when omitted
+    since: Option<String>,
+}
+
+impl Validate for ChangesQuery {
+    fn validate(&self, checks: &mut Checks) {
+        checks.parses("since", self.since.as_deref(), |v| {
+            parse_since(v).filter(|&since| since < chrono::Utc::now())
+        });
+    }
+}
+
+/// Get what changed since a point in time: metric deltas, score, kernel and new releases
+pub async fn get_distro_changes(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+    ValidQuery(query): ValidQuery<ChangesQuery>,
+) -> impl IntoResponse {
+    let since = query
+        .since
+        .as_deref()
+        .and_then(parse_since)
+        .unwrap_or_else(|| chrono::Utc::now() - chrono::TimeDelta::days(7));
+
+    let distro = match state.reads().get_distribution_by_slug(&slug).await {
         Ok(d) => d,
-        Err(e) => return ApiResponse::<()>::err(e.to_string()).into_response(),
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response()
+        }
     };
 
-    let scores = match state.db.get_all_latest_health_scores().await {
-        Ok(s) => s,
-        Err(e) => return ApiResponse::<()>::err(e.to_string()).into_response(),
+    match Changes::load(state.reads(), &distro, since).await {
+        Ok(changes) => ApiResponse::ok(changes).into_response(),
+        Err(e) => {
+            error!("Failed to compute changes for {}: {}", slug, e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ChartsQuery {
+    #[serde(default = "default_chart_days")]
+    days: i64,
+    /// "daily", "weekly" or "monthly"; picked from `days` when omitted
+    resolution: Option<String>,
+}
+
+fn default_chart_days() -> i64 {
+    90
+}
+
+/// Longest range the chart endpoint will bin
+const MAX_CHART_DAYS: i64 = 1825;
+
+/// Accepted `resolution` values
+const RESOLUTIONS: [&str; 3] = ["daily", "weekly", "monthly"];
+
+impl Validate for ChartsQuery {
+    fn validate(&self, checks: &mut Checks) {
+        checks
+            .range("days", self.days, 1..=MAX_CHART_DAYS)
+            .one_of("resolution", self.resolution.as_deref(), &RESOLUTIONS);
+    }
+}
+
+/// Get pre-binned chart series: score history, commit activity and release timeline
+pub async fn get_distro_charts(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+    ValidQuery(query): ValidQuery<ChartsQuery>,
+) -> impl IntoResponse {
+    let resolution = match query.resolution.as_deref().and_then(Resolution::parse) {
+        Some(resolution) => resolution,
+        // Keep the number of buckets readable
+        None if query.days <= 90 => Resolution::Daily,
+        None if query.days <= 365 => Resolution::Weekly,
+        None => Resolution::Monthly,
     };
 
-    let mut rankings: Vec<DistroHealthSummary> = Vec::new();
+    let distro = match state.reads().get_distribution_by_slug(&slug).await {
+        Ok(d) => d,
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response()
+        }
+    };
 
-    for (idx, score) in scores.into_iter().enumerate() {
-        if let Some(d) = distros.iter().find(|d| d.id == score.distro_id) {
-            let snapshots = state.db.get_latest_github_snapshots(d.id).await.unwrap_or_default();
-            let releases = state.db.get_latest_release_snapshots(d.id).await.unwrap_or_default();
-            let community = state.db.get_latest_community_snapshots(d.id).await.unwrap_or_default();
-            let metrics = RawMetrics::from_github_snapshots(&snapshots)
-                .with_releases(&releases)
-                .with_community(&community);
+    let now = chrono::Utc::now();
+    let since = resolution.bucket_start((now - chrono::TimeDelta::days(query.days)).date_naive());
+    let window = TimeWindow {
+        since: since.and_time(chrono::NaiveTime::MIN).and_utc(),
+        until: None,
+        after_id: None,
+        limit: query.days + 1,
+    };
 
-            rankings.push(DistroHealthSummary {
-                slug: d.slug.clone(),
-                name: d.name.clone(),
-                overall_score: score.overall_score,
-                development_score: score.development_score,
-                community_score: score.community_score,
-                maintenance_score: score.maintenance_score,
-                trend: score.trend,
-                rank: idx + 1,
-                metrics,
-                github_org: d.github_org.clone(),
-                subreddit: d.subreddit.clone(),
-                description: d.description.clone(),
-            });
+    let scores = match state.reads().get_health_score_buckets(distro.id, resolution, &window).await {
+        Ok(scores) => scores,
+        Err(e) => {
+            error!("Failed to get score history for {}: {}", slug, e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+    let activity = match state.reads().get_commit_activity_buckets(distro.id, resolution, &window).await {
+        Ok(activity) => activity,
+        Err(e) => {
+            error!("Failed to get commit activity for {}: {}", slug, e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+    // `since` was moved back to a bucket start, up to a month earlier
+    let releases = match state.reads().get_recent_releases(distro.id, query.days as i32 + 31).await {
+        Ok(releases) => releases,
+        Err(e) => {
+            error!("Failed to get releases for {}: {}", slug, e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
         }
+    };
+
+    let charts = Charts::build(resolution, since, now.date_naive(), &scores, &activity, &releases);
+    ApiResponse::ok(charts).into_response()
+}
+
+#[derive(Deserialize)]
+pub struct DownloadsQuery {
+    #[serde(default = "default_download_days")]
+    days: i64,
+}
+
+fn default_download_days() -> i64 {
+    90
+}
+
+impl Validate for DownloadsQuery {
+    fn validate(&self, checks: &mut Checks) {
+        checks.range("days", self.days, 1..=3650);
     }
+}
 
-    // Add distros without scores
-    for distro in &distros {
-        if !rankings.iter().any(|r| r.slug == distro.slug) {
-            rankings.push(DistroHealthSummary {
-                slug: distro.slug.clone(),
-                name: distro.name.clone(),
-                overall_score: 0.0,
-                development_score: 0.0,
-                community_score: 0.0,
-                maintenance_score: 0.0,
-                trend: "unknown".to_string(),
-                rank: rankings.len() + 1,
-                metrics: RawMetrics::default(),
-                github_org: distro.github_org.clone(),
-                subreddit: distro.subreddit.clone(),
-                description: distro.description.clone(),
-            });
+/// Most releases listed in a downloads response
+const MAX_DOWNLOAD_RELEASES: usize = 20;
+
+/// Get release asset download totals and their daily trend for a distribution
+pub async fn get_distro_downloads(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+    ValidQuery(query): ValidQuery<DownloadsQuery>,
+) -> impl IntoResponse {
+
+    let distro = match state.reads().get_distribution_by_slug(&slug).await {
+        Ok(d) => d,
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response()
         }
+    };
+
+    #[derive(Serialize)]
+    struct ReleaseDownloads {
+        repo_name: String,
+        tag_name: String,
+        published_at: Option<chrono::DateTime<chrono::Utc>>,
+        downloads: i64,
     }
 
-    ApiResponse::ok(rankings).into_response()
+    #[derive(Serialize)]
+    struct DownloadsResponse {
+        total: i64,
+        /// Most downloaded releases first
+        releases: Vec<ReleaseDownloads>,
+        history: Vec<distrovitals_database::DownloadPoint>,
+    }
+
+    let since = chrono::Utc::now() - chrono::TimeDelta::days(query.days);
+    let releases = match state.reads().get_latest_release_snapshots(distro.id).await {
+        Ok(releases) => releases,
+        Err(e) => {
+            error!("Failed to get downloads for {}: {}", slug, e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+    let history = match state.reads().get_download_history(distro.id, since).await {
+        Ok(history) => history,
+        Err(e) => {
+            error!("Failed to get downloads for {}: {}", slug, e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+
+    let mut releases: Vec<ReleaseDownloads> = releases
+        .into_iter()
+        .filter_map(|r| {
+            Some(ReleaseDownloads {
+                downloads: r.downloads?,
+                repo_name: r.repo_name,
+                tag_name: r.tag_name,
+                published_at: r.published_at,
+            })
+        })
+        .collect();
+    let total = releases.iter().map(|r| r.downloads).sum();
+    releases.sort_by_key(|r| std::cmp::Reverse(r.downloads));
+    releases.truncate(MAX_DOWNLOAD_RELEASES);
+
+    ApiResponse::ok(DownloadsResponse {
+        total,
+        releases,
+        history,
+    })
+    .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct HistoryQuery {
+    /// Downsample into "daily", "weekly" or "monthly" buckets
+    resolution: Option<String>,
+    /// "annotations" for v1 to return annotations and freezes alongside the scores
+    include: Option<String>,
+}
+
+impl Validate for HistoryQuery {
+    fn validate(&self, checks: &mut Checks) {
+        checks.one_of("resolution", self.resolution.as_deref(), &RESOLUTIONS);
+        checks.one_of("include", self.include.as_deref(), &["annotations"]);
+    }
 }
 
-/// Trigger data collection for a distribution (admin endpoint)
-pub async fn trigger_collection(
+/// Get health score history for a distribution
+///
+/// Paginated with `since`/`until`/`cursor`/`limit`; the next page is linked via the
+/// `Link` header and `next_cursor`. Annotations for the whole range come with the first page.
+/// With `resolution`, returns avg/min/max buckets instead of raw scores.
+///
+/// v1 returns the bare list of scores it always has unless `include=annotations` asks for
+/// the object with annotations and freezes, which v2 always returns.
+pub async fn get_distro_history(
     State(state): State<SharedState>,
+    Extension(api): Extension<ApiVersion>,
     Path(slug): Path<String>,
+    OriginalUri(uri): OriginalUri,
+    ValidQuery(query): ValidQuery<PageQuery>,
+    ValidQuery(history): ValidQuery<HistoryQuery>,
+    Query(archive): Query<ArchiveQuery>,
 ) -> impl IntoResponse {
-    let distro = match state.db.get_distribution_by_slug(&slug).await {
+    let distro = match find_distro(&state, &slug, archive.include_archived).await {
         Ok(d) => d,
         Err(_) => {
             return (
@@ -242,39 +689,2390 @@ pub async fn trigger_collection(
         }
     };
 
-    // Collect GitHub data if org is configured
-    if let Some(ref org) = distro.github_org {
-        let config = CollectorConfig::default();
-        let collector = match GithubCollector::new(config) {
-            Ok(c) => c,
-            Err(e) => return ApiResponse::<()>::err(e.to_string()).into_response(),
-        };
+    let window = match query.window() {
+        Ok(w) => w,
+        Err(msg) => return ApiResponse::<()>::error(StatusCode::BAD_REQUEST, msg).into_response(),
+    };
+
+    if let Some(resolution) = history.resolution.as_deref().and_then(Resolution::parse) {
+        return get_bucketed_history(&state, &distro, resolution, &uri, window)
+            .await
+            .into_response();
+    }
 
-        if let Err(e) = collector.collect_org_repos(&state.db, distro.id, org).await {
-            error!("GitHub collection failed for {}: {}", slug, e);
+    // Fetch one extra row to learn whether another page follows
+    let page = TimeWindow {
+        limit: window.limit + 1,
+        ..window.clone()
+    };
+    let mut scores = match state.reads().get_health_score_page(distro.id, &page).await {
+        Ok(scores) => scores,
+        Err(e) => {
+            error!("Failed to get history for {}: {}", slug, e);
             return ApiResponse::<()>::err(e.to_string()).into_response();
         }
+    };
+    let next_cursor = split_page(&mut scores, window.limit, |s| s.id);
+    let link = next_cursor
+        .as_deref()
+        .map(|cursor| next_link(uri.path(), uri.query(), window.since, Some(cursor)));
 
-        // Collect releases
-        if let Err(e) = collector.collect_org_releases(&state.db, distro.id, org).await {
-            error!("GitHub release collection failed for {}: {}", slug, e);
-            // Don't fail the whole request for release errors
-        }
+    if api == ApiVersion::V1 && history.include.is_none() {
+        let body = ApiResponse::ok(scores);
+        return match link {
+            Some(link) => ([(header::LINK, link)], body).into_response(),
+            None => body.into_response(),
+        };
     }
 
-    // Calculate new health score
-    if let Err(e) = Analyzer::calculate_health_score(&state.db, distro.id).await {
-        error!("Health score calculation failed for {}: {}", slug, e);
-        return ApiResponse::<()>::err(e.to_string()).into_response();
-    }
+    let annotations = if window.after_id.is_none() {
+        match state.reads().get_annotations_in(distro.id, &window).await {
+            Ok(annotations) => annotations,
+            Err(e) => {
+                error!("Failed to get annotations for {}: {}", slug, e);
+                return ApiResponse::<()>::err(e.to_string()).into_response();
+            }
+        }
+    } else {
+        Vec::new()
+    };
+
+    let freezes = if window.after_id.is_none() {
+        match state.reads().get_score_freezes_in(distro.id, &window).await {
+            Ok(freezes) => freezes,
+            Err(e) => {
+                error!("Failed to get score freezes for {}: {}", slug, e);
+                return ApiResponse::<()>::err(e.to_string()).into_response();
+            }
+        }
+    } else {
+        Vec::new()
+    };
 
     #[derive(Serialize)]
-    struct CollectionResult {
-        message: String,
+    struct History {
+        scores: Vec<HealthScore>,
+        annotations: Vec<distrovitals_database::Annotation>,
+        /// Periods the score was held while data was known to be bad
+        freezes: Vec<ScoreFreeze>,
+        next_cursor: Option<String>,
     }
 
-    ApiResponse::ok(CollectionResult {
-        message: format!("Collection completed for {}", slug),
+    let body = ApiResponse::ok(History {
+        scores,
+        annotations,
+        freezes,
+        next_cursor,
+    });
+
+    match link {
+        Some(link) => ([(header::LINK, link)], body).into_response(),
+        None => body.into_response(),
+    }
+}
+
+/// Downsampled variant of [`get_distro_history`], paged by advancing `since`
+async fn get_bucketed_history(
+    state: &AppState,
+    distro: &Distribution,
+    resolution: Resolution,
+    uri: &Uri,
+    window: TimeWindow,
+) -> axum::response::Response {
+    if window.after_id.is_some() {
+        return ApiResponse::<()>::error(
+            StatusCode::BAD_REQUEST,
+            "cursor is not supported with resolution; follow the Link header instead",
+        )
+        .into_response();
+    }
+
+    let page = TimeWindow {
+        limit: window.limit + 1,
+        ..window.clone()
+    };
+    let mut buckets = match state.reads().get_health_score_buckets(distro.id, resolution, &page).await {
+        Ok(buckets) => buckets,
+        Err(e) => {
+            error!("Failed to get history for {}: {}", distro.slug, e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+
+    let next_since = if buckets.len() as i64 > window.limit {
+        buckets.pop().map(|b| b.bucket.and_time(chrono::NaiveTime::MIN).and_utc())
+    } else {
+        None
+    };
+
+    let annotations = match state.reads().get_annotations_in(distro.id, &window).await {
+        Ok(annotations) => annotations,
+        Err(e) => {
+            error!("Failed to get annotations for {}: {}", distro.slug, e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+
+    let freezes = match state.reads().get_score_freezes_in(distro.id, &window).await {
+        Ok(freezes) => freezes,
+        Err(e) => {
+            error!("Failed to get score freezes for {}: {}", distro.slug, e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+
+    #[derive(Serialize)]
+    struct BucketedHistory {
+        resolution: String,
+        buckets: Vec<HealthScoreBucket>,
+        annotations: Vec<distrovitals_database::Annotation>,
+        freezes: Vec<ScoreFreeze>,
+    }
+
+    let body = ApiResponse::ok(BucketedHistory {
+        resolution: resolution.as_str().to_string(),
+        buckets,
+        annotations,
+        freezes,
+    });
+
+    match next_since {
+        Some(since) => {
+            let link = next_link(uri.path(), uri.query(), since, None);
+            ([(header::LINK, link)], body).into_response()
+        }
+        None => body.into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SnapshotFilter {
+    /// Only snapshots of this repository ("owner/name")
+    repo: Option<String>,
+}
+
+/// Get raw GitHub snapshots for a distribution, paginated like history
+pub async fn get_distro_snapshots(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+    OriginalUri(uri): OriginalUri,
+    ValidQuery(query): ValidQuery<PageQuery>,
+    Query(filter): Query<SnapshotFilter>,
+    Query(archive): Query<ArchiveQuery>,
+) -> impl IntoResponse {
+    let distro = match find_distro(&state, &slug, archive.include_archived).await {
+        Ok(d) => d,
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response()
+        }
+    };
+
+    let window = match query.window() {
+        Ok(w) => w,
+        Err(msg) => return ApiResponse::<()>::error(StatusCode::BAD_REQUEST, msg).into_response(),
+    };
+
+    let page = TimeWindow {
+        limit: window.limit + 1,
+        ..window.clone()
+    };
+    let mut query = SnapshotQuery::new().distro(distro.id).window(&page);
+    if let Some(repo) = filter.repo {
+        query = query.repo(repo);
+    }
+
+    let mut snapshots: Vec<GithubSnapshot> = match query.fetch(state.reads()).await {
+        Ok(snapshots) => snapshots,
+        Err(e) => {
+            error!("Failed to get snapshots for {}: {}", slug, e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+    let next_cursor = split_page(&mut snapshots, window.limit, |s| s.id);
+
+    #[derive(Serialize)]
+    struct Snapshots {
+        snapshots: Vec<distrovitals_database::GithubSnapshot>,
+        next_cursor: Option<String>,
+    }
+
+    let link = next_cursor
+        .as_deref()
+        .map(|cursor| next_link(uri.path(), uri.query(), window.since, Some(cursor)));
+
+    let body = ApiResponse::ok(Snapshots {
+        snapshots,
+        next_cursor,
+    });
+
+    match link {
+        Some(link) => ([(header::LINK, link)], body).into_response(),
+        None => body.into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SnapshotListQuery {
+    /// Distribution slug
+    distro: Option<String>,
+    /// Repository ("owner/name"), for github and release snapshots
+    repo: Option<String>,
+    /// Origin such as "reddit", for community and metric snapshots
+    source: Option<String>,
+    /// Metric key, for metric snapshots
+    key: Option<String>,
+    /// Start of the range (RFC 3339 timestamp or YYYY-MM-DD)
+    since: Option<String>,
+    /// End of the range, inclusive (RFC 3339 timestamp or YYYY-MM-DD)
+    until: Option<String>,
+    #[serde(default = "default_snapshot_limit")]
+    limit: i64,
+    #[serde(default)]
+    offset: i64,
+    /// "asc" (default) or "desc" by collection time
+    order: Option<String>,
+}
+
+fn default_snapshot_limit() -> i64 {
+    DEFAULT_PAGE_SIZE
+}
+
+impl Validate for SnapshotListQuery {
+    fn validate(&self, checks: &mut Checks) {
+        checks
+            .range("limit", self.limit, 1..=MAX_PAGE_SIZE)
+            .at_least("offset", self.offset, 0)
+            .parses("since", self.since.as_deref(), |v| parse_time_bound(v, false))
+            .parses("until", self.until.as_deref(), |v| parse_time_bound(v, true))
+            .one_of("order", self.order.as_deref(), &["asc", "desc"]);
+    }
+}
+
+/// List raw snapshots of one kind with optional filters, paged by offset
+pub async fn list_snapshots(
+    State(state): State<SharedState>,
+    Path(kind): Path<String>,
+    ValidQuery(params): ValidQuery<SnapshotListQuery>,
+) -> impl IntoResponse {
+    let bad_request = |msg: String| ApiResponse::<()>::error(StatusCode::BAD_REQUEST, msg).into_response();
+
+    let Some(kind) = SnapshotKind::parse(&kind) else {
+        let kinds: Vec<&str> = SnapshotKind::ALL.iter().map(|k| k.as_str()).collect();
+        return bad_request(format!("Unknown snapshot kind: {} (expected one of {})", kind, kinds.join(", ")));
+    };
+    // Fetch one extra row to tell whether another page follows
+    let mut query = SnapshotQuery::new().limit(params.limit + 1).offset(params.offset);
+
+    if let Some(slug) = &params.distro {
+        match state.reads().get_distribution_by_slug_including_archived(slug).await {
+            Ok(distro) => query = query.distro(distro.id),
+            Err(_) => {
+                return ApiResponse::<()>::error(
+                    StatusCode::NOT_FOUND,
+                    format!("Distribution not found: {}", slug),
+                )
+                .into_response()
+            }
+        }
+    }
+    if let Some(repo) = params.repo {
+        query = query.repo(repo);
+    }
+    if let Some(source) = params.source {
+        query = query.source(source);
+    }
+    if let Some(key) = params.key {
+        query = query.key(MetricKey::from(key));
+    }
+    if let Some(since) = params.since.as_deref().and_then(|v| parse_time_bound(v, false)) {
+        query = query.since(since);
+    }
+    if let Some(until) = params.until.as_deref().and_then(|v| parse_time_bound(v, true)) {
+        query = query.until(until);
+    }
+    if let Some(order) = params.order.as_deref().and_then(SortOrder::parse) {
+        query = query.order(order);
+    }
+
+    let page = (params.limit, params.offset);
+    let result = match kind {
+        SnapshotKind::Github => snapshot_page::<GithubSnapshot>(&state, &query, page).await,
+        SnapshotKind::Release => snapshot_page::<ReleaseSnapshot>(&state, &query, page).await,
+        SnapshotKind::Package => snapshot_page::<PackageSnapshot>(&state, &query, page).await,
+        SnapshotKind::Community => snapshot_page::<CommunitySnapshot>(&state, &query, page).await,
+        SnapshotKind::Metric => snapshot_page::<MetricValue>(&state, &query, page).await,
+    };
+
+    match result {
+        Ok(response) => response,
+        Err(DatabaseError::InvalidQuery(msg)) => bad_request(msg),
+        Err(e) => {
+            error!("Failed to list {} snapshots: {}", kind.as_str(), e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+async fn snapshot_page<T: SnapshotTable + Serialize>(
+    state: &AppState,
+    query: &SnapshotQuery,
+    (limit, offset): (i64, i64),
+) -> Result<axum::response::Response, DatabaseError> {
+    let mut snapshots: Vec<T> = query.fetch(state.reads()).await?;
+
+    let next_offset = (snapshots.len() as i64 > limit).then_some(offset + limit);
+    snapshots.truncate(limit as usize);
+
+    #[derive(Serialize)]
+    struct SnapshotList<T> {
+        snapshots: Vec<T>,
+        next_offset: Option<i64>,
+    }
+
+    Ok(ApiResponse::ok(SnapshotList {
+        snapshots,
+        next_offset,
+    })
+    .into_response())
+}
+
+#[derive(Deserialize)]
+pub struct AnnotationRequest {
+    /// Date the note refers to (YYYY-MM-DD)
+    date: NaiveDate,
+    note: String,
+    author: Option<String>,
+}
+
+/// Add an annotation to a distribution's history (admin endpoint)
+pub async fn create_annotation(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+    Json(request): Json<AnnotationRequest>,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&state, &headers) {
+        return rejection.into_response();
+    }
+
+    let distro = match state.db.get_distribution_by_slug(&slug).await {
+        Ok(d) => d,
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response()
+        }
+    };
+
+    if request.note.trim().is_empty() {
+        return ApiResponse::<()>::error(StatusCode::BAD_REQUEST, "Annotation note must not be empty")
+            .into_response();
+    }
+
+    let annotation = NewAnnotation {
+        distro_id: distro.id,
+        annotated_on: request.date,
+        note: request.note.trim().to_string(),
+        author: request.author,
+    };
+
+    match state.db.insert_annotation(annotation).await {
+        Ok(created) => (StatusCode::CREATED, ApiResponse::ok(created)).into_response(),
+        Err(e) => {
+            error!("Failed to create annotation for {}: {}", slug, e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RankingsQuery {
+    /// Comma-separated OS families to include (e.g. "linux" or "bsd,haiku")
+    os_family: Option<String>,
+    /// Comma-separated OS families to exclude
+    exclude_os_family: Option<String>,
+    /// "score" (default) or "momentum"
+    sort: Option<String>,
+    /// Rankings as archived on this day (YYYY-MM-DD), or the last day archived before it
+    as_of: Option<NaiveDate>,
+}
+
+impl RankingsQuery {
+    fn matches(&self, os_family: &str) -> bool {
+        let contains = |list: &str| list.split(',').any(|f| f.trim().eq_ignore_ascii_case(os_family));
+
+        if let Some(ref include) = self.os_family {
+            if !contains(include) {
+                return false;
+            }
+        }
+        if let Some(ref exclude) = self.exclude_os_family {
+            if contains(exclude) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Rankings as one list; v2 pages them with [`get_rankings_page`]
+pub async fn get_rankings(
+    State(state): State<SharedState>,
+    Query(query): Query<RankingsQuery>,
+) -> impl IntoResponse {
+    match rankings(&state, &query).await {
+        Ok(rankings) => ApiResponse::ok(rankings).into_response(),
+        Err(response) => response,
+    }
+}
+
+/// Rows per rankings page in v2 when `limit` isn't given
+const DEFAULT_RANKINGS_PAGE: i64 = 50;
+
+#[derive(Deserialize)]
+pub struct RankingsPage {
+    /// Opaque cursor from the previous page's `next_cursor`
+    cursor: Option<String>,
+    limit: Option<i64>,
+}
+
+impl Validate for RankingsPage {
+    fn validate(&self, checks: &mut Checks) {
+        checks.parses("cursor", self.cursor.as_deref(), |v| v.parse::<usize>().ok());
+        if let Some(limit) = self.limit {
+            checks.range("limit", limit, 1..=MAX_PAGE_SIZE);
+        }
+    }
+}
+
+/// Rankings a page at a time, with the total and the next page linked via the `Link`
+/// header and `next_cursor`
+///
+/// Ranks are computed over the whole field before paging, so a page's ranks follow on
+/// from the previous one's.
+pub async fn get_rankings_page(
+    State(state): State<SharedState>,
+    OriginalUri(uri): OriginalUri,
+    Query(query): Query<RankingsQuery>,
+    ValidQuery(page): ValidQuery<RankingsPage>,
+) -> impl IntoResponse {
+    let mut rankings = match rankings(&state, &query).await {
+        Ok(rankings) => rankings,
+        Err(response) => return response,
+    };
+
+    #[derive(Serialize)]
+    struct Page {
+        rankings: Vec<DistroHealthSummary>,
+        total: usize,
+        next_cursor: Option<String>,
+    }
+
+    let total = rankings.len();
+    let offset = page.cursor.as_deref().and_then(|c| c.parse().ok()).unwrap_or(0usize).min(total);
+    let limit = page.limit.unwrap_or(DEFAULT_RANKINGS_PAGE) as usize;
+    let end = offset.saturating_add(limit).min(total);
+    let next_cursor = (end < total).then(|| end.to_string());
+    rankings.truncate(end);
+    rankings.drain(..offset);
+
+    let link = next_cursor.as_deref().map(|cursor| cursor_link(uri.path(), uri.query(), cursor));
+    let body = ApiResponse::ok(Page {
+        rankings,
+        total,
+        next_cursor,
+    });
+
+    match link {
+        Some(link) => ([(header::LINK, link)], body).into_response(),
+        None => body.into_response(),
+    }
+}
+
+/// Every distro `query` matches, ranked, or the error response to send instead
+async fn rankings(
+    state: &AppState,
+    query: &RankingsQuery,
+) -> Result<Vec<DistroHealthSummary>, axum::response::Response> {
+    let by_momentum = match query.sort.as_deref() {
+        None | Some("score") => false,
+        Some("momentum") => true,
+        Some(other) => {
+            return Err(ApiResponse::<()>::error(
+                StatusCode::BAD_REQUEST,
+                format!("Unknown sort: {} (expected score or momentum)", other),
+            )
+            .into_response())
+        }
+    };
+
+    if let Some(day) = query.as_of {
+        if by_momentum {
+            return Err(ApiResponse::<()>::error(
+                StatusCode::BAD_REQUEST,
+                "Momentum isn't archived; as_of only supports sort=score",
+            )
+            .into_response());
+        }
+        return archived_rankings(state, query, day).await;
+    }
+
+    let all_distros = match state.reads().get_distributions().await {
+        Ok(d) => d,
+        Err(e) => return Err(ApiResponse::<()>::err(e.to_string()).into_response()),
+    };
+
+    let scores = match state.reads().get_all_latest_health_scores().await {
+        Ok(s) => s,
+        Err(e) => return Err(ApiResponse::<()>::err(e.to_string()).into_response()),
+    };
+
+    // Cohorts span the whole field, regardless of the OS family filter
+    let mut cohorts = cohort_stats(&all_distros, &scores);
+    let distros: Vec<_> = all_distros
+        .into_iter()
+        .filter(|d| query.matches(&d.os_family))
+        .collect();
+
+    let mut rankings: Vec<DistroHealthSummary> = Vec::new();
+
+    for score in scores {
+        if let Some(d) = distros.iter().find(|d| d.id == score.distro_id) {
+            let metrics = RawMetrics::load(state.reads(), d).await.unwrap_or_default();
+
+            rankings.push(DistroHealthSummary {
+                slug: d.slug.clone(),
+                name: d.name.clone(),
+                os_family: d.os_family.clone(),
+                overall_score: score.overall_score,
+                development_score: score.development_score,
+                community_score: score.community_score,
+                maintenance_score: score.maintenance_score,
+                trend: score.trend,
+                rank: rankings.len() + 1,
+                previous_rank: None,
+                rank_change_30d: None,
+                metrics,
+                github_org: d.github_org.clone(),
+                forgejo_org: d.forgejo_org.clone(),
+                subreddit: d.subreddit.clone(),
+                description: d.description.clone(),
+                variant_of: d.variant_of.clone(),
+                cohort: cohorts.remove(&d.id),
+                data_quality: DataQuality::assess(state.reads(), d.id).await.unwrap_or_default(),
+                momentum: None,
+            });
+        }
+    }
+
+    // Add distros without scores
+    for distro in &distros {
+        if !rankings.iter().any(|r| r.slug == distro.slug) {
+            rankings.push(DistroHealthSummary {
+                slug: distro.slug.clone(),
+                name: distro.name.clone(),
+                os_family: distro.os_family.clone(),
+                overall_score: 0.0,
+                development_score: 0.0,
+                community_score: 0.0,
+                maintenance_score: 0.0,
+                trend: "unknown".to_string(),
+                rank: rankings.len() + 1,
+                previous_rank: None,
+                rank_change_30d: None,
+                metrics: RawMetrics::default(),
+                github_org: distro.github_org.clone(),
+                forgejo_org: distro.forgejo_org.clone(),
+                subreddit: distro.subreddit.clone(),
+                description: distro.description.clone(),
+                variant_of: distro.variant_of.clone(),
+                cohort: None,
+                data_quality: DataQuality::assess(state.reads(), distro.id).await.unwrap_or_default(),
+                momentum: None,
+            });
+        }
+    }
+
+    if by_momentum {
+        for summary in rankings.iter_mut() {
+            if let Some(distro) = distros.iter().find(|d| d.slug == summary.slug) {
+                summary.momentum = Momentum::load(state.reads(), distro).await.ok();
+            }
+        }
+        let momentum = |s: &DistroHealthSummary| s.momentum.as_ref().and_then(|m| m.score).unwrap_or(-1.0);
+        rankings.sort_by(|a, b| momentum(b).total_cmp(&momentum(a)));
+        for (i, summary) in rankings.iter_mut().enumerate() {
+            summary.rank = i + 1;
+        }
+    } else {
+        fill_rank_changes(state, query, chrono::Utc::now().date_naive(), &mut rankings).await;
+    }
+
+    Ok(rankings)
+}
+
+/// Days over which rank changes are measured
+const RANK_CHANGE_DAYS: i64 = 30;
+
+/// Set each scored distro's rank `RANK_CHANGE_DAYS` before `day` from the ranking archive
+///
+/// Earlier ranks are counted among the distros `query` matches, as current ones are. Left
+/// unset when nothing was archived that far back.
+async fn fill_rank_changes(
+    state: &AppState,
+    query: &RankingsQuery,
+    day: NaiveDate,
+    rankings: &mut [DistroHealthSummary],
+) {
+    let since = day - chrono::TimeDelta::days(RANK_CHANGE_DAYS);
+    let (Ok(archived), Ok(distros)) = (
+        state.reads().get_rankings_as_of(since).await,
+        state.reads().get_distributions_including_archived().await,
+    ) else {
+        return;
+    };
+
+    let previous: HashMap<String, usize> = archived
+        .into_iter()
+        .filter(|entry| distros.iter().any(|d| d.id == entry.distro_id && query.matches(&d.os_family)))
+        .enumerate()
+        .map(|(i, entry)| (entry.slug, i + 1))
+        .collect();
+    for summary in rankings.iter_mut() {
+        summary.previous_rank = previous.get(&summary.slug).copied();
+        summary.rank_change_30d = summary.previous_rank.map(|prev| prev as i64 - summary.rank as i64);
+    }
+}
+
+/// Rankings from the daily archive; metrics and cohorts aren't archived, so they're left empty
+async fn archived_rankings(
+    state: &AppState,
+    query: &RankingsQuery,
+    day: NaiveDate,
+) -> Result<Vec<DistroHealthSummary>, axum::response::Response> {
+    let archived = match state.reads().get_rankings_as_of(day).await {
+        Ok(r) => r,
+        Err(e) => return Err(ApiResponse::<()>::err(e.to_string()).into_response()),
+    };
+    if archived.is_empty() {
+        return Err(ApiResponse::<()>::error(
+            StatusCode::NOT_FOUND,
+            format!("No rankings archived on or before {}", day),
+        )
+        .into_response());
+    }
+
+    // Distros archived since still appear where they stood
+    let distros = match state.reads().get_distributions_including_archived().await {
+        Ok(d) => d,
+        Err(e) => return Err(ApiResponse::<()>::err(e.to_string()).into_response()),
+    };
+
+    let mut rankings: Vec<DistroHealthSummary> = Vec::new();
+    for entry in archived {
+        let Some(d) = distros.iter().find(|d| d.id == entry.distro_id && query.matches(&d.os_family)) else {
+            continue;
+        };
+        rankings.push(DistroHealthSummary {
+            slug: entry.slug,
+            name: entry.name,
+            os_family: d.os_family.clone(),
+            overall_score: entry.overall_score,
+            development_score: entry.development_score,
+            community_score: entry.community_score,
+            maintenance_score: entry.maintenance_score,
+            trend: entry.trend,
+            rank: rankings.len() + 1,
+            previous_rank: None,
+            rank_change_30d: None,
+            metrics: RawMetrics::default(),
+            github_org: d.github_org.clone(),
+            forgejo_org: d.forgejo_org.clone(),
+            subreddit: d.subreddit.clone(),
+            description: d.description.clone(),
+            variant_of: d.variant_of.clone(),
+            cohort: None,
+            data_quality: None,
+            momentum: None,
+        });
+    }
+    fill_rank_changes(state, query, day, &mut rankings).await;
+
+    Ok(rankings)
+}
+
+/// Get every distro with its latest score and metrics in one document
+///
+/// Meant for static-site builds and offline apps. The `version` stamp changes whenever
+/// any data does and doubles as the ETag, so unchanged copies revalidate with a 304.
+pub async fn get_bulk(State(state): State<SharedState>, headers: HeaderMap) -> impl IntoResponse {
+    #[derive(Serialize)]
+    struct BulkDistro {
+        #[serde(flatten)]
+        distribution: Distribution,
+        rank: Option<usize>,
+        score: Option<HealthScore>,
+        metrics: RawMetrics,
+    }
+
+    #[derive(Serialize)]
+    struct Bulk {
+        version: String,
+        generated_at: chrono::DateTime<chrono::Utc>,
+        methodology_version: &'static str,
+        distros: Vec<BulkDistro>,
+    }
+
+    let (latest, distros, scores) = match bulk_sources(state.reads()).await {
+        Ok(sources) => sources,
+        Err(e) => {
+            error!("Failed to load bulk export: {}", e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+
+    let version = format!(
+        "{}-{}",
+        latest.map_or_else(|| "empty".to_string(), |t| t.format("%Y%m%dT%H%M%SZ").to_string()),
+        distros.len()
+    );
+    let etag = format!("\"{}\"", version);
+    let cache_headers = [(header::ETAG, etag.clone()), (header::CACHE_CONTROL, BULK_CACHE_CONTROL.to_string())];
+    if headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) == Some(etag.as_str()) {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    // Scores come highest first; rank among the distros being exported
+    let ranked: Vec<&HealthScore> = scores
+        .iter()
+        .filter(|s| distros.iter().any(|d| d.id == s.distro_id))
+        .collect();
+
+    let mut entries = Vec::with_capacity(distros.len());
+    for distribution in distros {
+        let position = ranked.iter().position(|s| s.distro_id == distribution.id);
+        let metrics = RawMetrics::load(state.reads(), &distribution).await.unwrap_or_default();
+        entries.push(BulkDistro {
+            rank: position.map(|p| p + 1),
+            score: position.map(|p| ranked[p].clone()),
+            metrics,
+            distribution,
+        });
+    }
+
+    let bulk = Bulk {
+        version,
+        generated_at: chrono::Utc::now(),
+        methodology_version: methodology::METHODOLOGY_VERSION,
+        distros: entries,
+    };
+    (cache_headers, ApiResponse::ok(bulk)).into_response()
+}
+
+/// How long clients and caches may reuse a bulk export before revalidating
+const BULK_CACHE_CONTROL: &str = "public, max-age=300";
+
+/// Data version, active distros and latest scores for a bulk export
+type BulkSources = (Option<chrono::DateTime<chrono::Utc>>, Vec<Distribution>, Vec<HealthScore>);
+
+async fn bulk_sources(db: &Database) -> Result<BulkSources, DatabaseError> {
+    let latest = db.get_data_version().await?;
+    let distros = db.get_distributions().await?;
+    let scores = db.get_all_latest_health_scores().await?;
+    Ok((latest, distros, scores))
+}
+
+/// Page on the site showing a distro's health
+fn distro_page_url(public_url: &str, slug: &str) -> String {
+    format!("{}/?distro={}", public_url, slug)
+}
+
+/// Escape text for an XML or HTML element
+pub(crate) fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// Sitemap of the home page and every tracked distro's health page, for search indexing
+pub async fn get_sitemap(State(state): State<SharedState>) -> impl IntoResponse {
+    let (distros, scores) = match (state.reads().get_distributions().await, state.reads().get_all_latest_health_scores().await) {
+        (Ok(distros), Ok(scores)) => (distros, scores),
+        (Err(e), _) | (_, Err(e)) => {
+            error!("Failed to build sitemap: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to build sitemap").into_response();
+        }
+    };
+
+    let lastmod = |at: chrono::DateTime<chrono::Utc>| at.format("%Y-%m-%d").to_string();
+    let mut xml = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <urlset xmlns=\"http://www.sitemaps.org/schemas/sitemap/0.9\">\n",
+    );
+
+    let home_modified = scores.iter().map(|s| s.calculated_at).max();
+    xml.push_str(&format!("  <url>\n    <loc>{}/</loc>\n", xml_escape(&state.public_url)));
+    if let Some(at) = home_modified {
+        xml.push_str(&format!("    <lastmod>{}</lastmod>\n", lastmod(at)));
+    }
+    xml.push_str("    <changefreq>daily</changefreq>\n  </url>\n");
+
+    for distro in &distros {
+        let modified = scores
+            .iter()
+            .find(|s| s.distro_id == distro.id)
+            .map_or(distro.updated_at, |s| s.calculated_at);
+        xml.push_str(&format!(
+            "  <url>\n    <loc>{}</loc>\n    <lastmod>{}</lastmod>\n    <changefreq>daily</changefreq>\n  </url>\n",
+            xml_escape(&distro_page_url(&state.public_url, &distro.slug)),
+            lastmod(modified)
+        ));
+    }
+    xml.push_str("</urlset>\n");
+
+    ([(header::CONTENT_TYPE, "application/xml; charset=utf-8")], xml).into_response()
+}
+
+/// schema.org structured data for a distro's health page, as JSON-LD
+///
+/// Returned bare rather than in the API envelope so it can be embedded in the page as-is.
+pub async fn get_distro_jsonld(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+) -> impl IntoResponse {
+    let distro = match state.reads().get_distribution_by_slug(&slug).await {
+        Ok(d) => d,
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response()
+        }
+    };
+
+    let score = match state.reads().get_latest_health_score(distro.id).await {
+        Ok(score) => score,
+        Err(e) => {
+            error!("Failed to load health score for {}: {}", slug, e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+
+    let page_url = distro_page_url(&state.public_url, &distro.slug);
+    let mut software = serde_json::json!({
+        "@type": "SoftwareApplication",
+        "name": distro.name,
+        "applicationCategory": "OperatingSystem",
+    });
+    if let Some(homepage) = &distro.homepage {
+        software["url"] = homepage.clone().into();
+    }
+    if let Some(description) = &distro.description {
+        software["description"] = description.clone().into();
+    }
+
+    let mut dataset = serde_json::json!({
+        "@context": "https://schema.org",
+        "@type": "Dataset",
+        "@id": page_url,
+        "name": format!("{} health metrics", distro.name),
+        "description": format!(
+            "Development activity, community engagement and maintenance metrics for {}, tracked by DistroVitals.",
+            distro.name
+        ),
+        "url": page_url,
+        "about": software,
+        "creator": {
+            "@type": "Organization",
+            "name": "DistroVitals",
+            "url": format!("{}/", state.public_url),
+        },
+        "image": format!("{}/api/v1/distros/{}/og.png", state.public_url, distro.slug),
+        "isAccessibleForFree": true,
+        "distribution": {
+            "@type": "DataDownload",
+            "encodingFormat": "application/json",
+            "contentUrl": format!("{}/api/v1/distros/{}/health", state.public_url, distro.slug),
+        },
+    });
+    if let Some(score) = score {
+        dataset["dateModified"] = score.calculated_at.to_rfc3339().into();
+        dataset["variableMeasured"] = serde_json::json!([
+            score_property("Overall health score", score.overall_score),
+            score_property("Development score", score.development_score),
+            score_property("Community score", score.community_score),
+            score_property("Maintenance score", score.maintenance_score),
+        ]);
+    }
+
+    ([(header::CONTENT_TYPE, "application/ld+json")], dataset.to_string()).into_response()
+}
+
+/// Share card image for link previews: name, score, trend and a 30-day sparkline
+pub async fn get_distro_og_image(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    let distro = match state.reads().get_distribution_by_slug(&slug).await {
+        Ok(d) => d,
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response()
+        }
+    };
+
+    let score = match state.reads().get_latest_health_score(distro.id).await {
+        Ok(score) => score,
+        Err(e) => {
+            error!("Failed to load health score for {}: {}", slug, e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+    let scored_at = score.as_ref().map(|s| s.calculated_at);
+
+    // The card only changes when a new score is calculated
+    let etag = format!(
+        "\"{}-{}\"",
+        distro.slug,
+        scored_at.map_or(0, |at| at.timestamp())
+    );
+    let cache_headers = [
+        (header::CONTENT_TYPE, "image/png".to_string()),
+        (header::ETAG, etag.clone()),
+        (header::CACHE_CONTROL, OG_IMAGE_CACHE_CONTROL.to_string()),
+    ];
+    if headers.get(header::IF_NONE_MATCH).and_then(|v| v.to_str().ok()) == Some(etag.as_str()) {
+        return (StatusCode::NOT_MODIFIED, cache_headers).into_response();
+    }
+
+    if let Some(png) = state.share_cards.get(&distro.slug, scored_at) {
+        return (cache_headers, png).into_response();
+    }
+
+    let history = match state.reads().get_health_score_history(distro.id, 30).await {
+        Ok(history) => history,
+        Err(e) => {
+            error!("Failed to load score history for {}: {}", slug, e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+    let sparkline: Vec<f64> = history.iter().map(|s| s.overall_score).collect();
+
+    let card = ShareCard {
+        name: &distro.name,
+        score: score.as_ref().map(|s| s.overall_score),
+        trend: score.as_ref().map(|s| s.trend.as_str()),
+        sparkline: &sparkline,
+    };
+    let png = card.render();
+    state.share_cards.insert(&distro.slug, scored_at, png.clone());
+
+    (cache_headers, png).into_response()
+}
+
+/// Share cards are fetched by crawlers; an hour is fresh enough for a daily score
+const OG_IMAGE_CACHE_CONTROL: &str = "public, max-age=3600";
+
+fn score_property(name: &str, value: f64) -> serde_json::Value {
+    serde_json::json!({
+        "@type": "PropertyValue",
+        "name": name,
+        "value": (value * 10.0).round() / 10.0,
+        "minValue": 0,
+        "maxValue": 100,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct DistributionQuery {
+    #[serde(default = "default_metric")]
+    metric: String,
+    #[serde(default = "default_buckets")]
+    buckets: usize,
+    /// Optional slug to place within the distribution
+    distro: Option<String>,
+}
+
+fn default_metric() -> String {
+    "overall_score".to_string()
+}
+
+fn default_buckets() -> usize {
+    10
+}
+
+impl Validate for DistributionQuery {
+    fn validate(&self, checks: &mut Checks) {
+        checks.range("buckets", self.buckets, 1..=100);
+    }
+}
+
+/// Get a histogram and summary statistics for one metric across all distros
+pub async fn get_metric_distribution(
+    State(state): State<SharedState>,
+    ValidQuery(query): ValidQuery<DistributionQuery>,
+) -> impl IntoResponse {
+    let Some(metric) = Metric::parse(&query.metric) else {
+        let valid: Vec<&str> = Metric::ALL.iter().map(|m| m.as_str()).collect();
+        return ApiResponse::<()>::error(
+            StatusCode::BAD_REQUEST,
+            format!("Unknown metric '{}', expected one of: {}", query.metric, valid.join(", ")),
+        )
+        .into_response();
+    };
+
+    let samples = match stats::load_samples(state.reads()).await {
+        Ok(s) => s,
+        Err(e) => {
+            error!("Failed to load metrics for distribution: {}", e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+
+    if let Some(slug) = query.distro.as_deref() {
+        if !samples.iter().any(|s| s.distro.slug == slug) {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("No health score for distribution: {}", slug),
+            )
+            .into_response();
+        }
+    }
+
+    ApiResponse::ok(MetricDistribution::build(
+        &samples,
+        metric,
+        query.buckets,
+        query.distro.as_deref(),
+    ))
+    .into_response()
+}
+
+#[derive(Deserialize)]
+pub struct LeaderboardQuery {
+    #[serde(default = "default_leaderboard_limit")]
+    limit: usize,
+}
+
+fn default_leaderboard_limit() -> usize {
+    10
+}
+
+/// Longest top or bottom list a leaderboard returns
+const MAX_LEADERBOARD_LIMIT: usize = 50;
+
+impl Validate for LeaderboardQuery {
+    fn validate(&self, checks: &mut Checks) {
+        checks.range("limit", self.limit, 1..=MAX_LEADERBOARD_LIMIT);
+    }
+}
+
+/// Get the top and bottom distros by a single metric
+pub async fn get_leaderboard(
+    State(state): State<SharedState>,
+    Path(metric): Path<String>,
+    ValidQuery(query): ValidQuery<LeaderboardQuery>,
+) -> impl IntoResponse {
+    let Some(metric) = Metric::parse(&metric) else {
+        let valid: Vec<&str> = Metric::ALL.iter().map(|m| m.as_str()).collect();
+        return ApiResponse::<()>::error(
+            StatusCode::BAD_REQUEST,
+            format!("Unknown metric '{}', expected one of: {}", metric, valid.join(", ")),
+        )
+        .into_response();
+    };
+
+    match stats::load_samples(state.reads()).await {
+        Ok(samples) => ApiResponse::ok(Leaderboard::build(&samples, metric, query.limit)).into_response(),
+        Err(e) => {
+            error!("Failed to load metrics for leaderboard: {}", e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct OverlapQuery {
+    /// Leave out pairs sharing fewer contributors than this
+    #[serde(default = "default_min_shared")]
+    min_shared: i64,
+    /// Only pairs involving this distro
+    distro: Option<String>,
+}
+
+fn default_min_shared() -> i64 {
+    1
+}
+
+impl Validate for OverlapQuery {
+    fn validate(&self, checks: &mut Checks) {
+        checks.at_least("min_shared", self.min_shared, 1);
+    }
+}
+
+/// Get pairs of distros that share contributors, most shared first
+pub async fn get_contributor_overlap(
+    State(state): State<SharedState>,
+    ValidQuery(query): ValidQuery<OverlapQuery>,
+) -> impl IntoResponse {
+    if let Some(slug) = query.distro.as_deref() {
+        if state.reads().get_distribution_by_slug(slug).await.is_err() {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response();
+        }
+    }
+
+    match state.reads().get_contributor_overlap(query.min_shared).await {
+        Ok(mut pairs) => {
+            if let Some(slug) = query.distro.as_deref() {
+                pairs.retain(|p| p.distro_a == slug || p.distro_b == slug);
+            }
+            ApiResponse::ok(pairs).into_response()
+        }
+        Err(e) => {
+            error!("Failed to compute contributor overlap: {}", e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CorrelationQuery {
+    #[serde(default = "default_correlation_days")]
+    days: i64,
+}
+
+fn default_correlation_days() -> i64 {
+    90
+}
+
+impl Validate for CorrelationQuery {
+    fn validate(&self, checks: &mut Checks) {
+        checks.range("days", self.days, 1..=3650);
+    }
+}
+
+/// Get correlations between raw metrics and score components
+pub async fn get_correlations(
+    State(state): State<SharedState>,
+    ValidQuery(query): ValidQuery<CorrelationQuery>,
+) -> impl IntoResponse {
+    match CorrelationReport::build(state.reads(), query.days).await {
+        Ok(report) => ApiResponse::ok(report).into_response(),
+        Err(e) => {
+            error!("Failed to compute correlations: {}", e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct LifecycleQuery {
+    #[serde(default = "default_horizon_days")]
+    horizon_days: i64,
+}
+
+fn default_horizon_days() -> i64 {
+    365
+}
+
+impl Validate for LifecycleQuery {
+    fn validate(&self, checks: &mut Checks) {
+        checks.range("horizon_days", self.horizon_days, 30..=1825);
+    }
+}
+
+/// Get each distro's probability of going the horizon without a stable release
+pub async fn get_release_risks(
+    State(state): State<SharedState>,
+    ValidQuery(query): ValidQuery<LifecycleQuery>,
+) -> impl IntoResponse {
+    match LifecycleReport::build(state.reads(), query.horizon_days).await {
+        Ok(report) => ApiResponse::ok(report).into_response(),
+        Err(e) => {
+            error!("Failed to build release risk report: {}", e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct MilestoneQuery {
+    #[serde(default = "default_milestone_days")]
+    days: i64,
+}
+
+fn default_milestone_days() -> i64 {
+    90
+}
+
+impl Validate for MilestoneQuery {
+    fn validate(&self, checks: &mut Checks) {
+        checks.range("days", self.days, 1..=3650);
+    }
+}
+
+/// Get recent milestones across all distributions
+pub async fn get_milestones(
+    State(state): State<SharedState>,
+    ValidQuery(query): ValidQuery<MilestoneQuery>,
+) -> impl IntoResponse {
+    let since = chrono::Utc::now() - chrono::TimeDelta::days(query.days);
+
+    match state.reads().get_milestones(None, since).await {
+        Ok(milestones) => ApiResponse::ok(milestones).into_response(),
+        Err(e) => {
+            error!("Failed to get milestones: {}", e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+/// Get recent milestones for a distribution
+pub async fn get_distro_milestones(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+    ValidQuery(query): ValidQuery<MilestoneQuery>,
+) -> impl IntoResponse {
+    let distro = match state.reads().get_distribution_by_slug(&slug).await {
+        Ok(d) => d,
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response()
+        }
+    };
+
+    let since = chrono::Utc::now() - chrono::TimeDelta::days(query.days);
+
+    match state.reads().get_milestones(Some(distro.id), since).await {
+        Ok(milestones) => ApiResponse::ok(milestones).into_response(),
+        Err(e) => {
+            error!("Failed to get milestones for {}: {}", slug, e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+/// Get the current "distro of the week" for the homepage
+pub async fn get_highlight(State(state): State<SharedState>) -> impl IntoResponse {
+    match state.reads().get_latest_highlight().await {
+        Ok(Some(highlight)) => ApiResponse::ok(highlight).into_response(),
+        Ok(None) => {
+            ApiResponse::<()>::error(StatusCode::NOT_FOUND, "No highlight has been selected yet")
+                .into_response()
+        }
+        Err(e) => {
+            error!("Failed to get highlight: {}", e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+/// Get the active scoring rules and the changelog of methodology changes
+pub async fn get_methodology(State(state): State<SharedState>) -> impl IntoResponse {
+    #[derive(Serialize)]
+    struct Methodology {
+        version: &'static str,
+        components: [methodology::Component; 3],
+        neutral_score: f64,
+        trend_threshold: f64,
+        trend_window: usize,
+        changelog: Vec<MethodologyChange>,
+    }
+
+    match state.reads().get_methodology_changes().await {
+        Ok(changelog) => ApiResponse::ok(Methodology {
+            version: methodology::METHODOLOGY_VERSION,
+            components: methodology::COMPONENTS,
+            neutral_score: methodology::NEUTRAL_SCORE,
+            trend_threshold: methodology::TREND_THRESHOLD,
+            trend_window: trend::trend_window(),
+            changelog,
+        })
+        .into_response(),
+        Err(e) => {
+            error!("Failed to get methodology changelog: {}", e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct SparklineQuery {
+    #[serde(default = "default_sparkline_days")]
+    days: i32,
+}
+
+fn default_sparkline_days() -> i32 {
+    30
+}
+
+impl Validate for SparklineQuery {
+    fn validate(&self, checks: &mut Checks) {
+        checks.range("days", self.days, 1..=365);
+    }
+}
+
+/// Get compact `[slug, [scores...]]` pairs for every distro, for trend charts in list views
+pub async fn get_sparklines(
+    State(state): State<SharedState>,
+    ValidQuery(query): ValidQuery<SparklineQuery>,
+) -> impl IntoResponse {
+    match state.reads().get_score_sparklines(query.days).await {
+        Ok(sparklines) => ApiResponse::ok(sparklines).into_response(),
+        Err(e) => {
+            error!("Failed to get sparklines: {}", e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CollectQuery {
+    /// Comma-separated sources to collect; all of them when absent
+    sources: Option<String>,
+}
+
+/// Resolve requested source names against the collector registry, defaulting to all
+fn parse_sources<S: AsRef<str>>(names: &[S]) -> Result<Vec<Source>, String> {
+    match Source::parse_list(names) {
+        Ok(sources) if sources.is_empty() => Ok(Source::ALL.to_vec()),
+        Ok(sources) => Ok(sources),
+        Err(unknown) => Err(format!("Unknown sources: {} (expected {})", unknown.join(", "), Source::names())),
+    }
+}
+
+/// How long a public refresh of a distro and source stands in for further requests
+const REFRESH_DEDUP: chrono::TimeDelta = chrono::TimeDelta::hours(1);
+
+/// Rejection, or whether to queue low-priority refreshes, for a trigger without the admin token
+fn public_trigger(state: &AppState, headers: &HeaderMap) -> Result<bool, (StatusCode, Json<ApiResponse<()>>)> {
+    if require_admin(state, headers).is_ok() {
+        return Ok(false);
+    }
+    match state.public_triggers {
+        TriggerMode::Run => Ok(false),
+        TriggerMode::Queue => Ok(true),
+        TriggerMode::Off => Err(ApiResponse::error(
+            StatusCode::FORBIDDEN,
+            "Collection triggers require the admin token",
+        )),
+    }
+}
+
+#[derive(Serialize)]
+struct QueuedJob {
+    id: i64,
+    distro: String,
+    source: Source,
+    /// An earlier refresh within the hour is being reused instead of a new job
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    already_queued: bool,
+}
+
+/// Queue a collection job, as a deduplicated low-priority refresh for public triggers
+async fn queue_job(
+    state: &AppState,
+    distro: &Distribution,
+    source: Source,
+    refresh: bool,
+) -> Result<QueuedJob, DatabaseError> {
+    let (id, new) = if refresh {
+        let since = chrono::Utc::now() - REFRESH_DEDUP;
+        state.db.enqueue_refresh_job(distro.id, source.as_str(), since).await?
+    } else {
+        (state.db.enqueue_collection_job(distro.id, source.as_str()).await?, true)
+    };
+
+    Ok(QueuedJob {
+        id,
+        distro: distro.slug.clone(),
+        source,
+        already_queued: !new,
+    })
+}
+
+/// Trigger data collection for a distribution
+///
+/// Runs straight away for admins. Without the admin token, `DV_PUBLIC_TRIGGERS` decides:
+/// run, queue a low-priority refresh (responding 202 with the job IDs), or reject.
+pub async fn trigger_collection(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+    Query(query): Query<CollectQuery>,
+) -> impl IntoResponse {
+    let refresh = match public_trigger(&state, &headers) {
+        Ok(refresh) => refresh,
+        Err(rejection) => return rejection.into_response(),
+    };
+
+    let sources = match parse_sources(query.sources.as_slice()) {
+        Ok(sources) => sources,
+        Err(e) => return ApiResponse::<()>::error(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    let distro = match state.db.get_distribution_by_slug(&slug).await {
+        Ok(d) => d,
+        Err(_) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<()> {
+                    success: false,
+                    data: None,
+                    error: Some(format!("Distribution not found: {}", slug)),
+                }),
+            )
+                .into_response()
+        }
+    };
+
+    if refresh {
+        let mut jobs = Vec::new();
+        for source in sources {
+            match queue_job(&state, &distro, source, true).await {
+                Ok(job) => jobs.push(job),
+                Err(e) => {
+                    error!("Failed to queue {} refresh for {}: {}", source, slug, e);
+                    return ApiResponse::<()>::err(e.to_string()).into_response();
+                }
+            }
+        }
+        state.job_signal.notify_one();
+        return (StatusCode::ACCEPTED, ApiResponse::ok(jobs)).into_response();
+    }
+
+    let collectors = match SourceCollectors::new(CollectorConfig::default()) {
+        Ok(c) => c,
+        Err(e) => return ApiResponse::<()>::err(e.to_string()).into_response(),
+    };
+
+    #[derive(Serialize)]
+    struct SourceResult {
+        source: Source,
+        status: &'static str,
+        collected: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    }
+
+    let mut results = Vec::new();
+    for (source, result) in collectors.collect_distro(&state.db, &distro, &sources, None).await {
+        let result = match result {
+            Ok(SourceOutcome::Collected(collected)) => SourceResult {
+                source,
+                status: "done",
+                collected,
+                message: None,
+            },
+            Ok(SourceOutcome::Skipped(reason)) => SourceResult {
+                source,
+                status: "skipped",
+                collected: 0,
+                message: Some(reason.to_string()),
+            },
+            Err(e) => {
+                error!("{} collection failed for {}: {}", source.label(), slug, e);
+                SourceResult {
+                    source,
+                    status: "failed",
+                    collected: 0,
+                    message: Some(e.to_string()),
+                }
+            }
+        };
+        results.push(result);
+    }
+
+    // Calculate new health score
+    let mut summary = RunSummary::start();
+    let frozen = match Analyzer::calculate_health_score(&state.db, distro.id).await {
+        Ok(ScoreOutcome::Scored(_) | ScoreOutcome::Unchanged(_)) => None,
+        Ok(ScoreOutcome::Frozen(freeze)) => Some(freeze.reason),
+        Err(e) => {
+            error!("Health score calculation failed for {}: {}", slug, e);
+            return ApiResponse::<()>::err(e.to_string()).into_response();
+        }
+    };
+
+    // Run post-analysis hooks in the background so the response isn't held up
+    if frozen.is_none() && !state.hooks.is_empty() {
+        if let Ok(Some(score)) = state.db.get_latest_health_score(distro.id).await {
+            summary.record_score(&distro, &score);
+        }
+        let state = state.clone();
+        tokio::spawn(async move { state.hooks.run(&summary.finish()).await });
+    }
+
+    #[derive(Serialize)]
+    struct CollectionResult {
+        message: String,
+        sources: Vec<SourceResult>,
+    }
+
+    let message = match frozen {
+        Some(reason) => format!("Collection completed for {}; scores are frozen: {}", slug, reason),
+        None => format!("Collection completed for {}", slug),
+    };
+    ApiResponse::ok(CollectionResult {
+        message,
+        sources: results,
+    })
+    .into_response()
+}
+
+/// Most distros accepted by one bulk collection request
+const MAX_BULK_DISTROS: usize = 100;
+
+#[derive(Deserialize)]
+pub struct BulkCollectRequest {
+    distros: Vec<String>,
+    /// Sources to collect; all of them when empty
+    #[serde(default)]
+    sources: Vec<String>,
+}
+
+/// Queue collection jobs for several distros and sources at once (admin endpoint)
+///
+/// Responds immediately with the job IDs; poll `/jobs/{id}` for progress. One request can
+/// queue thousands of jobs, so unlike single-distro triggers it's never public.
+pub async fn trigger_bulk_collection(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(request): Json<BulkCollectRequest>,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&state, &headers) {
+        return rejection.into_response();
+    }
+    if request.distros.is_empty() {
+        return ApiResponse::<()>::error(StatusCode::BAD_REQUEST, "distros must not be empty").into_response();
+    }
+    if request.distros.len() > MAX_BULK_DISTROS {
+        return ApiResponse::<()>::error(
+            StatusCode::BAD_REQUEST,
+            format!("At most {} distros per request", MAX_BULK_DISTROS),
+        )
+        .into_response();
+    }
+
+    let sources = match parse_sources(&request.sources) {
+        Ok(sources) => sources,
+        Err(e) => return ApiResponse::<()>::error(StatusCode::BAD_REQUEST, e).into_response(),
+    };
+
+    // Resolve every slug before queueing anything
+    let mut distros = Vec::new();
+    let mut missing = Vec::new();
+    for slug in &request.distros {
+        match state.db.get_distribution_by_slug(slug).await {
+            Ok(d) => distros.push(d),
+            Err(_) => missing.push(slug.as_str()),
+        }
+    }
+    if !missing.is_empty() {
+        return ApiResponse::<()>::error(
+            StatusCode::NOT_FOUND,
+            format!("Distributions not found: {}", missing.join(", ")),
+        )
+        .into_response();
+    }
+
+    let mut jobs = Vec::new();
+    for distro in &distros {
+        for source in &sources {
+            match queue_job(&state, distro, *source, false).await {
+                Ok(job) => jobs.push(job),
+                Err(e) => {
+                    error!("Failed to queue {} collection for {}: {}", source, distro.slug, e);
+                    return ApiResponse::<()>::err(e.to_string()).into_response();
+                }
+            }
+        }
+    }
+    state.job_signal.notify_one();
+
+    (StatusCode::ACCEPTED, ApiResponse::ok(jobs)).into_response()
+}
+
+/// Get the status of a queued collection job
+pub async fn get_job(State(state): State<SharedState>, Path(id): Path<i64>) -> impl IntoResponse {
+    match state.db.get_collection_job(id).await {
+        Ok(Some(job)) => ApiResponse::ok(job).into_response(),
+        Ok(None) => ApiResponse::<()>::error(StatusCode::NOT_FOUND, format!("Job not found: {}", id))
+            .into_response(),
+        Err(e) => ApiResponse::<()>::err(e.to_string()).into_response(),
+    }
+}
+
+/// Update a distribution's metadata (maintainer or admin endpoint)
+pub async fn update_distro(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+    headers: HeaderMap,
+    Json(update): Json<DistributionUpdate>,
+) -> impl IntoResponse {
+    let distro = match state.db.get_distribution_by_slug(&slug).await {
+        Ok(d) => d,
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response()
+        }
+    };
+
+    if let Err(rejection) = require_maintainer(&state, &headers, distro.id).await {
+        return rejection.into_response();
+    }
+
+    let admin_only = update.admin_only_fields();
+    if !admin_only.is_empty() && require_admin(&state, &headers).is_err() {
+        return ApiResponse::<()>::error(
+            StatusCode::FORBIDDEN,
+            format!("Only admins can change: {}", admin_only.join(", ")),
+        )
+        .into_response();
+    }
+
+    if let Some(ref aggregation) = update.aggregation {
+        if Aggregation::parse(aggregation).is_none() {
+            return ApiResponse::<()>::error(
+                StatusCode::BAD_REQUEST,
+                "aggregation must be one of: sum, mean, top-k, top-k:N",
+            )
+            .into_response();
+        }
+    }
+
+    if let Some(ref format) = update.mirror_list_format {
+        if MirrorListFormat::parse(format).is_none() {
+            return ApiResponse::<()>::error(
+                StatusCode::BAD_REQUEST,
+                format!("mirror_list_format must be one of: {}", MirrorListFormat::names()),
+            )
+            .into_response();
+        }
+    }
+
+    if let Some(ref kind) = update.forge_kind {
+        if ForgeKind::parse(kind).is_none() {
+            return ApiResponse::<()>::error(
+                StatusCode::BAD_REQUEST,
+                format!("forge_kind must be one of: {}", ForgeKind::names()),
+            )
+            .into_response();
+        }
+    }
+
+    if let Some(ref url) = update.forge_base_url {
+        let valid = reqwest::Url::parse(url).is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.has_host());
+        if !valid {
+            return ApiResponse::<()>::error(StatusCode::BAD_REQUEST, "forge_base_url must be an http(s) URL")
+                .into_response();
+        }
+    }
+
+    if let Some(ref url) = update.bugzilla_url {
+        let valid = reqwest::Url::parse(url).is_ok_and(|u| matches!(u.scheme(), "http" | "https") && u.has_host());
+        if !valid {
+            return ApiResponse::<()>::error(StatusCode::BAD_REQUEST, "bugzilla_url must be an http(s) URL")
+                .into_response();
+        }
+    }
+
+    if let Some(ref model) = update.release_model {
+        if !matches!(model.as_str(), "rolling" | "point") {
+            return ApiResponse::<()>::error(StatusCode::BAD_REQUEST, "release_model must be one of: rolling, point")
+                .into_response();
+        }
+    }
+
+    if let Some(ref format) = update.update_index_format {
+        if IndexFormat::parse(format).is_none() {
+            return ApiResponse::<()>::error(
+                StatusCode::BAD_REQUEST,
+                format!("update_index_format must be one of: {}", IndexFormat::names()),
+            )
+            .into_response();
+        }
+    }
+
+    if let Some(ref based_on) = update.based_on {
+        if based_on == &distro.slug || state.db.get_distribution_by_slug(based_on).await.is_err() {
+            return ApiResponse::<()>::error(
+                StatusCode::BAD_REQUEST,
+                "based_on must be the slug of another tracked distribution",
+            )
+            .into_response();
+        }
+    }
+
+    // One level only: a variant's parent is scored on its own data
+    if let Some(ref variant_of) = update.variant_of {
+        let parent_ok = variant_of != &distro.slug
+            && matches!(state.db.get_distribution_by_slug(variant_of).await, Ok(p) if p.variant_of.is_none());
+        if !parent_ok {
+            return ApiResponse::<()>::error(
+                StatusCode::BAD_REQUEST,
+                "variant_of must be the slug of another tracked distribution that isn't itself a variant",
+            )
+            .into_response();
+        }
+        // Its own variants would otherwise read their data from a distro that has none
+        match state.db.get_variants(&distro.slug).await {
+            Ok(variants) if variants.is_empty() => {}
+            Ok(_) => {
+                return ApiResponse::<()>::error(
+                    StatusCode::BAD_REQUEST,
+                    "A distribution with variants can't itself be a variant",
+                )
+                .into_response()
+            }
+            Err(e) => {
+                error!("Failed to look up variants of {}: {}", slug, e);
+                return ApiResponse::<()>::err(e.to_string()).into_response();
+            }
+        }
+    }
+
+    match state.db.update_distribution(distro.id, update).await {
+        Ok(updated) => ApiResponse::ok(updated).into_response(),
+        Err(e) => {
+            error!("Failed to update distro {}: {}", slug, e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ClaimRequest {
+    /// "domain" or "github"
+    method: String,
+    /// How moderators can reach the claimant
+    contact: String,
+}
+
+/// Start a maintainer claim on a distribution
+///
+/// Returns the challenge to publish and the API key, which stays inactive until verified.
+pub async fn create_claim(
+    State(state): State<SharedState>,
+    Path(slug): Path<String>,
+    Json(request): Json<ClaimRequest>,
+) -> impl IntoResponse {
+    let distro = match state.db.get_distribution_by_slug(&slug).await {
+        Ok(d) => d,
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", slug),
+            )
+            .into_response()
+        }
+    };
+
+    let instructions = match (request.method.as_str(), &distro.homepage, &distro.github_org) {
+        ("domain", Some(homepage), _) => format!(
+            "Publish the challenge as a line in {}{}",
+            homepage.trim_end_matches('/'),
+            DOMAIN_CHALLENGE_PATH
+        ),
+        ("github", _, Some(org)) => format!(
+            "Create the public repository {}/{} with the challenge in its description",
+            org, GITHUB_CHALLENGE_REPO
+        ),
+        ("domain", None, _) | ("github", _, None) => {
+            return ApiResponse::<()>::error(
+                StatusCode::BAD_REQUEST,
+                format!("{} has no {} configured to verify against", slug, request.method),
+            )
+            .into_response()
+        }
+        _ => {
+            return ApiResponse::<()>::error(
+                StatusCode::BAD_REQUEST,
+                "Claim method must be \"domain\" or \"github\"",
+            )
+            .into_response()
+        }
+    };
+
+    let new_claim = NewClaim {
+        distro_id: distro.id,
+        method: request.method,
+        contact: request.contact,
+        challenge: generate_token("dv-verify-"),
+    };
+
+    let claim = match state.db.insert_claim(new_claim).await {
+        Ok(c) => c,
+        Err(e) => return ApiResponse::<()>::err(e.to_string()).into_response(),
+    };
+
+    let api_key = generate_token("dvk_");
+    if let Err(e) = state.db.insert_maintainer(&claim, &hash_key(&api_key)).await {
+        return ApiResponse::<()>::err(e.to_string()).into_response();
+    }
+
+    #[derive(Serialize)]
+    struct ClaimCreated {
+        claim_id: i64,
+        challenge: String,
+        instructions: String,
+        api_key: String,
+    }
+
+    (
+        StatusCode::CREATED,
+        ApiResponse::ok(ClaimCreated {
+            claim_id: claim.id,
+            challenge: claim.challenge,
+            instructions,
+            api_key,
+        }),
+    )
+        .into_response()
+}
+
+/// Check a pending claim's challenge and activate the maintainer key on success
+pub async fn verify_claim(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+) -> impl IntoResponse {
+    let claim = match state.db.get_claim(id).await {
+        Ok(c) => c,
+        Err(_) => {
+            return ApiResponse::<()>::error(StatusCode::NOT_FOUND, format!("Claim not found: {}", id))
+                .into_response()
+        }
+    };
+
+    if claim.status != "pending" {
+        return ApiResponse::<()>::error(
+            StatusCode::CONFLICT,
+            format!("Claim is already {}", claim.status),
+        )
+        .into_response();
+    }
+
+    let distro = match state.db.get_distribution_by_id(claim.distro_id).await {
+        Ok(d) => d,
+        Err(e) => return ApiResponse::<()>::err(e.to_string()).into_response(),
+    };
+
+    let verifier = match ClaimVerifier::new(CollectorConfig::default()) {
+        Ok(v) => v,
+        Err(e) => return ApiResponse::<()>::err(e.to_string()).into_response(),
+    };
+
+    let verified = match (claim.method.as_str(), &distro.homepage, &distro.github_org) {
+        ("domain", Some(homepage), _) => verifier.verify_domain(homepage, &claim.challenge).await,
+        ("github", _, Some(org)) => verifier.verify_github(org, &claim.challenge).await,
+        _ => Ok(false),
+    };
+
+    match verified {
+        Ok(true) => {
+            if let Err(e) = state.db.verify_claim(claim.id).await {
+                return ApiResponse::<()>::err(e.to_string()).into_response();
+            }
+            match state.db.get_claim(claim.id).await {
+                Ok(claim) => ApiResponse::ok(claim).into_response(),
+                Err(e) => ApiResponse::<()>::err(e.to_string()).into_response(),
+            }
+        }
+        Ok(false) => ApiResponse::<()>::error(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "Challenge not found yet, publish it and retry",
+        )
+        .into_response(),
+        Err(e) => {
+            error!("Claim verification failed for claim {}: {}", claim.id, e);
+            ApiResponse::<()>::error(StatusCode::BAD_GATEWAY, e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ClaimsQuery {
+    #[serde(default = "default_claim_status")]
+    status: String,
+}
+
+fn default_claim_status() -> String {
+    "pending".to_string()
+}
+
+/// List claims for moderation (admin endpoint)
+pub async fn list_claims(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Query(query): Query<ClaimsQuery>,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&state, &headers) {
+        return rejection.into_response();
+    }
+
+    match state.db.get_claims_by_status(&query.status).await {
+        Ok(claims) => ApiResponse::ok(claims).into_response(),
+        Err(e) => ApiResponse::<()>::err(e.to_string()).into_response(),
+    }
+}
+
+/// List open configuration drift issues (admin endpoint)
+pub async fn list_config_issues(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&state, &headers) {
+        return rejection.into_response();
+    }
+
+    match state.db.get_open_config_issues().await {
+        Ok(issues) => ApiResponse::ok(issues).into_response(),
+        Err(e) => ApiResponse::<()>::err(e.to_string()).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FreezeListQuery {
+    /// Include freezes that have ended
+    #[serde(default)]
+    all: bool,
+}
+
+/// List score freezes in effect, or all of them with `?all=true` (admin endpoint)
+pub async fn list_score_freezes(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Query(query): Query<FreezeListQuery>,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&state, &headers) {
+        return rejection.into_response();
+    }
+
+    match state.db.get_score_freezes(query.all).await {
+        Ok(freezes) => ApiResponse::ok(freezes).into_response(),
+        Err(e) => ApiResponse::<()>::err(e.to_string()).into_response(),
+    }
+}
+
+#[derive(Deserialize)]
+pub struct FreezeRequest {
+    /// Distro to freeze; every distro when omitted
+    distro: Option<String>,
+    reason: String,
+    /// When the freeze lifts by itself; it lasts until lifted when omitted
+    until: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Stop recalculating one distro's score, or every score (admin endpoint)
+pub async fn create_score_freeze(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+    Json(request): Json<FreezeRequest>,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&state, &headers) {
+        return rejection.into_response();
+    }
+
+    if request.reason.trim().is_empty() {
+        return ApiResponse::<()>::error(StatusCode::BAD_REQUEST, "Freeze reason must not be empty")
+            .into_response();
+    }
+    if request.until.is_some_and(|until| until <= chrono::Utc::now()) {
+        return ApiResponse::<()>::error(StatusCode::BAD_REQUEST, "until must be in the future").into_response();
+    }
+
+    let distro_id = match &request.distro {
+        Some(slug) => match state.db.get_distribution_by_slug(slug).await {
+            Ok(distro) => Some(distro.id),
+            Err(_) => {
+                return ApiResponse::<()>::error(
+                    StatusCode::NOT_FOUND,
+                    format!("Distribution not found: {}", slug),
+                )
+                .into_response()
+            }
+        },
+        None => None,
+    };
+
+    let freeze = NewScoreFreeze {
+        distro_id,
+        reason: request.reason.trim().to_string(),
+        ends_at: request.until,
+    };
+    match state.db.insert_score_freeze(freeze).await {
+        Ok(created) => (StatusCode::CREATED, ApiResponse::ok(created)).into_response(),
+        Err(e) => {
+            error!("Failed to create score freeze: {}", e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+/// Lift a score freeze now (admin endpoint)
+pub async fn end_score_freeze(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&state, &headers) {
+        return rejection.into_response();
+    }
+
+    match state.db.end_score_freeze(id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => ApiResponse::<()>::error(
+            StatusCode::NOT_FOUND,
+            format!("No score freeze {} in effect", id),
+        )
+        .into_response(),
+        Err(e) => ApiResponse::<()>::err(e.to_string()).into_response(),
+    }
+}
+
+/// Window for per-collector success rates
+const COLLECTOR_HEALTH_DAYS: i64 = 7;
+
+/// Failed runs in a row before a distro is listed as repeatedly failing
+const REPEATED_FAILURES: i64 = 3;
+
+/// Collector status overview for operators (admin endpoint)
+///
+/// Last run and 7-day success rate per source, GitHub token budgets, the job queue,
+/// distros whose collections keep failing, and upstream circuit breakers.
+pub async fn get_admin_health(
+    State(state): State<SharedState>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&state, &headers) {
+        return rejection.into_response();
+    }
+
+    #[derive(Serialize)]
+    struct CollectorHealth {
+        source: &'static str,
+        last_run_at: Option<chrono::DateTime<chrono::Utc>>,
+        last_error: Option<String>,
+        runs_7d: i64,
+        failures_7d: i64,
+        /// Share of runs that succeeded, None without runs in the window
+        success_rate_7d: Option<f64>,
+    }
+
+    #[derive(Serialize)]
+    struct GithubBudget {
+        tokens_configured: usize,
+        /// Tokens GitHub accepted, with their remaining quota
+        tokens: Vec<TokenBudget>,
+    }
+
+    #[derive(Serialize)]
+    struct AdminHealth {
+        generated_at: chrono::DateTime<chrono::Utc>,
+        collectors: Vec<CollectorHealth>,
+        github_rate_limit: GithubBudget,
+        queue: QueueDepth,
+        repeated_failures: Vec<RepeatedFailure>,
+        circuit_breakers: Vec<BreakerStatus>,
+    }
+
+    let since = chrono::Utc::now() - chrono::TimeDelta::days(COLLECTOR_HEALTH_DAYS);
+    let stats = match state.db.get_collection_run_stats(since).await {
+        Ok(stats) => stats,
+        Err(e) => return ApiResponse::<()>::err(e.to_string()).into_response(),
+    };
+    let queue = match state.db.get_collection_queue_depth().await {
+        Ok(queue) => queue,
+        Err(e) => return ApiResponse::<()>::err(e.to_string()).into_response(),
+    };
+    let repeated_failures = match state.db.get_repeated_collection_failures(REPEATED_FAILURES).await {
+        Ok(failures) => failures,
+        Err(e) => return ApiResponse::<()>::err(e.to_string()).into_response(),
+    };
+    let circuit_breakers = match state.db.get_breaker_statuses().await {
+        Ok(breakers) => breakers,
+        Err(e) => return ApiResponse::<()>::err(e.to_string()).into_response(),
+    };
+
+    let collectors = Source::ALL
+        .iter()
+        .map(|source| {
+            let stats = stats.iter().find(|s| s.source == source.as_str());
+            let runs = stats.map_or(0, |s| s.runs);
+            let failures = stats.map_or(0, |s| s.failures);
+            CollectorHealth {
+                source: source.as_str(),
+                last_run_at: stats.and_then(|s| s.last_run_at),
+                last_error: stats.and_then(|s| s.last_error.clone()),
+                runs_7d: runs,
+                failures_7d: failures,
+                success_rate_7d: (runs > 0)
+                    .then(|| ((runs - failures) as f64 / runs as f64 * 1000.0).round() / 1000.0),
+            }
+        })
+        .collect();
+
+    // A fresh check of every token; /rate_limit doesn't count against the quota
+    let config = CollectorConfig::default();
+    let pool = TokenPool::new(&config.github_tokens);
+    if !pool.is_empty() {
+        match reqwest::Client::builder().user_agent(&config.user_agent).build() {
+            Ok(client) => pool.check(&client).await,
+            Err(e) => error!("Failed to build HTTP client: {}", e),
+        }
+    }
+
+    ApiResponse::ok(AdminHealth {
+        generated_at: chrono::Utc::now(),
+        collectors,
+        github_rate_limit: GithubBudget {
+            tokens_configured: config.github_tokens.len(),
+            tokens: pool.budgets(),
+        },
+        queue,
+        repeated_failures,
+        circuit_breakers,
     })
     .into_response()
 }
+
+/// Reject a pending claim (admin endpoint)
+pub async fn reject_claim(
+    State(state): State<SharedState>,
+    Path(id): Path<i64>,
+    headers: HeaderMap,
+) -> impl IntoResponse {
+    if let Err(rejection) = require_admin(&state, &headers) {
+        return rejection.into_response();
+    }
+
+    if let Err(e) = state.db.reject_claim(id).await {
+        return ApiResponse::<()>::err(e.to_string()).into_response();
+    }
+
+    match state.db.get_claim(id).await {
+        Ok(claim) => ApiResponse::ok(claim).into_response(),
+        Err(_) => ApiResponse::<()>::error(StatusCode::NOT_FOUND, format!("Claim not found: {}", id))
+            .into_response(),
+    }
+}
+
+/// Maximum suggestions accepted per client per day
+const SUGGESTIONS_PER_DAY: i64 = 3;
+
+#[derive(Deserialize)]
+pub struct SuggestionRequest {
+    name: String,
+    slug: String,
+    homepage: Option<String>,
+    github_org: Option<String>,
+    subreddit: Option<String>,
+    description: Option<String>,
+    #[serde(default = "default_os_family")]
+    os_family: String,
+    reason: Option<String>,
+    /// Token from the captcha widget, required when captcha is configured
+    captcha_token: Option<String>,
+}
+
+fn default_os_family() -> String {
+    "linux".to_string()
+}
+
+impl SuggestionRequest {
+    fn validate(&self) -> std::result::Result<(), String> {
+        let slug_ok = !self.slug.is_empty()
+            && self.slug.len() <= 32
+            && self
+                .slug
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+
+        if !slug_ok {
+            return Err("slug must be 1-32 characters of a-z, 0-9 and '-'".to_string());
+        }
+        if self.name.trim().is_empty() || self.name.len() > 100 {
+            return Err("name must be 1-100 characters".to_string());
+        }
+        if !["linux", "bsd", "haiku", "other"].contains(&self.os_family.as_str()) {
+            return Err("os_family must be one of linux, bsd, haiku, other".to_string());
+        }
+        if let Some(ref homepage) = self.homepage {
+            if !homepage.starts_with("https://") && !homepage.starts_with("http://") {
+                return Err("homepage must be an http(s) URL".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Identify the requesting client: the address the configured proxy reports, otherwise
+/// the peer's
+///
+/// Clients can send any of these headers themselves, so one is only read when a proxy
+/// that sets it is configured. Proxies append to `x-forwarded-for`, making the last
+/// entry the one the trusted proxy added.
+fn client_ip(state: &AppState, headers: &HeaderMap, addr: SocketAddr) -> String {
+    state
+        .client_ip_header
+        .as_ref()
+        .and_then(|name| headers.get(name))
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.rsplit(',').next())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| addr.ip().to_string())
+}
+
+/// Propose a new distribution to track
+pub async fn create_suggestion(
+    State(state): State<SharedState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(request): Json<SuggestionRequest>,
+) -> impl IntoResponse {
+    if let Err(message) = request.validate() {
+        return ApiResponse::<()>::error(StatusCode::BAD_REQUEST, message).into_response();
+    }
+
+    if let Some(ref captcha) = state.captcha {
+        let passed = match request.captcha_token.as_deref() {
+            Some(token) => captcha.verify(token).await,
+            None => false,
+        };
+        if !passed {
+            return ApiResponse::<()>::error(StatusCode::FORBIDDEN, "Captcha verification failed")
+                .into_response();
+        }
+    }
+
+    let client_hash = hash_key(&client_ip(&state, &headers, addr));
+    match state.db.count_recent_suggestions(&client_hash, 24).await {
+        Ok(count) if count >= SUGGESTIONS_PER_DAY => {
+            return ApiResponse::<()>::error(
+                StatusCode::TOO_MANY_REQUESTS,
+                "Too many suggestions, please try again tomorrow",
+            )
+            .into_response()
+        }
+        Ok(_) => {}
+        Err(e) => return ApiResponse::<()>::err(e.to_string()).into_response(),
+    }
+
+    match state.db.slug_in_use(&request.slug).await {
+        Ok(true) => {
+            return ApiResponse::<()>::error(
+                StatusCode::CONFLICT,
+                format!("{} is already tracked or suggested", request.slug),
+            )
+            .into_response()
+        }
+        Ok(false) => {}
+        Err(e) => return ApiResponse::<()>::err(e.to_string()).into_response(),
+    }
+
+    let suggestion = NewSuggestion {
+        name: request.name.trim().to_string(),
+        slug: request.slug,
+        homepage: request.homepage,
+        github_org: request.github_org,
+        subreddit: request.subreddit,
+        description: request.description,
+        os_family: request.os_family,
+        reason: request.reason,
+        client_hash,
+    };
+
+    match state.db.insert_suggestion(suggestion).await {
+        Ok(created) => (StatusCode::CREATED, ApiResponse::ok(created)).into_response(),
+        Err(e) => {
+            error!("Failed to store suggestion: {}", e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
+
+/// Maximum pings counted per client address per day; generous for installs behind one NAT
+const PINGS_PER_CLIENT_PER_DAY: i64 = 200;
+
+#[derive(Deserialize)]
+pub struct PingRequest {
+    /// Slug of the distribution the install runs
+    distro: String,
+    /// SHA-256 of an install-specific ID and a salt chosen by the distro, in hex
+    install_id: String,
+}
+
+/// Count an opt-in ping from a distro's welcome app towards its install base
+///
+/// Nothing identifying is stored: installs are kept by a hash of the hash the client sends,
+/// and per-address limits by a hash of the address. Counts are only published once a distro
+/// has enough active installs that none stands out (see `MIN_PING_INSTALLS`).
+///
+/// The address is the peer's unless a trusted proxy is configured, so the limit can't be
+/// dodged with forged headers. Install IDs are still the client's word, which is why the
+/// count is published as unverified.
+pub async fn record_ping(
+    State(state): State<SharedState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(request): Json<PingRequest>,
+) -> impl IntoResponse {
+    let hashed = request.install_id.len() == 64 && request.install_id.chars().all(|c| c.is_ascii_hexdigit());
+    if !hashed {
+        return ApiResponse::<()>::error(StatusCode::BAD_REQUEST, "install_id must be a hex SHA-256 hash")
+            .into_response();
+    }
+
+    let distro = match state.db.get_distribution_by_slug(&request.distro).await {
+        Ok(distro) => distro,
+        Err(_) => {
+            return ApiResponse::<()>::error(
+                StatusCode::NOT_FOUND,
+                format!("Distribution not found: {}", request.distro),
+            )
+            .into_response()
+        }
+    };
+
+    let install_hash = hash_key(&request.install_id.to_ascii_lowercase());
+    let client_hash = hash_key(&client_ip(&state, &headers, addr));
+    match state
+        .db
+        .record_install_ping(distro.id, &install_hash, &client_hash, PINGS_PER_CLIENT_PER_DAY)
+        .await
+    {
+        Ok(true) => (StatusCode::ACCEPTED, ApiResponse::ok(())).into_response(),
+        Ok(false) => {
+            ApiResponse::<()>::error(StatusCode::TOO_MANY_REQUESTS, "Too many pings from this address today")
+                .into_response()
+        }
+        Err(e) => {
+            error!("Failed to record ping: {}", e);
+            ApiResponse::<()>::err(e.to_string()).into_response()
+        }
+    }
+}
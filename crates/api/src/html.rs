@@ -0,0 +1,184 @@
+//! HTML views of API responses for people opening API URLs in a browser
+//!
+//! Browsers ask for `text/html` ahead of anything else, so when a JSON response is about
+//! to go to one it's rendered as a plain page instead: objects as tables, lists of records
+//! as tables with a column per field. Anything asking for JSON, or for everything equally
+//! like curl, gets JSON as before; `?format=json` forces it from a browser.
+
+use crate::handlers::xml_escape;
+use axum::{
+    body::Body,
+    extract::{OriginalUri, Request},
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+use serde_json::{Map, Value};
+
+/// Largest JSON body rendered as HTML; bigger ones go out as JSON
+const MAX_RENDERED_BYTES: usize = 4 * 1024 * 1024;
+
+/// Render JSON responses as HTML for clients that prefer it
+pub async fn negotiate(request: Request, next: Next) -> Response {
+    let html = prefers_html(request.headers()) && !forces_json(request.uri().query());
+    // Nested routers see the path without the /api/v1 prefix
+    let uri = match request.extensions().get::<OriginalUri>() {
+        Some(OriginalUri(uri)) => uri.clone(),
+        None => request.uri().clone(),
+    };
+    let path = uri.path().to_string();
+    let query = uri.query().map(str::to_string);
+
+    let mut response = next.run(request).await;
+    response.headers_mut().append(header::VARY, HeaderValue::from_static("accept"));
+    if !html || !is_json(response.headers()) {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_RENDERED_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+    let Ok(value) = serde_json::from_slice::<Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let page = render_page(&path, query.as_deref(), &value);
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("text/html; charset=utf-8"));
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(page))
+}
+
+/// Whether `text/html` is accepted with a higher quality than JSON
+fn prefers_html(headers: &HeaderMap) -> bool {
+    let Some(accept) = headers.get(header::ACCEPT).and_then(|v| v.to_str().ok()) else {
+        return false;
+    };
+
+    let mut html = 0.0;
+    let mut json = None;
+    let mut any = 0.0;
+    for entry in accept.split(',') {
+        let mut params = entry.split(';');
+        let media_type = params.next().unwrap_or("").trim().to_ascii_lowercase();
+        let quality = params
+            .filter_map(|p| p.trim().strip_prefix("q="))
+            .find_map(|q| q.parse::<f32>().ok())
+            .unwrap_or(1.0);
+        match media_type.as_str() {
+            "text/html" => html = quality,
+            "application/json" => json = Some(quality),
+            "*/*" => any = quality,
+            _ => {}
+        }
+    }
+
+    html > json.unwrap_or(any)
+}
+
+fn forces_json(query: Option<&str>) -> bool {
+    query.is_some_and(|q| q.split('&').any(|pair| pair == "format=json"))
+}
+
+fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/json") || ct.starts_with("application/problem+json"))
+}
+
+fn render_page(path: &str, query: Option<&str>, value: &Value) -> String {
+    let title = match query {
+        Some(q) => format!("{}?{}", path, q),
+        None => path.to_string(),
+    };
+    let json_link = match query {
+        Some(q) => format!("{}?{}&format=json", path, q),
+        None => format!("{}?format=json", path),
+    };
+
+    // Unwrap the response envelope; the error message is the only interesting part of a failure
+    let content = match value {
+        Value::Object(envelope) if envelope.contains_key("success") => match envelope.get("error") {
+            Some(Value::String(error)) => format!("<p class=\"error\">{}</p>", xml_escape(error)),
+            _ => render(envelope.get("data").unwrap_or(&Value::Null)),
+        },
+        other => render(other),
+    };
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{title} · DistroVitals API</title>\n<style>{STYLE}</style>\n</head>\n<body>\n\
+         <h1>{title}</h1>\n<p class=\"meta\"><a href=\"{json}\">View as JSON</a></p>\n{content}\n</body>\n</html>\n",
+        title = xml_escape(&title),
+        json = xml_escape(&json_link),
+        content = content,
+    )
+}
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;margin:2rem;color:#1f2328;background:#fff}\
+h1{font-size:1.2rem;font-family:ui-monospace,monospace}\
+table{border-collapse:collapse;margin:.25rem 0}\
+th,td{border:1px solid #d0d7de;padding:.25rem .5rem;text-align:left;vertical-align:top}\
+th{background:#f6f8fa}.null{color:#8c959f}.error{color:#cf222e}.meta{font-size:.9rem}\
+@media (prefers-color-scheme:dark){body{color:#e6edf3;background:#0d1117}th{background:#161b22}\
+th,td{border-color:#30363d}a{color:#58a6ff}}";
+
+fn render(value: &Value) -> String {
+    match value {
+        Value::Null => "<span class=\"null\">null</span>".to_string(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        Value::String(s) if s.starts_with("https://") || s.starts_with("http://") => {
+            format!("<a href=\"{0}\">{0}</a>", xml_escape(s))
+        }
+        Value::String(s) => xml_escape(s),
+        Value::Array(items) if items.is_empty() => "<span class=\"null\">none</span>".to_string(),
+        Value::Array(items) => match records(items) {
+            Some(rows) => render_records(&rows),
+            None => {
+                let items: String = items.iter().map(|item| format!("<li>{}</li>", render(item))).collect();
+                format!("<ol>{}</ol>", items)
+            }
+        },
+        Value::Object(fields) => {
+            let rows: String = fields
+                .iter()
+                .map(|(key, value)| format!("<tr><th>{}</th><td>{}</td></tr>", xml_escape(key), render(value)))
+                .collect();
+            format!("<table>{}</table>", rows)
+        }
+    }
+}
+
+/// The items as objects, when every item is one
+fn records(items: &[Value]) -> Option<Vec<&Map<String, Value>>> {
+    items.iter().map(Value::as_object).collect()
+}
+
+/// Objects as rows of one table, with a column for every field any of them has
+fn render_records(rows: &[&Map<String, Value>]) -> String {
+    let mut columns: Vec<&str> = Vec::new();
+    for row in rows {
+        for key in row.keys() {
+            if !columns.contains(&key.as_str()) {
+                columns.push(key);
+            }
+        }
+    }
+
+    let header: String = columns.iter().map(|c| format!("<th>{}</th>", xml_escape(c))).collect();
+    let body: String = rows
+        .iter()
+        .map(|row| {
+            let cells: String = columns
+                .iter()
+                .map(|c| format!("<td>{}</td>", row.get(*c).map(render).unwrap_or_default()))
+                .collect();
+            format!("<tr>{}</tr>", cells)
+        })
+        .collect();
+    format!("<table><thead><tr>{}</tr></thead><tbody>{}</tbody></table>", header, body)
+}
@@ -0,0 +1,172 @@
+//! Background worker for queued collection jobs
+//!
+//! Jobs run one at a time so bulk triggers never multiply concurrent calls to GitHub,
+//! Reddit or Repology. Each finished job recalculates the distro's health score. A job
+//! that fails transiently (a 502, a rate limit) goes back in the queue with a delay; one
+//! that can't succeed as configured (an org that doesn't exist) fails straight away.
+
+use crate::{AppState, SharedState};
+use distrovitals_analyzer::Analyzer;
+use distrovitals_collector::sources::{Source, SourceCollectors, SourceOutcome};
+use distrovitals_collector::{CollectorConfig, CollectorError};
+use distrovitals_database::{CollectionJob, DatabaseError};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{error, info, warn};
+
+/// How often the worker checks the queue when nobody wakes it
+const POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Runs of a job before a transient failure is final
+const MAX_ATTEMPTS: i64 = 3;
+
+/// Delay before the first retry, doubled for each one after
+const RETRY_DELAY: chrono::TimeDelta = chrono::TimeDelta::minutes(1);
+
+/// How collection triggers without the admin token are handled, from `DV_PUBLIC_TRIGGERS`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerMode {
+    /// Collect straight away, as for admins ("run", the default)
+    Run,
+    /// Queue a low-priority refresh, at most one per distro and source an hour ("queue"),
+    /// so the public site can offer a refresh button
+    Queue,
+    /// Reject them ("off")
+    Off,
+}
+
+impl TriggerMode {
+    pub fn from_env() -> Self {
+        match std::env::var("DV_PUBLIC_TRIGGERS").ok().as_deref().map(str::trim) {
+            None | Some("") | Some("run") => Self::Run,
+            Some("queue") => Self::Queue,
+            Some("off") => Self::Off,
+            Some(other) => {
+                warn!("Ignoring DV_PUBLIC_TRIGGERS={:?}: expected run, queue or off", other);
+                Self::Run
+            }
+        }
+    }
+}
+
+/// How a job ended
+enum Outcome {
+    Done,
+    /// Nothing to collect, e.g. no subreddit configured
+    Skipped(String),
+}
+
+/// Why a job failed
+struct Failure {
+    message: String,
+    /// Worth running again later
+    retryable: bool,
+}
+
+impl Failure {
+    fn permanent(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            retryable: false,
+        }
+    }
+}
+
+impl From<CollectorError> for Failure {
+    fn from(e: CollectorError) -> Self {
+        Self {
+            message: e.to_string(),
+            retryable: e.retryable(),
+        }
+    }
+}
+
+impl From<DatabaseError> for Failure {
+    fn from(e: DatabaseError) -> Self {
+        Self {
+            message: e.to_string(),
+            retryable: true,
+        }
+    }
+}
+
+/// Start the job worker on the current runtime
+pub fn spawn_job_worker(state: SharedState) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        match state.db.requeue_running_collection_jobs().await {
+            Ok(0) => {}
+            Ok(n) => info!("Requeued {} interrupted collection jobs", n),
+            Err(e) => error!("Failed to requeue interrupted collection jobs: {}", e),
+        }
+
+        loop {
+            match state.db.claim_collection_job().await {
+                Ok(Some(job)) => run_job(&state, job).await,
+                Ok(None) => {
+                    let _ = tokio::time::timeout(POLL_INTERVAL, state.job_signal.notified()).await;
+                }
+                Err(e) => {
+                    error!("Failed to claim collection job: {}", e);
+                    tokio::time::sleep(POLL_INTERVAL).await;
+                }
+            }
+        }
+    })
+}
+
+async fn run_job(state: &AppState, job: CollectionJob) {
+    info!("Running {} collection job {} for {}", job.source, job.id, job.distro_slug);
+
+    let (status, message) = match collect(state, &job).await {
+        Ok(Outcome::Done) => ("done", None),
+        Ok(Outcome::Skipped(reason)) => ("skipped", Some(reason)),
+        Err(failure) if failure.retryable && job.attempts < MAX_ATTEMPTS => {
+            let delay = RETRY_DELAY * 2i32.pow(job.attempts.saturating_sub(1) as u32);
+            warn!(
+                "Collection job {} failed (attempt {} of {}), retrying in {}s: {}",
+                job.id,
+                job.attempts,
+                MAX_ATTEMPTS,
+                delay.num_seconds(),
+                failure.message
+            );
+            let run_after = chrono::Utc::now() + delay;
+            if let Err(e) = state.db.retry_collection_job(job.id, &failure.message, run_after).await {
+                error!("Failed to requeue collection job {}: {}", job.id, e);
+            }
+            return;
+        }
+        Err(failure) => {
+            warn!("Collection job {} failed: {}", job.id, failure.message);
+            ("failed", Some(failure.message))
+        }
+    };
+
+    if let Err(e) = state
+        .db
+        .finish_collection_job(job.id, status, message.as_deref())
+        .await
+    {
+        error!("Failed to record result of collection job {}: {}", job.id, e);
+    }
+}
+
+async fn collect(state: &AppState, job: &CollectionJob) -> Result<Outcome, Failure> {
+    let db = &state.db;
+    let source = Source::parse(&job.source)
+        .ok_or_else(|| Failure::permanent(format!("Unknown source: {}", job.source)))?;
+    let distro = db.get_distribution_by_id(job.distro_id).await?;
+
+    let collectors = SourceCollectors::new(CollectorConfig::default())?;
+    match collectors.collect(db, &distro, source).await? {
+        SourceOutcome::Collected(_) => {}
+        SourceOutcome::Skipped(reason) => return Ok(Outcome::Skipped(reason.to_string())),
+    }
+
+    // Collection succeeded; running it again wouldn't fix the analysis
+    Analyzer::calculate_health_score(db, distro.id)
+        .await
+        .map_err(|e| Failure::permanent(format!("Health score calculation failed: {}", e)))?;
+
+    Ok(Outcome::Done)
+}
@@ -2,23 +2,97 @@
 //!
 //! Axum-based REST API and static file server.
 
+mod auth;
+mod captcha;
+mod charts;
+mod dev;
+mod discovery;
 mod handlers;
+mod html;
+mod jobs;
+mod og;
+mod pagination;
 mod routes;
+mod validation;
+mod versioning;
 
+pub use dev::with_dev_proxy;
+pub use jobs::spawn_job_worker;
 pub use routes::create_router;
 
+use axum::http::HeaderName;
+use captcha::CaptchaConfig;
+use distrovitals_analyzer::PostAnalysisHooks;
 use distrovitals_database::Database;
+use jobs::TriggerMode;
+use og::ShareCardCache;
 use std::sync::Arc;
+use tokio::sync::Notify;
+
+/// Site address used when `DV_PUBLIC_URL` isn't set
+const DEFAULT_PUBLIC_URL: &str = "https://distrovitals.org";
 
 /// Shared application state
 #[derive(Clone)]
 pub struct AppState {
     pub db: Database,
+    /// Read replica for public read endpoints, when configured
+    pub replica: Option<Database>,
+    /// Bearer token required by admin endpoints (from `DV_ADMIN_TOKEN`)
+    pub admin_token: Option<String>,
+    /// Captcha verification for public submissions, disabled when unset
+    pub captcha: Option<CaptchaConfig>,
+    /// Hooks run after each triggered analysis (from `DV_ANALYSIS_HOOKS`)
+    pub hooks: PostAnalysisHooks,
+    /// Wakes the job worker when collection jobs are queued
+    pub job_signal: Arc<Notify>,
+    /// What collection triggers without the admin token do
+    pub public_triggers: TriggerMode,
+    /// Public address of the site, used for absolute links in the sitemap and
+    /// structured data (from `DV_PUBLIC_URL`)
+    pub public_url: String,
+    /// Rendered Open Graph images, reused until a distro's score changes
+    pub share_cards: Arc<ShareCardCache>,
+    /// Header a trusted reverse proxy puts the client's address in (from
+    /// `DV_CLIENT_IP_HEADER`, e.g. `fly-client-ip`); without one the peer address is used
+    pub client_ip_header: Option<HeaderName>,
 }
 
 impl AppState {
     pub fn new(db: Database) -> Self {
-        Self { db }
+        Self {
+            db,
+            replica: None,
+            admin_token: std::env::var("DV_ADMIN_TOKEN").ok().filter(|t| !t.is_empty()),
+            captcha: CaptchaConfig::from_env(),
+            hooks: PostAnalysisHooks::from_env(),
+            job_signal: Arc::new(Notify::new()),
+            public_triggers: TriggerMode::from_env(),
+            public_url: std::env::var("DV_PUBLIC_URL")
+                .ok()
+                .filter(|url| !url.is_empty())
+                .unwrap_or_else(|| DEFAULT_PUBLIC_URL.to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            share_cards: Arc::new(ShareCardCache::default()),
+            client_ip_header: std::env::var("DV_CLIENT_IP_HEADER")
+                .ok()
+                .and_then(|name| HeaderName::try_from(name.trim()).ok()),
+        }
+    }
+
+    /// Serve public reads from a replica instead of the primary
+    pub fn with_replica(mut self, replica: Database) -> Self {
+        self.replica = Some(replica);
+        self
+    }
+
+    /// Database for public read endpoints: the replica if there is one
+    ///
+    /// Anything that writes, or reads back what was just written (auth, jobs, admin
+    /// views), uses `db` so it never sees replication lag.
+    pub fn reads(&self) -> &Database {
+        self.replica.as_ref().unwrap_or(&self.db)
     }
 }
 
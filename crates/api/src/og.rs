@@ -0,0 +1,312 @@
+//! Open Graph share cards: a PNG summary of a distro's health for link previews
+//!
+//! Cards are drawn onto a plain RGB canvas with a built-in 5x7 bitmap font and encoded
+//! as PNG directly, which keeps font files and a rendering stack out of the server for
+//! what is a handful of rectangles, lines and upper-case text.
+
+use chrono::{DateTime, Utc};
+use flate2::{write::ZlibEncoder, Compression};
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::Mutex;
+
+const WIDTH: usize = 1200;
+const HEIGHT: usize = 630;
+const MARGIN: usize = 80;
+
+type Rgb = [u8; 3];
+
+// Colours from the frontend stylesheet
+const BG_PRIMARY: Rgb = [0x0d, 0x11, 0x17];
+const BG_CARD: Rgb = [0x21, 0x26, 0x2d];
+const TEXT_PRIMARY: Rgb = [0xf0, 0xf6, 0xfc];
+const TEXT_SECONDARY: Rgb = [0x8b, 0x94, 0x9e];
+const ACCENT: Rgb = [0x58, 0xa6, 0xff];
+const GREEN: Rgb = [0x3f, 0xb9, 0x50];
+const RED: Rgb = [0xf8, 0x51, 0x49];
+const YELLOW: Rgb = [0xd2, 0x99, 0x22];
+const BORDER: Rgb = [0x30, 0x36, 0x3d];
+
+/// What goes on a distro's share card
+pub struct ShareCard<'a> {
+    pub name: &'a str,
+    pub score: Option<f64>,
+    /// "up", "down" or "stable"
+    pub trend: Option<&'a str>,
+    /// Overall scores, oldest first
+    pub sparkline: &'a [f64],
+}
+
+impl ShareCard<'_> {
+    /// Render the card as a 1200x630 PNG
+    pub fn render(&self) -> Vec<u8> {
+        let mut canvas = Canvas::new(WIDTH, HEIGHT, BG_PRIMARY);
+        canvas.fill_rect(0, 0, WIDTH, 12, ACCENT);
+
+        canvas.text(MARGIN, 60, "DISTROVITALS", 5, TEXT_SECONDARY);
+
+        // Shrink long names before resorting to truncation
+        let width = WIDTH - 2 * MARGIN;
+        let scale = [12, 9, 7]
+            .into_iter()
+            .find(|&scale| text_width(self.name, scale) <= width)
+            .unwrap_or(7);
+        let name = fit(self.name, scale, width);
+        canvas.text(MARGIN, 130, &name, scale, TEXT_PRIMARY);
+
+        let (score_text, score_colour) = match self.score {
+            Some(score) => (format!("{:.1}", score), score_colour(score)),
+            None => ("--".to_string(), TEXT_SECONDARY),
+        };
+        canvas.text(MARGIN, 260, &score_text, 16, score_colour);
+        let after_score = MARGIN + text_width(&score_text, 16) + 24;
+        canvas.text(after_score, 260 + 16 * 7 - 8 * 7, "/100", 8, TEXT_SECONDARY);
+
+        if let Some(trend) = self.trend {
+            let (label, colour) = match trend {
+                "up" => ("TRENDING UP", GREEN),
+                "down" => ("TRENDING DOWN", RED),
+                _ => ("STABLE", TEXT_SECONDARY),
+            };
+            let x = WIDTH - MARGIN - text_width(label, 5);
+            canvas.text(x, 260 + 16 * 7 - 5 * 7, label, 5, colour);
+        }
+
+        let (top, bottom) = (430, 560);
+        canvas.fill_rect(MARGIN, top - 20, width, bottom - top + 40, BG_CARD);
+        canvas.fill_rect(MARGIN, bottom + 20, width, 2, BORDER);
+        let line_colour = match self.trend {
+            Some("up") => GREEN,
+            Some("down") => RED,
+            _ => ACCENT,
+        };
+        if self.sparkline.len() < 2 {
+            let note = "NOT ENOUGH HISTORY YET";
+            let x = MARGIN + (width - text_width(note, 4)) / 2;
+            canvas.text(x, (top + bottom) / 2 - 14, note, 4, TEXT_SECONDARY);
+        } else {
+            canvas.sparkline(self.sparkline, MARGIN + 20, top, width - 40, bottom - top, line_colour);
+        }
+
+        canvas.encode_png()
+    }
+}
+
+/// Same bands as the frontend's score classes
+fn score_colour(score: f64) -> Rgb {
+    if score >= 70.0 {
+        GREEN
+    } else if score >= 40.0 {
+        YELLOW
+    } else {
+        RED
+    }
+}
+
+/// A rendered PNG and the score time it was rendered for
+type CachedCard = (Option<DateTime<Utc>>, Vec<u8>);
+
+/// Rendered PNGs by slug, kept until the distro's latest score changes
+#[derive(Default)]
+pub struct ShareCardCache {
+    cards: Mutex<HashMap<String, CachedCard>>,
+}
+
+impl ShareCardCache {
+    /// The cached card for a slug, if it was rendered from the score calculated at `scored_at`
+    pub fn get(&self, slug: &str, scored_at: Option<DateTime<Utc>>) -> Option<Vec<u8>> {
+        let cards = self.cards.lock().unwrap();
+        cards
+            .get(slug)
+            .filter(|(rendered_for, _)| *rendered_for == scored_at)
+            .map(|(_, png)| png.clone())
+    }
+
+    pub fn insert(&self, slug: &str, scored_at: Option<DateTime<Utc>>, png: Vec<u8>) {
+        self.cards.lock().unwrap().insert(slug.to_string(), (scored_at, png));
+    }
+}
+
+struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<Rgb>,
+}
+
+impl Canvas {
+    fn new(width: usize, height: usize, background: Rgb) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![background; width * height],
+        }
+    }
+
+    fn fill_rect(&mut self, x: usize, y: usize, w: usize, h: usize, colour: Rgb) {
+        for row in y.min(self.height)..(y + h).min(self.height) {
+            let start = row * self.width;
+            self.pixels[start + x.min(self.width)..start + (x + w).min(self.width)].fill(colour);
+        }
+    }
+
+    /// Draw upper-cased text with its top-left corner at (x, y); each font pixel is
+    /// `scale` pixels square
+    fn text(&mut self, x: usize, y: usize, text: &str, scale: usize, colour: Rgb) {
+        for (i, c) in text.chars().enumerate() {
+            let left = x + i * 6 * scale;
+            for (row, bits) in glyph(c).iter().enumerate() {
+                for col in 0..5 {
+                    if bits & (0x10 >> col) != 0 {
+                        self.fill_rect(left + col * scale, y + row * scale, scale, scale, colour);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Plot at least two values as a line scaled to fill the box
+    fn sparkline(&mut self, values: &[f64], x: usize, y: usize, w: usize, h: usize, colour: Rgb) {
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        // Flat lines sit in the middle instead of along the bottom
+        let (low, span) = if max - min < 1.0 {
+            (min - 0.5, 1.0)
+        } else {
+            (min, max - min)
+        };
+
+        let points: Vec<(f64, f64)> = values
+            .iter()
+            .enumerate()
+            .map(|(i, value)| {
+                let px = x as f64 + i as f64 / (values.len() - 1) as f64 * w as f64;
+                let py = (y + h) as f64 - (value - low) / span * h as f64;
+                (px, py)
+            })
+            .collect();
+
+        for pair in points.windows(2) {
+            self.line(pair[0], pair[1], 6, colour);
+        }
+    }
+
+    /// A line `thickness` pixels wide, stamped one square per step along its length
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), thickness: usize, colour: Rgb) {
+        let steps = (to.0 - from.0).abs().max((to.1 - from.1).abs()).ceil().max(1.0) as usize;
+        let half = thickness as f64 / 2.0;
+        for step in 0..=steps {
+            let t = step as f64 / steps as f64;
+            let px = from.0 + (to.0 - from.0) * t - half;
+            let py = from.1 + (to.1 - from.1) * t - half;
+            self.fill_rect(px.max(0.0) as usize, py.max(0.0) as usize, thickness, thickness, colour);
+        }
+    }
+
+    /// Encode as an 8-bit RGB PNG, unfiltered
+    fn encode_png(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(self.height * (1 + self.width * 3));
+        for row in self.pixels.chunks(self.width) {
+            raw.push(0); // filter type: none
+            raw.extend(row.iter().flatten());
+        }
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&raw).expect("writing to a Vec can't fail");
+        let data = encoder.finish().expect("writing to a Vec can't fail");
+
+        let mut header = Vec::with_capacity(13);
+        header.extend((self.width as u32).to_be_bytes());
+        header.extend((self.height as u32).to_be_bytes());
+        // Bit depth 8, colour type 2 (RGB), default compression, filtering and no interlace
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &data);
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    png.extend(kind);
+    png.extend(data);
+
+    let mut crc = crc32fast::Hasher::new();
+    crc.update(kind);
+    crc.update(data);
+    png.extend(crc.finalize().to_be_bytes());
+}
+
+/// Width in pixels of text drawn at `scale`, without trailing spacing
+fn text_width(text: &str, scale: usize) -> usize {
+    (text.chars().count() * 6).saturating_sub(1) * scale
+}
+
+/// Truncate text with an ellipsis so it fits within `width` pixels
+fn fit(text: &str, scale: usize, width: usize) -> String {
+    if text_width(text, scale) <= width {
+        return text.to_string();
+    }
+    let keep = (width / scale + 1) / 6 - 3;
+    format!("{}...", text.chars().take(keep).collect::<String>().trim_end())
+}
+
+/// Rows of a 5x7 glyph, high bit on the left; lower case is drawn as upper case and
+/// anything unsupported as a question mark
+fn glyph(c: char) -> [u8; 7] {
+    match c.to_ascii_uppercase() {
+        'A' => [0x0E, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'B' => [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+        'C' => [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+        'D' => [0x1E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x1E],
+        'E' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+        'F' => [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+        'G' => [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+        'H' => [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+        'I' => [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        'J' => [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+        'K' => [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+        'L' => [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+        'M' => [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+        'N' => [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+        'O' => [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'P' => [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+        'Q' => [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+        'R' => [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+        'S' => [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+        'T' => [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+        'U' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+        'V' => [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+        'W' => [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+        'X' => [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+        'Y' => [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+        'Z' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+        '0' => [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+        '1' => [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+        '2' => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+        '3' => [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+        '4' => [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+        '5' => [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+        '6' => [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+        '7' => [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+        '8' => [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+        '9' => [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+        ' ' => [0x00; 7],
+        '.' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+        ',' => [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+        '-' => [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+        '_' => [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+        '/' => [0x01, 0x01, 0x02, 0x04, 0x08, 0x10, 0x10],
+        ':' => [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+        '%' => [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+        '+' => [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+        '(' => [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+        ')' => [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+        '\'' => [0x0C, 0x04, 0x08, 0x00, 0x00, 0x00, 0x00],
+        '!' => [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+        '&' => [0x0C, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0D],
+        _ => [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    }
+}
@@ -0,0 +1,142 @@
+//! Time-window and cursor pagination for history endpoints and rankings
+
+use crate::validation::{Checks, Validate};
+use chrono::{DateTime, Utc};
+use distrovitals_database::{parse_time_bound, TimeWindow};
+use serde::Deserialize;
+
+/// Rows per page when `limit` isn't given
+pub const DEFAULT_PAGE_SIZE: i64 = 500;
+
+/// Upper bound on `limit`
+pub const MAX_PAGE_SIZE: i64 = 1000;
+
+/// Query parameters shared by paginated history endpoints
+#[derive(Debug, Deserialize)]
+pub struct PageQuery {
+    /// Look-back window in days, used when `since` isn't given
+    #[serde(default = "default_days")]
+    pub days: i32,
+    /// Start of the range (RFC 3339 timestamp or YYYY-MM-DD)
+    pub since: Option<String>,
+    /// End of the range, inclusive (RFC 3339 timestamp or YYYY-MM-DD)
+    pub until: Option<String>,
+    /// Opaque cursor from the previous page's `next_cursor`
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+}
+
+fn default_days() -> i32 {
+    30
+}
+
+impl Validate for PageQuery {
+    fn validate(&self, checks: &mut Checks) {
+        checks
+            .range("days", self.days, 1..=3650)
+            .parses("since", self.since.as_deref(), |v| parse_time_bound(v, false))
+            .parses("until", self.until.as_deref(), |v| parse_time_bound(v, true))
+            .parses("cursor", self.cursor.as_deref(), |v| v.parse::<i64>().ok());
+        if let Some(limit) = self.limit {
+            checks.range("limit", limit, 1..=MAX_PAGE_SIZE);
+        }
+    }
+}
+
+impl PageQuery {
+    /// Resolve the query into a database window, or a message describing the bad parameter
+    pub fn window(&self) -> Result<TimeWindow, String> {
+        let since = match self.since.as_deref() {
+            Some(value) => parse_time_bound(value, false).ok_or_else(|| format!("Invalid since: {}", value))?,
+            None => Utc::now() - chrono::TimeDelta::days(self.days.max(0) as i64),
+        };
+
+        let until = match self.until.as_deref() {
+            Some(value) => Some(parse_time_bound(value, true).ok_or_else(|| format!("Invalid until: {}", value))?),
+            None => None,
+        };
+
+        let after_id = match self.cursor.as_deref() {
+            Some(cursor) => Some(cursor.parse().map_err(|_| format!("Invalid cursor: {}", cursor))?),
+            None => None,
+        };
+
+        let limit = self.limit.unwrap_or(DEFAULT_PAGE_SIZE);
+        if !(1..=MAX_PAGE_SIZE).contains(&limit) {
+            return Err(format!("limit must be between 1 and {}", MAX_PAGE_SIZE));
+        }
+
+        Ok(TimeWindow {
+            since,
+            until,
+            after_id,
+            limit,
+        })
+    }
+}
+
+/// Parse a relative start like "7d", "24h" or "2w" ago, or an absolute bound
+pub fn parse_since(value: &str) -> Option<DateTime<Utc>> {
+    let unit = value.chars().last()?;
+    let count = value[..value.len() - unit.len_utf8()].parse::<i64>().ok().filter(|&n| n > 0);
+    let ago = match (count, unit) {
+        (Some(n), 'h') => chrono::TimeDelta::try_hours(n),
+        (Some(n), 'd') => chrono::TimeDelta::try_days(n),
+        (Some(n), 'w') => chrono::TimeDelta::try_weeks(n),
+        _ => return parse_time_bound(value, false),
+    };
+    Utc::now().checked_sub_signed(ago?)
+}
+
+/// Trim a page fetched with `limit + 1` rows, returning the cursor for the next page if any
+pub fn split_page<T>(rows: &mut Vec<T>, limit: i64, id: impl Fn(&T) -> i64) -> Option<String> {
+    if rows.len() as i64 <= limit {
+        return None;
+    }
+
+    rows.truncate(limit as usize);
+    rows.last().map(|row| id(row).to_string())
+}
+
+/// Build a `Link` header value pointing at the next page
+///
+/// `since` is pinned so a relative `days` window doesn't drift between pages. Pages
+/// without a cursor (downsampled history) advance `since` instead.
+pub fn next_link(
+    path: &str,
+    query: Option<&str>,
+    since: DateTime<Utc>,
+    cursor: Option<&str>,
+) -> String {
+    let mut params: Vec<String> = query
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter(|pair| {
+            let key = pair.split('=').next().unwrap_or_default();
+            !matches!(key, "cursor" | "days" | "since")
+        })
+        .map(String::from)
+        .collect();
+
+    params.push(format!("since={}", since.format("%Y-%m-%dT%H:%M:%SZ")));
+    if let Some(cursor) = cursor {
+        params.push(format!("cursor={}", cursor));
+    }
+
+    format!("<{}?{}>; rel=\"next\"", path, params.join("&"))
+}
+
+/// Build a `Link` header value pointing at the next page of a list that isn't a time
+/// window, keeping every parameter but the cursor
+pub fn cursor_link(path: &str, query: Option<&str>, cursor: &str) -> String {
+    let mut params: Vec<&str> = query
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| !pair.is_empty() && pair.split('=').next() != Some("cursor"))
+        .collect();
+    let cursor = format!("cursor={}", cursor);
+    params.push(&cursor);
+
+    format!("<{}?{}>; rel=\"next\"", path, params.join("&"))
+}
@@ -1,29 +1,115 @@
 //! API route definitions
 
 use crate::handlers;
+use crate::html;
+use crate::versioning::{self, ApiVersion};
 use crate::SharedState;
 use axum::{
-    routing::{get, post},
-    Router,
+    middleware,
+    routing::{delete, get, post},
+    Extension, Router,
 };
 use std::path::PathBuf;
 use tower_http::{
-    compression::CompressionLayer,
+    compression::{
+        predicate::{NotForContentType, Predicate, SizeAbove},
+        CompressionLayer,
+    },
     cors::{Any, CorsLayer},
     services::ServeDir,
 };
 
-/// Create the main application router
-pub fn create_router(state: SharedState, static_dir: Option<PathBuf>) -> Router {
-    let api_routes = Router::new()
+/// Responses smaller than this go out uncompressed: they fit in a packet or two either
+/// way, so compressing only adds latency
+const MIN_COMPRESSED_SIZE: u16 = 1024;
+
+/// Compress JSON, HTML and text, skipping small bodies, event streams and images
+///
+/// SVG is skipped too, unlike tower-http's default, since it's only ever small icons and
+/// badges. Clients negotiate the encoding through `Accept-Encoding`; only gzip is built in.
+fn compression() -> CompressionLayer<impl Predicate> {
+    let predicate = SizeAbove::new(MIN_COMPRESSED_SIZE)
+        .and(NotForContentType::GRPC)
+        .and(NotForContentType::SSE)
+        .and(NotForContentType::IMAGES)
+        .and(NotForContentType::const_new("image/svg+xml"));
+
+    CompressionLayer::new().compress_when(predicate)
+}
+
+/// Routes every API version serves from the same handlers
+///
+/// Routes added here also go in [`crate::discovery::ENDPOINTS`], which `GET /api/v1` lists.
+fn shared_routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(handlers::get_api_root))
         .route("/health", get(handlers::health_check))
         .route("/distros", get(handlers::list_distros))
-        .route("/distros/{slug}", get(handlers::get_distro))
+        .route(
+            "/distros/{slug}",
+            get(handlers::get_distro)
+                .patch(handlers::update_distro)
+                .delete(handlers::archive_distro),
+        )
+        .route("/distros/{slug}/claims", post(handlers::create_claim))
         .route("/distros/{slug}/health", get(handlers::get_distro_health))
         .route("/distros/{slug}/history", get(handlers::get_distro_history))
-        .route("/rankings", get(handlers::get_rankings))
+        .route("/distros/{slug}/snapshots", get(handlers::get_distro_snapshots))
+        .route("/distros/{slug}/tech", get(handlers::get_distro_tech))
+        .route("/distros/{slug}/downloads", get(handlers::get_distro_downloads))
+        .route("/distros/{slug}/charts", get(handlers::get_distro_charts))
+        .route("/distros/{slug}/momentum", get(handlers::get_distro_momentum))
+        .route("/distros/{slug}/changes", get(handlers::get_distro_changes))
+        .route("/distros/{slug}/risk", get(handlers::get_distro_risk))
+        .route("/distros/{slug}/jsonld", get(handlers::get_distro_jsonld))
+        .route("/distros/{slug}/og.png", get(handlers::get_distro_og_image))
+        .route("/distros/{slug}/milestones", get(handlers::get_distro_milestones))
+        .route("/distros/{slug}/annotations", post(handlers::create_annotation))
+        .route("/bulk", get(handlers::get_bulk))
+        .route("/rankings/sparklines", get(handlers::get_sparklines))
+        .route("/snapshots/{kind}", get(handlers::list_snapshots))
+        .route("/highlight", get(handlers::get_highlight))
+        .route("/methodology", get(handlers::get_methodology))
+        .route("/milestones", get(handlers::get_milestones))
+        .route("/leaderboards/{metric}", get(handlers::get_leaderboard))
+        .route("/ecosystem/overlap", get(handlers::get_contributor_overlap))
+        .route("/stats/distribution", get(handlers::get_metric_distribution))
+        .route("/stats/correlations", get(handlers::get_correlations))
+        .route("/reports/risks", get(handlers::get_release_risks))
+        .route("/collect", post(handlers::trigger_bulk_collection))
         .route("/collect/{slug}", post(handlers::trigger_collection))
-        .with_state(state);
+        .route("/jobs/{id}", get(handlers::get_job))
+        .route("/suggestions", post(handlers::create_suggestion))
+        .route("/ping", post(handlers::record_ping))
+        .route("/claims", get(handlers::list_claims))
+        .route("/claims/{id}/verify", post(handlers::verify_claim))
+        .route("/claims/{id}/reject", post(handlers::reject_claim))
+        .route("/admin/config-issues", get(handlers::list_config_issues))
+        .route("/admin/health", get(handlers::get_admin_health))
+        .route(
+            "/admin/freezes",
+            get(handlers::list_score_freezes).post(handlers::create_score_freeze),
+        )
+        .route("/admin/freezes/{id}", delete(handlers::end_score_freeze))
+}
+
+/// Create the main application router
+pub fn create_router(state: SharedState, static_dir: Option<PathBuf>) -> Router {
+    let v1 = shared_routes()
+        .route("/rankings", get(handlers::get_rankings))
+        .layer(middleware::from_fn(versioning::bare_responses))
+        .layer(middleware::from_fn(versioning::deprecate_v1))
+        .layer(middleware::from_fn(html::negotiate))
+        .layer(Extension(ApiVersion::V1))
+        .with_state(state.clone());
+
+    let v2 = shared_routes()
+        .route("/rankings", get(handlers::get_rankings_page))
+        .layer(middleware::from_fn(versioning::bare_responses))
+        .layer(middleware::from_fn(versioning::problem_details))
+        .layer(middleware::from_fn(html::negotiate))
+        .layer(Extension(ApiVersion::V2))
+        .with_state(state.clone());
 
     let cors = CorsLayer::new()
         .allow_origin(Any)
@@ -31,9 +117,12 @@ pub fn create_router(state: SharedState, static_dir: Option<PathBuf>) -> Router
         .allow_headers(Any);
 
     let mut app = Router::new()
-        .nest("/api/v1", api_routes)
+        .route("/sitemap.xml", get(handlers::get_sitemap))
+        .with_state(state)
+        .nest(ApiVersion::V1.base(), v1)
+        .nest(ApiVersion::V2.base(), v2)
         .layer(cors)
-        .layer(CompressionLayer::new());
+        .layer(compression());
 
     // Serve static files if directory provided
     if let Some(dir) = static_dir {
@@ -0,0 +1,130 @@
+//! Query parameter validation
+//!
+//! [`ValidQuery`] replaces `Query` for endpoints with bounded parameters. Every field is
+//! checked before the handler runs, and a bad request gets one 400 listing each invalid
+//! field rather than failing on the first, or running with a nonsensical interval.
+
+use axum::{
+    extract::{FromRequestParts, Query},
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt::Display;
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+/// Parameters that can check themselves
+pub trait Validate {
+    fn validate(&self, checks: &mut Checks);
+}
+
+/// Collects the problems found while validating a query
+#[derive(Debug, Default)]
+pub struct Checks(Vec<FieldError>);
+
+impl Checks {
+    /// Record a problem with `field` unless `ok`
+    pub fn check(&mut self, field: &str, ok: bool, message: impl FnOnce() -> String) -> &mut Self {
+        if !ok {
+            self.0.push(FieldError {
+                field: field.to_string(),
+                message: message(),
+            });
+        }
+        self
+    }
+
+    pub fn range<T: PartialOrd + Display>(&mut self, field: &str, value: T, range: RangeInclusive<T>) -> &mut Self {
+        let ok = range.contains(&value);
+        self.check(field, ok, || {
+            format!("{} must be between {} and {}", field, range.start(), range.end())
+        })
+    }
+
+    pub fn at_least<T: PartialOrd + Display>(&mut self, field: &str, value: T, min: T) -> &mut Self {
+        let ok = value >= min;
+        self.check(field, ok, || format!("{} must be at least {}", field, min))
+    }
+
+    /// When present, `value` must be one of `allowed`
+    pub fn one_of(&mut self, field: &str, value: Option<&str>, allowed: &[&str]) -> &mut Self {
+        let ok = value.is_none_or(|v| allowed.contains(&v));
+        self.check(field, ok, || {
+            format!("{} must be one of: {}", field, allowed.join(", "))
+        })
+    }
+
+    /// When present, `value` must parse with `parse`
+    pub fn parses<T>(&mut self, field: &str, value: Option<&str>, parse: impl Fn(&str) -> Option<T>) -> &mut Self {
+        let ok = value.is_none_or(|v| parse(v).is_some());
+        self.check(field, ok, || format!("Invalid {}: {}", field, value.unwrap_or_default()))
+    }
+}
+
+/// A 400 listing every invalid parameter
+#[derive(Debug)]
+pub struct ValidationError(Vec<FieldError>);
+
+impl IntoResponse for ValidationError {
+    fn into_response(self) -> Response {
+        #[derive(Serialize)]
+        struct Body {
+            success: bool,
+            data: Option<()>,
+            error: String,
+            errors: Vec<FieldError>,
+        }
+
+        let messages: Vec<&str> = self.0.iter().map(|e| e.message.as_str()).collect();
+        let body = Body {
+            success: false,
+            data: None,
+            error: format!("Invalid query parameters: {}", messages.join("; ")),
+            errors: self.0,
+        };
+        (StatusCode::BAD_REQUEST, Json(body)).into_response()
+    }
+}
+
+/// `Query` that rejects unparseable or out-of-range parameters with a [`ValidationError`]
+#[derive(Debug)]
+pub struct ValidQuery<T>(pub T);
+
+impl<T, S> FromRequestParts<S> for ValidQuery<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ValidationError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let Query(query) = Query::<T>::from_request_parts(parts, state).await.map_err(|rejection| {
+            // "Failed to deserialize query string: days: invalid digit found in string"
+            let text = rejection.body_text();
+            let detail = text.split_once(": ").map_or(text.as_str(), |(_, detail)| detail);
+            let (field, message) = match detail.split_once(": ") {
+                Some((field, reason)) if !field.contains(' ') => (field, format!("{}: {}", field, reason)),
+                _ => ("query", detail.to_string()),
+            };
+            ValidationError(vec![FieldError {
+                field: field.to_string(),
+                message,
+            }])
+        })?;
+
+        let mut checks = Checks::default();
+        query.validate(&mut checks);
+        if checks.0.is_empty() {
+            Ok(Self(query))
+        } else {
+            Err(ValidationError(checks.0))
+        }
+    }
+}
@@ -0,0 +1,251 @@
+//! API versions and what changed between them
+//!
+//! Both versions are served from the same handlers. v2 differs where payloads changed:
+//! rankings are paginated, history always comes with its annotations (v1 keeps a bare
+//! list unless asked), and errors are RFC 9457 problem details instead of the
+//! `{success, data, error}` envelope. v1 responses that v2 changed carry `Deprecation`,
+//! `Sunset` and a `successor-version` link, so clients notice before v1 goes away.
+//!
+//! Either version drops the envelope on request, with `?envelope=false` or an
+//! `Accept-Profile: bare` header: a success returns its data alone and an error a problem,
+//! as client generators expect. Bare responses are planned as v2's default.
+
+use axum::{
+    body::Body,
+    extract::Request,
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::Response,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Largest error body rewritten as a problem; bigger ones pass through unchanged
+const MAX_ERROR_BYTES: usize = 64 * 1024;
+
+/// When v1 was deprecated, as an RFC 9745 date: 2026-10-15
+const V1_DEPRECATED: &str = "@1792022400";
+
+/// When v1 may stop being served, as an HTTP-date
+const V1_SUNSET: &str = "Thu, 15 Apr 2027 00:00:00 GMT";
+
+/// v1 paths whose successful responses changed shape in v2, `*` standing for a slug
+const CHANGED_IN_V2: [&str; 2] = ["/rankings", "/distros/*/history"];
+
+const DEPRECATION: HeaderName = HeaderName::from_static("deprecation");
+const SUNSET: HeaderName = HeaderName::from_static("sunset");
+const ACCEPT_PROFILE: HeaderName = HeaderName::from_static("accept-profile");
+
+/// A version of the API's paths and response shapes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiVersion {
+    V1,
+    V2,
+}
+
+impl ApiVersion {
+    pub const ALL: [ApiVersion; 2] = [ApiVersion::V1, ApiVersion::V2];
+
+    /// Prefix the version's paths are nested under
+    pub fn base(self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "/api/v1",
+            ApiVersion::V2 => "/api/v2",
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ApiVersion::V1 => "v1",
+            ApiVersion::V2 => "v2",
+        }
+    }
+
+    /// Date the version may stop being served, if it's deprecated
+    pub fn sunset(self) -> Option<&'static str> {
+        match self {
+            ApiVersion::V1 => Some(V1_SUNSET),
+            ApiVersion::V2 => None,
+        }
+    }
+}
+
+/// A version as listed by the discovery document
+#[derive(Debug, Serialize)]
+pub struct VersionInfo {
+    pub version: ApiVersion,
+    pub href: &'static str,
+    pub deprecated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sunset: Option<&'static str>,
+}
+
+/// Every version the server answers
+pub fn versions() -> Vec<VersionInfo> {
+    ApiVersion::ALL
+        .into_iter()
+        .map(|version| VersionInfo {
+            version,
+            href: version.base(),
+            deprecated: version.sunset().is_some(),
+            sunset: version.sunset(),
+        })
+        .collect()
+}
+
+/// Mark v1 responses that differ in v2: changed endpoints, and errors, whose format changed everywhere
+pub async fn deprecate_v1(request: Request, next: Next) -> Response {
+    // Nested routers see the path without the version prefix
+    let path = request.uri().path().to_string();
+    let query = request.uri().query().map(str::to_string);
+
+    let mut response = next.run(request).await;
+    let changed = CHANGED_IN_V2.iter().any(|pattern| matches_path(pattern, &path));
+    if !changed && !is_error(response.status()) {
+        return response;
+    }
+
+    let successor = match query {
+        Some(query) => format!("<{}{}?{}>; rel=\"successor-version\"", ApiVersion::V2.base(), path, query),
+        None => format!("<{}{}>; rel=\"successor-version\"", ApiVersion::V2.base(), path),
+    };
+    let headers = response.headers_mut();
+    headers.insert(DEPRECATION, HeaderValue::from_static(V1_DEPRECATED));
+    headers.insert(SUNSET, HeaderValue::from_static(V1_SUNSET));
+    if let Ok(link) = HeaderValue::from_str(&successor) {
+        headers.append(header::LINK, link);
+    }
+    response
+}
+
+/// Rewrite error responses as `application/problem+json`
+///
+/// The envelope's `error` becomes `detail`, and any other fields it had (such as a
+/// validation error's `errors`) are kept as extension members. Plain-text rejections
+/// from extractors and empty error bodies are converted the same way.
+pub async fn problem_details(request: Request, next: Next) -> Response {
+    let response = next.run(request).await;
+    if !is_error(response.status()) {
+        return response;
+    }
+    into_problem(response).await
+}
+
+/// Drop the `{success, data, error}` envelope for clients that ask to
+///
+/// Successes return their `data` (no content when there's none) and errors become
+/// problems, keeping their status codes.
+pub async fn bare_responses(request: Request, next: Next) -> Response {
+    let bare = wants_bare(request.uri().query(), request.headers());
+    let mut response = next.run(request).await;
+    response.headers_mut().append(header::VARY, HeaderValue::from_static("accept-profile"));
+    if !bare {
+        return response;
+    }
+    if is_error(response.status()) {
+        return into_problem(response).await;
+    }
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("application/json"));
+    if !is_json {
+        return response;
+    }
+
+    // JSON bodies are already in memory, so there's no limit to keep
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, usize::MAX).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+    let data = match serde_json::from_slice::<Value>(&bytes) {
+        Ok(Value::Object(mut envelope)) if envelope.get("success") == Some(&Value::Bool(true)) => {
+            envelope.remove("data").unwrap_or(Value::Null)
+        }
+        // Endpoints that never had the envelope
+        _ => return Response::from_parts(parts, Body::from(bytes)),
+    };
+
+    parts.headers.remove(header::CONTENT_LENGTH);
+    if data.is_null() && parts.status == StatusCode::OK {
+        parts.status = StatusCode::NO_CONTENT;
+        parts.headers.remove(header::CONTENT_TYPE);
+        return Response::from_parts(parts, Body::empty());
+    }
+    Response::from_parts(parts, Body::from(data.to_string()))
+}
+
+/// Whether the request opted out of the envelope
+fn wants_bare(query: Option<&str>, headers: &HeaderMap) -> bool {
+    let profiles = headers.get(ACCEPT_PROFILE).and_then(|v| v.to_str().ok()).unwrap_or_default();
+    query.is_some_and(|q| q.split('&').any(|pair| pair == "envelope=false"))
+        || profiles.split(',').any(|p| p.trim().trim_matches('"').eq_ignore_ascii_case("bare"))
+}
+
+/// An error response as a problem, built from its envelope or plain-text body
+async fn into_problem(response: Response) -> Response {
+    let status = response.status();
+    let (mut parts, body) = response.into_parts();
+    let bytes = match axum::body::to_bytes(body, MAX_ERROR_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(_) => return Response::from_parts(parts, Body::empty()),
+    };
+    let content_type = parts.headers.get(header::CONTENT_TYPE).and_then(|v| v.to_str().ok()).unwrap_or_default();
+
+    let (detail, extensions) = if content_type.starts_with("application/json") {
+        match serde_json::from_slice::<Value>(&bytes) {
+            Ok(Value::Object(mut envelope)) if envelope.get("success") == Some(&Value::Bool(false)) => {
+                let detail = envelope.remove("error").and_then(|e| e.as_str().map(str::to_string));
+                envelope.remove("success");
+                envelope.remove("data");
+                (detail, envelope)
+            }
+            _ => return Response::from_parts(parts, Body::from(bytes)),
+        }
+    } else if bytes.is_empty() || content_type.starts_with("text/plain") {
+        let text = String::from_utf8_lossy(&bytes).trim().to_string();
+        ((!text.is_empty()).then_some(text), Map::new())
+    } else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+
+    let problem = problem(status, detail, extensions);
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/problem+json"));
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Response::from_parts(parts, Body::from(problem.to_string()))
+}
+
+/// An RFC 9457 problem for `status`, with no more specific type than the status itself
+fn problem(status: StatusCode, detail: Option<String>, extensions: Map<String, Value>) -> Value {
+    let mut problem = Map::new();
+    problem.insert("type".into(), "about:blank".into());
+    problem.insert("title".into(), status.canonical_reason().unwrap_or("Error").into());
+    problem.insert("status".into(), status.as_u16().into());
+    if let Some(detail) = detail {
+        problem.insert("detail".into(), detail.into());
+    }
+    for (key, value) in extensions {
+        problem.entry(key).or_insert(value);
+    }
+    Value::Object(problem)
+}
+
+/// Whether `path` matches `pattern`, segment by segment
+fn matches_path(pattern: &str, path: &str) -> bool {
+    let (mut pattern, mut path) = (pattern.split('/'), path.split('/'));
+    loop {
+        match (pattern.next(), path.next()) {
+            (None, None) => return true,
+            (Some(expected), Some(segment)) if expected == "*" || expected == segment => {}
+            _ => return false,
+        }
+    }
+}
+
+fn is_error(status: StatusCode) -> bool {
+    status.is_client_error() || status.is_server_error()
+}
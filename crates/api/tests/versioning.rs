@@ -0,0 +1,213 @@
+//! Version negotiation: v1 keeps its shapes and announces its deprecation, v2 serves the
+//! changed ones
+
+use axum::body::Body;
+use axum::http::{header, HeaderMap, Request, StatusCode};
+use axum::Router;
+use distrovitals_api::{create_router, AppState};
+use distrovitals_database::Database;
+use serde_json::Value;
+use std::sync::Arc;
+use tower::ServiceExt;
+
+const DISTROS: usize = 5;
+
+async fn router() -> Router {
+    let db = Database::in_memory().await.expect("open database");
+    db.create_synthetic_distributions("versioning", DISTROS)
+        .await
+        .expect("create distros");
+    create_router(Arc::new(AppState::new(db)), None)
+}
+
+async fn get(router: &Router, uri: &str) -> (StatusCode, HeaderMap, Value) {
+    let request = Request::get(uri).body(Body::empty()).unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let headers = response.headers().clone();
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+    (status, headers, body)
+}
+
+fn header<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|v| v.to_str().ok())
+}
+
+fn links(headers: &HeaderMap) -> Vec<&str> {
+    headers.get_all(header::LINK).iter().filter_map(|v| v.to_str().ok()).collect()
+}
+
+#[tokio::test]
+async fn v1_rankings_keep_their_shape() {
+    let router = router().await;
+    let (status, _, body) = get(&router, "/api/v1/rankings").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["success"], true);
+    let rankings = body["data"].as_array().expect("v1 rankings are a bare list");
+    assert_eq!(rankings.len(), DISTROS);
+    assert!(body["error"].is_null());
+}
+
+#[tokio::test]
+async fn v1_rankings_announce_their_successor() {
+    let router = router().await;
+    let (_, headers, _) = get(&router, "/api/v1/rankings?os_family=linux").await;
+
+    assert!(header(&headers, "deprecation").is_some_and(|v| v.starts_with('@')));
+    assert!(header(&headers, "sunset").is_some_and(|v| v.ends_with(" GMT")));
+    assert!(links(&headers).contains(&"</api/v2/rankings?os_family=linux>; rel=\"successor-version\""));
+}
+
+#[tokio::test]
+async fn unchanged_v1_endpoints_are_not_deprecated() {
+    let router = router().await;
+    let (status, headers, body) = get(&router, "/api/v1/distros").await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["data"].as_array().map(Vec::len), Some(DISTROS));
+    assert!(headers.get("deprecation").is_none());
+    assert!(headers.get("sunset").is_none());
+}
+
+#[tokio::test]
+async fn v1_errors_keep_the_envelope() {
+    let router = router().await;
+    let (status, headers, body) = get(&router, "/api/v1/rankings?sort=bogus").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(header(&headers, "content-type"), Some("application/json"));
+    assert_eq!(body["success"], false);
+    assert!(body["error"].as_str().is_some_and(|e| e.contains("Unknown sort")));
+    assert!(headers.get("deprecation").is_some());
+}
+
+#[tokio::test]
+async fn v2_rankings_are_paginated() {
+    let router = router().await;
+    let mut uri = "/api/v2/rankings?limit=2".to_string();
+    let mut ranks = Vec::new();
+    let mut pages = 0;
+
+    loop {
+        let (status, headers, body) = get(&router, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(headers.get("deprecation").is_none());
+        assert_eq!(body["data"]["total"], DISTROS);
+        let page = body["data"]["rankings"].as_array().expect("v2 rankings are paged");
+        assert!(page.len() <= 2);
+        ranks.extend(page.iter().map(|r| r["rank"].as_u64().unwrap()));
+        pages += 1;
+
+        let Some(cursor) = body["data"]["next_cursor"].as_str() else {
+            assert!(links(&headers).is_empty());
+            break;
+        };
+        let next = format!("</api/v2/rankings?limit=2&cursor={}>; rel=\"next\"", cursor);
+        assert_eq!(links(&headers), [next.as_str()]);
+        uri = format!("/api/v2/rankings?limit=2&cursor={}", cursor);
+    }
+
+    assert_eq!(pages, 3);
+    assert_eq!(ranks, (1..=DISTROS as u64).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn v2_errors_are_problem_details() {
+    let router = router().await;
+    let (status, headers, body) = get(&router, "/api/v2/rankings?sort=bogus").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(header(&headers, "content-type"), Some("application/problem+json"));
+    assert_eq!(body["type"], "about:blank");
+    assert_eq!(body["title"], "Bad Request");
+    assert_eq!(body["status"], 400);
+    assert!(body["detail"].as_str().is_some_and(|d| d.contains("Unknown sort")));
+    assert!(body.get("success").is_none());
+
+    let (status, _, body) = get(&router, "/api/v2/distros/missing").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["status"], 404);
+}
+
+#[tokio::test]
+async fn v2_validation_errors_keep_their_fields() {
+    let router = router().await;
+    let (status, _, body) = get(&router, "/api/v2/rankings?limit=0").await;
+
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(body["status"], 400);
+    assert_eq!(body["errors"][0]["field"], "limit");
+}
+
+#[tokio::test]
+async fn discovery_lists_both_versions() {
+    let router = router().await;
+
+    let (_, _, v1) = get(&router, "/api/v1").await;
+    assert_eq!(v1["data"]["api_version"], "v1");
+    assert_eq!(v1["data"]["_links"]["rankings"]["href"], "/api/v1/rankings");
+
+    let (_, _, v2) = get(&router, "/api/v2").await;
+    assert_eq!(v2["data"]["api_version"], "v2");
+    assert_eq!(v2["data"]["_links"]["rankings"]["href"], "/api/v2/rankings");
+
+    let versions = v2["data"]["versions"].as_array().unwrap();
+    assert_eq!(versions[0]["version"], "v1");
+    assert_eq!(versions[0]["deprecated"], true);
+    assert_eq!(versions[1]["version"], "v2");
+    assert_eq!(versions[1]["deprecated"], false);
+}
+
+#[tokio::test]
+async fn envelope_can_be_dropped() {
+    let router = router().await;
+
+    let (status, _, body) = get(&router, "/api/v1/distros?envelope=false").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body.as_array().map(Vec::len), Some(DISTROS));
+
+    let (status, headers, body) = get(&router, "/api/v1/distros/missing?envelope=false").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(header(&headers, "content-type"), Some("application/problem+json"));
+    assert_eq!(body["status"], 404);
+    assert!(body.get("success").is_none());
+}
+
+#[tokio::test]
+async fn envelope_can_be_dropped_with_a_profile() {
+    let router = router().await;
+    let request = Request::get("/api/v2/rankings?limit=2")
+        .header("accept-profile", "bare")
+        .body(Body::empty())
+        .unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(header(response.headers(), "vary").is_some());
+
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    let body: Value = serde_json::from_slice(&bytes).unwrap();
+    assert_eq!(body["total"], DISTROS);
+    assert_eq!(body["rankings"].as_array().map(Vec::len), Some(2));
+}
+
+#[tokio::test]
+async fn v1_history_stays_a_list() {
+    let router = router().await;
+    let (_, _, distros) = get(&router, "/api/v1/distros").await;
+    let slug = distros["data"][0]["slug"].as_str().unwrap().to_string();
+
+    let (status, headers, body) = get(&router, &format!("/api/v1/distros/{}/history", slug)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["data"].is_array());
+    assert!(links(&headers).iter().any(|l| l.contains("successor-version")));
+
+    let uri = format!("/api/v1/distros/{}/history?include=annotations", slug);
+    let (_, _, body) = get(&router, &uri).await;
+    assert!(body["data"]["scores"].is_array());
+    assert!(body["data"]["annotations"].is_array());
+
+    let (_, _, body) = get(&router, &format!("/api/v2/distros/{}/history", slug)).await;
+    assert!(body["data"]["scores"].is_array());
+}
@@ -0,0 +1,276 @@
+//! Shell completion scripts and the man page, generated from the CLI definition
+//!
+//! Written out by `dv completions <shell>` and `dv manpage` so packages can install them
+//! without keeping copies in sync with the commands by hand.
+
+use clap::{Arg, Command, ValueEnum};
+use std::fmt::Write;
+
+/// Shells completion scripts are generated for
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// Completion script for `shell`
+pub fn completions(cmd: &mut Command, shell: Shell) -> String {
+    cmd.build();
+    match shell {
+        Shell::Bash => bash(cmd),
+        // zsh runs bash completion functions through bashcompinit
+        Shell::Zsh => format!(
+            "#compdef {}\n\nautoload -U +X bashcompinit && bashcompinit\n\n{}",
+            cmd.get_name(),
+            bash(cmd)
+        ),
+        Shell::Fish => fish(cmd),
+    }
+}
+
+/// Man page in roff, covering every subcommand
+pub fn manpage(cmd: &mut Command) -> String {
+    cmd.build();
+    let name = cmd.get_name().to_string();
+    let mut out = String::new();
+
+    let _ = writeln!(
+        out,
+        ".TH {} 1 \"\" \"{} {}\" \"User Commands\"",
+        name.to_uppercase(),
+        name,
+        cmd.get_version().unwrap_or_default()
+    );
+    let _ = writeln!(out, ".SH NAME\n{} \\- {}", name, roff(&about(cmd)));
+    let _ = writeln!(out, ".SH SYNOPSIS\n\\fB{}\\fR [OPTIONS] <COMMAND>", name);
+    if let Some(long_about) = cmd.get_long_about() {
+        let _ = writeln!(out, ".SH DESCRIPTION\n{}", roff(&long_about.to_string()));
+    }
+
+    out.push_str(".SH OPTIONS\n");
+    for arg in visible_args(cmd) {
+        man_arg(&mut out, arg);
+    }
+
+    out.push_str(".SH COMMANDS\n");
+    for sub in visible_subcommands(cmd).filter(|sub| !is_help(sub)) {
+        man_command(&mut out, &name, sub);
+    }
+    out
+}
+
+fn man_command(out: &mut String, parent: &str, cmd: &Command) {
+    let path = format!("{} {}", parent, cmd.get_name());
+    let _ = writeln!(out, ".TP\n\\fB{}\\fR{}\n{}", roff(&path), roff(&usage_args(cmd)), roff(&about(cmd)));
+
+    let args: Vec<&Arg> = visible_args(cmd).collect();
+    if !args.is_empty() {
+        out.push_str(".RS\n");
+        for arg in args {
+            man_arg(out, arg);
+        }
+        out.push_str(".RE\n");
+    }
+
+    for sub in visible_subcommands(cmd).filter(|sub| !is_help(sub)) {
+        man_command(out, &path, sub);
+    }
+}
+
+fn man_arg(out: &mut String, arg: &Arg) {
+    let mut names = Vec::new();
+    if let Some(short) = arg.get_short() {
+        names.push(format!("\\fB\\-{}\\fR", short));
+    }
+    if let Some(long) = arg.get_long() {
+        names.push(format!("\\fB\\-\\-{}\\fR", roff(long)));
+    }
+    let mut heading = if arg.is_positional() {
+        format!("<{}>", arg.get_id().as_str().to_uppercase())
+    } else {
+        names.join(", ")
+    };
+    if !arg.is_positional() && takes_value(arg) {
+        heading.push_str(&format!(" <{}>", arg.get_id().as_str().to_uppercase()));
+    }
+
+    let mut help = arg.get_help().map(|h| h.to_string()).unwrap_or_default();
+    let defaults: Vec<_> = arg.get_default_values().iter().map(|v| v.to_string_lossy()).collect();
+    if !defaults.is_empty() && takes_value(arg) {
+        help.push_str(&format!(" [default: {}]", defaults.join(",")));
+    }
+    let _ = writeln!(out, ".TP\n{}\n{}", heading, roff(help.trim()));
+}
+
+/// Positional arguments for a command's synopsis line, e.g. ` <DISTRO>`
+fn usage_args(cmd: &Command) -> String {
+    cmd.get_positionals()
+        .filter(|arg| !arg.is_hide_set())
+        .map(|arg| format!(" <{}>", arg.get_id().as_str().to_uppercase()))
+        .collect()
+}
+
+/// Escape text for roff, including a leading control character
+fn roff(text: &str) -> String {
+    let escaped = text.replace('\\', "\\\\").replace('-', "\\-").replace('\n', " ");
+    if escaped.starts_with('.') || escaped.starts_with('\'') {
+        format!("\\&{}", escaped)
+    } else {
+        escaped
+    }
+}
+
+fn bash(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let func = format!("_{}", name.replace('-', "_"));
+    let mut transitions = String::new();
+    let mut words = String::new();
+    bash_command(cmd, name, &mut transitions, &mut words);
+
+    format!(
+        r#"{func}() {{
+    local cur path i
+    cur="${{COMP_WORDS[COMP_CWORD]}}"
+    path="{name}"
+    for ((i = 1; i < COMP_CWORD; i++)); do
+        case "$path:${{COMP_WORDS[i]}}" in
+{transitions}        esac
+    done
+    case "$path" in
+{words}    esac
+}}
+
+complete -F {func} -o default {name}
+"#
+    )
+}
+
+fn bash_command(cmd: &Command, path: &str, transitions: &mut String, words: &mut String) {
+    let mut candidates: Vec<String> = visible_subcommands(cmd).map(|sub| sub.get_name().to_string()).collect();
+    candidates.extend(visible_args(cmd).flat_map(flags));
+    let _ = writeln!(
+        words,
+        "        {}) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")) ;;",
+        path,
+        candidates.join(" ")
+    );
+
+    for sub in visible_subcommands(cmd).filter(|sub| !is_help(sub)) {
+        let sub_path = format!("{}__{}", path, sub.get_name());
+        let _ = writeln!(transitions, "            {}:{}) path=\"{}\" ;;", path, sub.get_name(), sub_path);
+        bash_command(sub, &sub_path, transitions, words);
+    }
+}
+
+fn fish(cmd: &Command) -> String {
+    let name = cmd.get_name();
+    let helper = format!("__{}_using", name.replace('-', "_"));
+    // Whether the words typed so far, options left out, start with the given subcommands;
+    // with --exact, whether they're nothing more, so a further subcommand can be offered
+    let mut out = format!(
+        r#"function {helper}
+    set -l exact 0
+    if test "$argv[1]" = --exact
+        set exact 1
+        set -e argv[1]
+    end
+    set -l words (string match -v -- '-*' (commandline -opc))[2..-1]
+    if test $exact -eq 1 -a (count $words) -ne (count $argv)
+        return 1
+    end
+    test (count $words) -ge (count $argv); or return 1
+    test (count $argv) -eq 0; or test "$words[1..(count $argv)]" = "$argv"
+end
+
+"#
+    );
+    fish_command(cmd, &[], name, &helper, &mut out);
+    out
+}
+
+fn fish_command(cmd: &Command, path: &[&str], name: &str, helper: &str, out: &mut String) {
+    let path_words = path.join(" ");
+    for arg in visible_args(cmd).filter(|arg| !arg.is_positional()) {
+        let mut line = format!("complete -c {} -n \"{}\"", name, condition(helper, &path_words));
+        if let Some(short) = arg.get_short() {
+            let _ = write!(line, " -s {}", short);
+        }
+        if let Some(long) = arg.get_long() {
+            let _ = write!(line, " -l {}", long);
+        }
+        if takes_value(arg) {
+            line.push_str(" -r");
+        }
+        if let Some(help) = arg.get_help() {
+            let _ = write!(line, " -d '{}'", fish_quote(&first_line(&help.to_string())));
+        }
+        let _ = writeln!(out, "{}", line);
+    }
+
+    for sub in visible_subcommands(cmd) {
+        let _ = writeln!(
+            out,
+            "complete -c {} -f -n \"{}\" -a {} -d '{}'",
+            name,
+            condition(&format!("{} --exact", helper), &path_words),
+            sub.get_name(),
+            fish_quote(&about(sub))
+        );
+        if is_help(sub) {
+            continue;
+        }
+        let mut sub_path = path.to_vec();
+        sub_path.push(sub.get_name());
+        fish_command(sub, &sub_path, name, helper, out);
+    }
+}
+
+fn condition(helper: &str, path_words: &str) -> String {
+    if path_words.is_empty() {
+        helper.to_string()
+    } else {
+        format!("{} {}", helper, path_words)
+    }
+}
+
+fn fish_quote(text: &str) -> String {
+    text.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+/// clap's `help` subcommand, which mirrors the whole tree beneath it
+fn is_help(cmd: &Command) -> bool {
+    cmd.get_name() == "help"
+}
+
+fn visible_subcommands(cmd: &Command) -> impl Iterator<Item = &Command> {
+    cmd.get_subcommands().filter(|sub| !sub.is_hide_set())
+}
+
+fn visible_args(cmd: &Command) -> impl Iterator<Item = &Arg> {
+    cmd.get_arguments().filter(|arg| !arg.is_hide_set())
+}
+
+/// `-s` and `--long` spellings of an option
+fn flags(arg: &Arg) -> Vec<String> {
+    let mut flags = Vec::new();
+    if let Some(short) = arg.get_short() {
+        flags.push(format!("-{}", short));
+    }
+    if let Some(long) = arg.get_long() {
+        flags.push(format!("--{}", long));
+    }
+    flags
+}
+
+fn takes_value(arg: &Arg) -> bool {
+    arg.get_num_args().is_some_and(|n| n.takes_values())
+}
+
+fn about(cmd: &Command) -> String {
+    cmd.get_about().map(|a| first_line(&a.to_string())).unwrap_or_default()
+}
+
+fn first_line(text: &str) -> String {
+    text.lines().next().unwrap_or_default().to_string()
+}
@@ -2,15 +2,35 @@
 //!
 //! Admin tool and web server runner.
 
+mod generate;
+
 use anyhow::Result;
-use clap::{Parser, Subcommand};
-use distrovitals_analyzer::Analyzer;
-use distrovitals_api::{create_router, AppState};
-use distrovitals_collector::{github::GithubCollector, reddit::RedditCollector, CollectorConfig};
-use distrovitals_database::Database;
+use clap::{CommandFactory, Parser, Subcommand};
+use distrovitals_analyzer::correlation::{format_coefficient, CorrelationReport};
+use distrovitals_analyzer::lifecycle::LifecycleReport;
+use distrovitals_analyzer::{
+    is_overloaded, Analyzer, DataQuality, GovernanceChecklist, HardwareEnablement, Highlighter, MastodonPublisher,
+    PostAnalysisHooks, RunSummary, ScoreOutcome, UpstreamRisk, WeeklyDigest, DEFAULT_TEMPLATE,
+};
+use distrovitals_api::{create_router, spawn_job_worker, with_dev_proxy, AppState};
+use distrovitals_collector::{
+    drift::DriftChecker,
+    forge::{Forge, ForgeKind},
+    github::GithubCollector,
+    sources::{Source, SourceCollectors, SourceOutcome},
+    CollectorConfig,
+};
+use distrovitals_database::{
+    parse_time_bound, CommunitySnapshot, Database, Distribution, GithubSnapshot, MetricKey, MetricValue,
+    NewScoreFreeze, PackageSnapshot, ReleaseSnapshot, SnapshotKind, SnapshotQuery, SnapshotTable, SortOrder,
+    SyntheticHistory, DEFAULT_SEED,
+};
+use std::io::Write;
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
 use tracing::{info, Level};
 use tracing_subscriber::FmtSubscriber;
 
@@ -42,178 +62,845 @@ enum Commands {
         /// Static files directory
         #[arg(short, long)]
         static_dir: Option<PathBuf>,
+
+        /// Development mode: proxy non-API paths to a frontend dev server, turn off
+        /// caching and open the browser
+        #[arg(long, conflicts_with = "static_dir")]
+        dev: bool,
+
+        /// Frontend dev server to proxy to in --dev mode
+        #[arg(long, default_value = "http://localhost:5173", requires = "dev")]
+        frontend: String,
+
+        /// Don't open the browser in --dev mode
+        #[arg(long, requires = "dev")]
+        no_open: bool,
     },
 
-    /// Collect GitHub data for distributions
+    /// Collect GitHub, Reddit and Repology data for distributions
     Collect {
         /// Distribution slug (or "all" for all distributions)
         #[arg(default_value = "all")]
         distro: String,
+
+        /// Sources to collect, comma-separated: github, gitlab, forgejo, pagure, reddit, discussions,
+        /// packages, governance, mirrors, downloads, archive, bugzilla, custom (default: all)
+        #[arg(long = "source", value_delimiter = ',')]
+        sources: Vec<String>,
+
+        /// Collect sources even if they were collected within their TTL (DV_<SOURCE>_TTL)
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Collect GitHub data for upstream projects (desktop environments)
+    CollectUpstreams {
+        /// Upstream slug (or "all" for all upstreams)
+        #[arg(default_value = "all")]
+        upstream: String,
     },
 
-    /// Collect Reddit community data for distributions
-    CollectReddit {
+    /// Validate configured homepages, orgs, groups and subreddits still exist
+    CheckConfig {
         /// Distribution slug (or "all" for all distributions)
         #[arg(default_value = "all")]
         distro: String,
     },
 
+    /// Collect on a schedule, analyzing distros as soon as they have new snapshots and checking config daily
+    Daemon {
+        /// Hours between collection passes; passes come sooner when a source's TTL is shorter
+        #[arg(long, default_value_t = 6)]
+        interval_hours: u64,
+
+        /// Sources to collect, comma-separated: github, gitlab, forgejo, pagure, reddit, discussions,
+        /// packages, governance, mirrors, downloads, archive, bugzilla, custom (default: all)
+        #[arg(long = "source", value_delimiter = ',')]
+        sources: Vec<String>,
+
+        /// Command or webhook URL to run with each analysis summary, in addition to DV_ANALYSIS_HOOKS
+        #[arg(long = "hook")]
+        hooks: Vec<String>,
+
+        /// Litestream replica URL to stream the database to, e.g. s3://bucket/distrovitals.db
+        /// (defaults to DV_BACKUP_URL)
+        #[arg(long)]
+        replicate: Option<String>,
+    },
+
     /// Calculate health scores
+    #[command(args_conflicts_with_subcommands = true)]
     Analyze {
+        #[command(subcommand)]
+        report: Option<AnalyzeReport>,
+
         /// Distribution slug (or "all" for all distributions)
         #[arg(default_value = "all")]
         distro: String,
+
+        /// Command or webhook URL to run with the run summary, in addition to DV_ANALYSIS_HOOKS
+        #[arg(long = "hook")]
+        hooks: Vec<String>,
     },
 
     /// List tracked distributions
     List,
 
     /// Show health rankings
-    Rankings,
+    Rankings {
+        /// Only include these OS families (comma-separated, e.g. "linux" or "bsd")
+        #[arg(long)]
+        os_family: Option<String>,
+    },
 
     /// Show status of a distribution
     Status {
         /// Distribution slug
         distro: String,
     },
+
+    /// Export raw snapshots as JSON lines or CSV
+    Export(ExportArgs),
+
+    /// Review user-submitted distribution suggestions
+    Suggestions {
+        #[command(subcommand)]
+        action: SuggestionAction,
+    },
+
+    /// Seed distros and reference data; safe to re-run after upgrading
+    Seed {
+        /// SQL file to apply instead of the bundled distro list
+        #[arg(long)]
+        file: Option<PathBuf>,
+    },
+
+    /// Diagnose environment and configuration problems
+    Doctor,
+
+    /// Select this week's highlighted distro for the homepage
+    Highlight {
+        /// Show the pick without recording it
+        #[arg(long)]
+        dry_run: bool,
+    },
+
+    /// Post this week's ranking changes and events to Mastodon
+    PublishWeekly {
+        /// Print the posts instead of sending them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Message template file (defaults to DV_MASTODON_TEMPLATE, then the built-in template)
+        #[arg(long)]
+        template: Option<PathBuf>,
+    },
+
+    /// Review and override mirror repo detection
+    Mirrors {
+        #[command(subcommand)]
+        action: MirrorAction,
+    },
+
+    /// Archive, restore or permanently purge distributions
+    Archive {
+        #[command(subcommand)]
+        action: ArchiveAction,
+    },
+
+    /// Reports for choosing a distro, beyond the health scores
+    Report {
+        #[command(subcommand)]
+        action: ReportAction,
+    },
+
+    /// Pause score recalculation while collected data is known to be bad
+    Freeze {
+        #[command(subcommand)]
+        action: FreezeAction,
+    },
+
+    /// Record a GitHub org rename and move its history to the new name
+    RenameOrg {
+        /// Former org name
+        old: String,
+
+        /// New org name
+        new: String,
+    },
+
+    /// Restore the database from a continuous backup
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+
+    /// Fill the database with randomized history for every distro, for demos and
+    /// frontend work without API tokens or waiting on collection
+    Demo {
+        /// Days of history to generate
+        #[arg(long, default_value = "365")]
+        days: i64,
+
+        /// Random seed, for reproducible data
+        #[arg(long, default_value = "0")]
+        seed: u64,
+
+        /// Generate into a throwaway in-memory database and serve it, leaving the
+        /// database file untouched
+        #[arg(long)]
+        in_memory: bool,
+
+        /// Start the web server once the data is generated (implied by --in-memory)
+        #[arg(long)]
+        serve: bool,
+
+        /// Address to serve on
+        #[arg(short, long, default_value = "127.0.0.1:3000")]
+        bind: SocketAddr,
+    },
+
+    /// Tools for performance work
+    Bench {
+        #[command(subcommand)]
+        action: BenchAction,
+    },
+
+    /// Print a shell completion script, e.g. `dv completions bash > /usr/share/bash-completion/completions/dv`
+    Completions {
+        /// Shell to generate for
+        shell: generate::Shell,
+    },
+
+    /// Print the man page, e.g. `dv manpage > dv.1`
+    Manpage,
+}
+
+#[derive(Subcommand)]
+enum DbAction {
+    /// Rebuild the database file from a Litestream replica (requires `litestream` on PATH)
+    Restore {
+        /// Replica URL, e.g. s3://bucket/distrovitals.db
+        #[arg(long)]
+        from: String,
+
+        /// Replace an existing database, keeping it as <database>.pre-restore
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum ReportAction {
+    /// Probability of each distro going a year without a stable release, from its past
+    /// release cadence
+    Risks {
+        /// Forecast horizon in days
+        #[arg(long, default_value = "365")]
+        horizon_days: i64,
+
+        /// Show only the riskiest N distros
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+}
+
+#[derive(Subcommand)]
+enum FreezeAction {
+    /// List freezes in effect
+    List {
+        /// Include freezes that have ended
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Stop recalculating a distro's score, or every score without --distro
+    Start {
+        /// Distribution slug; freezes every distro when omitted
+        #[arg(long)]
+        distro: Option<String>,
+
+        /// Why, shown alongside the distro's history
+        #[arg(long)]
+        reason: String,
+
+        /// Lift the freeze automatically at this time (RFC 3339, e.g. 2026-11-01T00:00:00Z)
+        #[arg(long)]
+        until: Option<chrono::DateTime<chrono::Utc>>,
+    },
+
+    /// Lift a freeze now
+    End {
+        /// Freeze ID, from `dv freeze list`
+        id: i64,
+    },
+}
+
+#[derive(Subcommand)]
+enum BenchAction {
+    /// Fill the database with synthetic distros and history to benchmark against
+    Seed {
+        /// Synthetic distros to create
+        #[arg(long, default_value = "500")]
+        distros: usize,
+
+        /// Days of history per distro
+        #[arg(long, default_value = "730")]
+        days: i64,
+
+        /// Days between snapshot batches
+        #[arg(long, default_value = "1")]
+        interval_days: i64,
+
+        /// Random seed, for reproducible data
+        #[arg(long, default_value = "0")]
+        seed: u64,
+    },
+}
+
+#[derive(clap::Args)]
+struct ExportArgs {
+    /// Snapshot kind: github, release, package, community or metric
+    kind: String,
+
+    /// Only this distribution (slug)
+    #[arg(long)]
+    distro: Option<String>,
+
+    /// Only this repository ("owner/name")
+    #[arg(long)]
+    repo: Option<String>,
+
+    /// Only rows from this origin, e.g. "reddit"
+    #[arg(long)]
+    source: Option<String>,
+
+    /// Only this metric key
+    #[arg(long)]
+    key: Option<String>,
+
+    /// Start of the range (RFC 3339 timestamp or YYYY-MM-DD)
+    #[arg(long)]
+    since: Option<String>,
+
+    /// End of the range, inclusive (RFC 3339 timestamp or YYYY-MM-DD)
+    #[arg(long)]
+    until: Option<String>,
+
+    /// Newest first
+    #[arg(long)]
+    desc: bool,
+
+    /// Output format: jsonl or csv
+    #[arg(long, default_value = "jsonl")]
+    format: String,
+
+    /// Write to a file instead of stdout
+    #[arg(short, long)]
+    output: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum AnalyzeReport {
+    /// Correlate raw metrics with score components across distros and over time
+    Correlations {
+        /// Length of the predictive window in days
+        #[arg(long, default_value = "90")]
+        days: i64,
+
+        /// Write the full report as Markdown to this file
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum SuggestionAction {
+    /// List suggestions
+    List {
+        /// Filter by status (pending, approved, rejected)
+        #[arg(long, default_value = "pending")]
+        status: String,
+    },
+
+    /// Approve a suggestion and start tracking the distribution
+    Approve {
+        /// Suggestion ID
+        id: i64,
+    },
+
+    /// Reject a suggestion
+    Reject {
+        /// Suggestion ID
+        id: i64,
+    },
+}
+
+#[derive(Subcommand)]
+enum MirrorAction {
+    /// List repos excluded as mirrors and any manual overrides
+    List {
+        /// Distribution slug
+        distro: String,
+    },
+
+    /// Treat a repo as a mirror and exclude it from aggregation
+    Mark {
+        /// Distribution slug
+        distro: String,
+        /// Repository ("owner/name")
+        repo: String,
+    },
+
+    /// Treat a repo as the distro's own source, even if it looks like a mirror
+    Source {
+        /// Distribution slug
+        distro: String,
+        /// Repository ("owner/name")
+        repo: String,
+    },
+
+    /// Remove an override and go back to automatic detection
+    Clear {
+        /// Distribution slug
+        distro: String,
+        /// Repository ("owner/name")
+        repo: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ArchiveAction {
+    /// List archived distributions
+    List,
+
+    /// Hide a distribution from listings and collection, keeping its history
+    Add {
+        /// Distribution slug
+        distro: String,
+    },
+
+    /// Bring an archived distribution back
+    Restore {
+        /// Distribution slug
+        distro: String,
+    },
+
+    /// Permanently delete an archived distribution and all of its history
+    Purge {
+        /// Distribution slug
+        distro: String,
+
+        /// Confirm the deletion
+        #[arg(long)]
+        yes: bool,
+    },
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     let cli = Cli::parse();
 
-    // Initialize logging
+    // Generated from the CLI definition alone, without logging or a database
+    match cli.command {
+        Commands::Completions { shell } => {
+            print!("{}", generate::completions(&mut Cli::command(), shell));
+            return Ok(());
+        }
+        Commands::Manpage => {
+            print!("{}", generate::manpage(&mut Cli::command()));
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    // Initialize logging on stderr, keeping stdout clean for exports
     let level = if cli.verbose { Level::DEBUG } else { Level::INFO };
     FmtSubscriber::builder()
+        .with_writer(std::io::stderr)
         .with_max_level(level)
         .with_target(false)
         .compact()
         .init();
 
+    // Restoring has to happen before connecting, which would create an empty database
+    if let Commands::Db {
+        action: DbAction::Restore { from, force },
+    } = &cli.command
+    {
+        return restore(&cli.database, from, *force).await;
+    }
+
+    // An in-memory demo never touches the database file
+    if let Commands::Demo {
+        in_memory: true,
+        days,
+        seed,
+        bind,
+        ..
+    } = cli.command
+    {
+        let db = Database::in_memory().await?;
+        db.seed(DEFAULT_SEED).await?;
+        demo(&db, days, seed).await?;
+        return serve(db, bind, None, None).await;
+    }
+
     // Connect to database
     let db = Database::connect(&cli.database).await?;
 
+    // Publish snapshot and score events if a broker is configured
+    #[cfg(feature = "events")]
+    let db = match std::env::var("DV_EVENTS_URL").ok().filter(|url| !url.is_empty()) {
+        Some(url) => db.with_events(distrovitals_database::events::EventPublisher::from_url(&url)?),
+        None => db,
+    };
+
+    // A brand new database gets the bundled distro list unless it's being seeded by hand
+    if !matches!(cli.command, Commands::Seed { .. }) && db.needs_seed().await? {
+        info!("New database, seeding the bundled distro list (see `dv seed --file` for custom sets)");
+        db.seed(DEFAULT_SEED).await?;
+    }
+
     match cli.command {
-        Commands::Serve { bind, static_dir } => {
-            serve(db, bind, static_dir).await?;
+        Commands::Serve {
+            bind,
+            static_dir,
+            dev,
+            frontend,
+            no_open,
+        } => {
+            let dev = dev.then_some(DevMode {
+                frontend,
+                open: !no_open,
+            });
+            serve(db.clone(), bind, static_dir, dev).await?;
+        }
+        Commands::Collect { distro, sources, force } => {
+            collect(&db, &distro, &sources, force).await?;
         }
-        Commands::Collect { distro } => {
-            collect(&db, &distro).await?;
+        Commands::CollectUpstreams { upstream } => {
+            collect_upstreams(&db, &upstream).await?;
         }
-        Commands::CollectReddit { distro } => {
-            collect_reddit(&db, &distro).await?;
+        Commands::CheckConfig { distro } => {
+            check_config(&db, &distro).await?;
         }
-        Commands::Analyze { distro } => {
-            analyze(&db, &distro).await?;
+        Commands::Daemon {
+            interval_hours,
+            sources,
+            hooks,
+            replicate,
+        } => {
+            let replicate = replicate.or_else(backup_url).map(|url| (cli.database.clone(), url));
+            daemon(&db, interval_hours, &sources, &hooks, replicate).await?;
+        }
+        Commands::Analyze {
+            report: Some(AnalyzeReport::Correlations { days, output }),
+            ..
+        } => {
+            correlations(&db, days, output).await?;
+        }
+        Commands::Analyze { distro, hooks, .. } => {
+            analyze(&db, &distro, &hooks).await?;
         }
         Commands::List => {
             list(&db).await?;
         }
-        Commands::Rankings => {
-            rankings(&db).await?;
+        Commands::Rankings { os_family } => {
+            rankings(&db, os_family.as_deref()).await?;
         }
         Commands::Status { distro } => {
             status(&db, &distro).await?;
         }
+        Commands::Export(args) => {
+            export(&db, args).await?;
+        }
+        Commands::Suggestions { action } => {
+            suggestions(&db, action).await?;
+        }
+        Commands::Seed { file } => {
+            seed(&db, file).await?;
+        }
+        Commands::Doctor => {
+            doctor(&db).await?;
+        }
+        Commands::Highlight { dry_run } => {
+            highlight(&db, dry_run).await?;
+        }
+        Commands::PublishWeekly { dry_run, template } => {
+            publish_weekly(&db, dry_run, template).await?;
+        }
+        Commands::Mirrors { action } => {
+            mirrors(&db, action).await?;
+        }
+        Commands::Archive { action } => {
+            archive(&db, action).await?;
+        }
+        Commands::Report { action } => {
+            report(&db, action).await?;
+        }
+        Commands::Freeze { action } => {
+            freeze(&db, action).await?;
+        }
+        Commands::RenameOrg { old, new } => {
+            rename_org(&db, &old, &new).await?;
+        }
+        Commands::Db { .. } => unreachable!("restore runs before the database is opened"),
+        Commands::Demo {
+            days,
+            seed,
+            serve: then_serve,
+            bind,
+            ..
+        } => {
+            demo(&db, days, seed).await?;
+            if then_serve {
+                serve(db.clone(), bind, None, None).await?;
+            }
+        }
+        Commands::Bench { action } => {
+            bench(&db, action).await?;
+        }
+        Commands::Completions { .. } | Commands::Manpage => unreachable!("handled before connecting"),
     }
 
+    db.close().await;
     Ok(())
 }
 
-async fn serve(db: Database, bind: SocketAddr, static_dir: Option<PathBuf>) -> Result<()> {
-    let state = Arc::new(AppState::new(db));
-    let router = create_router(state, static_dir.clone());
+/// Resolve on Ctrl-C or SIGTERM, so long-running commands can close the database cleanly
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            eprintln!("Failed to listen for Ctrl-C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                eprintln!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+    info!("Shutting down");
+}
+
+/// Settings for `serve --dev`
+struct DevMode {
+    frontend: String,
+    open: bool,
+}
+
+async fn serve(db: Database, bind: SocketAddr, static_dir: Option<PathBuf>, dev: Option<DevMode>) -> Result<()> {
+    let mut state = AppState::new(db.clone());
+    if let Some(path) = &db.config().read_replica {
+        let replica = Database::connect_replica(path, db.config().clone()).await?;
+        state = state.with_replica(replica);
+    }
+    let state = Arc::new(state);
+    spawn_job_worker(state.clone());
+    let mut router = create_router(state, static_dir.clone());
+    if let Some(dev) = &dev {
+        router = with_dev_proxy(router, &dev.frontend);
+    }
 
     info!("Starting DistroVitals server on {}", bind);
     if let Some(ref dir) = static_dir {
         info!("Serving static files from {}", dir.display());
     }
+    if let Some(dev) = &dev {
+        info!("Development mode: proxying everything outside /api/v1 to {}", dev.frontend);
+    }
     info!("API available at http://{}/api/v1", bind);
 
     let listener = tokio::net::TcpListener::bind(bind).await?;
-    axum::serve(listener, router).await?;
+    if dev.as_ref().is_some_and(|dev| dev.open) {
+        open_browser(&format!("http://{}/", bind));
+    }
+    axum::serve(
+        listener,
+        router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await?;
 
     Ok(())
 }
 
-async fn collect_reddit(db: &Database, distro_slug: &str) -> Result<()> {
+/// Open a URL in the desktop's browser, if there is one
+fn open_browser(url: &str) {
+    let opener = if cfg!(target_os = "macos") {
+        "open"
+    } else if cfg!(windows) {
+        "explorer"
+    } else {
+        "xdg-open"
+    };
+    if let Err(e) = std::process::Command::new(opener)
+        .arg(url)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+    {
+        info!("Couldn't open a browser ({}), visit {}", e, url);
+    }
+}
+
+async fn collect(db: &Database, distro_slug: &str, source_names: &[String], force: bool) -> Result<()> {
+    let sources = match Source::parse_list(source_names) {
+        Ok(sources) if sources.is_empty() => Source::ALL.to_vec(),
+        Ok(sources) => sources,
+        Err(unknown) => anyhow::bail!(
+            "Unknown sources: {} (expected {})",
+            unknown.join(", "),
+            Source::names()
+        ),
+    };
+
     let config = CollectorConfig::default();
-    let collector = RedditCollector::new(config)?;
 
-    if distro_slug == "all" {
-        println!("Collecting Reddit data for all distributions...");
-        match collector.collect_all(db).await {
-            Ok(ids) => println!("Reddit: {} snapshots collected", ids.len()),
-            Err(e) => eprintln!("Reddit: Error - {}", e),
-        }
+    let uses_github = sources.contains(&Source::Github) || sources.contains(&Source::Governance);
+    if uses_github && config.github_tokens.is_empty() {
+        eprintln!("Warning: GITHUB_TOKEN not set. API rate limits will be restricted.");
+    }
+
+    let collectors = SourceCollectors::new(config)?;
+
+    let distros = if distro_slug == "all" {
+        db.get_distributions().await?
     } else {
-        let distro = db.get_distribution_by_slug(distro_slug).await?;
-        println!("Collecting Reddit data for {}...", distro.name);
+        vec![db.get_distribution_by_slug(distro_slug).await?]
+    };
 
-        if let Some(ref subreddit) = distro.subreddit {
-            match collector.collect_subreddit(db, distro.id, subreddit).await {
-                Ok(_) => println!("  Reddit: r/{} collected", subreddit),
-                Err(e) => eprintln!("  Reddit: Error - {}", e),
-            }
+    let deadline = collectors.run_deadline();
+    for (i, distro) in distros.iter().enumerate() {
+        if deadline.is_some_and(|d| tokio::time::Instant::now() >= d) {
+            let skipped: Vec<&str> = distros[i..].iter().map(|d| d.slug.as_str()).collect();
+            println!("\nRun deadline reached; skipped {} distros: {}", skipped.len(), skipped.join(", "));
+            break;
+        }
+
+        println!("Collecting data for {}...", distro.name);
+
+        let results = if force {
+            collectors.collect_distro(db, distro, &sources, deadline).await
         } else {
-            println!("  Reddit: No subreddit configured, skipping");
+            collectors.collect_stale(db, distro, &sources, deadline).await
+        };
+        for (source, result) in results {
+            match result {
+                Ok(SourceOutcome::Collected(count)) => {
+                    println!("  {}: {} snapshots collected", source.label(), count)
+                }
+                Ok(SourceOutcome::Skipped(reason)) => println!("  {}: {}, skipping", source.label(), reason),
+                Err(e) => eprintln!("  {}: Error - {}", source.label(), e),
+            }
+        }
+
+        if i + 1 < distros.len() {
+            if let Some(pause) = sources.iter().filter_map(|s| s.pacing()).max() {
+                tokio::time::sleep(pause).await;
+            }
         }
     }
 
-    println!("\nReddit collection complete!");
+    println!("\nCollection complete!");
     Ok(())
 }
 
-async fn collect(db: &Database, distro_slug: &str) -> Result<()> {
+async fn collect_upstreams(db: &Database, upstream_slug: &str) -> Result<()> {
     let config = CollectorConfig::default();
 
-    if config.github_token.is_none() {
+    if config.github_tokens.is_empty() {
         eprintln!("Warning: GITHUB_TOKEN not set. API rate limits will be restricted.");
     }
 
     let collector = GithubCollector::new(config)?;
 
-    let distros = if distro_slug == "all" {
-        db.get_distributions().await?
+    let upstreams = if upstream_slug == "all" {
+        db.get_upstreams().await?
     } else {
-        vec![db.get_distribution_by_slug(distro_slug).await?]
+        vec![db.get_upstream_by_slug(upstream_slug).await?]
     };
 
-    for distro in distros {
-        println!("Collecting data for {}...", distro.name);
+    for upstream in upstreams {
+        println!("Collecting upstream data for {}...", upstream.name);
 
-        if let Some(ref org) = distro.github_org {
-            match collector.collect_org_repos(db, distro.id, org).await {
-                Ok(ids) => println!("  GitHub: {} snapshots collected", ids.len()),
-                Err(e) => eprintln!("  GitHub: Error - {}", e),
-            }
+        match collector.collect_upstream(db, &upstream).await {
+            Ok(ids) => println!("  GitHub: {} snapshots collected", ids.len()),
+            Err(e) => eprintln!("  GitHub: Error - {}", e),
+        }
+    }
 
-            match collector.collect_org_releases(db, distro.id, org).await {
-                Ok(ids) => println!("  Releases: {} collected", ids.len()),
-                Err(e) => eprintln!("  Releases: Error - {}", e),
-            }
-        } else {
-            println!("  GitHub: No org configured, skipping");
+    println!("\nUpstream collection complete!");
+    Ok(())
+}
+
+async fn check_config(db: &Database, distro_slug: &str) -> Result<()> {
+    let config = CollectorConfig::default();
+    let checker = DriftChecker::new(config)?;
+
+    if distro_slug == "all" {
+        println!("Checking configuration for all distributions...");
+        let problems = checker.check_all(db).await?;
+        println!("Config: {} problems found", problems);
+    } else {
+        let distro = db.get_distribution_by_slug(distro_slug).await?;
+        println!("Checking configuration for {}...", distro.name);
+
+        let problems = checker.check_and_record(db, &distro).await?;
+        for p in &problems {
+            println!("  {}: {} ({})", p.field, p.value, p.problem);
+        }
+        if problems.is_empty() {
+            println!("  No problems found");
         }
     }
 
-    println!("\nCollection complete!");
+    println!("\nRun `dv doctor` to review open issues.");
     Ok(())
 }
 
-async fn analyze(db: &Database, distro_slug: &str) -> Result<()> {
+async fn analyze(db: &Database, distro_slug: &str, hooks: &[String]) -> Result<()> {
     let distros = if distro_slug == "all" {
         db.get_distributions().await?
     } else {
         vec![db.get_distribution_by_slug(distro_slug).await?]
     };
 
+    // A full run picks a new homepage highlight once the current one is a week old
+    analyze_distros(db, distros, distro_slug == "all", hooks).await;
+
+    Ok(())
+}
+
+async fn analyze_distros(db: &Database, distros: Vec<Distribution>, refresh_highlight: bool, hooks: &[String]) {
+    let mut summary = RunSummary::start();
+
     for distro in distros {
         print!("Analyzing {}... ", distro.name);
 
         match Analyzer::calculate_health_score(db, distro.id).await {
-            Ok(_) => {
+            Ok(ScoreOutcome::Frozen(freeze)) => println!("Frozen, score unchanged ({})", freeze.reason),
+            Ok(outcome @ (ScoreOutcome::Scored(_) | ScoreOutcome::Unchanged(_))) => {
                 if let Ok(Some(score)) = db.get_latest_health_score(distro.id).await {
+                    if matches!(outcome, ScoreOutcome::Unchanged(_)) {
+                        print!("Unchanged. ");
+                    }
                     println!(
                         "Score: {:.1} (Dev: {:.1}, Community: {:.1}, Maint: {:.1}) [{}]",
                         score.overall_score,
@@ -222,55 +909,458 @@ async fn analyze(db: &Database, distro_slug: &str) -> Result<()> {
                         score.maintenance_score,
                         score.trend
                     );
+                    summary.record_score(&distro, &score);
                 }
             }
-            Err(e) => eprintln!("Error: {}", e),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                summary.record_failure(&distro, e);
+            }
         }
     }
 
-    Ok(())
+    if refresh_highlight {
+        if let Err(e) = Highlighter::refresh(db).await {
+            eprintln!("Highlight selection failed: {}", e);
+        }
+    }
+
+    PostAnalysisHooks::from_env()
+        .with(hooks)
+        .run(&summary.finish())
+        .await;
 }
 
-async fn list(db: &Database) -> Result<()> {
-    let distros = db.get_distributions().await?;
+/// How often the daemon looks for freshly collected distros between collection passes
+const ANALYSIS_POLL: Duration = Duration::from_secs(5 * 60);
 
-    println!("{:<15} {:<20} {:<15}", "SLUG", "NAME", "GITHUB ORG");
-    println!("{}", "-".repeat(50));
+/// How often the daemon checks configured homepages, orgs and subreddits for drift
+const CONFIG_CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
 
-    for distro in distros {
-        println!(
-            "{:<15} {:<20} {:<15}",
-            distro.slug,
-            distro.name,
-            distro.github_org.as_deref().unwrap_or("-")
+async fn daemon(
+    db: &Database,
+    interval_hours: u64,
+    sources: &[String],
+    hooks: &[String],
+    replicate: Option<(PathBuf, String)>,
+) -> Result<()> {
+    anyhow::ensure!(interval_hours > 0, "--interval-hours must be at least 1");
+    // Each pass only refreshes what's stale, so run one whenever the freshest source expires
+    let config = CollectorConfig::default();
+    let shortest_ttl = match Source::parse_list(sources) {
+        Ok(parsed) if !parsed.is_empty() => parsed.iter().map(|&s| config.source_ttl(s)).min(),
+        _ => Source::ALL.iter().map(|&s| config.source_ttl(s)).min(),
+    };
+    let interval = Duration::from_secs(interval_hours * 60 * 60);
+    let interval = shortest_ttl.map_or(interval, |ttl| ttl.min(interval));
+
+    info!("Daemon started: collecting every {} minutes", interval.as_secs() / 60);
+
+    // Litestream runs its own checkpoints and has to see every WAL frame before it's
+    // folded back, so ours are left off while it's replicating
+    let replicating = replicate.is_some();
+    let _litestream = replicate.map(|(database, url)| {
+        info!("Streaming the database to {}", url);
+        AbortOnDrop(tokio::spawn(supervise_litestream(database, url)))
+    });
+    let mut next_collection = Instant::now();
+    let drift = DriftChecker::new(config)?;
+    let mut next_config_check = Instant::now();
+    let checkpoint_interval = db.config().checkpoint_interval;
+    let mut next_checkpoint = Instant::now() + checkpoint_interval;
+    let shutdown = shutdown_signal();
+    tokio::pin!(shutdown);
+
+    loop {
+        if !replicating && Instant::now() >= next_checkpoint {
+            next_checkpoint = Instant::now() + checkpoint_interval;
+            if let Err(e) = db.checkpoint().await {
+                eprintln!("WAL checkpoint failed: {}", e);
+            }
+        }
+
+        if Instant::now() >= next_collection {
+            next_collection = Instant::now() + interval;
+            if let Err(e) = collect(db, "all", sources, false).await {
+                eprintln!("Collection pass failed: {}", e);
+            }
+        }
+
+        if Instant::now() >= next_config_check {
+            next_config_check = Instant::now() + CONFIG_CHECK_INTERVAL;
+            if let Err(e) = drift.check_all(db).await {
+                eprintln!("Config drift check failed: {}", e);
+            }
+        }
+
+        // Analyze whatever got new snapshots, whether from this daemon, `dv collect`
+        // or the API's collection triggers
+        match db.get_distros_pending_analysis().await {
+            Ok(pending) if pending.is_empty() => {}
+            Ok(pending) => {
+                info!("Analyzing {} distros with new snapshots", pending.len());
+                analyze_distros(db, pending, true, hooks).await;
+            }
+            Err(e) => eprintln!("Failed to find distros pending analysis: {}", e),
+        }
+
+        let wait = next_collection
+            .min(next_config_check)
+            .min(next_checkpoint)
+            .saturating_duration_since(Instant::now())
+            .min(ANALYSIS_POLL);
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = &mut shutdown => return Ok(()),
+        }
+    }
+}
+
+/// Litestream replica URL from `DV_BACKUP_URL`
+fn backup_url() -> Option<String> {
+    std::env::var("DV_BACKUP_URL").ok().filter(|url| !url.is_empty())
+}
+
+/// Aborts a background task when the daemon returns
+struct AbortOnDrop(tokio::task::JoinHandle<()>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Keep `litestream replicate` running, restarting it with a backoff when it exits
+///
+/// The child is killed when this task is aborted. Litestream tolerates that: anything
+/// not yet shipped is still in the WAL and goes out on the next start.
+async fn supervise_litestream(database: PathBuf, url: String) {
+    const MIN_BACKOFF: Duration = Duration::from_secs(5);
+    const MAX_BACKOFF: Duration = Duration::from_secs(5 * 60);
+    let mut backoff = MIN_BACKOFF;
+
+    loop {
+        let started = Instant::now();
+        let status = tokio::process::Command::new("litestream")
+            .arg("replicate")
+            .arg(&database)
+            .arg(&url)
+            .kill_on_drop(true)
+            .status()
+            .await;
+        match status {
+            Ok(status) => eprintln!("litestream exited ({}), restarting in {}s", status, backoff.as_secs()),
+            Err(e) => eprintln!("Failed to start litestream: {}; retrying in {}s", e, backoff.as_secs()),
+        }
+
+        // A run that lasted a while was healthy; don't hold its exit against the next one
+        if started.elapsed() > MAX_BACKOFF {
+            backoff = MIN_BACKOFF;
+        }
+        tokio::time::sleep(backoff).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+async fn restore(database: &std::path::Path, from: &str, force: bool) -> Result<()> {
+    let sidecars = ["", "-wal", "-shm"].map(|suffix| {
+        let mut path = database.as_os_str().to_owned();
+        path.push(suffix);
+        PathBuf::from(path)
+    });
+    let kept = sidecars.clone().map(|path| {
+        let mut kept = path.into_os_string();
+        kept.push(".pre-restore");
+        PathBuf::from(kept)
+    });
+
+    if database.exists() {
+        anyhow::ensure!(
+            force,
+            "{} already exists; pass --force to replace it (it will be kept as {})",
+            database.display(),
+            kept[0].display()
+        );
+        for (path, kept) in sidecars.iter().zip(&kept) {
+            if path.exists() {
+                std::fs::rename(path, kept)?;
+            }
+        }
+    }
+
+    println!("Restoring {} from {}", database.display(), from);
+    let status = tokio::process::Command::new("litestream")
+        .arg("restore")
+        .arg("-o")
+        .arg(database)
+        .arg(from)
+        .status()
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to run litestream (is it installed?): {}", e));
+
+    if !matches!(status, Ok(ref status) if status.success()) {
+        for (path, kept) in sidecars.iter().zip(&kept) {
+            if kept.exists() {
+                std::fs::rename(kept, path)?;
+            }
+        }
+        match status {
+            Ok(status) => anyhow::bail!("litestream restore failed ({}); the previous database is untouched", status),
+            Err(e) => return Err(e),
+        }
+    }
+
+    // Bring the restored copy up to the current schema
+    let db = Database::connect(database).await?;
+    let distros = db.get_distributions().await?.len();
+    db.close().await;
+    println!("Restored {} ({} distributions tracked)", database.display(), distros);
+    if kept[0].exists() {
+        println!("The previous database was kept as {}", kept[0].display());
+    }
+    Ok(())
+}
+
+/// Rows fetched per round trip when exporting
+const EXPORT_BATCH: i64 = 5000;
+
+async fn export(db: &Database, args: ExportArgs) -> Result<()> {
+    let kinds: Vec<&str> = SnapshotKind::ALL.iter().map(|k| k.as_str()).collect();
+    let kind = SnapshotKind::parse(&args.kind).ok_or_else(|| {
+        anyhow::anyhow!("Unknown snapshot kind: {} (expected one of {})", args.kind, kinds.join(", "))
+    })?;
+    let csv = match args.format.as_str() {
+        "jsonl" => false,
+        "csv" => true,
+        other => anyhow::bail!("Unknown format: {} (expected jsonl or csv)", other),
+    };
+
+    let mut query = SnapshotQuery::new().limit(EXPORT_BATCH);
+    if let Some(slug) = &args.distro {
+        query = query.distro(db.get_distribution_by_slug_including_archived(slug).await?.id);
+    }
+    if let Some(repo) = args.repo {
+        query = query.repo(repo);
+    }
+    if let Some(source) = args.source {
+        query = query.source(source);
+    }
+    if let Some(key) = args.key {
+        query = query.key(MetricKey::from(key));
+    }
+    if let Some(value) = &args.since {
+        query = query.since(parse_time(value, false)?);
+    }
+    if let Some(value) = &args.until {
+        query = query.until(parse_time(value, true)?);
+    }
+    if args.desc {
+        query = query.order(SortOrder::Desc);
+    }
+
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => Box::new(std::io::BufWriter::new(std::fs::File::create(path)?)),
+        None => Box::new(std::io::stdout().lock()),
+    };
+
+    // Page by keyset so large tables export in constant memory
+    let mut columns: Option<Vec<String>> = None;
+    let mut total = 0;
+    loop {
+        let rows = match kind {
+            SnapshotKind::Github => export_batch::<GithubSnapshot>(db, &query).await?,
+            SnapshotKind::Release => export_batch::<ReleaseSnapshot>(db, &query).await?,
+            SnapshotKind::Package => export_batch::<PackageSnapshot>(db, &query).await?,
+            SnapshotKind::Community => export_batch::<CommunitySnapshot>(db, &query).await?,
+            SnapshotKind::Metric => export_batch::<MetricValue>(db, &query).await?,
+        };
+
+        for row in &rows {
+            if !csv {
+                writeln!(out, "{}", row)?;
+                continue;
+            }
+
+            let Some(fields) = row.as_object() else { continue };
+            let columns = columns.get_or_insert_with(|| {
+                let header: Vec<String> = fields.keys().cloned().collect();
+                let _ = writeln!(out, "{}", header.join(","));
+                header
+            });
+            let cells: Vec<String> = columns.iter().map(|c| csv_cell(&fields[c])).collect();
+            writeln!(out, "{}", cells.join(","))?;
+        }
+
+        total += rows.len();
+        match rows.last().and_then(|row| row["id"].as_i64()) {
+            Some(last_id) if rows.len() as i64 == EXPORT_BATCH => query = query.after(last_id),
+            _ => break,
+        }
+    }
+    out.flush()?;
+
+    if let Some(path) = args.output {
+        println!("Exported {} {} snapshots to {}", total, kind.as_str(), path.display());
+    }
+    Ok(())
+}
+
+async fn export_batch<T: SnapshotTable + serde::Serialize>(
+    db: &Database,
+    query: &SnapshotQuery,
+) -> Result<Vec<serde_json::Value>> {
+    let rows: Vec<T> = query.fetch(db).await?;
+    Ok(rows.iter().map(serde_json::to_value).collect::<Result<_, _>>()?)
+}
+
+fn csv_cell(value: &serde_json::Value) -> String {
+    let text = match value {
+        serde_json::Value::Null => return String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text
+    }
+}
+
+/// Parse an RFC 3339 timestamp or a bare date (start of day, or end of day for `until`)
+fn parse_time(value: &str, end_of_day: bool) -> Result<chrono::DateTime<chrono::Utc>> {
+    parse_time_bound(value, end_of_day)
+        .ok_or_else(|| anyhow::anyhow!("Invalid time: {} (expected RFC 3339 or YYYY-MM-DD)", value))
+}
+
+async fn correlations(db: &Database, days: i64, output: Option<PathBuf>) -> Result<()> {
+    let report = CorrelationReport::build(db, days).await?;
+
+    if let Some(path) = output {
+        std::fs::write(&path, report.to_markdown())?;
+        println!("Report written to {}", path.display());
+        return Ok(());
+    }
+
+    println!("Cross-sectional ({} distros)", report.distros);
+    println!("{:<20} {:<20} {:>4} {:>9} {:>9}", "X", "Y", "N", "SPEARMAN", "PEARSON");
+    println!("{}", "-".repeat(66));
+    for c in report.cross_sectional.iter().take(15) {
+        println!(
+            "{:<20} {:<20} {:>4} {:>9} {:>9}",
+            c.x,
+            c.y,
+            c.n,
+            format_coefficient(c.spearman),
+            format_coefficient(c.pearson)
         );
     }
 
+    println!("\nPredictive ({} days, {} distros)", report.window_days, report.predictive_distros);
+    println!("{:<20} {:<28} {:>4} {:>9} {:>9}", "X AT START", "Y", "N", "SPEARMAN", "PEARSON");
+    println!("{}", "-".repeat(74));
+    for c in report.predictive.iter().take(15) {
+        println!(
+            "{:<20} {:<28} {:>4} {:>9} {:>9}",
+            c.x,
+            c.y,
+            c.n,
+            format_coefficient(c.spearman),
+            format_coefficient(c.pearson)
+        );
+    }
+
+    println!("\nUse --output to write the full report as Markdown.");
+    Ok(())
+}
+
+async fn report(db: &Database, action: ReportAction) -> Result<()> {
+    match action {
+        ReportAction::Risks { horizon_days, limit } => {
+            anyhow::ensure!(horizon_days > 0, "--horizon-days must be positive");
+            let report = LifecycleReport::build(db, horizon_days).await?;
+            if report.forecasts.is_empty() {
+                println!("No distro has enough stable releases to forecast.");
+                return Ok(());
+            }
+
+            println!("Probability of no stable release in the next {} days\n", report.horizon_days);
+            println!("{:<24} {:>8} {:>9} {:>11} {:>10}", "DISTRO", "RELEASES", "MEDIAN GAP", "SINCE LAST", "NO RELEASE");
+            println!("{}", "-".repeat(67));
+            for forecast in report.forecasts.iter().take(limit.unwrap_or(usize::MAX)) {
+                println!(
+                    "{:<24} {:>8} {:>9}d {:>10}d {:>9.0}%",
+                    forecast.slug,
+                    forecast.stable_releases,
+                    forecast.median_gap_days,
+                    forecast.days_since_release,
+                    forecast.probability_no_release * 100.0
+                );
+            }
+
+            if !report.insufficient_history.is_empty() {
+                println!(
+                    "\n{} distros have too few stable releases to forecast: {}",
+                    report.insufficient_history.len(),
+                    report.insufficient_history.join(", ")
+                );
+            }
+        }
+    }
     Ok(())
 }
 
-async fn rankings(db: &Database) -> Result<()> {
+async fn list(db: &Database) -> Result<()> {
     let distros = db.get_distributions().await?;
+
+    println!("{:<15} {:<20} {:<8} {:<15}", "SLUG", "NAME", "FAMILY", "GITHUB ORG");
+    println!("{}", "-".repeat(60));
+
+    for distro in distros {
+        println!(
+            "{:<15} {:<20} {:<8} {:<15}",
+            distro.slug,
+            distro.name,
+            distro.os_family,
+            distro.github_org.as_deref().unwrap_or("-")
+        );
+    }
+
+    Ok(())
+}
+
+async fn rankings(db: &Database, os_family: Option<&str>) -> Result<()> {
+    let distros: Vec<_> = db
+        .get_distributions()
+        .await?
+        .into_iter()
+        .filter(|d| {
+            os_family
+                .map(|f| f.split(',').any(|f| f.trim().eq_ignore_ascii_case(&d.os_family)))
+                .unwrap_or(true)
+        })
+        .collect();
     let scores = db.get_all_latest_health_scores().await?;
 
     println!("{:<5} {:<15} {:<10} {:<8}", "RANK", "DISTRO", "SCORE", "TREND");
     println!("{}", "-".repeat(40));
 
-    for (idx, score) in scores.iter().enumerate() {
-        if let Some(distro) = distros.iter().find(|d| d.id == score.distro_id) {
-            let trend_icon = match score.trend.as_str() {
-                "up" => "↑",
-                "down" => "↓",
-                _ => "→",
-            };
-            println!(
-                "{:<5} {:<15} {:<10.1} {}",
-                idx + 1,
-                distro.slug,
-                score.overall_score,
-                trend_icon
-            );
-        }
+    let ranked = scores
+        .iter()
+        .filter_map(|score| distros.iter().find(|d| d.id == score.distro_id).map(|d| (score, d)));
+
+    for (idx, (score, distro)) in ranked.enumerate() {
+        let trend_icon = match score.trend.as_str() {
+            "up" => "↑",
+            "down" => "↓",
+            _ => "→",
+        };
+        println!(
+            "{:<5} {:<15} {:<10.1} {}",
+            idx + 1,
+            distro.slug,
+            score.overall_score,
+            trend_icon
+        );
     }
 
     if scores.is_empty() {
@@ -286,6 +1376,26 @@ async fn status(db: &Database, distro_slug: &str) -> Result<()> {
     println!("Distribution: {} ({})", distro.name, distro.slug);
     println!("Homepage: {}", distro.homepage.as_deref().unwrap_or("-"));
     println!("GitHub Org: {}", distro.github_org.as_deref().unwrap_or("-"));
+    if let Some(ref group) = distro.gitlab_group {
+        println!("GitLab Group: {} ({})", group, Forge::for_distro(&distro, ForgeKind::Gitlab).base_url);
+    }
+    if let Some(ref org) = distro.forgejo_org {
+        println!("Forgejo Org: {} ({})", org, Forge::for_distro(&distro, ForgeKind::Forgejo).base_url);
+    }
+    if let Some(ref namespace) = distro.pagure_namespace {
+        println!("Pagure Namespace: {} ({})", namespace, Forge::for_distro(&distro, ForgeKind::Pagure).base_url);
+    }
+    if let Some(ref repo) = distro.discussions_repo {
+        println!("GitHub Discussions: {}", repo);
+    }
+    if let Some(ref parent) = distro.variant_of {
+        println!("Variant of: {} (scored on its development data, see `dv status {}`)", parent, parent);
+    }
+    let variants = db.get_variants(&distro.slug).await?;
+    if !variants.is_empty() {
+        let slugs: Vec<&str> = variants.iter().map(|v| v.slug.as_str()).collect();
+        println!("Variants: {}", slugs.join(", "));
+    }
     println!();
 
     if let Ok(Some(score)) = db.get_latest_health_score(distro.id).await {
@@ -304,6 +1414,139 @@ async fn status(db: &Database, distro_slug: &str) -> Result<()> {
         println!("No health score available yet.");
     }
 
+    if let Some(quality) = DataQuality::assess(db, distro.id).await? {
+        println!(
+            "Data Quality: {:.0}/100, {} ({} of {} sources current)",
+            quality.score,
+            quality.level.as_str(),
+            quality.sources_ok,
+            quality.sources
+        );
+        for flag in &quality.flags {
+            println!("  ! {}", flag.detail);
+        }
+    }
+
+    if let Some(packages) = db.get_latest_package_snapshot(distro.id).await? {
+        println!(
+            "\nPackages: {} ({} outdated)",
+            packages.total_packages, packages.outdated_packages
+        );
+        if let Some(official) = packages.official_packages {
+            let flagged = packages.flagged_packages.map_or(String::new(), |f| format!(" ({} flagged out of date)", f));
+            println!("Official Repos: {} packages{}", official, flagged);
+        }
+        if let Some(aur) = packages.aur_packages {
+            println!("AUR: {} packages", aur);
+        }
+        if let Some(ref version) = packages.kernel_version {
+            let status = packages.kernel_status.as_deref().unwrap_or("unknown");
+            let warning = if status == "eol" { " ⚠ shipping EOL kernel" } else { "" };
+            println!("Kernel: {} [{}]{}", version, status, warning);
+        }
+    }
+    if let Some(hardware) = HardwareEnablement::load(db, &distro).await? {
+        println!(
+            "Hardware Enablement Lag: {} upstream releases behind since {}",
+            hardware.lag,
+            hardware.released_at.format("%Y-%m-%d")
+        );
+        for (name, lag) in [("Kernel", &hardware.kernel), ("Mesa", &hardware.mesa)] {
+            if let Some(lag) = lag {
+                println!("  {}: {} (upstream {}, {} behind)", name, lag.shipped, lag.upstream, lag.releases_behind);
+            }
+        }
+    }
+
+    if let Some(infra) = db.get_latest_infra_snapshot(distro.id).await? {
+        let countries = match infra.mirror_countries {
+            Some(1) => " in 1 country".to_string(),
+            Some(c) => format!(" in {} countries", c),
+            None => String::new(),
+        };
+        let warning = if infra.mirrors <= 1 { " ⚠ single mirror" } else { "" };
+        println!("Mirrors: {}{}{}", infra.mirrors, countries, warning);
+    }
+
+    if let Some(archive) = db.get_latest_archive_snapshot(distro.id).await? {
+        println!("Archive: {} source packages", archive.source_packages);
+        if let Some(rc_bugs) = archive.rc_bugs {
+            println!("RC Bugs: {}", rc_bugs);
+        }
+        match archive.uploads_30d {
+            Some(uploads) => println!("Uploads (30d): {}", uploads),
+            None => println!("Uploads (30d): not enough history yet"),
+        }
+    }
+
+    if let Some(bugs) = db.get_latest_bugtracker_snapshot(distro.id).await? {
+        let flow = match (bugs.opened_30d, bugs.closed_30d) {
+            (Some(opened), Some(closed)) => format!(" ({} filed, {} resolved in 30d)", opened, closed),
+            _ => String::new(),
+        };
+        println!("Bugzilla: {} open bugs{}", bugs.open_bugs, flow);
+        if let Some(days) = bugs.mean_resolution_days {
+            println!("Mean Time to Resolution (30d): {:.1} days", days);
+        }
+    }
+
+    let metrics = db.get_latest_metrics(distro.id).await?;
+    if let Some(load) = metrics.iter().find(|m| m.key == MetricKey::MaintainerLoad) {
+        let warning = if is_overloaded(load.value) { " ⚠ demand outpacing maintainers" } else { "" };
+        println!("\nMaintainer Load: {:.0} subscribers + open issues per active maintainer{}", load.value, warning);
+    }
+    if let Some(newcomers) = metrics.iter().find(|m| m.key == MetricKey::FirstTimeContributors30d) {
+        println!("First-time contributors merged (30d): {:.0}", newcomers.value);
+    }
+    if let Some(iso) = metrics.iter().find(|m| m.key == MetricKey::IsoSizeMb) {
+        println!("Installer Image: {:.0} MB", iso.value);
+    }
+    if let Some(payload) = metrics.iter().find(|m| m.key == MetricKey::UpdatePayloadMb30d) {
+        println!("Update Payload (30d): {:.0} MB", payload.value);
+    }
+    if let Some(popularity) = db.get_latest_popularity_snapshot(distro.id).await? {
+        println!("Active Installs (30d, opt-in pings, unverified): {}", popularity.active_installs_30d);
+    }
+    for metric in metrics.iter().filter(|m| matches!(m.key, MetricKey::Custom(_))) {
+        println!("{}: {}", metric.key, metric.value);
+    }
+
+    if let Some(risk) = UpstreamRisk::assess(db, distro.id).await? {
+        println!("\nUpstream Risk: {}", risk.level);
+        for upstream in &risk.upstreams {
+            println!(
+                "  {} - {} (commits 30d: {}, last commit: {})",
+                upstream.name,
+                upstream.level,
+                upstream.commits_30d,
+                upstream
+                    .days_since_commit
+                    .map(|d| format!("{}d ago", d))
+                    .unwrap_or_else(|| "-".to_string())
+            );
+        }
+    }
+
+    if let Some(governance) = GovernanceChecklist::load(db, distro.id).await? {
+        println!("\nGovernance Checklist: {:.0}% ({} core repos)", governance.score, governance.repos_checked);
+        for item in &governance.items {
+            println!("  [{}] {}", if item.present { "x" } else { " " }, item.label);
+        }
+    }
+
+    if let Some(org) = db.get_latest_org_snapshot(distro.id).await? {
+        let count = |n: Option<i64>| n.map_or_else(|| "-".to_string(), |n| n.to_string());
+        println!(
+            "\nGitHub Org Profile: {} - {} public repos, {} public members, {} followers, {} sponsors{}",
+            org.org,
+            org.public_repos,
+            count(org.public_members),
+            org.followers,
+            count(org.sponsors),
+            org.org_created_at.map(|at| format!(", since {}", at.format("%Y-%m-%d"))).unwrap_or_default()
+        );
+    }
+
     let github_snapshots = db.get_latest_github_snapshots(distro.id).await?;
     if !github_snapshots.is_empty() {
         println!("\nGitHub Metrics:");
@@ -318,5 +1561,503 @@ async fn status(db: &Database, distro_slug: &str) -> Result<()> {
         }
     }
 
+    let mirrors = db.get_mirror_github_snapshots(distro.id).await?;
+    if !mirrors.is_empty() {
+        println!("  ({} mirror repos excluded, see `dv mirrors list {}`)", mirrors.len(), distro.slug);
+    }
+
+    if let Some(activity) = db.get_issue_activity(distro.id).await? {
+        println!(
+            "\nIssues (30d, core repos): {} opened, {} closed ({} by stale bots)",
+            activity.opened_30d, activity.closed_30d, activity.stale_closed_30d
+        );
+        if let (Some(raw), Some(adjusted)) = (activity.resolution_pct(), activity.adjusted_resolution_pct()) {
+            println!("  Resolution: {:.0}% raw, {:.0}% without stale-bot closures", raw, adjusted);
+        }
+    }
+
+    Ok(())
+}
+
+async fn suggestions(db: &Database, action: SuggestionAction) -> Result<()> {
+    match action {
+        SuggestionAction::List { status } => {
+            let suggestions = db.get_suggestions_by_status(&status).await?;
+
+            println!("{:<5} {:<15} {:<20} {:<8} {:<15}", "ID", "SLUG", "NAME", "FAMILY", "GITHUB ORG");
+            println!("{}", "-".repeat(65));
+
+            for s in &suggestions {
+                println!(
+                    "{:<5} {:<15} {:<20} {:<8} {:<15}",
+                    s.id,
+                    s.slug,
+                    s.name,
+                    s.os_family,
+                    s.github_org.as_deref().unwrap_or("-")
+                );
+                if let Some(ref reason) = s.reason {
+                    println!("      {}", reason);
+                }
+            }
+
+            if suggestions.is_empty() {
+                println!("No {} suggestions.", status);
+            }
+        }
+        SuggestionAction::Approve { id } => {
+            let distro = db.approve_suggestion(id).await?;
+            println!("Approved suggestion {}: now tracking {} ({})", id, distro.name, distro.slug);
+        }
+        SuggestionAction::Reject { id } => {
+            db.reject_suggestion(id).await?;
+            println!("Rejected suggestion {}", id);
+        }
+    }
+
+    Ok(())
+}
+
+async fn highlight(db: &Database, dry_run: bool) -> Result<()> {
+    if dry_run {
+        match Highlighter::select(db, chrono::Utc::now()).await? {
+            Some(pick) => println!("[{}] {}\n\n(dry run: not recorded)", pick.reason, pick.blurb),
+            None => println!("No scored distributions to highlight"),
+        }
+        return Ok(());
+    }
+
+    match Highlighter::run(db).await? {
+        Some(pick) => println!("[{}] {}", pick.reason, pick.blurb),
+        None => println!("No scored distributions to highlight"),
+    }
+
+    Ok(())
+}
+
+async fn publish_weekly(db: &Database, dry_run: bool, template: Option<PathBuf>) -> Result<()> {
+    let template_path = template.or_else(|| {
+        std::env::var("DV_MASTODON_TEMPLATE")
+            .ok()
+            .filter(|p| !p.is_empty())
+            .map(PathBuf::from)
+    });
+    let template = match template_path {
+        Some(path) => std::fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read template {}: {}", path.display(), e))?,
+        None => DEFAULT_TEMPLATE.to_string(),
+    };
+
+    let now = chrono::Utc::now();
+    let digest = WeeklyDigest::build(db, now).await?;
+    let summary = digest.render(template.trim_end());
+    let replies = digest.event_posts();
+
+    if dry_run {
+        println!("{}", summary);
+        for reply in &replies {
+            println!("\n  ↳ {}", reply);
+        }
+        println!("\n(dry run: {} posts not sent)", replies.len() + 1);
+        return Ok(());
+    }
+
+    let publisher = MastodonPublisher::from_env().ok_or_else(|| {
+        anyhow::anyhow!("DV_MASTODON_INSTANCE and DV_MASTODON_TOKEN must be set (or use --dry-run)")
+    })?;
+
+    let key = format!("distrovitals-weekly-{}", digest.week_ending);
+    let thread = publisher
+        .publish(&key, &summary, &replies)
+        .await
+        .map_err(|e| anyhow::anyhow!(e))?;
+
+    println!("Posted {} statuses (thread {})", thread.status_ids.len(), thread.status_ids[0]);
+    Ok(())
+}
+
+async fn seed(db: &Database, file: Option<PathBuf>) -> Result<()> {
+    let before = db.get_distributions().await?.len();
+    match file {
+        Some(path) => {
+            let sql = std::fs::read_to_string(&path)
+                .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+            db.seed(&sql).await?;
+            println!("Applied {}", path.display());
+        }
+        None => {
+            db.seed(DEFAULT_SEED).await?;
+            println!("Applied the bundled seed");
+        }
+    }
+
+    let after = db.get_distributions().await?.len();
+    println!("{} distributions tracked ({} new)", after, after.saturating_sub(before));
+    Ok(())
+}
+
+async fn demo(db: &Database, days: i64, seed: u64) -> Result<()> {
+    if !db.get_all_latest_health_scores().await?.is_empty() {
+        anyhow::bail!("This database already has scores; demo data goes in a fresh one (or use --in-memory)");
+    }
+
+    let started = Instant::now();
+    let distros = db.get_distributions().await?;
+    let history = SyntheticHistory {
+        days,
+        interval_days: 1,
+        seed,
+    };
+    let summary = db.generate_history(&distros, &history).await?;
+
+    println!(
+        "Generated {} days of history for {} distros: {} snapshots, {} scores in {:.1}s",
+        days,
+        summary.distros,
+        summary.snapshots,
+        summary.scores,
+        started.elapsed().as_secs_f64()
+    );
+    Ok(())
+}
+
+/// Slug prefix of the distros `dv bench seed` creates
+const BENCH_PREFIX: &str = "bench";
+
+async fn bench(db: &Database, action: BenchAction) -> Result<()> {
+    match action {
+        BenchAction::Seed {
+            distros,
+            days,
+            interval_days,
+            seed,
+        } => {
+            if db.get_distribution_by_slug(&format!("{}-0001", BENCH_PREFIX)).await.is_ok() {
+                anyhow::bail!("This database already has benchmark distros; seed a fresh one with -d");
+            }
+
+            let started = Instant::now();
+            let created = db.create_synthetic_distributions(BENCH_PREFIX, distros).await?;
+            let history = SyntheticHistory {
+                days,
+                interval_days,
+                seed,
+            };
+            let summary = db.generate_history(&created, &history).await?;
+            db.checkpoint().await?;
+
+            println!(
+                "Generated {} distros x {} batches: {} snapshots, {} scores in {:.1}s",
+                summary.distros,
+                summary.batches / summary.distros.max(1),
+                summary.snapshots,
+                summary.scores,
+                started.elapsed().as_secs_f64()
+            );
+        }
+    }
+    Ok(())
+}
+
+async fn doctor(db: &Database) -> Result<()> {
+    println!("Environment");
+    println!("{}", "-".repeat(65));
+    let config = CollectorConfig::default();
+    match config.github_tokens.len() {
+        0 => println!("  warn  GITHUB_TOKEN not set: GitHub API rate limits will be restricted"),
+        1 => println!("  ok    GITHUB_TOKEN"),
+        n => println!("  ok    GITHUB_TOKENS ({} tokens rotated by quota)", n),
+    }
+    let deadline = match config.run_deadline {
+        Some(limit) => format!("{}s per run", limit.as_secs()),
+        None => "no run deadline".to_string(),
+    };
+    println!(
+        "  ok    collection limits: {}s per request, {}s per distro, {}",
+        config.request_timeout.as_secs(),
+        config.distro_budget.as_secs(),
+        deadline
+    );
+    if !config.custom_metrics.is_empty() {
+        let distros = db.get_distributions().await?;
+        let untracked: Vec<&str> = config
+            .custom_metrics
+            .iter()
+            .filter(|m| !distros.iter().any(|d| d.slug == m.distro))
+            .map(|m| m.key.as_str())
+            .collect();
+        if untracked.is_empty() {
+            println!("  ok    DV_CUSTOM_METRICS ({} metrics)", config.custom_metrics.len());
+        } else {
+            println!("  warn  DV_CUSTOM_METRICS: no tracked distro for {}", untracked.join(", "));
+        }
+    }
+
+    let tuning = db.config();
+    println!(
+        "  ok    database: {} connections, {}ms busy timeout, {} KiB cache, {} MiB mmap, checkpoint every {}s",
+        tuning.pool_size,
+        tuning.busy_timeout.as_millis(),
+        tuning.cache_size_kib,
+        tuning.mmap_size / (1024 * 1024),
+        tuning.checkpoint_interval.as_secs()
+    );
+    if let Some(path) = &tuning.read_replica {
+        match Database::connect_replica(path, tuning.clone()).await {
+            Ok(replica) => match replica.get_distributions().await {
+                Ok(_) => println!("  ok    read replica: {}", path.display()),
+                Err(e) => println!("  warn  read replica {} is unreadable: {}", path.display(), e),
+            },
+            Err(e) => println!("  warn  read replica {} can't be opened: {}", path.display(), e),
+        }
+    }
+
+    match db.seeded_at().await? {
+        Some(at) => println!("  ok    seeded {}", at.format("%Y-%m-%d %H:%M")),
+        None => println!("  warn  seed never recorded: run `dv seed` after upgrading to pick up new distros"),
+    }
+
+    let env_checks = [
+        ("DV_ADMIN_TOKEN", "admin endpoints are disabled"),
+        ("DV_CAPTCHA_SECRET", "suggestions are accepted without a captcha"),
+        #[cfg(feature = "events")]
+        ("DV_EVENTS_URL", "no events are published"),
+    ];
+    for (var, consequence) in env_checks {
+        if std::env::var(var).is_ok() {
+            println!("  ok    {}", var);
+        } else {
+            println!("  warn  {} not set: {}", var, consequence);
+        }
+    }
+
+    match backup_url() {
+        Some(url) => match std::process::Command::new("litestream").arg("version").output() {
+            Ok(output) if output.status.success() => println!(
+                "  ok    backups stream to {} (litestream {})",
+                url,
+                String::from_utf8_lossy(&output.stdout).trim()
+            ),
+            _ => println!("  warn  DV_BACKUP_URL is set but `litestream` isn't on PATH: the daemon can't back up"),
+        },
+        None => println!("  warn  DV_BACKUP_URL not set: the database isn't backed up continuously"),
+    }
+
+    let breakers = db.get_breaker_statuses().await?;
+
+    println!("\nUpstream circuit breakers");
+    println!("{}", "-".repeat(65));
+    if breakers.is_empty() {
+        println!("No state recorded yet; breakers are saved after each collection.");
+    }
+    for breaker in &breakers {
+        let status = if breaker.state == "closed" { "ok  " } else { "warn" };
+        print!("  {}  {:<12} {}", status, breaker.service, breaker.state);
+        if let Some(opened_at) = breaker.opened_at {
+            print!(
+                " since {} after {} failures",
+                opened_at.format("%Y-%m-%d %H:%M"),
+                breaker.consecutive_failures
+            );
+        }
+        println!(" (as of {})", breaker.updated_at.format("%Y-%m-%d %H:%M"));
+    }
+
+    let issues = db.get_open_config_issues().await?;
+
+    println!("\nConfiguration drift");
+    println!("{}", "-".repeat(65));
+    println!("{:<20} {:<14} {:<20} PROBLEM", "DISTRO", "FIELD", "VALUE");
+
+    for issue in &issues {
+        println!(
+            "{:<20} {:<14} {:<20} {} (since {})",
+            issue.distro_slug,
+            issue.field,
+            issue.value,
+            issue.problem,
+            issue.first_seen_at.format("%Y-%m-%d")
+        );
+    }
+
+    if issues.is_empty() {
+        println!("No open issues. Run `dv check-config` to re-validate.");
+    }
+
+    Ok(())
+}
+
+async fn rename_org(db: &Database, old: &str, new: &str) -> Result<()> {
+    if old.eq_ignore_ascii_case(new) {
+        anyhow::bail!("Old and new org names are the same");
+    }
+
+    let renamed = db.rename_github_org(old, new).await?;
+
+    println!("Renamed GitHub org {} -> {}", old, new);
+    println!("  Distributions:     {}", renamed.distributions);
+    println!("  Upstreams:         {}", renamed.upstreams);
+    println!("  GitHub snapshots:  {}", renamed.github_snapshots);
+    println!("  Release snapshots: {}", renamed.release_snapshots);
+
+    Ok(())
+}
+
+async fn archive(db: &Database, action: ArchiveAction) -> Result<()> {
+    match action {
+        ArchiveAction::List => {
+            let archived: Vec<_> = db
+                .get_distributions_including_archived()
+                .await?
+                .into_iter()
+                .filter_map(|d| d.archived_at.map(|at| (d, at)))
+                .collect();
+
+            println!("Archived distributions:");
+            for (distro, at) in &archived {
+                println!("  {} ({}) - archived {}", distro.name, distro.slug, at.format("%Y-%m-%d"));
+            }
+            if archived.is_empty() {
+                println!("  None");
+            }
+        }
+        ArchiveAction::Add { distro } => {
+            let distro = db.get_distribution_by_slug(&distro).await?;
+            db.archive_distribution(distro.id).await?;
+            println!("Archived {}; its history is kept", distro.name);
+        }
+        ArchiveAction::Restore { distro } => {
+            let distro = db.get_distribution_by_slug_including_archived(&distro).await?;
+            if distro.archived_at.is_none() {
+                anyhow::bail!("{} is not archived", distro.slug);
+            }
+            db.restore_distribution(distro.id).await?;
+            println!("Restored {}", distro.name);
+        }
+        ArchiveAction::Purge { distro, yes } => {
+            let distro = db.get_distribution_by_slug_including_archived(&distro).await?;
+            if distro.archived_at.is_none() {
+                anyhow::bail!("Archive {} before purging it: dv archive add {}", distro.slug, distro.slug);
+            }
+            if !yes {
+                anyhow::bail!(
+                    "Purging deletes all history for {} and cannot be undone; re-run with --yes",
+                    distro.slug
+                );
+            }
+
+            let deleted = db.purge_distribution(distro.id).await?;
+            println!("Purged {} ({} rows deleted)", distro.name, deleted);
+            println!("Running `dv seed` re-creates it, empty, if it's in the seed list");
+        }
+    }
+
+    Ok(())
+}
+
+async fn freeze(db: &Database, action: FreezeAction) -> Result<()> {
+    match action {
+        FreezeAction::List { all } => {
+            let freezes = db.get_score_freezes(all).await?;
+            let distros = db.get_distributions_including_archived().await?;
+
+            println!("Score freezes:");
+            for freeze in &freezes {
+                let scope = match freeze.distro_id {
+                    Some(id) => distros
+                        .iter()
+                        .find(|d| d.id == id)
+                        .map_or_else(|| id.to_string(), |d| d.slug.clone()),
+                    None => "all distros".to_string(),
+                };
+                let until = match freeze.ends_at {
+                    Some(at) => format!("until {}", at.format("%Y-%m-%d %H:%M")),
+                    None => "until lifted".to_string(),
+                };
+                println!(
+                    "  #{} {} - since {} {}: {}",
+                    freeze.id,
+                    scope,
+                    freeze.starts_at.format("%Y-%m-%d %H:%M"),
+                    until,
+                    freeze.reason
+                );
+            }
+            if freezes.is_empty() {
+                println!("  None");
+            }
+        }
+        FreezeAction::Start { distro, reason, until } => {
+            anyhow::ensure!(!reason.trim().is_empty(), "--reason must not be empty");
+            anyhow::ensure!(
+                until.is_none_or(|until| until > chrono::Utc::now()),
+                "--until must be in the future"
+            );
+            let distro = match distro {
+                Some(slug) => Some(db.get_distribution_by_slug(&slug).await?),
+                None => None,
+            };
+
+            let freeze = db
+                .insert_score_freeze(NewScoreFreeze {
+                    distro_id: distro.as_ref().map(|d| d.id),
+                    reason: reason.trim().to_string(),
+                    ends_at: until,
+                })
+                .await?;
+            let scope = distro.map_or_else(|| "every distro".to_string(), |d| d.name);
+            println!("Froze scores for {} (#{}); lift it with `dv freeze end {}`", scope, freeze.id, freeze.id);
+        }
+        FreezeAction::End { id } => {
+            if !db.end_score_freeze(id).await? {
+                anyhow::bail!("No score freeze {} in effect", id);
+            }
+            println!("Lifted freeze #{}; the next analysis recalculates scores", id);
+        }
+    }
+
+    Ok(())
+}
+
+async fn mirrors(db: &Database, action: MirrorAction) -> Result<()> {
+    match action {
+        MirrorAction::List { distro } => {
+            let distro = db.get_distribution_by_slug(&distro).await?;
+            let mirrors = db.get_mirror_github_snapshots(distro.id).await?;
+
+            println!("Mirror repos excluded for {}:", distro.name);
+            for snap in &mirrors {
+                println!("  {} - ⭐{} 🍴{}", snap.repo_name, snap.stars, snap.forks);
+            }
+            if mirrors.is_empty() {
+                println!("  None");
+            }
+
+            let overrides = db.get_repo_overrides(distro.id).await?;
+            if !overrides.is_empty() {
+                println!("\nManual overrides:");
+                for o in &overrides {
+                    let kind = if o.is_mirror { "mirror" } else { "source" };
+                    println!("  {} -> {}", o.repo_name, kind);
+                }
+            }
+        }
+        MirrorAction::Mark { distro, repo } => {
+            let distro = db.get_distribution_by_slug(&distro).await?;
+            db.set_repo_override(distro.id, &repo, true).await?;
+            println!("{} will be treated as a mirror for {}", repo, distro.name);
+        }
+        MirrorAction::Source { distro, repo } => {
+            let distro = db.get_distribution_by_slug(&distro).await?;
+            db.set_repo_override(distro.id, &repo, false).await?;
+            println!("{} will be treated as source for {}", repo, distro.name);
+        }
+        MirrorAction::Clear { distro, repo } => {
+            let distro = db.get_distribution_by_slug(&distro).await?;
+            db.clear_repo_override(distro.id, &repo).await?;
+            println!("Cleared override for {}", repo);
+        }
+    }
+
     Ok(())
 }
@@ -0,0 +1,152 @@
+//! Arch Linux repo and AUR counts for Arch and its derivatives
+//!
+//! Repology counts projects; archlinux.org's package search counts the packages Arch
+//! actually builds and which of them users have flagged out of date. The AUR's size
+//! comes from its package list, since the RPC interface only answers searches and
+//! lookups and caps results well below the AUR's size. Official repo counts are only
+//! Arch's own; derivatives get the AUR count, which their users build from too.
+
+use crate::{polite, CollectorConfig, CollectorError, Result};
+use distrovitals_database::{Distribution, NewPackageSnapshot, Provenance};
+use flate2::read::GzDecoder;
+use reqwest::Client;
+use serde::Deserialize;
+use std::io::Read;
+use tracing::info;
+
+/// Slug of Arch Linux itself, whose official repos are counted
+pub const ARCH_SLUG: &str = "arch";
+
+const PACKAGE_SEARCH_URL: &str = "https://archlinux.org/packages/search/json/";
+
+/// Gzipped list of every AUR package name, one per line
+const AUR_PACKAGES_URL: &str = "https://aur.archlinux.org/packages.gz";
+
+/// Official repos, without the testing and staging ones
+const OFFICIAL_REPOS: [&str; 3] = ["Core", "Extra", "Multilib"];
+
+/// Which Arch counts apply to a distro
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchScope {
+    /// Arch itself: official repos and the AUR
+    Arch,
+    /// A derivative: the AUR only
+    Derivative,
+}
+
+impl ArchScope {
+    pub fn of(distro: &Distribution) -> Option<Self> {
+        if distro.slug == ARCH_SLUG {
+            Some(Self::Arch)
+        } else if distro.based_on.as_deref() == Some(ARCH_SLUG) {
+            Some(Self::Derivative)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchPage {
+    num_pages: u32,
+    limit: usize,
+    results: Vec<serde_json::Value>,
+}
+
+/// Count the package names in the AUR's package list
+pub fn count_aur_packages(list: &str) -> i64 {
+    list.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .count() as i64
+}
+
+/// archlinux.org and AUR client
+pub struct ArchCollector {
+    client: Client,
+}
+
+impl ArchCollector {
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(config.request_timeout)
+            .build()?;
+
+        Ok(Self { client })
+    }
+
+    /// Add the counts `scope` covers to a package snapshot; a failed count is left unset
+    pub async fn add_counts(&self, snapshot: &mut NewPackageSnapshot, scope: ArchScope) {
+        let provenance = &mut snapshot.provenance;
+        if scope == ArchScope::Arch {
+            match self.count_official(&[], provenance).await {
+                Ok(count) => snapshot.official_packages = Some(count),
+                Err(e) => provenance.fallback(format!("official package count failed ({}) → unset", e)),
+            }
+            match self.count_official(&[("flagged", "Flagged")], provenance).await {
+                Ok(count) => snapshot.flagged_packages = Some(count),
+                Err(e) => provenance.fallback(format!("out-of-date flag count failed ({}) → unset", e)),
+            }
+        }
+        match self.count_aur(provenance).await {
+            Ok(count) => snapshot.aur_packages = Some(count),
+            Err(e) => provenance.fallback(format!("AUR package count failed ({}) → unset", e)),
+        }
+
+        info!(
+            official = snapshot.official_packages,
+            flagged = snapshot.flagged_packages,
+            aur = snapshot.aur_packages,
+            "Collected Arch package counts"
+        );
+    }
+
+    /// Packages in the official repos matching `filters`, from the first and last result pages
+    async fn count_official(&self, filters: &[(&str, &str)], provenance: &mut Provenance) -> Result<i64> {
+        let first = self.search_page(filters, 1, provenance).await?;
+        if first.num_pages <= 1 {
+            return Ok(first.results.len() as i64);
+        }
+
+        let last = self.search_page(filters, first.num_pages, provenance).await?;
+        Ok(((first.num_pages - 1) as usize * first.limit + last.results.len()) as i64)
+    }
+
+    async fn search_page(
+        &self,
+        filters: &[(&str, &str)],
+        page: u32,
+        provenance: &mut Provenance,
+    ) -> Result<SearchPage> {
+        let mut query: Vec<(&str, String)> = OFFICIAL_REPOS.iter().map(|repo| ("repo", repo.to_string())).collect();
+        query.extend(filters.iter().map(|&(key, value)| (key, value.to_string())));
+        query.push(("page", page.to_string()));
+
+        provenance.request(PACKAGE_SEARCH_URL);
+        let response = self.client.get(PACKAGE_SEARCH_URL).query(&query).send().await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("archlinux.org", response.status(), "package search"));
+        }
+        Ok(response.json().await?)
+    }
+
+    async fn count_aur(&self, provenance: &mut Provenance) -> Result<i64> {
+        provenance.request(AUR_PACKAGES_URL);
+        let response = polite::send(self.client.get(AUR_PACKAGES_URL)).await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("aur", response.status(), "package list"));
+        }
+
+        // Served as a gzipped file, though a proxy may have unpacked it on the way
+        let body = response.bytes().await?;
+        if !body.starts_with(&[0x1f, 0x8b]) {
+            return Ok(count_aur_packages(&String::from_utf8_lossy(&body)));
+        }
+        let mut list = String::new();
+        GzDecoder::new(&body[..])
+            .read_to_string(&mut list)
+            .map_err(|e| CollectorError::Parse(format!("AUR package list isn't gzipped text: {}", e)))?;
+        Ok(count_aur_packages(&list))
+    }
+}
@@ -0,0 +1,69 @@
+//! Bot account detection for commit and contributor counts
+
+/// Automation accounts excluded everywhere; distros can add their own
+pub const DEFAULT_BOTS: &[&str] = &[
+    "dependabot",
+    "dependabot-preview",
+    "renovate",
+    "renovate-bot",
+    "renovatebot",
+    "weblate",
+    "github-actions",
+    "pre-commit-ci",
+    "allcontributors",
+    "imgbot",
+    "snyk-bot",
+    "transifex-integration",
+    "fedora-infra",
+];
+
+/// Label fragments stale bots mark issues with before closing them
+const STALE_LABELS: &[&str] = &["stale", "inactive", "no-activity", "no activity", "lifecycle/rotten", "expired"];
+
+/// Decides whether a commit author is an automation account
+#[derive(Debug, Clone, Default)]
+pub struct BotFilter {
+    extra: Vec<String>,
+}
+
+impl BotFilter {
+    /// Build a filter from a distro's comma-separated `bot_accounts` setting
+    pub fn new(extra: Option<&str>) -> Self {
+        let extra = extra
+            .unwrap_or_default()
+            .split(',')
+            .map(|login| login.trim().to_ascii_lowercase())
+            .filter(|login| !login.is_empty())
+            .collect();
+
+        Self { extra }
+    }
+
+    /// Whether the login belongs to a bot
+    pub fn is_bot(&self, login: &str) -> bool {
+        let login = login.to_ascii_lowercase();
+
+        // GitHub Apps commit as "name[bot]"
+        if login.ends_with("[bot]") {
+            return true;
+        }
+
+        DEFAULT_BOTS.contains(&login.as_str()) || self.extra.contains(&login)
+    }
+
+    /// Whether closing an issue was a stale bot expiring it rather than someone resolving it
+    ///
+    /// Either the closer is a dedicated stale bot, or it's another bot (usually
+    /// github-actions running actions/stale) closing an issue carrying a staleness label.
+    pub fn is_stale_closure<'a>(&self, closer: &str, labels: impl IntoIterator<Item = &'a str>) -> bool {
+        if closer.to_ascii_lowercase().contains("stale") {
+            return true;
+        }
+
+        self.is_bot(closer)
+            && labels.into_iter().any(|label| {
+                let label = label.to_ascii_lowercase();
+                STALE_LABELS.iter().any(|marker| label.contains(marker))
+            })
+    }
+}
@@ -0,0 +1,177 @@
+//! Circuit breakers, one per upstream service
+//!
+//! When Reddit starts blocking us or GitHub is down, every remaining distro in a pass
+//! would otherwise wait out the same timeouts and retries. After a run of failures the
+//! breaker opens and calls to that service fail immediately until a cool-down passes;
+//! the next call after that is let through as a trial, closing the breaker on success.
+//!
+//! Breakers are process-wide so collectors built per job or per pass share them.
+
+use crate::{CollectorError, Result};
+use chrono::{DateTime, Utc};
+use distrovitals_database::{BreakerStatus, Database};
+use reqwest::{RequestBuilder, Response};
+use std::sync::Mutex;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Consecutive failures that open a breaker
+const FAILURE_THRESHOLD: u32 = 5;
+
+/// How long an open breaker rejects calls before letting a trial through
+const COOL_DOWN: Duration = Duration::from_secs(5 * 60);
+
+pub static GITHUB: CircuitBreaker = CircuitBreaker::new("github");
+pub static REDDIT: CircuitBreaker = CircuitBreaker::new("reddit");
+pub static REPOLOGY: CircuitBreaker = CircuitBreaker::new("repology");
+pub static KERNEL_ORG: CircuitBreaker = CircuitBreaker::new("kernel.org");
+pub static CODEBERG: CircuitBreaker = CircuitBreaker::new("codeberg");
+pub static GITLAB: CircuitBreaker = CircuitBreaker::new("gitlab.com");
+pub static PAGURE: CircuitBreaker = CircuitBreaker::new("pagure.io");
+
+/// Every breaker, for reporting
+pub static ALL: [&CircuitBreaker; 7] = [&GITHUB, &REDDIT, &REPOLOGY, &KERNEL_ORG, &CODEBERG, &GITLAB, &PAGURE];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Closed,
+    Open,
+    /// Cool-down over; the next result decides
+    HalfOpen,
+}
+
+impl State {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Closed => "closed",
+            Self::Open => "open",
+            Self::HalfOpen => "half-open",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: State,
+    failures: u32,
+    opened_at: Option<DateTime<Utc>>,
+    /// Whether this process has called the service, so idle breakers don't overwrite
+    /// state saved by another process
+    used: bool,
+}
+
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    service: &'static str,
+    inner: Mutex<Inner>,
+}
+
+impl CircuitBreaker {
+    const fn new(service: &'static str) -> Self {
+        Self {
+            service,
+            inner: Mutex::new(Inner {
+                state: State::Closed,
+                failures: 0,
+                opened_at: None,
+                used: false,
+            }),
+        }
+    }
+
+    /// Send a request unless the breaker is open, counting the outcome
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        self.check()?;
+        let result = request.send().await;
+        match &result {
+            Ok(response) if failed_status(response.status().as_u16()) => self.failure(),
+            Ok(_) => self.success(),
+            Err(e) if CollectorError::retryable_http(e) => self.failure(),
+            Err(_) => {}
+        }
+        Ok(result?)
+    }
+
+    /// Fail fast while open, moving to half-open once the cool-down has passed
+    fn check(&self) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+        inner.used = true;
+        if inner.state != State::Open {
+            return Ok(());
+        }
+
+        let cool_down = chrono::TimeDelta::from_std(COOL_DOWN).unwrap_or_default();
+        let retry_at = inner.opened_at.unwrap_or_else(Utc::now) + cool_down;
+        let now = Utc::now();
+        if now < retry_at {
+            return Err(CollectorError::CircuitOpen {
+                service: self.service,
+                retry_after: (retry_at - now).num_seconds().max(1) as u64,
+            });
+        }
+
+        info!(service = self.service, "Circuit breaker half-open, trying a request");
+        inner.state = State::HalfOpen;
+        Ok(())
+    }
+
+    fn success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.state != State::Closed {
+            info!(service = self.service, "Circuit breaker closed");
+        }
+        inner.state = State::Closed;
+        inner.failures = 0;
+        inner.opened_at = None;
+    }
+
+    fn failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.failures += 1;
+        let trip = match inner.state {
+            State::HalfOpen => true,
+            State::Closed => inner.failures >= FAILURE_THRESHOLD,
+            State::Open => false,
+        };
+        if trip {
+            warn!(
+                service = self.service,
+                failures = inner.failures,
+                cool_down_secs = COOL_DOWN.as_secs(),
+                "Circuit breaker open"
+            );
+            inner.state = State::Open;
+            inner.opened_at = Some(Utc::now());
+        }
+    }
+
+    /// Current state, as stored in the database
+    pub fn status(&self) -> BreakerStatus {
+        let inner = self.inner.lock().unwrap();
+        BreakerStatus {
+            service: self.service.to_string(),
+            state: inner.state.as_str().to_string(),
+            consecutive_failures: inner.failures as i64,
+            opened_at: inner.opened_at,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Responses that suggest the service is down or turning us away
+fn failed_status(status: u16) -> bool {
+    status == 429 || status >= 500
+}
+
+/// Save the state of every breaker this process has used, so `dv doctor` and the admin
+/// API can see it from other processes
+pub async fn persist(db: &Database) {
+    for breaker in ALL {
+        if !breaker.inner.lock().unwrap().used {
+            continue;
+        }
+        if let Err(e) = db.upsert_breaker_status(&breaker.status()).await {
+            warn!(service = breaker.service, error = %e, "Failed to record circuit breaker state");
+        }
+    }
+}
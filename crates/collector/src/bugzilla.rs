@@ -0,0 +1,191 @@
+//! Bugzilla collector for distros that track bugs there rather than in forge issues
+//!
+//! Counts come from the REST API's `count_only` searches, so they cost one request each
+//! however many bugs match. Bugzilla has no resolution timestamp, so a resolved bug's
+//! last change stands in for when it was resolved; comments added afterwards push it
+//! later, which makes the mean time to resolution an upper bound.
+
+use crate::{provenance, CollectorConfig, CollectorError, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use distrovitals_database::{Database, NewBugtrackerSnapshot, Provenance};
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::info;
+
+/// Resolved bugs fetched to average time to resolution over; busier months are sampled
+const MAX_RESOLVED: usize = 500;
+
+#[derive(Debug, Deserialize)]
+struct CountResponse {
+    bug_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BugsResponse {
+    bugs: Vec<ResolvedBug>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ResolvedBug {
+    creation_time: DateTime<Utc>,
+    last_change_time: DateTime<Utc>,
+}
+
+/// A distro's Bugzilla instance and product
+#[derive(Debug, Clone, Copy)]
+pub struct Bugzilla<'a> {
+    pub base_url: &'a str,
+    pub product: Option<&'a str>,
+}
+
+impl Bugzilla<'_> {
+    /// Search URL for the product's bugs matching `params`
+    fn search(&self, params: &[(&str, String)]) -> Result<String> {
+        let mut url = reqwest::Url::parse(&format!("{}/rest/bug", self.base_url.trim_end_matches('/')))
+            .map_err(|e| CollectorError::Api(format!("Invalid Bugzilla URL {:?}: {}", self.base_url, e)))?;
+        {
+            let mut query = url.query_pairs_mut();
+            if let Some(product) = self.product {
+                query.append_pair("product", product);
+            }
+            for (key, value) in params {
+                query.append_pair(key, value);
+            }
+        }
+        Ok(url.into())
+    }
+}
+
+/// Mean days from filing to resolution
+pub fn mean_resolution_days(bugs: &[(DateTime<Utc>, DateTime<Utc>)]) -> Option<f64> {
+    if bugs.is_empty() {
+        return None;
+    }
+    let total: f64 = bugs.iter().map(|(created, resolved)| (*resolved - *created).num_seconds().max(0) as f64).sum();
+    Some((total / bugs.len() as f64 / 86_400.0 * 10.0).round() / 10.0)
+}
+
+/// Bugzilla REST client
+pub struct BugzillaCollector {
+    client: Client,
+}
+
+impl BugzillaCollector {
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(config.request_timeout)
+            .build()?;
+
+        Ok(Self { client })
+    }
+
+    /// Count open, filed and resolved bugs and store them as a bug tracker snapshot
+    pub async fn collect_bugs(&self, db: &Database, distro_id: i64, bugzilla: Bugzilla<'_>) -> Result<i64> {
+        info!(url = bugzilla.base_url, product = bugzilla.product, "Collecting Bugzilla bugs");
+
+        let mut provenance = provenance();
+        let open_bugs = self.count(&bugzilla, &[("resolution", "---".to_string())], &mut provenance).await?;
+
+        let since = (Utc::now() - TimeDelta::days(30)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let opened_30d = match self.count(&bugzilla, &[("creation_time", since.clone())], &mut provenance).await {
+            Ok(count) => Some(count),
+            Err(e) => {
+                provenance.fallback(format!("filed bug count failed ({}) → unset", e));
+                None
+            }
+        };
+
+        // Resolution set to something other than open within the window
+        let resolved = [
+            ("f1", "resolution".to_string()),
+            ("o1", "changedafter".to_string()),
+            ("v1", since),
+            ("f2", "resolution".to_string()),
+            ("o2", "notequals".to_string()),
+            ("v2", "---".to_string()),
+        ];
+        let closed_30d = match self.count(&bugzilla, &resolved, &mut provenance).await {
+            Ok(count) => Some(count),
+            Err(e) => {
+                provenance.fallback(format!("resolved bug count failed ({}) → unset", e));
+                None
+            }
+        };
+        let mean_resolution_days = match self.resolution_times(&bugzilla, &resolved, &mut provenance).await {
+            Ok(bugs) => mean_resolution_days(&bugs),
+            Err(e) => {
+                provenance.fallback(format!("resolved bug listing failed ({}) → unset", e));
+                None
+            }
+        };
+
+        let id = db
+            .insert_bugtracker_snapshot(NewBugtrackerSnapshot {
+                distro_id,
+                open_bugs,
+                opened_30d,
+                closed_30d,
+                mean_resolution_days,
+                provenance,
+            })
+            .await?;
+
+        info!(
+            open = open_bugs,
+            opened_30d = opened_30d,
+            closed_30d = closed_30d,
+            mean_resolution_days = mean_resolution_days,
+            "Collected Bugzilla bugs"
+        );
+        Ok(id)
+    }
+
+    async fn count(
+        &self,
+        bugzilla: &Bugzilla<'_>,
+        params: &[(&str, String)],
+        provenance: &mut Provenance,
+    ) -> Result<i64> {
+        let mut params = params.to_vec();
+        params.push(("count_only", "1".to_string()));
+        let url = bugzilla.search(&params)?;
+
+        provenance.request(&url);
+        let response = self.client.get(&url).send().await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("bugzilla", response.status(), bugzilla.base_url));
+        }
+        // Instances older than Bugzilla 5.0 ignore count_only and list the bugs instead
+        let count: CountResponse = response
+            .json()
+            .await
+            .map_err(|e| CollectorError::Parse(format!("No bug count from {} ({})", bugzilla.base_url, e)))?;
+        Ok(count.bug_count)
+    }
+
+    /// Filing and last-change times of up to `MAX_RESOLVED` bugs matching `params`
+    async fn resolution_times(
+        &self,
+        bugzilla: &Bugzilla<'_>,
+        params: &[(&str, String)],
+        provenance: &mut Provenance,
+    ) -> Result<Vec<(DateTime<Utc>, DateTime<Utc>)>> {
+        let mut params = params.to_vec();
+        params.push(("include_fields", "creation_time,last_change_time".to_string()));
+        params.push(("limit", MAX_RESOLVED.to_string()));
+        let url = bugzilla.search(&params)?;
+
+        provenance.request(&url);
+        let response = self.client.get(&url).send().await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("bugzilla", response.status(), bugzilla.base_url));
+        }
+
+        let bugs: BugsResponse = response.json().await?;
+        if bugs.bugs.len() >= MAX_RESOLVED {
+            provenance.fallback(format!("time to resolution averaged over {} resolved bugs", MAX_RESOLVED));
+        }
+        Ok(bugs.bugs.into_iter().map(|bug| (bug.creation_time, bug.last_change_time)).collect())
+    }
+}
@@ -0,0 +1,236 @@
+//! Operator-defined metrics fetched over HTTP
+//!
+//! Instances can track signals specific to one distro, such as a figure from its own
+//! stats page, without a dedicated collector. Each definition names a URL and how to
+//! pull one number out of the response, and the value is stored in the generic
+//! `metrics` table under the definition's key. They're read from the JSON file named by
+//! `DV_CUSTOM_METRICS`:
+//!
+//! ```json
+//! [
+//!   {"distro": "fedora", "key": "fedora_packagers", "url": "https://example.org/stats.json",
+//!    "json_path": "$.people.packagers"},
+//!   {"distro": "void", "key": "void_open_prs", "url": "https://example.org/status",
+//!    "regex": "([0-9,]+) open pull requests"}
+//! ]
+//! ```
+//!
+//! A JSON path selects a single value with `.key`, `['key']` and `[index]` steps
+//! (negative indexes count from the end); numbers, numeric strings and booleans are
+//! taken as they are, and arrays and objects count their elements. A regex takes its
+//! first group, or the whole match without one, with thousands separators dropped.
+
+use crate::{polite, CollectorConfig, CollectorError, Result};
+use distrovitals_database::{Database, MetricKey, NewMetricValue};
+use regex_automata::meta::Regex;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// `source` of the stored metric values
+const SOURCE: &str = "custom";
+
+/// A step in a [`JsonPath`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Step {
+    Key(String),
+    Index(i64),
+}
+
+/// The subset of JSONPath that selects one value: `$.a.b`, `$['a b']`, `$.list[0]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonPath(Vec<Step>);
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Option<Self> {
+        let mut rest = path.trim().strip_prefix('$')?;
+        let mut steps = Vec::new();
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix('.') {
+                let end = after.find(['.', '[']).unwrap_or(after.len());
+                if end == 0 {
+                    return None;
+                }
+                steps.push(Step::Key(after[..end].to_string()));
+                rest = &after[end..];
+            } else if let Some(after) = rest.strip_prefix('[') {
+                let end = after.find(']')?;
+                let inner = after[..end].trim();
+                let quoted = inner
+                    .strip_prefix('\'')
+                    .and_then(|s| s.strip_suffix('\''))
+                    .or_else(|| inner.strip_prefix('"').and_then(|s| s.strip_suffix('"')));
+                steps.push(match quoted {
+                    Some(key) => Step::Key(key.to_string()),
+                    None => Step::Index(inner.parse().ok()?),
+                });
+                rest = &after[end + 1..];
+            } else {
+                return None;
+            }
+        }
+        Some(Self(steps))
+    }
+
+    pub fn select<'a>(&self, value: &'a Value) -> Option<&'a Value> {
+        self.0.iter().try_fold(value, |value, step| match step {
+            Step::Key(key) => value.get(key),
+            Step::Index(index) => {
+                let items = value.as_array()?;
+                let index = if *index < 0 { items.len() as i64 + index } else { *index };
+                items.get(usize::try_from(index).ok()?)
+            }
+        })
+    }
+}
+
+/// How a definition pulls its number out of the response
+#[derive(Debug, Clone)]
+pub enum Extraction {
+    JsonPath(JsonPath),
+    Regex(Regex),
+}
+
+/// One configured metric
+#[derive(Debug, Clone)]
+pub struct CustomMetric {
+    /// Slug of the distro the metric belongs to
+    pub distro: String,
+    pub key: String,
+    pub url: String,
+    pub extraction: Extraction,
+}
+
+/// A definition as written in the file
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Definition {
+    distro: String,
+    key: String,
+    url: String,
+    json_path: Option<String>,
+    regex: Option<String>,
+}
+
+impl CustomMetric {
+    fn from_definition(definition: Definition) -> std::result::Result<Self, String> {
+        let Definition { distro, key, url, json_path, regex } = definition;
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_') {
+            return Err(format!("key {:?} must be lowercase letters, digits and underscores", key));
+        }
+        // Known keys mean something specific to the analyzer
+        if !matches!(MetricKey::from(key.clone()), MetricKey::Custom(_)) {
+            return Err(format!("key {:?} is reserved for a built-in metric", key));
+        }
+        if !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(format!("{}: url must be http(s)", key));
+        }
+
+        let extraction = match (json_path, regex) {
+            (Some(path), None) => Extraction::JsonPath(
+                JsonPath::parse(&path).ok_or_else(|| format!("{}: invalid json_path {:?}", key, path))?,
+            ),
+            (None, Some(pattern)) => Extraction::Regex(
+                Regex::new(&pattern).map_err(|e| format!("{}: invalid regex {:?}: {}", key, pattern, e))?,
+            ),
+            _ => return Err(format!("{}: give exactly one of json_path and regex", key)),
+        };
+
+        Ok(Self { distro, key, url, extraction })
+    }
+
+    /// Pull the metric's value out of a response body
+    pub fn extract(&self, body: &str) -> std::result::Result<f64, String> {
+        match &self.extraction {
+            Extraction::JsonPath(path) => {
+                let document: Value = serde_json::from_str(body).map_err(|e| format!("response isn't JSON: {}", e))?;
+                let value = path.select(&document).ok_or("json_path matched nothing")?;
+                match value {
+                    Value::Number(n) => n.as_f64().ok_or_else(|| format!("{} isn't a finite number", n)),
+                    Value::String(s) => number(s.trim()).ok_or_else(|| format!("{:?} isn't a number", s)),
+                    Value::Bool(b) => Ok(if *b { 1.0 } else { 0.0 }),
+                    Value::Array(items) => Ok(items.len() as f64),
+                    Value::Object(fields) => Ok(fields.len() as f64),
+                    Value::Null => Err("json_path matched null".to_string()),
+                }
+            }
+            Extraction::Regex(regex) => {
+                let mut captures = regex.create_captures();
+                regex.captures(body, &mut captures);
+                let span = captures.get_group(1).or(captures.get_group(0)).ok_or("regex matched nothing")?;
+                let text: String = body[span.range()].chars().filter(|c| !matches!(c, ',' | '_' | ' ')).collect();
+                number(&text).ok_or_else(|| format!("{:?} isn't a number", &body[span.range()]))
+            }
+        }
+    }
+}
+
+/// A finite number, so "NaN" and "inf" in a page aren't stored as values
+fn number(text: &str) -> Option<f64> {
+    text.parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// Read metric definitions from a JSON file, skipping invalid ones with a warning
+pub fn load_custom_metrics(path: &Path) -> std::result::Result<Vec<CustomMetric>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let definitions: Vec<Definition> = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+
+    let mut metrics: Vec<CustomMetric> = Vec::with_capacity(definitions.len());
+    for definition in definitions {
+        match CustomMetric::from_definition(definition) {
+            Ok(metric) if metrics.iter().any(|m| m.distro == metric.distro && m.key == metric.key) => {
+                warn!("Ignoring custom metric {} for {}: defined twice", metric.key, metric.distro);
+            }
+            Ok(metric) => metrics.push(metric),
+            Err(e) => warn!("Ignoring custom metric in {}: {}", path.display(), e),
+        }
+    }
+    Ok(metrics)
+}
+
+/// Runs the configured custom metrics
+pub struct HttpJsonCollector {
+    client: Client,
+    metrics: Vec<CustomMetric>,
+}
+
+impl HttpJsonCollector {
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(config.request_timeout)
+            .build()?;
+
+        Ok(Self { client, metrics: config.custom_metrics })
+    }
+
+    /// Metrics defined for the distro with `slug`
+    pub fn metrics_for<'a>(&'a self, slug: &'a str) -> impl Iterator<Item = &'a CustomMetric> {
+        self.metrics.iter().filter(move |m| m.distro == slug)
+    }
+
+    /// Fetch one metric and store its value
+    pub async fn collect(&self, db: &Database, distro_id: i64, metric: &CustomMetric) -> Result<i64> {
+        let response = polite::send(self.client.get(&metric.url)).await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status(SOURCE, response.status(), &metric.url));
+        }
+
+        let body = response.text().await?;
+        let value = metric
+            .extract(&body)
+            .map_err(|e| CollectorError::Parse(format!("{} from {}: {}", metric.key, metric.url, e)))?;
+
+        info!(key = metric.key, value = value, "Collected custom metric");
+        Ok(db
+            .insert_metric(NewMetricValue {
+                distro_id,
+                source: SOURCE.to_string(),
+                key: MetricKey::Custom(metric.key.clone()),
+                value,
+            })
+            .await?)
+    }
+}
@@ -0,0 +1,129 @@
+//! Debian archive collector, from ftp-master and the Ultimate Debian Database
+//!
+//! Debian is developed in its archive rather than on a forge, so its development and
+//! maintenance signals come from there: the source packages in unstable, the
+//! release-critical bugs open against them and how many get uploaded. Neither service
+//! lists uploads by date, so they're counted as packages whose version in unstable
+//! changed between collections; a package uploaded twice in between counts once.
+
+use crate::{polite, provenance, CollectorConfig, CollectorError, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use distrovitals_database::{Database, NewArchiveSnapshot, Provenance, UploadHistory};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::info;
+
+/// Slug of Debian, the one distro whose archive is collected
+pub const DEBIAN_SLUG: &str = "debian";
+
+/// Every source package in unstable with its version
+const SOURCES_URL: &str = "https://api.ftp-master.debian.org/sources_in_suite/unstable";
+
+/// Release-critical bugs affecting unstable, merged bugs counted once
+const RC_BUGS_URL: &str = "https://udd.debian.org/bugs/?release=sid&merged=ign&rc=1&format=json";
+
+#[derive(Debug, Deserialize)]
+struct SourceEntry {
+    source: String,
+    version: String,
+}
+
+/// Uploads over the last 30 days from the uploads counted so far, extrapolated while
+/// there's less than 30 days of history and None until there's a day of it
+pub fn uploads_30d(history: UploadHistory, uploads: Option<i64>, now: DateTime<Utc>) -> Option<i64> {
+    let uploads = uploads?;
+    let tracked = now - history.tracked_since?;
+    let total = history.uploads + uploads;
+    if tracked >= TimeDelta::days(30) {
+        Some(total)
+    } else if tracked >= TimeDelta::days(1) {
+        Some((total as f64 * 30.0 * 86_400.0 / tracked.num_seconds() as f64).round() as i64)
+    } else {
+        None
+    }
+}
+
+/// ftp-master and UDD client
+pub struct DebianCollector {
+    client: Client,
+}
+
+impl DebianCollector {
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(config.request_timeout)
+            .build()?;
+
+        Ok(Self { client })
+    }
+
+    /// Count the archive's packages, RC bugs and uploads and store them as an archive snapshot
+    pub async fn collect_archive(&self, db: &Database, distro_id: i64) -> Result<i64> {
+        info!("Collecting Debian archive");
+
+        let mut provenance = provenance();
+        let sources = self.get_sources(&mut provenance).await?;
+        // An empty suite is far likelier a changed API than an empty archive
+        if sources.is_empty() {
+            return Err(CollectorError::Parse(format!("No source packages in {}", SOURCES_URL)));
+        }
+        let rc_bugs = match self.count_rc_bugs(&mut provenance).await {
+            Ok(count) => Some(count),
+            Err(e) => {
+                provenance.fallback(format!("RC bug count failed ({}) → unset", e));
+                None
+            }
+        };
+
+        let now = Utc::now();
+        let history = db.get_upload_history(distro_id, now - TimeDelta::days(30)).await?;
+        let uploads = db.replace_archive_sources(distro_id, &sources).await?;
+        let uploads_30d = uploads_30d(history, uploads, now);
+
+        let id = db
+            .insert_archive_snapshot(NewArchiveSnapshot {
+                distro_id,
+                source_packages: sources.len() as i64,
+                rc_bugs,
+                uploads,
+                uploads_30d,
+                provenance,
+            })
+            .await?;
+
+        info!(
+            source_packages = sources.len(),
+            rc_bugs = rc_bugs,
+            uploads = uploads,
+            uploads_30d = uploads_30d,
+            "Collected Debian archive"
+        );
+        Ok(id)
+    }
+
+    /// Source packages in unstable and their versions, one entry per package
+    async fn get_sources(&self, provenance: &mut Provenance) -> Result<Vec<(String, String)>> {
+        provenance.request(SOURCES_URL);
+        let response = self.client.get(SOURCES_URL).send().await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("ftp-master", response.status(), "sources in unstable"));
+        }
+
+        let entries: Vec<SourceEntry> = response.json().await?;
+        let versions: HashMap<String, String> = entries.into_iter().map(|e| (e.source, e.version)).collect();
+        Ok(versions.into_iter().collect())
+    }
+
+    async fn count_rc_bugs(&self, provenance: &mut Provenance) -> Result<i64> {
+        provenance.request(RC_BUGS_URL);
+        let response = polite::send(self.client.get(RC_BUGS_URL)).await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("udd", response.status(), "RC bugs"));
+        }
+
+        let bugs: Vec<serde_json::Value> = response.json().await?;
+        Ok(bugs.len() as i64)
+    }
+}
@@ -0,0 +1,213 @@
+//! Download sizes: the installer image and a month of updates
+//!
+//! The image size comes from the Content-Length of its URL. The update payload is read from
+//! the main repository's package index: the compressed size of every package built in the
+//! last 30 days, which is what a system with all of them installed downloads in a month.
+
+use crate::{polite, CollectorConfig, CollectorError, Result};
+use chrono::{TimeDelta, Utc};
+use distrovitals_database::{Database, MetricKey, NewMetricValue};
+use flate2::read::MultiGzDecoder;
+use reqwest::header::CONTENT_LENGTH;
+use reqwest::Client;
+use std::io::Read;
+use tracing::info;
+
+/// Days of builds counted toward the update payload
+const PAYLOAD_DAYS: i64 = 30;
+
+const BYTES_PER_MB: f64 = 1024.0 * 1024.0;
+
+/// How a repository's package index is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexFormat {
+    /// pacman repo database (`core.db`): a gzipped tar with a `desc` file per package
+    Pacman,
+    /// Alpine `APKINDEX.tar.gz`: blank-line separated records in an `APKINDEX` file
+    Apk,
+}
+
+impl IndexFormat {
+    pub const ALL: [IndexFormat; 2] = [Self::Pacman, Self::Apk];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.as_str() == name)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pacman => "pacman",
+            Self::Apk => "apk",
+        }
+    }
+
+    /// Comma-separated names of every format, for error messages
+    pub fn names() -> String {
+        Self::ALL.iter().map(|f| f.as_str()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// Packages built since a time and their combined compressed size
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UpdatePayload {
+    pub packages: u64,
+    pub bytes: u64,
+}
+
+/// Sum the packages in a gzipped index built at or after `since` (Unix seconds)
+pub fn update_payload(format: IndexFormat, compressed: &[u8], since: i64) -> Result<UpdatePayload> {
+    let mut archive = Vec::new();
+    MultiGzDecoder::new(compressed)
+        .read_to_end(&mut archive)
+        .map_err(|e| CollectorError::Parse(format!("{} index isn't gzipped: {}", format.as_str(), e)))?;
+
+    let mut payload = UpdatePayload::default();
+    let mut add = |size: Option<u64>, built: Option<i64>| {
+        if let (Some(size), Some(built)) = (size, built) {
+            if built >= since {
+                payload.packages += 1;
+                payload.bytes += size;
+            }
+        }
+    };
+
+    for (name, contents) in tar_files(&archive) {
+        let text = String::from_utf8_lossy(contents);
+        match format {
+            IndexFormat::Pacman if name.ends_with("/desc") => {
+                add(pacman_field(&text, "%CSIZE%"), pacman_field(&text, "%BUILDDATE%"));
+            }
+            IndexFormat::Apk if name == "APKINDEX" => {
+                for record in text.split("\n\n") {
+                    add(apk_field(record, 'S'), apk_field(record, 't'));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(payload)
+}
+
+/// Value on the line after a `%FIELD%` header in a pacman `desc` file
+fn pacman_field<T: std::str::FromStr>(desc: &str, header: &str) -> Option<T> {
+    let mut lines = desc.lines();
+    lines.find(|line| line.trim() == header)?;
+    lines.next()?.trim().parse().ok()
+}
+
+/// Value of a `X:value` line in an APKINDEX record
+fn apk_field<T: std::str::FromStr>(record: &str, key: char) -> Option<T> {
+    record
+        .lines()
+        .find_map(|line| line.strip_prefix(key)?.strip_prefix(':'))
+        .and_then(|value| value.trim().parse().ok())
+}
+
+/// Regular files in a tar archive, by name; stops at the first malformed header
+fn tar_files(archive: &[u8]) -> Vec<(String, &[u8])> {
+    const BLOCK: usize = 512;
+
+    let mut files = Vec::new();
+    let mut offset = 0;
+    while offset + BLOCK <= archive.len() {
+        let header = &archive[offset..offset + BLOCK];
+        if header.iter().all(|&b| b == 0) {
+            // End-of-archive marker, or padding between concatenated archives
+            offset += BLOCK;
+            continue;
+        }
+
+        let field = |range: std::ops::Range<usize>| {
+            let bytes = &header[range];
+            let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+            String::from_utf8_lossy(&bytes[..end]).trim().to_string()
+        };
+        let Ok(size) = usize::from_str_radix(&field(124..136), 8) else {
+            break;
+        };
+        let start = offset + BLOCK;
+        let Some(contents) = archive.get(start..start + size) else {
+            break;
+        };
+        // Regular files only: '0', or NUL from old tar implementations
+        if matches!(header[156], b'0' | 0) {
+            files.push((field(0..100), contents));
+        }
+        offset = start + size.div_ceil(BLOCK) * BLOCK;
+    }
+    files
+}
+
+fn megabytes(bytes: u64) -> f64 {
+    (bytes as f64 / BYTES_PER_MB * 10.0).round() / 10.0
+}
+
+/// Fetches installer images' sizes and package indexes
+pub struct DownloadCollector {
+    client: Client,
+}
+
+impl DownloadCollector {
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(config.request_timeout)
+            .build()?;
+
+        Ok(Self { client })
+    }
+
+    /// Record the installer image's size from a HEAD request, without downloading it
+    pub async fn collect_iso_size(&self, db: &Database, distro_id: i64, url: &str) -> Result<i64> {
+        let response = polite::send(self.client.head(url)).await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("downloads", response.status(), url));
+        }
+
+        let bytes: u64 = response
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| CollectorError::Parse(format!("No Content-Length for {}", url)))?;
+
+        info!(url = url, size_mb = megabytes(bytes), "Collected installer image size");
+        Ok(db
+            .insert_metric(NewMetricValue {
+                distro_id,
+                source: "downloads".to_string(),
+                key: MetricKey::IsoSizeMb,
+                value: megabytes(bytes),
+            })
+            .await?)
+    }
+
+    /// Record the size of the last 30 days of package builds in a repository index
+    pub async fn collect_update_payload(
+        &self,
+        db: &Database,
+        distro_id: i64,
+        url: &str,
+        format: IndexFormat,
+    ) -> Result<i64> {
+        let response = polite::send(self.client.get(url)).await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("downloads", response.status(), url));
+        }
+
+        let index = response.bytes().await?;
+        let since = (Utc::now() - TimeDelta::days(PAYLOAD_DAYS)).timestamp();
+        let payload = update_payload(format, &index, since)?;
+
+        info!(url = url, packages = payload.packages, size_mb = megabytes(payload.bytes), "Collected update payload");
+        Ok(db
+            .insert_metric(NewMetricValue {
+                distro_id,
+                source: "downloads".to_string(),
+                key: MetricKey::UpdatePayloadMb30d,
+                value: megabytes(payload.bytes),
+            })
+            .await?)
+    }
+}
@@ -0,0 +1,262 @@
+//! Configuration drift checker
+//!
+//! Validates that each distro's configured homepage, GitHub org, GitLab group, Forgejo org and
+//! subreddit still exist, so renamed orgs and private subreddits get noticed.
+
+use crate::forge::{Forge, ForgeKind};
+use crate::{polite, CollectorConfig, CollectorError, Result};
+use distrovitals_database::{Database, Distribution};
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use reqwest::{Client, StatusCode};
+use serde::Deserialize;
+use tracing::{debug, info, warn};
+
+/// Checks distro configuration against the live upstream services
+pub struct DriftChecker {
+    client: Client,
+    /// Sent to the GitHub API only; other forges reject tokens they didn't issue
+    github_auth: Option<HeaderValue>,
+}
+
+/// A problem found with one configured field
+#[derive(Debug, Clone)]
+pub struct DriftProblem {
+    pub field: &'static str,
+    pub value: String,
+    pub problem: String,
+}
+
+/// Outcome of checking a single field
+enum Verdict {
+    Ok,
+    Drift(String),
+    /// Network error or rate limit, says nothing about the field
+    Inconclusive,
+}
+
+#[derive(Debug, Deserialize)]
+struct RedditError {
+    reason: Option<String>,
+}
+
+impl DriftChecker {
+    /// Create a new drift checker
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        let github_auth = config
+            .github_token()
+            .map(|token| HeaderValue::from_str(&format!("Bearer {}", token)).unwrap());
+
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(std::time::Duration::from_secs(20))
+            .build()?;
+
+        Ok(Self { client, github_auth })
+    }
+
+    /// Check a distribution and record open/resolved issues in the database
+    pub async fn check_and_record(
+        &self,
+        db: &Database,
+        distro: &Distribution,
+    ) -> Result<Vec<DriftProblem>> {
+        let checks = self.check_distribution(distro).await;
+        let mut problems = Vec::new();
+
+        for check in checks {
+            match check.problem {
+                Some(problem) => {
+                    db.open_config_issue(distro.id, check.field, &check.value, &problem)
+                        .await?;
+                    problems.push(DriftProblem {
+                        field: check.field,
+                        value: check.value,
+                        problem,
+                    });
+                }
+                None => db.resolve_config_issue(distro.id, check.field).await?,
+            }
+        }
+
+        Ok(problems)
+    }
+
+    /// Check all distributions, returning the number of problems found
+    pub async fn check_all(&self, db: &Database) -> Result<usize> {
+        let distros = db.get_distributions().await?;
+        let mut total = 0;
+
+        for distro in &distros {
+            let problems = self.check_and_record(db, distro).await?;
+            for p in &problems {
+                warn!(
+                    distro = distro.slug,
+                    field = p.field,
+                    value = p.value,
+                    problem = p.problem,
+                    "Config drift"
+                );
+            }
+            total += problems.len();
+
+            // Reddit rate limiting - be gentle
+            if distro.subreddit.is_some() {
+                tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+            }
+        }
+
+        info!(
+            distros = distros.len(),
+            problems = total,
+            "Config drift check complete"
+        );
+        Ok(total)
+    }
+
+    /// Check every configured field of a distribution
+    ///
+    /// Fields whose check was inconclusive (network errors, rate limits) are omitted.
+    pub async fn check_distribution(&self, distro: &Distribution) -> Vec<FieldCheck> {
+        let mut checks = Vec::new();
+
+        if let Some(ref homepage) = distro.homepage {
+            // A homepage whose host doesn't resolve or refuses connections is drift in its own
+            // right; timeouts, resets and a robots.txt that turns us away or is failing say nothing
+            let verdict = match polite::send(self.client.get(homepage)).await {
+                Ok(response) => Self::verdict(response.status()),
+                Err(CollectorError::Http(e)) if e.is_connect() && Self::same_host(e.url(), homepage) => {
+                    Verdict::Drift(format!("unreachable: {}", e))
+                }
+                Err(e) => {
+                    warn!(url = homepage, error = %e, "Config check inconclusive");
+                    Verdict::Inconclusive
+                }
+            };
+            checks.extend(FieldCheck::from_verdict("homepage", homepage, verdict));
+        }
+
+        if let Some(ref org) = distro.github_org {
+            // Some distros publish from a user account rather than an org
+            let url = format!("https://api.github.com/users/{}", org);
+            let verdict = self.check_api(&url, self.github_auth.as_ref()).await;
+            checks.extend(FieldCheck::from_verdict("github_org", org, verdict));
+        }
+
+        if let Some(ref group) = distro.gitlab_group {
+            let forge = Forge::for_distro(distro, ForgeKind::Gitlab);
+            let url = format!(
+                "{}/api/v4/groups/{}",
+                forge.base_url,
+                group.replace('/', "%2F")
+            );
+            let verdict = self.check_api(&url, None).await;
+            checks.extend(FieldCheck::from_verdict("gitlab_group", group, verdict));
+        }
+
+        if let Some(ref org) = distro.forgejo_org {
+            let forge = Forge::for_distro(distro, ForgeKind::Forgejo);
+            let url = format!("{}/api/v1/orgs/{}", forge.base_url, org);
+            let verdict = self.check_api(&url, None).await;
+            checks.extend(FieldCheck::from_verdict("forgejo_org", org, verdict));
+        }
+
+        if let Some(ref subreddit) = distro.subreddit {
+            let verdict = self.check_subreddit(subreddit).await;
+            checks.extend(FieldCheck::from_verdict("subreddit", subreddit, verdict));
+        }
+
+        debug!(distro = distro.slug, checks = checks.len(), "Checked distro config");
+        checks
+    }
+
+    /// Whether a failed request went to the homepage's host, rather than one it redirected to
+    ///
+    /// robots.txt is fetched from the same host first, so failing to connect for it means the
+    /// homepage itself can't be reached.
+    fn same_host(failed: Option<&reqwest::Url>, homepage: &str) -> bool {
+        match (failed.and_then(|u| u.host_str()), reqwest::Url::parse(homepage)) {
+            (Some(host), Ok(homepage)) => homepage.host_str() == Some(host),
+            _ => false,
+        }
+    }
+
+    async fn check_api(&self, url: &str, auth: Option<&HeaderValue>) -> Verdict {
+        let mut request = self.client.get(url);
+        if let Some(auth) = auth {
+            request = request.header(AUTHORIZATION, auth.clone());
+        }
+        match request.send().await {
+            Ok(response) => Self::verdict(response.status()),
+            Err(e) => {
+                warn!(url = url, error = %e, "Config check inconclusive");
+                Verdict::Inconclusive
+            }
+        }
+    }
+
+    async fn check_subreddit(&self, subreddit: &str) -> Verdict {
+        let url = format!("https://www.reddit.com/r/{}/about.json", subreddit);
+
+        let response = match self.client.get(&url).send().await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!(url = url, error = %e, "Config check inconclusive");
+                return Verdict::Inconclusive;
+            }
+        };
+
+        match response.status() {
+            // Reddit answers private/quarantined subs with 403 and banned ones with 404,
+            // giving the reason in the body
+            StatusCode::FORBIDDEN | StatusCode::NOT_FOUND => {
+                let reason = response
+                    .json::<RedditError>()
+                    .await
+                    .ok()
+                    .and_then(|e| e.reason)
+                    .unwrap_or_else(|| "not found".to_string());
+                Verdict::Drift(reason)
+            }
+            status => Self::verdict(status),
+        }
+    }
+
+    fn verdict(status: StatusCode) -> Verdict {
+        match status {
+            s if s.is_success() => Verdict::Ok,
+            StatusCode::NOT_FOUND | StatusCode::GONE => Verdict::Drift("not found".to_string()),
+            // Rate limits and server errors say nothing about the resource itself
+            StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS => Verdict::Inconclusive,
+            s if s.is_server_error() => Verdict::Inconclusive,
+            s => Verdict::Drift(format!("HTTP {}", s)),
+        }
+    }
+}
+
+/// Result of a conclusive check of one configured field
+#[derive(Debug, Clone)]
+pub struct FieldCheck {
+    pub field: &'static str,
+    pub value: String,
+    pub problem: Option<String>,
+}
+
+impl FieldCheck {
+    fn from_verdict(
+        field: &'static str,
+        value: &str,
+        verdict: Verdict,
+    ) -> Option<Self> {
+        let problem = match verdict {
+            Verdict::Ok => None,
+            Verdict::Drift(problem) => Some(problem),
+            Verdict::Inconclusive => return None,
+        };
+
+        Some(Self {
+            field,
+            value: value.to_string(),
+            problem,
+        })
+    }
+}
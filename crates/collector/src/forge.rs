@@ -0,0 +1,141 @@
+//! Forges a distro's repos can live on besides GitHub
+//!
+//! A distro's GitLab group, Forgejo org and Pagure namespace are on the public instances
+//! (gitlab.com, Codeberg and pagure.io) unless `forge_kind` names that kind and
+//! `forge_base_url` points at the distro's own server, e.g. Alpine's gitlab.alpinelinux.org
+//! or Fedora's src.fedoraproject.org. Gitweb and cgit have no API to collect from.
+
+use crate::bots::BotFilter;
+use crate::breaker::{self, CircuitBreaker};
+use crate::Result;
+use chrono::{DateTime, Utc};
+use distrovitals_database::Distribution;
+use reqwest::{RequestBuilder, Response};
+use std::collections::HashMap;
+
+/// API a forge speaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeKind {
+    Gitlab,
+    /// Forgejo or Gitea
+    Forgejo,
+    Pagure,
+}
+
+impl ForgeKind {
+    pub const ALL: [ForgeKind; 3] = [Self::Gitlab, Self::Forgejo, Self::Pagure];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.as_str() == name)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Gitlab => "gitlab",
+            Self::Forgejo => "forgejo",
+            Self::Pagure => "pagure",
+        }
+    }
+
+    /// Comma-separated names of every kind, for error messages
+    pub fn names() -> String {
+        Self::ALL.iter().map(|k| k.as_str()).collect::<Vec<_>>().join(", ")
+    }
+
+    /// The public instance used when a distro doesn't host its own
+    pub fn public_url(&self) -> &'static str {
+        match self {
+            Self::Gitlab => "https://gitlab.com",
+            Self::Forgejo => "https://codeberg.org",
+            Self::Pagure => "https://pagure.io",
+        }
+    }
+}
+
+/// One forge instance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Forge {
+    pub kind: ForgeKind,
+    /// Without a trailing slash
+    pub base_url: String,
+}
+
+impl Forge {
+    /// The instance a distro's repos of this kind are on
+    pub fn for_distro(distro: &Distribution, kind: ForgeKind) -> Self {
+        let self_hosted = distro
+            .forge_base_url
+            .as_deref()
+            .filter(|_| distro.forge_kind.as_deref() == Some(kind.as_str()));
+        let base_url = self_hosted.unwrap_or(kind.public_url());
+
+        Self {
+            kind,
+            base_url: base_url.trim_end_matches('/').to_string(),
+        }
+    }
+
+    pub fn is_self_hosted(&self) -> bool {
+        self.base_url != self.kind.public_url()
+    }
+
+    /// Name used in errors
+    pub fn service(&self) -> &'static str {
+        match (self.kind, self.is_self_hosted()) {
+            (ForgeKind::Gitlab, false) => "gitlab.com",
+            (ForgeKind::Forgejo, false) => "codeberg",
+            (ForgeKind::Pagure, false) => "pagure.io",
+            (kind, true) => kind.as_str(),
+        }
+    }
+
+    /// Send a request, through the public instance's circuit breaker when it is one
+    ///
+    /// A self-hosted forge only serves one distro, so there are no other distros' calls
+    /// for a breaker to spare.
+    pub async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        match self.breaker() {
+            Some(breaker) => breaker.send(request).await,
+            None => Ok(request.send().await?),
+        }
+    }
+
+    fn breaker(&self) -> Option<&'static CircuitBreaker> {
+        match (self.kind, self.is_self_hosted()) {
+            (_, true) => None,
+            (ForgeKind::Gitlab, false) => Some(&breaker::GITLAB),
+            (ForgeKind::Forgejo, false) => Some(&breaker::CODEBERG),
+            (ForgeKind::Pagure, false) => Some(&breaker::PAGURE),
+        }
+    }
+}
+
+/// Commits over the last year, split between humans and bots
+#[derive(Debug, Default)]
+pub(crate) struct RecentActivity {
+    pub commits_30d: i64,
+    pub commits_365d: i64,
+    pub bot_commits_30d: i64,
+    pub bot_commits_365d: i64,
+    /// Commits per human author
+    pub authors: HashMap<String, i64>,
+}
+
+impl RecentActivity {
+    /// Count one commit from the last year; `recent` if it's from the last 30 days
+    pub fn record(&mut self, author: &str, recent: bool, bots: &BotFilter) {
+        if bots.is_bot(author) {
+            self.bot_commits_365d += 1;
+            self.bot_commits_30d += recent as i64;
+        } else {
+            self.commits_365d += 1;
+            self.commits_30d += recent as i64;
+            *self.authors.entry(author.to_string()).or_default() += 1;
+        }
+    }
+}
+
+/// Format a time as forges expect in `since` parameters
+pub(crate) fn since_param(at: DateTime<Utc>) -> String {
+    at.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
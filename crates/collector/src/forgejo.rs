@@ -0,0 +1,267 @@
+//! Forgejo collector for distros hosted on Codeberg or their own Forgejo or Gitea
+//!
+//! Forgejo keeps the Gitea API, so repo metrics map field for field onto what the GitHub
+//! collector stores. Snapshots go into the same table under their "owner/name", which
+//! lets a distro's Forgejo repos be scored exactly like GitHub ones.
+
+use crate::bots::BotFilter;
+use crate::forge::{since_param, Forge, RecentActivity};
+use crate::{provenance, CollectorConfig, CollectorError, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use distrovitals_database::{Database, NewGithubSnapshot, Provenance};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::{debug, info, warn};
+
+/// Most recently updated repos collected per org, as for GitHub orgs
+const MAX_REPOS: usize = 30;
+
+/// Forgejo's default maximum page size
+const PAGE_SIZE: usize = 50;
+
+/// Safety cap on repo listing pages for very large orgs
+const MAX_REPO_PAGES: u32 = 10;
+
+/// Commit pages read per repo; the rest of a busy year is taken from the total count
+const MAX_COMMIT_PAGES: u32 = 10;
+
+/// Forgejo API client
+pub struct ForgejoCollector {
+    client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoResponse {
+    name: String,
+    full_name: String,
+    stars_count: i64,
+    forks_count: i64,
+    /// Unlike GitHub's, this excludes pull requests
+    open_issues_count: i64,
+    #[serde(default)]
+    open_pr_counter: i64,
+    #[serde(default)]
+    fork: bool,
+    /// Set for pull mirrors of repos hosted elsewhere
+    #[serde(default)]
+    mirror: bool,
+    updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    topics: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitResponse {
+    commit: CommitDetails,
+    /// Forge account of the author; None when the email matches no account
+    author: Option<AccountRef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitDetails {
+    author: CommitSignature,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitSignature {
+    name: String,
+    date: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountRef {
+    login: String,
+}
+
+impl ForgejoCollector {
+    /// Create a new Forgejo collector
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(config.request_timeout)
+            .build()?;
+
+        Ok(Self { client })
+    }
+
+    /// Collect metrics for a Forgejo organization's repositories
+    pub async fn collect_org_repos(
+        &self,
+        db: &Database,
+        distro_id: i64,
+        forge: &Forge,
+        org: &str,
+    ) -> Result<Vec<i64>> {
+        info!(forge = forge.base_url, org = org, "Collecting Forgejo metrics");
+
+        let distro = db.get_distribution_by_id(distro_id).await?;
+        let bots = BotFilter::new(distro.bot_accounts.as_deref());
+
+        let repos = self.get_org_repos(forge, org).await?;
+        let mut snapshot_ids = Vec::new();
+        for repo in repos {
+            let name = repo.name.clone();
+            match self.fetch_repo_snapshot(distro_id, forge, org, repo, &bots).await {
+                Ok(snapshot) => snapshot_ids.push(db.insert_github_snapshot(snapshot).await?),
+                Err(e) => warn!(repo = name, error = %e, "Failed to collect repo metrics"),
+            }
+        }
+
+        info!(forge = forge.base_url, org = org, count = snapshot_ids.len(), "Collected Forgejo snapshots");
+        Ok(snapshot_ids)
+    }
+
+    async fn fetch_repo_snapshot(
+        &self,
+        distro_id: i64,
+        forge: &Forge,
+        owner: &str,
+        repo: RepoResponse,
+        bots: &BotFilter,
+    ) -> Result<NewGithubSnapshot> {
+        debug!(owner = owner, repo = repo.name, "Collecting repo metrics");
+
+        let mut provenance = provenance();
+        let activity = match self.get_recent_activity(forge, owner, &repo.name, bots, &mut provenance).await {
+            Ok(activity) => activity,
+            Err(e) => {
+                provenance.fallback(format!("commit history failed ({}) → 0", e));
+                RecentActivity::default()
+            }
+        };
+        let languages = self
+            .get_languages(forge, owner, &repo.name, &mut provenance)
+            .await
+            .unwrap_or_default();
+
+        Ok(NewGithubSnapshot {
+            distro_id,
+            repo_name: repo.full_name,
+            stars: repo.stars_count,
+            forks: repo.forks_count,
+            // Counted like GitHub's open_issues_count, which includes pull requests
+            open_issues: repo.open_issues_count + repo.open_pr_counter,
+            open_prs: repo.open_pr_counter,
+            commits_30d: activity.commits_30d,
+            commits_365d: activity.commits_365d,
+            contributors_30d: activity.authors.len() as i64,
+            bot_commits_30d: activity.bot_commits_30d,
+            bot_commits_365d: activity.bot_commits_365d,
+            is_mirror: repo.mirror,
+            topics: repo.topics,
+            languages,
+            contributors: activity.authors.into_iter().collect(),
+            issue_labels: Vec::new(),
+            issue_activity: None,
+            last_commit_at: repo.updated_at,
+            provenance,
+        })
+    }
+
+    /// An org's most recently updated source repos (forks excluded)
+    async fn get_org_repos(&self, forge: &Forge, org: &str) -> Result<Vec<RepoResponse>> {
+        let mut repos = Vec::new();
+        for page in 1..=MAX_REPO_PAGES {
+            let url = format!("{}/api/v1/orgs/{}/repos?limit={}&page={}", forge.base_url, org, PAGE_SIZE, page);
+            let response = forge.send(self.client.get(&url)).await?;
+            if !response.status().is_success() {
+                return Err(CollectorError::from_status(forge.service(), response.status(), format!("org {}", org)));
+            }
+
+            let batch: Vec<RepoResponse> = response.json().await?;
+            let last_page = batch.len() < PAGE_SIZE;
+            repos.extend(batch.into_iter().filter(|r| !r.fork));
+            if last_page {
+                break;
+            }
+        }
+
+        repos.sort_by_key(|r| std::cmp::Reverse(r.updated_at));
+        repos.truncate(MAX_REPOS);
+        Ok(repos)
+    }
+
+    async fn get_recent_activity(
+        &self,
+        forge: &Forge,
+        owner: &str,
+        repo: &str,
+        bots: &BotFilter,
+        provenance: &mut Provenance,
+    ) -> Result<RecentActivity> {
+        let now = Utc::now();
+        let month_ago = now - TimeDelta::days(30);
+        let since = since_param(now - TimeDelta::days(365));
+
+        let mut activity = RecentActivity::default();
+        let mut fetched = 0;
+        let mut total = None;
+        for page in 1..=MAX_COMMIT_PAGES {
+            let url = format!(
+                "{}/api/v1/repos/{}/{}/commits?since={}&limit={}&page={}&stat=false&verification=false&files=false",
+                forge.base_url, owner, repo, since, PAGE_SIZE, page
+            );
+            let response = self.get(forge, &url, provenance).await?;
+            // Empty repos have no branch to list commits from
+            if response.status() == reqwest::StatusCode::CONFLICT {
+                break;
+            }
+            if !response.status().is_success() {
+                return Err(CollectorError::from_status(
+                    forge.service(),
+                    response.status(),
+                    format!("commits {}/{}", owner, repo),
+                ));
+            }
+
+            total = response
+                .headers()
+                .get("x-total-count")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<i64>().ok());
+            let commits: Vec<CommitResponse> = response.json().await?;
+            fetched += commits.len() as i64;
+
+            for commit in &commits {
+                let author = commit.author.as_ref().map(|a| a.login.as_str()).unwrap_or(&commit.commit.author.name);
+                activity.record(author, commit.commit.author.date >= month_ago, bots);
+            }
+
+            if commits.len() < PAGE_SIZE {
+                break;
+            }
+        }
+
+        // Pages are newest first, so only the year's older commits can be left unread
+        if let Some(unread) = total.map(|t| t - fetched).filter(|&n| n > 0) {
+            provenance.fallback(format!("{} older commits past the page cap → counted as human", unread));
+            activity.commits_365d += unread;
+        }
+
+        Ok(activity)
+    }
+
+    async fn get_languages(
+        &self,
+        forge: &Forge,
+        owner: &str,
+        repo: &str,
+        provenance: &mut Provenance,
+    ) -> Result<Vec<(String, i64)>> {
+        let url = format!("{}/api/v1/repos/{}/{}/languages", forge.base_url, owner, repo);
+        let response = self.get(forge, &url, provenance).await?;
+        if !response.status().is_success() {
+            return Ok(Vec::new());
+        }
+
+        let languages: HashMap<String, i64> = response.json().await?;
+        Ok(languages.into_iter().collect())
+    }
+
+    /// GET `url`, recording the request in the snapshot's provenance
+    async fn get(&self, forge: &Forge, url: &str, provenance: &mut Provenance) -> Result<reqwest::Response> {
+        provenance.request(url);
+        forge.send(self.client.get(url)).await
+    }
+}
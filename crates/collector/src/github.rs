@@ -1,41 +1,440 @@
 //! GitHub API collector
 
-use crate::{CollectorConfig, CollectorError, Result};
+use crate::bots::BotFilter;
+use crate::breaker;
+use crate::github_graphql as graphql;
+use crate::governance::{DocsFound, DOC_DIRS};
+use crate::labels;
+use crate::tokens::TokenPool;
+use crate::{provenance, CollectorConfig, CollectorError, Result};
 use chrono::{DateTime, Utc};
-use distrovitals_database::{Database, NewGithubSnapshot, NewReleaseSnapshot};
-use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, USER_AGENT};
+use distrovitals_database::{
+    Database, IssueActivity, IssueCategory, MetricKey, NewCommunitySnapshot, NewGithubSnapshot,
+    NewGovernanceSnapshot, NewMetricValue, NewOrgSnapshot, NewReleaseSnapshot, NewUpstreamSnapshot, Provenance,
+    ReleaseCursor, Upstream,
+};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, LINK, RETRY_AFTER, USER_AGENT};
 use reqwest::Client;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 use tracing::{debug, info, warn};
 
+/// Releases fetched for a repo seen for the first time
+const INITIAL_RELEASES: usize = 30;
+
+/// Page size when catching up on releases after the cursor
+const RELEASE_PAGE_SIZE: usize = 100;
+
+/// Most pages fetched while catching up on one repo's releases
+const MAX_RELEASE_PAGES: u32 = 10;
+
+/// Most tag commits looked up per repo per run
+const MAX_TAG_LOOKUPS: usize = 10;
+
+/// Most-starred repos per org whose open issues are broken down by label (each costs a
+/// search request per label category) and whose governance docs are checked
+const CORE_REPOS: usize = 3;
+
+/// Pages of issue events read per core repo looking for stale-bot closures
+const MAX_EVENT_PAGES: u32 = 5;
+
+/// Days of issue activity and merged pull requests measured for core repos
+const ISSUE_ACTIVITY_DAYS: i64 = 30;
+
+/// Pages of recently closed pull requests read per core repo looking for first-time authors
+const MAX_PULL_PAGES: u32 = 3;
+
+/// Times one request waits out a secondary rate limit before giving up
+const MAX_SECONDARY_RETRIES: u32 = 3;
+
+/// Wait after a secondary rate limit without Retry-After; GitHub asks for at least a minute
+const SECONDARY_LIMIT_WAIT: Duration = Duration::from_secs(60);
+
+/// Retries of a request that failed transiently (network errors, 5xx responses)
+const MAX_TRANSIENT_RETRIES: u32 = 2;
+
+/// Wait before the first transient retry, doubled for each one after
+const TRANSIENT_RETRY_WAIT: Duration = Duration::from_secs(2);
+
+/// Pages of 100 discussions read back through the last 30 days
+const MAX_DISCUSSION_PAGES: u32 = 5;
+
 /// GitHub API client
 pub struct GithubCollector {
     client: Client,
     #[allow(dead_code)]
     config: CollectorConfig,
+    tokens: TokenPool,
 }
 
 #[derive(Debug, Deserialize)]
 struct RepoResponse {
     name: String,
+    /// Current "owner/name", which differs from the requested path after a rename or transfer
+    full_name: String,
     stargazers_count: i64,
     forks_count: i64,
     open_issues_count: i64,
     pushed_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    fork: bool,
+    description: Option<String>,
+    homepage: Option<String>,
+    has_issues: Option<bool>,
+    /// Set by GitHub for repos configured as pull mirrors
+    mirror_url: Option<String>,
+    #[serde(default)]
+    topics: Vec<String>,
+}
+
+/// Hosts whose URLs are code repositories, for spotting a homepage that names the
+/// canonical copy of a repo
+const CODE_HOSTS: &[&str] = &[
+    "github.com",
+    "gitlab.com",
+    "codeberg.org",
+    "git.kernel.org",
+    "gitlab.freedesktop.org",
+    "gitlab.gnome.org",
+    "invent.kde.org",
+    "salsa.debian.org",
+    "src.fedoraproject.org",
+    "pagure.io",
+    "git.sr.ht",
+    "sourceware.org",
+    "git.savannah.gnu.org",
+];
+
+impl RepoResponse {
+    /// Heuristic for repos that mirror upstream code rather than hold the distro's own work
+    ///
+    /// Only positive signs count. Repos taking no issues or pull requests are common among
+    /// distros with their own bug tracker, so that alone says nothing.
+    fn looks_like_mirror(&self) -> bool {
+        if self.fork || self.mirror_url.is_some() {
+            return true;
+        }
+
+        let described_as_mirror = self
+            .description
+            .as_deref()
+            .map(|d| d.to_ascii_lowercase().contains("mirror"))
+            .unwrap_or(false);
+        described_as_mirror || self.homepage.as_deref().is_some_and(|url| self.is_other_repo(url))
+    }
+
+    /// Whether `url` is a repository on a code host other than this one
+    fn is_other_repo(&self, url: &str) -> bool {
+        let Ok(url) = reqwest::Url::parse(url.trim()) else {
+            return false;
+        };
+        let Some(host) = url.host_str().map(|h| h.trim_start_matches("www.").to_ascii_lowercase()) else {
+            return false;
+        };
+        let path = url.path().trim_matches('/').trim_end_matches(".git");
+        if !CODE_HOSTS.contains(&host.as_str()) || path.split('/').filter(|s| !s.is_empty()).count() < 2 {
+            return false;
+        }
+        !(host == "github.com" && path.eq_ignore_ascii_case(&self.full_name))
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct CommitResponse {
     #[allow(dead_code)]
     sha: String,
+    /// GitHub account linked to the commit author, if any
+    author: Option<AccountRef>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AccountRef {
+    login: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContributorStats {
+    author: Option<AccountRef>,
+    #[serde(default)]
+    weeks: Vec<ContributorWeek>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ContributorWeek {
+    /// Commits that week
+    c: i64,
+}
+
+/// Commit activity for a repository, split between humans and bots
+#[derive(Debug, Default)]
+struct RecentActivity {
+    commits_30d: i64,
+    commits_365d: i64,
+    contributors: i64,
+    bot_commits_30d: i64,
+    bot_commits_365d: i64,
+    /// (login, commits) of human contributors
+    logins: Vec<(String, i64)>,
 }
 
 #[derive(Debug, Deserialize)]
 struct ReleaseResponse {
+    id: i64,
     tag_name: String,
     name: Option<String>,
     published_at: Option<DateTime<Utc>>,
     prerelease: bool,
+    #[serde(default)]
+    assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReleaseAsset {
+    name: String,
+    #[serde(default)]
+    download_count: i64,
+}
+
+/// Releases from one collection pass, split by the repo's release cursor
+#[derive(Debug, Default)]
+struct FetchedReleases {
+    /// Published after the cursor (or everything on a first pass)
+    new: Vec<ReleaseResponse>,
+    /// Already recorded, returned alongside the new ones
+    seen: Vec<ReleaseResponse>,
+}
+
+impl ReleaseResponse {
+    /// Whether any asset is a detached signature, or None without assets
+    fn signed(&self) -> Option<bool> {
+        const SIGNATURES: &[&str] = &[".asc", ".sig", ".sign", ".minisig", ".gpg", ".p7s"];
+        self.any_asset(|name| SIGNATURES.iter().any(|ext| name.ends_with(ext)))
+    }
+
+    /// Whether any asset is a checksum file, or None without assets
+    fn checksummed(&self) -> Option<bool> {
+        const CHECKSUMS: &[&str] = &["sha256", "sha512", "sha1", "md5", "b2sum", "checksum"];
+        self.any_asset(|name| CHECKSUMS.iter().any(|marker| name.contains(marker)))
+    }
+
+    /// Downloads summed over all assets, or None without assets
+    fn downloads(&self) -> Option<i64> {
+        if self.assets.is_empty() {
+            return None;
+        }
+        Some(self.assets.iter().map(|a| a.download_count).sum())
+    }
+
+    fn any_asset(&self, matches: impl Fn(&str) -> bool) -> Option<bool> {
+        if self.assets.is_empty() {
+            return None;
+        }
+        Some(self.assets.iter().any(|a| matches(&a.name.to_lowercase())))
+    }
+}
+
+/// Public profile of an org, or of the user account some distros publish from
+#[derive(Debug, Deserialize)]
+struct OwnerResponse {
+    login: String,
+    /// "Organization" or "User"
+    #[serde(rename = "type")]
+    kind: String,
+    public_repos: i64,
+    followers: i64,
+    created_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagResponse {
+    name: String,
+    commit: TagRef,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagRef {
+    sha: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TagCommitResponse {
+    commit: GitCommit,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitCommit {
+    committer: Option<GitSignature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GitSignature {
+    date: DateTime<Utc>,
+}
+
+/// A repo's metrics beyond its metadata, however they were fetched
+struct RepoMetrics {
+    open_prs: i64,
+    languages: Vec<(String, i64)>,
+    activity: RecentActivity,
+    issue_labels: Vec<(IssueCategory, i64)>,
+    issue_activity: Option<IssueActivity>,
+}
+
+/// Snapshot of a repo requested as "owner/name"
+fn repo_snapshot(
+    distro_id: i64,
+    requested: &str,
+    repo_info: RepoResponse,
+    metrics: RepoMetrics,
+    provenance: Provenance,
+) -> NewGithubSnapshot {
+    if !repo_info.full_name.eq_ignore_ascii_case(requested) {
+        info!(
+            requested = requested,
+            canonical = repo_info.full_name,
+            "Repository was renamed or transferred"
+        );
+    }
+
+    let is_mirror = repo_info.looks_like_mirror();
+    if is_mirror {
+        debug!(repo = repo_info.full_name, "Repository looks like a mirror");
+    }
+
+    // Record under the canonical name so history doesn't split across names
+    NewGithubSnapshot {
+        distro_id,
+        repo_name: repo_info.full_name,
+        stars: repo_info.stargazers_count,
+        forks: repo_info.forks_count,
+        open_issues: repo_info.open_issues_count,
+        open_prs: metrics.open_prs,
+        commits_30d: metrics.activity.commits_30d,
+        commits_365d: metrics.activity.commits_365d,
+        contributors_30d: metrics.activity.contributors,
+        bot_commits_30d: metrics.activity.bot_commits_30d,
+        bot_commits_365d: metrics.activity.bot_commits_365d,
+        is_mirror,
+        topics: repo_info.topics,
+        languages: metrics.languages,
+        contributors: metrics.activity.logins,
+        issue_labels: metrics.issue_labels,
+        issue_activity: metrics.issue_activity,
+        last_commit_at: repo_info.pushed_at,
+        provenance,
+    }
+}
+
+impl From<graphql::RepoNode> for RepoResponse {
+    fn from(node: graphql::RepoNode) -> Self {
+        let name = node.name_with_owner.split_once('/').map_or(&*node.name_with_owner, |(_, name)| name);
+        Self {
+            name: name.to_string(),
+            // Like REST's open_issues_count, which includes pull requests
+            open_issues_count: node.issues.total_count + node.pull_requests.total_count,
+            full_name: node.name_with_owner,
+            stargazers_count: node.stargazer_count,
+            forks_count: node.fork_count,
+            pushed_at: node.pushed_at,
+            fork: node.is_fork,
+            description: node.description,
+            homepage: node.homepage_url,
+            has_issues: Some(node.has_issues_enabled),
+            // REST only sets mirror_url for pull mirrors, which GraphQL flags separately
+            mirror_url: node.mirror_url.filter(|_| node.is_mirror),
+            topics: node.repository_topics.nodes.into_iter().map(|n| n.topic.name).collect(),
+        }
+    }
+}
+
+impl From<graphql::ReleaseNode> for ReleaseResponse {
+    fn from(node: graphql::ReleaseNode) -> Self {
+        Self {
+            id: node.database_id,
+            tag_name: node.tag_name,
+            name: node.name,
+            published_at: node.published_at,
+            prerelease: node.is_prerelease,
+            assets: node
+                .release_assets
+                .nodes
+                .into_iter()
+                .map(|a| ReleaseAsset { name: a.name, download_count: a.download_count })
+                .collect(),
+        }
+    }
+}
+
+/// Commit activity from a repo's default branch history over GraphQL
+///
+/// The last 30 days' commits are read up to [`graphql::RECENT_COMMITS`]; any past that
+/// count as human. Bots are only told apart within those 30 days, so the year's bot
+/// commits are the month's.
+fn graphql_activity(
+    branch: Option<&graphql::BranchRef>,
+    bots: &BotFilter,
+    provenance: &mut Provenance,
+) -> RecentActivity {
+    let mut activity = RecentActivity::default();
+    let Some(history) = branch.and_then(|b| b.target.as_ref()) else {
+        return activity;
+    };
+
+    let mut authors: HashMap<&str, i64> = HashMap::new();
+    for commit in &history.month.nodes {
+        let author = commit.author().unwrap_or_default();
+        if bots.is_bot(author) {
+            activity.bot_commits_30d += 1;
+        } else {
+            activity.commits_30d += 1;
+            *authors.entry(author).or_default() += 1;
+        }
+    }
+    let unread = history.month.total_count - history.month.nodes.len() as i64;
+    if unread > 0 {
+        provenance.fallback(format!(
+            "{} commits past the first {} → counted as human",
+            unread,
+            graphql::RECENT_COMMITS
+        ));
+        activity.commits_30d += unread;
+    }
+
+    activity.bot_commits_365d = activity.bot_commits_30d;
+    activity.commits_365d = (history.year.total_count - activity.bot_commits_365d).max(0);
+    activity.contributors = authors.len() as i64;
+    activity.logins = authors
+        .into_iter()
+        .filter(|(author, _)| !author.is_empty())
+        .map(|(author, commits)| (author.to_string(), commits))
+        .collect();
+    activity
+}
+
+/// Most-starred repos taking issues, up to [`CORE_REPOS`]
+fn core_repos(repos: &[RepoResponse]) -> Vec<String> {
+    let mut by_stars: Vec<&RepoResponse> = repos.iter().filter(|r| r.has_issues != Some(false)).collect();
+    by_stars.sort_by_key(|r| std::cmp::Reverse(r.stargazers_count));
+    by_stars.iter().take(CORE_REPOS).map(|r| r.name.clone()).collect()
+}
+
+/// Number of the last page from a `Link` header, when there's more than one page
+fn last_page(headers: &HeaderMap) -> Option<i64> {
+    let link = headers.get(LINK)?.to_str().ok()?;
+    let last = link.split(',').find(|part| part.contains("rel=\"last\""))?;
+    let url = last.split(';').next()?.trim().trim_start_matches('<').trim_end_matches('>');
+    let query = url.split_once('?')?.1;
+    query.split('&').find_map(|pair| pair.strip_prefix("page="))?.parse().ok()
+}
+
+/// Whether a tag name marks a pre-release, e.g. "v2.0-rc1" or "1.4.0-beta"
+fn looks_like_prerelease(tag: &str) -> bool {
+    let tag = tag.to_lowercase();
+    ["alpha", "beta", "-rc", ".rc", "_rc", "-pre", "preview", "snapshot"]
+        .iter()
+        .any(|marker| tag.contains(marker))
 }
 
 impl GithubCollector {
@@ -45,16 +444,15 @@ impl GithubCollector {
         headers.insert(ACCEPT, HeaderValue::from_static("application/vnd.github.v3+json"));
         headers.insert(USER_AGENT, HeaderValue::from_str(&config.user_agent).unwrap());
 
-        if let Some(ref token) = config.github_token {
-            headers.insert(
-                AUTHORIZATION,
-                HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
-            );
-        }
-
-        let client = Client::builder().default_headers(headers).build()?;
+        // Renamed and transferred repos answer with a 301 to their new location
+        let client = Client::builder()
+            .default_headers(headers)
+            .redirect(reqwest::redirect::Policy::limited(10))
+            .timeout(config.request_timeout)
+            .build()?;
 
-        Ok(Self { client, config })
+        let tokens = TokenPool::new(&config.github_tokens);
+        Ok(Self { client, config, tokens })
     }
 
     /// Collect metrics for a GitHub organization's repositories
@@ -66,20 +464,289 @@ impl GithubCollector {
     ) -> Result<Vec<i64>> {
         info!(org = org, "Collecting GitHub metrics");
 
-        let repos = self.get_org_repos(org).await?;
+        let (org, repos) = self.list_org_repos(db, org).await?;
+        let org = org.as_str();
         let mut snapshot_ids = Vec::new();
 
-        for repo in repos {
-            match self.collect_repo(db, distro_id, org, &repo.name).await {
-                Ok(id) => snapshot_ids.push(id),
-                Err(e) => warn!(repo = repo.name, error = %e, "Failed to collect repo metrics"),
+        let distro = db.get_distribution_by_id(distro_id).await?;
+        let bots = BotFilter::new(distro.bot_accounts.as_deref());
+        let core = core_repos(&repos);
+        let wanted: Vec<(&str, bool)> = repos.iter().map(|r| (r.name.as_str(), core.contains(&r.name))).collect();
+        for (repo, snapshot) in self.fetch_repo_snapshots(distro_id, org, &wanted, &bots).await {
+            match snapshot {
+                Ok(snapshot) => snapshot_ids.push(db.insert_github_snapshot(snapshot).await?),
+                Err(e) => warn!(repo = repo, error = %e, "Failed to collect repo metrics"),
+            }
+        }
+
+        // Distinct across repos: someone's first PRs to two repos are still one newcomer
+        let mut newcomers = HashSet::new();
+        let mut measured = false;
+        for repo in &core {
+            match self.first_time_contributors(org, repo).await {
+                Ok(logins) => {
+                    newcomers.extend(logins);
+                    measured = true;
+                }
+                Err(e) => warn!(repo = repo, error = %e, "Failed to count first-time contributors"),
             }
         }
+        if measured {
+            db.insert_metric(NewMetricValue {
+                distro_id,
+                source: "github".to_string(),
+                key: MetricKey::FirstTimeContributors30d,
+                value: newcomers.len() as f64,
+            })
+            .await?;
+        }
 
         info!(org = org, count = snapshot_ids.len(), "Collected GitHub snapshots");
         Ok(snapshot_ids)
     }
 
+    /// Check an org's core repos, and the org-wide defaults in its `.github` repo, for
+    /// contributing guidelines, a code of conduct, a security policy and governance docs
+    pub async fn collect_governance(&self, db: &Database, distro_id: i64, org: &str) -> Result<i64> {
+        info!(org = org, "Checking governance docs");
+
+        let (org, repos) = self.list_org_repos(db, org).await?;
+        let core = core_repos(&repos);
+        let mut provenance = provenance();
+
+        let mut found = self.repo_docs(&org, ".github", &mut provenance).await?.unwrap_or_default();
+        for repo in &core {
+            match self.repo_docs(&org, repo, &mut provenance).await? {
+                Some(docs) => found.merge(docs),
+                None => provenance.fallback(format!("{}/{} not found → skipped", org, repo)),
+            }
+        }
+
+        let id = db
+            .insert_governance_snapshot(NewGovernanceSnapshot {
+                distro_id,
+                repos_checked: core.len() as i64,
+                contributing: found.contributing,
+                code_of_conduct: found.code_of_conduct,
+                security_policy: found.security_policy,
+                governance: found.governance,
+                provenance,
+            })
+            .await?;
+
+        info!(org = org, ?found, "Checked governance docs");
+        Ok(id)
+    }
+
+    /// Read an org's profile: its public repos, public members, followers and sponsors
+    ///
+    /// A handful of requests however many repos the org has, where the per-repo
+    /// collection only covers the 30 most recently pushed.
+    pub async fn collect_org(&self, db: &Database, distro_id: i64, org: &str) -> Result<i64> {
+        info!(org = org, "Collecting GitHub org profile");
+
+        let org = db.canonical_org(org).await?;
+        let mut provenance = provenance();
+
+        // Some distros publish from a user account rather than an org
+        let url = format!("https://api.github.com/users/{}", org);
+        let response = self.get(&url, &mut provenance).await?;
+        self.check_rate_limit(&response)?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("github", response.status(), format!("org {}", org)));
+        }
+        let owner: OwnerResponse = response.json().await?;
+
+        let public_members = if owner.kind == "Organization" {
+            match self.count_public_members(&owner.login, &mut provenance).await {
+                Ok(count) => Some(count),
+                Err(e) => {
+                    provenance.fallback(format!("public member count failed ({}) → unset", e));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let sponsors = if self.tokens.is_empty() {
+            provenance.fallback("sponsors need a GitHub token → unset");
+            None
+        } else {
+            match self.count_sponsors(&owner.login, &mut provenance).await {
+                Ok(count) => count,
+                Err(e) => {
+                    provenance.fallback(format!("sponsor count failed ({}) → unset", e));
+                    None
+                }
+            }
+        };
+
+        let id = db
+            .insert_org_snapshot(NewOrgSnapshot {
+                distro_id,
+                org: owner.login.clone(),
+                public_repos: owner.public_repos,
+                public_members,
+                followers: owner.followers,
+                sponsors,
+                org_created_at: owner.created_at,
+                provenance,
+            })
+            .await?;
+
+        info!(
+            org = owner.login,
+            public_repos = owner.public_repos,
+            public_members = public_members,
+            sponsors = sponsors,
+            "Collected GitHub org profile"
+        );
+        Ok(id)
+    }
+
+    /// Collect the last 30 days of a repo's GitHub Discussions as a community snapshot
+    ///
+    /// Posts are the discussions started, questions the ones in answerable (Q&A)
+    /// categories, and the response time the mean hours from a question to the comment
+    /// accepted as its answer. Active users are the people who started or answered one.
+    /// Discussions are only in the GraphQL API, which always needs a token.
+    pub async fn collect_discussions(&self, db: &Database, distro_id: i64, repo: &str) -> Result<i64> {
+        let (owner, name) = repo
+            .split_once('/')
+            .ok_or_else(|| CollectorError::Api(format!("Discussions repo {:?} isn't \"owner/name\"", repo)))?;
+        info!(repo = repo, "Collecting GitHub Discussions");
+
+        let distro = db.get_distribution_by_id(distro_id).await?;
+        let bots = BotFilter::new(distro.bot_accounts.as_deref());
+        let mut provenance = provenance();
+        let since = Utc::now() - chrono::TimeDelta::days(30);
+
+        let mut discussions = Vec::new();
+        let mut cursor: Option<String> = None;
+        for page in 1..=MAX_DISCUSSION_PAGES {
+            let variables = serde_json::json!({ "owner": owner, "name": name, "cursor": cursor });
+            let data: graphql::DiscussionsData =
+                self.graphql(graphql::DISCUSSIONS_QUERY, variables, &mut provenance).await?;
+            let repository = data.repository.ok_or_else(|| CollectorError::NotFound {
+                service: "github",
+                resource: format!("repo {}", repo),
+                status: 404,
+            })?;
+            if !repository.has_discussions_enabled {
+                return Err(CollectorError::Api(format!("Discussions aren't enabled on {}", repo)));
+            }
+
+            let connection = repository.discussions;
+            let reached_since = connection.nodes.last().is_none_or(|d| d.created_at < since);
+            discussions.extend(connection.nodes.into_iter().filter(|d| d.created_at >= since));
+            match connection.page_info.end_cursor {
+                Some(end) if connection.page_info.has_next_page && !reached_since => {
+                    if page == MAX_DISCUSSION_PAGES {
+                        provenance.fallback(format!(
+                            "discussions past the first {} → not counted",
+                            MAX_DISCUSSION_PAGES * 100
+                        ));
+                    }
+                    cursor = Some(end);
+                }
+                _ => break,
+            }
+        }
+
+        let questions: Vec<&graphql::DiscussionNode> =
+            discussions.iter().filter(|d| d.category.is_answerable).collect();
+        let answer_hours: Vec<f64> = questions
+            .iter()
+            .filter_map(|d| d.answer.as_ref().map(|a| (a.created_at - d.created_at).num_minutes() as f64 / 60.0))
+            .collect();
+        let people: HashSet<&str> = discussions
+            .iter()
+            .flat_map(|d| [d.author.as_ref(), d.answer.as_ref().and_then(|a| a.author.as_ref())])
+            .flatten()
+            .map(|user| user.login.as_str())
+            .filter(|login| !bots.is_bot(login))
+            .collect();
+
+        let snapshot = NewCommunitySnapshot {
+            distro_id,
+            source: format!("github-discussions:{}", repo),
+            active_users_30d: Some(people.len() as i64),
+            posts_30d: Some(discussions.len() as i64),
+            response_time_avg_hours: (!answer_hours.is_empty())
+                .then(|| answer_hours.iter().sum::<f64>() / answer_hours.len() as f64),
+            questions_30d: Some(questions.len() as i64),
+            answers_30d: Some(answer_hours.len() as i64),
+            provenance,
+        };
+        let id = db.insert_community_snapshot(snapshot).await?;
+
+        info!(
+            repo = repo,
+            discussions = discussions.len(),
+            questions = questions.len(),
+            answered = answer_hours.len(),
+            "Collected GitHub Discussions snapshot"
+        );
+        Ok(id)
+    }
+
+    /// Whether a token is configured, which GraphQL-only data needs
+    pub fn has_token(&self) -> bool {
+        !self.tokens.is_empty()
+    }
+
+    /// Collect metrics for an upstream project's repositories (desktop environments etc.)
+    pub async fn collect_upstream(&self, db: &Database, upstream: &Upstream) -> Result<Vec<i64>> {
+        let org = upstream.github_org.as_deref().ok_or_else(|| {
+            CollectorError::Api(format!("No GitHub org configured for upstream {}", upstream.slug))
+        })?;
+
+        info!(upstream = upstream.slug, org = org, "Collecting upstream GitHub metrics");
+
+        let (org, repos) = self.list_org_repos(db, org).await?;
+        let org = org.as_str();
+        let mut snapshot_ids = Vec::new();
+
+        let matching: Vec<(&str, bool)> = repos
+            .iter()
+            .filter(|r| {
+                upstream
+                    .repo_prefix
+                    .as_deref()
+                    .map(|prefix| r.name.starts_with(prefix))
+                    .unwrap_or(true)
+            })
+            .map(|r| (r.name.as_str(), false))
+            .collect();
+
+        for (repo, snapshot) in self
+            .fetch_repo_snapshots(upstream.id, org, &matching, &BotFilter::default())
+            .await {
+            match snapshot {
+                Ok(snap) => {
+                    let snapshot = NewUpstreamSnapshot {
+                        upstream_id: upstream.id,
+                        repo_name: snap.repo_name,
+                        stars: snap.stars,
+                        forks: snap.forks,
+                        open_issues: snap.open_issues,
+                        open_prs: snap.open_prs,
+                        commits_30d: snap.commits_30d,
+                        contributors_30d: snap.contributors_30d,
+                        last_commit_at: snap.last_commit_at,
+                        provenance: snap.provenance,
+                    };
+                    snapshot_ids.push(db.insert_upstream_snapshot(snapshot).await?);
+                }
+                Err(e) => warn!(repo = repo, error = %e, "Failed to collect upstream repo metrics"),
+            }
+        }
+
+        info!(upstream = upstream.slug, count = snapshot_ids.len(), "Collected upstream snapshots");
+        Ok(snapshot_ids)
+    }
+
     /// Collect releases for a GitHub organization's repositories
     pub async fn collect_org_releases(
         &self,
@@ -89,13 +756,27 @@ impl GithubCollector {
     ) -> Result<Vec<i64>> {
         info!(org = org, "Collecting GitHub releases");
 
-        let repos = self.get_org_repos(org).await?;
+        let (org, repos) = self.list_org_repos(db, org).await?;
+        let org = org.as_str();
         let mut release_ids = Vec::new();
 
-        for repo in repos {
-            match self.collect_repo_releases(db, distro_id, org, &repo.name).await {
-                Ok(ids) => release_ids.extend(ids),
-                Err(e) => warn!(repo = repo.name, error = %e, "Failed to collect releases"),
+        let names: Vec<&str> = repos.iter().map(|r| r.name.as_str()).collect();
+        for batch in names.chunks(graphql::BATCH_SIZE) {
+            // Latest releases of the whole batch in one query when there's a token
+            let mut latest = match self.tokens.is_empty() {
+                true => Vec::new(),
+                false => self.graphql_releases(org, batch).await.unwrap_or_else(|e| {
+                    warn!(org = org, error = %e, "GitHub GraphQL release batch failed, falling back to REST");
+                    Vec::new()
+                }),
+            };
+            latest.resize_with(batch.len(), || None);
+
+            for (&repo, latest) in batch.iter().zip(latest) {
+                match self.store_repo_releases(db, distro_id, org, repo, latest).await {
+                    Ok(ids) => release_ids.extend(ids),
+                    Err(e) => warn!(repo = repo, error = %e, "Failed to collect releases"),
+                }
             }
         }
 
@@ -104,6 +785,8 @@ impl GithubCollector {
     }
 
     /// Collect releases for a single repository
+    ///
+    /// Only releases published after the newest one seen last time are fetched and stored.
     pub async fn collect_repo_releases(
         &self,
         db: &Database,
@@ -111,116 +794,510 @@ impl GithubCollector {
         owner: &str,
         repo: &str,
     ) -> Result<Vec<i64>> {
-        let releases = self.get_releases(owner, repo).await?;
-        let mut ids = Vec::new();
+        self.store_repo_releases(db, distro_id, owner, repo, None).await
+    }
 
+    /// Fetch and store a repo's new releases, starting from `latest` when its latest
+    /// releases were already fetched over GraphQL
+    async fn store_repo_releases(
+        &self,
+        db: &Database,
+        distro_id: i64,
+        owner: &str,
+        repo: &str,
+        latest: Option<Vec<ReleaseResponse>>,
+    ) -> Result<Vec<i64>> {
         let repo_name = format!("{}/{}", owner, repo);
-        for release in releases {
+        let cursor = db.get_release_cursor(distro_id, &repo_name).await?;
+        let mut provenance = provenance();
+        let FetchedReleases { new, seen } =
+            self.get_releases(owner, repo, cursor.as_ref(), latest, &mut provenance).await?;
+
+        // Repos that never published a Release may still tag their versions
+        if new.is_empty() && cursor.is_none() {
+            provenance.fallback("no releases → tags");
+            return self.collect_repo_tags(db, distro_id, owner, repo, provenance).await;
+        }
+
+        // Listed newest first
+        let newest = new.first().map(|r| (r.id, r.published_at));
+
+        // Seen releases are snapshotted again only when their download counts moved
+        let known_downloads = if seen.iter().any(|r| r.downloads().is_some()) {
+            db.get_release_downloads(distro_id, &repo_name).await?
+        } else {
+            HashMap::new()
+        };
+        let changed = seen
+            .into_iter()
+            .filter(|r| r.downloads().is_some_and(|d| known_downloads.get(&r.tag_name) != Some(&d)));
+
+        let mut ids = Vec::new();
+        for release in new.into_iter().chain(changed) {
             let snapshot = NewReleaseSnapshot {
                 distro_id,
                 repo_name: repo_name.clone(),
+                signed: release.signed(),
+                checksummed: release.checksummed(),
+                downloads: release.downloads(),
                 tag_name: release.tag_name,
                 release_name: release.name,
                 published_at: release.published_at,
                 is_prerelease: release.prerelease,
+                is_tag: false,
+                provenance: provenance.clone(),
             };
 
             let id = db.insert_release_snapshot(snapshot).await?;
             ids.push(id);
         }
 
+        if let Some((release_id, published_at)) = newest {
+            db.set_release_cursor(distro_id, &repo_name, release_id, published_at).await?;
+        }
+
         debug!(owner = owner, repo = repo, count = ids.len(), "Collected releases");
         Ok(ids)
     }
 
-    async fn get_releases(&self, owner: &str, repo: &str) -> Result<Vec<ReleaseResponse>> {
+    /// Record tags as releases for a repo without GitHub Releases, dated by their commits
+    ///
+    /// Tags already recorded are skipped, so each tag costs one commit lookup ever.
+    async fn collect_repo_tags(
+        &self,
+        db: &Database,
+        distro_id: i64,
+        owner: &str,
+        repo: &str,
+        mut provenance: Provenance,
+    ) -> Result<Vec<i64>> {
         let url = format!(
-            "https://api.github.com/repos/{}/{}/releases?per_page=30",
-            owner, repo
+            "https://api.github.com/repos/{}/{}/tags?per_page={}",
+            owner, repo, INITIAL_RELEASES
         );
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.get(&url, &mut provenance).await?;
         self.check_rate_limit(&response)?;
 
         if !response.status().is_success() {
             return Ok(Vec::new());
         }
 
-        let releases: Vec<ReleaseResponse> = response.json().await.unwrap_or_default();
-        Ok(releases)
-    }
+        let tags: Vec<TagResponse> = response.json().await.unwrap_or_default();
+        if tags.is_empty() {
+            return Ok(Vec::new());
+        }
 
-    /// Collect metrics for a single repository
-    pub async fn collect_repo(
-        &self,
-        db: &Database,
-        distro_id: i64,
-        owner: &str,
-        repo: &str,
-    ) -> Result<i64> {
-        debug!(owner = owner, repo = repo, "Collecting repo metrics");
+        let repo_name = format!("{}/{}", owner, repo);
+        let known = db.get_release_tag_names(distro_id, &repo_name).await?;
 
-        let repo_info = self.get_repo(owner, repo).await?;
-        let open_prs = self.count_open_prs(owner, repo).await.unwrap_or(0);
-        let (commits_30d, commits_365d, contributors_30d) = self
-            .get_recent_activity(owner, repo)
-            .await
-            .unwrap_or((0, 0, 0));
+        let mut ids = Vec::new();
+        for tag in tags
+            .into_iter()
+            .filter(|t| !known.contains(&t.name))
+            .take(MAX_TAG_LOOKUPS)
+        {
+            let published_at = self.get_commit_date(owner, repo, &tag.commit.sha, &mut provenance).await?;
+            let snapshot = NewReleaseSnapshot {
+                distro_id,
+                repo_name: repo_name.clone(),
+                is_prerelease: looks_like_prerelease(&tag.name),
+                tag_name: tag.name,
+                release_name: None,
+                published_at,
+                is_tag: true,
+                signed: None,
+                checksummed: None,
+                downloads: None,
+                provenance: provenance.clone(),
+            };
 
-        let snapshot = NewGithubSnapshot {
-            distro_id,
-            repo_name: format!("{}/{}", owner, repo),
-            stars: repo_info.stargazers_count,
-            forks: repo_info.forks_count,
-            open_issues: repo_info.open_issues_count,
-            open_prs,
-            commits_30d,
-            commits_365d,
-            contributors_30d,
-            last_commit_at: repo_info.pushed_at,
-        };
+            ids.push(db.insert_release_snapshot(snapshot).await?);
+        }
 
-        let id = db.insert_github_snapshot(snapshot).await?;
-        Ok(id)
+        debug!(owner = owner, repo = repo, count = ids.len(), "Collected tags as releases");
+        Ok(ids)
     }
 
-    async fn get_org_repos(&self, org: &str) -> Result<Vec<RepoResponse>> {
-        let url = format!(
-            "https://api.github.com/orgs/{}/repos?type=sources&sort=pushed&per_page=30",
-            org
-        );
+    /// When a commit was committed, used to date tags
+    async fn get_commit_date(
+        &self,
+        owner: &str,
+        repo: &str,
+        sha: &str,
+        provenance: &mut Provenance,
+    ) -> Result<Option<DateTime<Utc>>> {
+        let url = format!("https://api.github.com/repos/{}/{}/commits/{}", owner, repo, sha);
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.get(&url, provenance).await?;
         self.check_rate_limit(&response)?;
 
-        let repos: Vec<RepoResponse> = response.json().await?;
-        Ok(repos)
+        if !response.status().is_success() {
+            return Ok(None);
+        }
+
+        let commit: TagCommitResponse = response.json().await.map_err(|e| CollectorError::Parse(e.to_string()))?;
+        Ok(commit.commit.committer.map(|c| c.date))
     }
 
-    async fn get_repo(&self, owner: &str, repo: &str) -> Result<RepoResponse> {
-        let url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+    /// Fetch releases newer than the cursor, newest first
+    ///
+    /// Without a cursor only the latest page is fetched rather than the whole history.
+    /// Already-seen releases that came back on the same pages are returned separately so
+    /// their download counts can be refreshed without extra requests.
+    ///
+    /// `latest` stands in for the first page when it reaches the cursor; otherwise the
+    /// pages are fetched over REST from the start.
+    async fn get_releases(
+        &self,
+        owner: &str,
+        repo: &str,
+        cursor: Option<&ReleaseCursor>,
+        latest: Option<Vec<ReleaseResponse>>,
+        provenance: &mut Provenance,
+    ) -> Result<FetchedReleases> {
+        if latest.is_some() {
+            provenance.request(graphql::GRAPHQL_URL);
+        }
+        let Some(cursor) = cursor else {
+            let new = match latest {
+                Some(releases) => releases,
+                None => self.get_release_page(owner, repo, INITIAL_RELEASES, 1, provenance).await?,
+            };
+            return Ok(FetchedReleases { new, seen: Vec::new() });
+        };
 
-        let response = self.client.get(&url).send().await?;
-        self.check_rate_limit(&response)?;
+        let is_newer = |release: &ReleaseResponse| {
+            release.id != cursor.release_id
+                && match (release.published_at, cursor.published_at) {
+                    (Some(published), Some(seen)) => published > seen,
+                    _ => release.id > cursor.release_id,
+                }
+        };
 
-        if !response.status().is_success() {
-            return Err(CollectorError::Api(format!(
-                "GitHub API error: {}",
-                response.status()
-            )));
+        if let Some(releases) = latest {
+            let complete = releases.len() < graphql::RELEASES;
+            let (new, seen): (Vec<_>, Vec<_>) = releases.into_iter().partition(|r| is_newer(r));
+            if complete || !seen.is_empty() {
+                return Ok(FetchedReleases { new, seen });
+            }
+            provenance.fallback("more new releases than GraphQL returned → REST pages");
         }
 
-        let repo: RepoResponse = response.json().await?;
-        Ok(repo)
-    }
+        let mut fetched = FetchedReleases::default();
+        for page in 1..=MAX_RELEASE_PAGES {
+            let releases = self.get_release_page(owner, repo, RELEASE_PAGE_SIZE, page, provenance).await?;
+            let page_len = releases.len();
+            let (new, seen): (Vec<_>, Vec<_>) = releases.into_iter().partition(|r| is_newer(r));
+            let reached_cursor = !seen.is_empty();
+            fetched.new.extend(new);
+            fetched.seen.extend(seen);
 
-    async fn count_open_prs(&self, owner: &str, repo: &str) -> Result<i64> {
-        let url = format!(
+            if reached_cursor || page_len < RELEASE_PAGE_SIZE {
+                return Ok(fetched);
+            }
+        }
+
+        warn!(
+            owner = owner,
+            repo = repo,
+            pages = MAX_RELEASE_PAGES,
+            "Stopped catching up on releases before reaching the last one seen"
+        );
+        Ok(fetched)
+    }
+
+    async fn get_release_page(
+        &self,
+        owner: &str,
+        repo: &str,
+        per_page: usize,
+        page: u32,
+        provenance: &mut Provenance,
+    ) -> Result<Vec<ReleaseResponse>> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/releases?per_page={}&page={}",
+            owner, repo, per_page, page
+        );
+
+        let response = self.get(&url, provenance).await?;
+        self.check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Ok(Vec::new());
+        }
+
+        let releases: Vec<ReleaseResponse> = response.json().await.unwrap_or_default();
+        Ok(releases)
+    }
+
+    /// Collect metrics for a single repository
+    /// Collect metrics for one repository; `core` repos also get open issues by label
+    pub async fn collect_repo(
+        &self,
+        db: &Database,
+        distro_id: i64,
+        owner: &str,
+        repo: &str,
+        core: bool,
+    ) -> Result<i64> {
+        let distro = db.get_distribution_by_id(distro_id).await?;
+        let bots = BotFilter::new(distro.bot_accounts.as_deref());
+
+        let snapshot = self.fetch_repo_snapshot(distro_id, owner, repo, &bots, core).await?;
+        let id = db.insert_github_snapshot(snapshot).await?;
+        Ok(id)
+    }
+
+    /// Fetch metrics for a single repository without storing them
+    async fn fetch_repo_snapshot(
+        &self,
+        distro_id: i64,
+        owner: &str,
+        repo: &str,
+        bots: &BotFilter,
+        core: bool,
+    ) -> Result<NewGithubSnapshot> {
+        debug!(owner = owner, repo = repo, "Collecting repo metrics");
+
+        let mut provenance = provenance();
+        let repo_info = self.get_repo(owner, repo, &mut provenance).await?;
+        let open_prs = match self.count_open_prs(owner, repo, &mut provenance).await {
+            Ok(count) => count,
+            Err(e) => {
+                provenance.fallback(format!("open PR search failed ({}) → 0", e));
+                0
+            }
+        };
+        let languages = self.get_languages(owner, repo, &mut provenance).await.unwrap_or_default();
+        let activity = match self.get_recent_activity(owner, repo, bots, &mut provenance).await {
+            Ok(activity) => activity,
+            Err(e) => {
+                provenance.fallback(format!("recent activity failed ({}) → 0", e));
+                RecentActivity::default()
+            }
+        };
+        let (issue_labels, issue_activity) = self.core_issues(owner, repo, bots, core, &mut provenance).await;
+
+        let metrics = RepoMetrics { open_prs, languages, activity, issue_labels, issue_activity };
+        Ok(repo_snapshot(distro_id, &format!("{}/{}", owner, repo), repo_info, metrics, provenance))
+    }
+
+    /// Fetch metrics for an owner's repos, given as (name, core), without storing them
+    ///
+    /// With a token the repos go [`graphql::BATCH_SIZE`] to a query; a batch whose query
+    /// fails, and every repo without a token, is fetched over REST.
+    async fn fetch_repo_snapshots(
+        &self,
+        distro_id: i64,
+        owner: &str,
+        repos: &[(&str, bool)],
+        bots: &BotFilter,
+    ) -> Vec<(String, Result<NewGithubSnapshot>)> {
+        let mut snapshots = Vec::with_capacity(repos.len());
+        for batch in repos.chunks(graphql::BATCH_SIZE) {
+            if !self.tokens.is_empty() {
+                let names: Vec<&str> = batch.iter().map(|(name, _)| *name).collect();
+                match self.graphql_repos(owner, &names).await {
+                    Ok(nodes) => {
+                        for (&(name, core), node) in batch.iter().zip(nodes) {
+                            let snapshot = match node {
+                                Some(node) => {
+                                    Ok(self.graphql_repo_snapshot(distro_id, owner, node, bots, core).await)
+                                }
+                                None => Err(CollectorError::NotFound {
+                                    service: "github",
+                                    resource: format!("repo {}/{}", owner, name),
+                                    status: 404,
+                                }),
+                            };
+                            snapshots.push((name.to_string(), snapshot));
+                        }
+                        continue;
+                    }
+                    Err(e) => {
+                        warn!(owner = owner, error = %e, "GitHub GraphQL repo batch failed, falling back to REST")
+                    }
+                }
+            }
+
+            for &(name, core) in batch {
+                let snapshot = self.fetch_repo_snapshot(distro_id, owner, name, bots, core).await;
+                snapshots.push((name.to_string(), snapshot));
+            }
+        }
+        snapshots
+    }
+
+    /// Snapshot of a repo fetched over GraphQL, adding issue counts over REST for core repos
+    async fn graphql_repo_snapshot(
+        &self,
+        distro_id: i64,
+        owner: &str,
+        node: graphql::RepoNode,
+        bots: &BotFilter,
+        core: bool,
+    ) -> NewGithubSnapshot {
+        let mut provenance = provenance();
+        provenance.request(graphql::GRAPHQL_URL);
+        let languages = node.languages.edges.iter().map(|e| (e.node.name.clone(), e.size)).collect();
+        let activity = graphql_activity(node.default_branch_ref.as_ref(), bots, &mut provenance);
+        let open_prs = node.pull_requests.total_count;
+        let repo_info = RepoResponse::from(node);
+        let (issue_labels, issue_activity) =
+            self.core_issues(owner, &repo_info.name, bots, core, &mut provenance).await;
+
+        let metrics = RepoMetrics { open_prs, languages, activity, issue_labels, issue_activity };
+        let requested = repo_info.full_name.clone();
+        repo_snapshot(distro_id, &requested, repo_info, metrics, provenance)
+    }
+
+    /// Repo nodes for a batch of an owner's repos, None for repos that weren't found
+    async fn graphql_repos(&self, owner: &str, names: &[&str]) -> Result<Vec<Option<graphql::RepoNode>>> {
+        let now = Utc::now();
+        let since = |days| (now - chrono::TimeDelta::days(days)).format("%Y-%m-%dT%H:%M:%SZ").to_string();
+        let variables = serde_json::json!({ "month": since(30), "year": since(365) });
+        let query = graphql::repos_query(owner, names);
+        // Each repo's snapshot records the request itself
+        let data = self.graphql(&query, variables, &mut provenance()).await?;
+        Ok(graphql::by_alias(data, names.len()))
+    }
+
+    /// Latest releases for a batch of an owner's repos, None for repos that weren't found
+    async fn graphql_releases(&self, owner: &str, names: &[&str]) -> Result<Vec<Option<Vec<ReleaseResponse>>>> {
+        let query = graphql::releases_query(owner, names);
+        let data = self.graphql(&query, serde_json::json!({}), &mut provenance()).await?;
+        Ok(graphql::by_alias::<graphql::ReleasesNode>(data, names.len())
+            .into_iter()
+            .map(|node| node.map(|n| n.releases.nodes.into_iter().map(ReleaseResponse::from).collect()))
+            .collect())
+    }
+
+    /// Open issues by label and issue activity for a core repo, nothing for others
+    async fn core_issues(
+        &self,
+        owner: &str,
+        repo: &str,
+        bots: &BotFilter,
+        core: bool,
+        provenance: &mut Provenance,
+    ) -> (Vec<(IssueCategory, i64)>, Option<IssueActivity>) {
+        if !core {
+            return (Vec::new(), None);
+        }
+        let issue_labels = match self.count_issues_by_label(owner, repo, provenance).await {
+            Ok(counts) => counts,
+            Err(e) => {
+                provenance.fallback(format!("issue label counts failed ({}) → none", e));
+                Vec::new()
+            }
+        };
+        let issue_activity = match self.get_issue_activity(owner, repo, bots, provenance).await {
+            Ok(activity) => Some(activity),
+            Err(e) => {
+                provenance.fallback(format!("issue activity failed ({}) → none", e));
+                None
+            }
+        };
+        (issue_labels, issue_activity)
+    }
+
+    /// List an org's repos under its canonical name, following recorded and detected renames
+    async fn list_org_repos(
+        &self,
+        db: &Database,
+        org: &str,
+    ) -> Result<(String, Vec<RepoResponse>)> {
+        let canonical = db.canonical_org(org).await?;
+        if canonical != org {
+            debug!(org = org, canonical = canonical, "Using renamed org");
+        }
+
+        let repos = self.get_org_repos(&canonical).await?;
+
+        // GitHub follows a 301 for a renamed org; the repos then report their new owner
+        let owners: Vec<&str> = repos
+            .iter()
+            .filter_map(|r| r.full_name.split_once('/').map(|(owner, _)| owner))
+            .collect();
+
+        match owners.first() {
+            Some(first) if !first.eq_ignore_ascii_case(&canonical) && owners.iter().all(|o| o == first) => {
+                warn!(
+                    org = canonical,
+                    renamed_to = first,
+                    "GitHub org was renamed; run `dv rename-org` to stitch history"
+                );
+                db.record_org_alias(&canonical, first).await?;
+                let renamed = first.to_string();
+                Ok((renamed, repos))
+            }
+            _ => Ok((canonical, repos)),
+        }
+    }
+
+    async fn get_org_repos(&self, org: &str) -> Result<Vec<RepoResponse>> {
+        let url = format!(
+            "https://api.github.com/orgs/{}/repos?type=sources&sort=pushed&per_page=30",
+            org
+        );
+
+        let response = self.send(&url).await?;
+        self.check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("github", response.status(), format!("org {}", org)));
+        }
+
+        let repos: Vec<RepoResponse> = response.json().await?;
+        Ok(repos)
+    }
+
+    async fn get_repo(&self, owner: &str, repo: &str, provenance: &mut Provenance) -> Result<RepoResponse> {
+        let url = format!("https://api.github.com/repos/{}/{}", owner, repo);
+
+        let response = self.get(&url, provenance).await?;
+        self.check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status(
+                "github",
+                response.status(),
+                format!("repo {}/{}", owner, repo),
+            ));
+        }
+
+        let repo: RepoResponse = response.json().await?;
+        Ok(repo)
+    }
+
+    /// Bytes of code per language
+    async fn get_languages(
+        &self,
+        owner: &str,
+        repo: &str,
+        provenance: &mut Provenance,
+    ) -> Result<Vec<(String, i64)>> {
+        let url = format!("https://api.github.com/repos/{}/{}/languages", owner, repo);
+
+        let response = self.get(&url, provenance).await?;
+        self.check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Ok(Vec::new());
+        }
+
+        let languages: std::collections::HashMap<String, i64> = response.json().await?;
+        Ok(languages.into_iter().collect())
+    }
+
+    async fn count_open_prs(&self, owner: &str, repo: &str, provenance: &mut Provenance) -> Result<i64> {
+        let url = format!(
             "https://api.github.com/repos/{}/{}/pulls?state=open&per_page=1",
             owner, repo
         );
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.get(&url, provenance).await?;
         self.check_rate_limit(&response)?;
 
         // GitHub returns the total count in the Link header for pagination
@@ -230,7 +1307,7 @@ impl GithubCollector {
             owner, repo
         );
 
-        let search_response = self.client.get(&search_url).send().await?;
+        let search_response = self.get(&search_url, provenance).await?;
         self.check_rate_limit(&search_response)?;
 
         #[derive(Deserialize)]
@@ -242,7 +1319,315 @@ impl GithubCollector {
         Ok(result.total_count)
     }
 
-    async fn get_recent_activity(&self, owner: &str, repo: &str) -> Result<(i64, i64, i64)> {
+    /// Governance docs in a repo, from its community profile and the files in [`DOC_DIRS`];
+    /// None if the repo doesn't exist
+    async fn repo_docs(&self, owner: &str, repo: &str, provenance: &mut Provenance) -> Result<Option<DocsFound>> {
+        let mut found = DocsFound::default();
+        for (i, dir) in DOC_DIRS.iter().enumerate() {
+            match self.list_dir(owner, repo, dir, provenance).await? {
+                Some(names) => found.add_files(names.iter().map(String::as_str)),
+                // The root listing 404s only when there's no repo
+                None if i == 0 => return Ok(None),
+                None => {}
+            }
+        }
+
+        #[derive(Deserialize)]
+        struct ProfileFiles {
+            contributing: Option<serde_json::Value>,
+            code_of_conduct: Option<serde_json::Value>,
+            code_of_conduct_file: Option<serde_json::Value>,
+        }
+
+        #[derive(Deserialize)]
+        struct Profile {
+            files: ProfileFiles,
+        }
+
+        // Also reports the org's defaults and files in places not listed above
+        let url = format!("https://api.github.com/repos/{}/{}/community/profile", owner, repo);
+        let response = self.get(&url, provenance).await?;
+        self.check_rate_limit(&response)?;
+
+        if response.status().is_success() {
+            let profile: Profile = response.json().await?;
+            found.contributing |= profile.files.contributing.is_some();
+            found.code_of_conduct |=
+                profile.files.code_of_conduct.is_some() || profile.files.code_of_conduct_file.is_some();
+        } else {
+            provenance.fallback(format!(
+                "community profile of {}/{} unavailable ({}) → file listing only",
+                owner,
+                repo,
+                response.status()
+            ));
+        }
+
+        Ok(Some(found))
+    }
+
+    /// Names of the files in a repo directory ("" for the root); None if it doesn't exist
+    async fn list_dir(
+        &self,
+        owner: &str,
+        repo: &str,
+        dir: &str,
+        provenance: &mut Provenance,
+    ) -> Result<Option<Vec<String>>> {
+        let url = format!("https://api.github.com/repos/{}/{}/contents/{}", owner, repo, dir);
+
+        let response = self.get(&url, provenance).await?;
+        self.check_rate_limit(&response)?;
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status(
+                "github",
+                response.status(),
+                format!("contents of {}/{}/{}", owner, repo, dir),
+            ));
+        }
+
+        #[derive(Deserialize)]
+        struct Entry {
+            name: String,
+            #[serde(rename = "type")]
+            kind: String,
+        }
+
+        // A path naming a file answers with the file rather than a listing
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Contents {
+            Dir(Vec<Entry>),
+            File(#[allow(dead_code)] serde_json::Value),
+        }
+
+        match response.json::<Contents>().await? {
+            Contents::Dir(entries) => {
+                Ok(Some(entries.into_iter().filter(|e| e.kind == "file").map(|e| e.name).collect()))
+            }
+            Contents::File(_) => Ok(None),
+        }
+    }
+
+    /// Open issues per label category, from the repo's labels and a search per category
+    async fn count_issues_by_label(
+        &self,
+        owner: &str,
+        repo: &str,
+        provenance: &mut Provenance,
+    ) -> Result<Vec<(IssueCategory, i64)>> {
+        let url = format!("https://api.github.com/repos/{}/{}/labels?per_page=100", owner, repo);
+
+        let response = self.get(&url, provenance).await?;
+        self.check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status(
+                "github",
+                response.status(),
+                format!("labels of {}/{}", owner, repo),
+            ));
+        }
+
+        #[derive(Deserialize)]
+        struct Label {
+            name: String,
+        }
+
+        let repo_labels: Vec<Label> = response.json().await?;
+        let mut counts = Vec::new();
+        for (category, names) in labels::group(repo_labels.iter().map(|l| l.name.as_str())) {
+            // Comma-separated label values match issues with any of them
+            let quoted: Vec<String> = names.iter().map(|name| format!("\"{}\"", name)).collect();
+            let query = format!("repo:{}/{} type:issue state:open label:{}", owner, repo, quoted.join(","));
+            counts.push((category, self.search_count(&query, provenance).await?));
+        }
+
+        Ok(counts)
+    }
+
+    /// Issues opened and closed over the last 30 days, and how many of the closures were a
+    /// stale bot's
+    ///
+    /// Stale closures come from the issue events feed, read up to [`MAX_EVENT_PAGES`] back,
+    /// so on very busy repos they're a lower bound.
+    async fn get_issue_activity(
+        &self,
+        owner: &str,
+        repo: &str,
+        bots: &BotFilter,
+        provenance: &mut Provenance,
+    ) -> Result<IssueActivity> {
+        let since = Utc::now() - chrono::TimeDelta::days(ISSUE_ACTIVITY_DAYS);
+        let date = since.format("%Y-%m-%d");
+        let opened_30d = self
+            .search_count(&format!("repo:{}/{} type:issue created:>={}", owner, repo, date), provenance)
+            .await?;
+        let closed_30d = self
+            .search_count(&format!("repo:{}/{} type:issue closed:>={}", owner, repo, date), provenance)
+            .await?;
+
+        #[derive(Deserialize)]
+        struct Actor {
+            login: String,
+        }
+
+        #[derive(Deserialize)]
+        struct EventLabel {
+            name: String,
+        }
+
+        #[derive(Deserialize)]
+        struct EventIssue {
+            #[serde(default)]
+            labels: Vec<EventLabel>,
+            pull_request: Option<serde_json::Value>,
+        }
+
+        #[derive(Deserialize)]
+        struct IssueEvent {
+            event: String,
+            actor: Option<Actor>,
+            issue: Option<EventIssue>,
+            created_at: DateTime<Utc>,
+        }
+
+        let mut stale_closed_30d = 0;
+        'pages: for page in 1..=MAX_EVENT_PAGES {
+            let url = format!(
+                "https://api.github.com/repos/{}/{}/issues/events?per_page=100&page={}",
+                owner, repo, page
+            );
+            let response = self.get(&url, provenance).await?;
+            self.check_rate_limit(&response)?;
+
+            if !response.status().is_success() {
+                return Err(CollectorError::from_status(
+                    "github",
+                    response.status(),
+                    format!("issue events of {}/{}", owner, repo),
+                ));
+            }
+
+            let events: Vec<IssueEvent> = response.json().await?;
+            let last_page = events.len() < 100;
+            for event in events {
+                if event.created_at < since {
+                    break 'pages;
+                }
+                let (Some(actor), Some(issue)) = (event.actor, event.issue) else {
+                    continue;
+                };
+                if event.event == "closed"
+                    && issue.pull_request.is_none()
+                    && bots.is_stale_closure(&actor.login, issue.labels.iter().map(|l| l.name.as_str()))
+                {
+                    stale_closed_30d += 1;
+                }
+            }
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(IssueActivity {
+            opened_30d,
+            closed_30d,
+            stale_closed_30d: stale_closed_30d.min(closed_30d),
+        })
+    }
+
+    /// Logins whose first contribution to the repo was a pull request merged in the last 30 days
+    ///
+    /// GitHub marks the author of such a pull request FIRST_TIME_CONTRIBUTOR (or FIRST_TIMER
+    /// for a first contribution anywhere on GitHub).
+    async fn first_time_contributors(&self, owner: &str, repo: &str) -> Result<HashSet<String>> {
+        let since = Utc::now() - chrono::TimeDelta::days(ISSUE_ACTIVITY_DAYS);
+
+        #[derive(Deserialize)]
+        struct User {
+            login: String,
+        }
+
+        #[derive(Deserialize)]
+        struct Pull {
+            user: Option<User>,
+            author_association: String,
+            merged_at: Option<DateTime<Utc>>,
+            updated_at: DateTime<Utc>,
+        }
+
+        let mut logins = HashSet::new();
+        'pages: for page in 1..=MAX_PULL_PAGES {
+            let url = format!(
+                "https://api.github.com/repos/{}/{}/pulls?state=closed&sort=updated&direction=desc&per_page=100&page={}",
+                owner, repo, page
+            );
+            let response = self.send(&url).await?;
+            self.check_rate_limit(&response)?;
+
+            if !response.status().is_success() {
+                return Err(CollectorError::from_status(
+                    "github",
+                    response.status(),
+                    format!("pull requests of {}/{}", owner, repo),
+                ));
+            }
+
+            let pulls: Vec<Pull> = response.json().await?;
+            let last_page = pulls.len() < 100;
+            for pull in pulls {
+                // Sorted by last update, which is never before the merge
+                if pull.updated_at < since {
+                    break 'pages;
+                }
+                let first_time = matches!(pull.author_association.as_str(), "FIRST_TIME_CONTRIBUTOR" | "FIRST_TIMER");
+                if let (true, Some(merged_at), Some(user)) = (first_time, pull.merged_at, pull.user) {
+                    if merged_at >= since {
+                        logins.insert(user.login);
+                    }
+                }
+            }
+            if last_page {
+                break;
+            }
+        }
+
+        Ok(logins)
+    }
+
+    /// Total results of an issue search
+    async fn search_count(&self, query: &str, provenance: &mut Provenance) -> Result<i64> {
+        let url = reqwest::Url::parse_with_params("https://api.github.com/search/issues", [("q", query)])
+            .map_err(|e| CollectorError::Api(e.to_string()))?;
+
+        let response = self.get(url.as_str(), provenance).await?;
+        self.check_rate_limit(&response)?;
+
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("github", response.status(), format!("search {}", query)));
+        }
+
+        #[derive(Deserialize)]
+        struct SearchResult {
+            total_count: i64,
+        }
+
+        let result: SearchResult = response.json().await?;
+        Ok(result.total_count)
+    }
+
+    async fn get_recent_activity(
+        &self,
+        owner: &str,
+        repo: &str,
+        bots: &BotFilter,
+        provenance: &mut Provenance,
+    ) -> Result<RecentActivity> {
         // Try stats API first, fall back to commits API if it's not ready
         let stats_url = format!(
             "https://api.github.com/repos/{}/{}/stats/commit_activity",
@@ -256,79 +1641,276 @@ impl GithubCollector {
             week: i64,
         }
 
-        let mut commits_30d_count: i64 = 0;
-        let mut commits_365d_count: i64 = 0;
+        let mut activity = RecentActivity::default();
 
         // Try stats API (returns 202 if computing - need to use fallback)
-        let stats_response = self.client.get(&stats_url).send().await?;
-        if stats_response.status() == reqwest::StatusCode::OK {
+        let stats_response = self.get(&stats_url, provenance).await?;
+        let stats_status = stats_response.status();
+        if stats_status == reqwest::StatusCode::OK {
             let weekly_stats: Vec<WeeklyCommits> = stats_response.json().await.unwrap_or_default();
             if !weekly_stats.is_empty() {
-                commits_365d_count = weekly_stats.iter().map(|w| w.total).sum();
-                commits_30d_count = weekly_stats.iter().rev().take(4).map(|w| w.total).sum();
-            }
-        }
-
-        // If stats API didn't return data, fall back to commits API
-        if commits_365d_count == 0 {
-            // Get 30-day commits
-            let since_30d = (Utc::now() - chrono::TimeDelta::days(30))
-                .format("%Y-%m-%dT%H:%M:%SZ")
-                .to_string();
-            let url_30d = format!(
-                "https://api.github.com/repos/{}/{}/commits?since={}&per_page=100",
-                owner, repo, since_30d
-            );
-            let response_30d = self.client.get(&url_30d).send().await?;
-            if response_30d.status().is_success() {
-                let commits: Vec<CommitResponse> = response_30d.json().await.unwrap_or_default();
-                commits_30d_count = commits.len() as i64;
-            }
-
-            // Get 365-day commits (limited to 100, but better than 0)
-            let since_365d = (Utc::now() - chrono::TimeDelta::days(365))
-                .format("%Y-%m-%dT%H:%M:%SZ")
-                .to_string();
-            let url_365d = format!(
-                "https://api.github.com/repos/{}/{}/commits?since={}&per_page=100",
-                owner, repo, since_365d
-            );
-            let response_365d = self.client.get(&url_365d).send().await?;
-            if response_365d.status().is_success() {
-                let commits: Vec<CommitResponse> = response_365d.json().await.unwrap_or_default();
-                commits_365d_count = commits.len() as i64;
+                activity.commits_365d = weekly_stats.iter().map(|w| w.total).sum();
+                activity.commits_30d = weekly_stats.iter().rev().take(4).map(|w| w.total).sum();
             }
         }
 
-        // Get unique contributors
+        // Per-author weekly stats, used for contributor counts and the bot split
         let contributors_url = format!(
             "https://api.github.com/repos/{}/{}/stats/contributors",
             owner, repo
         );
-        let contrib_response = self.client.get(&contributors_url).send().await?;
-        let contributors: Vec<serde_json::Value> = contrib_response.json().await.unwrap_or_default();
-        let contributors_count = contributors.len() as i64;
+        let contrib_response = self.get(&contributors_url, provenance).await?;
+        if contrib_response.status() != reqwest::StatusCode::OK {
+            provenance.fallback(format!(
+                "contributor stats {} → no contributor counts",
+                contrib_response.status().as_u16()
+            ));
+        }
+        let contributors: Vec<ContributorStats> = contrib_response.json().await.unwrap_or_default();
+
+        for contributor in &contributors {
+            let login = contributor.author.as_ref().map(|a| a.login.as_str()).unwrap_or("");
+            if bots.is_bot(login) {
+                activity.bot_commits_365d += contributor.weeks.iter().rev().take(52).map(|w| w.c).sum::<i64>();
+                activity.bot_commits_30d += contributor.weeks.iter().rev().take(4).map(|w| w.c).sum::<i64>();
+            } else {
+                activity.contributors += 1;
+                if let Some(author) = &contributor.author {
+                    let commits = contributor.weeks.iter().map(|w| w.c).sum();
+                    activity.logins.push((author.login.clone(), commits));
+                }
+            }
+        }
+
+        if activity.commits_365d > 0 {
+            // Weekly totals include bots; the per-author stats tell us how many to remove
+            activity.commits_365d = (activity.commits_365d - activity.bot_commits_365d).max(0);
+            activity.commits_30d = (activity.commits_30d - activity.bot_commits_30d).max(0);
+        } else {
+            // If stats API didn't return data, fall back to commits API
+            provenance.fallback(if stats_status == reqwest::StatusCode::OK {
+                "stats API empty → commits fallback".to_string()
+            } else {
+                format!("stats API {} → commits fallback", stats_status.as_u16())
+            });
+            let (human, bot) = self.count_commits_since(owner, repo, 30, bots, provenance).await?;
+            activity.commits_30d = human;
+            activity.bot_commits_30d = bot;
+
+            // 365-day commits are limited to 100, but better than 0
+            let (human, bot) = self.count_commits_since(owner, repo, 365, bots, provenance).await?;
+            activity.commits_365d = human;
+            activity.bot_commits_365d = bot;
+        }
 
-        Ok((commits_30d_count, commits_365d_count, contributors_count))
+        Ok(activity)
     }
 
-    fn check_rate_limit(&self, response: &reqwest::Response) -> Result<()> {
-        if response.status() == reqwest::StatusCode::FORBIDDEN {
-            if let Some(remaining) = response.headers().get("x-ratelimit-remaining") {
-                if remaining == "0" {
-                    let reset = response
-                        .headers()
-                        .get("x-ratelimit-reset")
-                        .and_then(|v| v.to_str().ok())
-                        .and_then(|v| v.parse::<u64>().ok())
-                        .unwrap_or(60);
-
-                    let now = Utc::now().timestamp() as u64;
-                    let wait = reset.saturating_sub(now);
-
-                    return Err(CollectorError::RateLimited(wait));
+    /// Members who made their membership of the org public, from one single-item page
+    async fn count_public_members(&self, org: &str, provenance: &mut Provenance) -> Result<i64> {
+        let url = format!("https://api.github.com/orgs/{}/public_members?per_page=1", org);
+        let response = self.get(&url, provenance).await?;
+        self.check_rate_limit(&response)?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("github", response.status(), format!("members of {}", org)));
+        }
+
+        if let Some(pages) = last_page(response.headers()) {
+            return Ok(pages);
+        }
+        let members: Vec<AccountRef> = response.json().await?;
+        Ok(members.len() as i64)
+    }
+
+    /// GitHub Sponsors sponsoring an account, 0 without a Sponsors profile and None when
+    /// the account is gone
+    ///
+    /// Sponsors are only in the GraphQL API, which always needs a token.
+    async fn count_sponsors(&self, login: &str, provenance: &mut Provenance) -> Result<Option<i64>> {
+        let variables = serde_json::json!({ "login": login });
+        let data: graphql::SponsorsData = self.graphql(graphql::SPONSORS_QUERY, variables, provenance).await?;
+        let owner = data.repository_owner;
+        Ok(owner.map(|owner| if owner.has_sponsors_listing { owner.sponsors.total_count } else { 0 }))
+    }
+
+    /// Count (human, bot) commits in the last N days from the commits API (first page only)
+    async fn count_commits_since(
+        &self,
+        owner: &str,
+        repo: &str,
+        days: i64,
+        bots: &BotFilter,
+        provenance: &mut Provenance,
+    ) -> Result<(i64, i64)> {
+        let since = (Utc::now() - chrono::TimeDelta::days(days))
+            .format("%Y-%m-%dT%H:%M:%SZ")
+            .to_string();
+        let url = format!(
+            "https://api.github.com/repos/{}/{}/commits?since={}&per_page=100",
+            owner, repo, since
+        );
+
+        let response = self.get(&url, provenance).await?;
+        if !response.status().is_success() {
+            return Ok((0, 0));
+        }
+
+        let commits: Vec<CommitResponse> = response.json().await.unwrap_or_default();
+        let bot = commits
+            .iter()
+            .filter(|c| c.author.as_ref().map(|a| bots.is_bot(&a.login)).unwrap_or(false))
+            .count() as i64;
+
+        Ok((commits.len() as i64 - bot, bot))
+    }
+
+    /// GET `url`, recording the request in the snapshot's provenance
+    async fn get(&self, url: &str, provenance: &mut Provenance) -> Result<reqwest::Response> {
+        provenance.request(url);
+        self.send(url).await
+    }
+
+    /// Run a GraphQL query under the pooled token with the most quota left
+    ///
+    /// Unlike REST requests these aren't retried: a failed batch falls back to REST.
+    async fn graphql<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+        provenance: &mut Provenance,
+    ) -> Result<T> {
+        self.tokens.check(&self.client).await;
+        let token = self
+            .tokens
+            .pick()?
+            .ok_or_else(|| CollectorError::Api("GitHub GraphQL needs a token".to_string()))?;
+
+        provenance.request(graphql::GRAPHQL_URL);
+        let body = serde_json::json!({ "query": query, "variables": variables });
+        let request = self.client.post(graphql::GRAPHQL_URL).bearer_auth(&token).json(&body);
+        let response = breaker::GITHUB.send(request).await?;
+        self.tokens.observe(&token, response.headers());
+        self.check_rate_limit(&response)?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("github", response.status(), "GraphQL API"));
+        }
+
+        let response: graphql::Response<T> = response
+            .json()
+            .await
+            .map_err(|e| CollectorError::Parse(format!("GitHub GraphQL response ({})", e)))?;
+        response.into_data()
+    }
+
+    /// GET `url` under the pooled token with the most quota left
+    ///
+    /// A token that turns out to be out of quota is retried with the next one, a
+    /// secondary rate limit is waited out before retrying, and network errors and
+    /// server errors are retried a couple of times with backoff.
+    async fn send(&self, url: &str) -> Result<reqwest::Response> {
+        self.tokens.check(&self.client).await;
+
+        let mut rotations = self.tokens.len();
+        let mut backoffs = 0;
+        let mut retries = 0;
+        loop {
+            let token = self.tokens.pick()?;
+            let mut request = self.client.get(url);
+            if let Some(ref token) = token {
+                request = request.bearer_auth(token);
+            }
+            let response = match breaker::GITHUB.send(request).await {
+                Ok(response) => response,
+                Err(e @ CollectorError::Http(_)) if e.retryable() && retries < MAX_TRANSIENT_RETRIES => {
+                    retries += 1;
+                    warn!(url = url, error = %e, "GitHub request failed, retrying");
+                    tokio::time::sleep(TRANSIENT_RETRY_WAIT * 2u32.pow(retries - 1)).await;
+                    continue;
                 }
+                Err(e) => return Err(e),
+            };
+            if let Some(ref token) = token {
+                self.tokens.observe(token, response.headers());
             }
+
+            if response.status().is_server_error() && retries < MAX_TRANSIENT_RETRIES {
+                retries += 1;
+                warn!(url = url, status = response.status().as_u16(), "GitHub server error, retrying");
+                tokio::time::sleep(TRANSIENT_RETRY_WAIT * 2u32.pow(retries - 1)).await;
+                continue;
+            }
+
+            if Self::out_of_quota(&response) && rotations > 1 {
+                rotations -= 1;
+                debug!("GitHub token out of quota, rotating to the next");
+                continue;
+            }
+
+            let (response, wait) = Self::secondary_limit(response).await?;
+            let Some(wait) = wait else {
+                return Ok(response);
+            };
+            if backoffs == MAX_SECONDARY_RETRIES {
+                return Err(CollectorError::RateLimited(wait.as_secs()));
+            }
+            backoffs += 1;
+            warn!(url = url, wait_secs = wait.as_secs(), "Hit GitHub secondary rate limit, backing off");
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Primary rate limit: the token's hourly quota is used up
+    fn out_of_quota(response: &reqwest::Response) -> bool {
+        matches!(response.status(), reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::TOO_MANY_REQUESTS)
+            && response.headers().get("x-ratelimit-remaining").is_some_and(|r| r == "0")
+    }
+
+    /// Detect a secondary (abuse detection) rate limit and how long to wait it out
+    ///
+    /// These come as a 403 or 429 that leaves quota remaining, with either a Retry-After
+    /// header or only a message saying so. Reading the message consumes the body, so
+    /// the response is handed back rebuilt.
+    async fn secondary_limit(response: reqwest::Response) -> Result<(reqwest::Response, Option<Duration>)> {
+        let status = response.status();
+        if !matches!(status, reqwest::StatusCode::FORBIDDEN | reqwest::StatusCode::TOO_MANY_REQUESTS)
+            || Self::out_of_quota(&response)
+        {
+            return Ok((response, None));
+        }
+
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok());
+        if let Some(secs) = retry_after {
+            return Ok((response, Some(Duration::from_secs(secs))));
+        }
+
+        let headers = response.headers().clone();
+        let body = response.bytes().await?;
+        let message = String::from_utf8_lossy(&body).to_ascii_lowercase();
+        let limited = message.contains("secondary rate limit") || message.contains("abuse detection");
+
+        let mut rebuilt = http::Response::new(body);
+        *rebuilt.status_mut() = status;
+        *rebuilt.headers_mut() = headers;
+        Ok((reqwest::Response::from(rebuilt), limited.then_some(SECONDARY_LIMIT_WAIT)))
+    }
+
+    fn check_rate_limit(&self, response: &reqwest::Response) -> Result<()> {
+        if Self::out_of_quota(response) {
+            let reset = response
+                .headers()
+                .get("x-ratelimit-reset")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(60);
+
+            let now = Utc::now().timestamp() as u64;
+            let wait = reset.saturating_sub(now);
+
+            return Err(CollectorError::RateLimited(wait));
         }
         Ok(())
     }
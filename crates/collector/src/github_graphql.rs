@@ -0,0 +1,306 @@
+//! GitHub GraphQL queries batching what the REST flow fetches repo by repo
+//!
+//! Over REST a repo costs five or more requests (the repo, its open pull requests, its
+//! languages, weekly and per-author commit stats) and its releases one more, so a 30-repo
+//! org takes around 200. The queries here fetch the same for [`BATCH_SIZE`] repos at once,
+//! each under an alias (`r0`, `r1`, ...). GraphQL always needs a token, so without one the
+//! collector stays on REST.
+//!
+//! GraphQL has no per-author commit stats. Instead the last 30 days' commits (up to
+//! [`RECENT_COMMITS`]) are read to count contributors and bots, and the year's commits
+//! come from the default branch's history count.
+
+use crate::{CollectorError, Result};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+pub const GRAPHQL_URL: &str = "https://api.github.com/graphql";
+
+/// Repos per query; GitHub rejects queries that could return more than 500,000 nodes
+pub const BATCH_SIZE: usize = 10;
+
+/// Commits from the last 30 days read per repo for authors (`first` in the repo fragment)
+pub const RECENT_COMMITS: usize = 100;
+
+/// Latest releases read per repo, as many as a first REST pass takes (`first` in the
+/// releases fragment)
+pub const RELEASES: usize = 30;
+
+const REPO_FRAGMENT: &str = "fragment repo on Repository {
+  nameWithOwner stargazerCount forkCount isFork isMirror mirrorUrl description homepageUrl hasIssuesEnabled
+  pushedAt
+  repositoryTopics(first: 20) { nodes { topic { name } } }
+  issues(states: OPEN) { totalCount }
+  pullRequests(states: OPEN) { totalCount }
+  languages(first: 20) { edges { size node { name } } }
+  defaultBranchRef { target { ... on Commit {
+    month: history(since: $month, first: 100) { totalCount nodes { author { name user { login } } } }
+    year: history(since: $year) { totalCount }
+  } } }
+}";
+
+const RELEASES_FRAGMENT: &str = "fragment releases on Repository {
+  releases(first: 30, orderBy: {field: CREATED_AT, direction: DESC}) { nodes {
+    databaseId tagName name publishedAt isPrerelease
+    releaseAssets(first: 50) { nodes { name downloadCount } }
+  } }
+}";
+
+#[derive(Debug, Deserialize)]
+pub struct Response<T> {
+    pub data: Option<T>,
+    #[serde(default)]
+    pub errors: Vec<Error>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Error {
+    pub message: String,
+}
+
+impl<T> Response<T> {
+    /// The data, which can be partial: a repo that isn't found comes back as null with
+    /// an error alongside the others
+    pub fn into_data(self) -> Result<T> {
+        match (self.data, self.errors.first()) {
+            (Some(data), _) => Ok(data),
+            (None, Some(error)) => Err(CollectorError::Api(format!("GitHub GraphQL: {}", error.message))),
+            (None, None) => Err(CollectorError::Parse("GitHub GraphQL response without data".to_string())),
+        }
+    }
+}
+
+/// Query for the repos in `names`, to run with `$month` and `$year` set to the start of
+/// the last 30 days and year
+pub fn repos_query(owner: &str, names: &[&str]) -> String {
+    aliased("query($month: GitTimestamp!, $year: GitTimestamp!)", owner, names, "repo", REPO_FRAGMENT)
+}
+
+/// Query for the latest releases of the repos in `names`
+pub fn releases_query(owner: &str, names: &[&str]) -> String {
+    aliased("query", owner, names, "releases", RELEASES_FRAGMENT)
+}
+
+fn aliased(operation: &str, owner: &str, names: &[&str], fragment: &str, definition: &str) -> String {
+    let mut query = format!("{} {{\n", operation);
+    for (i, name) in names.iter().enumerate() {
+        // JSON string literals are valid GraphQL ones
+        query.push_str(&format!(
+            "  r{}: repository(owner: {}, name: {}) {{ ...{} }}\n",
+            i,
+            serde_json::Value::from(owner),
+            serde_json::Value::from(*name),
+            fragment
+        ));
+    }
+    query.push_str("}\n");
+    query.push_str(definition);
+    query
+}
+
+/// Each repo of a batch by position, None for repos that weren't found
+pub fn by_alias<T>(mut data: HashMap<String, Option<T>>, count: usize) -> Vec<Option<T>> {
+    (0..count).map(|i| data.remove(&format!("r{}", i)).flatten()).collect()
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RepoNode {
+    pub name_with_owner: String,
+    pub stargazer_count: i64,
+    pub fork_count: i64,
+    pub is_fork: bool,
+    pub is_mirror: bool,
+    pub mirror_url: Option<String>,
+    pub description: Option<String>,
+    pub homepage_url: Option<String>,
+    pub has_issues_enabled: bool,
+    pub pushed_at: Option<DateTime<Utc>>,
+    pub repository_topics: Nodes<TopicNode>,
+    pub issues: TotalCount,
+    pub pull_requests: TotalCount,
+    pub languages: LanguageConnection,
+    pub default_branch_ref: Option<BranchRef>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Nodes<T> {
+    pub nodes: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TotalCount {
+    pub total_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TopicNode {
+    pub topic: Named,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Named {
+    pub name: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LanguageConnection {
+    pub edges: Vec<LanguageEdge>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LanguageEdge {
+    /// Bytes of code, as REST reports them
+    pub size: i64,
+    pub node: Named,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BranchRef {
+    /// None for the odd default branch pointing at something other than a commit
+    pub target: Option<CommitTarget>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommitTarget {
+    pub month: History,
+    pub year: TotalCount,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct History {
+    pub total_count: i64,
+    pub nodes: Vec<HistoryCommit>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryCommit {
+    pub author: Option<GitActor>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GitActor {
+    pub name: Option<String>,
+    /// GitHub account the commit's email belongs to
+    pub user: Option<User>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct User {
+    pub login: String,
+}
+
+impl HistoryCommit {
+    /// Login of the author's account, or failing that the name they committed under
+    pub fn author(&self) -> Option<&str> {
+        let author = self.author.as_ref()?;
+        author.user.as_ref().map(|u| u.login.as_str()).or(author.name.as_deref())
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReleasesNode {
+    pub releases: Nodes<ReleaseNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseNode {
+    /// The release's REST id
+    pub database_id: i64,
+    pub tag_name: String,
+    pub name: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub is_prerelease: bool,
+    pub release_assets: Nodes<AssetNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetNode {
+    pub name: String,
+    pub download_count: i64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsorsData {
+    pub repository_owner: Option<SponsorsOwner>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SponsorsOwner {
+    pub has_sponsors_listing: bool,
+    pub sponsors: TotalCount,
+}
+
+/// Query for an account's sponsor count, to run with `$login` set
+pub const SPONSORS_QUERY: &str = "query($login: String!) { repositoryOwner(login: $login) {
+  ... on Sponsorable { hasSponsorsListing sponsors { totalCount } }
+} }";
+
+/// Query for a page of a repo's discussions, newest first, to run with `$owner`, `$name`
+/// and `$cursor` (null for the first page) set
+pub const DISCUSSIONS_QUERY: &str = "query($owner: String!, $name: String!, $cursor: String) {
+  repository(owner: $owner, name: $name) {
+    hasDiscussionsEnabled
+    discussions(first: 100, after: $cursor, orderBy: {field: CREATED_AT, direction: DESC}) {
+      pageInfo { hasNextPage endCursor }
+      nodes { createdAt author { login } category { isAnswerable } answer { createdAt author { login } } }
+    }
+  }
+}";
+
+#[derive(Debug, Deserialize)]
+pub struct DiscussionsData {
+    pub repository: Option<DiscussionsRepo>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscussionsRepo {
+    pub has_discussions_enabled: bool,
+    pub discussions: DiscussionConnection,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscussionConnection {
+    pub page_info: PageInfo,
+    pub nodes: Vec<DiscussionNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscussionNode {
+    pub created_at: DateTime<Utc>,
+    /// None for deleted accounts
+    pub author: Option<User>,
+    pub category: DiscussionCategory,
+    /// The comment accepted as the answer, in answerable (Q&A) categories
+    pub answer: Option<AnswerNode>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscussionCategory {
+    pub is_answerable: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnswerNode {
+    pub created_at: DateTime<Utc>,
+    pub author: Option<User>,
+}
@@ -0,0 +1,239 @@
+//! GitLab collector for distros on gitlab.com or their own GitLab
+//!
+//! Projects are stored as GitHub-style snapshots under their full path, like Forgejo repos.
+//! GitLab reports languages as percentages rather than bytes, so they can't be added to
+//! other repos' byte counts and aren't collected; commits carry no account, so authors
+//! are told apart by name.
+
+use crate::bots::BotFilter;
+use crate::forge::{since_param, Forge, RecentActivity};
+use crate::{provenance, CollectorConfig, CollectorError, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use distrovitals_database::{Database, NewGithubSnapshot, Provenance};
+use reqwest::Client;
+use serde::Deserialize;
+use tracing::{debug, info, warn};
+
+/// Most recently active projects collected per group, as for GitHub orgs
+const MAX_PROJECTS: usize = 30;
+
+/// GitLab's maximum page size
+const PAGE_SIZE: usize = 100;
+
+/// Commit pages read per project; GitLab doesn't total commits, so a busier year is cut short
+const MAX_COMMIT_PAGES: u32 = 10;
+
+/// GitLab API client
+pub struct GitlabCollector {
+    client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectResponse {
+    id: i64,
+    path_with_namespace: String,
+    star_count: i64,
+    forks_count: i64,
+    /// Absent when the project has issues turned off
+    open_issues_count: Option<i64>,
+    /// Set for forks of projects elsewhere
+    forked_from_project: Option<serde_json::Value>,
+    /// Pull mirrors; only shown to project maintainers
+    #[serde(default)]
+    mirror: bool,
+    last_activity_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    topics: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitResponse {
+    author_name: String,
+    authored_date: DateTime<Utc>,
+}
+
+impl GitlabCollector {
+    /// Create a new GitLab collector
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(config.request_timeout)
+            .build()?;
+
+        Ok(Self { client })
+    }
+
+    /// Collect metrics for a GitLab group's projects, subgroups included
+    pub async fn collect_group_projects(
+        &self,
+        db: &Database,
+        distro_id: i64,
+        forge: &Forge,
+        group: &str,
+    ) -> Result<Vec<i64>> {
+        info!(forge = forge.base_url, group = group, "Collecting GitLab metrics");
+
+        let distro = db.get_distribution_by_id(distro_id).await?;
+        let bots = BotFilter::new(distro.bot_accounts.as_deref());
+
+        let projects = self.get_group_projects(forge, group).await?;
+        let mut snapshot_ids = Vec::new();
+        for project in projects {
+            let name = project.path_with_namespace.clone();
+            match self.fetch_project_snapshot(distro_id, forge, project, &bots).await {
+                Ok(snapshot) => snapshot_ids.push(db.insert_github_snapshot(snapshot).await?),
+                Err(e) => warn!(project = name, error = %e, "Failed to collect project metrics"),
+            }
+        }
+
+        info!(forge = forge.base_url, group = group, count = snapshot_ids.len(), "Collected GitLab snapshots");
+        Ok(snapshot_ids)
+    }
+
+    async fn fetch_project_snapshot(
+        &self,
+        distro_id: i64,
+        forge: &Forge,
+        project: ProjectResponse,
+        bots: &BotFilter,
+    ) -> Result<NewGithubSnapshot> {
+        debug!(project = project.path_with_namespace, "Collecting project metrics");
+
+        let mut provenance = provenance();
+        let open_prs = match self.count_open_merge_requests(forge, project.id, &mut provenance).await {
+            Ok(count) => count,
+            Err(e) => {
+                provenance.fallback(format!("merge request count failed ({}) → 0", e));
+                0
+            }
+        };
+        let activity = match self.get_recent_activity(forge, project.id, bots, &mut provenance).await {
+            Ok(activity) => activity,
+            Err(e) => {
+                provenance.fallback(format!("commit history failed ({}) → 0", e));
+                RecentActivity::default()
+            }
+        };
+
+        Ok(NewGithubSnapshot {
+            distro_id,
+            repo_name: project.path_with_namespace,
+            stars: project.star_count,
+            forks: project.forks_count,
+            // Counted like GitHub's open_issues_count, which includes pull requests
+            open_issues: project.open_issues_count.unwrap_or(0) + open_prs,
+            open_prs,
+            commits_30d: activity.commits_30d,
+            commits_365d: activity.commits_365d,
+            contributors_30d: activity.authors.len() as i64,
+            bot_commits_30d: activity.bot_commits_30d,
+            bot_commits_365d: activity.bot_commits_365d,
+            is_mirror: project.mirror,
+            topics: project.topics,
+            languages: Vec::new(),
+            contributors: activity.authors.into_iter().collect(),
+            issue_labels: Vec::new(),
+            issue_activity: None,
+            last_commit_at: project.last_activity_at,
+            provenance,
+        })
+    }
+
+    /// A group's most recently active source projects (forks excluded)
+    async fn get_group_projects(&self, forge: &Forge, group: &str) -> Result<Vec<ProjectResponse>> {
+        let url = format!(
+            "{}/api/v4/groups/{}/projects?include_subgroups=true&with_shared=false&archived=false\
+             &order_by=last_activity_at&sort=desc&per_page={}",
+            forge.base_url,
+            group.replace('/', "%2F"),
+            PAGE_SIZE
+        );
+        let response = forge.send(self.client.get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status(forge.service(), response.status(), format!("group {}", group)));
+        }
+
+        let projects: Vec<ProjectResponse> = response.json().await?;
+        Ok(projects
+            .into_iter()
+            .filter(|p| p.forked_from_project.is_none())
+            .take(MAX_PROJECTS)
+            .collect())
+    }
+
+    async fn count_open_merge_requests(
+        &self,
+        forge: &Forge,
+        project_id: i64,
+        provenance: &mut Provenance,
+    ) -> Result<i64> {
+        let url = format!("{}/api/v4/projects/{}/merge_requests?state=opened&per_page=1", forge.base_url, project_id);
+        let response = self.get(forge, &url, provenance).await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status(
+                forge.service(),
+                response.status(),
+                format!("merge requests of project {}", project_id),
+            ));
+        }
+
+        response
+            .headers()
+            .get("x-total")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok())
+            .ok_or_else(|| CollectorError::Parse(format!("No X-Total for {}", url)))
+    }
+
+    async fn get_recent_activity(
+        &self,
+        forge: &Forge,
+        project_id: i64,
+        bots: &BotFilter,
+        provenance: &mut Provenance,
+    ) -> Result<RecentActivity> {
+        let now = Utc::now();
+        let month_ago = now - TimeDelta::days(30);
+        let since = since_param(now - TimeDelta::days(365));
+
+        let mut activity = RecentActivity::default();
+        for page in 1..=MAX_COMMIT_PAGES {
+            let url = format!(
+                "{}/api/v4/projects/{}/repository/commits?since={}&per_page={}&page={}",
+                forge.base_url, project_id, since, PAGE_SIZE, page
+            );
+            let response = self.get(forge, &url, provenance).await?;
+            // Projects without a repository
+            if response.status() == reqwest::StatusCode::NOT_FOUND {
+                break;
+            }
+            if !response.status().is_success() {
+                return Err(CollectorError::from_status(
+                    forge.service(),
+                    response.status(),
+                    format!("commits of project {}", project_id),
+                ));
+            }
+
+            let commits: Vec<CommitResponse> = response.json().await?;
+            for commit in &commits {
+                activity.record(&commit.author_name, commit.authored_date >= month_ago, bots);
+            }
+
+            if commits.len() < PAGE_SIZE {
+                break;
+            }
+            if page == MAX_COMMIT_PAGES {
+                provenance.fallback(format!("commits past {} pages not counted", MAX_COMMIT_PAGES));
+            }
+        }
+
+        Ok(activity)
+    }
+
+    /// GET `url`, recording the request in the snapshot's provenance
+    async fn get(&self, forge: &Forge, url: &str, provenance: &mut Provenance) -> Result<reqwest::Response> {
+        provenance.request(url);
+        forge.send(self.client.get(url)).await
+    }
+}
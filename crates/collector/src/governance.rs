@@ -0,0 +1,64 @@
+//! Governance and contributor documentation checks
+//!
+//! Projects keep their community health files in the repo root, `.github/` or `docs/`, and
+//! an org can publish defaults for all of its repos from a repo named `.github`. Files are
+//! recognised by name whatever the extension, so SECURITY.md and security.rst both count.
+
+/// Documents on the governance checklist
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Doc {
+    Contributing,
+    CodeOfConduct,
+    SecurityPolicy,
+    Governance,
+}
+
+/// Directories of a repo searched for documents, besides what the community profile reports
+pub const DOC_DIRS: &[&str] = &["", ".github", "docs"];
+
+/// Document a file is, by name
+pub fn classify(file_name: &str) -> Option<Doc> {
+    let name = file_name.to_ascii_lowercase();
+    let stem = name.split('.').next().unwrap_or_default();
+    match stem {
+        "contributing" => Some(Doc::Contributing),
+        "code_of_conduct" | "code-of-conduct" | "codeofconduct" => Some(Doc::CodeOfConduct),
+        "security" => Some(Doc::SecurityPolicy),
+        "governance" => Some(Doc::Governance),
+        _ => None,
+    }
+}
+
+/// Which documents were found, across however many repos and directories were checked
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DocsFound {
+    pub contributing: bool,
+    pub code_of_conduct: bool,
+    pub security_policy: bool,
+    pub governance: bool,
+}
+
+impl DocsFound {
+    pub fn add(&mut self, doc: Doc) {
+        match doc {
+            Doc::Contributing => self.contributing = true,
+            Doc::CodeOfConduct => self.code_of_conduct = true,
+            Doc::SecurityPolicy => self.security_policy = true,
+            Doc::Governance => self.governance = true,
+        }
+    }
+
+    /// Add every document among `file_names`
+    pub fn add_files<'a>(&mut self, file_names: impl IntoIterator<Item = &'a str>) {
+        for doc in file_names.into_iter().filter_map(classify) {
+            self.add(doc);
+        }
+    }
+
+    pub fn merge(&mut self, other: DocsFound) {
+        self.contributing |= other.contributing;
+        self.code_of_conduct |= other.code_of_conduct;
+        self.security_policy |= other.security_policy;
+        self.governance |= other.governance;
+    }
+}
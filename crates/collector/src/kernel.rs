@@ -0,0 +1,85 @@
+//! kernel.org release lookup for kernel series LTS/EOL classification
+
+use crate::breaker;
+use crate::{CollectorError, Result};
+use reqwest::Client;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+struct ReleasesResponse {
+    releases: Vec<KernelRelease>,
+}
+
+#[derive(Debug, Deserialize)]
+struct KernelRelease {
+    moniker: String,
+    version: String,
+    iseol: bool,
+}
+
+/// Currently published kernel releases from kernel.org
+pub struct KernelReleases {
+    releases: Vec<KernelRelease>,
+}
+
+impl KernelReleases {
+    pub const URL: &'static str = "https://www.kernel.org/releases.json";
+
+    /// Fetch the current release list from kernel.org
+    pub async fn fetch(client: &Client) -> Result<Self> {
+        let response = breaker::KERNEL_ORG.send(client.get(Self::URL)).await?;
+
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("kernel.org", response.status(), "releases.json"));
+        }
+
+        let body: ReleasesResponse = response.json().await?;
+        Ok(Self {
+            releases: body.releases,
+        })
+    }
+
+    /// Classify a kernel series (e.g. "6.6") as "mainline", "stable", "longterm" or "eol"
+    pub fn classify(&self, series: &str) -> &'static str {
+        let tracked = self
+            .releases
+            .iter()
+            .filter(|r| r.moniker != "linux-next")
+            .find(|r| kernel_series(&r.version).as_deref() == Some(series));
+
+        if let Some(release) = tracked {
+            return match (release.iseol, release.moniker.as_str()) {
+                (true, _) => "eol",
+                (false, "mainline") => "mainline",
+                (false, "longterm") => "longterm",
+                _ => "stable",
+            };
+        }
+
+        // Series newer than anything published (e.g. distro shipping an -rc) counts as mainline
+        let newest = self
+            .releases
+            .iter()
+            .filter_map(|r| kernel_series(&r.version))
+            .filter_map(|s| series_key(&s))
+            .max();
+
+        match (series_key(series), newest) {
+            (Some(ours), Some(newest)) if ours > newest => "mainline",
+            _ => "eol",
+        }
+    }
+}
+
+/// Extract the "major.minor" series from a kernel version string
+pub fn kernel_series(version: &str) -> Option<String> {
+    let mut parts = version.split(|c: char| !c.is_ascii_digit());
+    let major: u32 = parts.next()?.parse().ok()?;
+    let minor: u32 = parts.next()?.parse().ok()?;
+    Some(format!("{}.{}", major, minor))
+}
+
+fn series_key(series: &str) -> Option<(u32, u32)> {
+    let (major, minor) = series.split_once('.')?;
+    Some((major.parse().ok()?, minor.parse().ok()?))
+}
@@ -0,0 +1,38 @@
+//! Issue label classification
+//!
+//! Every project names its labels differently ("bug", "type: bug", "kind/bug", "C-bug"), so
+//! labels are sorted into a few categories by keyword. A label matching nothing is ignored.
+
+use distrovitals_database::IssueCategory;
+
+/// Keywords per category, checked in order so "security bug" counts as security
+const KEYWORDS: &[(IssueCategory, &[&str])] = &[
+    (IssueCategory::Security, &["security", "cve", "vulnerab"]),
+    (IssueCategory::Bug, &["bug", "crash", "regression"]),
+    (IssueCategory::Packaging, &["packag", "pkg", "ebuild", "recipe"]),
+    (IssueCategory::Feature, &["feature", "enhancement", "rfe", "wishlist"]),
+];
+
+/// Category of a label, by name
+pub fn classify(label: &str) -> Option<IssueCategory> {
+    let label = label.to_ascii_lowercase();
+    KEYWORDS
+        .iter()
+        .find(|(_, keywords)| keywords.iter().any(|keyword| label.contains(keyword)))
+        .map(|(category, _)| *category)
+}
+
+/// Label names grouped by category, in category order, leaving out empty categories
+pub fn group<'a>(labels: impl IntoIterator<Item = &'a str>) -> Vec<(IssueCategory, Vec<&'a str>)> {
+    let mut groups: Vec<(IssueCategory, Vec<&str>)> =
+        IssueCategory::ALL.into_iter().map(|category| (category, Vec::new())).collect();
+    for label in labels {
+        if let Some(category) = classify(label) {
+            if let Some((_, names)) = groups.iter_mut().find(|(c, _)| *c == category) {
+                names.push(label);
+            }
+        }
+    }
+    groups.retain(|(_, names)| !names.is_empty());
+    groups
+}
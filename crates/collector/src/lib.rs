@@ -2,10 +2,40 @@
 //!
 //! Fetches metrics from various sources (GitHub, Reddit, package repos, etc.)
 
+pub mod arch;
+pub mod bots;
+pub mod breaker;
+pub mod bugzilla;
+pub mod custom;
+pub mod debian;
+pub mod downloads;
+pub mod drift;
+pub mod forge;
+pub mod forgejo;
+pub mod gitlab;
 pub mod github;
+pub mod github_graphql;
+pub mod governance;
+pub mod kernel;
+pub mod labels;
+pub mod mirrors;
+pub mod pagure;
+pub mod polite;
 pub mod reddit;
+pub mod repology;
+pub mod sources;
+pub mod tokens;
+pub mod verification;
 
+use custom::CustomMetric;
+use distrovitals_database::{Provenance, RunFailure};
+use reqwest::StatusCode;
+use sources::Source;
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
 use thiserror::Error;
+use tracing::warn;
 
 #[derive(Error, Debug)]
 pub enum CollectorError {
@@ -15,6 +45,45 @@ pub enum CollectorError {
     #[error("Rate limited, retry after {0} seconds")]
     RateLimited(u64),
 
+    /// The org, repo, subreddit or repository doesn't exist (any more)
+    #[error("{service}: {resource} not found ({status})")]
+    NotFound {
+        service: &'static str,
+        resource: String,
+        status: u16,
+    },
+
+    /// Credentials were rejected, or the resource is private
+    #[error("{service}: access denied to {resource} ({status})")]
+    AuthFailed {
+        service: &'static str,
+        resource: String,
+        status: u16,
+    },
+
+    /// Calls to the service are paused after repeated failures
+    #[error("{service} unavailable after repeated failures, retry after {retry_after} seconds")]
+    CircuitOpen {
+        service: &'static str,
+        retry_after: u64,
+    },
+
+    /// The site's robots.txt asks us not to fetch the URL
+    #[error("{url} is disallowed by robots.txt")]
+    Disallowed { url: String },
+
+    /// A collection ran past its time budget; whatever it wrote before then is kept
+    #[error("{what} timed out after {after_secs} seconds")]
+    TimedOut { what: String, after_secs: u64 },
+
+    /// Any other unsuccessful response
+    #[error("{service} error {status} for {resource}")]
+    Upstream {
+        service: &'static str,
+        resource: String,
+        status: u16,
+    },
+
     #[error("API error: {0}")]
     Api(String),
 
@@ -25,20 +94,191 @@ pub enum CollectorError {
     Database(#[from] distrovitals_database::DatabaseError),
 }
 
+impl CollectorError {
+    /// Classify an unsuccessful response from `service` about `resource`
+    pub fn from_status(service: &'static str, status: StatusCode, resource: impl Into<String>) -> Self {
+        let resource = resource.into();
+        let status = status.as_u16();
+        match status {
+            404 | 410 => Self::NotFound { service, resource, status },
+            401 | 403 => Self::AuthFailed { service, resource, status },
+            _ => Self::Upstream { service, resource, status },
+        }
+    }
+
+    /// Whether the same request could succeed later: network trouble, rate limits and
+    /// server errors are worth retrying, a missing org or a rejected token isn't
+    pub fn retryable(&self) -> bool {
+        match self {
+            Self::Http(e) => Self::retryable_http(e),
+            Self::RateLimited(_) | Self::CircuitOpen { .. } | Self::TimedOut { .. } | Self::Database(_) => true,
+            Self::Upstream { status, .. } => transient_status(*status),
+            Self::NotFound { .. }
+            | Self::AuthFailed { .. }
+            | Self::Disallowed { .. }
+            | Self::Api(_)
+            | Self::Parse(_) => false,
+        }
+    }
+
+    /// Whether a failed request is worth retrying
+    pub(crate) fn retryable_http(e: &reqwest::Error) -> bool {
+        match e.status() {
+            Some(status) => transient_status(status.as_u16()),
+            None => !(e.is_decode() || e.is_builder() || e.is_redirect()),
+        }
+    }
+
+    /// Short name of the error class, as stored in the run log
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Http(_) => "http",
+            Self::RateLimited(_) => "rate_limited",
+            Self::CircuitOpen { .. } => "circuit_open",
+            Self::Disallowed { .. } => "disallowed",
+            Self::TimedOut { .. } => "timeout",
+            Self::NotFound { .. } => "not_found",
+            Self::AuthFailed { .. } => "auth_failed",
+            Self::Upstream { .. } => "upstream",
+            Self::Api(_) => "api",
+            Self::Parse(_) => "parse",
+            Self::Database(_) => "database",
+        }
+    }
+
+    /// Service that answered, when the error came from a response
+    pub fn service(&self) -> Option<&'static str> {
+        match self {
+            Self::NotFound { service, .. }
+            | Self::AuthFailed { service, .. }
+            | Self::Upstream { service, .. }
+            | Self::CircuitOpen { service, .. } => Some(service),
+            _ => None,
+        }
+    }
+
+    /// HTTP status of the response, when there was one
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            Self::Http(e) => e.status().map(|s| s.as_u16()),
+            Self::NotFound { status, .. } | Self::AuthFailed { status, .. } | Self::Upstream { status, .. } => {
+                Some(*status)
+            }
+            _ => None,
+        }
+    }
+
+    /// Failure details for the run log
+    pub fn run_failure(&self) -> RunFailure {
+        RunFailure {
+            message: self.to_string(),
+            kind: self.kind().to_string(),
+            retryable: self.retryable(),
+        }
+    }
+}
+
+/// Timeouts, rate limits and server errors
+fn transient_status(status: u16) -> bool {
+    matches!(status, 408 | 429) || status >= 500
+}
+
 pub type Result<T> = std::result::Result<T, CollectorError>;
 
 /// Configuration for collectors
 #[derive(Debug, Clone)]
 pub struct CollectorConfig {
-    pub github_token: Option<String>,
+    /// GitHub tokens to rotate among, from `GITHUB_TOKEN` and the comma-separated `GITHUB_TOKENS`
+    pub github_tokens: Vec<String>,
     pub user_agent: String,
+    /// Limit on any single HTTP request, from `DV_REQUEST_TIMEOUT` in seconds
+    pub request_timeout: Duration,
+    /// Time allowed for all of one distro's sources, from `DV_DISTRO_BUDGET` in seconds
+    pub distro_budget: Duration,
+    /// Time allowed for a pass over many distros, from `DV_RUN_DEADLINE` in seconds;
+    /// unbounded if unset
+    pub run_deadline: Option<Duration>,
+    /// How long each source's data stays fresh, from `DV_<SOURCE>_TTL` in seconds (e.g.
+    /// `DV_REDDIT_TTL`); scheduled passes skip sources collected more recently than this
+    pub source_ttls: HashMap<Source, Duration>,
+    /// Operator-defined metrics, from the JSON file named by `DV_CUSTOM_METRICS`
+    pub custom_metrics: Vec<CustomMetric>,
+}
+
+impl CollectorConfig {
+    /// The first configured token, for clients that make a handful of requests
+    pub fn github_token(&self) -> Option<&str> {
+        self.github_tokens.first().map(String::as_str)
+    }
+
+    /// How long a source's data stays fresh
+    pub fn source_ttl(&self, source: Source) -> Duration {
+        self.source_ttls.get(&source).copied().unwrap_or_else(|| source.default_ttl())
+    }
 }
 
 impl Default for CollectorConfig {
     fn default() -> Self {
+        let mut github_tokens: Vec<String> = Vec::new();
+        let single = std::env::var("GITHUB_TOKEN").ok();
+        let pool = std::env::var("GITHUB_TOKENS").unwrap_or_default();
+        for token in single.iter().map(String::as_str).chain(pool.split(',')).map(str::trim) {
+            if !token.is_empty() && !github_tokens.iter().any(|t| t == token) {
+                github_tokens.push(token.to_string());
+            }
+        }
+
         Self {
-            github_token: std::env::var("GITHUB_TOKEN").ok(),
-            user_agent: "DistroVitals/0.1 (https://distrovitals.org)".to_string(),
+            github_tokens,
+            user_agent: USER_AGENT.to_string(),
+            request_timeout: env_secs("DV_REQUEST_TIMEOUT").unwrap_or(DEFAULT_REQUEST_TIMEOUT),
+            distro_budget: env_secs("DV_DISTRO_BUDGET").unwrap_or(DEFAULT_DISTRO_BUDGET),
+            run_deadline: env_secs("DV_RUN_DEADLINE"),
+            source_ttls: Source::ALL
+                .iter()
+                .map(|&source| {
+                    let var = format!("DV_{}_TTL", source.as_str().to_ascii_uppercase());
+                    (source, env_secs(&var).unwrap_or(source.default_ttl()))
+                })
+                .collect(),
+            custom_metrics: env_custom_metrics(),
+        }
+    }
+}
+
+/// How every collector identifies itself; robots.txt groups match its `DistroVitals` token
+pub const USER_AGENT: &str = concat!("DistroVitals/", env!("CARGO_PKG_VERSION"), " (+https://distrovitals.org)");
+
+const DEFAULT_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Generous enough for a large org's repos and releases with a few rate-limit waits
+const DEFAULT_DISTRO_BUDGET: Duration = Duration::from_secs(10 * 60);
+
+/// A positive number of seconds from the environment, ignoring anything else
+fn env_secs(var: &str) -> Option<Duration> {
+    let value = std::env::var(var).ok()?;
+    match value.trim().parse::<u64>() {
+        Ok(secs) if secs > 0 => Some(Duration::from_secs(secs)),
+        _ => {
+            warn!("Ignoring {}={:?}: expected a positive number of seconds", var, value);
+            None
         }
     }
 }
+
+/// Custom metric definitions from the file named by `DV_CUSTOM_METRICS`, if any
+fn env_custom_metrics() -> Vec<CustomMetric> {
+    let Some(path) = std::env::var_os("DV_CUSTOM_METRICS").filter(|p| !p.is_empty()) else {
+        return Vec::new();
+    };
+    let path = Path::new(&path);
+    custom::load_custom_metrics(path).unwrap_or_else(|e| {
+        warn!("Ignoring DV_CUSTOM_METRICS={}: {}", path.display(), e);
+        Vec::new()
+    })
+}
+
+/// Empty provenance stamped with this collector's version
+pub(crate) fn provenance() -> Provenance {
+    Provenance::new(concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")))
+}
@@ -0,0 +1,227 @@
+//! Mirror list collector for download redundancy
+//!
+//! Each distro publishes its mirrors its own way, so the list's URL and format are set
+//! per distro. A mirror is a host: one serving HTTP, HTTPS and rsync counts once.
+
+use crate::{polite, provenance, CollectorConfig, CollectorError, Result};
+use distrovitals_database::{Database, NewInfraSnapshot};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashSet;
+use tracing::info;
+
+/// How a mirror list is laid out
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MirrorListFormat {
+    /// Arch Linux mirror status JSON (`/mirrors/status/json/`), active mirrors only
+    Archlinux,
+    /// Metalink XML as served by MirrorManager, with a country per URL
+    Metalink,
+    /// Debian's Mirrors.masterlist: blank-line separated records with Site and Country
+    DebianMasterlist,
+    /// One mirror URL per line; `#` comments and pacman-style `Server = ` prefixes are
+    /// allowed. Doesn't say where mirrors are.
+    Text,
+}
+
+impl MirrorListFormat {
+    pub const ALL: [MirrorListFormat; 4] = [Self::Archlinux, Self::Metalink, Self::DebianMasterlist, Self::Text];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|f| f.as_str() == name)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Archlinux => "archlinux",
+            Self::Metalink => "metalink",
+            Self::DebianMasterlist => "debian-masterlist",
+            Self::Text => "text",
+        }
+    }
+
+    /// Comma-separated names of every format, for error messages
+    pub fn names() -> String {
+        Self::ALL.iter().map(|f| f.as_str()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+/// What a mirror list says about a distro's mirror network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MirrorCount {
+    /// Distinct hosts
+    pub mirrors: i64,
+    /// Distinct countries; None when the format doesn't record them
+    pub countries: Option<i64>,
+}
+
+/// Count the mirrors in a mirror list
+pub fn count_mirrors(format: MirrorListFormat, body: &str) -> Result<MirrorCount> {
+    let (hosts, countries) = match format {
+        MirrorListFormat::Archlinux => parse_archlinux(body)?,
+        MirrorListFormat::Metalink => parse_metalink(body),
+        MirrorListFormat::DebianMasterlist => parse_debian_masterlist(body),
+        MirrorListFormat::Text => (body.lines().filter_map(text_line_host).collect(), None),
+    };
+
+    Ok(MirrorCount {
+        mirrors: hosts.len() as i64,
+        countries: countries.map(|c| c.len() as i64),
+    })
+}
+
+type Hosts = HashSet<String>;
+type Countries = HashSet<String>;
+
+fn host(url: &str) -> Option<String> {
+    let url = reqwest::Url::parse(url.trim()).ok()?;
+    url.host_str().map(str::to_ascii_lowercase)
+}
+
+fn parse_archlinux(body: &str) -> Result<(Hosts, Option<Countries>)> {
+    #[derive(Deserialize)]
+    struct MirrorUrl {
+        url: String,
+        #[serde(default)]
+        country_code: String,
+        #[serde(default)]
+        active: bool,
+    }
+
+    #[derive(Deserialize)]
+    struct Status {
+        urls: Vec<MirrorUrl>,
+    }
+
+    let status: Status =
+        serde_json::from_str(body).map_err(|e| CollectorError::Parse(format!("Arch mirror status: {}", e)))?;
+    let mut hosts = HashSet::new();
+    let mut countries = HashSet::new();
+    for mirror in status.urls.iter().filter(|m| m.active) {
+        if let Some(host) = host(&mirror.url) {
+            hosts.insert(host);
+            if !mirror.country_code.is_empty() {
+                countries.insert(mirror.country_code.to_ascii_uppercase());
+            }
+        }
+    }
+    Ok((hosts, Some(countries)))
+}
+
+/// `<url protocol="https" location="DE" ...>https://...</url>` elements, without an XML parser
+fn parse_metalink(body: &str) -> (Hosts, Option<Countries>) {
+    let mut hosts = HashSet::new();
+    let mut countries = HashSet::new();
+    for element in body.split("<url").skip(1) {
+        // Not some other element starting "url"
+        if !element.starts_with([' ', '>']) {
+            continue;
+        }
+        let Some((attributes, rest)) = element.split_once('>') else {
+            continue;
+        };
+        let Some((url, _)) = rest.split_once("</url>") else {
+            continue;
+        };
+        if let Some(host) = host(url) {
+            hosts.insert(host);
+            if let Some(location) = attribute(attributes, "location") {
+                countries.insert(location.to_ascii_uppercase());
+            }
+        }
+    }
+    (hosts, Some(countries))
+}
+
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let start = attributes.find(&format!("{}=\"", name))? + name.len() + 2;
+    let value = &attributes[start..];
+    value.split('"').next().filter(|v| !v.is_empty())
+}
+
+fn parse_debian_masterlist(body: &str) -> (Hosts, Option<Countries>) {
+    let mut hosts = HashSet::new();
+    let mut countries = HashSet::new();
+    for record in body.split("\n\n") {
+        let field = |name: &str| {
+            record
+                .lines()
+                .find_map(|line| line.strip_prefix(name)?.strip_prefix(':'))
+                .map(str::trim)
+        };
+        let Some(site) = field("Site").filter(|s| !s.is_empty()) else {
+            continue;
+        };
+        hosts.insert(site.to_ascii_lowercase());
+        // "Country: DE Germany"
+        if let Some(code) = field("Country").and_then(|c| c.split_whitespace().next()) {
+            countries.insert(code.to_ascii_uppercase());
+        }
+    }
+    (hosts, Some(countries))
+}
+
+fn text_line_host(line: &str) -> Option<String> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let url = match line.split_once('=') {
+        Some((key, value)) if key.trim().eq_ignore_ascii_case("server") => value,
+        _ => line,
+    };
+    host(url)
+}
+
+/// Fetches mirror lists from wherever distros publish them
+pub struct MirrorCollector {
+    client: Client,
+}
+
+impl MirrorCollector {
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(config.request_timeout)
+            .build()?;
+
+        Ok(Self { client })
+    }
+
+    /// Count the mirrors in a distro's mirror list and store them as an infrastructure snapshot
+    pub async fn collect_mirror_list(
+        &self,
+        db: &Database,
+        distro_id: i64,
+        url: &str,
+        format: MirrorListFormat,
+    ) -> Result<i64> {
+        info!(url = url, format = format.as_str(), "Collecting mirror list");
+
+        let mut provenance = provenance();
+        provenance.request(url);
+        let response = polite::send(self.client.get(url)).await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status("mirrors", response.status(), url));
+        }
+
+        let body = response.text().await?;
+        let count = count_mirrors(format, &body)?;
+        // An empty list is far likelier a changed format than a distro without mirrors
+        if count.mirrors == 0 {
+            return Err(CollectorError::Parse(format!("No mirrors found in {} as {}", url, format.as_str())));
+        }
+
+        let id = db
+            .insert_infra_snapshot(NewInfraSnapshot {
+                distro_id,
+                mirrors: count.mirrors,
+                mirror_countries: count.countries,
+                provenance,
+            })
+            .await?;
+
+        info!(url = url, mirrors = count.mirrors, countries = ?count.countries, "Collected mirror list");
+        Ok(id)
+    }
+}
@@ -0,0 +1,262 @@
+//! Pagure collector for distros keeping their package dist-git there
+//!
+//! Fedora's packaging work happens in src.fedoraproject.org's `rpms` namespace, over twenty
+//! thousand repos that GitHub's `fedora-infra` org says nothing about. Pagure's API has no
+//! commit log, so the namespace is collected as a whole and stored as one snapshot under
+//! "<host>/<namespace>":
+//!
+//! - commits are the packages pushed to in the last 30 days and year, one per package, so
+//!   they're a lower bound on the commits made;
+//! - the latest commit of up to [`MAX_SAMPLED`] of the most recently pushed packages is
+//!   read, and the share of them made by bots splits the counts between humans and bots;
+//! - their human authors are the namespace's 30-day contributors.
+
+use crate::bots::BotFilter;
+use crate::forge::Forge;
+use crate::{provenance, CollectorConfig, CollectorError, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use distrovitals_database::{Database, NewGithubSnapshot, Provenance};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use tracing::{debug, info};
+
+/// Pagure's maximum page size
+const PAGE_SIZE: usize = 100;
+
+/// Safety cap on project listing pages, well above Fedora's ~250
+const MAX_PAGES: u32 = 500;
+
+/// Recently pushed packages whose latest commit is read for authors and bot share
+const MAX_SAMPLED: usize = 100;
+
+/// Branches a package's latest commit is read from, in order of preference
+const MAIN_BRANCHES: &[&str] = &["rawhide", "main", "master"];
+
+#[derive(Debug, Deserialize)]
+struct ProjectsResponse {
+    projects: Vec<ProjectResponse>,
+    pagination: Pagination,
+}
+
+#[derive(Debug, Deserialize)]
+struct Pagination {
+    pages: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProjectResponse {
+    /// "namespace/name"
+    fullname: String,
+    /// Unix time as a string, bumped by every push
+    date_modified: String,
+}
+
+impl ProjectResponse {
+    fn modified_at(&self) -> Option<DateTime<Utc>> {
+        DateTime::from_timestamp(self.date_modified.parse().ok()?, 0)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BranchesResponse {
+    /// Branch name to head commit, with `with_commits`
+    branches: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CommitResponse {
+    author: String,
+    /// Unix time as a string
+    commit_time: String,
+}
+
+/// Pagure API client
+pub struct PagureCollector {
+    client: Client,
+}
+
+impl PagureCollector {
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(config.request_timeout)
+            .build()?;
+
+        Ok(Self { client })
+    }
+
+    /// Collect a Pagure namespace's packaging activity as one snapshot
+    pub async fn collect_namespace(
+        &self,
+        db: &Database,
+        distro_id: i64,
+        forge: &Forge,
+        namespace: &str,
+    ) -> Result<i64> {
+        info!(forge = forge.base_url, namespace = namespace, "Collecting Pagure metrics");
+
+        let distro = db.get_distribution_by_id(distro_id).await?;
+        let bots = BotFilter::new(distro.bot_accounts.as_deref());
+        let mut provenance = provenance();
+
+        let now = Utc::now();
+        let month_ago = now - TimeDelta::days(30);
+        let year_ago = now - TimeDelta::days(365);
+        let mut pushed = self.get_pushed_projects(forge, namespace, year_ago, &mut provenance).await?;
+        pushed.sort_by_key(|(_, at)| std::cmp::Reverse(*at));
+        let pushed_30d = pushed.iter().filter(|(_, at)| *at >= month_ago).count() as i64;
+        let pushed_365d = pushed.len() as i64;
+
+        // Latest commits of the most recently pushed packages
+        let mut authors: HashMap<String, i64> = HashMap::new();
+        let (mut sampled, mut bot_heads) = (0, 0);
+        let mut failed = None;
+        for (project, _) in pushed.iter().take(MAX_SAMPLED.min(pushed_30d as usize)) {
+            match self.get_head_commit(forge, namespace, project, &mut provenance).await {
+                Ok(Some((author, at))) if at >= month_ago => {
+                    sampled += 1;
+                    if bots.is_bot(&author) {
+                        bot_heads += 1;
+                    } else {
+                        *authors.entry(author).or_default() += 1;
+                    }
+                }
+                // Pushed to a branch other than the main one, or changed without a push
+                Ok(_) => {}
+                Err(e) => {
+                    debug!(project = project, error = %e, "Failed to read latest commit");
+                    failed.get_or_insert(e);
+                }
+            }
+        }
+
+        let bot_share = if sampled > 0 {
+            provenance.fallback(format!(
+                "commits → packages pushed to, split by the latest commit of {} recently pushed packages",
+                sampled
+            ));
+            bot_heads as f64 / sampled as f64
+        } else {
+            if let Some(e) = failed.filter(|_| pushed_30d > 0) {
+                provenance.fallback(format!("latest commits failed ({}) → counted as human", e));
+            }
+            0.0
+        };
+        let bot_commits_30d = (pushed_30d as f64 * bot_share).round() as i64;
+        let bot_commits_365d = (pushed_365d as f64 * bot_share).round() as i64;
+
+        let host = forge.base_url.split("://").nth(1).unwrap_or(&forge.base_url);
+        let id = db
+            .insert_github_snapshot(NewGithubSnapshot {
+                distro_id,
+                repo_name: format!("{}/{}", host, namespace),
+                stars: 0,
+                forks: 0,
+                open_issues: 0,
+                open_prs: 0,
+                commits_30d: pushed_30d - bot_commits_30d,
+                commits_365d: pushed_365d - bot_commits_365d,
+                contributors_30d: authors.len() as i64,
+                bot_commits_30d,
+                bot_commits_365d,
+                is_mirror: false,
+                topics: Vec::new(),
+                languages: Vec::new(),
+                contributors: authors.into_iter().collect(),
+                issue_labels: Vec::new(),
+                issue_activity: None,
+                last_commit_at: pushed.first().map(|(_, at)| *at),
+                provenance,
+            })
+            .await?;
+
+        info!(
+            forge = forge.base_url,
+            namespace = namespace,
+            pushed_30d = pushed_30d,
+            pushed_365d = pushed_365d,
+            "Collected Pagure snapshot"
+        );
+        Ok(id)
+    }
+
+    /// Non-fork projects in the namespace last pushed to since `since`, with when
+    async fn get_pushed_projects(
+        &self,
+        forge: &Forge,
+        namespace: &str,
+        since: DateTime<Utc>,
+        provenance: &mut Provenance,
+    ) -> Result<Vec<(String, DateTime<Utc>)>> {
+        let mut pushed = Vec::new();
+        for page in 1..=MAX_PAGES {
+            let url = format!(
+                "{}/api/0/projects?namespace={}&fork=false&per_page={}&page={}",
+                forge.base_url, namespace, PAGE_SIZE, page
+            );
+            let response = self.get(forge, &url, provenance).await?;
+            if !response.status().is_success() {
+                return Err(CollectorError::from_status(
+                    forge.service(),
+                    response.status(),
+                    format!("namespace {}", namespace),
+                ));
+            }
+
+            let batch: ProjectsResponse = response
+                .json()
+                .await
+                .map_err(|e| CollectorError::Parse(format!("Project listing from {} ({})", forge.base_url, e)))?;
+            pushed.extend(
+                batch
+                    .projects
+                    .iter()
+                    .filter_map(|p| p.modified_at().filter(|&at| at >= since).map(|at| (p.fullname.clone(), at))),
+            );
+            if page >= batch.pagination.pages || batch.projects.len() < PAGE_SIZE {
+                break;
+            }
+        }
+        Ok(pushed)
+    }
+
+    /// Author and time of the latest commit on a project's main branch, None without one
+    ///
+    /// Requests are recorded with the package as `*`, so provenance lists two endpoints
+    /// rather than two per package.
+    async fn get_head_commit(
+        &self,
+        forge: &Forge,
+        namespace: &str,
+        project: &str,
+        provenance: &mut Provenance,
+    ) -> Result<Option<(String, DateTime<Utc>)>> {
+        provenance.request(&format!("{}/api/0/{}/*/git/branches", forge.base_url, namespace));
+        let url = format!("{}/api/0/{}/git/branches?with_commits=true", forge.base_url, project);
+        let response = forge.send(self.client.get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status(forge.service(), response.status(), project));
+        }
+        let branches: BranchesResponse = response.json().await?;
+        let Some(head) = MAIN_BRANCHES.iter().find_map(|name| branches.branches.get(*name)) else {
+            return Ok(None);
+        };
+
+        provenance.request(&format!("{}/api/0/{}/*/c/*/info", forge.base_url, namespace));
+        let url = format!("{}/api/0/{}/c/{}/info", forge.base_url, project, head);
+        let response = forge.send(self.client.get(&url)).await?;
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status(forge.service(), response.status(), project));
+        }
+        let commit: CommitResponse = response.json().await?;
+        let at = commit.commit_time.parse().ok().and_then(|secs| DateTime::from_timestamp(secs, 0));
+        Ok(at.map(|at| (commit.author, at)))
+    }
+
+    /// GET `url`, recording the request in the snapshot's provenance
+    async fn get(&self, forge: &Forge, url: &str, provenance: &mut Provenance) -> Result<reqwest::Response> {
+        provenance.request(url);
+        forge.send(self.client.get(url)).await
+    }
+}
@@ -0,0 +1,239 @@
+//! Etiquette for fetching from distro websites rather than APIs meant for programs
+//!
+//! Mirror lists, package indexes, installer images and pages read for custom metrics are
+//! served by projects that often run on donated hosting. Requests to them go through
+//! [`send`], which:
+//!
+//! - checks the URL against the host's robots.txt, using the rules for `DistroVitals` or
+//!   failing that `*`, and refuses disallowed URLs without requesting them;
+//! - sends one request at a time per host;
+//! - waits at least [`MIN_INTERVAL`] between requests to a host, or its `Crawl-delay` if
+//!   that's longer (up to [`MAX_CRAWL_DELAY`]).
+//!
+//! robots.txt is fetched once per host and kept for [`ROBOTS_TTL`]. As RFC 9309 says, a
+//! missing one (4xx) allows everything, while a server or network error fails the request,
+//! since the rules can't be known. Hosts are tracked process-wide so collectors built per
+//! job or per pass share them.
+
+use crate::{CollectorError, Result};
+use reqwest::{Client, RequestBuilder, Response, Url};
+use std::collections::HashMap;
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, warn};
+
+/// Shortest gap between two requests to the same host
+pub const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest `Crawl-delay` honoured; slower hosts would hold a pass up for hours
+pub const MAX_CRAWL_DELAY: Duration = Duration::from_secs(30);
+
+/// How long a host's robots.txt is kept before it's fetched again
+pub const ROBOTS_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Product token matched against robots.txt `User-agent` lines
+const ROBOTS_TOKEN: &str = "distrovitals";
+
+/// robots.txt bytes read; RFC 9309 lets crawlers ignore anything past 500 KiB
+const MAX_ROBOTS_BYTES: usize = 500 * 1024;
+
+static HOSTS: LazyLock<Mutex<HashMap<String, Arc<tokio::sync::Mutex<Host>>>>> = LazyLock::new(Default::default);
+
+/// What's known about one origin (scheme, host and port)
+#[derive(Debug, Default)]
+struct Host {
+    robots: Option<(Robots, Instant)>,
+    last_request: Option<Instant>,
+}
+
+/// Send a request politely: refused if robots.txt disallows it, otherwise paced and
+/// serialised with other requests to the same host
+///
+/// The host is held until the response's headers arrive, so bodies are read concurrently.
+pub async fn send(request: RequestBuilder) -> Result<Response> {
+    let (client, request) = request.build_split();
+    let request = request?;
+    let url = request.url().clone();
+    let origin = url.origin().ascii_serialization();
+
+    let host = HOSTS.lock().unwrap().entry(origin.clone()).or_default().clone();
+    let mut host = host.lock().await;
+
+    let stale = host.robots.as_ref().is_none_or(|(_, fetched)| fetched.elapsed() >= ROBOTS_TTL);
+    if stale {
+        host.pace(MIN_INTERVAL).await;
+        let robots = fetch_robots(&client, &url).await?;
+        host.robots = Some((robots, Instant::now()));
+    }
+    let Some((robots, _)) = &host.robots else {
+        unreachable!("robots.txt was just fetched");
+    };
+
+    let path = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    if !robots.allows(&path) {
+        debug!(url = %url, "Disallowed by robots.txt");
+        return Err(CollectorError::Disallowed { url: url.to_string() });
+    }
+
+    let interval = robots.crawl_delay.map_or(MIN_INTERVAL, |delay| delay.clamp(MIN_INTERVAL, MAX_CRAWL_DELAY));
+    host.pace(interval).await;
+    Ok(client.execute(request).await?)
+}
+
+impl Host {
+    /// Wait until `interval` has passed since the last request, then count one
+    async fn pace(&mut self, interval: Duration) {
+        if let Some(last) = self.last_request {
+            tokio::time::sleep_until(last + interval).await;
+        }
+        self.last_request = Some(Instant::now());
+    }
+}
+
+async fn fetch_robots(client: &Client, url: &Url) -> Result<Robots> {
+    let mut robots_url = url.clone();
+    robots_url.set_path("/robots.txt");
+    robots_url.set_query(None);
+    robots_url.set_fragment(None);
+
+    let response = client.get(robots_url.as_str()).send().await?;
+    let status = response.status();
+    if status.is_client_error() {
+        debug!(url = %robots_url, status = status.as_u16(), "No robots.txt, allowing everything");
+        return Ok(Robots::default());
+    }
+    if !status.is_success() {
+        warn!(url = %robots_url, status = status.as_u16(), "robots.txt unavailable, not fetching from host");
+        return Err(CollectorError::from_status("robots.txt", status, robots_url.as_str()));
+    }
+
+    let body = response.bytes().await?;
+    let body = &body[..body.len().min(MAX_ROBOTS_BYTES)];
+    Ok(Robots::parse(&String::from_utf8_lossy(body), ROBOTS_TOKEN))
+}
+
+/// One `Allow` or `Disallow` line
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    allow: bool,
+    pattern: String,
+}
+
+/// The rules of a robots.txt that apply to one user agent
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Robots {
+    rules: Vec<Rule>,
+    pub crawl_delay: Option<Duration>,
+}
+
+impl Robots {
+    /// Rules for `token` from a robots.txt: every group naming it, or else every `*` group
+    pub fn parse(text: &str, token: &str) -> Self {
+        let mut named = Robots::default();
+        let mut wildcard = Robots::default();
+        let mut any_named = false;
+        // Agents of the group being read, and whether its rules have started
+        let mut agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in text.lines() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            let Some((field, value)) = line.split_once(':') else {
+                continue;
+            };
+            let field = field.trim().to_ascii_lowercase();
+            let value = value.trim();
+
+            if field == "user-agent" {
+                if in_rules {
+                    agents.clear();
+                    in_rules = false;
+                }
+                agents.push(value.to_ascii_lowercase());
+                continue;
+            }
+            // Sitemap and other lines don't belong to a group
+            if !matches!(field.as_str(), "allow" | "disallow" | "crawl-delay") || agents.is_empty() {
+                continue;
+            }
+            in_rules = true;
+
+            let is_named = agents.iter().any(|agent| agent == token);
+            any_named |= is_named;
+            let target = if is_named {
+                &mut named
+            } else if agents.iter().any(|agent| agent == "*") {
+                &mut wildcard
+            } else {
+                continue;
+            };
+
+            match field.as_str() {
+                "crawl-delay" => {
+                    if let Some(secs) = value.parse::<f64>().ok().filter(|s| s.is_finite() && *s >= 0.0) {
+                        target.crawl_delay = Some(Duration::from_secs_f64(secs.min(MAX_CRAWL_DELAY.as_secs_f64())));
+                    }
+                }
+                // An empty Disallow allows everything, and an empty Allow says nothing
+                _ if value.is_empty() => {}
+                field => target.rules.push(Rule { allow: field == "allow", pattern: value.to_string() }),
+            }
+        }
+
+        if any_named {
+            named
+        } else {
+            wildcard
+        }
+    }
+
+    /// Whether a path (with its query) may be fetched: the longest matching rule decides,
+    /// with `Allow` winning ties
+    pub fn allows(&self, path: &str) -> bool {
+        let mut best: Option<&Rule> = None;
+        for rule in self.rules.iter().filter(|rule| matches(&rule.pattern, path)) {
+            let better = match best {
+                None => true,
+                Some(best) => {
+                    rule.pattern.len() > best.pattern.len()
+                        || (rule.pattern.len() == best.pattern.len() && rule.allow && !best.allow)
+                }
+            };
+            if better {
+                best = Some(rule);
+            }
+        }
+        best.is_none_or(|rule| rule.allow)
+    }
+}
+
+/// Whether a robots.txt path pattern matches the start of `path`; `*` matches any run of
+/// characters and a trailing `$` anchors the end
+fn matches(pattern: &str, path: &str) -> bool {
+    let (pattern, anchored) = match pattern.strip_suffix('$') {
+        Some(pattern) => (pattern, true),
+        None => (pattern, false),
+    };
+
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = path.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    for (i, part) in parts.iter().enumerate() {
+        // The last part of an anchored pattern has to end the path
+        if anchored && i == parts.len() - 1 {
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    !anchored || rest.is_empty()
+}
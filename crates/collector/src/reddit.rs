@@ -1,7 +1,8 @@
 //! Reddit API collector for community metrics
 
-use crate::{CollectorConfig, CollectorError, Result};
-use distrovitals_database::{Database, NewCommunitySnapshot};
+use crate::breaker;
+use crate::{provenance, CollectorConfig, CollectorError, Result};
+use distrovitals_database::{Database, NewCommunitySnapshot, Provenance};
 use reqwest::Client;
 use serde::Deserialize;
 use tracing::{debug, info, warn};
@@ -18,6 +19,7 @@ struct SubredditResponse {
 
 #[derive(Debug, Deserialize)]
 struct SubredditData {
+    #[allow(dead_code)]
     display_name: String,
     subscribers: i64,
     accounts_active: Option<i64>,
@@ -43,14 +45,16 @@ struct PostWrapper {
 #[derive(Debug, Deserialize)]
 struct PostData {
     created_utc: f64,
+    #[allow(dead_code)]
     num_comments: i64,
 }
 
 impl RedditCollector {
     /// Create a new Reddit collector
-    pub fn new(_config: CollectorConfig) -> Result<Self> {
+    pub fn new(config: CollectorConfig) -> Result<Self> {
         let client = Client::builder()
-            .user_agent("DistroVitals/0.1 (Linux distribution health tracker)")
+            .user_agent(&config.user_agent)
+            .timeout(config.request_timeout)
             .build()?;
 
         Ok(Self { client })
@@ -66,19 +70,17 @@ impl RedditCollector {
         info!(subreddit = subreddit, "Collecting Reddit metrics");
 
         // Get subreddit info
+        let mut provenance = provenance();
         let about_url = format!("https://www.reddit.com/r/{}/about.json", subreddit);
-        let response = self.client.get(&about_url).send().await?;
+        provenance.request(&about_url);
+        let response = breaker::REDDIT.send(self.client.get(&about_url)).await?;
 
         if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
             return Err(CollectorError::RateLimited(60));
         }
 
         if !response.status().is_success() {
-            return Err(CollectorError::Api(format!(
-                "Reddit API error: {} for r/{}",
-                response.status(),
-                subreddit
-            )));
+            return Err(CollectorError::from_status("reddit", response.status(), format!("r/{}", subreddit)));
         }
 
         let about: SubredditResponse = response.json().await?;
@@ -86,7 +88,13 @@ impl RedditCollector {
         let active_users = about.data.accounts_active.or(about.data.active_user_count);
 
         // Get recent posts to count activity
-        let posts_30d = self.count_recent_posts(subreddit, 30).await.unwrap_or(0);
+        let posts_30d = match self.count_recent_posts(subreddit, 30, &mut provenance).await {
+            Ok(count) => count,
+            Err(e) => {
+                provenance.fallback(format!("recent posts failed ({}) → 0", e));
+                0
+            }
+        };
 
         debug!(
             subreddit = subreddit,
@@ -102,6 +110,9 @@ impl RedditCollector {
             active_users_30d: Some(subscribers), // Using subscribers as proxy
             posts_30d: Some(posts_30d),
             response_time_avg_hours: None, // Could calculate from comment times
+            questions_30d: None,
+            answers_30d: None,
+            provenance,
         };
 
         let id = db.insert_community_snapshot(snapshot).await?;
@@ -111,15 +122,17 @@ impl RedditCollector {
     }
 
     /// Count posts in the last N days
-    async fn count_recent_posts(&self, subreddit: &str, days: i64) -> Result<i64> {
+    async fn count_recent_posts(&self, subreddit: &str, days: i64, provenance: &mut Provenance) -> Result<i64> {
         let url = format!(
             "https://www.reddit.com/r/{}/new.json?limit=100",
             subreddit
         );
 
-        let response = self.client.get(&url).send().await?;
+        provenance.request(&url);
+        let response = breaker::REDDIT.send(self.client.get(&url)).await?;
 
         if !response.status().is_success() {
+            provenance.fallback(format!("new posts {} → 0", response.status().as_u16()));
             return Ok(0);
         }
 
@@ -0,0 +1,280 @@
+//! Repology collector for package/ports freshness
+
+use crate::breaker;
+use crate::kernel::{kernel_series, KernelReleases};
+use crate::{provenance, CollectorConfig, CollectorError, Result};
+use distrovitals_database::{Database, NewPackageSnapshot, Provenance};
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use tracing::{debug, info, warn};
+
+/// Repology returns at most this many projects per page
+const PAGE_SIZE: usize = 200;
+
+/// Safety cap so a misconfigured repository can't page forever
+const MAX_PAGES: usize = 400;
+
+/// Repology API client
+pub struct RepologyCollector {
+    client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct PackageEntry {
+    repo: String,
+    status: String,
+    #[serde(default)]
+    version: String,
+}
+
+/// Kernel shipped by a repository and its upstream support status
+struct ShippedKernel {
+    version: String,
+    series: String,
+    status: String,
+    /// Newest kernel any repository ships
+    upstream: Option<String>,
+}
+
+/// A project's version in one repository and the newest version across all of them
+struct ProjectVersions {
+    shipped: Option<String>,
+    upstream: Option<String>,
+}
+
+impl RepologyCollector {
+    /// Create a new Repology collector
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .timeout(config.request_timeout)
+            .build()?;
+
+        Ok(Self { client })
+    }
+
+    /// Collect package freshness for a Repology repository
+    pub async fn collect_repository(
+        &self,
+        db: &Database,
+        distro_id: i64,
+        repository: &str,
+    ) -> Result<i64> {
+        let snapshot = self.package_snapshot(distro_id, repository).await?;
+        let id = db.insert_package_snapshot(snapshot).await?;
+        Ok(id)
+    }
+
+    /// Package freshness for a Repology repository, for callers adding their own counts
+    pub async fn package_snapshot(&self, distro_id: i64, repository: &str) -> Result<NewPackageSnapshot> {
+        info!(repository = repository, "Collecting Repology package metrics");
+
+        let mut total: i64 = 0;
+        let mut outdated: i64 = 0;
+        let mut start: Option<String> = None;
+        let mut provenance = provenance();
+
+        for _ in 0..MAX_PAGES {
+            let page = self.get_projects_page(repository, start.as_deref(), &mut provenance).await?;
+            let page_len = page.len();
+
+            for (name, packages) in &page {
+                // Pages are inclusive of the start project, skip it on follow-up pages
+                if start.as_deref() == Some(name.as_str()) {
+                    continue;
+                }
+
+                let ours: Vec<_> = packages.iter().filter(|p| p.repo == repository).collect();
+                if ours.is_empty() {
+                    continue;
+                }
+
+                total += 1;
+                if ours.iter().any(|p| p.status == "outdated") {
+                    outdated += 1;
+                }
+            }
+
+            if page_len < PAGE_SIZE {
+                break;
+            }
+
+            start = page.keys().next_back().cloned();
+
+            // Repology asks clients to stay at or below one request per second
+            tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        }
+
+        debug!(
+            repository = repository,
+            total = total,
+            outdated = outdated,
+            "Collected Repology metrics"
+        );
+
+        let kernel = match self.get_shipped_kernel(repository, &mut provenance).await {
+            Ok(kernel) => kernel,
+            Err(e) => {
+                warn!(repository = repository, error = %e, "Failed to determine shipped kernel");
+                provenance.fallback(format!("shipped kernel lookup failed ({}) → no kernel", e));
+                None
+            }
+        };
+
+        let mesa = match self.get_project_versions("mesa", repository, &mut provenance).await {
+            Ok(mesa) => mesa,
+            Err(e) => {
+                warn!(repository = repository, error = %e, "Failed to determine shipped Mesa");
+                provenance.fallback(format!("Mesa lookup failed ({}) → no Mesa", e));
+                ProjectVersions {
+                    shipped: None,
+                    upstream: None,
+                }
+            }
+        };
+
+        Ok(NewPackageSnapshot {
+            distro_id,
+            total_packages: total,
+            outdated_packages: outdated,
+            security_updates: 0,
+            kernel_version: kernel.as_ref().map(|k| k.version.clone()),
+            kernel_series: kernel.as_ref().map(|k| k.series.clone()),
+            kernel_upstream: kernel.as_ref().and_then(|k| k.upstream.clone()),
+            kernel_status: kernel.map(|k| k.status),
+            mesa_version: mesa.shipped,
+            mesa_upstream: mesa.upstream,
+            official_packages: None,
+            flagged_packages: None,
+            aur_packages: None,
+            provenance,
+        })
+    }
+
+    /// Collect package metrics for all distributions with a Repology repository
+    pub async fn collect_all(&self, db: &Database) -> Result<Vec<i64>> {
+        let distros = db.get_distributions().await?;
+        let mut snapshot_ids = Vec::new();
+
+        for distro in distros {
+            if let Some(ref repository) = distro.repology_repo {
+                match self.collect_repository(db, distro.id, repository).await {
+                    Ok(id) => snapshot_ids.push(id),
+                    Err(e) => warn!(
+                        distro = distro.slug,
+                        repository = repository,
+                        error = %e,
+                        "Failed to collect Repology metrics"
+                    ),
+                }
+            }
+        }
+
+        info!(count = snapshot_ids.len(), "Collected package snapshots");
+        Ok(snapshot_ids)
+    }
+
+    /// Find the newest Linux kernel a repository ships and classify its series
+    async fn get_shipped_kernel(
+        &self,
+        repository: &str,
+        provenance: &mut Provenance,
+    ) -> Result<Option<ShippedKernel>> {
+        let versions = self.get_project_versions("linux", repository, provenance).await?;
+
+        let Some(version) = versions.shipped else {
+            return Ok(None);
+        };
+        let Some(series) = kernel_series(&version) else {
+            return Ok(None);
+        };
+
+        provenance.request(KernelReleases::URL);
+        let releases = KernelReleases::fetch(&self.client).await?;
+        let status = releases.classify(&series).to_string();
+
+        debug!(repository = repository, version = %version, status = %status, "Found shipped kernel");
+
+        Ok(Some(ShippedKernel {
+            version,
+            series,
+            status,
+            upstream: versions.upstream,
+        }))
+    }
+
+    /// Find the newest version of a project a repository ships, and the newest any ships
+    async fn get_project_versions(
+        &self,
+        project: &str,
+        repository: &str,
+        provenance: &mut Provenance,
+    ) -> Result<ProjectVersions> {
+        let url = format!("https://repology.org/api/v1/project/{}", project);
+        provenance.request(&url);
+        let response = breaker::REPOLOGY.send(self.client.get(&url)).await?;
+
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status(
+                "repology",
+                response.status(),
+                format!("project {}", project),
+            ));
+        }
+
+        let packages: Vec<PackageEntry> = response.json().await?;
+
+        Ok(ProjectVersions {
+            shipped: newest_version(packages.iter().filter(|p| p.repo == repository)),
+            // Repology marks packages at the newest upstream version "newest"
+            upstream: newest_version(packages.iter().filter(|p| p.status == "newest")),
+        })
+    }
+
+    async fn get_projects_page(
+        &self,
+        repository: &str,
+        start: Option<&str>,
+        provenance: &mut Provenance,
+    ) -> Result<BTreeMap<String, Vec<PackageEntry>>> {
+        let url = match start {
+            Some(project) => format!(
+                "https://repology.org/api/v1/projects/{}/?inrepo={}",
+                project, repository
+            ),
+            None => format!("https://repology.org/api/v1/projects/?inrepo={}", repository),
+        };
+
+        // Pages differ only by their start project, so they're recorded as one endpoint
+        provenance.request("https://repology.org/api/v1/projects/");
+        let response = breaker::REPOLOGY.send(self.client.get(&url)).await?;
+
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(CollectorError::RateLimited(60));
+        }
+
+        if !response.status().is_success() {
+            return Err(CollectorError::from_status(
+                "repology",
+                response.status(),
+                format!("repository {}", repository),
+            ));
+        }
+
+        let page: BTreeMap<String, Vec<PackageEntry>> = response.json().await?;
+        Ok(page)
+    }
+}
+
+fn newest_version<'a>(packages: impl Iterator<Item = &'a PackageEntry>) -> Option<String> {
+    packages.map(|p| p.version.clone()).max_by_key(|v| version_key(v))
+}
+
+/// Numeric components of a version string, for ordering
+fn version_key(version: &str) -> Vec<u32> {
+    version
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|part| part.parse().ok())
+        .collect()
+}
@@ -0,0 +1,511 @@
+//! Registry of per-distro collection sources
+//!
+//! The CLI, the trigger endpoints and the job worker all pick sources by name from
+//! here, so adding a source means adding a [`Source`] variant rather than a new
+//! subcommand and handler.
+
+use crate::arch::{ArchCollector, ArchScope};
+use crate::breaker;
+use crate::bugzilla::{Bugzilla, BugzillaCollector};
+use crate::custom::HttpJsonCollector;
+use crate::debian::{DebianCollector, DEBIAN_SLUG};
+use crate::downloads::{DownloadCollector, IndexFormat};
+use crate::forge::{Forge, ForgeKind};
+use crate::forgejo::ForgejoCollector;
+use crate::github::GithubCollector;
+use crate::gitlab::GitlabCollector;
+use crate::mirrors::{MirrorCollector, MirrorListFormat};
+use crate::pagure::PagureCollector;
+use crate::reddit::RedditCollector;
+use crate::repology::RepologyCollector;
+use crate::{CollectorConfig, CollectorError, Result};
+use chrono::Utc;
+use distrovitals_database::{Database, Distribution};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::warn;
+
+/// How early before its TTL is up a source counts as stale
+const TTL_GRACE: Duration = Duration::from_secs(15 * 60);
+
+/// A source of per-distro data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Source {
+    Github,
+    Gitlab,
+    Forgejo,
+    Pagure,
+    Reddit,
+    Discussions,
+    Packages,
+    Governance,
+    Mirrors,
+    Downloads,
+    Archive,
+    Bugzilla,
+    Custom,
+}
+
+impl Source {
+    pub const ALL: &'static [Source] = &[
+        Self::Github,
+        Self::Gitlab,
+        Self::Forgejo,
+        Self::Pagure,
+        Self::Reddit,
+        Self::Discussions,
+        Self::Packages,
+        Self::Governance,
+        Self::Mirrors,
+        Self::Downloads,
+        Self::Archive,
+        Self::Bugzilla,
+        Self::Custom,
+    ];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|s| s.as_str() == name)
+    }
+
+    /// Parse a comma-separated list of source names, e.g. "github,reddit"
+    ///
+    /// Duplicates are dropped. Fails with the unknown names.
+    pub fn parse_list<S: AsRef<str>>(names: &[S]) -> std::result::Result<Vec<Self>, Vec<String>> {
+        let mut sources = Vec::new();
+        let mut unknown = Vec::new();
+        for name in names.iter().flat_map(|n| n.as_ref().split(',')).map(str::trim) {
+            if name.is_empty() {
+                continue;
+            }
+            match Self::parse(name) {
+                Some(source) if !sources.contains(&source) => sources.push(source),
+                Some(_) => {}
+                None => unknown.push(name.to_string()),
+            }
+        }
+        if unknown.is_empty() {
+            Ok(sources)
+        } else {
+            Err(unknown)
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Github => "github",
+            Self::Gitlab => "gitlab",
+            Self::Forgejo => "forgejo",
+            Self::Pagure => "pagure",
+            Self::Reddit => "reddit",
+            Self::Discussions => "discussions",
+            Self::Packages => "packages",
+            Self::Governance => "governance",
+            Self::Mirrors => "mirrors",
+            Self::Downloads => "downloads",
+            Self::Archive => "archive",
+            Self::Bugzilla => "bugzilla",
+            Self::Custom => "custom",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Github => "GitHub",
+            Self::Gitlab => "GitLab",
+            Self::Forgejo => "Forgejo",
+            Self::Pagure => "Pagure",
+            Self::Reddit => "Reddit",
+            Self::Discussions => "GitHub Discussions",
+            Self::Packages => "Packages",
+            Self::Governance => "Governance",
+            Self::Mirrors => "Mirrors",
+            Self::Downloads => "Downloads",
+            Self::Archive => "Archive",
+            Self::Bugzilla => "Bugzilla",
+            Self::Custom => "Custom metrics",
+        }
+    }
+
+    /// How long collected data stays fresh unless `DV_<SOURCE>_TTL` says otherwise
+    ///
+    /// Stars and commits move hourly but a GitHub pass over every distro costs thousands
+    /// of API calls, so forges default to the daemon's six-hour interval; the rest change
+    /// more slowly. Pagure lists every package in a namespace, so it's collected daily.
+    pub fn default_ttl(&self) -> Duration {
+        const HOUR: u64 = 60 * 60;
+        let hours = match self {
+            Self::Github | Self::Gitlab | Self::Forgejo => 6,
+            Self::Packages | Self::Archive | Self::Bugzilla => 12,
+            Self::Pagure | Self::Reddit | Self::Discussions | Self::Downloads | Self::Custom => 24,
+            Self::Governance | Self::Mirrors => 7 * 24,
+        };
+        Duration::from_secs(hours * HOUR)
+    }
+
+    /// Pause between distros when collecting this source for many of them
+    pub fn pacing(&self) -> Option<Duration> {
+        match self {
+            // Reddit rate limiting - be gentle
+            Self::Reddit => Some(Duration::from_secs(2)),
+            _ => None,
+        }
+    }
+
+    /// Comma-separated names of every source, for help and error messages
+    pub fn names() -> String {
+        Self::ALL.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")
+    }
+}
+
+impl std::fmt::Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What collecting one source for one distro produced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SourceOutcome {
+    /// Rows written
+    Collected(usize),
+    /// The distro isn't configured for this source
+    Skipped(&'static str),
+}
+
+/// One collector per source, built once and reused across distros
+pub struct SourceCollectors {
+    github: GithubCollector,
+    gitlab: GitlabCollector,
+    forgejo: ForgejoCollector,
+    pagure: PagureCollector,
+    reddit: RedditCollector,
+    repology: RepologyCollector,
+    arch: ArchCollector,
+    mirrors: MirrorCollector,
+    downloads: DownloadCollector,
+    debian: DebianCollector,
+    bugzilla: BugzillaCollector,
+    custom: HttpJsonCollector,
+    ttls: HashMap<Source, Duration>,
+    distro_budget: Duration,
+    run_deadline: Option<Duration>,
+}
+
+impl SourceCollectors {
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        Ok(Self {
+            github: GithubCollector::new(config.clone())?,
+            gitlab: GitlabCollector::new(config.clone())?,
+            forgejo: ForgejoCollector::new(config.clone())?,
+            pagure: PagureCollector::new(config.clone())?,
+            reddit: RedditCollector::new(config.clone())?,
+            mirrors: MirrorCollector::new(config.clone())?,
+            downloads: DownloadCollector::new(config.clone())?,
+            arch: ArchCollector::new(config.clone())?,
+            debian: DebianCollector::new(config.clone())?,
+            bugzilla: BugzillaCollector::new(config.clone())?,
+            custom: HttpJsonCollector::new(config.clone())?,
+            ttls: config.source_ttls.clone(),
+            distro_budget: config.distro_budget,
+            run_deadline: config.run_deadline,
+            repology: RepologyCollector::new(config)?,
+        })
+    }
+
+    /// When a pass starting now has to stop, if the config sets a run deadline
+    pub fn run_deadline(&self) -> Option<Instant> {
+        self.run_deadline.map(|limit| Instant::now() + limit)
+    }
+
+    /// Collect several sources for one distro within its time budget
+    ///
+    /// A source still running when the budget or `run_deadline` runs out fails with
+    /// [`CollectorError::TimedOut`], keeping the snapshots it already wrote; sources
+    /// not started by then are skipped.
+    pub async fn collect_distro(
+        &self,
+        db: &Database,
+        distro: &Distribution,
+        sources: &[Source],
+        run_deadline: Option<Instant>,
+    ) -> Vec<(Source, Result<SourceOutcome>)> {
+        let budget_end = Instant::now() + self.distro_budget;
+        let deadline = run_deadline.map_or(budget_end, |run_end| run_end.min(budget_end));
+
+        let mut results = Vec::with_capacity(sources.len());
+        for &source in sources {
+            let result = if Instant::now() >= deadline {
+                let reason = if run_deadline.is_some_and(|run_end| run_end <= budget_end) {
+                    "Run deadline reached"
+                } else {
+                    "Distro time budget used up"
+                };
+                warn!(distro = distro.slug, source = source.as_str(), "{}, skipping", reason);
+                Ok(SourceOutcome::Skipped(reason))
+            } else {
+                self.collect_until(db, distro, source, deadline).await
+            };
+            results.push((source, result));
+        }
+        results
+    }
+
+    /// Like [`Self::collect_distro`], but skips sources collected within their TTL
+    pub async fn collect_stale(
+        &self,
+        db: &Database,
+        distro: &Distribution,
+        sources: &[Source],
+        run_deadline: Option<Instant>,
+    ) -> Vec<(Source, Result<SourceOutcome>)> {
+        let last_collected = match db.get_last_successful_collections(distro.id).await {
+            Ok(last) => last,
+            Err(e) => {
+                warn!(distro = distro.slug, error = %e, "Failed to look up previous collections, collecting all");
+                HashMap::new()
+            }
+        };
+        let now = Utc::now();
+        let fresh = |source: &Source| {
+            let Some(&at) = last_collected.get(source.as_str()) else {
+                return false;
+            };
+            let ttl = self.ttls.get(source).copied().unwrap_or_else(|| source.default_ttl());
+            let age = (now - at).to_std().unwrap_or_default();
+            // Due a little early, so a pass started one TTL after the last doesn't skip it
+            // over the minutes the previous pass took to reach this distro
+            age + TTL_GRACE < ttl
+        };
+
+        let stale: Vec<Source> = sources.iter().copied().filter(|s| !fresh(s)).collect();
+        let mut collected = self.collect_distro(db, distro, &stale, run_deadline).await.into_iter();
+        sources
+            .iter()
+            .map(|&source| {
+                if stale.contains(&source) {
+                    collected.next().expect("one result per stale source")
+                } else {
+                    (source, Ok(SourceOutcome::Skipped("Collected within its TTL")))
+                }
+            })
+            .collect()
+    }
+
+    /// Collect one source for one distro within the distro time budget
+    pub async fn collect(&self, db: &Database, distro: &Distribution, source: Source) -> Result<SourceOutcome> {
+        self.collect_until(db, distro, source, Instant::now() + self.distro_budget)
+            .await
+    }
+
+    /// Collections that ran are recorded in `collection_runs` so the daemon knows which
+    /// distros have new data to analyze.
+    async fn collect_until(
+        &self,
+        db: &Database,
+        distro: &Distribution,
+        source: Source,
+        deadline: Instant,
+    ) -> Result<SourceOutcome> {
+        let started_at = Utc::now();
+        let started = Instant::now();
+        let result = match tokio::time::timeout_at(deadline, self.collect_source(db, distro, source)).await {
+            Ok(result) => result,
+            Err(_) => Err(CollectorError::TimedOut {
+                what: format!("{} collection for {}", source.label(), distro.slug),
+                after_secs: started.elapsed().as_secs(),
+            }),
+        };
+
+        let recorded = match &result {
+            Ok(SourceOutcome::Collected(count)) => {
+                db.record_collection_run(distro.id, source.as_str(), started_at, *count as i64, None)
+                    .await
+            }
+            Ok(SourceOutcome::Skipped(_)) => return result,
+            Err(e) => {
+                db.record_collection_run(distro.id, source.as_str(), started_at, 0, Some(&e.run_failure()))
+                    .await
+            }
+        };
+        if let Err(e) = recorded {
+            warn!(distro = distro.slug, source = source.as_str(), error = %e, "Failed to record collection run");
+        }
+        breaker::persist(db).await;
+
+        result
+    }
+
+    async fn collect_source(&self, db: &Database, distro: &Distribution, source: Source) -> Result<SourceOutcome> {
+        match source {
+            Source::Github => {
+                let Some(ref org) = distro.github_org else {
+                    return Ok(SourceOutcome::Skipped("No GitHub org configured"));
+                };
+                let mut collected = self.github.collect_org_repos(db, distro.id, org).await?.len();
+
+                // Don't fail the source for release or org profile errors
+                match self.github.collect_org_releases(db, distro.id, org).await {
+                    Ok(ids) => collected += ids.len(),
+                    Err(e) => warn!(distro = distro.slug, error = %e, "Failed to collect GitHub releases"),
+                }
+                match self.github.collect_org(db, distro.id, org).await {
+                    Ok(_) => collected += 1,
+                    Err(e) => warn!(distro = distro.slug, error = %e, "Failed to collect GitHub org profile"),
+                }
+                Ok(SourceOutcome::Collected(collected))
+            }
+            Source::Gitlab => {
+                let Some(ref group) = distro.gitlab_group else {
+                    return Ok(SourceOutcome::Skipped("No GitLab group configured"));
+                };
+                let forge = Forge::for_distro(distro, ForgeKind::Gitlab);
+                let collected = self.gitlab.collect_group_projects(db, distro.id, &forge, group).await?.len();
+                Ok(SourceOutcome::Collected(collected))
+            }
+            Source::Forgejo => {
+                let Some(ref org) = distro.forgejo_org else {
+                    return Ok(SourceOutcome::Skipped("No Forgejo org configured"));
+                };
+                let forge = Forge::for_distro(distro, ForgeKind::Forgejo);
+                let collected = self.forgejo.collect_org_repos(db, distro.id, &forge, org).await?.len();
+                Ok(SourceOutcome::Collected(collected))
+            }
+            Source::Pagure => {
+                let Some(ref namespace) = distro.pagure_namespace else {
+                    return Ok(SourceOutcome::Skipped("No Pagure namespace configured"));
+                };
+                let forge = Forge::for_distro(distro, ForgeKind::Pagure);
+                self.pagure.collect_namespace(db, distro.id, &forge, namespace).await?;
+                Ok(SourceOutcome::Collected(1))
+            }
+            Source::Reddit => {
+                let Some(ref subreddit) = distro.subreddit else {
+                    return Ok(SourceOutcome::Skipped("No subreddit configured"));
+                };
+                self.reddit.collect_subreddit(db, distro.id, subreddit).await?;
+                Ok(SourceOutcome::Collected(1))
+            }
+            Source::Discussions => {
+                let Some(ref repo) = distro.discussions_repo else {
+                    return Ok(SourceOutcome::Skipped("No GitHub Discussions repo configured"));
+                };
+                if !self.github.has_token() {
+                    return Ok(SourceOutcome::Skipped("GitHub Discussions need a GitHub token"));
+                }
+                self.github.collect_discussions(db, distro.id, repo).await?;
+                Ok(SourceOutcome::Collected(1))
+            }
+            Source::Packages => {
+                let Some(ref repository) = distro.repology_repo else {
+                    return Ok(SourceOutcome::Skipped("No Repology repository configured"));
+                };
+                let mut snapshot = self.repology.package_snapshot(distro.id, repository).await?;
+                if let Some(scope) = ArchScope::of(distro) {
+                    self.arch.add_counts(&mut snapshot, scope).await;
+                }
+                db.insert_package_snapshot(snapshot).await?;
+                Ok(SourceOutcome::Collected(1))
+            }
+            Source::Governance => {
+                let Some(ref org) = distro.github_org else {
+                    return Ok(SourceOutcome::Skipped("No GitHub org configured"));
+                };
+                self.github.collect_governance(db, distro.id, org).await?;
+                Ok(SourceOutcome::Collected(1))
+            }
+            Source::Mirrors => {
+                let Some(ref url) = distro.mirror_list_url else {
+                    return Ok(SourceOutcome::Skipped("No mirror list configured"));
+                };
+                let name = distro.mirror_list_format.as_deref().unwrap_or("text");
+                let format = MirrorListFormat::parse(name).ok_or_else(|| {
+                    CollectorError::Api(format!(
+                        "Unknown mirror list format {:?} (expected {})",
+                        name,
+                        MirrorListFormat::names()
+                    ))
+                })?;
+                self.mirrors.collect_mirror_list(db, distro.id, url, format).await?;
+                Ok(SourceOutcome::Collected(1))
+            }
+            Source::Downloads => {
+                if distro.iso_url.is_none() && distro.update_index_url.is_none() {
+                    return Ok(SourceOutcome::Skipped("No installer image or update index configured"));
+                }
+
+                // Either measurement is worth keeping without the other
+                let mut collected = 0;
+                let mut failure = None;
+                if let Some(ref url) = distro.iso_url {
+                    match self.downloads.collect_iso_size(db, distro.id, url).await {
+                        Ok(_) => collected += 1,
+                        Err(e) => {
+                            warn!(distro = distro.slug, error = %e, "Failed to collect installer image size");
+                            failure = Some(e);
+                        }
+                    }
+                }
+                if let Some(ref url) = distro.update_index_url {
+                    let name = distro.update_index_format.as_deref().unwrap_or_default();
+                    let format = IndexFormat::parse(name).ok_or_else(|| {
+                        CollectorError::Api(format!(
+                            "Unknown update index format {:?} (expected {})",
+                            name,
+                            IndexFormat::names()
+                        ))
+                    })?;
+                    match self.downloads.collect_update_payload(db, distro.id, url, format).await {
+                        Ok(_) => collected += 1,
+                        Err(e) => {
+                            warn!(distro = distro.slug, error = %e, "Failed to collect update payload");
+                            failure = Some(e);
+                        }
+                    }
+                }
+                match failure {
+                    Some(e) if collected == 0 => Err(e),
+                    _ => Ok(SourceOutcome::Collected(collected)),
+                }
+            }
+            Source::Archive => {
+                if distro.slug != DEBIAN_SLUG {
+                    return Ok(SourceOutcome::Skipped("No package archive collector for this distro"));
+                }
+                self.debian.collect_archive(db, distro.id).await?;
+                Ok(SourceOutcome::Collected(1))
+            }
+            Source::Bugzilla => {
+                let Some(ref base_url) = distro.bugzilla_url else {
+                    return Ok(SourceOutcome::Skipped("No Bugzilla configured"));
+                };
+                let bugzilla = Bugzilla {
+                    base_url,
+                    product: distro.bugzilla_product.as_deref(),
+                };
+                self.bugzilla.collect_bugs(db, distro.id, bugzilla).await?;
+                Ok(SourceOutcome::Collected(1))
+            }
+            Source::Custom => {
+                let mut collected = 0;
+                let mut failure = None;
+                for metric in self.custom.metrics_for(&distro.slug) {
+                    match self.custom.collect(db, distro.id, metric).await {
+                        Ok(_) => collected += 1,
+                        Err(e) => {
+                            warn!(distro = distro.slug, key = metric.key, error = %e, "Failed to collect custom metric");
+                            failure = Some(e);
+                        }
+                    }
+                }
+                match failure {
+                    Some(e) if collected == 0 => Err(e),
+                    None if collected == 0 => Ok(SourceOutcome::Skipped("No custom metrics configured")),
+                    _ => Ok(SourceOutcome::Collected(collected)),
+                }
+            }
+        }
+    }
+}
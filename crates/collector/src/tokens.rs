@@ -0,0 +1,183 @@
+//! A pool of GitHub tokens, rotated by remaining quota
+//!
+//! One token gets 5,000 core requests an hour, which a full collection of every distro
+//! can run through. With several configured, each request goes out under whichever
+//! token has the most quota left, and a token that runs dry is skipped until it resets.
+
+use crate::{CollectorError, Result};
+use chrono::{DateTime, Utc};
+use reqwest::header::HeaderMap;
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tokio::sync::OnceCell;
+use tracing::{debug, warn};
+
+const RATE_LIMIT_URL: &str = "https://api.github.com/rate_limit";
+
+#[derive(Debug, Clone)]
+struct TokenState {
+    token: String,
+    /// Core requests left; None until GitHub has told us
+    remaining: Option<u32>,
+    /// When the quota refills, as a Unix timestamp
+    reset: Option<u64>,
+}
+
+impl TokenState {
+    fn exhausted(&self, now: u64) -> bool {
+        self.remaining == Some(0) && self.reset.is_some_and(|reset| reset > now)
+    }
+
+    /// Quota to rank tokens by; unknown sorts ahead of known-low so it gets checked
+    fn available(&self, now: u64) -> u32 {
+        if self.exhausted(now) {
+            0
+        } else {
+            self.remaining.unwrap_or(u32::MAX)
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RateLimitResponse {
+    resources: RateLimitResources,
+}
+
+#[derive(Deserialize)]
+struct RateLimitResources {
+    core: RateLimitWindow,
+}
+
+#[derive(Deserialize)]
+struct RateLimitWindow {
+    remaining: u32,
+    reset: u64,
+}
+
+/// What's left of one token's quota, without the token itself
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenBudget {
+    /// Core requests left; None until GitHub has told us
+    pub remaining: Option<u32>,
+    pub resets_at: Option<DateTime<Utc>>,
+}
+
+/// GitHub tokens shared by a collector's requests
+#[derive(Debug)]
+pub struct TokenPool {
+    tokens: Mutex<Vec<TokenState>>,
+    checked: OnceCell<()>,
+}
+
+impl TokenPool {
+    pub fn new(tokens: &[String]) -> Self {
+        let tokens = tokens
+            .iter()
+            .map(|token| TokenState {
+                token: token.clone(),
+                remaining: None,
+                reset: None,
+            })
+            .collect();
+
+        Self {
+            tokens: Mutex::new(tokens),
+            checked: OnceCell::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tokens.lock().unwrap().is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tokens.lock().unwrap().len()
+    }
+
+    /// Ask GitHub for every token's quota, once per pool
+    ///
+    /// `/rate_limit` doesn't count against the quota. A token it rejects is dropped.
+    pub async fn check(&self, client: &Client) {
+        self.checked
+            .get_or_init(|| async {
+                let tokens: Vec<String> = self.tokens.lock().unwrap().iter().map(|t| t.token.clone()).collect();
+                for (index, token) in tokens.iter().enumerate() {
+                    let window = match client.get(RATE_LIMIT_URL).bearer_auth(token).send().await {
+                        Ok(response) if response.status() == reqwest::StatusCode::UNAUTHORIZED => {
+                            warn!(token = index + 1, "GitHub rejected token, leaving it out of the pool");
+                            self.tokens.lock().unwrap().retain(|t| t.token != *token);
+                            continue;
+                        }
+                        Ok(response) => response.json::<RateLimitResponse>().await.ok().map(|r| r.resources.core),
+                        Err(e) => {
+                            warn!(token = index + 1, error = %e, "Failed to check GitHub rate limit");
+                            None
+                        }
+                    };
+
+                    if let Some(window) = window {
+                        debug!(token = index + 1, remaining = window.remaining, "GitHub token quota");
+                        self.set(token, window.remaining, window.reset);
+                    }
+                }
+            })
+            .await;
+    }
+
+    /// The token with the most quota left, None when the pool is empty
+    ///
+    /// Fails with the wait until the first reset when every token is out of quota.
+    pub fn pick(&self) -> Result<Option<String>> {
+        let tokens = self.tokens.lock().unwrap();
+        let now = Utc::now().timestamp() as u64;
+
+        let Some(best) = tokens.iter().max_by_key(|t| t.available(now)) else {
+            return Ok(None);
+        };
+        if !best.exhausted(now) {
+            return Ok(Some(best.token.clone()));
+        }
+
+        let wait = tokens
+            .iter()
+            .filter_map(|t| t.reset)
+            .min()
+            .map_or(60, |reset| reset.saturating_sub(now));
+        Err(CollectorError::RateLimited(wait))
+    }
+
+    /// Each token's quota as last seen, in configuration order
+    pub fn budgets(&self) -> Vec<TokenBudget> {
+        self.tokens
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|t| TokenBudget {
+                remaining: t.remaining,
+                resets_at: t.reset.and_then(|reset| DateTime::from_timestamp(reset as i64, 0)),
+            })
+            .collect()
+    }
+
+    /// Update a token's quota from a response's rate limit headers
+    ///
+    /// Search and GraphQL responses report their own budgets (`x-ratelimit-resource`),
+    /// which say nothing about the core quota tracked here.
+    pub fn observe(&self, token: &str, headers: &HeaderMap) {
+        if headers.get("x-ratelimit-resource").is_some_and(|r| r != "core") {
+            return;
+        }
+        let header = |name: &str| headers.get(name)?.to_str().ok()?.parse::<u64>().ok();
+        if let (Some(remaining), Some(reset)) = (header("x-ratelimit-remaining"), header("x-ratelimit-reset")) {
+            self.set(token, remaining as u32, reset);
+        }
+    }
+
+    fn set(&self, token: &str, remaining: u32, reset: u64) {
+        if let Some(state) = self.tokens.lock().unwrap().iter_mut().find(|t| t.token == token) {
+            state.remaining = Some(remaining);
+            state.reset = Some(reset);
+        }
+    }
+}
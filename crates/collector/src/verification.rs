@@ -0,0 +1,84 @@
+//! Ownership verification for maintainer claims
+
+use crate::{CollectorConfig, CollectorError, Result};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION};
+use reqwest::{Client, Url};
+use serde::Deserialize;
+use tracing::debug;
+
+/// Well-known path a claimant publishes the challenge at for domain verification
+pub const DOMAIN_CHALLENGE_PATH: &str = "/.well-known/distrovitals-verify.txt";
+
+/// Repository a claimant creates in their GitHub org, with the challenge in its description
+pub const GITHUB_CHALLENGE_REPO: &str = "distrovitals-verify";
+
+/// Checks that a claimant controls a distro's homepage domain or GitHub org
+pub struct ClaimVerifier {
+    client: Client,
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoDescription {
+    description: Option<String>,
+}
+
+impl ClaimVerifier {
+    /// Create a new claim verifier
+    pub fn new(config: CollectorConfig) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static("application/vnd.github.v3+json"));
+
+        if let Some(token) = config.github_token() {
+            headers.insert(
+                AUTHORIZATION,
+                HeaderValue::from_str(&format!("Bearer {}", token)).unwrap(),
+            );
+        }
+
+        let client = Client::builder()
+            .user_agent(&config.user_agent)
+            .default_headers(headers)
+            .timeout(config.request_timeout)
+            .build()?;
+
+        Ok(Self { client })
+    }
+
+    /// Verify the challenge is published under the homepage's well-known path
+    pub async fn verify_domain(&self, homepage: &str, challenge: &str) -> Result<bool> {
+        let url = Url::parse(homepage)
+            .and_then(|u| u.join(DOMAIN_CHALLENGE_PATH))
+            .map_err(|e| CollectorError::Parse(format!("Invalid homepage {}: {}", homepage, e)))?;
+
+        debug!(url = %url, "Checking domain challenge");
+
+        let response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Ok(false);
+        }
+
+        let body = response.text().await?;
+        Ok(body.lines().any(|line| line.trim() == challenge))
+    }
+
+    /// Verify the challenge is in the description of the org's verification repository
+    pub async fn verify_github(&self, org: &str, challenge: &str) -> Result<bool> {
+        let url = format!(
+            "https://api.github.com/repos/{}/{}",
+            org, GITHUB_CHALLENGE_REPO
+        );
+
+        debug!(url = %url, "Checking GitHub challenge");
+
+        let response = self.client.get(&url).send().await?;
+        if !response.status().is_success() {
+            return Ok(false);
+        }
+
+        let repo: RepoDescription = response.json().await?;
+        Ok(repo
+            .description
+            .map(|d| d.contains(challenge))
+            .unwrap_or(false))
+    }
+}
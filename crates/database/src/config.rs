@@ -0,0 +1,56 @@
+//! Connection pool and SQLite tuning
+//!
+//! The defaults suit a single `dv serve` next to a daemon collecting into the same file.
+//! Busier deployments that see `database is locked` can raise the busy timeout or pool
+//! size through the environment without a rebuild.
+
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::warn;
+
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    /// Pooled connections, from `DV_DB_POOL_SIZE`
+    pub pool_size: u32,
+    /// How long a write waits on another connection's lock, from `DV_DB_BUSY_TIMEOUT` in
+    /// milliseconds
+    pub busy_timeout: Duration,
+    /// Page cache per connection in KiB, from `DV_DB_CACHE_SIZE`
+    pub cache_size_kib: u32,
+    /// Bytes of the file to memory-map, from `DV_DB_MMAP_SIZE`; 0 turns it off
+    pub mmap_size: u64,
+    /// How often long-running processes truncate the WAL, from `DV_DB_CHECKPOINT_INTERVAL`
+    /// in seconds
+    pub checkpoint_interval: Duration,
+    /// Replica of the database for the API to read from, e.g. kept up to date by
+    /// Litestream or LiteFS, from `DV_DB_READ_REPLICA`; writes always go to the primary
+    pub read_replica: Option<PathBuf>,
+}
+
+impl Default for DatabaseConfig {
+    fn default() -> Self {
+        Self {
+            pool_size: env_number("DV_DB_POOL_SIZE").filter(|&n| n > 0).unwrap_or(5),
+            busy_timeout: Duration::from_millis(env_number("DV_DB_BUSY_TIMEOUT").unwrap_or(5_000)),
+            cache_size_kib: env_number("DV_DB_CACHE_SIZE").unwrap_or(16 * 1024),
+            mmap_size: env_number("DV_DB_MMAP_SIZE").unwrap_or(256 * 1024 * 1024),
+            checkpoint_interval: Duration::from_secs(
+                env_number("DV_DB_CHECKPOINT_INTERVAL").filter(|&n| n > 0).unwrap_or(10 * 60),
+            ),
+            read_replica: std::env::var_os("DV_DB_READ_REPLICA")
+                .filter(|path| !path.is_empty())
+                .map(PathBuf::from),
+        }
+    }
+}
+
+/// A number from the environment, ignoring anything unparseable
+fn env_number<T: FromStr>(var: &str) -> Option<T> {
+    let value = std::env::var(var).ok()?;
+    let parsed = value.trim().parse().ok();
+    if parsed.is_none() {
+        warn!("Ignoring {}={:?}: expected a number", var, value);
+    }
+    parsed
+}
@@ -0,0 +1,388 @@
+//! Change events for downstream consumers
+//!
+//! Every snapshot insert, score calculation and milestone produces an [`Event`]. With the `events`
+//! feature enabled and a publisher attached, events are published to NATS or Redis
+//! pub/sub so warehouses and bots can react without polling the API.
+
+use serde::Serialize;
+
+/// Something that changed in the database
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event {
+    SnapshotInserted {
+        /// Snapshot table: github, release, package, community, upstream or metric
+        source: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        distro_id: Option<i64>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        upstream_id: Option<i64>,
+        snapshot_id: i64,
+    },
+    ScoreCalculated {
+        distro_id: i64,
+        score_id: i64,
+        overall_score: f64,
+        trend: String,
+    },
+    MilestoneReached {
+        distro_id: i64,
+        milestone_id: i64,
+        kind: String,
+        title: String,
+    },
+}
+
+impl Event {
+    /// Subject (NATS) or channel (Redis) suffix, appended to the publisher's prefix
+    pub fn topic(&self) -> String {
+        match self {
+            Self::SnapshotInserted { source, .. } => format!("snapshot.{}", source),
+            Self::ScoreCalculated { .. } => "score.calculated".to_string(),
+            Self::MilestoneReached { kind, .. } => format!("milestone.{}", kind),
+        }
+    }
+}
+
+#[cfg(feature = "events")]
+pub use publisher::EventPublisher;
+
+#[cfg(feature = "events")]
+mod publisher {
+    use super::Event;
+    use chrono::{DateTime, Utc};
+    use serde::Serialize;
+    use std::io;
+    use std::sync::Mutex;
+    use std::time::Duration;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufStream};
+    use tokio::net::TcpStream;
+    use tokio::sync::mpsc;
+    use tokio::task::JoinHandle;
+    use tokio::time::{timeout, Instant};
+    use tracing::{debug, info, warn};
+
+    /// Subject/channel prefix when the URL has no path
+    const DEFAULT_PREFIX: &str = "distrovitals";
+
+    /// Upper bound on connecting or publishing, so a dead broker can't stall the queue
+    const IO_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// Events waiting to be published; more than this are dropped rather than slowing writes
+    const QUEUE_SIZE: usize = 1024;
+
+    /// Wait after a failed connection before trying again, dropping events meanwhile
+    const RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+    /// Longest wait on close for queued events to go out
+    const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Broker {
+        Nats,
+        Redis,
+    }
+
+    #[derive(Serialize)]
+    struct Envelope<'a> {
+        at: DateTime<Utc>,
+        #[serde(flatten)]
+        event: &'a Event,
+    }
+
+    /// An event with when it happened
+    type Queued = (DateTime<Utc>, Event);
+
+    /// Where and how to connect
+    struct Target {
+        broker: Broker,
+        addr: String,
+        user: Option<String>,
+        password: Option<String>,
+        prefix: String,
+    }
+
+    /// Publishes events to a NATS server or Redis pub/sub over a single lazy connection
+    ///
+    /// Events are queued and published from a background task, so a slow or unreachable
+    /// broker never holds up the insert that produced them. Publishing is best effort:
+    /// failures are logged and the event is dropped, as are events that don't fit in the
+    /// queue.
+    pub struct EventPublisher {
+        queue: Mutex<Option<mpsc::Sender<Queued>>>,
+        task: Mutex<Option<JoinHandle<()>>>,
+    }
+
+    impl EventPublisher {
+        /// Parse `nats://[user:pass@]host[:port][/prefix]` or `redis://[:pass@]host[:port][/prefix]`
+        /// and start the publishing task, which needs a Tokio runtime
+        pub fn from_url(url: &str) -> io::Result<Self> {
+            let invalid = |msg: &str| io::Error::new(io::ErrorKind::InvalidInput, format!("{}: {}", msg, url));
+
+            let (broker, rest, default_port) = if let Some(rest) = url.strip_prefix("nats://") {
+                (Broker::Nats, rest, 4222)
+            } else if let Some(rest) = url.strip_prefix("redis://") {
+                (Broker::Redis, rest, 6379)
+            } else {
+                return Err(invalid("Event URL must start with nats:// or redis://"));
+            };
+
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            let (userinfo, host) = match authority.rsplit_once('@') {
+                Some((userinfo, host)) => (Some(userinfo), host),
+                None => (None, authority),
+            };
+            if host.is_empty() {
+                return Err(invalid("Event URL has no host"));
+            }
+
+            let (user, password) = match userinfo.map(|u| u.split_once(':').unwrap_or((u, ""))) {
+                Some((user, password)) => (
+                    Some(user.to_string()).filter(|u| !u.is_empty()),
+                    Some(password.to_string()).filter(|p| !p.is_empty()),
+                ),
+                None => (None, None),
+            };
+
+            let addr = if host.contains(':') {
+                host.to_string()
+            } else {
+                format!("{}:{}", host, default_port)
+            };
+
+            let prefix = path.trim_matches('/');
+            let prefix = if prefix.is_empty() { DEFAULT_PREFIX } else { prefix };
+
+            info!("Publishing events to {:?} at {} with prefix {}", broker, addr, prefix);
+
+            let target = Target {
+                broker,
+                addr,
+                user,
+                password,
+                prefix: prefix.to_string(),
+            };
+            let (sender, receiver) = mpsc::channel(QUEUE_SIZE);
+            let task = tokio::spawn(target.run(receiver));
+
+            Ok(Self {
+                queue: Mutex::new(Some(sender)),
+                task: Mutex::new(Some(task)),
+            })
+        }
+
+        /// Queue an event for publishing, dropping it if the queue is full or closed
+        pub fn publish(&self, event: &Event) {
+            let queue = self.queue.lock().unwrap_or_else(|e| e.into_inner());
+            let Some(sender) = queue.as_ref() else { return };
+            if let Err(e) = sender.try_send((Utc::now(), event.clone())) {
+                let reason = match e {
+                    mpsc::error::TrySendError::Full(_) => "queue full",
+                    mpsc::error::TrySendError::Closed(_) => "publisher stopped",
+                };
+                warn!("Dropped event {} ({})", event.topic(), reason);
+            }
+        }
+
+        /// Stop accepting events and wait briefly for queued ones to be published
+        pub async fn close(&self) {
+            self.queue.lock().unwrap_or_else(|e| e.into_inner()).take();
+            let task = self.task.lock().unwrap_or_else(|e| e.into_inner()).take();
+            if let Some(task) = task {
+                if timeout(CLOSE_TIMEOUT, task).await.is_err() {
+                    warn!("Gave up waiting for queued events to be published");
+                }
+            }
+        }
+    }
+
+    /// A live connection, with what's been read of the broker's next line
+    struct Connection {
+        stream: BufStream<TcpStream>,
+        line: Vec<u8>,
+    }
+
+    impl Target {
+        /// Publish queued events until the publisher closes
+        ///
+        /// Between events a NATS connection is read so the server's `PING`s are answered;
+        /// without a `PONG` it drops the connection as stale.
+        async fn run(self, mut queue: mpsc::Receiver<Queued>) {
+            let mut conn: Option<Connection> = None;
+            let mut retry_at: Option<Instant> = None;
+
+            loop {
+                let next = match conn.as_mut().filter(|_| self.broker == Broker::Nats) {
+                    Some(live) => tokio::select! {
+                        next = queue.recv() => next,
+                        read = live.stream.read_until(b'\n', &mut live.line) => {
+                            if let Err(e) = self.handle_server_line(live, read).await {
+                                debug!("Event broker connection closed: {}", e);
+                                conn = None;
+                            }
+                            continue;
+                        }
+                    },
+                    None => queue.recv().await,
+                };
+                let Some((at, event)) = next else { return };
+                self.publish(&mut conn, &mut retry_at, at, &event).await;
+            }
+        }
+
+        /// Act on a line the NATS server sent unprompted
+        async fn handle_server_line(&self, live: &mut Connection, read: io::Result<usize>) -> io::Result<()> {
+            if read? == 0 {
+                return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed"));
+            }
+            let line = String::from_utf8_lossy(&live.line).trim().to_string();
+            live.line.clear();
+
+            if line == "PING" {
+                timeout(IO_TIMEOUT, async {
+                    live.stream.write_all(b"PONG\r\n").await?;
+                    live.stream.flush().await
+                })
+                .await
+                .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "PONG timed out"))??;
+            } else if let Some(error) = line.strip_prefix("-ERR") {
+                return Err(io::Error::other(error.trim().to_string()));
+            }
+            // INFO updates, +OK and PONG need nothing
+            Ok(())
+        }
+
+        /// Publish one event, reconnecting once if the connection went stale
+        async fn publish(
+            &self,
+            conn: &mut Option<Connection>,
+            retry_at: &mut Option<Instant>,
+            at: DateTime<Utc>,
+            event: &Event,
+        ) {
+            let subject = format!("{}.{}", self.prefix, event.topic());
+            let payload = match serde_json::to_vec(&Envelope { at, event }) {
+                Ok(payload) => payload,
+                Err(e) => {
+                    warn!("Failed to serialize event {}: {}", subject, e);
+                    return;
+                }
+            };
+
+            for attempt in 0..2 {
+                if conn.is_none() {
+                    if retry_at.is_some_and(|at| Instant::now() < at) {
+                        debug!("Dropped {} while the event broker is unreachable", subject);
+                        return;
+                    }
+                    match timeout(IO_TIMEOUT, self.connect()).await {
+                        Ok(Ok(stream)) => {
+                            *conn = Some(Connection { stream, line: Vec::new() });
+                            *retry_at = None;
+                        }
+                        Ok(Err(e)) => {
+                            warn!("Failed to connect to event broker {}: {}", self.addr, e);
+                            *retry_at = Some(Instant::now() + RECONNECT_DELAY);
+                            return;
+                        }
+                        Err(_) => {
+                            warn!("Timed out connecting to event broker {}", self.addr);
+                            *retry_at = Some(Instant::now() + RECONNECT_DELAY);
+                            return;
+                        }
+                    }
+                }
+
+                let Some(live) = conn.as_mut() else { return };
+                let result = match timeout(IO_TIMEOUT, self.send(&mut live.stream, &subject, &payload)).await {
+                    Ok(result) => result,
+                    Err(_) => Err(io::Error::new(io::ErrorKind::TimedOut, "publish timed out")),
+                };
+
+                match result {
+                    Ok(()) => {
+                        debug!("Published {}", subject);
+                        return;
+                    }
+                    Err(e) => {
+                        *conn = None;
+                        if attempt == 1 {
+                            warn!("Failed to publish {}: {}", subject, e);
+                        }
+                    }
+                }
+            }
+        }
+
+        async fn connect(&self) -> io::Result<BufStream<TcpStream>> {
+            let mut stream = BufStream::new(TcpStream::connect(&self.addr).await?);
+
+            match self.broker {
+                Broker::Nats => {
+                    // The server greets with INFO; CONNECT must follow before any PUB
+                    let mut info = String::new();
+                    stream.read_line(&mut info).await?;
+                    if !info.starts_with("INFO") {
+                        return Err(io::Error::other(format!("unexpected greeting: {}", info.trim())));
+                    }
+
+                    let connect = serde_json::json!({
+                        "verbose": false,
+                        "pedantic": false,
+                        "name": "distrovitals",
+                        "user": self.user,
+                        "pass": self.password,
+                    });
+                    stream.write_all(format!("CONNECT {}\r\n", connect).as_bytes()).await?;
+                    stream.flush().await?;
+                }
+                Broker::Redis => {
+                    if let Some(password) = &self.password {
+                        let mut args = vec!["AUTH".as_bytes()];
+                        if let Some(user) = &self.user {
+                            args.push(user.as_bytes());
+                        }
+                        args.push(password.as_bytes());
+                        redis_command(&mut stream, &args).await?;
+                    }
+                }
+            }
+
+            Ok(stream)
+        }
+
+        async fn send(&self, stream: &mut BufStream<TcpStream>, subject: &str, payload: &[u8]) -> io::Result<()> {
+            match self.broker {
+                Broker::Nats => {
+                    stream
+                        .write_all(format!("PUB {} {}\r\n", subject, payload.len()).as_bytes())
+                        .await?;
+                    stream.write_all(payload).await?;
+                    stream.write_all(b"\r\n").await?;
+                    stream.flush().await
+                }
+                Broker::Redis => redis_command(stream, &[b"PUBLISH", subject.as_bytes(), payload]).await,
+            }
+        }
+    }
+
+    /// Send a RESP command and check the single-line reply for an error
+    async fn redis_command(stream: &mut BufStream<TcpStream>, args: &[&[u8]]) -> io::Result<()> {
+        stream.write_all(format!("*{}\r\n", args.len()).as_bytes()).await?;
+        for arg in args {
+            stream.write_all(format!("${}\r\n", arg.len()).as_bytes()).await?;
+            stream.write_all(arg).await?;
+            stream.write_all(b"\r\n").await?;
+        }
+        stream.flush().await?;
+
+        let mut reply = String::new();
+        if stream.read_line(&mut reply).await? == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "connection closed"));
+        }
+        match reply.strip_prefix('-') {
+            Some(error) => Err(io::Error::other(error.trim().to_string())),
+            None => Ok(()),
+        }
+    }
+}
@@ -2,12 +2,24 @@
 //!
 //! SQLite-based storage for distribution health metrics.
 
+mod config;
+pub mod events;
 mod models;
 mod queries;
 mod schema;
+mod seed;
+mod snapshot_query;
+mod synthetic;
+mod timestamp;
+mod writer;
 
+pub use config::DatabaseConfig;
 pub use models::*;
 pub use schema::Database;
+pub use seed::DEFAULT_SEED;
+pub use snapshot_query::{SnapshotKind, SnapshotQuery, SnapshotTable, SortOrder};
+pub use synthetic::{SyntheticHistory, SyntheticSummary};
+pub use timestamp::{parse_time_bound, to_sql_timestamp, TIMESTAMP_FORMAT};
 
 use thiserror::Error;
 
@@ -21,6 +33,9 @@ pub enum DatabaseError {
 
     #[error("Record not found: {0}")]
     NotFound(String),
+
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
 }
 
 pub type Result<T> = std::result::Result<T, DatabaseError>;
@@ -1,7 +1,9 @@
 //! Database models for DistroVitals
 
-use chrono::{DateTime, Utc};
+use crate::timestamp::to_sql_timestamp;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 /// A Linux distribution being tracked
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
@@ -12,12 +14,84 @@ pub struct Distribution {
     pub homepage: Option<String>,
     pub github_org: Option<String>,
     pub gitlab_group: Option<String>,
+    /// Organization on Codeberg, collected through the Forgejo API
+    pub forgejo_org: Option<String>,
+    /// Namespace of the distro's dist-git on Pagure, e.g. "rpms" for Fedora's packages
+    pub pagure_namespace: Option<String>,
+    /// Which of `gitlab_group`, `forgejo_org` and `pagure_namespace` lives on the distro's own
+    /// forge: "gitlab", "forgejo", "pagure"
+    pub forge_kind: Option<String>,
+    /// The self-hosted forge, used instead of gitlab.com, Codeberg or pagure.io for `forge_kind`
+    pub forge_base_url: Option<String>,
     pub subreddit: Option<String>,
+    /// GitHub repo ("owner/name") hosting the distro's Discussions
+    pub discussions_repo: Option<String>,
     pub description: Option<String>,
+    pub os_family: String, // "linux", "bsd", "haiku"
+    pub repology_repo: Option<String>,
+    pub verified: bool,
+    pub bot_accounts: Option<String>, // comma-separated extra bot logins
+    pub aggregation: Option<String>,  // "sum" (default), "mean", "top-k[:N]"
+    pub based_on: Option<String>,     // slug of the parent distro, NULL if independent
+    /// Official mirror list, parsed according to `mirror_list_format`
+    pub mirror_list_url: Option<String>,
+    pub mirror_list_format: Option<String>, // "archlinux", "metalink", "debian-masterlist", "text"
+    /// Current installer image, whose size is tracked
+    pub iso_url: Option<String>,
+    /// Package index of the main repository, for the monthly update payload
+    pub update_index_url: Option<String>,
+    pub update_index_format: Option<String>, // "pacman", "apk"
+    pub release_model: Option<String>,       // "rolling", "point"; NULL if unknown
+    /// Bugzilla instance the distro's bugs are filed in, e.g. "https://bugs.gentoo.org"
+    pub bugzilla_url: Option<String>,
+    /// Product the distro's bugs are filed under on `bugzilla_url`; all products if unset
+    pub bugzilla_product: Option<String>,
+    /// Slug of the distro this is an edition of (e.g. Silverblue of Fedora), sharing its code
+    /// and packages; NULL if it isn't a variant
+    pub variant_of: Option<String>,
+    /// Set when the distro was archived; archived distros are hidden but keep their history
+    pub archived_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// How a snapshot was fetched, so odd numbers can be traced back to their requests
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Collector crate and version that fetched it, e.g. "distrovitals-collector/0.1.0"
+    pub collector_version: String,
+    /// Endpoints requested, without query strings, in request order
+    pub endpoints: Vec<String>,
+    /// Fallback paths taken, e.g. "stats API 202 → commits fallback"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fallbacks: Vec<String>,
+    /// HTTP requests made, including repeats of the same endpoint
+    pub requests: u32,
+}
+
+impl Provenance {
+    pub fn new(collector_version: impl Into<String>) -> Self {
+        Self {
+            collector_version: collector_version.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Record a request to `url`
+    pub fn request(&mut self, url: &str) {
+        self.requests += 1;
+        let endpoint = url.split('?').next().unwrap_or(url);
+        if !self.endpoints.iter().any(|e| e == endpoint) {
+            self.endpoints.push(endpoint.to_string());
+        }
+    }
+
+    /// Record that a fallback path was taken
+    pub fn fallback(&mut self, note: impl Into<String>) {
+        self.fallbacks.push(note.into());
+    }
+}
+
 /// GitHub repository metrics snapshot
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct GithubSnapshot {
@@ -31,7 +105,15 @@ pub struct GithubSnapshot {
     pub commits_30d: i64,
     pub commits_365d: i64,
     pub contributors_30d: i64,
+    pub bot_commits_30d: i64,
+    pub bot_commits_365d: i64,
+    /// Mirror of upstream code (detected or overridden), excluded from aggregation
+    pub is_mirror: bool,
+    pub topics: Option<String>, // comma-separated GitHub topics
     pub last_commit_at: Option<DateTime<Utc>>,
+    /// How the snapshot was fetched; None for rows collected before provenance was recorded
+    #[sqlx(default)]
+    pub provenance: Option<sqlx::types::Json<Provenance>>,
     pub collected_at: DateTime<Utc>,
 }
 
@@ -43,6 +125,22 @@ pub struct PackageSnapshot {
     pub total_packages: i64,
     pub outdated_packages: i64,
     pub security_updates: i64,
+    pub kernel_version: Option<String>,
+    pub kernel_series: Option<String>,
+    pub kernel_status: Option<String>, // "mainline", "stable", "longterm", "eol"
+    /// Newest kernel any repository ships, i.e. upstream's latest at collection time
+    pub kernel_upstream: Option<String>,
+    pub mesa_version: Option<String>,
+    pub mesa_upstream: Option<String>,
+    /// Packages in Arch's official repos, counted by archlinux.org rather than Repology
+    pub official_packages: Option<i64>,
+    /// Official packages flagged out of date by users
+    pub flagged_packages: Option<i64>,
+    /// Packages in the AUR; set for Arch and its derivatives
+    pub aur_packages: Option<i64>,
+    /// How the snapshot was fetched; None for rows collected before provenance was recorded
+    #[sqlx(default)]
+    pub provenance: Option<sqlx::types::Json<Provenance>>,
     pub collected_at: DateTime<Utc>,
 }
 
@@ -55,9 +153,35 @@ pub struct CommunitySnapshot {
     pub active_users_30d: Option<i64>,
     pub posts_30d: Option<i64>,
     pub response_time_avg_hours: Option<f64>,
+    /// Posts asking a question, and how many of those got an accepted answer, for sources
+    /// that mark answers (GitHub Discussions)
+    pub questions_30d: Option<i64>,
+    pub answers_30d: Option<i64>,
+    /// How the snapshot was fetched; None for rows collected before provenance was recorded
+    #[sqlx(default)]
+    pub provenance: Option<sqlx::types::Json<Provenance>>,
     pub collected_at: DateTime<Utc>,
 }
 
+/// Input for creating a package snapshot
+#[derive(Debug, Clone)]
+pub struct NewPackageSnapshot {
+    pub distro_id: i64,
+    pub total_packages: i64,
+    pub outdated_packages: i64,
+    pub security_updates: i64,
+    pub kernel_version: Option<String>,
+    pub kernel_series: Option<String>,
+    pub kernel_status: Option<String>,
+    pub kernel_upstream: Option<String>,
+    pub mesa_version: Option<String>,
+    pub mesa_upstream: Option<String>,
+    pub official_packages: Option<i64>,
+    pub flagged_packages: Option<i64>,
+    pub aur_packages: Option<i64>,
+    pub provenance: Provenance,
+}
+
 /// Calculated health score for a distribution
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct HealthScore {
@@ -81,6 +205,8 @@ pub struct NewDistribution {
     pub gitlab_group: Option<String>,
     pub subreddit: Option<String>,
     pub description: Option<String>,
+    pub os_family: Option<String>,
+    pub repology_repo: Option<String>,
 }
 
 /// Input for creating a community snapshot
@@ -91,6 +217,192 @@ pub struct NewCommunitySnapshot {
     pub active_users_30d: Option<i64>,
     pub posts_30d: Option<i64>,
     pub response_time_avg_hours: Option<f64>,
+    pub questions_30d: Option<i64>,
+    pub answers_30d: Option<i64>,
+    pub provenance: Provenance,
+}
+
+/// Contributor-facing docs found across a distro's core repos
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct GovernanceSnapshot {
+    pub id: i64,
+    pub distro_id: i64,
+    pub repos_checked: i64,
+    /// Each item counts when any core repo has it, or the org publishes it as a default
+    pub contributing: bool,
+    pub code_of_conduct: bool,
+    pub security_policy: bool,
+    /// GOVERNANCE.md or similar, describing how decisions are made
+    pub governance: bool,
+    #[sqlx(default)]
+    pub provenance: Option<sqlx::types::Json<Provenance>>,
+    pub collected_at: DateTime<Utc>,
+}
+
+/// Input for creating a governance snapshot
+#[derive(Debug, Clone)]
+pub struct NewGovernanceSnapshot {
+    pub distro_id: i64,
+    pub repos_checked: i64,
+    pub contributing: bool,
+    pub code_of_conduct: bool,
+    pub security_policy: bool,
+    pub governance: bool,
+    pub provenance: Provenance,
+}
+
+/// Download mirrors listed in a distro's official mirror list
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct InfraSnapshot {
+    pub id: i64,
+    pub distro_id: i64,
+    /// Distinct mirror hosts, however many protocols each serves
+    pub mirrors: i64,
+    /// Countries the mirrors are in; None when the list doesn't say
+    pub mirror_countries: Option<i64>,
+    #[sqlx(default)]
+    pub provenance: Option<sqlx::types::Json<Provenance>>,
+    pub collected_at: DateTime<Utc>,
+}
+
+/// Input for creating an infrastructure snapshot
+#[derive(Debug, Clone)]
+pub struct NewInfraSnapshot {
+    pub distro_id: i64,
+    pub mirrors: i64,
+    pub mirror_countries: Option<i64>,
+    pub provenance: Provenance,
+}
+
+/// Bugs in a distro's Bugzilla
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BugtrackerSnapshot {
+    pub id: i64,
+    pub distro_id: i64,
+    pub open_bugs: i64,
+    /// Bugs filed / resolved over the last 30 days; None when the count failed
+    pub opened_30d: Option<i64>,
+    pub closed_30d: Option<i64>,
+    /// Mean days from filing to resolution of the bugs resolved over the last 30 days
+    pub mean_resolution_days: Option<f64>,
+    #[sqlx(default)]
+    pub provenance: Option<sqlx::types::Json<Provenance>>,
+    pub collected_at: DateTime<Utc>,
+}
+
+/// Input for creating a bug tracker snapshot
+#[derive(Debug, Clone)]
+pub struct NewBugtrackerSnapshot {
+    pub distro_id: i64,
+    pub open_bugs: i64,
+    pub opened_30d: Option<i64>,
+    pub closed_30d: Option<i64>,
+    pub mean_resolution_days: Option<f64>,
+    pub provenance: Provenance,
+}
+
+/// Profile of a distro's GitHub org, collected once per org however many repos it has
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OrgSnapshot {
+    pub id: i64,
+    pub distro_id: i64,
+    /// Login the profile was read under, after renames
+    pub org: String,
+    /// All public source and fork repos, not just the ones collected
+    pub public_repos: i64,
+    /// Members who made their membership public; None for user accounts
+    pub public_members: Option<i64>,
+    pub followers: i64,
+    /// GitHub Sponsors sponsoring the org, 0 without a Sponsors profile; None when not
+    /// measured (it needs a token)
+    pub sponsors: Option<i64>,
+    pub org_created_at: Option<DateTime<Utc>>,
+    #[sqlx(default)]
+    pub provenance: Option<sqlx::types::Json<Provenance>>,
+    pub collected_at: DateTime<Utc>,
+}
+
+/// Input for creating an org snapshot
+#[derive(Debug, Clone)]
+pub struct NewOrgSnapshot {
+    pub distro_id: i64,
+    pub org: String,
+    pub public_repos: i64,
+    pub public_members: Option<i64>,
+    pub followers: i64,
+    pub sponsors: Option<i64>,
+    pub org_created_at: Option<DateTime<Utc>>,
+    pub provenance: Provenance,
+}
+
+/// A distro's own package archive: its size, release-critical bugs and upload activity
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ArchiveSnapshot {
+    pub id: i64,
+    pub distro_id: i64,
+    pub source_packages: i64,
+    /// Open release-critical bugs; None when the bug tracker couldn't be read
+    pub rc_bugs: Option<i64>,
+    /// Source packages with a new version since the previous snapshot; None for the first
+    pub uploads: Option<i64>,
+    /// Uploads over the last 30 days, extrapolated while there is less history
+    pub uploads_30d: Option<i64>,
+    #[sqlx(default)]
+    pub provenance: Option<sqlx::types::Json<Provenance>>,
+    pub collected_at: DateTime<Utc>,
+}
+
+/// Input for creating an archive snapshot
+#[derive(Debug, Clone)]
+pub struct NewArchiveSnapshot {
+    pub distro_id: i64,
+    pub source_packages: i64,
+    pub rc_bugs: Option<i64>,
+    pub uploads: Option<i64>,
+    pub uploads_30d: Option<i64>,
+    pub provenance: Provenance,
+}
+
+/// Uploads counted by a distro's archive snapshots since some time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UploadHistory {
+    pub uploads: i64,
+    /// When the first archive snapshot was taken, which uploads are counted from
+    pub tracked_since: Option<DateTime<Utc>>,
+}
+
+/// Install base measured from opt-in pings
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct PopularitySnapshot {
+    pub id: i64,
+    pub distro_id: i64,
+    pub source: String, // "ping"
+    /// Distinct installs that pinged in the 30 days before collection
+    pub active_installs_30d: i64,
+    pub collected_at: DateTime<Utc>,
+}
+
+/// Input for creating a popularity snapshot
+#[derive(Debug, Clone)]
+pub struct NewPopularitySnapshot {
+    pub distro_id: i64,
+    pub source: String,
+    pub active_installs_30d: i64,
+}
+
+/// A distro's place in the rankings as archived for one day
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RankingSnapshot {
+    pub day: NaiveDate,
+    pub distro_id: i64,
+    pub slug: String,
+    pub name: String,
+    pub rank: i64,
+    pub overall_score: f64,
+    pub development_score: f64,
+    pub community_score: f64,
+    pub maintenance_score: f64,
+    pub trend: String,
 }
 
 /// Input for creating a GitHub snapshot
@@ -105,7 +417,20 @@ pub struct NewGithubSnapshot {
     pub commits_30d: i64,
     pub commits_365d: i64,
     pub contributors_30d: i64,
+    pub bot_commits_30d: i64,
+    pub bot_commits_365d: i64,
+    pub is_mirror: bool,
+    pub topics: Vec<String>,
+    /// (language, bytes) as reported by GitHub
+    pub languages: Vec<(String, i64)>,
+    /// (login, commits) of human contributors
+    pub contributors: Vec<(String, i64)>,
+    /// Open issues per label category; empty for repos outside the core set
+    pub issue_labels: Vec<(IssueCategory, i64)>,
+    /// Issues opened and closed in the last 30 days; None for repos outside the core set
+    pub issue_activity: Option<IssueActivity>,
     pub last_commit_at: Option<DateTime<Utc>>,
+    pub provenance: Provenance,
 }
 
 /// Input for creating a health score
@@ -117,6 +442,8 @@ pub struct NewHealthScore {
     pub community_score: f64,
     pub maintenance_score: f64,
     pub trend: String,
+    /// Identifies the snapshots and methodology the score was computed from
+    pub inputs_fingerprint: Option<String>,
 }
 
 /// Release snapshot from GitHub
@@ -129,9 +456,39 @@ pub struct ReleaseSnapshot {
     pub release_name: Option<String>,
     pub published_at: Option<DateTime<Utc>>,
     pub is_prerelease: bool,
+    /// Inferred from a git tag (dated by its commit) rather than a published GitHub Release
+    pub is_tag: bool,
+    /// Ships a detached signature (.asc, .sig, ...); None when there were no assets to check
+    pub signed: Option<bool>,
+    /// Ships a checksum file (SHA256SUMS, .sha256, ...); None when there were no assets to check
+    pub checksummed: Option<bool>,
+    /// Downloads summed over the release's assets; None when it has no assets
+    pub downloads: Option<i64>,
+    /// How the snapshot was fetched; None for rows collected before provenance was recorded
+    #[sqlx(default)]
+    pub provenance: Option<sqlx::types::Json<Provenance>>,
     pub collected_at: DateTime<Utc>,
 }
 
+/// Newest GitHub release seen for a repo
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ReleaseCursor {
+    pub distro_id: i64,
+    pub repo_name: String,
+    /// GitHub's release ID
+    pub release_id: i64,
+    pub published_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// A distro's release downloads as of one day
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct DownloadPoint {
+    pub date: NaiveDate,
+    /// Downloads summed over every release asset counted by that day
+    pub downloads: i64,
+}
+
 /// Input for creating a release snapshot
 #[derive(Debug, Clone)]
 pub struct NewReleaseSnapshot {
@@ -141,4 +498,721 @@ pub struct NewReleaseSnapshot {
     pub release_name: Option<String>,
     pub published_at: Option<DateTime<Utc>>,
     pub is_prerelease: bool,
+    pub is_tag: bool,
+    pub signed: Option<bool>,
+    pub checksummed: Option<bool>,
+    pub downloads: Option<i64>,
+    pub provenance: Provenance,
+}
+
+/// An upstream project (desktop environment, toolkit) that distributions build on
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Upstream {
+    pub id: i64,
+    pub name: String,
+    pub slug: String,
+    pub kind: String, // "desktop", ...
+    pub homepage: Option<String>,
+    pub github_org: Option<String>,
+    pub repo_prefix: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// GitHub repository metrics snapshot for an upstream project
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct UpstreamSnapshot {
+    pub id: i64,
+    pub upstream_id: i64,
+    pub repo_name: String,
+    pub stars: i64,
+    pub forks: i64,
+    pub open_issues: i64,
+    pub open_prs: i64,
+    pub commits_30d: i64,
+    pub contributors_30d: i64,
+    pub last_commit_at: Option<DateTime<Utc>>,
+    /// How the snapshot was fetched; None for rows collected before provenance was recorded
+    #[sqlx(default)]
+    pub provenance: Option<sqlx::types::Json<Provenance>>,
+    pub collected_at: DateTime<Utc>,
+}
+
+/// Input for creating an upstream snapshot
+#[derive(Debug, Clone)]
+pub struct NewUpstreamSnapshot {
+    pub upstream_id: i64,
+    pub repo_name: String,
+    pub stars: i64,
+    pub forks: i64,
+    pub open_issues: i64,
+    pub open_prs: i64,
+    pub commits_30d: i64,
+    pub contributors_30d: i64,
+    pub last_commit_at: Option<DateTime<Utc>>,
+    pub provenance: Provenance,
+}
+
+/// Human-written note explaining a change in a distribution's history
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Annotation {
+    pub id: i64,
+    pub distro_id: i64,
+    pub annotated_on: NaiveDate,
+    pub note: String,
+    pub author: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Input for creating an annotation
+#[derive(Debug, Clone)]
+pub struct NewAnnotation {
+    pub distro_id: i64,
+    pub annotated_on: NaiveDate,
+    pub note: String,
+    pub author: Option<String>,
+}
+
+/// Metadata changes to a distribution, by a maintainer or an admin; see [`Self::admin_only_fields`]
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct DistributionUpdate {
+    pub homepage: Option<String>,
+    pub description: Option<String>,
+    pub github_org: Option<String>,
+    pub gitlab_group: Option<String>,
+    pub forgejo_org: Option<String>,
+    pub pagure_namespace: Option<String>,
+    pub forge_kind: Option<String>,
+    pub forge_base_url: Option<String>,
+    pub subreddit: Option<String>,
+    pub discussions_repo: Option<String>,
+    pub bot_accounts: Option<String>,
+    pub aggregation: Option<String>,
+    pub based_on: Option<String>,
+    pub mirror_list_url: Option<String>,
+    pub mirror_list_format: Option<String>,
+    pub iso_url: Option<String>,
+    pub update_index_url: Option<String>,
+    pub update_index_format: Option<String>,
+    pub release_model: Option<String>,
+    pub bugzilla_url: Option<String>,
+    pub bugzilla_product: Option<String>,
+    pub variant_of: Option<String>,
+}
+
+impl DistributionUpdate {
+    /// Fields the update sets that only admins may change: how the distro is scored, the
+    /// servers the collectors fetch from, and the homepage and GitHub org a maintainer claim
+    /// was verified against
+    pub fn admin_only_fields(&self) -> Vec<&'static str> {
+        [
+            ("homepage", self.homepage.is_some()),
+            ("github_org", self.github_org.is_some()),
+            ("forge_kind", self.forge_kind.is_some()),
+            ("forge_base_url", self.forge_base_url.is_some()),
+            ("aggregation", self.aggregation.is_some()),
+            ("based_on", self.based_on.is_some()),
+            ("mirror_list_url", self.mirror_list_url.is_some()),
+            ("iso_url", self.iso_url.is_some()),
+            ("update_index_url", self.update_index_url.is_some()),
+            ("bugzilla_url", self.bugzilla_url.is_some()),
+            ("bugzilla_product", self.bugzilla_product.is_some()),
+            ("variant_of", self.variant_of.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(name, set)| set.then_some(name))
+        .collect()
+    }
+}
+
+/// A maintainer's claim on a distribution entry, pending verification
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Claim {
+    pub id: i64,
+    pub distro_id: i64,
+    pub method: String, // "domain", "github"
+    pub contact: String,
+    pub challenge: String,
+    pub status: String, // "pending", "verified", "rejected"
+    pub created_at: DateTime<Utc>,
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+/// Input for creating a claim
+#[derive(Debug, Clone)]
+pub struct NewClaim {
+    pub distro_id: i64,
+    pub method: String,
+    pub contact: String,
+    pub challenge: String,
+}
+
+/// A verified maintainer holding a distro-scoped API key
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Maintainer {
+    pub id: i64,
+    pub distro_id: i64,
+    pub claim_id: i64,
+    pub contact: String,
+    #[serde(skip_serializing)]
+    pub api_key_hash: String,
+    pub active: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A user-submitted proposal to track a new distribution
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Suggestion {
+    pub id: i64,
+    pub name: String,
+    pub slug: String,
+    pub homepage: Option<String>,
+    pub github_org: Option<String>,
+    pub subreddit: Option<String>,
+    pub description: Option<String>,
+    pub os_family: String,
+    pub reason: Option<String>,
+    pub status: String, // "pending", "approved", "rejected"
+    pub distro_id: Option<i64>,
+    #[serde(skip_serializing)]
+    pub client_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+/// Input for creating a suggestion
+#[derive(Debug, Clone)]
+pub struct NewSuggestion {
+    pub name: String,
+    pub slug: String,
+    pub homepage: Option<String>,
+    pub github_org: Option<String>,
+    pub subreddit: Option<String>,
+    pub description: Option<String>,
+    pub os_family: String,
+    pub reason: Option<String>,
+    pub client_hash: String,
+}
+
+/// A configured distro field that no longer resolves upstream
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ConfigIssue {
+    pub id: i64,
+    pub distro_id: i64,
+    pub distro_slug: String,
+    pub field: String, // "homepage", "github_org", "gitlab_group", "forgejo_org", "subreddit"
+    pub value: String,
+    pub problem: String,
+    pub first_seen_at: DateTime<Utc>,
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// A former GitHub org name and the name it was renamed to
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct OrgAlias {
+    pub old_name: String,
+    pub canonical_name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Rows rewritten when stitching a renamed org's history together
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OrgRename {
+    pub distributions: u64,
+    pub upstreams: u64,
+    pub github_snapshots: u64,
+    pub release_snapshots: u64,
+}
+
+/// A manual mirror/source classification for one of a distro's repos
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RepoOverride {
+    pub distro_id: i64,
+    pub repo_name: String,
+    pub is_mirror: bool,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Bytes of code in a language across a distro's repos
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct LanguageTotal {
+    pub language: String,
+    pub bytes: i64,
+    pub repos: i64,
+}
+
+/// What an issue label says an issue is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueCategory {
+    Security,
+    Bug,
+    Packaging,
+    Feature,
+}
+
+impl IssueCategory {
+    pub const ALL: [IssueCategory; 4] = [Self::Security, Self::Bug, Self::Packaging, Self::Feature];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Security => "security",
+            Self::Bug => "bug",
+            Self::Packaging => "packaging",
+            Self::Feature => "feature",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_str() == value)
+    }
+}
+
+/// Open issues in one label category across a distro's core repos
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct IssueLabelCount {
+    pub category: String,
+    pub open_issues: i64,
+    pub repos: i64,
+}
+
+/// Issues opened and closed over the last 30 days
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, sqlx::FromRow)]
+pub struct IssueActivity {
+    pub opened_30d: i64,
+    /// Every closure, including those by stale bots
+    pub closed_30d: i64,
+    /// Closures by a bot for inactivity rather than by a person resolving the issue
+    pub stale_closed_30d: i64,
+}
+
+impl IssueActivity {
+    /// Issues closed per issue opened, in percent; None when nothing was opened
+    pub fn resolution_pct(&self) -> Option<f64> {
+        (self.opened_30d > 0).then(|| self.closed_30d as f64 / self.opened_30d as f64 * 100.0)
+    }
+
+    /// Like [`resolution_pct`](Self::resolution_pct), not counting stale-bot closures
+    pub fn adjusted_resolution_pct(&self) -> Option<f64> {
+        let resolved = (self.closed_30d - self.stale_closed_30d).max(0);
+        (self.opened_30d > 0).then(|| resolved as f64 / self.opened_30d as f64 * 100.0)
+    }
+}
+
+/// Contributors two distros have in common
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ContributorOverlap {
+    pub distro_a: String,
+    pub distro_a_name: String,
+    pub distro_b: String,
+    pub distro_b_name: String,
+    /// Logins contributing to repos of both
+    pub shared: i64,
+    pub contributors_a: i64,
+    pub contributors_b: i64,
+    /// Shared contributors over contributors to either (Jaccard index, 0-1)
+    pub similarity: f64,
+}
+
+/// Raw GitHub and Reddit sums for a distro at a point in time
+#[derive(Debug, Clone, Default, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MetricTotals {
+    pub stars: i64,
+    pub forks: i64,
+    pub commits_30d: i64,
+    pub contributors_30d: i64,
+    pub open_issues: i64,
+    pub open_prs: i64,
+    pub reddit_subscribers: i64,
+    pub reddit_posts_30d: i64,
+}
+
+/// A time range plus keyset cursor for paging through history
+#[derive(Debug, Clone)]
+pub struct TimeWindow {
+    pub since: DateTime<Utc>,
+    pub until: Option<DateTime<Utc>>,
+    /// Return rows after this ID (the previous page's last row)
+    pub after_id: Option<i64>,
+    pub limit: i64,
+}
+
+impl TimeWindow {
+    /// `since` in the stored timestamp format
+    pub fn since_sql(&self) -> String {
+        to_sql_timestamp(self.since)
+    }
+
+    /// `until` in the stored timestamp format, open-ended when unset
+    pub fn until_sql(&self) -> String {
+        self.until
+            .map(to_sql_timestamp)
+            .unwrap_or_else(|| "9999-12-31T23:59:59Z".to_string())
+    }
+}
+
+/// Bucket size for downsampled history
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    Daily,
+    Weekly,
+    Monthly,
+}
+
+impl Resolution {
+    /// Parse "daily", "weekly" or "monthly"
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "daily" => Some(Self::Daily),
+            "weekly" => Some(Self::Weekly),
+            "monthly" => Some(Self::Monthly),
+            _ => None,
+        }
+    }
+
+    /// Name as used in query parameters
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Daily => "daily",
+            Self::Weekly => "weekly",
+            Self::Monthly => "monthly",
+        }
+    }
+
+    /// Start date of the bucket containing `date`, matching `bucket_sql`
+    pub fn bucket_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Daily => date,
+            Self::Weekly => date - chrono::Days::new(date.weekday().num_days_from_monday() as u64),
+            Self::Monthly => date.with_day(1).unwrap_or(date),
+        }
+    }
+
+    /// Start date of the bucket after the one starting at `start`
+    pub fn next_bucket(&self, start: NaiveDate) -> NaiveDate {
+        match self {
+            Self::Daily => start + chrono::Days::new(1),
+            Self::Weekly => start + chrono::Days::new(7),
+            Self::Monthly => start + chrono::Months::new(1),
+        }
+    }
+
+    /// SQLite expression for the start date of the bucket containing `column`
+    pub(crate) fn bucket_sql(&self, column: &str) -> String {
+        match self {
+            Self::Daily => format!("date({})", column),
+            // Weeks start on Monday
+            Self::Weekly => format!("date({}, 'weekday 0', '-6 days')", column),
+            Self::Monthly => format!("date({}, 'start of month')", column),
+        }
+    }
+}
+
+/// Health scores aggregated over one time bucket
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct HealthScoreBucket {
+    /// First day of the bucket
+    pub bucket: NaiveDate,
+    pub samples: i64,
+    pub overall_avg: f64,
+    pub overall_min: f64,
+    pub overall_max: f64,
+    pub development_avg: f64,
+    pub development_min: f64,
+    pub development_max: f64,
+    pub community_avg: f64,
+    pub community_min: f64,
+    pub community_max: f64,
+    pub maintenance_avg: f64,
+    pub maintenance_min: f64,
+    pub maintenance_max: f64,
+}
+
+/// GitHub activity over one time bucket: each non-mirror repo's last snapshot in the
+/// bucket, summed across repos
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CommitActivityBucket {
+    /// First day of the bucket
+    pub bucket: NaiveDate,
+    pub commits_30d: i64,
+    pub contributors_30d: i64,
+}
+
+/// A featured distro picked for the homepage
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Highlight {
+    pub id: i64,
+    pub distro_id: i64,
+    pub distro_slug: String,
+    pub distro_name: String,
+    pub reason: String, // "milestone", "newly_tracked", "improver", "top_ranked"
+    pub blurb: String,
+    pub overall_score: f64,
+    /// Change in overall score over the past week, when there is a previous score
+    pub score_change: Option<f64>,
+    pub selected_at: DateTime<Utc>,
+}
+
+/// Input for recording a highlight
+#[derive(Debug, Clone)]
+pub struct NewHighlight {
+    pub distro_id: i64,
+    pub reason: String,
+    pub blurb: String,
+    pub overall_score: f64,
+    pub score_change: Option<f64>,
+}
+
+/// A notable moment in a distro's history, detected during analysis
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct Milestone {
+    pub id: i64,
+    pub distro_id: i64,
+    pub distro_slug: String,
+    pub kind: String, // "score_threshold", "stars", "release_anniversary"
+    pub title: String,
+    pub value: Option<f64>,
+    pub occurred_at: DateTime<Utc>,
+}
+
+/// A period during which a distro's score, or every score, isn't recalculated
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct ScoreFreeze {
+    pub id: i64,
+    /// None when the freeze covers every distro
+    pub distro_id: Option<i64>,
+    pub reason: String,
+    pub starts_at: DateTime<Utc>,
+    /// None while the freeze lasts until it's lifted
+    pub ends_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Input for starting a score freeze
+#[derive(Debug, Clone)]
+pub struct NewScoreFreeze {
+    pub distro_id: Option<i64>,
+    pub reason: String,
+    pub ends_at: Option<DateTime<Utc>>,
+}
+
+/// Input for recording a milestone
+#[derive(Debug, Clone)]
+pub struct NewMilestone {
+    pub distro_id: i64,
+    pub kind: String,
+    /// Identifies the milestone so re-running analysis doesn't record it twice
+    pub key: String,
+    pub title: String,
+    pub value: Option<f64>,
+}
+
+/// Key of a value in the generic `metrics` table
+///
+/// Known keys have a fixed meaning and unit; anything else lands as [`MetricKey::Custom`]
+/// so experimental collectors can store data before a key is settled on.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum MetricKey {
+    /// Size of the default installation image, in megabytes
+    IsoSizeMb,
+    /// Packages in the main repository built in the last 30 days, in compressed megabytes: what
+    /// a month of updates downloads on a system with all of them installed
+    UpdatePayloadMb30d,
+    /// Kernel/Mesa releases upstream had out when a point release's kernel arrived that its
+    /// installer still lacks (derived by the analyzer)
+    HardwareEnablementLag,
+    /// Number of public download mirrors
+    MirrorCount,
+    /// Contributors whose first commit landed in the last 30 days
+    FirstTimeContributors30d,
+    /// Stars gained over the last 30 days by repos tracked throughout (derived by the analyzer)
+    StarGrowth30d,
+    /// Subreddit subscribers gained over the last 30 / 90 days (derived by the analyzer)
+    SubscriberGrowth30d,
+    SubscriberGrowth90d,
+    /// Reddit subscribers plus open issues per maintainer active in the last 30 days
+    /// (derived by the analyzer)
+    MaintainerLoad,
+    Custom(String),
+}
+
+impl MetricKey {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::IsoSizeMb => "iso_size_mb",
+            Self::UpdatePayloadMb30d => "update_payload_mb_30d",
+            Self::HardwareEnablementLag => "hardware_enablement_lag",
+            Self::MirrorCount => "mirror_count",
+            Self::FirstTimeContributors30d => "first_time_contributors_30d",
+            Self::StarGrowth30d => "star_growth_30d",
+            Self::SubscriberGrowth30d => "subscriber_growth_30d",
+            Self::SubscriberGrowth90d => "subscriber_growth_90d",
+            Self::MaintainerLoad => "maintainer_load",
+            Self::Custom(key) => key,
+        }
+    }
+
+    /// Whether the metric describes a distro's code and packages, which its variants share
+    /// rather than have their own
+    pub fn is_upstream(&self) -> bool {
+        matches!(
+            self,
+            Self::UpdatePayloadMb30d | Self::HardwareEnablementLag | Self::MirrorCount | Self::FirstTimeContributors30d
+        )
+    }
+}
+
+impl From<String> for MetricKey {
+    fn from(key: String) -> Self {
+        match key.as_str() {
+            "iso_size_mb" => Self::IsoSizeMb,
+            "update_payload_mb_30d" => Self::UpdatePayloadMb30d,
+            "hardware_enablement_lag" => Self::HardwareEnablementLag,
+            "mirror_count" => Self::MirrorCount,
+            "first_time_contributors_30d" => Self::FirstTimeContributors30d,
+            "star_growth_30d" => Self::StarGrowth30d,
+            "subscriber_growth_30d" => Self::SubscriberGrowth30d,
+            "subscriber_growth_90d" => Self::SubscriberGrowth90d,
+            "maintainer_load" => Self::MaintainerLoad,
+            _ => Self::Custom(key),
+        }
+    }
+}
+
+impl From<MetricKey> for String {
+    fn from(key: MetricKey) -> Self {
+        match key {
+            MetricKey::Custom(key) => key,
+            known => known.as_str().to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for MetricKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// One value from the generic `metrics` table
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MetricValue {
+    pub id: i64,
+    pub distro_id: i64,
+    pub source: String, // collector that produced the value
+    #[sqlx(try_from = "String")]
+    pub key: MetricKey,
+    pub value: f64,
+    pub collected_at: DateTime<Utc>,
+}
+
+/// Input for recording a generic metric value
+#[derive(Debug, Clone)]
+pub struct NewMetricValue {
+    pub distro_id: i64,
+    pub source: String,
+    pub key: MetricKey,
+    pub value: f64,
+}
+
+/// A queued collection of one source for one distribution
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CollectionJob {
+    pub id: i64,
+    pub distro_id: i64,
+    pub distro_slug: String,
+    /// "github", "gitlab", "forgejo", "pagure", "reddit", "discussions", "packages", "governance",
+    /// "mirrors", "downloads", "archive", "custom", "bugzilla"
+    pub source: String,
+    pub status: String, // "queued", "running", "done", "skipped", "failed"
+    /// Failure message, or why the job was skipped
+    pub error: Option<String>,
+    /// Runs so far, counting retries after transient failures
+    pub attempts: i64,
+    /// Higher runs first; public refreshes queue below admin and maintainer jobs
+    pub priority: i64,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Why a collection run failed, as recorded in the run log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunFailure {
+    pub message: String,
+    /// Error class, e.g. "not_found", "auth_failed" or "upstream"
+    pub kind: String,
+    /// Whether the same collection could succeed if tried again later
+    pub retryable: bool,
+}
+
+/// State of an upstream service's circuit breaker
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct BreakerStatus {
+    pub service: String,
+    pub state: String, // "closed", "open", "half-open"
+    pub consecutive_failures: i64,
+    pub opened_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Collection jobs waiting or in progress
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct QueueDepth {
+    pub queued: i64,
+    pub running: i64,
+}
+
+/// How current the data is: when each source and the scores were last brought up to date
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DataFreshness {
+    /// When each source last finished a successful collection for any distro
+    pub collected_at: BTreeMap<String, DateTime<Utc>>,
+    /// When a health score was last calculated
+    pub scored_at: Option<DateTime<Utc>>,
+}
+
+/// How one source's collections have gone recently
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct CollectionRunStats {
+    pub source: String,
+    pub last_run_at: Option<DateTime<Utc>>,
+    /// Error of the most recent run, None when it succeeded
+    pub last_error: Option<String>,
+    pub runs: i64,
+    pub failures: i64,
+}
+
+/// A distro whose collections of one source keep failing
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct RepeatedFailure {
+    pub distro_slug: String,
+    pub distro_name: String,
+    pub source: String,
+    /// Failed runs since the last successful one
+    pub consecutive_failures: i64,
+    pub last_error: Option<String>,
+    pub last_error_kind: Option<String>,
+    /// Whether the latest failure looked transient; None for runs logged before this was recorded
+    pub retryable: Option<bool>,
+    pub last_failed_at: DateTime<Utc>,
+}
+
+/// Where one source stands for a distro: its latest run, and its latest successful one
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SourceRunStatus {
+    pub source: String,
+    pub last_run_at: DateTime<Utc>,
+    /// Error of the latest run, None when it succeeded
+    pub last_error: Option<String>,
+    pub last_error_kind: Option<String>,
+    pub last_success_at: Option<DateTime<Utc>>,
+}
+
+/// One entry in the scoring methodology changelog
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct MethodologyChange {
+    pub version: String,
+    pub summary: String,
+    pub changed_at: DateTime<Utc>,
 }
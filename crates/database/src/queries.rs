@@ -1,17 +1,43 @@
 //! Database query functions
 
+use crate::events::Event;
 use crate::models::*;
 use crate::schema::Database;
+use crate::snapshot_query::SnapshotQuery;
+use crate::timestamp::to_sql_timestamp;
+use crate::writer::Write;
 use crate::{DatabaseError, Result};
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
+use std::collections::HashMap;
+
+/// Priority of jobs queued by public refresh requests, below the default of 0
+const REFRESH_PRIORITY: i64 = -10;
 
 impl Database {
     // ==================== Distributions ====================
 
-    /// Get all distributions
+    /// Get all active distributions (archived ones are hidden)
     pub async fn get_distributions(&self) -> Result<Vec<Distribution>> {
         let rows = sqlx::query_as::<_, Distribution>(
-            "SELECT id, name, slug, homepage, github_org, gitlab_group, subreddit, description,
-                    datetime(created_at) as created_at, datetime(updated_at) as updated_at
+            "SELECT id, name, slug, homepage, github_org, gitlab_group, forgejo_org, pagure_namespace, forge_kind, forge_base_url,
+                    subreddit, discussions_repo, description, os_family, repology_repo, verified, bot_accounts, aggregation, based_on,
+                    mirror_list_url, mirror_list_format, iso_url, update_index_url, update_index_format,
+                    release_model, bugzilla_url, bugzilla_product, variant_of, archived_at, created_at, updated_at
+             FROM distributions WHERE archived_at IS NULL ORDER BY name",
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Get all distributions, archived ones included
+    pub async fn get_distributions_including_archived(&self) -> Result<Vec<Distribution>> {
+        let rows = sqlx::query_as::<_, Distribution>(
+            "SELECT id, name, slug, homepage, github_org, gitlab_group, forgejo_org, pagure_namespace, forge_kind, forge_base_url,
+                    subreddit, discussions_repo, description, os_family, repology_repo, verified, bot_accounts, aggregation, based_on,
+                    mirror_list_url, mirror_list_format, iso_url, update_index_url, update_index_format,
+                    release_model, bugzilla_url, bugzilla_product, variant_of, archived_at, created_at, updated_at
              FROM distributions ORDER BY name",
         )
         .fetch_all(self.pool())
@@ -20,11 +46,44 @@ impl Database {
         Ok(rows)
     }
 
-    /// Get a distribution by slug
+    /// Active variants of a distribution
+    pub async fn get_variants(&self, slug: &str) -> Result<Vec<Distribution>> {
+        let rows = sqlx::query_as::<_, Distribution>(
+            "SELECT id, name, slug, homepage, github_org, gitlab_group, forgejo_org, pagure_namespace, forge_kind, forge_base_url,
+                    subreddit, discussions_repo, description, os_family, repology_repo, verified, bot_accounts, aggregation, based_on,
+                    mirror_list_url, mirror_list_format, iso_url, update_index_url, update_index_format,
+                    release_model, bugzilla_url, bugzilla_product, variant_of, archived_at, created_at, updated_at
+             FROM distributions WHERE variant_of = ? AND archived_at IS NULL ORDER BY name",
+        )
+        .bind(slug)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Get an active distribution by slug
     pub async fn get_distribution_by_slug(&self, slug: &str) -> Result<Distribution> {
         sqlx::query_as::<_, Distribution>(
-            "SELECT id, name, slug, homepage, github_org, gitlab_group, subreddit, description,
-                    datetime(created_at) as created_at, datetime(updated_at) as updated_at
+            "SELECT id, name, slug, homepage, github_org, gitlab_group, forgejo_org, pagure_namespace, forge_kind, forge_base_url,
+                    subreddit, discussions_repo, description, os_family, repology_repo, verified, bot_accounts, aggregation, based_on,
+                    mirror_list_url, mirror_list_format, iso_url, update_index_url, update_index_format,
+                    release_model, bugzilla_url, bugzilla_product, variant_of, archived_at, created_at, updated_at
+             FROM distributions WHERE slug = ? AND archived_at IS NULL",
+        )
+        .bind(slug)
+        .fetch_optional(self.pool())
+        .await?
+        .ok_or_else(|| DatabaseError::NotFound(format!("Distribution: {}", slug)))
+    }
+
+    /// Get a distribution by slug, archived or not
+    pub async fn get_distribution_by_slug_including_archived(&self, slug: &str) -> Result<Distribution> {
+        sqlx::query_as::<_, Distribution>(
+            "SELECT id, name, slug, homepage, github_org, gitlab_group, forgejo_org, pagure_namespace, forge_kind, forge_base_url,
+                    subreddit, discussions_repo, description, os_family, repology_repo, verified, bot_accounts, aggregation, based_on,
+                    mirror_list_url, mirror_list_format, iso_url, update_index_url, update_index_format,
+                    release_model, bugzilla_url, bugzilla_product, variant_of, archived_at, created_at, updated_at
              FROM distributions WHERE slug = ?",
         )
         .bind(slug)
@@ -36,8 +95,9 @@ impl Database {
     /// Create a new distribution
     pub async fn create_distribution(&self, distro: NewDistribution) -> Result<Distribution> {
         let id = sqlx::query(
-            "INSERT INTO distributions (name, slug, homepage, github_org, gitlab_group, subreddit)
-             VALUES (?, ?, ?, ?, ?, ?)",
+            "INSERT INTO distributions
+             (name, slug, homepage, github_org, gitlab_group, subreddit, description, os_family, repology_repo)
+             VALUES (?, ?, ?, ?, ?, ?, ?, COALESCE(?, 'linux'), ?)",
         )
         .bind(&distro.name)
         .bind(&distro.slug)
@@ -45,6 +105,9 @@ impl Database {
         .bind(&distro.github_org)
         .bind(&distro.gitlab_group)
         .bind(&distro.subreddit)
+        .bind(&distro.description)
+        .bind(&distro.os_family)
+        .bind(&distro.repology_repo)
         .execute(self.pool())
         .await?
         .last_insert_rowid();
@@ -55,8 +118,10 @@ impl Database {
     /// Get a distribution by ID
     pub async fn get_distribution_by_id(&self, id: i64) -> Result<Distribution> {
         sqlx::query_as::<_, Distribution>(
-            "SELECT id, name, slug, homepage, github_org, gitlab_group, subreddit, description,
-                    datetime(created_at) as created_at, datetime(updated_at) as updated_at
+            "SELECT id, name, slug, homepage, github_org, gitlab_group, forgejo_org, pagure_namespace, forge_kind, forge_base_url,
+                    subreddit, discussions_repo, description, os_family, repology_repo, verified, bot_accounts, aggregation, based_on,
+                    mirror_list_url, mirror_list_format, iso_url, update_index_url, update_index_format,
+                    release_model, bugzilla_url, bugzilla_product, variant_of, archived_at, created_at, updated_at
              FROM distributions WHERE id = ?",
         )
         .bind(id)
@@ -67,7 +132,7 @@ impl Database {
 
     /// Update a distribution's subreddit
     pub async fn update_distribution_subreddit(&self, id: i64, subreddit: &str) -> Result<()> {
-        sqlx::query("UPDATE distributions SET subreddit = ?, updated_at = datetime('now') WHERE id = ?")
+        sqlx::query("UPDATE distributions SET subreddit = ?, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = ?")
             .bind(subreddit)
             .bind(id)
             .execute(self.pool())
@@ -75,51 +140,426 @@ impl Database {
         Ok(())
     }
 
-    // ==================== GitHub Snapshots ====================
+    /// Update a distribution's metadata, leaving unset fields unchanged
+    pub async fn update_distribution(&self, id: i64, update: DistributionUpdate) -> Result<Distribution> {
+        sqlx::query(
+            "UPDATE distributions SET
+                homepage = COALESCE(?, homepage),
+                description = COALESCE(?, description),
+                github_org = COALESCE(?, github_org),
+                gitlab_group = COALESCE(?, gitlab_group),
+                forgejo_org = COALESCE(?, forgejo_org),
+                pagure_namespace = COALESCE(?, pagure_namespace),
+                forge_kind = COALESCE(?, forge_kind),
+                forge_base_url = COALESCE(?, forge_base_url),
+                subreddit = COALESCE(?, subreddit),
+                discussions_repo = COALESCE(?, discussions_repo),
+                bot_accounts = COALESCE(?, bot_accounts),
+                aggregation = COALESCE(?, aggregation),
+                based_on = COALESCE(?, based_on),
+                mirror_list_url = COALESCE(?, mirror_list_url),
+                mirror_list_format = COALESCE(?, mirror_list_format),
+                iso_url = COALESCE(?, iso_url),
+                update_index_url = COALESCE(?, update_index_url),
+                update_index_format = COALESCE(?, update_index_format),
+                release_model = COALESCE(?, release_model),
+                bugzilla_url = COALESCE(?, bugzilla_url),
+                bugzilla_product = COALESCE(?, bugzilla_product),
+                variant_of = COALESCE(?, variant_of),
+                updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE id = ?",
+        )
+        .bind(&update.homepage)
+        .bind(&update.description)
+        .bind(&update.github_org)
+        .bind(&update.gitlab_group)
+        .bind(&update.forgejo_org)
+        .bind(&update.pagure_namespace)
+        .bind(&update.forge_kind)
+        .bind(&update.forge_base_url)
+        .bind(&update.subreddit)
+        .bind(&update.discussions_repo)
+        .bind(&update.bot_accounts)
+        .bind(&update.aggregation)
+        .bind(&update.based_on)
+        .bind(&update.mirror_list_url)
+        .bind(&update.mirror_list_format)
+        .bind(&update.iso_url)
+        .bind(&update.update_index_url)
+        .bind(&update.update_index_format)
+        .bind(&update.release_model)
+        .bind(&update.bugzilla_url)
+        .bind(&update.bugzilla_product)
+        .bind(&update.variant_of)
+        .bind(id)
+        .execute(self.pool())
+        .await?;
 
-    /// Insert a new GitHub snapshot
-    pub async fn insert_github_snapshot(&self, snapshot: NewGithubSnapshot) -> Result<i64> {
-        let id = sqlx::query(
-            "INSERT INTO github_snapshots
-             (distro_id, repo_name, stars, forks, open_issues, open_prs,
-              commits_30d, commits_365d, contributors_30d, last_commit_at)
-             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-        )
-        .bind(snapshot.distro_id)
-        .bind(&snapshot.repo_name)
-        .bind(snapshot.stars)
-        .bind(snapshot.forks)
-        .bind(snapshot.open_issues)
-        .bind(snapshot.open_prs)
-        .bind(snapshot.commits_30d)
-        .bind(snapshot.commits_365d)
-        .bind(snapshot.contributors_30d)
-        .bind(snapshot.last_commit_at)
+        self.get_distribution_by_id(id).await
+    }
+
+    /// Archive a distribution, hiding it from listings while keeping its history
+    pub async fn archive_distribution(&self, id: i64) -> Result<()> {
+        sqlx::query(
+            "UPDATE distributions
+             SET archived_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'),
+                 updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE id = ? AND archived_at IS NULL",
+        )
+        .bind(id)
         .execute(self.pool())
-        .await?
-        .last_insert_rowid();
+        .await?;
+        Ok(())
+    }
 
-        Ok(id)
+    /// Bring an archived distribution back into listings
+    pub async fn restore_distribution(&self, id: i64) -> Result<()> {
+        sqlx::query(
+            "UPDATE distributions SET archived_at = NULL, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE id = ?",
+        )
+        .bind(id)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Permanently delete a distribution and all of its history
+    ///
+    /// Suggestions that created it are kept and unlinked. Returns the number of rows deleted.
+    pub async fn purge_distribution(&self, id: i64) -> Result<u64> {
+        let mut tx = self.pool().begin().await?;
+        let mut deleted = 0;
+
+        for table in [
+            "github_languages",
+            "github_contributors",
+            "github_issue_labels",
+            "github_issue_activity",
+        ] {
+            deleted += sqlx::query(&format!(
+                "DELETE FROM {}
+                 WHERE snapshot_id IN (SELECT id FROM github_snapshots WHERE distro_id = ?)",
+                table
+            ))
+            .bind(id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        // Children before parents: maintainers reference claims
+        for table in [
+            "github_snapshots",
+            "package_snapshots",
+            "community_snapshots",
+            "governance_snapshots",
+            "infra_snapshots",
+            "archive_snapshots",
+            "archive_sources",
+            "bugtracker_snapshots",
+            "org_snapshots",
+            "install_pings",
+            "popularity_snapshots",
+            "ranking_snapshots",
+            "release_snapshots",
+            "release_cursors",
+            "health_scores",
+            "distro_upstreams",
+            "annotations",
+            "maintainers",
+            "claims",
+            "config_issues",
+            "repo_overrides",
+            "highlights",
+            "milestones",
+            "metrics",
+            "score_freezes",
+            "collection_jobs",
+            "collection_runs",
+        ] {
+            deleted += sqlx::query(&format!("DELETE FROM {} WHERE distro_id = ?", table))
+                .bind(id)
+                .execute(&mut *tx)
+                .await?
+                .rows_affected();
+        }
+
+        sqlx::query("UPDATE suggestions SET distro_id = NULL WHERE distro_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        deleted += sqlx::query("DELETE FROM distributions WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+
+        tx.commit().await?;
+        Ok(deleted)
+    }
+
+    // ==================== GitHub Snapshots ====================
+
+    /// Insert a new GitHub snapshot along with its language breakdown
+    pub async fn insert_github_snapshot(&self, snapshot: NewGithubSnapshot) -> Result<i64> {
+        self.write(Write::Github(snapshot)).await
     }
 
-    /// Get latest GitHub snapshots for a distribution (most recent per repo)
+    /// Get latest GitHub snapshots for a distribution (most recent per repo), excluding mirrors
     pub async fn get_latest_github_snapshots(&self, distro_id: i64) -> Result<Vec<GithubSnapshot>> {
-        let rows = sqlx::query_as::<_, GithubSnapshot>(
-            "SELECT g.id, g.distro_id, g.repo_name, g.stars, g.forks, g.open_issues, g.open_prs,
-                    g.commits_30d, g.commits_365d, g.contributors_30d,
-                    datetime(g.last_commit_at) as last_commit_at,
-                    datetime(g.collected_at) as collected_at
-             FROM github_snapshots g
+        self.latest_github_snapshots(distro_id, false, None).await
+    }
+
+    /// Get the latest non-mirror GitHub snapshot per repo collected at or before a time
+    pub async fn get_github_snapshots_as_of(&self, distro_id: i64, at: DateTime<Utc>) -> Result<Vec<GithubSnapshot>> {
+        self.latest_github_snapshots(distro_id, false, Some(at)).await
+    }
+
+    /// Get latest snapshots of repos classified as mirrors, which aggregation ignores
+    pub async fn get_mirror_github_snapshots(&self, distro_id: i64) -> Result<Vec<GithubSnapshot>> {
+        self.latest_github_snapshots(distro_id, true, None).await
+    }
+
+    async fn latest_github_snapshots(
+        &self,
+        distro_id: i64,
+        mirrors: bool,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<Vec<GithubSnapshot>> {
+        let mut query = SnapshotQuery::new().distro(distro_id).mirrors(mirrors).latest();
+        if let Some(at) = as_of {
+            query = query.until(at);
+        }
+
+        // Every row passed the override-aware mirror filter, so report that classification
+        let mut rows = query.fetch::<GithubSnapshot>(self).await?;
+        for row in &mut rows {
+            row.is_mirror = mirrors;
+        }
+
+        Ok(rows)
+    }
+
+    /// Raw GitHub and Reddit totals for a distro as of a point in time
+    ///
+    /// Sums the latest snapshot of each non-mirror repo and each subreddit collected at or
+    /// before `at`, ignoring the distro's aggregation strategy.
+    pub async fn get_metric_totals_as_of(&self, distro_id: i64, at: DateTime<Utc>) -> Result<MetricTotals> {
+        let at = to_sql_timestamp(at);
+
+        let totals = sqlx::query_as::<_, MetricTotals>(
+            "WITH gh AS (
+                 SELECT g.*
+                 FROM github_snapshots g
+                 INNER JOIN (
+                     SELECT repo_name, MAX(collected_at) as max_collected
+                     FROM github_snapshots
+                     WHERE distro_id = ? AND collected_at <= ?
+                     GROUP BY repo_name
+                 ) latest ON g.repo_name = latest.repo_name AND g.collected_at = latest.max_collected
+                 LEFT JOIN repo_overrides o ON o.distro_id = g.distro_id AND o.repo_name = g.repo_name
+                 WHERE g.distro_id = ?
+                 AND COALESCE(o.is_mirror, g.is_mirror) = 0
+             ),
+             reddit AS (
+                 SELECT c.*
+                 FROM community_snapshots c
+                 INNER JOIN (
+                     SELECT source, MAX(collected_at) as max_collected
+                     FROM community_snapshots
+                     WHERE distro_id = ? AND collected_at <= ?
+                     GROUP BY source
+                 ) latest ON c.source = latest.source AND c.collected_at = latest.max_collected
+                 WHERE c.distro_id = ?
+                 AND c.source LIKE 'reddit:%'
+             )
+             SELECT
+                 (SELECT COALESCE(SUM(stars), 0) FROM gh) as stars,
+                 (SELECT COALESCE(SUM(forks), 0) FROM gh) as forks,
+                 (SELECT COALESCE(SUM(commits_30d), 0) FROM gh) as commits_30d,
+                 (SELECT COALESCE(SUM(contributors_30d), 0) FROM gh) as contributors_30d,
+                 (SELECT COALESCE(SUM(open_issues), 0) FROM gh) as open_issues,
+                 (SELECT COALESCE(SUM(open_prs), 0) FROM gh) as open_prs,
+                 (SELECT COALESCE(SUM(active_users_30d), 0) FROM reddit) as reddit_subscribers,
+                 (SELECT COALESCE(SUM(posts_30d), 0) FROM reddit) as reddit_posts_30d",
+        )
+        .bind(distro_id)
+        .bind(&at)
+        .bind(distro_id)
+        .bind(distro_id)
+        .bind(&at)
+        .bind(distro_id)
+        .fetch_one(self.pool())
+        .await?;
+
+        Ok(totals)
+    }
+
+    /// Sum language bytes over the latest snapshot of each non-mirror repo
+    pub async fn get_distro_languages(&self, distro_id: i64) -> Result<Vec<LanguageTotal>> {
+        let rows = sqlx::query_as::<_, LanguageTotal>(
+            "SELECT l.language, SUM(l.bytes) as bytes, COUNT(*) as repos
+             FROM github_languages l
+             INNER JOIN github_snapshots g ON g.id = l.snapshot_id
+             INNER JOIN (
+                 SELECT repo_name, MAX(collected_at) as max_collected
+                 FROM github_snapshots
+                 WHERE distro_id = ?
+                 GROUP BY repo_name
+             ) latest ON g.repo_name = latest.repo_name AND g.collected_at = latest.max_collected
+             LEFT JOIN repo_overrides o ON o.distro_id = g.distro_id AND o.repo_name = g.repo_name
+             WHERE g.distro_id = ?
+             AND COALESCE(o.is_mirror, g.is_mirror) = 0
+             GROUP BY l.language
+             ORDER BY bytes DESC",
+        )
+        .bind(distro_id)
+        .bind(distro_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Sum open issues per label category over the latest snapshot of each non-mirror repo
+    pub async fn get_issue_label_counts(&self, distro_id: i64) -> Result<Vec<IssueLabelCount>> {
+        let rows = sqlx::query_as::<_, IssueLabelCount>(
+            "SELECT l.category, SUM(l.open_issues) as open_issues, COUNT(*) as repos
+             FROM github_issue_labels l
+             INNER JOIN github_snapshots g ON g.id = l.snapshot_id
+             INNER JOIN (
+                 SELECT repo_name, MAX(collected_at) as max_collected
+                 FROM github_snapshots
+                 WHERE distro_id = ?
+                 GROUP BY repo_name
+             ) latest ON g.repo_name = latest.repo_name AND g.collected_at = latest.max_collected
+             LEFT JOIN repo_overrides o ON o.distro_id = g.distro_id AND o.repo_name = g.repo_name
+             WHERE g.distro_id = ?
+             AND COALESCE(o.is_mirror, g.is_mirror) = 0
+             GROUP BY l.category
+             ORDER BY l.category",
+        )
+        .bind(distro_id)
+        .bind(distro_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Sum 30-day issue activity over the latest snapshot of each non-mirror repo, None when
+    /// no repo has any recorded
+    pub async fn get_issue_activity(&self, distro_id: i64) -> Result<Option<IssueActivity>> {
+        let row = sqlx::query_as::<_, IssueActivity>(
+            "SELECT SUM(a.opened_30d) as opened_30d, SUM(a.closed_30d) as closed_30d,
+                    SUM(a.stale_closed_30d) as stale_closed_30d
+             FROM github_issue_activity a
+             INNER JOIN github_snapshots g ON g.id = a.snapshot_id
              INNER JOIN (
                  SELECT repo_name, MAX(collected_at) as max_collected
                  FROM github_snapshots
                  WHERE distro_id = ?
                  GROUP BY repo_name
              ) latest ON g.repo_name = latest.repo_name AND g.collected_at = latest.max_collected
+             LEFT JOIN repo_overrides o ON o.distro_id = g.distro_id AND o.repo_name = g.repo_name
              WHERE g.distro_id = ?
-             ORDER BY g.repo_name",
+             AND COALESCE(o.is_mirror, g.is_mirror) = 0
+             HAVING COUNT(*) > 0",
+        )
+        .bind(distro_id)
+        .bind(distro_id)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row)
+    }
+
+    /// Pairs of active distros sharing at least `min_shared` contributors, most shared first
+    ///
+    /// Contributors come from the latest snapshot of each non-mirror repo.
+    pub async fn get_contributor_overlap(&self, min_shared: i64) -> Result<Vec<ContributorOverlap>> {
+        let rows = sqlx::query_as::<_, ContributorOverlap>(
+            "WITH latest AS (
+                 SELECT g.id, g.distro_id
+                 FROM github_snapshots g
+                 INNER JOIN (
+                     SELECT distro_id, repo_name, MAX(collected_at) as max_collected
+                     FROM github_snapshots
+                     GROUP BY distro_id, repo_name
+                 ) l ON g.distro_id = l.distro_id
+                    AND g.repo_name = l.repo_name
+                    AND g.collected_at = l.max_collected
+                 LEFT JOIN repo_overrides o ON o.distro_id = g.distro_id AND o.repo_name = g.repo_name
+                 WHERE COALESCE(o.is_mirror, g.is_mirror) = 0
+             ),
+             people AS (
+                 SELECT DISTINCT latest.distro_id, lower(c.login) as login
+                 FROM github_contributors c
+                 INNER JOIN latest ON latest.id = c.snapshot_id
+                 INNER JOIN distributions d ON d.id = latest.distro_id
+                 WHERE d.archived_at IS NULL
+             ),
+             sizes AS (
+                 SELECT distro_id, COUNT(*) as contributors FROM people GROUP BY distro_id
+             ),
+             pairs AS (
+                 SELECT a.distro_id as a_id, b.distro_id as b_id, COUNT(*) as shared
+                 FROM people a
+                 INNER JOIN people b ON a.login = b.login AND a.distro_id < b.distro_id
+                 GROUP BY a.distro_id, b.distro_id
+                 HAVING COUNT(*) >= ?
+             )
+             SELECT da.slug as distro_a, da.name as distro_a_name,
+                    db.slug as distro_b, db.name as distro_b_name,
+                    p.shared, sa.contributors as contributors_a, sb.contributors as contributors_b,
+                    ROUND(CAST(p.shared AS REAL) / (sa.contributors + sb.contributors - p.shared), 4)
+                        as similarity
+             FROM pairs p
+             INNER JOIN distributions da ON da.id = p.a_id
+             INNER JOIN distributions db ON db.id = p.b_id
+             INNER JOIN sizes sa ON sa.distro_id = p.a_id
+             INNER JOIN sizes sb ON sb.distro_id = p.b_id
+             ORDER BY p.shared DESC, similarity DESC, da.slug, db.slug",
+        )
+        .bind(min_shared)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Force a repo to be treated as a mirror (`true`) or as source (`false`)
+    pub async fn set_repo_override(&self, distro_id: i64, repo_name: &str, is_mirror: bool) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO repo_overrides (distro_id, repo_name, is_mirror) VALUES (?, ?, ?)
+             ON CONFLICT(distro_id, repo_name) DO UPDATE SET is_mirror = excluded.is_mirror",
         )
         .bind(distro_id)
+        .bind(repo_name)
+        .bind(is_mirror)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Remove a manual override, returning the repo to heuristic detection
+    pub async fn clear_repo_override(&self, distro_id: i64, repo_name: &str) -> Result<()> {
+        sqlx::query("DELETE FROM repo_overrides WHERE distro_id = ? AND repo_name = ?")
+            .bind(distro_id)
+            .bind(repo_name)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// Get manual overrides for a distribution
+    pub async fn get_repo_overrides(&self, distro_id: i64) -> Result<Vec<RepoOverride>> {
+        let rows = sqlx::query_as::<_, RepoOverride>(
+            "SELECT distro_id, repo_name, is_mirror, created_at
+             FROM repo_overrides WHERE distro_id = ? ORDER BY repo_name",
+        )
         .bind(distro_id)
         .fetch_all(self.pool())
         .await?;
@@ -133,8 +573,9 @@ impl Database {
     pub async fn insert_health_score(&self, score: NewHealthScore) -> Result<i64> {
         let id = sqlx::query(
             "INSERT INTO health_scores
-             (distro_id, overall_score, development_score, community_score, maintenance_score, trend)
-             VALUES (?, ?, ?, ?, ?, ?)",
+             (distro_id, overall_score, development_score, community_score, maintenance_score, trend,
+              inputs_fingerprint)
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(score.distro_id)
         .bind(score.overall_score)
@@ -142,10 +583,19 @@ impl Database {
         .bind(score.community_score)
         .bind(score.maintenance_score)
         .bind(&score.trend)
+        .bind(&score.inputs_fingerprint)
         .execute(self.pool())
         .await?
         .last_insert_rowid();
 
+        self.emit(Event::ScoreCalculated {
+            distro_id: score.distro_id,
+            score_id: id,
+            overall_score: score.overall_score,
+            trend: score.trend,
+        })
+        .await;
+
         Ok(id)
     }
 
@@ -153,7 +603,7 @@ impl Database {
     pub async fn get_latest_health_score(&self, distro_id: i64) -> Result<Option<HealthScore>> {
         let row = sqlx::query_as::<_, HealthScore>(
             "SELECT id, distro_id, overall_score, development_score, community_score,
-                    maintenance_score, trend, datetime(calculated_at) as calculated_at
+                    maintenance_score, trend, calculated_at
              FROM health_scores
              WHERE distro_id = ?
              ORDER BY calculated_at DESC
@@ -166,19 +616,83 @@ impl Database {
         Ok(row)
     }
 
+    /// Inputs fingerprint of a health score, None for scores recorded before fingerprints
+    pub async fn get_health_score_fingerprint(&self, score_id: i64) -> Result<Option<String>> {
+        let fingerprint: Option<Option<String>> =
+            sqlx::query_scalar("SELECT inputs_fingerprint FROM health_scores WHERE id = ?")
+                .bind(score_id)
+                .fetch_optional(self.pool())
+                .await?;
+
+        Ok(fingerprint.flatten())
+    }
+
+    /// Get the `limit` most recent health scores for a distribution, newest first
+    pub async fn get_recent_health_scores(&self, distro_id: i64, limit: usize) -> Result<Vec<HealthScore>> {
+        let rows = sqlx::query_as::<_, HealthScore>(
+            "SELECT id, distro_id, overall_score, development_score, community_score,
+                    maintenance_score, trend, calculated_at
+             FROM health_scores
+             WHERE distro_id = ?
+             ORDER BY calculated_at DESC
+             LIMIT ?",
+        )
+        .bind(distro_id)
+        .bind(limit as i64)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
     /// Get all latest health scores
     pub async fn get_all_latest_health_scores(&self) -> Result<Vec<HealthScore>> {
         let rows = sqlx::query_as::<_, HealthScore>(
             "SELECT h.id, h.distro_id, h.overall_score, h.development_score, h.community_score,
-                    h.maintenance_score, h.trend, datetime(h.calculated_at) as calculated_at
+                    h.maintenance_score, h.trend, h.calculated_at as calculated_at
              FROM health_scores h
              INNER JOIN (
                  SELECT distro_id, MAX(calculated_at) as max_calc
                  FROM health_scores
                  GROUP BY distro_id
              ) latest ON h.distro_id = latest.distro_id AND h.calculated_at = latest.max_calc
+             JOIN distributions d ON d.id = h.distro_id
+             WHERE d.archived_at IS NULL
+             ORDER BY h.overall_score DESC",
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Get each distribution's latest health score as of a point in time, best first
+    pub async fn get_health_scores_as_of(&self, at: DateTime<Utc>) -> Result<Vec<HealthScore>> {
+        let rows = sqlx::query_as::<_, HealthScore>(
+            "SELECT h.id, h.distro_id, h.overall_score, h.development_score, h.community_score,
+                    h.maintenance_score, h.trend, h.calculated_at as calculated_at
+             FROM health_scores h
+             WHERE h.id = (
+                 SELECT id FROM health_scores
+                 WHERE distro_id = h.distro_id AND calculated_at <= ?
+                 ORDER BY calculated_at DESC, id DESC
+                 LIMIT 1
+             )
+             AND h.distro_id IN (SELECT id FROM distributions WHERE archived_at IS NULL)
              ORDER BY h.overall_score DESC",
         )
+        .bind(to_sql_timestamp(at))
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Get the first time each distribution was scored
+    pub async fn get_first_score_times(&self) -> Result<Vec<(i64, DateTime<Utc>)>> {
+        let rows = sqlx::query_as::<_, (i64, DateTime<Utc>)>(
+            "SELECT distro_id, MIN(calculated_at) FROM health_scores GROUP BY distro_id",
+        )
         .fetch_all(self.pool())
         .await?;
 
@@ -193,10 +707,10 @@ impl Database {
     ) -> Result<Vec<HealthScore>> {
         let rows = sqlx::query_as::<_, HealthScore>(
             "SELECT id, distro_id, overall_score, development_score, community_score,
-                    maintenance_score, trend, datetime(calculated_at) as calculated_at
+                    maintenance_score, trend, calculated_at
              FROM health_scores
              WHERE distro_id = ?
-             AND calculated_at >= datetime('now', ?)
+             AND calculated_at >= strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?)
              ORDER BY calculated_at ASC",
         )
         .bind(distro_id)
@@ -207,123 +721,1872 @@ impl Database {
         Ok(rows)
     }
 
-    // ==================== Release Snapshots ====================
+    /// Get daily-averaged overall scores for every distro over the last N days, oldest first
+    pub async fn get_score_sparklines(&self, days: i32) -> Result<Vec<(String, Vec<f64>)>> {
+        let rows = sqlx::query_as::<_, (String, f64)>(
+            "SELECT d.slug, ROUND(AVG(h.overall_score), 1) as score
+             FROM health_scores h
+             JOIN distributions d ON d.id = h.distro_id
+             WHERE h.calculated_at >= strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?)
+             AND d.archived_at IS NULL
+             GROUP BY d.slug, date(h.calculated_at)
+             ORDER BY d.slug, date(h.calculated_at)",
+        )
+        .bind(format!("-{} days", days))
+        .fetch_all(self.pool())
+        .await?;
 
-    /// Insert a new release snapshot
-    pub async fn insert_release_snapshot(&self, snapshot: NewReleaseSnapshot) -> Result<i64> {
-        let id = sqlx::query(
-            "INSERT INTO release_snapshots
-             (distro_id, repo_name, tag_name, release_name, published_at, is_prerelease)
-             VALUES (?, ?, ?, ?, ?, ?)",
-        )
-        .bind(snapshot.distro_id)
-        .bind(&snapshot.repo_name)
-        .bind(&snapshot.tag_name)
-        .bind(&snapshot.release_name)
-        .bind(snapshot.published_at)
-        .bind(snapshot.is_prerelease)
-        .execute(self.pool())
-        .await?
-        .last_insert_rowid();
+        let mut sparklines: Vec<(String, Vec<f64>)> = Vec::new();
+        for (slug, score) in rows {
+            match sparklines.last_mut() {
+                Some((last, scores)) if *last == slug => scores.push(score),
+                _ => sparklines.push((slug, vec![score])),
+            }
+        }
 
-        Ok(id)
+        Ok(sparklines)
     }
 
-    /// Get latest release snapshots for a distribution (most recent per tag)
-    pub async fn get_latest_release_snapshots(&self, distro_id: i64) -> Result<Vec<ReleaseSnapshot>> {
-        let rows = sqlx::query_as::<_, ReleaseSnapshot>(
-            "SELECT r.id, r.distro_id, r.repo_name, r.tag_name, r.release_name,
-                    datetime(r.published_at) as published_at, r.is_prerelease,
-                    datetime(r.collected_at) as collected_at
-             FROM release_snapshots r
-             INNER JOIN (
-                 SELECT repo_name, tag_name, MAX(collected_at) as max_collected
-                 FROM release_snapshots
-                 WHERE distro_id = ?
-                 GROUP BY repo_name, tag_name
-             ) latest ON r.repo_name = latest.repo_name
-                     AND r.tag_name = latest.tag_name
-                     AND r.collected_at = latest.max_collected
-             WHERE r.distro_id = ?
-             ORDER BY r.published_at DESC",
+    /// Get one page of health score history within a time window, oldest first
+    pub async fn get_health_score_page(
+        &self,
+        distro_id: i64,
+        window: &TimeWindow,
+    ) -> Result<Vec<HealthScore>> {
+        let rows = sqlx::query_as::<_, HealthScore>(
+            "SELECT id, distro_id, overall_score, development_score, community_score,
+                    maintenance_score, trend, calculated_at
+             FROM health_scores
+             WHERE distro_id = ?
+             AND calculated_at >= ? AND calculated_at <= ?
+             AND (? IS NULL OR (calculated_at, id) >
+                  (SELECT calculated_at, id FROM health_scores WHERE id = ?))
+             ORDER BY calculated_at ASC, id ASC
+             LIMIT ?",
         )
         .bind(distro_id)
-        .bind(distro_id)
+        .bind(window.since_sql())
+        .bind(window.until_sql())
+        .bind(window.after_id)
+        .bind(window.after_id)
+        .bind(window.limit)
         .fetch_all(self.pool())
         .await?;
 
         Ok(rows)
     }
 
-    /// Get releases from the last N days for a distribution
-    pub async fn get_recent_releases(&self, distro_id: i64, days: i32) -> Result<Vec<ReleaseSnapshot>> {
-        let rows = sqlx::query_as::<_, ReleaseSnapshot>(
-            "SELECT r.id, r.distro_id, r.repo_name, r.tag_name, r.release_name,
-                    datetime(r.published_at) as published_at, r.is_prerelease,
-                    datetime(r.collected_at) as collected_at
-             FROM release_snapshots r
-             INNER JOIN (
-                 SELECT repo_name, tag_name, MAX(collected_at) as max_collected
-                 FROM release_snapshots
-                 WHERE distro_id = ?
-                 GROUP BY repo_name, tag_name
-             ) latest ON r.repo_name = latest.repo_name
-                     AND r.tag_name = latest.tag_name
-                     AND r.collected_at = latest.max_collected
-             WHERE r.distro_id = ?
-             AND r.published_at >= datetime('now', ?)
-             ORDER BY r.published_at DESC",
-        )
-        .bind(distro_id)
-        .bind(distro_id)
-        .bind(format!("-{} days", days))
-        .fetch_all(self.pool())
-        .await?;
+    /// Get health score history downsampled into buckets, oldest first (`after_id` is ignored)
+    pub async fn get_health_score_buckets(
+        &self,
+        distro_id: i64,
+        resolution: Resolution,
+        window: &TimeWindow,
+    ) -> Result<Vec<HealthScoreBucket>> {
+        let sql = format!(
+            "SELECT {bucket} as bucket, COUNT(*) as samples,
+                    AVG(overall_score) as overall_avg, MIN(overall_score) as overall_min,
+                    MAX(overall_score) as overall_max,
+                    AVG(development_score) as development_avg, MIN(development_score) as development_min,
+                    MAX(development_score) as development_max,
+                    AVG(community_score) as community_avg, MIN(community_score) as community_min,
+                    MAX(community_score) as community_max,
+                    AVG(maintenance_score) as maintenance_avg, MIN(maintenance_score) as maintenance_min,
+                    MAX(maintenance_score) as maintenance_max
+             FROM health_scores
+             WHERE distro_id = ?
+             AND calculated_at >= ? AND calculated_at <= ?
+             GROUP BY bucket
+             ORDER BY bucket ASC
+             LIMIT ?",
+            bucket = resolution.bucket_sql("calculated_at")
+        );
+
+        let rows = sqlx::query_as::<_, HealthScoreBucket>(&sql)
+            .bind(distro_id)
+            .bind(window.since_sql())
+            .bind(window.until_sql())
+            .bind(window.limit)
+            .fetch_all(self.pool())
+            .await?;
 
         Ok(rows)
     }
 
-    // ==================== Community Snapshots ====================
+    /// Get GitHub activity downsampled into buckets, oldest first (`after_id` is ignored)
+    pub async fn get_commit_activity_buckets(
+        &self,
+        distro_id: i64,
+        resolution: Resolution,
+        window: &TimeWindow,
+    ) -> Result<Vec<CommitActivityBucket>> {
+        let sql = format!(
+            "SELECT bucket, SUM(commits_30d) as commits_30d, SUM(contributors_30d) as contributors_30d
+             FROM (
+                 SELECT {bucket} as bucket, g.commits_30d, g.contributors_30d,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY {bucket}, g.repo_name ORDER BY g.collected_at DESC
+                        ) as position
+                 FROM github_snapshots g
+                 LEFT JOIN repo_overrides o ON o.distro_id = g.distro_id AND o.repo_name = g.repo_name
+                 WHERE g.distro_id = ?
+                 AND COALESCE(o.is_mirror, g.is_mirror) = 0
+                 AND g.collected_at >= ? AND g.collected_at <= ?
+             )
+             WHERE position = 1
+             GROUP BY bucket
+             ORDER BY bucket ASC
+             LIMIT ?",
+            bucket = resolution.bucket_sql("g.collected_at")
+        );
 
-    /// Insert a new community snapshot
-    pub async fn insert_community_snapshot(&self, snapshot: NewCommunitySnapshot) -> Result<i64> {
-        let id = sqlx::query(
-            "INSERT INTO community_snapshots
-             (distro_id, source, active_users_30d, posts_30d, response_time_avg_hours)
-             VALUES (?, ?, ?, ?, ?)",
-        )
-        .bind(snapshot.distro_id)
-        .bind(&snapshot.source)
-        .bind(snapshot.active_users_30d)
-        .bind(snapshot.posts_30d)
-        .bind(snapshot.response_time_avg_hours)
-        .execute(self.pool())
-        .await?
-        .last_insert_rowid();
+        let rows = sqlx::query_as::<_, CommitActivityBucket>(&sql)
+            .bind(distro_id)
+            .bind(window.since_sql())
+            .bind(window.until_sql())
+            .bind(window.limit)
+            .fetch_all(self.pool())
+            .await?;
 
-        Ok(id)
+        Ok(rows)
     }
 
-    /// Get latest community snapshots for a distribution (most recent per source)
-    pub async fn get_latest_community_snapshots(&self, distro_id: i64) -> Result<Vec<CommunitySnapshot>> {
-        let rows = sqlx::query_as::<_, CommunitySnapshot>(
-            "SELECT c.id, c.distro_id, c.source, c.active_users_30d, c.posts_30d,
-                    c.response_time_avg_hours, datetime(c.collected_at) as collected_at
-             FROM community_snapshots c
-             INNER JOIN (
-                 SELECT source, MAX(collected_at) as max_collected
-                 FROM community_snapshots
-                 WHERE distro_id = ?
-                 GROUP BY source
-             ) latest ON c.source = latest.source AND c.collected_at = latest.max_collected
-             WHERE c.distro_id = ?
-             ORDER BY c.source",
+    // ==================== Release Snapshots ====================
+
+    /// Insert a new release snapshot
+    pub async fn insert_release_snapshot(&self, snapshot: NewReleaseSnapshot) -> Result<i64> {
+        self.write(Write::Release(snapshot)).await
+    }
+
+    /// Tag names already recorded as releases for a repo
+    pub async fn get_release_tag_names(&self, distro_id: i64, repo_name: &str) -> Result<Vec<String>> {
+        let rows = sqlx::query_scalar(
+            "SELECT DISTINCT tag_name FROM release_snapshots WHERE distro_id = ? AND repo_name = ?",
         )
         .bind(distro_id)
-        .bind(distro_id)
+        .bind(repo_name)
         .fetch_all(self.pool())
         .await?;
 
         Ok(rows)
     }
+
+    /// Latest recorded download count per tag for a repo
+    pub async fn get_release_downloads(&self, distro_id: i64, repo_name: &str) -> Result<HashMap<String, i64>> {
+        let rows: Vec<(String, i64)> = sqlx::query_as(
+            "SELECT r.tag_name, r.downloads
+             FROM release_snapshots r
+             WHERE r.distro_id = ? AND r.repo_name = ? AND r.downloads IS NOT NULL
+               AND r.collected_at = (
+                   SELECT MAX(collected_at) FROM release_snapshots
+                   WHERE distro_id = r.distro_id AND repo_name = r.repo_name AND tag_name = r.tag_name
+                     AND downloads IS NOT NULL
+               )",
+        )
+        .bind(distro_id)
+        .bind(repo_name)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// Total release downloads per day since a time, counting each release's latest count
+    /// as of that day
+    pub async fn get_download_history(&self, distro_id: i64, since: DateTime<Utc>) -> Result<Vec<DownloadPoint>> {
+        let rows = sqlx::query_as::<_, DownloadPoint>(
+            "WITH days AS (
+                 SELECT DISTINCT date(collected_at) as day
+                 FROM release_snapshots
+                 WHERE distro_id = ? AND downloads IS NOT NULL AND collected_at >= ?
+             )
+             SELECT days.day as date,
+                    (SELECT COALESCE(SUM(latest), 0) FROM (
+                        SELECT MAX(downloads) as latest
+                        FROM release_snapshots r
+                        WHERE r.distro_id = ? AND r.downloads IS NOT NULL
+                          AND date(r.collected_at) <= days.day
+                        GROUP BY r.repo_name, r.tag_name
+                    )) as downloads
+             FROM days
+             ORDER BY days.day ASC",
+        )
+        .bind(distro_id)
+        .bind(to_sql_timestamp(since))
+        .bind(distro_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Get the newest release seen for a repo
+    pub async fn get_release_cursor(&self, distro_id: i64, repo_name: &str) -> Result<Option<ReleaseCursor>> {
+        let row = sqlx::query_as::<_, ReleaseCursor>(
+            "SELECT distro_id, repo_name, release_id, published_at, updated_at
+             FROM release_cursors WHERE distro_id = ? AND repo_name = ?",
+        )
+        .bind(distro_id)
+        .bind(repo_name)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row)
+    }
+
+    /// Move a repo's release cursor to a newer release
+    pub async fn set_release_cursor(
+        &self,
+        distro_id: i64,
+        repo_name: &str,
+        release_id: i64,
+        published_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO release_cursors (distro_id, repo_name, release_id, published_at)
+             VALUES (?, ?, ?, ?)
+             ON CONFLICT(distro_id, repo_name) DO UPDATE SET
+                 release_id = excluded.release_id,
+                 published_at = excluded.published_at,
+                 updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+        )
+        .bind(distro_id)
+        .bind(repo_name)
+        .bind(release_id)
+        .bind(published_at.map(to_sql_timestamp))
+        .execute(self.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Get latest release snapshots for a distribution (most recent per tag)
+    pub async fn get_latest_release_snapshots(&self, distro_id: i64) -> Result<Vec<ReleaseSnapshot>> {
+        SnapshotQuery::new().distro(distro_id).latest().fetch(self).await
+    }
+
+    /// Get releases from the last N days for a distribution
+    pub async fn get_recent_releases(&self, distro_id: i64, days: i32) -> Result<Vec<ReleaseSnapshot>> {
+        let since = Utc::now() - TimeDelta::days(days as i64);
+        let mut rows = self.get_latest_release_snapshots(distro_id).await?;
+        rows.retain(|r| r.published_at.is_some_and(|at| at >= since));
+
+        Ok(rows)
+    }
+
+    // ==================== Package Snapshots ====================
+
+    /// Insert a new package snapshot
+    pub async fn insert_package_snapshot(&self, snapshot: NewPackageSnapshot) -> Result<i64> {
+        self.write(Write::Package(snapshot)).await
+    }
+
+    /// Get the latest package snapshot for a distribution
+    pub async fn get_latest_package_snapshot(&self, distro_id: i64) -> Result<Option<PackageSnapshot>> {
+        self.latest_package_snapshot(distro_id, None).await
+    }
+
+    /// Get the latest package snapshot collected at or before a time
+    pub async fn get_package_snapshot_as_of(
+        &self,
+        distro_id: i64,
+        at: DateTime<Utc>,
+    ) -> Result<Option<PackageSnapshot>> {
+        self.latest_package_snapshot(distro_id, Some(at)).await
+    }
+
+    async fn latest_package_snapshot(
+        &self,
+        distro_id: i64,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<Option<PackageSnapshot>> {
+        let mut query = SnapshotQuery::new().distro(distro_id).latest();
+        if let Some(at) = as_of {
+            query = query.until(at);
+        }
+
+        Ok(query.fetch(self).await?.pop())
+    }
+
+    /// Get the earliest package snapshot in which a distribution shipped a kernel series
+    pub async fn get_first_package_snapshot_with_kernel(
+        &self,
+        distro_id: i64,
+        kernel_series: &str,
+    ) -> Result<Option<PackageSnapshot>> {
+        let row = sqlx::query_as::<_, PackageSnapshot>(
+            "SELECT id, distro_id, total_packages, outdated_packages, security_updates,
+                    kernel_version, kernel_series, kernel_status, kernel_upstream, mesa_version, mesa_upstream,
+                    official_packages, flagged_packages, aur_packages, collected_at
+             FROM package_snapshots
+             WHERE distro_id = ? AND kernel_series = ?
+             ORDER BY collected_at
+             LIMIT 1",
+        )
+        .bind(distro_id)
+        .bind(kernel_series)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row)
+    }
+
+    // ==================== Community Snapshots ====================
+
+    /// Insert a new community snapshot
+    pub async fn insert_community_snapshot(&self, snapshot: NewCommunitySnapshot) -> Result<i64> {
+        self.write(Write::Community(snapshot)).await
+    }
+
+    /// Get latest community snapshots for a distribution (most recent per source)
+    pub async fn get_latest_community_snapshots(&self, distro_id: i64) -> Result<Vec<CommunitySnapshot>> {
+        self.latest_community_snapshots(distro_id, None).await
+    }
+
+    /// Get the latest community snapshot per source collected at or before a time
+    pub async fn get_community_snapshots_as_of(
+        &self,
+        distro_id: i64,
+        at: DateTime<Utc>,
+    ) -> Result<Vec<CommunitySnapshot>> {
+        self.latest_community_snapshots(distro_id, Some(at)).await
+    }
+
+    async fn latest_community_snapshots(
+        &self,
+        distro_id: i64,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<Vec<CommunitySnapshot>> {
+        let mut query = SnapshotQuery::new().distro(distro_id).latest();
+        if let Some(at) = as_of {
+            query = query.until(at);
+        }
+
+        query.fetch(self).await
+    }
+
+    // ==================== Governance Snapshots ====================
+
+    /// Insert a new governance snapshot
+    pub async fn insert_governance_snapshot(&self, snapshot: NewGovernanceSnapshot) -> Result<i64> {
+        self.write(Write::Governance(snapshot)).await
+    }
+
+    /// Get the latest governance snapshot for a distribution
+    pub async fn get_latest_governance_snapshot(&self, distro_id: i64) -> Result<Option<GovernanceSnapshot>> {
+        let row = sqlx::query_as::<_, GovernanceSnapshot>(
+            "SELECT id, distro_id, repos_checked, contributing, code_of_conduct, security_policy, governance,
+                    provenance, collected_at
+             FROM governance_snapshots
+             WHERE distro_id = ?
+             ORDER BY collected_at DESC, id DESC
+             LIMIT 1",
+        )
+        .bind(distro_id)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row)
+    }
+
+    // ==================== Infrastructure Snapshots ====================
+
+    /// Insert a new infrastructure snapshot
+    pub async fn insert_infra_snapshot(&self, snapshot: NewInfraSnapshot) -> Result<i64> {
+        self.write(Write::Infra(snapshot)).await
+    }
+
+    /// Get the latest infrastructure snapshot for a distribution
+    pub async fn get_latest_infra_snapshot(&self, distro_id: i64) -> Result<Option<InfraSnapshot>> {
+        let row = sqlx::query_as::<_, InfraSnapshot>(
+            "SELECT id, distro_id, mirrors, mirror_countries, provenance, collected_at
+             FROM infra_snapshots
+             WHERE distro_id = ?
+             ORDER BY collected_at DESC, id DESC
+             LIMIT 1",
+        )
+        .bind(distro_id)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row)
+    }
+
+    // ==================== Bug Tracker Snapshots ====================
+
+    /// Insert a new bug tracker snapshot
+    pub async fn insert_bugtracker_snapshot(&self, snapshot: NewBugtrackerSnapshot) -> Result<i64> {
+        self.write(Write::Bugtracker(snapshot)).await
+    }
+
+    /// Get the latest bug tracker snapshot for a distribution
+    pub async fn get_latest_bugtracker_snapshot(&self, distro_id: i64) -> Result<Option<BugtrackerSnapshot>> {
+        let row = sqlx::query_as::<_, BugtrackerSnapshot>(
+            "SELECT id, distro_id, open_bugs, opened_30d, closed_30d, mean_resolution_days, provenance, collected_at
+             FROM bugtracker_snapshots
+             WHERE distro_id = ?
+             ORDER BY collected_at DESC, id DESC
+             LIMIT 1",
+        )
+        .bind(distro_id)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row)
+    }
+
+    // ==================== Org Snapshots ====================
+
+    /// Insert a new org snapshot
+    pub async fn insert_org_snapshot(&self, snapshot: NewOrgSnapshot) -> Result<i64> {
+        self.write(Write::Org(snapshot)).await
+    }
+
+    /// Get the latest org snapshot for a distribution
+    pub async fn get_latest_org_snapshot(&self, distro_id: i64) -> Result<Option<OrgSnapshot>> {
+        let row = sqlx::query_as::<_, OrgSnapshot>(
+            "SELECT id, distro_id, org, public_repos, public_members, followers, sponsors, org_created_at,
+                    provenance, collected_at
+             FROM org_snapshots
+             WHERE distro_id = ?
+             ORDER BY collected_at DESC, id DESC
+             LIMIT 1",
+        )
+        .bind(distro_id)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row)
+    }
+
+    // ==================== Archive Snapshots ====================
+
+    /// Insert a new archive snapshot
+    pub async fn insert_archive_snapshot(&self, snapshot: NewArchiveSnapshot) -> Result<i64> {
+        self.write(Write::Archive(snapshot)).await
+    }
+
+    /// Get the latest archive snapshot for a distribution
+    pub async fn get_latest_archive_snapshot(&self, distro_id: i64) -> Result<Option<ArchiveSnapshot>> {
+        let row = sqlx::query_as::<_, ArchiveSnapshot>(
+            "SELECT id, distro_id, source_packages, rc_bugs, uploads, uploads_30d, provenance, collected_at
+             FROM archive_snapshots
+             WHERE distro_id = ?
+             ORDER BY collected_at DESC, id DESC
+             LIMIT 1",
+        )
+        .bind(distro_id)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row)
+    }
+
+    /// Uploads counted by a distribution's archive snapshots collected after `since`
+    pub async fn get_upload_history(&self, distro_id: i64, since: DateTime<Utc>) -> Result<UploadHistory> {
+        let (uploads, tracked_since): (Option<i64>, Option<DateTime<Utc>>) = sqlx::query_as(
+            "SELECT (SELECT SUM(uploads) FROM archive_snapshots WHERE distro_id = ?1 AND collected_at > ?2),
+                    (SELECT MIN(collected_at) FROM archive_snapshots WHERE distro_id = ?1)",
+        )
+        .bind(distro_id)
+        .bind(to_sql_timestamp(since))
+        .fetch_one(self.pool())
+        .await?;
+
+        Ok(UploadHistory {
+            uploads: uploads.unwrap_or(0),
+            tracked_since,
+        })
+    }
+
+    /// Replace the source package versions recorded for a distribution's archive
+    ///
+    /// Returns how many packages are new or have a new version since the versions last
+    /// recorded, or None when there were none to compare against.
+    pub async fn replace_archive_sources(&self, distro_id: i64, sources: &[(String, String)]) -> Result<Option<i64>> {
+        let mut tx = self.pool().begin().await?;
+
+        let previous: HashMap<String, String> =
+            sqlx::query_as("SELECT source, version FROM archive_sources WHERE distro_id = ?")
+                .bind(distro_id)
+                .fetch_all(&mut *tx)
+                .await?
+                .into_iter()
+                .collect();
+        let uploads = (!previous.is_empty()).then(|| {
+            sources
+                .iter()
+                .filter(|(source, version)| previous.get(source) != Some(version))
+                .count() as i64
+        });
+
+        sqlx::query("DELETE FROM archive_sources WHERE distro_id = ?")
+            .bind(distro_id)
+            .execute(&mut *tx)
+            .await?;
+        // Well under SQLite's limit on bound parameters per statement
+        for chunk in sources.chunks(1000) {
+            let mut insert = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
+                "INSERT OR REPLACE INTO archive_sources (distro_id, source, version) ",
+            );
+            insert.push_values(chunk, |mut row, (source, version)| {
+                row.push_bind(distro_id).push_bind(source).push_bind(version);
+            });
+            insert.build().execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+        Ok(uploads)
+    }
+
+    // ==================== Install Pings ====================
+
+    /// Record a ping from an install, unless its client address has sent `max_per_client`
+    /// pings today already; returns whether the ping was counted
+    pub async fn record_install_ping(
+        &self,
+        distro_id: i64,
+        install_hash: &str,
+        client_hash: &str,
+        max_per_client: i64,
+    ) -> Result<bool> {
+        let mut tx = self.pool().begin().await?;
+
+        // Yesterday's counters are only needed until today's start
+        sqlx::query("DELETE FROM ping_clients WHERE day < date('now')")
+            .execute(&mut *tx)
+            .await?;
+        let pings: i64 = sqlx::query_scalar(
+            "INSERT INTO ping_clients (client_hash, day, pings) VALUES (?, date('now'), 1)
+             ON CONFLICT (client_hash, day) DO UPDATE SET pings = pings + 1
+             RETURNING pings",
+        )
+        .bind(client_hash)
+        .fetch_one(&mut *tx)
+        .await?;
+        if pings > max_per_client {
+            tx.commit().await?;
+            return Ok(false);
+        }
+
+        sqlx::query(
+            "INSERT INTO install_pings (distro_id, install_hash) VALUES (?, ?)
+             ON CONFLICT (distro_id, install_hash)
+             DO UPDATE SET last_seen_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+        )
+        .bind(distro_id)
+        .bind(install_hash)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Forget installs that haven't pinged in 30 days, then record how many remain as a
+    /// popularity snapshot if there are at least `min_installs`; returns the count recorded
+    pub async fn record_ping_popularity(&self, distro_id: i64, min_installs: i64) -> Result<Option<i64>> {
+        sqlx::query(
+            "DELETE FROM install_pings
+             WHERE distro_id = ? AND last_seen_at < strftime('%Y-%m-%dT%H:%M:%SZ', 'now', '-30 days')",
+        )
+        .bind(distro_id)
+        .execute(self.pool())
+        .await?;
+
+        let installs: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM install_pings WHERE distro_id = ?")
+            .bind(distro_id)
+            .fetch_one(self.pool())
+            .await?;
+        if installs < min_installs {
+            return Ok(None);
+        }
+
+        self.write(Write::Popularity(NewPopularitySnapshot {
+            distro_id,
+            source: "ping".to_string(),
+            active_installs_30d: installs,
+        }))
+        .await?;
+        Ok(Some(installs))
+    }
+
+    /// Get the latest popularity snapshot for a distribution
+    pub async fn get_latest_popularity_snapshot(&self, distro_id: i64) -> Result<Option<PopularitySnapshot>> {
+        let row = sqlx::query_as::<_, PopularitySnapshot>(
+            "SELECT id, distro_id, source, active_installs_30d, collected_at
+             FROM popularity_snapshots
+             WHERE distro_id = ?
+             ORDER BY collected_at DESC, id DESC
+             LIMIT 1",
+        )
+        .bind(distro_id)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row)
+    }
+
+    // ==================== Ranking Snapshots ====================
+
+    /// Archive the current ranking order as `day`'s, replacing anything archived earlier that day
+    pub async fn archive_rankings(&self, day: NaiveDate) -> Result<u64> {
+        let mut tx = self.pool().begin().await?;
+
+        sqlx::query("DELETE FROM ranking_snapshots WHERE day = ?")
+            .bind(day)
+            .execute(&mut *tx)
+            .await?;
+        // Same order as get_all_latest_health_scores, ties broken so ranks are stable
+        let archived = sqlx::query(
+            "INSERT INTO ranking_snapshots
+             (day, distro_id, rank, overall_score, development_score, community_score, maintenance_score, trend)
+             SELECT ?, h.distro_id, ROW_NUMBER() OVER (ORDER BY h.overall_score DESC, h.distro_id),
+                    h.overall_score, h.development_score, h.community_score, h.maintenance_score, h.trend
+             FROM health_scores h
+             INNER JOIN (
+                 SELECT distro_id, MAX(calculated_at) as max_calc
+                 FROM health_scores
+                 GROUP BY distro_id
+             ) latest ON h.distro_id = latest.distro_id AND h.calculated_at = latest.max_calc
+             JOIN distributions d ON d.id = h.distro_id
+             WHERE d.archived_at IS NULL",
+        )
+        .bind(day)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+        Ok(archived)
+    }
+
+    /// Get the rankings archived on the last day at or before `day`, best first
+    pub async fn get_rankings_as_of(&self, day: NaiveDate) -> Result<Vec<RankingSnapshot>> {
+        let rows = sqlx::query_as::<_, RankingSnapshot>(
+            "SELECT r.day, r.distro_id, d.slug, d.name, r.rank, r.overall_score, r.development_score,
+                    r.community_score, r.maintenance_score, r.trend
+             FROM ranking_snapshots r
+             JOIN distributions d ON d.id = r.distro_id
+             WHERE r.day = (SELECT MAX(day) FROM ranking_snapshots WHERE day <= ?)
+             ORDER BY r.rank",
+        )
+        .bind(day)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    // ==================== Upstreams ====================
+
+    /// Get all upstream projects
+    pub async fn get_upstreams(&self) -> Result<Vec<Upstream>> {
+        let rows = sqlx::query_as::<_, Upstream>(
+            "SELECT id, name, slug, kind, homepage, github_org, repo_prefix,
+                    created_at
+             FROM upstreams ORDER BY name",
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Get an upstream project by slug
+    pub async fn get_upstream_by_slug(&self, slug: &str) -> Result<Upstream> {
+        sqlx::query_as::<_, Upstream>(
+            "SELECT id, name, slug, kind, homepage, github_org, repo_prefix,
+                    created_at
+             FROM upstreams WHERE slug = ?",
+        )
+        .bind(slug)
+        .fetch_optional(self.pool())
+        .await?
+        .ok_or_else(|| DatabaseError::NotFound(format!("Upstream: {}", slug)))
+    }
+
+    /// Get the upstream projects a distribution depends on
+    pub async fn get_distro_upstreams(&self, distro_id: i64) -> Result<Vec<Upstream>> {
+        let rows = sqlx::query_as::<_, Upstream>(
+            "SELECT u.id, u.name, u.slug, u.kind, u.homepage, u.github_org, u.repo_prefix,
+                    u.created_at as created_at
+             FROM upstreams u
+             INNER JOIN distro_upstreams du ON du.upstream_id = u.id
+             WHERE du.distro_id = ?
+             ORDER BY u.name",
+        )
+        .bind(distro_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Link a distribution to an upstream project
+    pub async fn link_distro_upstream(&self, distro_id: i64, upstream_id: i64) -> Result<()> {
+        sqlx::query("INSERT OR IGNORE INTO distro_upstreams (distro_id, upstream_id) VALUES (?, ?)")
+            .bind(distro_id)
+            .bind(upstream_id)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// Insert a new upstream snapshot
+    pub async fn insert_upstream_snapshot(&self, snapshot: NewUpstreamSnapshot) -> Result<i64> {
+        self.write(Write::Upstream(snapshot)).await
+    }
+
+    /// Get latest snapshots for an upstream project (most recent per repo)
+    pub async fn get_latest_upstream_snapshots(&self, upstream_id: i64) -> Result<Vec<UpstreamSnapshot>> {
+        let rows = sqlx::query_as::<_, UpstreamSnapshot>(
+            "SELECT s.id, s.upstream_id, s.repo_name, s.stars, s.forks, s.open_issues, s.open_prs,
+                    s.commits_30d, s.contributors_30d,
+                    s.last_commit_at as last_commit_at,
+                    s.collected_at as collected_at
+             FROM upstream_snapshots s
+             INNER JOIN (
+                 SELECT repo_name, MAX(collected_at) as max_collected
+                 FROM upstream_snapshots
+                 WHERE upstream_id = ?
+                 GROUP BY repo_name
+             ) latest ON s.repo_name = latest.repo_name AND s.collected_at = latest.max_collected
+             WHERE s.upstream_id = ?
+             ORDER BY s.repo_name",
+        )
+        .bind(upstream_id)
+        .bind(upstream_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    // ==================== Annotations ====================
+
+    /// Insert a new annotation
+    pub async fn insert_annotation(&self, annotation: NewAnnotation) -> Result<Annotation> {
+        let id = sqlx::query(
+            "INSERT INTO annotations (distro_id, annotated_on, note, author)
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(annotation.distro_id)
+        .bind(annotation.annotated_on)
+        .bind(&annotation.note)
+        .bind(&annotation.author)
+        .execute(self.pool())
+        .await?
+        .last_insert_rowid();
+
+        sqlx::query_as::<_, Annotation>(
+            "SELECT id, distro_id, annotated_on, note, author, created_at
+             FROM annotations WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(self.pool())
+        .await?
+        .ok_or_else(|| DatabaseError::NotFound(format!("Annotation ID: {}", id)))
+    }
+
+    /// Get annotations dated within a time window
+    pub async fn get_annotations_in(&self, distro_id: i64, window: &TimeWindow) -> Result<Vec<Annotation>> {
+        let rows = sqlx::query_as::<_, Annotation>(
+            "SELECT id, distro_id, annotated_on, note, author, created_at
+             FROM annotations
+             WHERE distro_id = ?
+             AND annotated_on >= date(?) AND annotated_on <= date(?)
+             ORDER BY annotated_on ASC",
+        )
+        .bind(distro_id)
+        .bind(window.since_sql())
+        .bind(window.until_sql())
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Get annotations across all distributions dated on or after a day
+    pub async fn get_annotations_since(&self, since: NaiveDate) -> Result<Vec<Annotation>> {
+        let rows = sqlx::query_as::<_, Annotation>(
+            "SELECT id, distro_id, annotated_on, note, author, created_at
+             FROM annotations
+             WHERE annotated_on >= ?
+             ORDER BY annotated_on ASC, id ASC",
+        )
+        .bind(since)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    // ==================== Claims & Maintainers ====================
+
+    /// Insert a new pending claim
+    pub async fn insert_claim(&self, claim: NewClaim) -> Result<Claim> {
+        let id = sqlx::query(
+            "INSERT INTO claims (distro_id, method, contact, challenge) VALUES (?, ?, ?, ?)",
+        )
+        .bind(claim.distro_id)
+        .bind(&claim.method)
+        .bind(&claim.contact)
+        .bind(&claim.challenge)
+        .execute(self.pool())
+        .await?
+        .last_insert_rowid();
+
+        self.get_claim(id).await
+    }
+
+    /// Get a claim by ID
+    pub async fn get_claim(&self, id: i64) -> Result<Claim> {
+        sqlx::query_as::<_, Claim>(
+            "SELECT id, distro_id, method, contact, challenge, status,
+                    created_at, verified_at
+             FROM claims WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(self.pool())
+        .await?
+        .ok_or_else(|| DatabaseError::NotFound(format!("Claim ID: {}", id)))
+    }
+
+    /// Get claims with the given status
+    pub async fn get_claims_by_status(&self, status: &str) -> Result<Vec<Claim>> {
+        let rows = sqlx::query_as::<_, Claim>(
+            "SELECT id, distro_id, method, contact, challenge, status,
+                    created_at, verified_at
+             FROM claims WHERE status = ?
+             ORDER BY created_at ASC",
+        )
+        .bind(status)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Mark a claim verified, activate its maintainer key and flag the distribution verified
+    pub async fn verify_claim(&self, claim_id: i64) -> Result<()> {
+        let claim = self.get_claim(claim_id).await?;
+        let mut tx = self.pool().begin().await?;
+
+        sqlx::query("UPDATE claims SET status = 'verified', verified_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = ?")
+            .bind(claim_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE maintainers SET active = 1 WHERE claim_id = ?")
+            .bind(claim_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("UPDATE distributions SET verified = 1, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = ?")
+            .bind(claim.distro_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Reject a pending claim
+    pub async fn reject_claim(&self, claim_id: i64) -> Result<()> {
+        sqlx::query("UPDATE claims SET status = 'rejected' WHERE id = ? AND status = 'pending'")
+            .bind(claim_id)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// Register a maintainer key for a claim (inactive until the claim is verified)
+    pub async fn insert_maintainer(&self, claim: &Claim, api_key_hash: &str) -> Result<i64> {
+        let id = sqlx::query(
+            "INSERT INTO maintainers (distro_id, claim_id, contact, api_key_hash) VALUES (?, ?, ?, ?)",
+        )
+        .bind(claim.distro_id)
+        .bind(claim.id)
+        .bind(&claim.contact)
+        .bind(api_key_hash)
+        .execute(self.pool())
+        .await?
+        .last_insert_rowid();
+
+        Ok(id)
+    }
+
+    /// Look up an active maintainer by API key hash
+    pub async fn get_active_maintainer(&self, api_key_hash: &str) -> Result<Option<Maintainer>> {
+        let row = sqlx::query_as::<_, Maintainer>(
+            "SELECT id, distro_id, claim_id, contact, api_key_hash, active,
+                    created_at
+             FROM maintainers
+             WHERE api_key_hash = ? AND active = 1",
+        )
+        .bind(api_key_hash)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row)
+    }
+
+    // ==================== Suggestions ====================
+
+    /// Insert a new pending suggestion
+    pub async fn insert_suggestion(&self, suggestion: NewSuggestion) -> Result<Suggestion> {
+        let id = sqlx::query(
+            "INSERT INTO suggestions
+             (name, slug, homepage, github_org, subreddit, description, os_family, reason, client_hash)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(&suggestion.name)
+        .bind(&suggestion.slug)
+        .bind(&suggestion.homepage)
+        .bind(&suggestion.github_org)
+        .bind(&suggestion.subreddit)
+        .bind(&suggestion.description)
+        .bind(&suggestion.os_family)
+        .bind(&suggestion.reason)
+        .bind(&suggestion.client_hash)
+        .execute(self.pool())
+        .await?
+        .last_insert_rowid();
+
+        self.get_suggestion(id).await
+    }
+
+    /// Get a suggestion by ID
+    pub async fn get_suggestion(&self, id: i64) -> Result<Suggestion> {
+        sqlx::query_as::<_, Suggestion>(
+            "SELECT id, name, slug, homepage, github_org, subreddit, description, os_family, reason,
+                    status, distro_id, client_hash,
+                    created_at, reviewed_at
+             FROM suggestions WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(self.pool())
+        .await?
+        .ok_or_else(|| DatabaseError::NotFound(format!("Suggestion ID: {}", id)))
+    }
+
+    /// Get suggestions with the given status
+    pub async fn get_suggestions_by_status(&self, status: &str) -> Result<Vec<Suggestion>> {
+        let rows = sqlx::query_as::<_, Suggestion>(
+            "SELECT id, name, slug, homepage, github_org, subreddit, description, os_family, reason,
+                    status, distro_id, client_hash,
+                    created_at, reviewed_at
+             FROM suggestions WHERE status = ?
+             ORDER BY created_at ASC",
+        )
+        .bind(status)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Count suggestions submitted by a client in the last N hours (for rate limiting)
+    pub async fn count_recent_suggestions(&self, client_hash: &str, hours: i32) -> Result<i64> {
+        let count: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM suggestions
+             WHERE client_hash = ? AND created_at >= strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ?)",
+        )
+        .bind(client_hash)
+        .bind(format!("-{} hours", hours))
+        .fetch_one(self.pool())
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Check whether a slug is taken by a distribution or a pending suggestion
+    pub async fn slug_in_use(&self, slug: &str) -> Result<bool> {
+        let taken: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM distributions WHERE slug = ?)
+                 OR EXISTS (SELECT 1 FROM suggestions WHERE slug = ? AND status = 'pending')",
+        )
+        .bind(slug)
+        .bind(slug)
+        .fetch_one(self.pool())
+        .await?;
+
+        Ok(taken)
+    }
+
+    /// Approve a pending suggestion, creating the distribution it proposes
+    pub async fn approve_suggestion(&self, id: i64) -> Result<Distribution> {
+        let suggestion = self.get_suggestion(id).await?;
+        if suggestion.status != "pending" {
+            return Err(DatabaseError::NotFound(format!("Pending suggestion ID: {}", id)));
+        }
+
+        let distro = self
+            .create_distribution(NewDistribution {
+                name: suggestion.name,
+                slug: suggestion.slug,
+                homepage: suggestion.homepage,
+                github_org: suggestion.github_org,
+                gitlab_group: None,
+                subreddit: suggestion.subreddit,
+                description: suggestion.description,
+                os_family: Some(suggestion.os_family),
+                repology_repo: None,
+            })
+            .await?;
+
+        sqlx::query(
+            "UPDATE suggestions SET status = 'approved', distro_id = ?, reviewed_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE id = ?",
+        )
+        .bind(distro.id)
+        .bind(id)
+        .execute(self.pool())
+        .await?;
+
+        Ok(distro)
+    }
+
+    /// Reject a pending suggestion
+    pub async fn reject_suggestion(&self, id: i64) -> Result<()> {
+        sqlx::query(
+            "UPDATE suggestions SET status = 'rejected', reviewed_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE id = ? AND status = 'pending'",
+        )
+        .bind(id)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    // ==================== Config Issues ====================
+
+    /// Record a problem with a configured field, refreshing any open issue for it
+    pub async fn open_config_issue(
+        &self,
+        distro_id: i64,
+        field: &str,
+        value: &str,
+        problem: &str,
+    ) -> Result<()> {
+        let updated = sqlx::query(
+            "UPDATE config_issues SET value = ?, problem = ?, last_seen_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE distro_id = ? AND field = ? AND resolved_at IS NULL",
+        )
+        .bind(value)
+        .bind(problem)
+        .bind(distro_id)
+        .bind(field)
+        .execute(self.pool())
+        .await?
+        .rows_affected();
+
+        if updated == 0 {
+            sqlx::query(
+                "INSERT INTO config_issues (distro_id, field, value, problem) VALUES (?, ?, ?, ?)",
+            )
+            .bind(distro_id)
+            .bind(field)
+            .bind(value)
+            .bind(problem)
+            .execute(self.pool())
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Mark any open issue for a field as resolved
+    pub async fn resolve_config_issue(&self, distro_id: i64, field: &str) -> Result<()> {
+        sqlx::query(
+            "UPDATE config_issues SET resolved_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE distro_id = ? AND field = ? AND resolved_at IS NULL",
+        )
+        .bind(distro_id)
+        .bind(field)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Get all unresolved config issues
+    pub async fn get_open_config_issues(&self) -> Result<Vec<ConfigIssue>> {
+        let rows = sqlx::query_as::<_, ConfigIssue>(
+            "SELECT c.id, c.distro_id, d.slug as distro_slug, c.field, c.value, c.problem,
+                    c.first_seen_at as first_seen_at,
+                    c.last_seen_at as last_seen_at
+             FROM config_issues c
+             JOIN distributions d ON d.id = c.distro_id
+             WHERE c.resolved_at IS NULL
+             ORDER BY d.slug, c.field",
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Get a distribution's unresolved config issues
+    pub async fn get_distro_config_issues(&self, distro_id: i64) -> Result<Vec<ConfigIssue>> {
+        let rows = sqlx::query_as::<_, ConfigIssue>(
+            "SELECT c.id, c.distro_id, d.slug as distro_slug, c.field, c.value, c.problem,
+                    c.first_seen_at as first_seen_at,
+                    c.last_seen_at as last_seen_at
+             FROM config_issues c
+             JOIN distributions d ON d.id = c.distro_id
+             WHERE c.distro_id = ? AND c.resolved_at IS NULL
+             ORDER BY c.field",
+        )
+        .bind(distro_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    // ==================== Org Aliases ====================
+
+    /// Record that a GitHub org was renamed
+    pub async fn record_org_alias(&self, old_name: &str, canonical_name: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO github_org_aliases (old_name, canonical_name) VALUES (?, ?)
+             ON CONFLICT(old_name) DO UPDATE SET canonical_name = excluded.canonical_name",
+        )
+        .bind(old_name)
+        .bind(canonical_name)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Resolve an org name through any recorded renames
+    pub async fn canonical_org(&self, name: &str) -> Result<String> {
+        let mut current = name.to_string();
+
+        // Bounded so an accidental alias cycle can't loop forever
+        for _ in 0..8 {
+            let next: Option<String> = sqlx::query_scalar(
+                "SELECT canonical_name FROM github_org_aliases WHERE old_name = ?",
+            )
+            .bind(&current)
+            .fetch_optional(self.pool())
+            .await?;
+
+            match next {
+                Some(next) if !next.eq_ignore_ascii_case(&current) => current = next,
+                _ => break,
+            }
+        }
+
+        Ok(current)
+    }
+
+    /// Get all recorded org renames
+    pub async fn get_org_aliases(&self) -> Result<Vec<OrgAlias>> {
+        let rows = sqlx::query_as::<_, OrgAlias>(
+            "SELECT old_name, canonical_name, created_at
+             FROM github_org_aliases ORDER BY old_name",
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Rename a GitHub org everywhere, stitching historical snapshots onto the new name
+    pub async fn rename_github_org(&self, old_name: &str, new_name: &str) -> Result<OrgRename> {
+        let mut tx = self.pool().begin().await?;
+        let old_prefix = format!("{}/", old_name);
+        let mut renamed = OrgRename::default();
+
+        sqlx::query(
+            "INSERT INTO github_org_aliases (old_name, canonical_name) VALUES (?, ?)
+             ON CONFLICT(old_name) DO UPDATE SET canonical_name = excluded.canonical_name",
+        )
+        .bind(old_name)
+        .bind(new_name)
+        .execute(&mut *tx)
+        .await?;
+
+        renamed.distributions = sqlx::query(
+            "UPDATE distributions SET github_org = ?, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE github_org = ? COLLATE NOCASE",
+        )
+        .bind(new_name)
+        .bind(old_name)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        renamed.upstreams = sqlx::query(
+            "UPDATE upstreams SET github_org = ? WHERE github_org = ? COLLATE NOCASE",
+        )
+        .bind(new_name)
+        .bind(old_name)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        for (table, counter) in [
+            ("github_snapshots", &mut renamed.github_snapshots),
+            ("release_snapshots", &mut renamed.release_snapshots),
+        ] {
+            *counter = sqlx::query(&format!(
+                "UPDATE {} SET repo_name = ? || substr(repo_name, length(?) + 1)
+                 WHERE substr(repo_name, 1, length(?)) = ? COLLATE NOCASE",
+                table
+            ))
+            .bind(format!("{}/", new_name))
+            .bind(&old_prefix)
+            .bind(&old_prefix)
+            .bind(&old_prefix)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        }
+
+        tx.commit().await?;
+        Ok(renamed)
+    }
+
+    // ==================== Highlights ====================
+
+    /// Record a newly selected highlight
+    pub async fn insert_highlight(&self, highlight: NewHighlight) -> Result<i64> {
+        let id = sqlx::query(
+            "INSERT INTO highlights (distro_id, reason, blurb, overall_score, score_change)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(highlight.distro_id)
+        .bind(&highlight.reason)
+        .bind(&highlight.blurb)
+        .bind(highlight.overall_score)
+        .bind(highlight.score_change)
+        .execute(self.pool())
+        .await?
+        .last_insert_rowid();
+
+        Ok(id)
+    }
+
+    /// Get the most recently selected highlight
+    pub async fn get_latest_highlight(&self) -> Result<Option<Highlight>> {
+        let row = sqlx::query_as::<_, Highlight>(
+            "SELECT h.id, h.distro_id, d.slug as distro_slug, d.name as distro_name, h.reason,
+                    h.blurb, h.overall_score, h.score_change, h.selected_at as selected_at
+             FROM highlights h
+             JOIN distributions d ON d.id = h.distro_id
+             WHERE d.archived_at IS NULL
+             ORDER BY h.selected_at DESC, h.id DESC
+             LIMIT 1",
+        )
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row)
+    }
+
+    // ==================== Milestones ====================
+
+    /// Record a milestone, returning false if it was already recorded
+    pub async fn insert_milestone(&self, milestone: NewMilestone) -> Result<bool> {
+        let result = sqlx::query(
+            "INSERT OR IGNORE INTO milestones (distro_id, kind, milestone_key, title, value)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(milestone.distro_id)
+        .bind(&milestone.kind)
+        .bind(&milestone.key)
+        .bind(&milestone.title)
+        .bind(milestone.value)
+        .execute(self.pool())
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        self.emit(Event::MilestoneReached {
+            distro_id: milestone.distro_id,
+            milestone_id: result.last_insert_rowid(),
+            kind: milestone.kind,
+            title: milestone.title,
+        })
+        .await;
+
+        Ok(true)
+    }
+
+    /// Get milestones since a point in time, newest first, optionally for one distribution
+    pub async fn get_milestones(
+        &self,
+        distro_id: Option<i64>,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<Milestone>> {
+        let rows = sqlx::query_as::<_, Milestone>(
+            "SELECT m.id, m.distro_id, d.slug as distro_slug, m.kind, m.title, m.value,
+                    m.occurred_at as occurred_at
+             FROM milestones m
+             JOIN distributions d ON d.id = m.distro_id
+             WHERE (? IS NULL AND d.archived_at IS NULL OR m.distro_id = ?)
+             AND m.occurred_at >= ?
+             ORDER BY m.occurred_at DESC, m.id DESC",
+        )
+        .bind(distro_id)
+        .bind(distro_id)
+        .bind(to_sql_timestamp(since))
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    // ==================== Score Freezes ====================
+
+    /// Start a score freeze now
+    pub async fn insert_score_freeze(&self, freeze: NewScoreFreeze) -> Result<ScoreFreeze> {
+        let id = sqlx::query("INSERT INTO score_freezes (distro_id, reason, ends_at) VALUES (?, ?, ?)")
+            .bind(freeze.distro_id)
+            .bind(&freeze.reason)
+            .bind(freeze.ends_at.map(to_sql_timestamp))
+            .execute(self.pool())
+            .await?
+            .last_insert_rowid();
+
+        sqlx::query_as::<_, ScoreFreeze>(
+            "SELECT id, distro_id, reason, starts_at, ends_at, created_at FROM score_freezes WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(self.pool())
+        .await?
+        .ok_or_else(|| DatabaseError::NotFound(format!("Score freeze ID: {}", id)))
+    }
+
+    /// Lift a freeze now, returning false if it doesn't exist or has already ended
+    pub async fn end_score_freeze(&self, id: i64) -> Result<bool> {
+        let now = to_sql_timestamp(Utc::now());
+        let result = sqlx::query(
+            "UPDATE score_freezes SET ends_at = ?
+             WHERE id = ? AND (ends_at IS NULL OR ends_at > ?)",
+        )
+        .bind(&now)
+        .bind(id)
+        .bind(&now)
+        .execute(self.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// The freeze in effect for a distro right now, its own before a global one
+    pub async fn get_active_score_freeze(&self, distro_id: i64) -> Result<Option<ScoreFreeze>> {
+        let now = to_sql_timestamp(Utc::now());
+        let row = sqlx::query_as::<_, ScoreFreeze>(
+            "SELECT id, distro_id, reason, starts_at, ends_at, created_at
+             FROM score_freezes
+             WHERE (distro_id = ? OR distro_id IS NULL)
+             AND starts_at <= ? AND (ends_at IS NULL OR ends_at > ?)
+             ORDER BY distro_id IS NULL, starts_at DESC
+             LIMIT 1",
+        )
+        .bind(distro_id)
+        .bind(&now)
+        .bind(&now)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row)
+    }
+
+    /// Get freezes, newest first; only those still in effect unless `include_ended`
+    pub async fn get_score_freezes(&self, include_ended: bool) -> Result<Vec<ScoreFreeze>> {
+        let rows = sqlx::query_as::<_, ScoreFreeze>(
+            "SELECT id, distro_id, reason, starts_at, ends_at, created_at
+             FROM score_freezes
+             WHERE ? OR ends_at IS NULL OR ends_at > ?
+             ORDER BY starts_at DESC, id DESC",
+        )
+        .bind(include_ended)
+        .bind(to_sql_timestamp(Utc::now()))
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Get a distro's freezes and global ones overlapping a time window, oldest first
+    pub async fn get_score_freezes_in(&self, distro_id: i64, window: &TimeWindow) -> Result<Vec<ScoreFreeze>> {
+        let rows = sqlx::query_as::<_, ScoreFreeze>(
+            "SELECT id, distro_id, reason, starts_at, ends_at, created_at
+             FROM score_freezes
+             WHERE (distro_id = ? OR distro_id IS NULL)
+             AND starts_at <= ? AND (ends_at IS NULL OR ends_at >= ?)
+             ORDER BY starts_at ASC, id ASC",
+        )
+        .bind(distro_id)
+        .bind(window.until_sql())
+        .bind(window.since_sql())
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    // ==================== Generic Metrics ====================
+
+    /// Record a value in the generic metrics table
+    pub async fn insert_metric(&self, metric: NewMetricValue) -> Result<i64> {
+        self.write(Write::Metric(metric)).await
+    }
+
+    /// Get the latest value of every generic metric for a distribution (most recent per source and key)
+    pub async fn get_latest_metrics(&self, distro_id: i64) -> Result<Vec<MetricValue>> {
+        SnapshotQuery::new().distro(distro_id).latest().fetch(self).await
+    }
+
+    // ==================== Collection Jobs ====================
+
+    /// Queue a collection job, returning its ID
+    pub async fn enqueue_collection_job(&self, distro_id: i64, source: &str) -> Result<i64> {
+        let id = sqlx::query("INSERT INTO collection_jobs (distro_id, source) VALUES (?, ?)")
+            .bind(distro_id)
+            .bind(source)
+            .execute(self.pool())
+            .await?
+            .last_insert_rowid();
+
+        Ok(id)
+    }
+
+    /// Queue a low-priority job unless one for the same distro and source was queued since
+    /// `since` and hasn't failed
+    ///
+    /// Returns the job ID and whether it's a new job rather than the earlier one.
+    pub async fn enqueue_refresh_job(
+        &self,
+        distro_id: i64,
+        source: &str,
+        since: DateTime<Utc>,
+    ) -> Result<(i64, bool)> {
+        let mut tx = self.pool().begin().await?;
+
+        let existing: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM collection_jobs
+             WHERE distro_id = ? AND source = ? AND created_at >= ? AND status != 'failed'
+             ORDER BY id DESC
+             LIMIT 1",
+        )
+        .bind(distro_id)
+        .bind(source)
+        .bind(to_sql_timestamp(since))
+        .fetch_optional(&mut *tx)
+        .await?;
+        if let Some(id) = existing {
+            return Ok((id, false));
+        }
+
+        let id = sqlx::query("INSERT INTO collection_jobs (distro_id, source, priority) VALUES (?, ?, ?)")
+            .bind(distro_id)
+            .bind(source)
+            .bind(REFRESH_PRIORITY)
+            .execute(&mut *tx)
+            .await?
+            .last_insert_rowid();
+        tx.commit().await?;
+
+        Ok((id, true))
+    }
+
+    /// Mark the oldest queued job with the highest priority as running and return it
+    pub async fn claim_collection_job(&self) -> Result<Option<CollectionJob>> {
+        let mut tx = self.pool().begin().await?;
+
+        let id: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM collection_jobs
+             WHERE status = 'queued'
+               AND (run_after IS NULL OR run_after <= strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+             ORDER BY priority DESC, id
+             LIMIT 1",
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(id) = id else {
+            return Ok(None);
+        };
+
+        sqlx::query(
+            "UPDATE collection_jobs
+             SET status = 'running', started_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now'),
+                 attempts = attempts + 1
+             WHERE id = ?",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        self.get_collection_job(id).await
+    }
+
+    /// Record how a running job ended: "done", "skipped" or "failed"
+    pub async fn finish_collection_job(&self, id: i64, status: &str, error: Option<&str>) -> Result<()> {
+        sqlx::query(
+            "UPDATE collection_jobs
+             SET status = ?, error = ?, finished_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')
+             WHERE id = ?",
+        )
+        .bind(status)
+        .bind(error)
+        .bind(id)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Put a job that failed transiently back in the queue, to run no sooner than `run_after`
+    pub async fn retry_collection_job(&self, id: i64, error: &str, run_after: DateTime<Utc>) -> Result<()> {
+        sqlx::query(
+            "UPDATE collection_jobs
+             SET status = 'queued', error = ?, run_after = ?, started_at = NULL
+             WHERE id = ?",
+        )
+        .bind(error)
+        .bind(to_sql_timestamp(run_after))
+        .bind(id)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Put jobs left running by a previous process back in the queue
+    pub async fn requeue_running_collection_jobs(&self) -> Result<u64> {
+        let requeued = sqlx::query(
+            "UPDATE collection_jobs SET status = 'queued', started_at = NULL WHERE status = 'running'",
+        )
+        .execute(self.pool())
+        .await?
+        .rows_affected();
+
+        Ok(requeued)
+    }
+
+    /// Get a collection job by ID
+    pub async fn get_collection_job(&self, id: i64) -> Result<Option<CollectionJob>> {
+        let row = sqlx::query_as::<_, CollectionJob>(
+            "SELECT j.id, j.distro_id, d.slug as distro_slug, j.source, j.status, j.error,
+                    j.attempts, j.priority, j.created_at, j.started_at, j.finished_at
+             FROM collection_jobs j
+             JOIN distributions d ON d.id = j.distro_id
+             WHERE j.id = ?",
+        )
+        .bind(id)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row)
+    }
+
+    /// Count queued and running collection jobs
+    pub async fn get_collection_queue_depth(&self) -> Result<QueueDepth> {
+        let depth = sqlx::query_as::<_, QueueDepth>(
+            "SELECT COALESCE(SUM(status = 'queued'), 0) as queued,
+                    COALESCE(SUM(status = 'running'), 0) as running
+             FROM collection_jobs
+             WHERE status IN ('queued', 'running')",
+        )
+        .fetch_one(self.pool())
+        .await?;
+
+        Ok(depth)
+    }
+
+    // ==================== Collection Runs ====================
+
+    /// Record a finished collection of one source for a distro
+    ///
+    /// `failure` is set when the collection failed; `snapshots` counts the rows written.
+    pub async fn record_collection_run(
+        &self,
+        distro_id: i64,
+        source: &str,
+        started_at: DateTime<Utc>,
+        snapshots: i64,
+        failure: Option<&RunFailure>,
+    ) -> Result<i64> {
+        self.write(Write::CollectionRun {
+            distro_id,
+            source: source.to_string(),
+            started_at,
+            snapshots,
+            failure: failure.cloned(),
+        })
+        .await
+    }
+
+    /// When each source last started a successful collection for a distro
+    pub async fn get_last_successful_collections(&self, distro_id: i64) -> Result<HashMap<String, DateTime<Utc>>> {
+        let rows: Vec<(String, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT source, MAX(started_at)
+             FROM collection_runs
+             WHERE distro_id = ? AND error IS NULL
+             GROUP BY source",
+        )
+        .bind(distro_id)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.into_iter().collect())
+    }
+
+    /// When each source last collected successfully and scores were last calculated
+    pub async fn get_data_freshness(&self) -> Result<DataFreshness> {
+        let collected: Vec<(String, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT source, MAX(finished_at)
+             FROM collection_runs
+             WHERE error IS NULL
+             GROUP BY source",
+        )
+        .fetch_all(self.pool())
+        .await?;
+        let scored_at: Option<DateTime<Utc>> = sqlx::query_scalar("SELECT MAX(calculated_at) FROM health_scores")
+            .fetch_one(self.pool())
+            .await?;
+
+        Ok(DataFreshness {
+            collected_at: collected.into_iter().collect(),
+            scored_at,
+        })
+    }
+
+    /// Per-source run counts since a time, with each source's latest run ever
+    pub async fn get_collection_run_stats(&self, since: DateTime<Utc>) -> Result<Vec<CollectionRunStats>> {
+        let rows = sqlx::query_as::<_, CollectionRunStats>(
+            "SELECT r.source, MAX(r.finished_at) as last_run_at,
+                    (SELECT error FROM collection_runs l
+                     WHERE l.source = r.source
+                     ORDER BY l.finished_at DESC, l.id DESC
+                     LIMIT 1) as last_error,
+                    COALESCE(SUM(r.finished_at >= ?1), 0) as runs,
+                    COALESCE(SUM(r.finished_at >= ?1 AND r.error IS NOT NULL), 0) as failures
+             FROM collection_runs r
+             GROUP BY r.source
+             ORDER BY r.source",
+        )
+        .bind(to_sql_timestamp(since))
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Active distros whose latest `min_failures` or more runs of a source all failed
+    pub async fn get_repeated_collection_failures(&self, min_failures: i64) -> Result<Vec<RepeatedFailure>> {
+        let rows = sqlx::query_as::<_, RepeatedFailure>(
+            "WITH ranked AS (
+                 SELECT distro_id, source, error, error_kind, retryable, finished_at,
+                        ROW_NUMBER() OVER (
+                            PARTITION BY distro_id, source ORDER BY finished_at DESC, id DESC
+                        ) as position
+                 FROM collection_runs
+             ),
+             last_success AS (
+                 SELECT distro_id, source, MIN(position) as position
+                 FROM ranked
+                 WHERE error IS NULL
+                 GROUP BY distro_id, source
+             )
+             SELECT d.slug as distro_slug, d.name as distro_name, r.source,
+                    COUNT(*) as consecutive_failures,
+                    MAX(CASE WHEN r.position = 1 THEN r.error END) as last_error,
+                    MAX(CASE WHEN r.position = 1 THEN r.error_kind END) as last_error_kind,
+                    MAX(CASE WHEN r.position = 1 THEN r.retryable END) as retryable,
+                    MAX(r.finished_at) as last_failed_at
+             FROM ranked r
+             JOIN distributions d ON d.id = r.distro_id
+             LEFT JOIN last_success s ON s.distro_id = r.distro_id AND s.source = r.source
+             WHERE d.archived_at IS NULL
+               AND r.position < COALESCE(s.position, 9223372036854775807)
+             GROUP BY r.distro_id, r.source
+             HAVING COUNT(*) >= ?
+             ORDER BY consecutive_failures DESC, d.slug, r.source",
+        )
+        .bind(min_failures)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Latest run of each source collected for a distro since a time, with its latest success
+    pub async fn get_source_run_status(&self, distro_id: i64, since: DateTime<Utc>) -> Result<Vec<SourceRunStatus>> {
+        let rows = sqlx::query_as::<_, SourceRunStatus>(
+            "WITH ranked AS (
+                 SELECT source, error, error_kind, finished_at,
+                        ROW_NUMBER() OVER (PARTITION BY source ORDER BY finished_at DESC, id DESC) as position
+                 FROM collection_runs
+                 WHERE distro_id = ?1 AND finished_at >= ?2
+             )
+             SELECT r.source, r.finished_at as last_run_at, r.error as last_error,
+                    r.error_kind as last_error_kind,
+                    (SELECT MAX(s.finished_at) FROM collection_runs s
+                     WHERE s.distro_id = ?1 AND s.source = r.source AND s.error IS NULL) as last_success_at
+             FROM ranked r
+             WHERE r.position = 1
+             ORDER BY r.source",
+        )
+        .bind(distro_id)
+        .bind(to_sql_timestamp(since))
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    /// Active distributions with snapshots collected since they were last analyzed
+    ///
+    /// Runs finishing in the second an analysis started count as after it; analyzing again
+    /// is cheaper than missing them. Distros analyzed before that was recorded go by their
+    /// latest health score.
+    pub async fn get_distros_pending_analysis(&self) -> Result<Vec<Distribution>> {
+        let distros = sqlx::query_as::<_, Distribution>(
+            "SELECT id, name, slug, homepage, github_org, gitlab_group, forgejo_org, pagure_namespace, forge_kind, forge_base_url,
+                    subreddit, discussions_repo, description, os_family, repology_repo, verified, bot_accounts, aggregation, based_on,
+                    mirror_list_url, mirror_list_format, iso_url, update_index_url, update_index_format,
+                    release_model, bugzilla_url, bugzilla_product, variant_of, archived_at, created_at, updated_at
+             FROM distributions d
+             WHERE archived_at IS NULL
+               AND EXISTS (
+                   SELECT 1 FROM collection_runs r
+                   WHERE r.distro_id = d.id
+                     AND r.snapshots > 0
+                     AND r.finished_at >= COALESCE(
+                         d.analyzed_at,
+                         (SELECT MAX(h.calculated_at) FROM health_scores h WHERE h.distro_id = d.id),
+                         ''
+                     )
+               )
+             ORDER BY name",
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(distros)
+    }
+
+    /// Record that an analysis starting at `at` considered the distro's snapshots, whatever
+    /// it decided, so they no longer leave it pending
+    pub async fn mark_distro_analyzed(&self, distro_id: i64, at: DateTime<Utc>) -> Result<()> {
+        sqlx::query("UPDATE distributions SET analyzed_at = ? WHERE id = ?")
+            .bind(to_sql_timestamp(at))
+            .bind(distro_id)
+            .execute(self.pool())
+            .await?;
+
+        Ok(())
+    }
+
+    // ==================== Settings ====================
+
+    /// Get an instance setting
+    pub async fn get_setting(&self, key: &str) -> Result<Option<String>> {
+        let value = sqlx::query_scalar("SELECT value FROM settings WHERE key = ?")
+            .bind(key)
+            .fetch_optional(self.pool())
+            .await?;
+
+        Ok(value)
+    }
+
+    /// Set an instance setting, replacing any previous value
+    pub async fn set_setting(&self, key: &str, value: &str) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO settings (key, value) VALUES (?, ?)
+             ON CONFLICT(key) DO UPDATE SET value = excluded.value,
+                 updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+        )
+        .bind(key)
+        .bind(value)
+        .execute(self.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    // ==================== Circuit Breakers ====================
+
+    /// Save a circuit breaker's current state
+    pub async fn upsert_breaker_status(&self, status: &BreakerStatus) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO circuit_breakers (service, state, consecutive_failures, opened_at, updated_at)
+             VALUES (?, ?, ?, ?, ?)
+             ON CONFLICT(service) DO UPDATE SET
+                 state = excluded.state,
+                 consecutive_failures = excluded.consecutive_failures,
+                 opened_at = excluded.opened_at,
+                 updated_at = excluded.updated_at",
+        )
+        .bind(&status.service)
+        .bind(&status.state)
+        .bind(status.consecutive_failures)
+        .bind(status.opened_at.map(to_sql_timestamp))
+        .bind(to_sql_timestamp(status.updated_at))
+        .execute(self.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    /// Last saved state of every circuit breaker
+    pub async fn get_breaker_statuses(&self) -> Result<Vec<BreakerStatus>> {
+        let rows = sqlx::query_as::<_, BreakerStatus>(
+            "SELECT service, state, consecutive_failures, opened_at, updated_at
+             FROM circuit_breakers
+             ORDER BY service",
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows)
+    }
+
+    // ==================== Methodology ====================
+
+    /// Scoring methodology changelog, newest first
+    pub async fn get_methodology_changes(&self) -> Result<Vec<MethodologyChange>> {
+        let changes = sqlx::query_as::<_, MethodologyChange>(
+            "SELECT version, summary, changed_at FROM methodology_changes ORDER BY changed_at DESC, version DESC",
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(changes)
+    }
+
+    // ==================== Bulk Export ====================
+
+    /// Time of the most recent write to distributions, snapshots, scores or metrics
+    ///
+    /// Stamps bulk exports so clients can tell whether anything changed since their copy.
+    pub async fn get_data_version(&self) -> Result<Option<DateTime<Utc>>> {
+        let latest: Option<DateTime<Utc>> = sqlx::query_scalar(
+            "SELECT MAX(t) FROM (
+                 SELECT MAX(updated_at) AS t FROM distributions
+                 UNION ALL SELECT MAX(calculated_at) FROM health_scores
+                 UNION ALL SELECT MAX(collected_at) FROM github_snapshots
+                 UNION ALL SELECT MAX(collected_at) FROM release_snapshots
+                 UNION ALL SELECT MAX(collected_at) FROM package_snapshots
+                 UNION ALL SELECT MAX(collected_at) FROM community_snapshots
+                 UNION ALL SELECT MAX(collected_at) FROM metrics
+                 UNION ALL SELECT MAX(collected_at) FROM infra_snapshots
+                 UNION ALL SELECT MAX(collected_at) FROM archive_snapshots
+                 UNION ALL SELECT MAX(collected_at) FROM bugtracker_snapshots
+                 UNION ALL SELECT MAX(collected_at) FROM popularity_snapshots
+             )",
+        )
+        .fetch_one(self.pool())
+        .await?;
+
+        Ok(latest)
+    }
 }
@@ -1,40 +1,98 @@
 //! Database schema and connection management
 
-use crate::{DatabaseError, Result};
+use crate::config::DatabaseConfig;
+use crate::events::Event;
+use crate::timestamp::TIMESTAMP_COLUMNS;
+use crate::writer::{Write, Writer};
+use crate::{DatabaseError, Result, TIMESTAMP_FORMAT};
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
 use sqlx::SqlitePool;
 use std::path::Path;
 use std::str::FromStr;
-use tracing::info;
+#[cfg(feature = "events")]
+use std::sync::Arc;
+use tracing::{info, warn};
 
 /// Database connection wrapper
 #[derive(Clone)]
 pub struct Database {
     pool: SqlitePool,
+    config: DatabaseConfig,
+    /// Serializes snapshot inserts onto one connection
+    writer: Writer,
+    #[cfg(feature = "events")]
+    events: Option<Arc<crate::events::EventPublisher>>,
 }
 
 impl Database {
     /// Connect to an existing database or create a new one
     pub async fn connect(path: &Path) -> Result<Self> {
+        Self::connect_with(path, DatabaseConfig::default()).await
+    }
+
+    /// Connect with explicit pool and SQLite tuning
+    pub async fn connect_with(path: &Path, config: DatabaseConfig) -> Result<Self> {
         let url = format!("sqlite:{}?mode=rwc", path.display());
 
         let options = SqliteConnectOptions::from_str(&url)?
             .create_if_missing(true)
             .journal_mode(sqlx::sqlite::SqliteJournalMode::Wal)
-            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal);
+            .synchronous(sqlx::sqlite::SqliteSynchronous::Normal)
+            .busy_timeout(config.busy_timeout)
+            // Negative sizes are in KiB rather than pages
+            .pragma("cache_size", format!("-{}", config.cache_size_kib))
+            .pragma("mmap_size", config.mmap_size.to_string());
 
         let pool = SqlitePoolOptions::new()
-            .max_connections(5)
+            .max_connections(config.pool_size)
+            .connect_with(options.clone())
+            .await?;
+        let writer_pool = SqlitePoolOptions::new()
+            .max_connections(1)
             .connect_with(options)
             .await?;
 
-        let db = Self { pool };
+        let db = Self {
+            pool,
+            config,
+            writer: Writer::spawn(writer_pool),
+            #[cfg(feature = "events")]
+            events: None,
+        };
         db.run_migrations().await?;
 
         info!("Database connected: {}", path.display());
         Ok(db)
     }
 
+    /// Open a read-only replica of the database
+    ///
+    /// The replica is maintained elsewhere (Litestream, LiteFS), so migrations aren't run
+    /// and the journal mode is left alone; any write through it fails.
+    pub async fn connect_replica(path: &Path, config: DatabaseConfig) -> Result<Self> {
+        let url = format!("sqlite:{}?mode=ro", path.display());
+
+        let options = SqliteConnectOptions::from_str(&url)?
+            .read_only(true)
+            .busy_timeout(config.busy_timeout)
+            .pragma("cache_size", format!("-{}", config.cache_size_kib))
+            .pragma("mmap_size", config.mmap_size.to_string());
+
+        let pool = SqlitePoolOptions::new()
+            .max_connections(config.pool_size)
+            .connect_with(options)
+            .await?;
+
+        info!("Read replica connected: {}", path.display());
+        Ok(Self {
+            writer: Writer::spawn(pool.clone()),
+            pool,
+            config,
+            #[cfg(feature = "events")]
+            events: None,
+        })
+    }
+
     /// Connect to an in-memory database (for testing)
     pub async fn in_memory() -> Result<Self> {
         let pool = SqlitePoolOptions::new()
@@ -42,7 +100,14 @@ impl Database {
             .connect("sqlite::memory:")
             .await?;
 
-        let db = Self { pool };
+        // Each connection to :memory: is its own database, so the writer shares the pool
+        let db = Self {
+            writer: Writer::spawn(pool.clone()),
+            pool,
+            config: DatabaseConfig::default(),
+            #[cfg(feature = "events")]
+            events: None,
+        };
         db.run_migrations().await?;
 
         info!("In-memory database initialized");
@@ -54,6 +119,73 @@ impl Database {
         &self.pool
     }
 
+    /// The pool and SQLite tuning this database was opened with
+    pub fn config(&self) -> &DatabaseConfig {
+        &self.config
+    }
+
+    /// Fold the WAL back into the database file and truncate it
+    ///
+    /// Long-running processes call this periodically; with readers always active the
+    /// automatic checkpoints can't finish and the WAL grows without bound.
+    pub async fn checkpoint(&self) -> Result<()> {
+        let (busy, log_frames, checkpointed): (i64, i64, i64) =
+            sqlx::query_as("PRAGMA wal_checkpoint(TRUNCATE)")
+                .fetch_one(&self.pool)
+                .await?;
+        if busy != 0 {
+            warn!(log_frames, checkpointed, "WAL checkpoint could not finish, readers still active");
+        }
+        Ok(())
+    }
+
+    /// Refresh query planner statistics and close the pool
+    pub async fn close(&self) {
+        if let Err(e) = sqlx::query("PRAGMA optimize").execute(&self.pool).await {
+            warn!("PRAGMA optimize failed: {}", e);
+        }
+        self.writer.close().await;
+        self.pool.close().await;
+        #[cfg(feature = "events")]
+        if let Some(publisher) = &self.events {
+            publisher.close().await;
+        }
+    }
+
+    /// Publish change events through the given publisher
+    #[cfg(feature = "events")]
+    pub fn with_events(mut self, publisher: crate::events::EventPublisher) -> Self {
+        self.events = Some(Arc::new(publisher));
+        self
+    }
+
+    /// Queue an insert on the writer and announce the row once it's committed
+    pub(crate) async fn write(&self, write: Write) -> Result<i64> {
+        let target = write.event_target();
+        let id = self.writer.submit(write).await?;
+        if let Some((source, distro_id, upstream_id)) = target {
+            self.emit(Event::SnapshotInserted {
+                source,
+                distro_id,
+                upstream_id,
+                snapshot_id: id,
+            })
+            .await;
+        }
+        Ok(id)
+    }
+
+    /// Hand an event to the publisher, if one is attached
+    pub(crate) async fn emit(&self, event: Event) {
+        #[cfg(feature = "events")]
+        if let Some(publisher) = &self.events {
+            publisher.publish(&event);
+        }
+
+        #[cfg(not(feature = "events"))]
+        let _ = event;
+    }
+
     /// Run database migrations
     async fn run_migrations(&self) -> Result<()> {
         // Run base schema (tables without subreddit for backwards compat)
@@ -64,12 +196,7 @@ impl Database {
 
         // Run incremental migrations (adds subreddit column if needed)
         self.run_incremental_migrations().await?;
-
-        // Seed distributions (with subreddit now available)
-        sqlx::query(SEED_DATA)
-            .execute(&self.pool)
-            .await
-            .map_err(|e| DatabaseError::Migration(e.to_string()))?;
+        self.normalize_timestamps().await?;
 
         Ok(())
     }
@@ -196,10 +323,452 @@ impl Database {
             info!("Added description column and populated data");
         }
 
+        // Add os_family and repology_repo columns so non-Linux systems can be tracked
+        if !self.has_column("distributions", "os_family").await {
+            sqlx::query("ALTER TABLE distributions ADD COLUMN os_family TEXT NOT NULL DEFAULT 'linux'")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add os_family column: {}", e)))?;
+
+            info!("Added os_family column to distributions");
+        }
+
+        if !self.has_column("distributions", "repology_repo").await {
+            sqlx::query("ALTER TABLE distributions ADD COLUMN repology_repo TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add repology_repo column: {}", e)))?;
+
+            info!("Added repology_repo column to distributions");
+        }
+
+        if !self.has_column("distributions", "verified").await {
+            sqlx::query("ALTER TABLE distributions ADD COLUMN verified INTEGER NOT NULL DEFAULT 0")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add verified column: {}", e)))?;
+
+            info!("Added verified column to distributions");
+        }
+
+        // Add kernel tracking columns to package_snapshots
+        for column in ["kernel_version", "kernel_series", "kernel_status"] {
+            if !self.has_column("package_snapshots", column).await {
+                sqlx::query(&format!("ALTER TABLE package_snapshots ADD COLUMN {} TEXT", column))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("Failed to add {} column: {}", column, e)))?;
+
+                info!("Added {} column to package_snapshots", column);
+            }
+        }
+
+        // Upstream's newest kernel and the Mesa versions, for hardware enablement lag
+        for column in ["kernel_upstream", "mesa_version", "mesa_upstream"] {
+            if !self.has_column("package_snapshots", column).await {
+                sqlx::query(&format!("ALTER TABLE package_snapshots ADD COLUMN {} TEXT", column))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("Failed to add {} column: {}", column, e)))?;
+
+                info!("Added {} column to package_snapshots", column);
+            }
+        }
+
+        // Arch's own repo and AUR counts
+        for column in ["official_packages", "flagged_packages", "aur_packages"] {
+            if !self.has_column("package_snapshots", column).await {
+                sqlx::query(&format!("ALTER TABLE package_snapshots ADD COLUMN {} INTEGER", column))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("Failed to add {} column: {}", column, e)))?;
+
+                info!("Added {} column to package_snapshots", column);
+            }
+        }
+
+        // Per-distro bot accounts, on top of the collector's default list
+        if !self.has_column("distributions", "bot_accounts").await {
+            sqlx::query("ALTER TABLE distributions ADD COLUMN bot_accounts TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add bot_accounts column: {}", e)))?;
+
+            info!("Added bot_accounts column to distributions");
+        }
+
+        // How per-repo metrics are combined: "sum" (default), "mean", "top-k[:N]"
+        if !self.has_column("distributions", "aggregation").await {
+            sqlx::query("ALTER TABLE distributions ADD COLUMN aggregation TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add aggregation column: {}", e)))?;
+
+            info!("Added aggregation column to distributions");
+        }
+
+        // Parent distro slug, used to group derivatives into families
+        if !self.has_column("distributions", "based_on").await {
+            sqlx::query("ALTER TABLE distributions ADD COLUMN based_on TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add based_on column: {}", e)))?;
+
+            info!("Added based_on column to distributions");
+        }
+
+        // Where to find each distro's official mirror list, and how to read it
+        for column in ["mirror_list_url", "mirror_list_format"] {
+            if !self.has_column("distributions", column).await {
+                sqlx::query(&format!("ALTER TABLE distributions ADD COLUMN {} TEXT", column))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("Failed to add {} column: {}", column, e)))?;
+
+                info!("Added {} column to distributions", column);
+            }
+        }
+
+        // Installer image and package index whose download sizes are tracked
+        for column in ["iso_url", "update_index_url", "update_index_format"] {
+            if !self.has_column("distributions", column).await {
+                sqlx::query(&format!("ALTER TABLE distributions ADD COLUMN {} TEXT", column))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("Failed to add {} column: {}", column, e)))?;
+
+                info!("Added {} column to distributions", column);
+            }
+        }
+
+        // Codeberg organization for distros that moved off GitHub
+        if !self.has_column("distributions", "forgejo_org").await {
+            sqlx::query("ALTER TABLE distributions ADD COLUMN forgejo_org TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add forgejo_org column: {}", e)))?;
+
+            info!("Added forgejo_org column to distributions");
+        }
+
+        // Self-hosted GitLab or Forgejo instance the distro's group or org lives on
+        for column in ["forge_kind", "forge_base_url"] {
+            if !self.has_column("distributions", column).await {
+                sqlx::query(&format!("ALTER TABLE distributions ADD COLUMN {} TEXT", column))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("Failed to add {} column: {}", column, e)))?;
+
+                info!("Added {} column to distributions", column);
+            }
+        }
+
+        // "rolling" or "point": hardware enablement lag only applies to point releases
+        if !self.has_column("distributions", "release_model").await {
+            sqlx::query("ALTER TABLE distributions ADD COLUMN release_model TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add release_model column: {}", e)))?;
+
+            info!("Added release_model column to distributions");
+        }
+
+        // Bugzilla instance and product the distro's bugs are filed under
+        for column in ["bugzilla_url", "bugzilla_product"] {
+            if !self.has_column("distributions", column).await {
+                sqlx::query(&format!("ALTER TABLE distributions ADD COLUMN {} TEXT", column))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("Failed to add {} column: {}", column, e)))?;
+
+                info!("Added {} column to distributions", column);
+            }
+        }
+
+        // Parent slug of variants, which are scored on their parent's development data
+        if !self.has_column("distributions", "variant_of").await {
+            sqlx::query("ALTER TABLE distributions ADD COLUMN variant_of TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add variant_of column: {}", e)))?;
+
+            info!("Added variant_of column to distributions");
+        }
+
+        // Pagure namespace of the distro's dist-git, collected for package maintenance commits
+        if !self.has_column("distributions", "pagure_namespace").await {
+            sqlx::query("ALTER TABLE distributions ADD COLUMN pagure_namespace TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add pagure_namespace column: {}", e)))?;
+
+            info!("Added pagure_namespace column to distributions");
+        }
+
+        // GitHub repo hosting the distro's Discussions, collected as a community source
+        if !self.has_column("distributions", "discussions_repo").await {
+            sqlx::query("ALTER TABLE distributions ADD COLUMN discussions_repo TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add discussions_repo column: {}", e)))?;
+
+            info!("Added discussions_repo column to distributions");
+        }
+
+        // Archived distros are hidden from listings but keep their history
+        if !self.has_column("distributions", "archived_at").await {
+            sqlx::query("ALTER TABLE distributions ADD COLUMN archived_at TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add archived_at column: {}", e)))?;
+
+            info!("Added archived_at column to distributions");
+        }
+
+        if !self.has_column("github_snapshots", "is_mirror").await {
+            sqlx::query("ALTER TABLE github_snapshots ADD COLUMN is_mirror INTEGER NOT NULL DEFAULT 0")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add is_mirror column: {}", e)))?;
+
+            info!("Added is_mirror column to github_snapshots");
+        }
+
+        if !self.has_column("github_snapshots", "topics").await {
+            sqlx::query("ALTER TABLE github_snapshots ADD COLUMN topics TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add topics column: {}", e)))?;
+
+            info!("Added topics column to github_snapshots");
+        }
+
+        // Releases inferred from git tags for repos that don't publish GitHub Releases
+        if !self.has_column("release_snapshots", "is_tag").await {
+            sqlx::query("ALTER TABLE release_snapshots ADD COLUMN is_tag INTEGER NOT NULL DEFAULT 0")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add is_tag column: {}", e)))?;
+
+            info!("Added is_tag column to release_snapshots");
+        }
+
+        // Whether a release ships detached signatures / checksum files; NULL when unknown
+        for column in ["signed", "checksummed"] {
+            if !self.has_column("release_snapshots", column).await {
+                sqlx::query(&format!("ALTER TABLE release_snapshots ADD COLUMN {} INTEGER", column))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("Failed to add {} column: {}", column, e)))?;
+
+                info!("Added {} column to release_snapshots", column);
+            }
+        }
+
+        // Summed asset download counts; NULL for releases without assets
+        if !self.has_column("release_snapshots", "downloads").await {
+            sqlx::query("ALTER TABLE release_snapshots ADD COLUMN downloads INTEGER")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add downloads column: {}", e)))?;
+
+            info!("Added downloads column to release_snapshots");
+        }
+
+        // How each snapshot was fetched, as JSON
+        for table in [
+            "github_snapshots",
+            "release_snapshots",
+            "package_snapshots",
+            "community_snapshots",
+            "upstream_snapshots",
+        ] {
+            if !self.has_column(table, "provenance").await {
+                sqlx::query(&format!("ALTER TABLE {} ADD COLUMN provenance TEXT", table))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("Failed to add provenance column: {}", e)))?;
+
+                info!("Added provenance column to {}", table);
+            }
+        }
+
+        // How a failed collection failed, and whether trying again could help
+        for (column, definition) in [("error_kind", "TEXT"), ("retryable", "INTEGER")] {
+            if !self.has_column("collection_runs", column).await {
+                sqlx::query(&format!("ALTER TABLE collection_runs ADD COLUMN {} {}", column, definition))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("Failed to add {} column: {}", column, e)))?;
+
+                info!("Added {} column to collection_runs", column);
+            }
+        }
+
+        // Jobs that failed transiently are requeued with a delay, a limited number of times;
+        // public refreshes run at a lower priority
+        for (column, definition) in [
+            ("attempts", "INTEGER NOT NULL DEFAULT 0"),
+            ("run_after", "TEXT"),
+            ("priority", "INTEGER NOT NULL DEFAULT 0"),
+        ] {
+            if !self.has_column("collection_jobs", column).await {
+                sqlx::query(&format!("ALTER TABLE collection_jobs ADD COLUMN {} {}", column, definition))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("Failed to add {} column: {}", column, e)))?;
+
+                info!("Added {} column to collection_jobs", column);
+            }
+        }
+
+        // Bot commits are excluded from commits_30d/365d but recorded separately
+        for column in ["bot_commits_30d", "bot_commits_365d"] {
+            if !self.has_column("github_snapshots", column).await {
+                sqlx::query(&format!(
+                    "ALTER TABLE github_snapshots ADD COLUMN {} INTEGER NOT NULL DEFAULT 0",
+                    column
+                ))
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add {} column: {}", column, e)))?;
+
+                info!("Added {} column to github_snapshots", column);
+            }
+        }
+
+        // Questions and accepted answers, for community sources that mark answers
+        for column in ["questions_30d", "answers_30d"] {
+            if !self.has_column("community_snapshots", column).await {
+                sqlx::query(&format!("ALTER TABLE community_snapshots ADD COLUMN {} INTEGER", column))
+                    .execute(&self.pool)
+                    .await
+                    .map_err(|e| DatabaseError::Migration(format!("Failed to add {} column: {}", column, e)))?;
+
+                info!("Added {} column to community_snapshots", column);
+            }
+        }
+
+        // Hash of the snapshots a score was computed from, so an unchanged batch isn't rescored
+        if !self.has_column("health_scores", "inputs_fingerprint").await {
+            sqlx::query("ALTER TABLE health_scores ADD COLUMN inputs_fingerprint TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add inputs_fingerprint column: {}", e)))?;
+
+            info!("Added inputs_fingerprint column to health_scores");
+        }
+
+        // When the distro was last analyzed, whether or not that recorded a score
+        if !self.has_column("distributions", "analyzed_at").await {
+            sqlx::query("ALTER TABLE distributions ADD COLUMN analyzed_at TEXT")
+                .execute(&self.pool)
+                .await
+                .map_err(|e| DatabaseError::Migration(format!("Failed to add analyzed_at column: {}", e)))?;
+
+            info!("Added analyzed_at column to distributions");
+        }
+
         Ok(())
     }
+
+    /// Convert timestamps written before RFC 3339 storage, once per database
+    ///
+    /// Legacy rows hold `YYYY-MM-DD HH:MM:SS` from `datetime('now')` or `+00:00` offsets
+    /// from bound values; both are rewritten to the stored format. Column defaults are
+    /// updated in place through `writable_schema`, the schema edit SQLite documents for
+    /// changing defaults without rebuilding tables. Progress is tracked in `user_version`.
+    async fn normalize_timestamps(&self) -> Result<()> {
+        let version: i64 = sqlx::query_scalar("PRAGMA user_version").fetch_one(&self.pool).await?;
+        if version >= TIMESTAMP_SCHEMA_VERSION {
+            return Ok(());
+        }
+
+        let migrate = |e: sqlx::Error| DatabaseError::Migration(format!("Failed to normalize timestamps: {}", e));
+
+        // writable_schema is per connection, so the whole conversion runs on one
+        let mut conn = self.pool.acquire().await?;
+        sqlx::query("BEGIN IMMEDIATE").execute(&mut *conn).await.map_err(migrate)?;
+
+        let result = async {
+            let mut converted = 0;
+            for (table, column) in TIMESTAMP_COLUMNS {
+                // Unparseable values are left alone rather than nulled
+                converted += sqlx::query(&format!(
+                    "UPDATE {table} SET {column} = COALESCE(strftime('{format}', {column}), {column})
+                     WHERE {column} IS NOT NULL AND {column} NOT GLOB '{pattern}'",
+                    format = TIMESTAMP_FORMAT,
+                    pattern = TIMESTAMP_GLOB,
+                ))
+                .execute(&mut *conn)
+                .await?
+                .rows_affected();
+            }
+
+            let schema_version: i64 = sqlx::query_scalar("PRAGMA schema_version")
+                .fetch_one(&mut *conn)
+                .await?;
+            sqlx::query("PRAGMA writable_schema = ON").execute(&mut *conn).await?;
+            let rewritten = sqlx::query(
+                "UPDATE sqlite_master
+                 SET sql = replace(sql, 'datetime(''now'')', 'strftime(''%Y-%m-%dT%H:%M:%SZ'', ''now'')')
+                 WHERE type = 'table' AND sql LIKE '%datetime(''now'')%'",
+            )
+            .execute(&mut *conn)
+            .await?
+            .rows_affected();
+            if rewritten > 0 {
+                // Makes every connection reload the schema
+                sqlx::query(&format!("PRAGMA schema_version = {}", schema_version + 1))
+                    .execute(&mut *conn)
+                    .await?;
+            }
+            sqlx::query("PRAGMA writable_schema = OFF").execute(&mut *conn).await?;
+
+            sqlx::query(&format!("PRAGMA user_version = {}", TIMESTAMP_SCHEMA_VERSION))
+                .execute(&mut *conn)
+                .await?;
+
+            Ok::<_, sqlx::Error>((converted, rewritten))
+        }
+        .await;
+
+        match result {
+            Ok((converted, rewritten)) => {
+                sqlx::query("COMMIT").execute(&mut *conn).await.map_err(migrate)?;
+                if converted > 0 || rewritten > 0 {
+                    info!(
+                        "Converted {} timestamps and {} table defaults to RFC 3339",
+                        converted, rewritten
+                    );
+                }
+                Ok(())
+            }
+            Err(e) => {
+                let _ = sqlx::query("PRAGMA writable_schema = OFF").execute(&mut *conn).await;
+                let _ = sqlx::query("ROLLBACK").execute(&mut *conn).await;
+                Err(migrate(e))
+            }
+        }
+    }
+
+    /// Check whether a table has the given column
+    async fn has_column(&self, table: &str, column: &str) -> bool {
+        sqlx::query_scalar("SELECT COUNT(*) > 0 FROM pragma_table_info(?) WHERE name = ?")
+            .bind(table)
+            .bind(column)
+            .fetch_one(&self.pool)
+            .await
+            .unwrap_or(false)
+    }
 }
 
+/// `user_version` once timestamps are stored as RFC 3339
+const TIMESTAMP_SCHEMA_VERSION: i64 = 1;
+
+/// GLOB matching timestamps already in the stored format
+const TIMESTAMP_GLOB: &str = "[0-9][0-9][0-9][0-9]-[0-9][0-9]-[0-9][0-9]T[0-9][0-9]:[0-9][0-9]:[0-9][0-9]Z";
+
 const BASE_SCHEMA: &str = r#"
 -- Distributions table
 CREATE TABLE IF NOT EXISTS distributions (
@@ -209,8 +778,8 @@ CREATE TABLE IF NOT EXISTS distributions (
     homepage TEXT,
     github_org TEXT,
     gitlab_group TEXT,
-    created_at TEXT NOT NULL DEFAULT (datetime('now')),
-    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
 );
 
 -- GitHub snapshots
@@ -226,7 +795,7 @@ CREATE TABLE IF NOT EXISTS github_snapshots (
     commits_365d INTEGER NOT NULL DEFAULT 0,
     contributors_30d INTEGER NOT NULL DEFAULT 0,
     last_commit_at TEXT,
-    collected_at TEXT NOT NULL DEFAULT (datetime('now'))
+    collected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
 );
 
 CREATE INDEX IF NOT EXISTS idx_github_snapshots_distro
@@ -239,7 +808,7 @@ CREATE TABLE IF NOT EXISTS package_snapshots (
     total_packages INTEGER NOT NULL DEFAULT 0,
     outdated_packages INTEGER NOT NULL DEFAULT 0,
     security_updates INTEGER NOT NULL DEFAULT 0,
-    collected_at TEXT NOT NULL DEFAULT (datetime('now'))
+    collected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
 );
 
 CREATE INDEX IF NOT EXISTS idx_package_snapshots_distro
@@ -253,7 +822,7 @@ CREATE TABLE IF NOT EXISTS community_snapshots (
     active_users_30d INTEGER,
     posts_30d INTEGER,
     response_time_avg_hours REAL,
-    collected_at TEXT NOT NULL DEFAULT (datetime('now'))
+    collected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
 );
 
 CREATE INDEX IF NOT EXISTS idx_community_snapshots_distro
@@ -268,12 +837,22 @@ CREATE TABLE IF NOT EXISTS release_snapshots (
     release_name TEXT,
     published_at TEXT,
     is_prerelease INTEGER NOT NULL DEFAULT 0,
-    collected_at TEXT NOT NULL DEFAULT (datetime('now'))
+    collected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
 );
 
 CREATE INDEX IF NOT EXISTS idx_release_snapshots_distro
     ON release_snapshots(distro_id, collected_at DESC);
 
+-- Newest release seen per repo, so collection only fetches releases after it
+CREATE TABLE IF NOT EXISTS release_cursors (
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    repo_name TEXT NOT NULL,
+    release_id INTEGER NOT NULL,
+    published_at TEXT,
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    PRIMARY KEY (distro_id, repo_name)
+);
+
 -- Health scores
 CREATE TABLE IF NOT EXISTS health_scores (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
@@ -283,96 +862,409 @@ CREATE TABLE IF NOT EXISTS health_scores (
     community_score REAL NOT NULL,
     maintenance_score REAL NOT NULL,
     trend TEXT NOT NULL DEFAULT 'stable',
-    calculated_at TEXT NOT NULL DEFAULT (datetime('now'))
+    calculated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
 );
 
 CREATE INDEX IF NOT EXISTS idx_health_scores_distro
     ON health_scores(distro_id, calculated_at DESC);
-"#;
 
-const SEED_DATA: &str = r#"
--- Seed distributions
--- Major independent distributions
-INSERT OR IGNORE INTO distributions (name, slug, homepage, github_org, subreddit) VALUES
-    ('Arch Linux', 'arch', 'https://archlinux.org', 'archlinux', 'archlinux'),
-    ('Debian', 'debian', 'https://debian.org', NULL, 'debian'),
-    ('Fedora', 'fedora', 'https://fedoraproject.org', 'fedora-infra', 'Fedora'),
-    ('openSUSE', 'opensuse', 'https://opensuse.org', 'openSUSE', 'openSUSE'),
-    ('Gentoo', 'gentoo', 'https://gentoo.org', 'gentoo', 'Gentoo'),
-    ('Slackware', 'slackware', 'http://www.slackware.com', NULL, 'slackware'),
-    ('Void Linux', 'void', 'https://voidlinux.org', 'void-linux', 'voidlinux'),
-    ('Alpine Linux', 'alpine', 'https://alpinelinux.org', 'alpinelinux', 'alpinelinux'),
-    ('NixOS', 'nixos', 'https://nixos.org', 'NixOS', 'NixOS'),
-    ('Clear Linux', 'clearlinux', 'https://clearlinux.org', 'clearlinux', NULL),
-    ('Solus', 'solus', 'https://getsol.us', 'getsolus', 'SolusProject'),
-    ('Mageia', 'mageia', 'https://www.mageia.org', NULL, NULL);
-
--- Debian-based
-INSERT OR IGNORE INTO distributions (name, slug, homepage, github_org, subreddit) VALUES
-    ('Ubuntu', 'ubuntu', 'https://ubuntu.com', 'ubuntu', 'Ubuntu'),
-    ('Linux Mint', 'mint', 'https://linuxmint.com', 'linuxmint', 'linuxmint'),
-    ('Pop!_OS', 'popos', 'https://pop.system76.com', 'pop-os', 'pop_os'),
-    ('elementary OS', 'elementary', 'https://elementary.io', 'elementary', 'elementaryos'),
-    ('Zorin OS', 'zorin', 'https://zorin.com/os', NULL, 'zorinos'),
-    ('MX Linux', 'mxlinux', 'https://mxlinux.org', 'MX-Linux', 'MXLinux'),
-    ('antiX', 'antix', 'https://antixlinux.com', NULL, NULL),
-    ('KDE neon', 'kdeneon', 'https://neon.kde.org', NULL, 'kdeneon'),
-    ('Kali Linux', 'kali', 'https://www.kali.org', 'kalilinux', 'Kalilinux'),
-    ('Parrot OS', 'parrot', 'https://www.parrotsec.org', 'ParrotSec', 'ParrotOS'),
-    ('Tails', 'tails', 'https://tails.net', NULL, 'tails'),
-    ('Raspberry Pi OS', 'raspios', 'https://www.raspberrypi.com/software', 'RPi-Distro', 'raspberry_pi'),
-    ('Deepin', 'deepin', 'https://www.deepin.org', 'linuxdeepin', 'deepin'),
-    ('PureOS', 'pureos', 'https://pureos.net', NULL, NULL),
-    ('Devuan', 'devuan', 'https://www.devuan.org', NULL, 'Devuan');
-
--- Arch-based
-INSERT OR IGNORE INTO distributions (name, slug, homepage, github_org, subreddit) VALUES
-    ('Manjaro', 'manjaro', 'https://manjaro.org', 'manjaro', 'ManjaroLinux'),
-    ('EndeavourOS', 'endeavouros', 'https://endeavouros.com', 'endeavouros-team', 'EndeavourOS'),
-    ('Garuda Linux', 'garuda', 'https://garudalinux.org', 'garuda-linux', 'GarudaLinux'),
-    ('ArcoLinux', 'arcolinux', 'https://arcolinux.com', 'arcolinux', 'arcolinux'),
-    ('Artix Linux', 'artix', 'https://artixlinux.org', 'artix-linux', 'artixlinux'),
-    ('CachyOS', 'cachyos', 'https://cachyos.org', 'CachyOS', 'cachyos');
-
--- Fedora-based / RPM
-INSERT OR IGNORE INTO distributions (name, slug, homepage, github_org, subreddit) VALUES
-    ('Rocky Linux', 'rocky', 'https://rockylinux.org', 'rocky-linux', 'RockyLinux'),
-    ('AlmaLinux', 'almalinux', 'https://almalinux.org', 'AlmaLinux', 'AlmaLinux'),
-    ('CentOS Stream', 'centosstream', 'https://www.centos.org', NULL, 'CentOS'),
-    ('Nobara', 'nobara', 'https://nobaraproject.org', 'Nobara-Project', 'NobaraProject'),
-    ('Ultramarine', 'ultramarine', 'https://ultramarine-linux.org', 'Ultramarine-Linux', NULL),
-    ('Bazzite', 'bazzite', 'https://bazzite.gg', 'ublue-os', 'bazzite');
-
--- Immutable / Container-focused
-INSERT OR IGNORE INTO distributions (name, slug, homepage, github_org, subreddit) VALUES
-    ('Fedora Silverblue', 'silverblue', 'https://fedoraproject.org/silverblue', NULL, 'Fedora'),
-    ('Fedora Kinoite', 'kinoite', 'https://fedoraproject.org/kinoite', NULL, 'Fedora'),
-    ('openSUSE MicroOS', 'microos', 'https://microos.opensuse.org', NULL, 'openSUSE'),
-    ('Vanilla OS', 'vanillaos', 'https://vanillaos.org', 'Vanilla-OS', 'vanillaos'),
-    ('blendOS', 'blendos', 'https://blendos.co', 'blend-os', 'blendos');
-
--- Specialized / Niche
-INSERT OR IGNORE INTO distributions (name, slug, homepage, github_org, subreddit) VALUES
-    ('Qubes OS', 'qubes', 'https://www.qubes-os.org', 'QubesOS', 'Qubes'),
-    ('Whonix', 'whonix', 'https://www.whonix.org', 'Whonix', 'Whonix'),
-    ('Bedrock Linux', 'bedrock', 'https://bedrocklinux.org', 'bedrocklinux', 'bedrocklinux'),
-    ('GoboLinux', 'gobolinux', 'https://gobolinux.org', 'gobolinux', NULL),
-    ('Guix System', 'guix', 'https://guix.gnu.org', NULL, 'GUIX'),
-    ('KISS Linux', 'kiss', 'https://kisslinux.org', 'kiss-community', 'kisslinux'),
-    ('Chimera Linux', 'chimera', 'https://chimera-linux.org', 'chimera-linux', NULL),
-    ('Serpent OS', 'serpent', 'https://serpentos.com', 'serpent-os', NULL);
-
--- Update existing distributions with subreddits (migration for existing data)
-UPDATE distributions SET subreddit = 'archlinux' WHERE slug = 'arch' AND subreddit IS NULL;
-UPDATE distributions SET subreddit = 'debian' WHERE slug = 'debian' AND subreddit IS NULL;
-UPDATE distributions SET subreddit = 'Fedora' WHERE slug = 'fedora' AND subreddit IS NULL;
-UPDATE distributions SET subreddit = 'NixOS' WHERE slug = 'nixos' AND subreddit IS NULL;
-UPDATE distributions SET subreddit = 'Ubuntu' WHERE slug = 'ubuntu' AND subreddit IS NULL;
-UPDATE distributions SET subreddit = 'pop_os' WHERE slug = 'popos' AND subreddit IS NULL;
-UPDATE distributions SET subreddit = 'ManjaroLinux' WHERE slug = 'manjaro' AND subreddit IS NULL;
-UPDATE distributions SET subreddit = 'EndeavourOS' WHERE slug = 'endeavouros' AND subreddit IS NULL;
-UPDATE distributions SET subreddit = 'linuxmint' WHERE slug = 'mint' AND subreddit IS NULL;
-UPDATE distributions SET subreddit = 'Gentoo' WHERE slug = 'gentoo' AND subreddit IS NULL;
-UPDATE distributions SET subreddit = 'voidlinux' WHERE slug = 'void' AND subreddit IS NULL;
-UPDATE distributions SET subreddit = 'openSUSE' WHERE slug = 'opensuse' AND subreddit IS NULL;
+-- Upstream projects (desktop environments, toolkits)
+CREATE TABLE IF NOT EXISTS upstreams (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    slug TEXT NOT NULL UNIQUE,
+    kind TEXT NOT NULL DEFAULT 'desktop',
+    homepage TEXT,
+    github_org TEXT,
+    repo_prefix TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+-- Which upstreams each distribution ships
+CREATE TABLE IF NOT EXISTS distro_upstreams (
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    upstream_id INTEGER NOT NULL REFERENCES upstreams(id),
+    PRIMARY KEY (distro_id, upstream_id)
+);
+
+-- Upstream GitHub snapshots
+CREATE TABLE IF NOT EXISTS upstream_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    upstream_id INTEGER NOT NULL REFERENCES upstreams(id),
+    repo_name TEXT NOT NULL,
+    stars INTEGER NOT NULL DEFAULT 0,
+    forks INTEGER NOT NULL DEFAULT 0,
+    open_issues INTEGER NOT NULL DEFAULT 0,
+    open_prs INTEGER NOT NULL DEFAULT 0,
+    commits_30d INTEGER NOT NULL DEFAULT 0,
+    contributors_30d INTEGER NOT NULL DEFAULT 0,
+    last_commit_at TEXT,
+    collected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_upstream_snapshots_upstream
+    ON upstream_snapshots(upstream_id, collected_at DESC);
+
+-- Human-written notes explaining score changes
+CREATE TABLE IF NOT EXISTS annotations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    annotated_on TEXT NOT NULL,
+    note TEXT NOT NULL,
+    author TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_annotations_distro
+    ON annotations(distro_id, annotated_on);
+
+-- Maintainer claims on distribution entries
+CREATE TABLE IF NOT EXISTS claims (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    method TEXT NOT NULL,
+    contact TEXT NOT NULL,
+    challenge TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    verified_at TEXT
+);
+
+-- Verified maintainers with distro-scoped API keys
+CREATE TABLE IF NOT EXISTS maintainers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    claim_id INTEGER NOT NULL REFERENCES claims(id),
+    contact TEXT NOT NULL,
+    api_key_hash TEXT NOT NULL UNIQUE,
+    active INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+-- User-submitted distribution suggestions awaiting review
+CREATE TABLE IF NOT EXISTS suggestions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    slug TEXT NOT NULL,
+    homepage TEXT,
+    github_org TEXT,
+    subreddit TEXT,
+    description TEXT,
+    os_family TEXT NOT NULL DEFAULT 'linux',
+    reason TEXT,
+    status TEXT NOT NULL DEFAULT 'pending',
+    distro_id INTEGER REFERENCES distributions(id),
+    client_hash TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    reviewed_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_suggestions_client
+    ON suggestions(client_hash, created_at DESC);
+
+-- Configured fields that failed validation (renamed orgs, private subreddits, dead links)
+CREATE TABLE IF NOT EXISTS config_issues (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    field TEXT NOT NULL,
+    value TEXT NOT NULL,
+    problem TEXT NOT NULL,
+    first_seen_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    last_seen_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    resolved_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_config_issues_open
+    ON config_issues(distro_id, field) WHERE resolved_at IS NULL;
+
+-- Language breakdown (bytes of code) for a GitHub snapshot
+CREATE TABLE IF NOT EXISTS github_languages (
+    snapshot_id INTEGER NOT NULL REFERENCES github_snapshots(id),
+    language TEXT NOT NULL,
+    bytes INTEGER NOT NULL,
+    PRIMARY KEY (snapshot_id, language)
+);
+
+-- Human contributors to a repo as of a GitHub snapshot (GitHub's stats cover the top 100)
+CREATE TABLE IF NOT EXISTS github_contributors (
+    snapshot_id INTEGER NOT NULL REFERENCES github_snapshots(id),
+    login TEXT NOT NULL COLLATE NOCASE,
+    commits INTEGER NOT NULL,
+    PRIMARY KEY (snapshot_id, login)
+);
+
+CREATE INDEX IF NOT EXISTS idx_github_contributors_login ON github_contributors(login);
+
+-- Open issues by label category for a GitHub snapshot (core repos only)
+CREATE TABLE IF NOT EXISTS github_issue_labels (
+    snapshot_id INTEGER NOT NULL REFERENCES github_snapshots(id),
+    category TEXT NOT NULL,
+    open_issues INTEGER NOT NULL,
+    PRIMARY KEY (snapshot_id, category)
+);
+
+-- Issues opened and closed over the 30 days before a GitHub snapshot (core repos only)
+CREATE TABLE IF NOT EXISTS github_issue_activity (
+    snapshot_id INTEGER PRIMARY KEY REFERENCES github_snapshots(id),
+    opened_30d INTEGER NOT NULL,
+    closed_30d INTEGER NOT NULL,
+    stale_closed_30d INTEGER NOT NULL
+);
+
+-- Contributor-facing docs found in a distro's core repos: one row per governance collection
+CREATE TABLE IF NOT EXISTS governance_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    repos_checked INTEGER NOT NULL,
+    contributing INTEGER NOT NULL,
+    code_of_conduct INTEGER NOT NULL,
+    security_policy INTEGER NOT NULL,
+    governance INTEGER NOT NULL,
+    provenance TEXT,
+    collected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_governance_snapshots_distro
+    ON governance_snapshots(distro_id, collected_at DESC);
+
+-- Size of each distro's download mirror network, from its official mirror list
+CREATE TABLE IF NOT EXISTS infra_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    mirrors INTEGER NOT NULL,
+    mirror_countries INTEGER,
+    provenance TEXT,
+    collected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_infra_snapshots_distro
+    ON infra_snapshots(distro_id, collected_at DESC);
+
+-- Size and activity of a distro's own package archive, for distros developed there rather
+-- than on a forge (Debian, from the Ultimate Debian Database and ftp-master)
+CREATE TABLE IF NOT EXISTS archive_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    source_packages INTEGER NOT NULL,
+    rc_bugs INTEGER,
+    -- Source packages with a new version since the previous snapshot; NULL for the first
+    uploads INTEGER,
+    uploads_30d INTEGER,
+    provenance TEXT,
+    collected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_archive_snapshots_distro
+    ON archive_snapshots(distro_id, collected_at DESC);
+
+-- Source package versions in the archive as of the latest snapshot, to count uploads against
+CREATE TABLE IF NOT EXISTS archive_sources (
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    source TEXT NOT NULL,
+    version TEXT NOT NULL,
+    PRIMARY KEY (distro_id, source)
+);
+
+-- Open bugs and bug flow in a distro's Bugzilla, for distros that track bugs there rather
+-- than in forge issues (Fedora, openSUSE, Gentoo)
+CREATE TABLE IF NOT EXISTS bugtracker_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    open_bugs INTEGER NOT NULL,
+    opened_30d INTEGER,
+    closed_30d INTEGER,
+    mean_resolution_days REAL,
+    provenance TEXT,
+    collected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_bugtracker_snapshots_distro
+    ON bugtracker_snapshots(distro_id, collected_at DESC);
+
+-- A distro's GitHub org as a whole, read from its profile rather than summed over repos
+CREATE TABLE IF NOT EXISTS org_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    org TEXT NOT NULL,
+    public_repos INTEGER NOT NULL,
+    public_members INTEGER,
+    followers INTEGER NOT NULL,
+    sponsors INTEGER,
+    org_created_at TEXT,
+    provenance TEXT,
+    collected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_org_snapshots_distro
+    ON org_snapshots(distro_id, collected_at DESC);
+
+-- Opt-in install pings from distros' welcome apps, one row per install. Installs are
+-- known only by a hash the client salts; nothing about the sender is kept.
+CREATE TABLE IF NOT EXISTS install_pings (
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    install_hash TEXT NOT NULL,
+    last_seen_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    PRIMARY KEY (distro_id, install_hash)
+);
+
+-- Pings per hashed client address today, capping how many installs one address can report
+CREATE TABLE IF NOT EXISTS ping_clients (
+    client_hash TEXT NOT NULL,
+    day TEXT NOT NULL,
+    pings INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (client_hash, day)
+);
+
+-- Installs seen pinging in the last 30 days, only recorded once there are enough of them
+-- that the count can't single anyone out
+CREATE TABLE IF NOT EXISTS popularity_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    source TEXT NOT NULL,
+    active_installs_30d INTEGER NOT NULL,
+    collected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_popularity_snapshots_distro
+    ON popularity_snapshots(distro_id, collected_at DESC);
+
+-- Each day's ranking order, as it stood after the day's last scoring
+CREATE TABLE IF NOT EXISTS ranking_snapshots (
+    day TEXT NOT NULL,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    rank INTEGER NOT NULL,
+    overall_score REAL NOT NULL,
+    development_score REAL NOT NULL,
+    community_score REAL NOT NULL,
+    maintenance_score REAL NOT NULL,
+    trend TEXT NOT NULL,
+    PRIMARY KEY (day, distro_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_ranking_snapshots_distro
+    ON ranking_snapshots(distro_id, day DESC);
+
+-- Manual corrections to mirror detection for a distro's repos
+CREATE TABLE IF NOT EXISTS repo_overrides (
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    repo_name TEXT NOT NULL COLLATE NOCASE,
+    is_mirror INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    PRIMARY KEY (distro_id, repo_name)
+);
+
+-- Former GitHub org names mapped to their current (canonical) name
+CREATE TABLE IF NOT EXISTS github_org_aliases (
+    old_name TEXT PRIMARY KEY COLLATE NOCASE,
+    canonical_name TEXT NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+-- Featured "distro of the week" picks for the homepage
+CREATE TABLE IF NOT EXISTS highlights (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    reason TEXT NOT NULL,
+    blurb TEXT NOT NULL,
+    overall_score REAL NOT NULL,
+    score_change REAL,
+    selected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+-- Notable moments detected by the analyzer (score thresholds, star counts, anniversaries)
+CREATE TABLE IF NOT EXISTS milestones (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    kind TEXT NOT NULL,
+    milestone_key TEXT NOT NULL,
+    title TEXT NOT NULL,
+    value REAL,
+    occurred_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    UNIQUE (distro_id, milestone_key)
+);
+
+CREATE INDEX IF NOT EXISTS idx_milestones_occurred
+    ON milestones(occurred_at DESC);
+
+-- Generic metrics for experimental collectors, keyed by MetricKey
+CREATE TABLE IF NOT EXISTS metrics (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    source TEXT NOT NULL,
+    key TEXT NOT NULL,
+    value REAL NOT NULL,
+    collected_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_metrics_distro_key
+    ON metrics(distro_id, key, collected_at DESC);
+
+-- Queued collection work, one source for one distro per job
+CREATE TABLE IF NOT EXISTS collection_jobs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    source TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'queued',
+    error TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    started_at TEXT,
+    finished_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_collection_jobs_status
+    ON collection_jobs(status, id);
+
+CREATE INDEX IF NOT EXISTS idx_collection_jobs_distro
+    ON collection_jobs(distro_id, source, created_at);
+
+-- Finished collections of one source for one distro, used to decide what needs analysis
+CREATE TABLE IF NOT EXISTS collection_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER NOT NULL REFERENCES distributions(id),
+    source TEXT NOT NULL,
+    snapshots INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    started_at TEXT NOT NULL,
+    finished_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_collection_runs_distro
+    ON collection_runs(distro_id, finished_at DESC);
+
+-- Changelog of the health scoring rules, published with GET /methodology
+CREATE TABLE IF NOT EXISTS methodology_changes (
+    version TEXT PRIMARY KEY,
+    summary TEXT NOT NULL,
+    changed_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+-- Instance-wide settings, such as when the database was seeded
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+-- Last saved state of each upstream's circuit breaker
+CREATE TABLE IF NOT EXISTS circuit_breakers (
+    service TEXT PRIMARY KEY,
+    state TEXT NOT NULL,
+    consecutive_failures INTEGER NOT NULL DEFAULT 0,
+    opened_at TEXT,
+    updated_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+-- Periods when scores aren't recalculated (known-bad data, upstream outages); NULL distro_id
+-- freezes every distro
+CREATE TABLE IF NOT EXISTS score_freezes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    distro_id INTEGER REFERENCES distributions(id),
+    reason TEXT NOT NULL,
+    starts_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    ends_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_score_freezes_distro
+    ON score_freezes(distro_id, starts_at);
 "#;
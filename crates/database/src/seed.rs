@@ -0,0 +1,259 @@
+//! Seed data for new instances
+//!
+//! Connecting never writes data; seeding is an explicit step. `dv seed` applies the
+//! bundled distro list and methodology changelog, or a custom SQL file for instances
+//! tracking their own set, and records when it ran in `settings`. The CLI seeds the
+//! bundled list on first run if nothing has been seeded yet.
+
+use crate::schema::Database;
+use crate::{DatabaseError, Result};
+use chrono::{DateTime, Utc};
+
+/// Setting recording when the database was last seeded
+const SEEDED_AT: &str = "seeded_at";
+
+impl Database {
+    /// Apply seed SQL and record that the database has been seeded
+    ///
+    /// The bundled seed only inserts rows that are missing, so it's safe to re-run after
+    /// an upgrade to pick up new distros and methodology entries.
+    pub async fn seed(&self, sql: &str) -> Result<()> {
+        let mut tx = self.pool().begin().await?;
+        sqlx::raw_sql(sql)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DatabaseError::Migration(format!("Seed failed: {}", e)))?;
+        tx.commit().await?;
+
+        self.set_setting(SEEDED_AT, &crate::to_sql_timestamp(Utc::now())).await
+    }
+
+    /// When the database was last seeded, if ever
+    pub async fn seeded_at(&self) -> Result<Option<DateTime<Utc>>> {
+        let value = self.get_setting(SEEDED_AT).await?;
+        Ok(value.and_then(|v| DateTime::parse_from_rfc3339(&v).ok()).map(|t| t.with_timezone(&Utc)))
+    }
+
+    /// Whether this is a fresh database: never seeded and tracking no distros
+    ///
+    /// Databases created before seeding was recorded already have their distros.
+    pub async fn needs_seed(&self) -> Result<bool> {
+        if self.get_setting(SEEDED_AT).await?.is_some() {
+            return Ok(false);
+        }
+        let distros: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM distributions")
+            .fetch_one(self.pool())
+            .await?;
+        Ok(distros == 0)
+    }
+}
+
+pub const DEFAULT_SEED: &str = r#"
+-- Scoring methodology changelog, newest version matching analyzer::methodology::METHODOLOGY_VERSION
+INSERT OR IGNORE INTO methodology_changes (version, summary, changed_at) VALUES
+    ('1.0', 'Overall score weights development 40%, community 30%, maintenance 30%, from GitHub activity and backlog and Reddit size and activity', '2026-10-15T00:00:00Z'),
+    ('1.1', 'Maintenance blends in Repology package freshness at 20% where available', '2026-10-15T00:00:00Z'),
+    ('1.2', 'Maintenance loses 15 points when the shipped kernel series is EOL upstream', '2026-10-15T00:00:00Z'),
+    ('1.3', 'Bot commits and mirror repos are excluded, and distros can aggregate repos by sum, mean or top-k', '2026-10-15T00:00:00Z'),
+    ('1.4', 'Community scores 30-day star growth instead of total stars once there is a month of history', '2026-10-15T00:00:00Z'),
+    ('1.5', 'Reddit scores 30/90-day subscriber growth instead of subscriber count once there is history', '2026-10-15T00:00:00Z'),
+    ('1.6', 'Trend is the median slope over the last 5 scores instead of the change since the previous one', '2026-10-15T00:00:00Z'),
+    ('1.7', 'Open security issues in core repos count triple toward the issue backlog and feature requests half', '2026-10-15T00:00:00Z'),
+    ('1.8', 'Maintenance scores issue resolution over 30 days, not counting issues closed by stale bots', '2026-10-15T00:00:00Z'),
+    ('1.9', 'Community rewards first-time contributors with a merged pull request in the last 30 days', '2026-10-15T00:00:00Z'),
+    ('1.10', 'Maintenance scores download mirror redundancy from official mirror lists', '2026-10-15T00:00:00Z'),
+    ('1.11', 'Distros without forge repos are scored on archive uploads and release-critical bugs', '2026-10-15T00:00:00Z'),
+    ('1.12', 'Community scores GitHub Discussions activity and answers alongside Reddit', '2026-10-15T00:00:00Z'),
+    ('1.13', 'Top-k aggregation is a weighted mean of the largest repos rather than a rescaled sum', '2026-10-15T00:00:00Z');
+
+-- Known GitHub org renames
+INSERT OR IGNORE INTO github_org_aliases (old_name, canonical_name) VALUES
+    ('solus-project', 'getsolus');
+
+-- Seed distributions
+-- Major independent distributions
+INSERT OR IGNORE INTO distributions (name, slug, homepage, github_org, subreddit) VALUES
+    ('Arch Linux', 'arch', 'https://archlinux.org', 'archlinux', 'archlinux'),
+    ('Debian', 'debian', 'https://debian.org', NULL, 'debian'),
+    ('Fedora', 'fedora', 'https://fedoraproject.org', 'fedora-infra', 'Fedora'),
+    ('openSUSE', 'opensuse', 'https://opensuse.org', 'openSUSE', 'openSUSE'),
+    ('Gentoo', 'gentoo', 'https://gentoo.org', 'gentoo', 'Gentoo'),
+    ('Slackware', 'slackware', 'http://www.slackware.com', NULL, 'slackware'),
+    ('Void Linux', 'void', 'https://voidlinux.org', 'void-linux', 'voidlinux'),
+    ('Alpine Linux', 'alpine', 'https://alpinelinux.org', 'alpinelinux', 'alpinelinux'),
+    ('NixOS', 'nixos', 'https://nixos.org', 'NixOS', 'NixOS'),
+    ('Clear Linux', 'clearlinux', 'https://clearlinux.org', 'clearlinux', NULL),
+    ('Solus', 'solus', 'https://getsol.us', 'getsolus', 'SolusProject'),
+    ('Mageia', 'mageia', 'https://www.mageia.org', NULL, NULL);
+
+-- Debian-based
+INSERT OR IGNORE INTO distributions (name, slug, homepage, github_org, subreddit) VALUES
+    ('Ubuntu', 'ubuntu', 'https://ubuntu.com', 'ubuntu', 'Ubuntu'),
+    ('Linux Mint', 'mint', 'https://linuxmint.com', 'linuxmint', 'linuxmint'),
+    ('Pop!_OS', 'popos', 'https://pop.system76.com', 'pop-os', 'pop_os'),
+    ('elementary OS', 'elementary', 'https://elementary.io', 'elementary', 'elementaryos'),
+    ('Zorin OS', 'zorin', 'https://zorin.com/os', NULL, 'zorinos'),
+    ('MX Linux', 'mxlinux', 'https://mxlinux.org', 'MX-Linux', 'MXLinux'),
+    ('antiX', 'antix', 'https://antixlinux.com', NULL, NULL),
+    ('KDE neon', 'kdeneon', 'https://neon.kde.org', NULL, 'kdeneon'),
+    ('Kali Linux', 'kali', 'https://www.kali.org', 'kalilinux', 'Kalilinux'),
+    ('Parrot OS', 'parrot', 'https://www.parrotsec.org', 'ParrotSec', 'ParrotOS'),
+    ('Tails', 'tails', 'https://tails.net', NULL, 'tails'),
+    ('Raspberry Pi OS', 'raspios', 'https://www.raspberrypi.com/software', 'RPi-Distro', 'raspberry_pi'),
+    ('Deepin', 'deepin', 'https://www.deepin.org', 'linuxdeepin', 'deepin'),
+    ('PureOS', 'pureos', 'https://pureos.net', NULL, NULL),
+    ('Devuan', 'devuan', 'https://www.devuan.org', NULL, 'Devuan');
+
+-- Arch-based
+INSERT OR IGNORE INTO distributions (name, slug, homepage, github_org, subreddit) VALUES
+    ('Manjaro', 'manjaro', 'https://manjaro.org', 'manjaro', 'ManjaroLinux'),
+    ('EndeavourOS', 'endeavouros', 'https://endeavouros.com', 'endeavouros-team', 'EndeavourOS'),
+    ('Garuda Linux', 'garuda', 'https://garudalinux.org', 'garuda-linux', 'GarudaLinux'),
+    ('ArcoLinux', 'arcolinux', 'https://arcolinux.com', 'arcolinux', 'arcolinux'),
+    ('Artix Linux', 'artix', 'https://artixlinux.org', 'artix-linux', 'artixlinux'),
+    ('CachyOS', 'cachyos', 'https://cachyos.org', 'CachyOS', 'cachyos');
+
+-- Fedora-based / RPM
+INSERT OR IGNORE INTO distributions (name, slug, homepage, github_org, subreddit) VALUES
+    ('Rocky Linux', 'rocky', 'https://rockylinux.org', 'rocky-linux', 'RockyLinux'),
+    ('AlmaLinux', 'almalinux', 'https://almalinux.org', 'AlmaLinux', 'AlmaLinux'),
+    ('CentOS Stream', 'centosstream', 'https://www.centos.org', NULL, 'CentOS'),
+    ('Nobara', 'nobara', 'https://nobaraproject.org', 'Nobara-Project', 'NobaraProject'),
+    ('Ultramarine', 'ultramarine', 'https://ultramarine-linux.org', 'Ultramarine-Linux', NULL),
+    ('Bazzite', 'bazzite', 'https://bazzite.gg', 'ublue-os', 'bazzite');
+
+-- Immutable / Container-focused
+INSERT OR IGNORE INTO distributions (name, slug, homepage, github_org, subreddit) VALUES
+    ('Fedora Silverblue', 'silverblue', 'https://fedoraproject.org/silverblue', NULL, 'Fedora'),
+    ('Fedora Kinoite', 'kinoite', 'https://fedoraproject.org/kinoite', NULL, 'Fedora'),
+    ('openSUSE MicroOS', 'microos', 'https://microos.opensuse.org', NULL, 'openSUSE'),
+    ('Vanilla OS', 'vanillaos', 'https://vanillaos.org', 'Vanilla-OS', 'vanillaos'),
+    ('blendOS', 'blendos', 'https://blendos.co', 'blend-os', 'blendos');
+
+-- Specialized / Niche
+INSERT OR IGNORE INTO distributions (name, slug, homepage, github_org, subreddit) VALUES
+    ('Qubes OS', 'qubes', 'https://www.qubes-os.org', 'QubesOS', 'Qubes'),
+    ('Whonix', 'whonix', 'https://www.whonix.org', 'Whonix', 'Whonix'),
+    ('Bedrock Linux', 'bedrock', 'https://bedrocklinux.org', 'bedrocklinux', 'bedrocklinux'),
+    ('GoboLinux', 'gobolinux', 'https://gobolinux.org', 'gobolinux', NULL),
+    ('Guix System', 'guix', 'https://guix.gnu.org', NULL, 'GUIX'),
+    ('KISS Linux', 'kiss', 'https://kisslinux.org', 'kiss-community', 'kisslinux'),
+    ('Chimera Linux', 'chimera', 'https://chimera-linux.org', 'chimera-linux', NULL),
+    ('Serpent OS', 'serpent', 'https://serpentos.com', 'serpent-os', NULL);
+
+-- Non-Linux operating systems (BSDs, Haiku)
+INSERT OR IGNORE INTO distributions (name, slug, homepage, github_org, subreddit, os_family, repology_repo) VALUES
+    ('FreeBSD', 'freebsd', 'https://www.freebsd.org', 'freebsd', 'freebsd', 'bsd', 'freebsd'),
+    ('OpenBSD', 'openbsd', 'https://www.openbsd.org', 'openbsd', 'openbsd', 'bsd', 'openbsd'),
+    ('NetBSD', 'netbsd', 'https://www.netbsd.org', 'NetBSD', 'NetBSD', 'bsd', 'pkgsrc_current'),
+    ('DragonFly BSD', 'dragonfly', 'https://www.dragonflybsd.org', 'DragonFlyBSD', 'dragonflybsd', 'bsd', NULL),
+    ('GhostBSD', 'ghostbsd', 'https://www.ghostbsd.org', 'ghostbsd', 'GhostBSD', 'bsd', NULL),
+    ('Haiku', 'haiku', 'https://www.haiku-os.org', 'haiku', 'haikuOS', 'haiku', 'haikuports_master');
+
+-- Upstream desktop environments
+INSERT OR IGNORE INTO upstreams (name, slug, kind, homepage, github_org, repo_prefix) VALUES
+    ('GNOME', 'gnome', 'desktop', 'https://www.gnome.org', 'GNOME', NULL),
+    ('KDE Plasma', 'kde', 'desktop', 'https://kde.org', 'KDE', NULL),
+    ('Xfce', 'xfce', 'desktop', 'https://xfce.org', 'xfce-mirror', NULL),
+    ('COSMIC', 'cosmic', 'desktop', 'https://system76.com/cosmic', 'pop-os', 'cosmic');
+
+-- Default desktop environment of each distribution
+INSERT OR IGNORE INTO distro_upstreams (distro_id, upstream_id)
+SELECT d.id, u.id FROM distributions d, upstreams u WHERE (d.slug, u.slug) IN (
+    VALUES ('fedora', 'gnome'), ('ubuntu', 'gnome'), ('debian', 'gnome'), ('silverblue', 'gnome'),
+           ('vanillaos', 'gnome'), ('centosstream', 'gnome'), ('rocky', 'gnome'), ('almalinux', 'gnome'),
+           ('kdeneon', 'kde'), ('kinoite', 'kde'), ('opensuse', 'kde'), ('manjaro', 'kde'),
+           ('garuda', 'kde'), ('cachyos', 'kde'), ('bazzite', 'kde'), ('nobara', 'kde'), ('kali', 'xfce'),
+           ('mxlinux', 'xfce'), ('endeavouros', 'xfce'), ('popos', 'cosmic')
+);
+
+-- Repology repositories for Linux distributions (package freshness, shipped kernel)
+UPDATE distributions SET repology_repo = 'arch' WHERE slug = 'arch' AND repology_repo IS NULL;
+UPDATE distributions SET repology_repo = 'debian_13' WHERE slug = 'debian' AND repology_repo IS NULL;
+UPDATE distributions SET repology_repo = 'fedora_42' WHERE slug = 'fedora' AND repology_repo IS NULL;
+UPDATE distributions SET repology_repo = 'ubuntu_24_04' WHERE slug = 'ubuntu' AND repology_repo IS NULL;
+UPDATE distributions SET repology_repo = 'opensuse_tumbleweed' WHERE slug = 'opensuse' AND repology_repo IS NULL;
+UPDATE distributions SET repology_repo = 'gentoo' WHERE slug = 'gentoo' AND repology_repo IS NULL;
+UPDATE distributions SET repology_repo = 'void_x86_64' WHERE slug = 'void' AND repology_repo IS NULL;
+UPDATE distributions SET repology_repo = 'alpine_edge' WHERE slug = 'alpine' AND repology_repo IS NULL;
+
+-- Official mirror lists (mirror count and spread)
+UPDATE distributions SET mirror_list_url = 'https://archlinux.org/mirrors/status/json/', mirror_list_format = 'archlinux'
+    WHERE slug = 'arch' AND mirror_list_url IS NULL;
+UPDATE distributions SET mirror_list_url = 'https://mirrors.fedoraproject.org/metalink?repo=fedora-42&arch=x86_64',
+    mirror_list_format = 'metalink' WHERE slug = 'fedora' AND mirror_list_url IS NULL;
+UPDATE distributions SET
+    mirror_list_url = 'https://salsa.debian.org/mirror-team/masterlist/-/raw/master/Mirrors.masterlist',
+    mirror_list_format = 'debian-masterlist' WHERE slug = 'debian' AND mirror_list_url IS NULL;
+
+-- Installer images and package indexes (download sizes)
+UPDATE distributions SET iso_url = 'https://geo.mirror.pkgbuild.com/iso/latest/archlinux-x86_64.iso',
+    update_index_url = 'https://geo.mirror.pkgbuild.com/core/os/x86_64/core.db', update_index_format = 'pacman'
+    WHERE slug = 'arch' AND iso_url IS NULL;
+UPDATE distributions SET
+    update_index_url = 'https://dl-cdn.alpinelinux.org/alpine/edge/main/x86_64/APKINDEX.tar.gz',
+    update_index_format = 'apk' WHERE slug = 'alpine' AND update_index_url IS NULL;
+
+-- Self-hosted forges
+UPDATE distributions SET gitlab_group = 'alpine', forge_kind = 'gitlab',
+    forge_base_url = 'https://gitlab.alpinelinux.org' WHERE slug = 'alpine' AND forge_base_url IS NULL;
+UPDATE distributions SET pagure_namespace = 'rpms', forge_kind = 'pagure',
+    forge_base_url = 'https://src.fedoraproject.org' WHERE slug = 'fedora' AND forge_base_url IS NULL;
+-- Mass rebuilds commit to every package in dist-git under this name
+UPDATE distributions SET bot_accounts = 'Fedora Release Engineering' WHERE slug = 'fedora' AND bot_accounts IS NULL;
+
+-- Bugzilla instances (open bugs and bug flow)
+UPDATE distributions SET bugzilla_url = 'https://bugzilla.redhat.com', bugzilla_product = 'Fedora'
+    WHERE slug = 'fedora' AND bugzilla_url IS NULL;
+UPDATE distributions SET bugzilla_url = 'https://bugzilla.opensuse.org', bugzilla_product = 'openSUSE Tumbleweed'
+    WHERE slug = 'opensuse' AND bugzilla_url IS NULL;
+UPDATE distributions SET bugzilla_url = 'https://bugs.gentoo.org', bugzilla_product = 'Gentoo Linux'
+    WHERE slug = 'gentoo' AND bugzilla_url IS NULL;
+
+-- Release models (hardware enablement lag is tracked for point releases)
+UPDATE distributions SET release_model = 'point' WHERE release_model IS NULL AND slug IN
+    ('debian', 'fedora', 'ubuntu');
+UPDATE distributions SET release_model = 'rolling' WHERE release_model IS NULL AND slug IN
+    ('arch', 'opensuse', 'gentoo', 'void');
+
+-- Lineage of derivative distributions (families are the root of each chain)
+UPDATE distributions SET based_on = 'debian' WHERE based_on IS NULL AND slug IN
+    ('ubuntu', 'mxlinux', 'antix', 'kali', 'parrot', 'tails', 'raspios', 'deepin', 'pureos',
+     'devuan', 'vanillaos', 'whonix');
+UPDATE distributions SET based_on = 'ubuntu' WHERE based_on IS NULL AND slug IN
+    ('mint', 'popos', 'elementary', 'zorin', 'kdeneon');
+UPDATE distributions SET based_on = 'arch' WHERE based_on IS NULL AND slug IN
+    ('manjaro', 'endeavouros', 'garuda', 'arcolinux', 'artix', 'cachyos', 'blendos');
+UPDATE distributions SET based_on = 'fedora' WHERE based_on IS NULL AND slug IN
+    ('nobara', 'ultramarine', 'bazzite', 'silverblue', 'kinoite', 'centosstream');
+UPDATE distributions SET based_on = 'centosstream' WHERE based_on IS NULL AND slug IN
+    ('rocky', 'almalinux');
+UPDATE distributions SET based_on = 'opensuse' WHERE based_on IS NULL AND slug = 'microos';
+UPDATE distributions SET based_on = 'freebsd' WHERE based_on IS NULL AND slug = 'ghostbsd';
+
+-- Variants: editions sharing their parent's code and packages but with their own community
+UPDATE distributions SET variant_of = 'fedora' WHERE variant_of IS NULL AND slug IN ('silverblue', 'kinoite');
+UPDATE distributions SET variant_of = 'opensuse' WHERE variant_of IS NULL AND slug = 'microos';
+-- The parent's subreddit was standing in for theirs, counting its community twice
+UPDATE distributions SET subreddit = 'silverblue' WHERE slug = 'silverblue' AND subreddit = 'Fedora';
+UPDATE distributions SET subreddit = NULL WHERE slug = 'kinoite' AND subreddit = 'Fedora';
+UPDATE distributions SET subreddit = NULL WHERE slug = 'microos' AND subreddit = 'openSUSE';
+
+-- GitHub Discussions, where these distros' users ask for help
+UPDATE distributions SET discussions_repo = 'pop-os/pop' WHERE slug = 'popos' AND discussions_repo IS NULL;
+UPDATE distributions SET discussions_repo = 'ublue-os/bazzite' WHERE slug = 'bazzite' AND discussions_repo IS NULL;
+
+-- Update existing distributions with subreddits (migration for existing data)
+UPDATE distributions SET subreddit = 'archlinux' WHERE slug = 'arch' AND subreddit IS NULL;
+UPDATE distributions SET subreddit = 'debian' WHERE slug = 'debian' AND subreddit IS NULL;
+UPDATE distributions SET subreddit = 'Fedora' WHERE slug = 'fedora' AND subreddit IS NULL;
+UPDATE distributions SET subreddit = 'NixOS' WHERE slug = 'nixos' AND subreddit IS NULL;
+UPDATE distributions SET subreddit = 'Ubuntu' WHERE slug = 'ubuntu' AND subreddit IS NULL;
+UPDATE distributions SET subreddit = 'pop_os' WHERE slug = 'popos' AND subreddit IS NULL;
+UPDATE distributions SET subreddit = 'ManjaroLinux' WHERE slug = 'manjaro' AND subreddit IS NULL;
+UPDATE distributions SET subreddit = 'EndeavourOS' WHERE slug = 'endeavouros' AND subreddit IS NULL;
+UPDATE distributions SET subreddit = 'linuxmint' WHERE slug = 'mint' AND subreddit IS NULL;
+UPDATE distributions SET subreddit = 'Gentoo' WHERE slug = 'gentoo' AND subreddit IS NULL;
+UPDATE distributions SET subreddit = 'voidlinux' WHERE slug = 'void' AND subreddit IS NULL;
+UPDATE distributions SET subreddit = 'openSUSE' WHERE slug = 'opensuse' AND subreddit IS NULL;
+"#;
@@ -0,0 +1,370 @@
+//! Composable filters over snapshot tables
+//!
+//! [`SnapshotQuery`] builds the filtering, ordering and paging shared by raw snapshot
+//! listings and by the latest and as-of readers, so endpoints, exports and the analyzer
+//! don't each need a hand-written query per combination of filters.
+
+use crate::models::{
+    CommunitySnapshot, GithubSnapshot, MetricKey, MetricValue, PackageSnapshot, ReleaseSnapshot,
+    TimeWindow,
+};
+use crate::schema::Database;
+use crate::timestamp::to_sql_timestamp;
+use crate::{DatabaseError, Result};
+use chrono::{DateTime, Utc};
+use sqlx::sqlite::SqliteRow;
+use sqlx::{FromRow, QueryBuilder, Sqlite};
+
+/// A snapshot table [`SnapshotQuery`] can read
+///
+/// Every snapshot table has `id`, `distro_id` and `collected_at` columns.
+pub trait SnapshotTable: for<'r> FromRow<'r, SqliteRow> + Send + Unpin {
+    const KIND: SnapshotKind;
+    /// Columns selected into the row type
+    const COLUMNS: &'static str;
+}
+
+impl SnapshotTable for GithubSnapshot {
+    const KIND: SnapshotKind = SnapshotKind::Github;
+    const COLUMNS: &'static str = "id, distro_id, repo_name, stars, forks, open_issues, open_prs,
+        commits_30d, commits_365d, contributors_30d, bot_commits_30d, bot_commits_365d,
+        is_mirror, topics, last_commit_at, provenance, collected_at";
+}
+
+impl SnapshotTable for ReleaseSnapshot {
+    const KIND: SnapshotKind = SnapshotKind::Release;
+    const COLUMNS: &'static str = "id, distro_id, repo_name, tag_name, release_name, published_at,
+        is_prerelease, is_tag, signed, checksummed, downloads, provenance, collected_at";
+}
+
+impl SnapshotTable for PackageSnapshot {
+    const KIND: SnapshotKind = SnapshotKind::Package;
+    const COLUMNS: &'static str = "id, distro_id, total_packages, outdated_packages, security_updates,
+        kernel_version, kernel_series, kernel_status, kernel_upstream, mesa_version, mesa_upstream,
+        official_packages, flagged_packages, aur_packages, provenance, collected_at";
+}
+
+impl SnapshotTable for CommunitySnapshot {
+    const KIND: SnapshotKind = SnapshotKind::Community;
+    const COLUMNS: &'static str =
+        "id, distro_id, source, active_users_30d, posts_30d, response_time_avg_hours, questions_30d,
+        answers_30d, provenance, collected_at";
+}
+
+impl SnapshotTable for MetricValue {
+    const KIND: SnapshotKind = SnapshotKind::Metric;
+    const COLUMNS: &'static str = "id, distro_id, source, key, value, collected_at";
+}
+
+/// Which snapshot table to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotKind {
+    Github,
+    Release,
+    Package,
+    Community,
+    Metric,
+}
+
+impl SnapshotKind {
+    pub const ALL: &'static [SnapshotKind] = &[
+        Self::Github,
+        Self::Release,
+        Self::Package,
+        Self::Community,
+        Self::Metric,
+    ];
+
+    pub fn parse(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|k| k.as_str() == name)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Github => "github",
+            Self::Release => "release",
+            Self::Package => "package",
+            Self::Community => "community",
+            Self::Metric => "metric",
+        }
+    }
+
+    fn table(&self) -> &'static str {
+        match self {
+            Self::Github => "github_snapshots",
+            Self::Release => "release_snapshots",
+            Self::Package => "package_snapshots",
+            Self::Community => "community_snapshots",
+            Self::Metric => "metrics",
+        }
+    }
+
+    /// Whether rows belong to a repository and can be filtered with [`SnapshotQuery::repo`]
+    pub fn has_repo(&self) -> bool {
+        matches!(self, Self::Github | Self::Release)
+    }
+
+    /// Whether rows name their origin and can be filtered with [`SnapshotQuery::source`]
+    pub fn has_source(&self) -> bool {
+        matches!(self, Self::Community | Self::Metric)
+    }
+
+    /// Whether rows have a key and can be filtered with [`SnapshotQuery::key`]
+    pub fn has_key(&self) -> bool {
+        matches!(self, Self::Metric)
+    }
+
+    /// Whether rows are classified as mirrors and can be filtered with [`SnapshotQuery::mirrors`]
+    pub fn has_mirror(&self) -> bool {
+        matches!(self, Self::Github)
+    }
+
+    /// Columns identifying one tracked thing, of which [`SnapshotQuery::latest`] keeps the newest row
+    fn latest_per(&self) -> &'static str {
+        match self {
+            Self::Github => "distro_id, repo_name",
+            Self::Release => "distro_id, repo_name, tag_name",
+            Self::Package => "distro_id",
+            Self::Community => "distro_id, source",
+            Self::Metric => "distro_id, source, key",
+        }
+    }
+
+    /// Ordering of [`SnapshotQuery::latest`] results
+    fn latest_order(&self) -> &'static str {
+        match self {
+            Self::Github => "distro_id, repo_name",
+            Self::Release => "distro_id, published_at DESC",
+            Self::Package => "distro_id",
+            Self::Community => "distro_id, source",
+            Self::Metric => "distro_id, key, source",
+        }
+    }
+}
+
+/// Direction of the `collected_at` ordering
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "asc" => Some(Self::Asc),
+            "desc" => Some(Self::Desc),
+            _ => None,
+        }
+    }
+
+    fn as_sql(&self) -> &'static str {
+        match self {
+            Self::Asc => "ASC",
+            Self::Desc => "DESC",
+        }
+    }
+}
+
+/// Filters for reading raw snapshots, oldest first unless ordered otherwise
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotQuery {
+    distro_id: Option<i64>,
+    repo: Option<String>,
+    source: Option<String>,
+    key: Option<MetricKey>,
+    mirrors: Option<bool>,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    latest: bool,
+    after_id: Option<i64>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    order: SortOrder,
+}
+
+impl SnapshotQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only snapshots of this distribution
+    pub fn distro(mut self, distro_id: i64) -> Self {
+        self.distro_id = Some(distro_id);
+        self
+    }
+
+    /// Only snapshots of this repository ("owner/name")
+    pub fn repo(mut self, repo: impl Into<String>) -> Self {
+        self.repo = Some(repo.into());
+        self
+    }
+
+    /// Only rows from this origin, e.g. "reddit" for community snapshots
+    pub fn source(mut self, source: impl Into<String>) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Only generic metric values with this key
+    pub fn key(mut self, key: MetricKey) -> Self {
+        self.key = Some(key);
+        self
+    }
+
+    /// Only repos whose mirror classification, with repo overrides applied, matches
+    pub fn mirrors(mut self, mirrors: bool) -> Self {
+        self.mirrors = Some(mirrors);
+        self
+    }
+
+    /// Only snapshots collected at or after this time
+    pub fn since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Only snapshots collected at or before this time
+    pub fn until(mut self, until: DateTime<Utc>) -> Self {
+        self.until = Some(until);
+        self
+    }
+
+    /// Only the newest row of each repo, tag, source or key, as of [`until`](Self::until) if set
+    ///
+    /// Results are ordered by what they're the latest of (releases newest first) rather than
+    /// by `collected_at`, and can't be paged with [`after`](Self::after).
+    pub fn latest(mut self) -> Self {
+        self.latest = true;
+        self
+    }
+
+    /// Continue after this row, in the query's order (keyset pagination)
+    pub fn after(mut self, id: i64) -> Self {
+        self.after_id = Some(id);
+        self
+    }
+
+    pub fn limit(mut self, limit: i64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: i64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub fn order(mut self, order: SortOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Apply a history time window: range, cursor and page size
+    pub fn window(mut self, window: &TimeWindow) -> Self {
+        self.since = Some(window.since);
+        self.until = window.until;
+        self.after_id = window.after_id;
+        self.limit = Some(window.limit);
+        self
+    }
+
+    /// Run the query against the table of `T`
+    ///
+    /// Fails with [`DatabaseError::InvalidQuery`] when a filter doesn't apply to the table.
+    pub async fn fetch<T: SnapshotTable>(&self, db: &Database) -> Result<Vec<T>> {
+        let kind = T::KIND;
+        let table = kind.table();
+
+        if self.repo.is_some() && !kind.has_repo() {
+            return Err(DatabaseError::InvalidQuery(format!("{} snapshots have no repository", kind.as_str())));
+        }
+        if self.source.is_some() && !kind.has_source() {
+            return Err(DatabaseError::InvalidQuery(format!("{} snapshots have no source", kind.as_str())));
+        }
+        if self.key.is_some() && !kind.has_key() {
+            return Err(DatabaseError::InvalidQuery(format!("{} snapshots have no key", kind.as_str())));
+        }
+        if self.mirrors.is_some() && !kind.has_mirror() {
+            return Err(DatabaseError::InvalidQuery(format!("{} snapshots have no mirrors", kind.as_str())));
+        }
+        if self.latest && self.after_id.is_some() {
+            return Err(DatabaseError::InvalidQuery("latest snapshots can't be paged with a cursor".to_string()));
+        }
+
+        // In latest mode the row filters pick the candidates inside a ranking subquery and the
+        // rest apply to the newest row of each group
+        let mut query = QueryBuilder::<Sqlite>::new(format!("SELECT {} FROM ", T::COLUMNS));
+        if self.latest {
+            query.push(format!(
+                "(SELECT *, ROW_NUMBER() OVER (PARTITION BY {} ORDER BY collected_at DESC, id DESC) AS latest_rank
+                 FROM {} WHERE 1 = 1",
+                kind.latest_per(),
+                table
+            ));
+        } else {
+            query.push(format!("{} s WHERE 1 = 1", table));
+        }
+
+        if let Some(distro_id) = self.distro_id {
+            query.push(" AND distro_id = ").push_bind(distro_id);
+        }
+        if let Some(repo) = &self.repo {
+            query.push(" AND repo_name = ").push_bind(repo.clone());
+        }
+        if let Some(source) = &self.source {
+            query.push(" AND source = ").push_bind(source.clone());
+        }
+        if let Some(key) = &self.key {
+            query.push(" AND key = ").push_bind(key.as_str().to_string());
+        }
+        if let Some(since) = self.since {
+            query.push(" AND collected_at >= ").push_bind(to_sql_timestamp(since));
+        }
+        if let Some(until) = self.until {
+            query.push(" AND collected_at <= ").push_bind(to_sql_timestamp(until));
+        }
+        if self.latest {
+            query.push(") s WHERE latest_rank = 1");
+        }
+        if let Some(mirrors) = self.mirrors {
+            query
+                .push(
+                    " AND COALESCE((SELECT o.is_mirror FROM repo_overrides o
+                     WHERE o.distro_id = s.distro_id AND o.repo_name = s.repo_name), s.is_mirror) = ",
+                )
+                .push_bind(mirrors);
+        }
+        if let Some(after_id) = self.after_id {
+            let direction = match self.order {
+                SortOrder::Asc => ">",
+                SortOrder::Desc => "<",
+            };
+            query
+                .push(format!(
+                    " AND (collected_at, id) {} (SELECT collected_at, id FROM {} WHERE id = ",
+                    direction, table
+                ))
+                .push_bind(after_id)
+                .push(")");
+        }
+
+        if self.latest {
+            query.push(format!(" ORDER BY {}", kind.latest_order()));
+        } else {
+            let order = self.order.as_sql();
+            query.push(format!(" ORDER BY collected_at {}, id {}", order, order));
+        }
+
+        // SQLite needs a LIMIT before OFFSET; -1 means unlimited
+        query.push(" LIMIT ").push_bind(self.limit.unwrap_or(-1));
+        if let Some(offset) = self.offset {
+            query.push(" OFFSET ").push_bind(offset);
+        }
+
+        let rows = query.build_query_as::<T>().fetch_all(db.pool()).await?;
+
+        Ok(rows)
+    }
+}
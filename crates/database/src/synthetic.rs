@@ -0,0 +1,305 @@
+//! Randomized snapshot and score history
+//!
+//! Benchmarks need a database the size of a long-running instance, and frontend work
+//! needs charts with something on them, without anyone collecting for months. This fills
+//! distros with a plausible history: counts that drift and grow from a per-distro baseline,
+//! a release every couple of months and a health score per batch. The numbers are made up;
+//! nothing here goes through the collectors or the analyzer.
+
+use crate::models::Distribution;
+use crate::schema::Database;
+use crate::timestamp::to_sql_timestamp;
+use crate::{NewDistribution, Result};
+use chrono::{DateTime, TimeDelta, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use sqlx::SqliteConnection;
+
+/// What to generate
+#[derive(Debug, Clone)]
+pub struct SyntheticHistory {
+    /// Days of history, ending now
+    pub days: i64,
+    /// Days between snapshot batches
+    pub interval_days: i64,
+    /// Seed for the random walks, so the same settings produce the same data
+    pub seed: u64,
+}
+
+impl Default for SyntheticHistory {
+    fn default() -> Self {
+        Self {
+            days: 365,
+            interval_days: 1,
+            seed: 0,
+        }
+    }
+}
+
+/// Rows written by [`Database::generate_history`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SyntheticSummary {
+    pub distros: usize,
+    pub batches: usize,
+    pub snapshots: usize,
+    pub scores: usize,
+}
+
+/// Fixed traits of one distro that its history wanders around
+struct Profile {
+    /// Stars of its main repo at the start
+    size: f64,
+    /// 0-1, how busy development is
+    activity: f64,
+    /// Growth over the whole history, as a fraction of the starting size
+    growth: f64,
+    repos: Vec<String>,
+    subreddit: Option<String>,
+    packages: Option<i64>,
+}
+
+impl Profile {
+    fn random(rng: &mut StdRng, distro: &Distribution) -> Self {
+        let org = distro.github_org.clone().unwrap_or_else(|| distro.slug.clone());
+        let names = ["packages", "installer", "website", "infra", "docs"];
+        let repos = names[..rng.gen_range(1..=names.len())]
+            .iter()
+            .map(|name| format!("{}/{}", org, name))
+            .collect();
+
+        Self {
+            size: 10f64.powf(rng.gen_range(1.5..4.8)),
+            activity: rng.gen_range(0.05..1.0),
+            growth: rng.gen_range(-0.05..0.6),
+            repos,
+            subreddit: distro.subreddit.clone().or_else(|| rng.gen_bool(0.6).then(|| distro.slug.clone())),
+            packages: (distro.repology_repo.is_some() || rng.gen_bool(0.5))
+                .then(|| rng.gen_range(2_000..90_000)),
+        }
+    }
+}
+
+impl Database {
+    /// Create `count` distros for synthetic data, with slugs `{prefix}-0001` onwards
+    pub async fn create_synthetic_distributions(
+        &self,
+        prefix: &str,
+        count: usize,
+    ) -> Result<Vec<Distribution>> {
+        let mut distros = Vec::with_capacity(count);
+        for n in 1..=count {
+            let slug = format!("{}-{:04}", prefix, n);
+            distros.push(
+                self.create_distribution(NewDistribution {
+                    name: format!("{} {:04}", prefix, n),
+                    github_org: Some(slug.clone()),
+                    slug,
+                    homepage: None,
+                    gitlab_group: None,
+                    subreddit: None,
+                    description: Some("Synthetic distribution".to_string()),
+                    os_family: Some("linux".to_string()),
+                    repology_repo: None,
+                })
+                .await?,
+            );
+        }
+        Ok(distros)
+    }
+
+    /// Fill `distros` with randomized snapshot and score history
+    ///
+    /// Rows are written directly with backdated timestamps, one transaction per distro,
+    /// rather than through the write queue, which stamps everything with the current time.
+    pub async fn generate_history(
+        &self,
+        distros: &[Distribution],
+        history: &SyntheticHistory,
+    ) -> Result<SyntheticSummary> {
+        let now = Utc::now();
+        let interval = history.interval_days.max(1);
+        let batches = (history.days.max(0) / interval + 1) as usize;
+        let mut summary = SyntheticSummary::default();
+
+        for distro in distros {
+            let seed = history.seed ^ (distro.id as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            let mut rng = StdRng::seed_from_u64(seed);
+            let profile = Profile::random(&mut rng, distro);
+
+            let mut tx = self.pool().begin().await?;
+            let mut previous_score = None;
+            for batch in 0..batches {
+                let days_ago = (batches - 1 - batch) as i64 * interval;
+                let at = now - TimeDelta::days(days_ago);
+                let progress = batch as f64 / batches.max(2).saturating_sub(1) as f64;
+
+                summary.snapshots += write_batch(&mut tx, distro.id, &profile, &mut rng, at, progress).await?;
+                if batch % 60 == 0 {
+                    write_release(&mut tx, distro.id, &profile, batch, at).await?;
+                    summary.snapshots += 1;
+                }
+
+                let score = write_score(&mut tx, distro.id, &profile, &mut rng, at, progress, previous_score);
+                previous_score = Some(score.await?);
+                summary.scores += 1;
+            }
+            tx.commit().await?;
+
+            summary.distros += 1;
+            summary.batches += batches;
+        }
+
+        Ok(summary)
+    }
+}
+
+/// Multiply by a random factor within `spread` of 1
+fn jitter(rng: &mut StdRng, value: f64, spread: f64) -> f64 {
+    value * rng.gen_range(1.0 - spread..1.0 + spread)
+}
+
+async fn write_batch(
+    conn: &mut SqliteConnection,
+    distro_id: i64,
+    profile: &Profile,
+    rng: &mut StdRng,
+    at: DateTime<Utc>,
+    progress: f64,
+) -> Result<usize> {
+    let collected_at = to_sql_timestamp(at);
+    let size = profile.size * (1.0 + profile.growth * progress);
+    let mut written = 0;
+
+    for (i, repo) in profile.repos.iter().enumerate() {
+        // Each further repo is a fraction of the main one
+        let share = 1.0 / (i as f64 * 2.5 + 1.0);
+        let stars = jitter(rng, size * share, 0.01);
+        let commits = jitter(rng, profile.activity * (stars.sqrt() * 4.0 + 5.0), 0.3);
+        let contributors = (commits.powf(0.6) * profile.activity).max(1.0);
+        let idle_days = ((1.0 - profile.activity).powi(3) * 400.0 * rng.gen_range(0.5..1.5)) as i64;
+
+        sqlx::query(
+            "INSERT INTO github_snapshots
+             (distro_id, repo_name, stars, forks, open_issues, open_prs, commits_30d, commits_365d,
+              contributors_30d, bot_commits_30d, bot_commits_365d, last_commit_at, collected_at)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(distro_id)
+        .bind(repo)
+        .bind(stars as i64)
+        .bind((stars * 0.18) as i64)
+        .bind(jitter(rng, stars * 0.04 + 3.0, 0.2) as i64)
+        .bind(jitter(rng, commits * 0.15 + 1.0, 0.3) as i64)
+        .bind(commits as i64)
+        .bind((commits * 11.0) as i64)
+        .bind(contributors as i64)
+        .bind((commits * 0.1) as i64)
+        .bind((commits * 1.2) as i64)
+        .bind(to_sql_timestamp(at - TimeDelta::days(idle_days)))
+        .bind(&collected_at)
+        .execute(&mut *conn)
+        .await?;
+        written += 1;
+    }
+
+    if let Some(subreddit) = &profile.subreddit {
+        sqlx::query(
+            "INSERT INTO community_snapshots (distro_id, source, active_users_30d, posts_30d, collected_at)
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(distro_id)
+        .bind(format!("reddit:r/{}", subreddit))
+        .bind((size * 6.0) as i64)
+        .bind(jitter(rng, size.sqrt() * 1.5, 0.4) as i64)
+        .bind(&collected_at)
+        .execute(&mut *conn)
+        .await?;
+        written += 1;
+    }
+
+    if let Some(total) = profile.packages {
+        let outdated_share = (0.35 - profile.activity * 0.3 + rng.gen_range(-0.03..0.03)).clamp(0.01, 0.9);
+        let kernel_status = if profile.activity < 0.15 { "eol" } else { "longterm" };
+        sqlx::query(
+            "INSERT INTO package_snapshots
+             (distro_id, total_packages, outdated_packages, security_updates, kernel_status, collected_at)
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(distro_id)
+        .bind(total)
+        .bind((total as f64 * outdated_share) as i64)
+        .bind(rng.gen_range(0..40))
+        .bind(kernel_status)
+        .bind(&collected_at)
+        .execute(&mut *conn)
+        .await?;
+        written += 1;
+    }
+
+    Ok(written)
+}
+
+async fn write_release(
+    conn: &mut SqliteConnection,
+    distro_id: i64,
+    profile: &Profile,
+    batch: usize,
+    at: DateTime<Utc>,
+) -> Result<()> {
+    let version = batch / 60 + 1;
+    sqlx::query(
+        "INSERT INTO release_snapshots
+         (distro_id, repo_name, tag_name, release_name, published_at, is_prerelease, collected_at)
+         VALUES (?, ?, ?, ?, ?, 0, ?)",
+    )
+    .bind(distro_id)
+    .bind(&profile.repos[0])
+    .bind(format!("v{}.0", version))
+    .bind(format!("Release {}.0", version))
+    .bind(to_sql_timestamp(at))
+    .bind(to_sql_timestamp(at))
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+async fn write_score(
+    conn: &mut SqliteConnection,
+    distro_id: i64,
+    profile: &Profile,
+    rng: &mut StdRng,
+    at: DateTime<Utc>,
+    progress: f64,
+    previous: Option<f64>,
+) -> Result<f64> {
+    let noise = |rng: &mut StdRng| rng.gen_range(-3.0..3.0);
+    let development = (20.0 + profile.activity * 75.0 + noise(rng)).clamp(0.0, 100.0);
+    let community =
+        (profile.size.log10() * 15.0 + profile.growth * 20.0 * progress + noise(rng)).clamp(0.0, 100.0);
+    let maintenance = (35.0 + profile.activity * 55.0 + noise(rng)).clamp(0.0, 100.0);
+    let overall = development * 0.4 + community * 0.3 + maintenance * 0.3;
+
+    let trend = match previous {
+        Some(previous) if overall - previous > 2.0 => "up",
+        Some(previous) if previous - overall > 2.0 => "down",
+        _ => "stable",
+    };
+
+    sqlx::query(
+        "INSERT INTO health_scores
+         (distro_id, overall_score, development_score, community_score, maintenance_score, trend,
+          calculated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(distro_id)
+    .bind(overall)
+    .bind(development)
+    .bind(community)
+    .bind(maintenance)
+    .bind(trend)
+    .bind(to_sql_timestamp(at))
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(overall)
+}
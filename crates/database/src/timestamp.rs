@@ -0,0 +1,79 @@
+//! Timestamp storage format
+//!
+//! Every timestamp column holds RFC 3339 UTC text with second precision, such as
+//! `2024-05-01T12:00:00Z`. The fixed width keeps string comparison, `MIN`/`MAX` and
+//! indexes chronological, so range filters compare columns directly against values from
+//! [`to_sql_timestamp`] or `strftime('%Y-%m-%dT%H:%M:%SZ', 'now', ...)` in SQL.
+
+use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
+
+/// `strftime` pattern of stored timestamps
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%dT%H:%M:%SZ";
+
+/// Format a timestamp for storage or for comparison against a timestamp column
+pub fn to_sql_timestamp(at: DateTime<Utc>) -> String {
+    at.format(TIMESTAMP_FORMAT).to_string()
+}
+
+/// Parse a user-supplied time bound: an RFC 3339 timestamp or a bare `YYYY-MM-DD` date
+///
+/// A date means the start of that day, or its last second when `end_of_day` is set, so an
+/// `until` date includes the whole day.
+pub fn parse_time_bound(value: &str, end_of_day: bool) -> Option<DateTime<Utc>> {
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Some(ts.with_timezone(&Utc));
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y-%m-%d").ok()?;
+    let time = if end_of_day {
+        NaiveTime::from_hms_opt(23, 59, 59)?
+    } else {
+        NaiveTime::MIN
+    };
+
+    Some(date.and_time(time).and_utc())
+}
+
+/// Every timestamp column, as (table, column)
+pub(crate) const TIMESTAMP_COLUMNS: &[(&str, &str)] = &[
+    ("distributions", "created_at"),
+    ("distributions", "updated_at"),
+    ("distributions", "archived_at"),
+    ("distributions", "analyzed_at"),
+    ("github_snapshots", "last_commit_at"),
+    ("github_snapshots", "collected_at"),
+    ("package_snapshots", "collected_at"),
+    ("community_snapshots", "collected_at"),
+    ("release_snapshots", "published_at"),
+    ("release_snapshots", "collected_at"),
+    ("release_cursors", "published_at"),
+    ("release_cursors", "updated_at"),
+    ("health_scores", "calculated_at"),
+    ("upstreams", "created_at"),
+    ("upstream_snapshots", "last_commit_at"),
+    ("upstream_snapshots", "collected_at"),
+    ("annotations", "created_at"),
+    ("claims", "created_at"),
+    ("claims", "verified_at"),
+    ("maintainers", "created_at"),
+    ("suggestions", "created_at"),
+    ("suggestions", "reviewed_at"),
+    ("config_issues", "first_seen_at"),
+    ("config_issues", "last_seen_at"),
+    ("config_issues", "resolved_at"),
+    ("repo_overrides", "created_at"),
+    ("github_org_aliases", "created_at"),
+    ("highlights", "selected_at"),
+    ("milestones", "occurred_at"),
+    ("metrics", "collected_at"),
+    ("collection_jobs", "created_at"),
+    ("collection_jobs", "started_at"),
+    ("collection_jobs", "finished_at"),
+    ("collection_jobs", "run_after"),
+    ("collection_runs", "started_at"),
+    ("collection_runs", "finished_at"),
+    ("methodology_changes", "changed_at"),
+    ("circuit_breakers", "opened_at"),
+    ("circuit_breakers", "updated_at"),
+    ("settings", "updated_at"),
+];
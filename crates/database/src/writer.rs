@@ -0,0 +1,418 @@
+//! Write-behind queue for snapshot inserts
+//!
+//! Collectors, the job worker and API-triggered collections all insert snapshots at
+//! once, and SQLite lets only one connection write at a time: with every pooled
+//! connection competing for the lock, writes queue on `busy_timeout` and eventually
+//! fail with `database is locked`. Instead they go through a channel to a single task
+//! that owns one connection and commits whatever has queued up in one transaction.
+//! Callers still wait for their row ID, so the API is unchanged.
+
+use crate::models::*;
+use crate::timestamp::to_sql_timestamp;
+use crate::{DatabaseError, Result};
+use chrono::{DateTime, Utc};
+use sqlx::{SqliteConnection, SqlitePool};
+use tokio::sync::{mpsc, oneshot};
+use tracing::warn;
+
+/// Writes committed together at most
+const MAX_BATCH: usize = 64;
+
+/// Writes waiting before submitters are held up
+const QUEUE_DEPTH: usize = 1024;
+
+/// An insert handled by the writer task
+pub(crate) enum Write {
+    Github(NewGithubSnapshot),
+    Package(NewPackageSnapshot),
+    Community(NewCommunitySnapshot),
+    Governance(NewGovernanceSnapshot),
+    Infra(NewInfraSnapshot),
+    Archive(NewArchiveSnapshot),
+    Bugtracker(NewBugtrackerSnapshot),
+    Org(NewOrgSnapshot),
+    Popularity(NewPopularitySnapshot),
+    Release(NewReleaseSnapshot),
+    Upstream(NewUpstreamSnapshot),
+    Metric(NewMetricValue),
+    CollectionRun {
+        distro_id: i64,
+        source: String,
+        started_at: DateTime<Utc>,
+        snapshots: i64,
+        failure: Option<RunFailure>,
+    },
+}
+
+struct Request {
+    write: Write,
+    reply: oneshot::Sender<Result<i64>>,
+}
+
+/// Handle to the writer task; clones share the task
+#[derive(Clone)]
+pub(crate) struct Writer {
+    sender: mpsc::Sender<Request>,
+    pool: SqlitePool,
+}
+
+impl Writer {
+    /// Start a writer task on `pool`, which should hold a single connection
+    pub fn spawn(pool: SqlitePool) -> Self {
+        let (sender, receiver) = mpsc::channel(QUEUE_DEPTH);
+        tokio::spawn(run(pool.clone(), receiver));
+        Self { sender, pool }
+    }
+
+    /// Queue a write and wait for its row ID
+    pub async fn submit(&self, write: Write) -> Result<i64> {
+        let (reply, response) = oneshot::channel();
+        self.sender
+            .send(Request { write, reply })
+            .await
+            .map_err(|_| DatabaseError::Connection(sqlx::Error::PoolClosed))?;
+        response
+            .await
+            .map_err(|_| DatabaseError::Connection(sqlx::Error::PoolClosed))?
+    }
+
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+}
+
+async fn run(pool: SqlitePool, mut receiver: mpsc::Receiver<Request>) {
+    while let Some(first) = receiver.recv().await {
+        let mut batch = vec![first];
+        while batch.len() < MAX_BATCH {
+            match receiver.try_recv() {
+                Ok(request) => batch.push(request),
+                Err(_) => break,
+            }
+        }
+        write_batch(&pool, batch).await;
+    }
+}
+
+/// Commit a batch in one transaction, falling back to one transaction per write so a
+/// bad row doesn't fail the rest
+async fn write_batch(pool: &SqlitePool, batch: Vec<Request>) {
+    if batch.len() > 1 {
+        match apply_all(pool, &batch).await {
+            Ok(ids) => {
+                for (request, id) in batch.into_iter().zip(ids) {
+                    let _ = request.reply.send(Ok(id));
+                }
+                return;
+            }
+            Err(e) => warn!(writes = batch.len(), error = %e, "Batched write failed, retrying one at a time"),
+        }
+    }
+
+    for request in batch {
+        let result = apply_all(pool, std::slice::from_ref(&request)).await;
+        let _ = request.reply.send(result.map(|ids| ids[0]));
+    }
+}
+
+async fn apply_all(pool: &SqlitePool, batch: &[Request]) -> Result<Vec<i64>> {
+    let mut tx = pool.begin().await?;
+    let mut ids = Vec::with_capacity(batch.len());
+    for request in batch {
+        ids.push(request.write.apply(&mut tx).await?);
+    }
+    tx.commit().await?;
+    Ok(ids)
+}
+
+impl Write {
+    async fn apply(&self, conn: &mut SqliteConnection) -> Result<i64> {
+        let id = match self {
+            Self::Github(snapshot) => {
+                let id = sqlx::query(
+                    "INSERT INTO github_snapshots
+                     (distro_id, repo_name, stars, forks, open_issues, open_prs,
+                      commits_30d, commits_365d, contributors_30d, bot_commits_30d, bot_commits_365d,
+                      is_mirror, topics, last_commit_at, provenance)
+                     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(snapshot.distro_id)
+                .bind(&snapshot.repo_name)
+                .bind(snapshot.stars)
+                .bind(snapshot.forks)
+                .bind(snapshot.open_issues)
+                .bind(snapshot.open_prs)
+                .bind(snapshot.commits_30d)
+                .bind(snapshot.commits_365d)
+                .bind(snapshot.contributors_30d)
+                .bind(snapshot.bot_commits_30d)
+                .bind(snapshot.bot_commits_365d)
+                .bind(snapshot.is_mirror)
+                .bind((!snapshot.topics.is_empty()).then(|| snapshot.topics.join(",")))
+                .bind(snapshot.last_commit_at.map(to_sql_timestamp))
+                .bind(sqlx::types::Json(&snapshot.provenance))
+                .execute(&mut *conn)
+                .await?
+                .last_insert_rowid();
+
+                for (language, bytes) in &snapshot.languages {
+                    sqlx::query("INSERT INTO github_languages (snapshot_id, language, bytes) VALUES (?, ?, ?)")
+                        .bind(id)
+                        .bind(language)
+                        .bind(bytes)
+                        .execute(&mut *conn)
+                        .await?;
+                }
+
+                for (login, commits) in &snapshot.contributors {
+                    sqlx::query(
+                        "INSERT INTO github_contributors (snapshot_id, login, commits) VALUES (?, ?, ?)
+                         ON CONFLICT(snapshot_id, login) DO UPDATE SET commits = commits + excluded.commits",
+                    )
+                    .bind(id)
+                    .bind(login)
+                    .bind(commits)
+                    .execute(&mut *conn)
+                    .await?;
+                }
+
+                for (category, open_issues) in &snapshot.issue_labels {
+                    sqlx::query(
+                        "INSERT INTO github_issue_labels (snapshot_id, category, open_issues) VALUES (?, ?, ?)",
+                    )
+                    .bind(id)
+                    .bind(category.as_str())
+                    .bind(open_issues)
+                    .execute(&mut *conn)
+                    .await?;
+                }
+
+                if let Some(activity) = &snapshot.issue_activity {
+                    sqlx::query(
+                        "INSERT INTO github_issue_activity (snapshot_id, opened_30d, closed_30d, stale_closed_30d)
+                         VALUES (?, ?, ?, ?)",
+                    )
+                    .bind(id)
+                    .bind(activity.opened_30d)
+                    .bind(activity.closed_30d)
+                    .bind(activity.stale_closed_30d)
+                    .execute(&mut *conn)
+                    .await?;
+                }
+
+                id
+            }
+            Self::Package(snapshot) => sqlx::query(
+                "INSERT INTO package_snapshots
+                 (distro_id, total_packages, outdated_packages, security_updates,
+                  kernel_version, kernel_series, kernel_status, kernel_upstream, mesa_version, mesa_upstream,
+                  official_packages, flagged_packages, aur_packages, provenance)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(snapshot.distro_id)
+            .bind(snapshot.total_packages)
+            .bind(snapshot.outdated_packages)
+            .bind(snapshot.security_updates)
+            .bind(&snapshot.kernel_version)
+            .bind(&snapshot.kernel_series)
+            .bind(&snapshot.kernel_status)
+            .bind(&snapshot.kernel_upstream)
+            .bind(&snapshot.mesa_version)
+            .bind(&snapshot.mesa_upstream)
+            .bind(snapshot.official_packages)
+            .bind(snapshot.flagged_packages)
+            .bind(snapshot.aur_packages)
+            .bind(sqlx::types::Json(&snapshot.provenance))
+            .execute(&mut *conn)
+            .await?
+            .last_insert_rowid(),
+            Self::Community(snapshot) => sqlx::query(
+                "INSERT INTO community_snapshots
+                 (distro_id, source, active_users_30d, posts_30d, response_time_avg_hours, questions_30d,
+                  answers_30d, provenance)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(snapshot.distro_id)
+            .bind(&snapshot.source)
+            .bind(snapshot.active_users_30d)
+            .bind(snapshot.posts_30d)
+            .bind(snapshot.response_time_avg_hours)
+            .bind(snapshot.questions_30d)
+            .bind(snapshot.answers_30d)
+            .bind(sqlx::types::Json(&snapshot.provenance))
+            .execute(&mut *conn)
+            .await?
+            .last_insert_rowid(),
+            Self::Governance(snapshot) => sqlx::query(
+                "INSERT INTO governance_snapshots
+                 (distro_id, repos_checked, contributing, code_of_conduct, security_policy, governance, provenance)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(snapshot.distro_id)
+            .bind(snapshot.repos_checked)
+            .bind(snapshot.contributing)
+            .bind(snapshot.code_of_conduct)
+            .bind(snapshot.security_policy)
+            .bind(snapshot.governance)
+            .bind(sqlx::types::Json(&snapshot.provenance))
+            .execute(&mut *conn)
+            .await?
+            .last_insert_rowid(),
+            Self::Infra(snapshot) => sqlx::query(
+                "INSERT INTO infra_snapshots (distro_id, mirrors, mirror_countries, provenance)
+                 VALUES (?, ?, ?, ?)",
+            )
+            .bind(snapshot.distro_id)
+            .bind(snapshot.mirrors)
+            .bind(snapshot.mirror_countries)
+            .bind(sqlx::types::Json(&snapshot.provenance))
+            .execute(&mut *conn)
+            .await?
+            .last_insert_rowid(),
+            Self::Archive(snapshot) => sqlx::query(
+                "INSERT INTO archive_snapshots (distro_id, source_packages, rc_bugs, uploads, uploads_30d, provenance)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(snapshot.distro_id)
+            .bind(snapshot.source_packages)
+            .bind(snapshot.rc_bugs)
+            .bind(snapshot.uploads)
+            .bind(snapshot.uploads_30d)
+            .bind(sqlx::types::Json(&snapshot.provenance))
+            .execute(&mut *conn)
+            .await?
+            .last_insert_rowid(),
+            Self::Bugtracker(snapshot) => sqlx::query(
+                "INSERT INTO bugtracker_snapshots
+                 (distro_id, open_bugs, opened_30d, closed_30d, mean_resolution_days, provenance)
+                 VALUES (?, ?, ?, ?, ?, ?)",
+            )
+            .bind(snapshot.distro_id)
+            .bind(snapshot.open_bugs)
+            .bind(snapshot.opened_30d)
+            .bind(snapshot.closed_30d)
+            .bind(snapshot.mean_resolution_days)
+            .bind(sqlx::types::Json(&snapshot.provenance))
+            .execute(&mut *conn)
+            .await?
+            .last_insert_rowid(),
+            Self::Org(snapshot) => sqlx::query(
+                "INSERT INTO org_snapshots
+                 (distro_id, org, public_repos, public_members, followers, sponsors, org_created_at, provenance)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(snapshot.distro_id)
+            .bind(&snapshot.org)
+            .bind(snapshot.public_repos)
+            .bind(snapshot.public_members)
+            .bind(snapshot.followers)
+            .bind(snapshot.sponsors)
+            .bind(snapshot.org_created_at.map(to_sql_timestamp))
+            .bind(sqlx::types::Json(&snapshot.provenance))
+            .execute(&mut *conn)
+            .await?
+            .last_insert_rowid(),
+            Self::Popularity(snapshot) => sqlx::query(
+                "INSERT INTO popularity_snapshots (distro_id, source, active_installs_30d) VALUES (?, ?, ?)",
+            )
+            .bind(snapshot.distro_id)
+            .bind(&snapshot.source)
+            .bind(snapshot.active_installs_30d)
+            .execute(&mut *conn)
+            .await?
+            .last_insert_rowid(),
+            Self::Release(snapshot) => sqlx::query(
+                "INSERT INTO release_snapshots
+                 (distro_id, repo_name, tag_name, release_name, published_at, is_prerelease, is_tag,
+                  signed, checksummed, downloads, provenance)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(snapshot.distro_id)
+            .bind(&snapshot.repo_name)
+            .bind(&snapshot.tag_name)
+            .bind(&snapshot.release_name)
+            .bind(snapshot.published_at.map(to_sql_timestamp))
+            .bind(snapshot.is_prerelease)
+            .bind(snapshot.is_tag)
+            .bind(snapshot.signed)
+            .bind(snapshot.checksummed)
+            .bind(snapshot.downloads)
+            .bind(sqlx::types::Json(&snapshot.provenance))
+            .execute(&mut *conn)
+            .await?
+            .last_insert_rowid(),
+            Self::Upstream(snapshot) => sqlx::query(
+                "INSERT INTO upstream_snapshots
+                 (upstream_id, repo_name, stars, forks, open_issues, open_prs,
+                  commits_30d, contributors_30d, last_commit_at, provenance)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(snapshot.upstream_id)
+            .bind(&snapshot.repo_name)
+            .bind(snapshot.stars)
+            .bind(snapshot.forks)
+            .bind(snapshot.open_issues)
+            .bind(snapshot.open_prs)
+            .bind(snapshot.commits_30d)
+            .bind(snapshot.contributors_30d)
+            .bind(snapshot.last_commit_at.map(to_sql_timestamp))
+            .bind(sqlx::types::Json(&snapshot.provenance))
+            .execute(&mut *conn)
+            .await?
+            .last_insert_rowid(),
+            Self::Metric(metric) => {
+                sqlx::query("INSERT INTO metrics (distro_id, source, key, value) VALUES (?, ?, ?, ?)")
+                    .bind(metric.distro_id)
+                    .bind(&metric.source)
+                    .bind(metric.key.as_str())
+                    .bind(metric.value)
+                    .execute(&mut *conn)
+                    .await?
+                    .last_insert_rowid()
+            }
+            Self::CollectionRun {
+                distro_id,
+                source,
+                started_at,
+                snapshots,
+                failure,
+            } => sqlx::query(
+                "INSERT INTO collection_runs (distro_id, source, snapshots, error, error_kind, retryable, started_at)
+                 VALUES (?, ?, ?, ?, ?, ?, ?)",
+            )
+            .bind(distro_id)
+            .bind(source)
+            .bind(snapshots)
+            .bind(failure.as_ref().map(|f| f.message.as_str()))
+            .bind(failure.as_ref().map(|f| f.kind.as_str()))
+            .bind(failure.as_ref().map(|f| f.retryable))
+            .bind(to_sql_timestamp(*started_at))
+            .execute(&mut *conn)
+            .await?
+            .last_insert_rowid(),
+        };
+
+        Ok(id)
+    }
+
+    /// Source, distro and upstream for the event announcing this row; None for rows that
+    /// aren't announced
+    pub fn event_target(&self) -> Option<(&'static str, Option<i64>, Option<i64>)> {
+        let target = match self {
+            Self::Github(s) => ("github", Some(s.distro_id), None),
+            Self::Package(s) => ("package", Some(s.distro_id), None),
+            Self::Community(s) => ("community", Some(s.distro_id), None),
+            Self::Governance(s) => ("governance", Some(s.distro_id), None),
+            Self::Infra(s) => ("infra", Some(s.distro_id), None),
+            Self::Archive(s) => ("archive", Some(s.distro_id), None),
+            Self::Bugtracker(s) => ("bugtracker", Some(s.distro_id), None),
+            Self::Org(s) => ("org", Some(s.distro_id), None),
+            Self::Popularity(s) => ("popularity", Some(s.distro_id), None),
+            Self::Release(s) => ("release", Some(s.distro_id), None),
+            Self::Upstream(s) => ("upstream", None, Some(s.upstream_id)),
+            Self::Metric(m) => ("metric", Some(m.distro_id), None),
+            Self::CollectionRun { .. } => return None,
+        };
+        Some(target)
+    }
+}
@@ -19,12 +19,28 @@ const detailSection = document.getElementById('distro-detail');
 const distroInfo = document.getElementById('distro-info');
 const backBtn = document.getElementById('back-btn');
 
+const DEFAULT_TITLE = document.title;
+
 // Initialize
 document.addEventListener('DOMContentLoaded', init);
 backBtn.addEventListener('click', showRankings);
+window.addEventListener('popstate', () => {
+    const slug = new URLSearchParams(window.location.search).get('distro');
+    if (slug) {
+        showDistroDetail(slug, false);
+    } else {
+        showRankings();
+    }
+});
 
 async function init() {
     await loadRankings();
+
+    // Open a distro's page directly when linked to one (e.g. from the sitemap)
+    const slug = new URLSearchParams(window.location.search).get('distro');
+    if (slug) {
+        await showDistroDetail(slug, false);
+    }
 }
 
 // API Functions
@@ -92,12 +108,12 @@ function renderRankings() {
 
         return `
             <div class="ranking-row" data-slug="${d.slug}">
-                <span class="rank ${rankClass}">#${rank}</span>
+                <span class="rank ${rankClass}">#${rank}${renderRankChange(d.rank_change_30d)}</span>
                 <span class="distro-name-cell">
                     <span class="distro-name" title="${d.description || 'No description available'}">${d.name}</span>
                     ${dataSources}
                 </span>
-                <span class="score">${d.overall_score.toFixed(1)}</span>
+                <span class="score">${d.overall_score.toFixed(1)}${renderQualityWarning(d.data_quality)}</span>
                 <span class="metric">${formatNumber(m.total_contributors || 0)}</span>
                 <span class="metric">${m.releases_30d || 0}</span>
                 <span class="metric">${formatNumber(m.total_stars || 0)}</span>
@@ -132,11 +148,16 @@ function goToPage(page) {
 }
 
 // Show detail view for a distribution
-async function showDistroDetail(slug) {
+async function showDistroDetail(slug, updateUrl = true) {
     const distro = rankings.find(d => d.slug === slug);
     if (!distro) return;
 
     currentDistro = distro;
+    if (updateUrl) {
+        history.pushState(null, '', `?distro=${encodeURIComponent(slug)}`);
+    }
+    document.title = `${distro.name} - DistroVitals`;
+    loadStructuredData(slug);
 
     // Fetch additional data
     let healthData = null;
@@ -149,18 +170,25 @@ async function showDistroDetail(slug) {
     }
 
     try {
-        history = await fetchApi(`/distros/${slug}/history?days=30`);
+        history = (await fetchApi(`/distros/${slug}/history?days=30&include=annotations`)).scores;
     } catch (e) {
         // No history available
     }
 
-    renderDistroDetail(distro, healthData, history);
+    let changes = null;
+    try {
+        changes = await fetchApi(`/distros/${slug}/changes?since=7d`);
+    } catch (e) {
+        // No changes available
+    }
+
+    renderDistroDetail(distro, healthData, history, changes);
 
     rankingsSection.classList.add('hidden');
     detailSection.classList.remove('hidden');
 }
 
-function renderDistroDetail(distro, healthData, history) {
+function renderDistroDetail(distro, healthData, history, changes) {
     const scoreClass = getScoreClass(distro.overall_score);
     const trendIcon = getTrendIcon(distro.trend);
     const trendClass = getTrendClass(distro.trend);
@@ -174,6 +202,7 @@ function renderDistroDetail(distro, healthData, history) {
                 <span class="trend ${trendClass}" style="font-size: 1.5rem;">${trendIcon} ${distro.trend}</span>
             </div>
             <div class="overall-score ${scoreClass}">${distro.overall_score.toFixed(1)}</div>
+            ${renderDataQuality((healthData && healthData.data_quality) || distro.data_quality)}
         </div>
 
         ${dataSources}
@@ -211,12 +240,73 @@ function renderDistroDetail(distro, healthData, history) {
                 <span class="metric-value">${m.total_releases || 0}</span>
                 <span class="metric-label">Total Releases</span>
             </div>
+            ${m.mirrors != null ? `
+            <div class="metric-card">
+                <span class="metric-value">${formatNumber(m.mirrors)}</span>
+                <span class="metric-label">Mirrors${m.mirror_countries != null ? ` (${m.mirror_countries} countries)` : ''}</span>
+            </div>
+            ` : ''}
+            ${m.iso_size_mb != null ? `
+            <div class="metric-card">
+                <span class="metric-value">${formatMegabytes(m.iso_size_mb)}</span>
+                <span class="metric-label">Installer Image</span>
+            </div>
+            ` : ''}
+            ${m.update_payload_mb_30d != null ? `
+            <div class="metric-card">
+                <span class="metric-value">${formatMegabytes(m.update_payload_mb_30d)}</span>
+                <span class="metric-label">Updates per Month</span>
+            </div>
+            ` : ''}
+            ${m.aur_packages != null ? `
+            <div class="metric-card">
+                <span class="metric-value">${formatNumber(m.aur_packages)}</span>
+                <span class="metric-label">AUR Packages</span>
+            </div>
+            ` : ''}
+            ${m.flagged_packages != null ? `
+            <div class="metric-card">
+                <span class="metric-value">${formatNumber(m.flagged_packages)}</span>
+                <span class="metric-label">Flagged Out of Date${m.official_packages ? ` (of ${formatNumber(m.official_packages)})` : ''}</span>
+            </div>
+            ` : ''}
+            ${m.uploads_30d != null ? `
+            <div class="metric-card">
+                <span class="metric-value">${formatNumber(m.uploads_30d)}</span>
+                <span class="metric-label">Archive Uploads (30d)</span>
+            </div>
+            ` : ''}
+            ${m.rc_bugs != null ? `
+            <div class="metric-card">
+                <span class="metric-value">${formatNumber(m.rc_bugs)}</span>
+                <span class="metric-label">RC Bugs${m.source_packages ? ` (${formatNumber(m.source_packages)} source packages)` : ''}</span>
+            </div>
+            ` : ''}
+            ${m.bugzilla_open_bugs != null ? `
+            <div class="metric-card">
+                <span class="metric-value">${formatNumber(m.bugzilla_open_bugs)}</span>
+                <span class="metric-label">Open Bugzilla Bugs${m.bugs_opened_30d != null && m.bugs_closed_30d != null ? ` (+${formatNumber(m.bugs_opened_30d)} / -${formatNumber(m.bugs_closed_30d)} in 30d)` : ''}</span>
+            </div>
+            ` : ''}
+            ${m.bug_resolution_days != null ? `
+            <div class="metric-card">
+                <span class="metric-value">${m.bug_resolution_days.toFixed(1)}d</span>
+                <span class="metric-label">Mean Bug Resolution Time</span>
+            </div>
+            ` : ''}
+            ${m.active_installs_30d != null ? `
+            <div class="metric-card">
+                <span class="metric-value">${formatNumber(m.active_installs_30d)}</span>
+                <span class="metric-label" title="Self-reported by opt-in pings, which can't be verified">Active Installs (30d, opt-in, unverified)</span>
+            </div>
+            ` : ''}
         </div>
 
         ${m.latest_release ? `
         <div class="latest-release">
             <span class="release-tag">${m.latest_release}</span>
             <span class="release-age">${m.days_since_release !== null ? formatDaysAgo(m.days_since_release) : ''}</span>
+            ${renderReleaseVerification(m)}
         </div>
         ` : ''}
 
@@ -244,12 +334,100 @@ function renderDistroDetail(distro, healthData, history) {
             </div>
         </div>
 
+        ${renderGovernance(healthData && healthData.governance)}
+
+        ${renderHardware(healthData && healthData.hardware)}
+
+        ${renderChanges(changes)}
+
         ${history.length > 0 ? renderHistory(history) : '<p>No historical data available yet.</p>'}
 
         ${renderDetailMethodology(distro)}
     `;
 }
 
+function renderReleaseVerification(m) {
+    if (m.latest_release_signed === null && m.latest_release_checksummed === null) return '';
+
+    if (m.latest_release_signed) {
+        return '<span class="release-verification verified" title="Release assets include a detached signature">Signed</span>';
+    }
+    if (m.latest_release_checksummed) {
+        return '<span class="release-verification partial" title="Release assets include checksums but no signature">Checksums only</span>';
+    }
+    return '<span class="release-verification unverified" title="Release assets include neither signatures nor checksums">Unsigned</span>';
+}
+
+// Shown beside a score whose data is incomplete or stale, so a low score isn't read as a struggling distro
+function renderQualityWarning(quality) {
+    if (!quality || quality.level === 'good') return '';
+
+    const title = `Data quality ${quality.level}: ${quality.flags.map(f => f.detail).join('; ')}`;
+    return ` <span class="quality-warning" title="${escapeHtml(title).replace(/"/g, '&quot;')}">⚠</span>`;
+}
+
+function renderDataQuality(quality) {
+    if (!quality) return '';
+
+    const title = quality.flags.length > 0
+        ? quality.flags.map(f => f.detail).join('\n')
+        : 'Every source collected recently';
+    return `
+        <span class="data-quality ${quality.level}" title="${escapeHtml(title).replace(/"/g, '&quot;')}">
+            Data quality: ${quality.level} (${quality.sources_ok}/${quality.sources} sources current)
+        </span>
+    `;
+}
+
+function renderGovernance(governance) {
+    if (!governance) return '';
+
+    return `
+        <div class="governance">
+            <h4>Governance Checklist <span class="governance-score ${getScoreClass(governance.score)}">${governance.score.toFixed(0)}%</span></h4>
+            <ul>
+                ${governance.items.map(item => `
+                    <li class="${item.present ? 'present' : 'missing'}">${item.present ? '✓' : '✗'} ${escapeHtml(item.label)}</li>
+                `).join('')}
+            </ul>
+        </div>
+    `;
+}
+
+function renderHardware(hardware) {
+    if (!hardware) return '';
+
+    const component = (name, lag) => lag ? `
+        <li>${name} ${escapeHtml(lag.shipped)} — upstream had ${escapeHtml(lag.upstream)}
+            (${lag.releases_behind} ${lag.releases_behind === 1 ? 'release' : 'releases'} behind)</li>
+    ` : '';
+
+    return `
+        <div class="hardware">
+            <h4>Hardware Enablement Lag: ${hardware.lag} ${hardware.lag === 1 ? 'release' : 'releases'}</h4>
+            <ul>
+                ${component('Kernel', hardware.kernel)}
+                ${component('Mesa', hardware.mesa)}
+            </ul>
+            <p class="note">Versions in the installer against upstream when its kernel arrived
+                (${new Date(hardware.released_at).toLocaleDateString()}). Newer hardware may need more recent ones.</p>
+        </div>
+    `;
+}
+
+function renderChanges(changes) {
+    if (!changes || changes.summary.length === 0) return '';
+
+    return `
+        <div class="recent-changes">
+            <h4>What Changed This Week</h4>
+            <ul>
+                ${changes.summary.map(line => `<li>${escapeHtml(line)}</li>`).join('')}
+            </ul>
+        </div>
+    `;
+}
+
 function renderHistory(history) {
     if (history.length < 2) return '';
 
@@ -312,15 +490,57 @@ function showRankings() {
     detailSection.classList.add('hidden');
     rankingsSection.classList.remove('hidden');
     currentDistro = null;
+    if (window.location.search) {
+        history.pushState(null, '', window.location.pathname);
+    }
+    document.title = DEFAULT_TITLE;
+    document.getElementById('structured-data')?.remove();
+}
+
+// Embed the distro's schema.org JSON-LD so crawlers can index the page
+async function loadStructuredData(slug) {
+    try {
+        const response = await fetch(`${API_BASE}/distros/${slug}/jsonld`);
+        if (!response.ok) return;
+        let script = document.getElementById('structured-data');
+        if (!script) {
+            script = document.createElement('script');
+            script.id = 'structured-data';
+            script.type = 'application/ld+json';
+            document.head.appendChild(script);
+        }
+        script.textContent = await response.text();
+    } catch (e) {
+        // Structured data is optional
+    }
 }
 
 // Utility functions
+function escapeHtml(text) {
+    const div = document.createElement('div');
+    div.textContent = text;
+    return div.innerHTML;
+}
+
+function renderRankChange(change) {
+    if (!change) return '';
+    const places = Math.abs(change) === 1 ? 'place' : 'places';
+    const arrow = change > 0 ? '↑' : '↓';
+    const cls = change > 0 ? 'trend-up' : 'trend-down';
+    return `<span class="rank-change ${cls}" title="${arrow}${Math.abs(change)} ${places} this month">${arrow}${Math.abs(change)}</span>`;
+}
+
 function formatNumber(num) {
     if (num >= 1000000) return (num / 1000000).toFixed(1) + 'M';
     if (num >= 1000) return (num / 1000).toFixed(1) + 'K';
     return num.toString();
 }
 
+function formatMegabytes(mb) {
+    if (mb >= 1024) return (mb / 1024).toFixed(1) + ' GB';
+    return Math.round(mb) + ' MB';
+}
+
 function formatDaysAgo(days) {
     if (days === 0) return 'today';
     if (days === 1) return 'yesterday';
@@ -374,6 +594,13 @@ function renderDataSources(distro, detailed = false) {
     const badges = [];
     const m = distro.metrics || {};
 
+    // A variant's development metrics are its parent's, so say where they come from
+    if (distro.variant_of) {
+        const label = detailed ? `Variant of ${distro.variant_of}` : distro.variant_of;
+        const title = `Variant of ${distro.variant_of}: development and maintenance metrics are ${distro.variant_of}'s, community and releases its own`;
+        badges.push(`<a href="?distro=${distro.variant_of}" class="source-badge variant" onclick="event.stopPropagation(); event.preventDefault(); showDistroDetail('${distro.variant_of}')" title="${title}"><span>${label}</span></a>`);
+    }
+
     if (distro.github_org) {
         const url = `https://github.com/${distro.github_org}`;
         const commits30d = m.commits_30d || 0;
@@ -387,6 +614,12 @@ function renderDataSources(distro, detailed = false) {
         badges.push(`<a href="${url}" target="_blank" class="source-badge github" onclick="event.stopPropagation()" title="${title}">${GITHUB_ICON}<span>${label}</span></a>`);
     }
 
+    if (distro.forgejo_org) {
+        const url = `https://codeberg.org/${distro.forgejo_org}`;
+        const label = detailed ? `Codeberg: ${distro.forgejo_org}` : 'Codeberg';
+        badges.push(`<a href="${url}" target="_blank" class="source-badge codeberg" onclick="event.stopPropagation()" title="Codeberg: ${distro.forgejo_org}"><span>${label}</span></a>`);
+    }
+
     if (distro.subreddit) {
         const url = `https://reddit.com/r/${distro.subreddit}`;
         const subs = m.reddit_subscribers || 0;